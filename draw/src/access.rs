@@ -0,0 +1,158 @@
+use {
+    std::rc::Rc,
+    std::cell::RefCell,
+    crate::{
+        cx_2d::Cx2d,
+        makepad_platform::{
+            AccessNode,
+            AccessTree,
+            Area,
+            Rect,
+            DrawListId,
+            Cx,
+        },
+    }
+};
+
+#[derive(Default)]
+pub struct CxAccessLists {
+    lists: Vec<CxAccessList>
+}
+
+#[derive(Clone)]
+pub struct CxAccessListsRc(pub Rc<RefCell<CxAccessLists >>);
+
+#[derive(Debug, Default, Clone)]
+pub struct CxAccessList {
+    pub items: Vec<AccessItem>
+}
+
+impl std::ops::Index<DrawListId> for CxAccessLists {
+    type Output = CxAccessList;
+    fn index(&self, index: DrawListId) -> &Self::Output {
+        &self.lists[index.index()]
+    }
+}
+
+impl std::ops::IndexMut<DrawListId> for CxAccessLists {
+    fn index_mut(&mut self, index: DrawListId) -> &mut Self::Output {
+        &mut self.lists[index.index()]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum AccessItem {
+    Child(DrawListId),
+    Node(AccessNode),
+    Begin(AccessNode),
+    End,
+}
+
+impl<'a> Cx2d<'a> {
+
+    pub fn lazy_construct_access_lists(cx: &mut Cx) {
+        if !cx.has_global::<CxAccessListsRc>() {
+            cx.set_global(CxAccessListsRc(Rc::new(RefCell::new(CxAccessLists::default()))));
+        }
+    }
+
+    pub fn access_list_clear(&mut self, draw_list_id: DrawListId) {
+        let mut access_lists = self.access_lists_rc.0.borrow_mut();
+        if draw_list_id.index() >= access_lists.lists.len() {
+            access_lists.lists.resize(draw_list_id.index() + 1, Default::default());
+        }
+        access_lists[draw_list_id].items.clear();
+    }
+
+    pub fn access_list_item_push(&mut self, draw_list_id: DrawListId, item: AccessItem) {
+        let mut access_lists = self.access_lists_rc.0.borrow_mut();
+        access_lists[draw_list_id].items.push(item);
+    }
+
+    /// Adds a leaf node to the accessibility tree of the window being drawn
+    pub fn add_access_node(&mut self, node: AccessNode) {
+        let draw_list_id = *self.draw_list_stack.last().unwrap();
+        self.access_list_item_push(draw_list_id, AccessItem::Node(node));
+    }
+
+    /// Adds a node that contains the nodes added until the matching `end_access_node`
+    pub fn begin_access_node(&mut self, node: AccessNode) {
+        let draw_list_id = *self.draw_list_stack.last().unwrap();
+        self.access_list_item_push(draw_list_id, AccessItem::Begin(node));
+    }
+
+    /// Call this from the same draw list as the `begin_access_node`
+    pub fn end_access_node(&mut self) {
+        let draw_list_id = *self.draw_list_stack.last().unwrap();
+        self.access_list_item_push(draw_list_id, AccessItem::End);
+    }
+
+    /// Ends a node whose area is only known after its children were drawn
+    pub fn end_access_node_with_area(&mut self, area: Area) {
+        let draw_list_id = *self.draw_list_stack.last().unwrap();
+        let mut access_lists = self.access_lists_rc.0.borrow_mut();
+        let items = &mut access_lists[draw_list_id].items;
+        let mut depth = 0;
+        for item in items.iter_mut().rev() {
+            match item {
+                AccessItem::End => depth += 1,
+                AccessItem::Begin(node) if depth == 0 => {
+                    node.area = area;
+                    break
+                }
+                AccessItem::Begin(_) => depth -= 1,
+                _ => ()
+            }
+        }
+        items.push(AccessItem::End);
+    }
+
+    pub (crate) fn publish_access_trees(&mut self) {
+        let access_lists_rc = self.access_lists_rc.clone();
+        let access_lists = &*access_lists_rc.0.borrow();
+
+        fn build_tree(cx: &Cx, access_lists: &CxAccessLists, draw_list_id: DrawListId, stack: &mut Vec<usize>, tree: &mut AccessTree) {
+            if draw_list_id.index() >= access_lists.lists.len() {
+                return
+            }
+            let depth = stack.len();
+            for item in &access_lists[draw_list_id].items {
+                match item {
+                    AccessItem::Child(draw_list_id) => {
+                        build_tree(cx, access_lists, *draw_list_id, stack, tree);
+                    }
+                    AccessItem::Node(node) | AccessItem::Begin(node) => {
+                        let mut node = node.clone();
+                        // nodes that weren't drawn this time around (clipped away, hidden) have no rect
+                        node.rect = if node.area.is_valid(cx) {node.area.clipped_rect(cx)} else {Default::default()};
+                        let index = tree.add_node(stack.last().cloned(), node);
+                        if let AccessItem::Begin(_) = item {
+                            stack.push(index);
+                        }
+                    }
+                    AccessItem::End => if stack.len() > depth {
+                        stack.pop();
+                    }
+                }
+            }
+            // an unbalanced begin doesn't leak into the rest of the tree
+            stack.truncate(depth);
+        }
+
+        let mut trees = Vec::new();
+        for window_id in self.cx.windows.id_iter() {
+            let window = &self.cx.windows[window_id];
+            if !window.is_created {
+                continue
+            }
+            let Some(main_draw_list_id) = window.main_pass_id.and_then( | pass_id | self.cx.passes[pass_id].main_draw_list_id) else {
+                continue
+            };
+            let mut tree = AccessTree::new(window_id, window.create_title.clone());
+            tree.rect = Rect {pos: window.window_geom.position, size: window.get_inner_size()};
+            build_tree(self.cx, access_lists, main_draw_list_id, &mut Vec::new(), &mut tree);
+            trees.push(tree);
+        }
+        self.cx.set_access_trees(trees);
+    }
+}
@@ -0,0 +1,721 @@
+//! Rasterization of color glyphs. Glyphs with layers in the `COLR`/`CPAL` tables (versions 0
+//! and 1) or a document in the `SVG ` table are painted in software into premultiplied RGBA
+//! images, which go into the color page of the font atlas next to the SDF glyphs.
+//!
+//! `SVG ` glyphs are painted by the document renderer in `svg`, which also shares the canvas and
+//! the color lines here.
+
+use {
+    std::f64::consts::PI,
+    crate::{
+        svg::SvgDocument,
+        makepad_vector::{
+            geometry::{AffineTransformation, Arc, LinearTransformation, Point, Rectangle, Transform, Transformation, Vector},
+            path::PathCommand,
+        },
+    },
+    makepad_rustybuzz::ttf_parser::{
+        colr::{self, ClipBox, CompositeMode, GradientExtend, Paint},
+        Face,
+        GlyphId,
+        OutlineBuilder,
+        RgbaColor,
+        Transform as ColrTransform,
+    },
+};
+
+/// The bounds of the color version of `glyph_id` in font units, `None` if it has no color data
+/// or paints nothing. This is the clip box of a `COLR` glyph if it has one, otherwise the hull of
+/// everything it paints.
+pub fn color_glyph_bounds(face: &Face, glyph_id: GlyphId) -> Option<Rectangle> {
+    if face.is_color_glyph(glyph_id) {
+        let mut painter = BoundsPainter {
+            face,
+            transforms: vec![AffineTransformation::identity()],
+            clip_box: None,
+            bounds: None,
+        };
+        face.paint_color_glyph(glyph_id, 0, FOREGROUND, &mut painter)?;
+        return painter.clip_box.or(painter.bounds)
+    }
+    // svg has y pointing down from the baseline
+    let bounds = svg_glyph_document(face, glyph_id)?.bounds()?;
+    Some(Rectangle::new(
+        Point::new(bounds.pos.x, -bounds.pos.y - bounds.size.y),
+        Point::new(bounds.pos.x + bounds.size.x, -bounds.pos.y),
+    ))
+}
+
+/// Paints the color version of `glyph_id` into a `width` by `height` image of premultiplied
+/// `0xAARRGGBB` pixels, the first row being the bottom one. `transform` maps font units to
+/// pixels. Layers in the text color are painted black.
+pub fn rasterize_color_glyph(
+    face: &Face,
+    glyph_id: GlyphId,
+    transform: AffineTransformation,
+    width: usize,
+    height: usize,
+) -> Option<Vec<u32>> {
+    if face.is_color_glyph(glyph_id) {
+        let mut painter = ColrPainter {
+            face,
+            canvas: Canvas::new(width, height),
+            transforms: vec![transform],
+            outline: None,
+        };
+        face.paint_color_glyph(glyph_id, 0, FOREGROUND, &mut painter)?;
+        return Some(painter.canvas.into_pixels())
+    }
+    let document = svg_glyph_document(face, glyph_id)?;
+    Some(document.rasterize(concat(transform, AffineTransformation::scaling(Vector::new(1.0, -1.0))), width, height))
+}
+
+const FOREGROUND: RgbaColor = RgbaColor {red: 0, green: 0, blue: 0, alpha: 255};
+
+// premultiplied rgba
+pub(crate) type Color = [f32; 4];
+
+fn premultiply(color: RgbaColor) -> Color {
+    let a = color.alpha as f32 / 255.0;
+    [color.red as f32 / 255.0 * a, color.green as f32 / 255.0 * a, color.blue as f32 / 255.0 * a, a]
+}
+
+// `outer` applied after `inner`
+pub(crate) fn concat(outer: AffineTransformation, inner: AffineTransformation) -> AffineTransformation {
+    AffineTransformation::new(outer.xy.compose(inner.xy), outer.transform_vector(inner.z) + outer.z)
+}
+
+pub(crate) fn invert(t: AffineTransformation) -> Option<AffineTransformation> {
+    let det = t.xy.x.x * t.xy.y.y - t.xy.y.x * t.xy.x.y;
+    if det.abs() < 1e-12 {
+        return None
+    }
+    let xy = LinearTransformation::new(
+        Vector::new(t.xy.y.y / det, -t.xy.x.y / det),
+        Vector::new(-t.xy.y.x / det, t.xy.x.x / det),
+    );
+    Some(AffineTransformation::new(xy, -xy.transform_vector(t.z)))
+}
+
+pub(crate) fn linear(a: f64, b: f64, c: f64, d: f64) -> AffineTransformation {
+    AffineTransformation::new(LinearTransformation::new(Vector::new(a, b), Vector::new(c, d)), Vector::zero())
+}
+
+fn from_colr_transform(t: ColrTransform) -> AffineTransformation {
+    AffineTransformation::new(
+        LinearTransformation::new(Vector::new(t.a as f64, t.b as f64), Vector::new(t.c as f64, t.d as f64)),
+        Vector::new(t.e as f64, t.f as f64),
+    )
+}
+
+// `COLR` angles are in half turns
+fn rotation(angle: f32) -> AffineTransformation {
+    let (sin, cos) = (angle as f64 * PI).sin_cos();
+    linear(cos, sin, -sin, cos)
+}
+
+fn skew(skew_x: f32, skew_y: f32) -> AffineTransformation {
+    linear(1.0, (skew_y as f64 * PI).tan(), -(skew_x as f64 * PI).tan(), 1.0)
+}
+
+fn hull(bounds: Option<Rectangle>, p: Point) -> Rectangle {
+    match bounds {
+        Some(r) => Rectangle::new(
+            Point::new(r.p_min.x.min(p.x), r.p_min.y.min(p.y)),
+            Point::new(r.p_max.x.max(p.x), r.p_max.y.max(p.y)),
+        ),
+        None => Rectangle::new(p, p),
+    }
+}
+
+fn rect_corners(r: ClipBox) -> [Point; 4] {
+    let (x0, y0, x1, y1) = (r.x_min as f64, r.y_min as f64, r.x_max as f64, r.y_max as f64);
+    [Point::new(x0, y0), Point::new(x1, y0), Point::new(x1, y1), Point::new(x0, y1)]
+}
+
+pub(crate) fn command_points(command: &PathCommand) -> Vec<Point> {
+    match *command {
+        PathCommand::MoveTo(p) | PathCommand::LineTo(p) => vec![p],
+        // the hull of an arc is at most a radius away from its end point
+        PathCommand::ArcTo(p, r, ..) => {
+            let r = r.x.abs().max(r.y.abs());
+            vec![Point::new(p.x - r, p.y - r), Point::new(p.x + r, p.y + r)]
+        }
+        PathCommand::QuadraticTo(p1, p) => vec![p1, p],
+        PathCommand::CubicTo(p1, p2, p) => vec![p1, p2, p],
+        PathCommand::Close => vec![],
+    }
+}
+
+// Flattens outlines into line segments in pixel space
+struct Lines {
+    transform: AffineTransformation,
+    lines: Vec<(Point, Point)>,
+    // in the space the outline is given in, arcs are flattened there
+    start: Point,
+    current: Point,
+}
+
+impl Lines {
+    fn new(transform: AffineTransformation) -> Self {
+        Self {transform, lines: Vec::new(), start: Point::origin(), current: Point::origin()}
+    }
+
+    fn move_to(&mut self, p: Point) {
+        self.close_contour();
+        self.start = p;
+        self.current = p;
+    }
+
+    fn line_to(&mut self, p: Point) {
+        self.lines.push((self.current.transform(&self.transform), p.transform(&self.transform)));
+        self.current = p;
+    }
+
+    fn bezier_to(&mut self, controls: &[Point]) {
+        let points: Vec<Point> = [self.current].iter().chain(controls).map( | p | p.transform(&self.transform)).collect();
+        let length: f64 = points.windows(2).map( | w | (w[1] - w[0]).length()).sum();
+        let steps = (length.sqrt() * 1.5).ceil().clamp(1.0, 64.0) as usize;
+        let mut last = points[0];
+        for step in 1..=steps {
+            let t = step as f64 / steps as f64;
+            // de casteljau
+            let mut level = points.clone();
+            while level.len() > 1 {
+                level = level.windows(2).map( | w | w[0].lerp(w[1], t)).collect();
+            }
+            self.lines.push((last, level[0]));
+            last = level[0];
+        }
+        self.current = *controls.last().unwrap();
+    }
+
+    fn close_contour(&mut self) {
+        if self.current != self.start {
+            self.line_to(self.start);
+        }
+    }
+
+    fn command(&mut self, command: PathCommand) {
+        match command {
+            PathCommand::MoveTo(p) => self.move_to(p),
+            PathCommand::LineTo(p) => self.line_to(p),
+            PathCommand::ArcTo(p, r, x_axis_rotation, large_arc, sweep) => {
+                let scale = (self.transform.xy.x.length() + self.transform.xy.y.length()).max(1e-6);
+                let arc = Arc::new(self.current, p, r, x_axis_rotation, large_arc, sweep).linearize(0.1 / scale);
+                crate::makepad_vector::internal_iter::InternalIterator::for_each(arc, &mut | p | {
+                    self.line_to(p);
+                    true
+                });
+                self.current = p;
+            }
+            PathCommand::QuadraticTo(p1, p) => self.bezier_to(&[p1, p]),
+            PathCommand::CubicTo(p1, p2, p) => self.bezier_to(&[p1, p2, p]),
+            PathCommand::Close => {
+                self.close_contour();
+                self.current = self.start;
+            }
+        }
+    }
+
+    fn rect(&mut self, corners: [Point; 4]) {
+        self.move_to(corners[0]);
+        for corner in &corners[1..] {
+            self.line_to(*corner);
+        }
+        self.close_contour();
+    }
+
+    // The nonzero coverage of the lines in a `width` by `height` image
+    fn mask(mut self, width: usize, height: usize) -> Vec<f32> {
+        self.close_contour();
+        // the rasterizer accumulates coverage along whole rows, so lines are split at the left
+        // and right edges and the parts outside are flattened onto them, which keeps everything
+        // they add inside the row. The extra columns take what lands on the right edge.
+        let mut rasterizer = ab_glyph_rasterizer::Rasterizer::new(width + 2, height);
+        let w = width as f64;
+        for (p0, p1) in self.lines {
+            let mut ts = vec![0.0, 1.0];
+            for edge in [0.0, w] {
+                if (p0.x - edge) * (p1.x - edge) < 0.0 {
+                    ts.push((edge - p0.x) / (p1.x - p0.x));
+                }
+            }
+            ts.sort_by(f64::total_cmp);
+            let at = | t: f64 | {
+                let p = p0.lerp(p1, t);
+                ab_glyph_rasterizer::point(p.x.clamp(0.0, w) as f32, p.y as f32)
+            };
+            for t in ts.windows(2) {
+                rasterizer.draw_line(at(t[0]), at(t[1]));
+            }
+        }
+        let mut mask = vec![0.0; width * height];
+        rasterizer.for_each_pixel_2d( | x, y, a | {
+            if (x as usize) < width {
+                mask[y as usize * width + x as usize] = a.min(1.0);
+            }
+        });
+        mask
+    }
+}
+
+impl OutlineBuilder for Lines {
+    fn move_to(&mut self, x: f32, y: f32) {
+        Lines::move_to(self, Point::new(x as f64, y as f64));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        Lines::line_to(self, Point::new(x as f64, y as f64));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.bezier_to(&[Point::new(x1 as f64, y1 as f64), Point::new(x as f64, y as f64)]);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.bezier_to(&[
+            Point::new(x1 as f64, y1 as f64),
+            Point::new(x2 as f64, y2 as f64),
+            Point::new(x as f64, y as f64),
+        ]);
+    }
+
+    fn close(&mut self) {
+        self.command(PathCommand::Close);
+    }
+}
+
+// Layers of premultiplied pixels that get composited onto each other, with a stack of clip masks
+pub(crate) struct Canvas {
+    width: usize,
+    height: usize,
+    layers: Vec<(Vec<Color>, CompositeMode)>,
+    // each one is already intersected with the ones below it
+    clips: Vec<Vec<f32>>,
+}
+
+impl Canvas {
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            layers: vec![(vec![[0.0; 4]; width * height], CompositeMode::SourceOver)],
+            clips: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push_clip(&mut self, mut mask: Vec<f32>) {
+        if let Some(clip) = self.clips.last() {
+            for (m, c) in mask.iter_mut().zip(clip) {
+                *m *= c;
+            }
+        }
+        self.clips.push(mask);
+    }
+
+    pub(crate) fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    pub(crate) fn push_layer(&mut self, mode: CompositeMode) {
+        self.layers.push((vec![[0.0; 4]; self.width * self.height], mode));
+    }
+
+    // Scales the top layer, used for the opacity of a group before it's popped
+    pub(crate) fn fade_layer(&mut self, opacity: f32) {
+        let (layer, _) = self.layers.last_mut().unwrap();
+        for pixel in layer {
+            *pixel = pixel.map( | c | c * opacity);
+        }
+    }
+
+    // Paints `color(x, y)` over the pixels covered by `mask` and the clip, `None` covers all
+    pub(crate) fn fill(&mut self, mask: Option<&[f32]>, color: impl Fn(Point) -> Color) {
+        let (layer, _) = self.layers.last_mut().unwrap();
+        let clip = self.clips.last();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y * self.width + x;
+                let coverage = mask.map_or(1.0, | m | m[i]) * clip.map_or(1.0, | c | c[i]);
+                if coverage <= 0.0 {
+                    continue;
+                }
+                let src = color(Point::new(x as f64 + 0.5, y as f64 + 0.5));
+                let dst = &mut layer[i];
+                let sa = src[3] * coverage;
+                for c in 0..4 {
+                    dst[c] = src[c] * coverage + dst[c] * (1.0 - sa);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn pop_layer(&mut self) {
+        if self.layers.len() < 2 {
+            return
+        }
+        let (src, mode) = self.layers.pop().unwrap();
+        let (dst, _) = self.layers.last_mut().unwrap();
+        for (d, s) in dst.iter_mut().zip(&src) {
+            *d = composite(mode, *s, *d);
+        }
+    }
+
+    pub(crate) fn into_pixels(mut self) -> Vec<u32> {
+        while self.layers.len() > 1 {
+            self.pop_layer();
+        }
+        self.layers[0].0.iter().map( | c | {
+            let [r, g, b, a] = c.map( | v | (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u32);
+            a << 24 | r << 16 | g << 8 | b
+        }).collect()
+    }
+}
+
+fn composite(mode: CompositeMode, s: Color, d: Color) -> Color {
+    let (sa, da) = (s[3], d[3]);
+    let porter_duff = | fs: f32, fd: f32 | [0, 1, 2, 3].map( | c | s[c] * fs + d[c] * fd);
+    let blend: fn(f32, f32) -> f32 = match mode {
+        CompositeMode::Clear => return [0.0; 4],
+        CompositeMode::Source => return s,
+        CompositeMode::Destination => return d,
+        CompositeMode::SourceOver => return porter_duff(1.0, 1.0 - sa),
+        CompositeMode::DestinationOver => return porter_duff(1.0 - da, 1.0),
+        CompositeMode::SourceIn => return porter_duff(da, 0.0),
+        CompositeMode::DestinationIn => return porter_duff(0.0, sa),
+        CompositeMode::SourceOut => return porter_duff(1.0 - da, 0.0),
+        CompositeMode::DestinationOut => return porter_duff(0.0, 1.0 - sa),
+        CompositeMode::SourceAtop => return porter_duff(da, 1.0 - sa),
+        CompositeMode::DestinationAtop => return porter_duff(1.0 - da, sa),
+        CompositeMode::Xor => return porter_duff(1.0 - da, 1.0 - sa),
+        CompositeMode::Plus => return porter_duff(1.0, 1.0).map( | v | v.min(1.0)),
+        CompositeMode::Screen => | cs, cd | cs + cd - cs * cd,
+        CompositeMode::Multiply => | cs, cd | cs * cd,
+        CompositeMode::Darken => f32::min,
+        CompositeMode::Lighten => f32::max,
+        CompositeMode::Difference => | cs, cd | (cs - cd).abs(),
+        CompositeMode::Exclusion => | cs, cd | cs + cd - 2.0 * cs * cd,
+        CompositeMode::Overlay => | cs, cd | hard_light(cd, cs),
+        CompositeMode::HardLight => hard_light,
+        CompositeMode::ColorDodge => | cs, cd | if cd <= 0.0 {0.0} else if cs >= 1.0 {1.0} else {(cd / (1.0 - cs)).min(1.0)},
+        CompositeMode::ColorBurn => | cs, cd | if cd >= 1.0 {1.0} else if cs <= 0.0 {0.0} else {1.0 - ((1.0 - cd) / cs).min(1.0)},
+        CompositeMode::SoftLight => | cs, cd | {
+            if cs <= 0.5 {
+                cd - (1.0 - 2.0 * cs) * cd * (1.0 - cd)
+            }
+            else {
+                let d = if cd <= 0.25 {((16.0 * cd - 12.0) * cd + 4.0) * cd} else {cd.sqrt()};
+                cd + (2.0 * cs - 1.0) * (d - cd)
+            }
+        },
+        // the non separable modes are painted as normal
+        CompositeMode::Hue | CompositeMode::Saturation | CompositeMode::Color | CompositeMode::Luminosity => | cs, _ | cs,
+    };
+    let unpremultiply = | v: f32, a: f32 | if a > 0.0 {v / a} else {0.0};
+    let mut out = [0.0, 0.0, 0.0, sa + da - sa * da];
+    for c in 0..3 {
+        let b = blend(unpremultiply(s[c], sa), unpremultiply(d[c], da));
+        out[c] = s[c] * (1.0 - da) + d[c] * (1.0 - sa) + sa * da * b;
+    }
+    out
+}
+
+fn hard_light(cs: f32, cd: f32) -> f32 {
+    if cs <= 0.5 {cd * 2.0 * cs} else {let s = 2.0 * cs - 1.0; cd + s - cd * s}
+}
+
+// Collects the bounds of what a `COLR` glyph paints
+struct BoundsPainter<'f, 'a> {
+    face: &'f Face<'a>,
+    transforms: Vec<AffineTransformation>,
+    clip_box: Option<Rectangle>,
+    bounds: Option<Rectangle>,
+}
+
+impl BoundsPainter<'_, '_> {
+    fn push(&mut self, transform: AffineTransformation) {
+        let top = *self.transforms.last().unwrap();
+        self.transforms.push(concat(top, transform));
+    }
+}
+
+impl<'a> colr::Painter<'a> for BoundsPainter<'_, 'a> {
+    fn outline_glyph(&mut self, glyph_id: GlyphId) {
+        if let Some(rect) = self.face.glyph_bounding_box(glyph_id) {
+            let transform = self.transforms.last().unwrap();
+            let rect = ClipBox {x_min: rect.x_min as f32, y_min: rect.y_min as f32, x_max: rect.x_max as f32, y_max: rect.y_max as f32};
+            for corner in rect_corners(rect) {
+                self.bounds = Some(hull(self.bounds, corner.transform(transform)));
+            }
+        }
+    }
+
+    fn paint(&mut self, _paint: Paint<'a>) {}
+
+    fn push_clip(&mut self) {}
+
+    fn push_clip_box(&mut self, clip_box: ClipBox) {
+        // the clip box of the glyph itself comes first, before any transform
+        if self.clip_box.is_none() && self.transforms.len() == 1 {
+            self.clip_box = Some(Rectangle::new(
+                Point::new(clip_box.x_min as f64, clip_box.y_min as f64),
+                Point::new(clip_box.x_max as f64, clip_box.y_max as f64),
+            ));
+        }
+    }
+
+    fn pop_clip(&mut self) {}
+
+    fn push_layer(&mut self, _mode: CompositeMode) {}
+
+    fn pop_layer(&mut self) {}
+
+    fn push_translate(&mut self, tx: f32, ty: f32) {
+        self.push(AffineTransformation::translation(Vector::new(tx as f64, ty as f64)));
+    }
+
+    fn push_scale(&mut self, sx: f32, sy: f32) {
+        self.push(AffineTransformation::scaling(Vector::new(sx as f64, sy as f64)));
+    }
+
+    fn push_rotate(&mut self, angle: f32) {
+        self.push(rotation(angle));
+    }
+
+    fn push_skew(&mut self, skew_x: f32, skew_y: f32) {
+        self.push(skew(skew_x, skew_y));
+    }
+
+    fn push_transform(&mut self, transform: ColrTransform) {
+        self.push(from_colr_transform(transform));
+    }
+
+    fn pop_transform(&mut self) {
+        self.transforms.pop();
+    }
+}
+
+// Paints a `COLR` glyph onto a canvas
+struct ColrPainter<'f, 'a> {
+    face: &'f Face<'a>,
+    canvas: Canvas,
+    // font units to pixels
+    transforms: Vec<AffineTransformation>,
+    // the mask of the last outlined glyph, until it's painted or becomes a clip
+    outline: Option<Vec<f32>>,
+}
+
+impl ColrPainter<'_, '_> {
+    fn push(&mut self, transform: AffineTransformation) {
+        let top = *self.transforms.last().unwrap();
+        self.transforms.push(concat(top, transform));
+    }
+}
+
+impl<'a> colr::Painter<'a> for ColrPainter<'_, 'a> {
+    fn outline_glyph(&mut self, glyph_id: GlyphId) {
+        let mut lines = Lines::new(*self.transforms.last().unwrap());
+        self.face.outline_glyph(glyph_id, &mut lines);
+        self.outline = Some(lines.mask(self.canvas.width, self.canvas.height));
+    }
+
+    fn paint(&mut self, paint: Paint<'a>) {
+        let mask = self.outline.take();
+        let mask = mask.as_deref();
+        let Some(inverse) = invert(*self.transforms.last().unwrap()) else {
+            return
+        };
+        match paint {
+            Paint::Solid(color) => {
+                let color = premultiply(color);
+                self.canvas.fill(mask, | _ | color);
+            }
+            Paint::LinearGradient(gradient) => {
+                let stops = ColorLine::new(gradient.stops(0, &[]).map( | stop | (stop.stop_offset, stop.color)), gradient.extend);
+                let p0 = Point::new(gradient.x0 as f64, gradient.y0 as f64);
+                let p1 = Point::new(gradient.x1 as f64, gradient.y1 as f64);
+                let p2 = Point::new(gradient.x2 as f64, gradient.y2 as f64);
+                // the gradient runs along p0 p1 projected onto the normal of p0 p2
+                let normal = Vector::new(p2.y - p0.y, p0.x - p2.x);
+                let d = p1 - p0;
+                let d = match normal.dot(normal) {
+                    n if n > 1e-9 => normal * (d.dot(normal) / n),
+                    _ => d,
+                };
+                let length = d.dot(d);
+                if length <= 1e-9 {
+                    return
+                }
+                self.canvas.fill(mask, | p | stops.color(((p.transform(&inverse) - p0).dot(d) / length) as f32));
+            }
+            Paint::RadialGradient(gradient) => {
+                let stops = ColorLine::new(gradient.stops(0, &[]).map( | stop | (stop.stop_offset, stop.color)), gradient.extend);
+                let c0 = Point::new(gradient.x0 as f64, gradient.y0 as f64);
+                let c1 = Point::new(gradient.x1 as f64, gradient.y1 as f64);
+                let (r0, r1) = (gradient.r0 as f64, gradient.r1 as f64);
+                self.canvas.fill(mask, | p | {
+                    match conical_t(p.transform(&inverse), c0, r0, c1, r1) {
+                        Some(t) => stops.color(t as f32),
+                        None => [0.0; 4],
+                    }
+                });
+            }
+            Paint::SweepGradient(gradient) => {
+                let stops = ColorLine::new(gradient.stops(0, &[]).map( | stop | (stop.stop_offset, stop.color)), gradient.extend);
+                let center = Point::new(gradient.center_x as f64, gradient.center_y as f64);
+                let start = gradient.start_angle as f64 * 180.0;
+                let end = gradient.end_angle as f64 * 180.0;
+                if (end - start).abs() < 1e-9 {
+                    return
+                }
+                self.canvas.fill(mask, | p | {
+                    let v = p.transform(&inverse) - center;
+                    let angle = v.y.atan2(v.x).to_degrees().rem_euclid(360.0);
+                    stops.color(((angle - start) / (end - start)) as f32)
+                });
+            }
+        }
+    }
+
+    fn push_clip(&mut self) {
+        let mask = self.outline.take().unwrap_or_else( | | vec![1.0; self.canvas.width * self.canvas.height]);
+        self.canvas.push_clip(mask);
+    }
+
+    fn push_clip_box(&mut self, clip_box: ClipBox) {
+        let mut lines = Lines::new(*self.transforms.last().unwrap());
+        lines.rect(rect_corners(clip_box));
+        let mask = lines.mask(self.canvas.width, self.canvas.height);
+        self.canvas.push_clip(mask);
+    }
+
+    fn pop_clip(&mut self) {
+        self.canvas.pop_clip();
+    }
+
+    fn push_layer(&mut self, mode: CompositeMode) {
+        self.canvas.push_layer(mode);
+    }
+
+    fn pop_layer(&mut self) {
+        self.canvas.pop_layer();
+    }
+
+    fn push_translate(&mut self, tx: f32, ty: f32) {
+        self.push(AffineTransformation::translation(Vector::new(tx as f64, ty as f64)));
+    }
+
+    fn push_scale(&mut self, sx: f32, sy: f32) {
+        self.push(AffineTransformation::scaling(Vector::new(sx as f64, sy as f64)));
+    }
+
+    fn push_rotate(&mut self, angle: f32) {
+        self.push(rotation(angle));
+    }
+
+    fn push_skew(&mut self, skew_x: f32, skew_y: f32) {
+        self.push(skew(skew_x, skew_y));
+    }
+
+    fn push_transform(&mut self, transform: ColrTransform) {
+        self.push(from_colr_transform(transform));
+    }
+
+    fn pop_transform(&mut self) {
+        self.transforms.pop();
+    }
+}
+
+// The largest t for which p is on the circle interpolated between the two, with a radius >= 0
+pub(crate) fn conical_t(p: Point, c0: Point, r0: f64, c1: Point, r1: f64) -> Option<f64> {
+    let cd = c1 - c0;
+    let pd = p - c0;
+    let dr = r1 - r0;
+    let a = cd.dot(cd) - dr * dr;
+    let b = pd.dot(cd) + r0 * dr;
+    let c = pd.dot(pd) - r0 * r0;
+    let valid = | t: f64 | r0 + t * dr >= 0.0;
+    if a.abs() < 1e-9 {
+        if b.abs() < 1e-9 {
+            return None
+        }
+        let t = c / (2.0 * b);
+        return valid(t).then_some(t)
+    }
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None
+    }
+    let root = discriminant.sqrt();
+    let (t0, t1) = ((b + root) / a, (b - root) / a);
+    let (t0, t1) = if t0 > t1 {(t0, t1)} else {(t1, t0)};
+    if valid(t0) {
+        Some(t0)
+    }
+    else {
+        valid(t1).then_some(t1)
+    }
+}
+
+pub(crate) struct ColorLine {
+    stops: Vec<(f32, Color)>,
+    extend: GradientExtend,
+}
+
+impl ColorLine {
+    fn new(stops: impl Iterator<Item = (f32, RgbaColor)>, extend: GradientExtend) -> Self {
+        Self::from_stops(stops.map( | (offset, color) | (offset, premultiply(color))).collect(), extend)
+    }
+
+    pub(crate) fn from_stops(mut stops: Vec<(f32, Color)>, extend: GradientExtend) -> Self {
+        stops.sort_by( | a, b | a.0.total_cmp(&b.0));
+        Self {stops, extend}
+    }
+
+    pub(crate) fn color(&self, t: f32) -> Color {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return [0.0; 4]
+        };
+        let (start, end) = (first.0, last.0);
+        let span = end - start;
+        let t = if span <= 0.0 {
+            t
+        }
+        else {
+            match self.extend {
+                GradientExtend::Pad => t,
+                GradientExtend::Repeat => start + (t - start).rem_euclid(span),
+                GradientExtend::Reflect => {
+                    let t = (t - start).rem_euclid(2.0 * span);
+                    start + if t > span {2.0 * span - t} else {t}
+                }
+            }
+        };
+        if t <= start {
+            return first.1
+        }
+        if t >= end {
+            return last.1
+        }
+        let next = self.stops.iter().position( | stop | stop.0 > t).unwrap();
+        let (o0, c0) = self.stops[next - 1];
+        let (o1, c1) = self.stops[next];
+        let f = (t - o0) / (o1 - o0);
+        [0, 1, 2, 3].map( | c | c0[c] + (c1[c] - c0[c]) * f)
+    }
+}
+
+// The element of the `SVG ` table document that holds `glyph_id`
+fn svg_glyph_document(face: &Face, glyph_id: GlyphId) -> Option<SvgDocument> {
+    let document = face.glyph_svg_image(glyph_id)?;
+    let data = if document.data.starts_with(&[0x1f, 0x8b]) {
+        makepad_zune_inflate::DeflateDecoder::new(document.data).decode_gzip().ok()?
+    }
+    else {
+        document.data.to_vec()
+    };
+    let text = std::str::from_utf8(&data).ok()?;
+    // documents can hold several glyphs, each one is the element with the id `glyph<id>`
+    SvgDocument::parse_element(text, &format!("glyph{}", glyph_id.0)).ok()
+}
@@ -16,6 +16,7 @@ use {
             Cx
         },
         nav::CxNavTreeRc,
+        access::CxAccessListsRc,
         icon_atlas::CxIconAtlasRc,
         font_atlas::{CxFontsAtlasRc, ShapeCacheRc},
         draw_list_2d::DrawList2d,
@@ -36,7 +37,7 @@ pub struct Cx2d<'a> {
     pub cx: &'a mut Cx,
     pub (crate) draw_event: &'a DrawEvent,
     pub (crate) pass_stack: Vec<PassStackItem>,
-    pub (crate) overlay_id: Option<DrawListId>,
+    pub (crate) overlay_stack: Vec<DrawListId>,
     //pub (crate) overlay_sweep_lock: Option<Rc<RefCell<Area>>>,
     pub draw_list_stack: Vec<DrawListId>,
     pub (crate) turtles: Vec<Turtle>,
@@ -47,6 +48,7 @@ pub struct Cx2d<'a> {
     pub shape_cache_rc: ShapeCacheRc,
     pub icon_atlas_rc: CxIconAtlasRc,
     pub nav_tree_rc: CxNavTreeRc,
+    pub access_lists_rc: CxAccessListsRc,
     pub rustybuzz_buffer: Option<UnicodeBuffer>, 
 }
 
@@ -57,6 +59,7 @@ impl<'a> Drop for Cx2d<'a> {
     fn drop(&mut self) {
         self.draw_font_atlas();
         self.draw_icon_atlas();
+        self.publish_access_trees();
     }
 }
 
@@ -77,14 +80,16 @@ impl<'a> Cx2d<'a> {
         Self::lazy_construct_font_atlas(cx);
         Self::lazy_construct_shape_cache(cx);
         Self::lazy_construct_nav_tree(cx);
+        Self::lazy_construct_access_lists(cx);
         Self::lazy_construct_icon_atlas(cx);
         cx.redraw_id += 1;
         let fonts_atlas_rc = cx.get_global::<CxFontsAtlasRc>().clone();
         let shape_cache_rc = cx.get_global::<ShapeCacheRc>().clone();
         let nav_tree_rc = cx.get_global::<CxNavTreeRc>().clone();
+        let access_lists_rc = cx.get_global::<CxAccessListsRc>().clone();
         let icon_atlas_rc = cx.get_global::<CxIconAtlasRc>().clone();
         Self {
-            overlay_id: None,
+            overlay_stack: Vec::new(),
             fonts_atlas_rc,
             shape_cache_rc,
             cx: cx,
@@ -97,6 +102,7 @@ impl<'a> Cx2d<'a> {
             turtles: Vec::with_capacity(64),
             align_list: Vec::with_capacity(4096),
             nav_tree_rc,
+            access_lists_rc,
             icon_atlas_rc,
             rustybuzz_buffer: Some(UnicodeBuffer::new()),
         }
@@ -1 +1,176 @@
+use {
+    crate::{
+        makepad_platform::*,
+        cx_2d::Cx2d,
+        shader::draw_mesh::DrawMesh,
+    },
+};
 
+/// The metallic-roughness material of glTF, without textures. The defaults are the ones of
+/// glTF for a mesh without a material.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material3d {
+    pub base_color: Vec4,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: Vec3,
+    /// Drawn after the opaque meshes, from back to front
+    pub is_transparent: bool,
+}
+
+impl Default for Material3d {
+    fn default() -> Self {
+        Self {
+            base_color: vec4(1.0, 1.0, 1.0, 1.0),
+            metallic: 1.0,
+            roughness: 1.0,
+            emissive: Vec3::default(),
+            is_transparent: false,
+        }
+    }
+}
+
+/// A triangle mesh in world space. Every vertex is 9 floats, in the layout of
+/// `GeometryCube3D`: a position, an id that isn't used, a normal and a uv.
+#[derive(Default)]
+pub struct Mesh3d {
+    pub vertices: Vec<f32>,
+    pub indices: Vec<u32>,
+    pub material: Material3d,
+    geometry: Option<Geometry>,
+}
+
+pub const MESH_3D_VERTEX_FLOATS: usize = 9;
+
+impl Mesh3d {
+    pub fn new(vertices: Vec<f32>, indices: Vec<u32>, material: Material3d) -> Self {
+        Self {vertices, indices, material, geometry: None}
+    }
+
+    /// The geometry of the mesh on the gpu, uploaded the first time it is drawn
+    pub fn geometry(&mut self, cx: &mut Cx) -> &Geometry {
+        if self.geometry.is_none() {
+            let geometry = Geometry::new(cx);
+            geometry.update(cx, self.indices.clone(), self.vertices.clone());
+            self.geometry = Some(geometry);
+        }
+        self.geometry.as_ref().unwrap()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Vec3> + '_ {
+        self.vertices.chunks_exact(MESH_3D_VERTEX_FLOATS).map( | v | vec3(v[0], v[1], v[2]))
+    }
+
+    /// The smallest and the largest corner of the box around the mesh
+    pub fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        bounds(self.positions())
+    }
+}
+
+fn bounds(mut positions: impl Iterator<Item = Vec3>) -> Option<(Vec3, Vec3)> {
+    let first = positions.next()?;
+    Some(positions.fold((first, first), | (min, max), p | (
+        vec3(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+        vec3(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+    )))
+}
+
+/// The meshes of a scene, with their transforms applied, see `Scene3d::parse_gltf`.
+#[derive(Default)]
+pub struct Scene3d {
+    pub meshes: Vec<Mesh3d>,
+}
+
+impl Scene3d {
+    pub fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        bounds(self.meshes.iter().flat_map( | mesh | mesh.positions()))
+    }
+
+    /// Draws the opaque meshes, and then the transparent ones from the one furthest from `eye`
+    /// to the nearest, so they blend over what is behind them.
+    pub fn draw(&mut self, cx: &mut Cx2d, draw_mesh: &mut DrawMesh, eye: Vec3) {
+        draw_mesh.set_camera_pos(cx, eye);
+        let mut transparent = Vec::new();
+        for (index, mesh) in self.meshes.iter_mut().enumerate() {
+            if mesh.material.is_transparent {
+                let distance = mesh.bounds().map( | (min, max) | {
+                    let d = (min + max) * 0.5 - eye;
+                    d.dot(d)
+                }).unwrap_or(0.0);
+                transparent.push((distance, index));
+            }
+            else {
+                draw_mesh.draw_mesh(cx, mesh);
+            }
+        }
+        transparent.sort_by( | a, b | b.0.total_cmp(&a.0));
+        for (_, index) in transparent {
+            draw_mesh.draw_mesh(cx, &mut self.meshes[index]);
+        }
+    }
+}
+
+/// A perspective camera that orbits around `target`, at `distance` from it. `yaw` turns it
+/// around the y axis, `pitch` raises it above the xz plane, both in degrees.
+#[derive(Clone, Debug, Live, LiveHook, LiveRegister)]
+pub struct OrbitCamera {
+    #[live] pub target: Vec3,
+    #[live(4.0)] pub distance: f32,
+    #[live(0.0)] pub yaw: f32,
+    #[live(20.0)] pub pitch: f32,
+    /// The vertical field of view, in degrees
+    #[live(45.0)] pub fov_y: f32,
+    #[live(0.1)] pub near: f32,
+    #[live(100.0)] pub far: f32,
+}
+
+impl OrbitCamera {
+    pub fn eye(&self) -> Vec3 {
+        let yaw = self.yaw.to_radians();
+        let pitch = self.pitch.clamp(-89.0, 89.0).to_radians();
+        self.target + vec3(pitch.cos() * yaw.sin(), pitch.sin(), pitch.cos() * yaw.cos()) * self.distance
+    }
+
+    /// The matrix from world space to the space of the camera, which looks down its -z axis
+    pub fn view_matrix(&self) -> Mat4 {
+        let eye = self.eye();
+        let f = (self.target - eye).normalize();
+        let r = Vec3::cross(f, vec3(0.0, 1.0, 0.0)).normalize();
+        let u = Vec3::cross(r, f);
+        Mat4 {v: [
+            r.x, u.x, -f.x, 0.0,
+            r.y, u.y, -f.y, 0.0,
+            r.z, u.z, -f.z, 0.0,
+            -r.dot(eye), -u.dot(eye), f.dot(eye), 1.0,
+        ]}
+    }
+
+    pub fn matrix_mode(&self) -> PassMatrixMode {
+        PassMatrixMode::Projection {
+            fov_y: self.fov_y,
+            near: self.near,
+            far: self.far,
+            cam: self.view_matrix(),
+        }
+    }
+
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = (self.yaw + yaw) % 360.0;
+        self.pitch = (self.pitch + pitch).clamp(-89.0, 89.0);
+    }
+
+    /// Multiplies the distance by `factor`, staying between the near and the far plane
+    pub fn zoom(&mut self, factor: f32) {
+        self.distance = (self.distance * factor).clamp(self.near * 2.0, self.far * 0.5);
+    }
+
+    /// Aims at the center of `bounds`, from as close as the sphere around them fits in view
+    pub fn fit(&mut self, bounds: (Vec3, Vec3)) {
+        let (min, max) = bounds;
+        let radius = ((max - min) * 0.5).dot((max - min) * 0.5).sqrt().max(0.001);
+        self.target = (min + max) * 0.5;
+        self.distance = radius / (self.fov_y.to_radians() * 0.5).sin();
+        self.near = self.near.min(self.distance * 0.1);
+        self.far = self.far.max((self.distance + radius) * 2.0);
+    }
+}
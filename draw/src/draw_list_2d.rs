@@ -2,6 +2,7 @@ use {
     crate::{
         makepad_platform::*,
         nav::*,
+        access::AccessItem,
         cx_2d::{Cx2d},
         turtle::{Walk,AlignEntry}
     }
@@ -100,7 +101,7 @@ impl DrawList2d {
         
         let codeflow_parent_id = cx.draw_list_stack.last().cloned().unwrap();
         
-        let overlay_id = cx.overlay_id.unwrap();
+        let overlay_id = *cx.overlay_stack.last().unwrap();
         if always_last{
             cx.draw_lists[overlay_id].store_sub_list_last(redraw_id, self.draw_list.id());
         }
@@ -109,6 +110,7 @@ impl DrawList2d {
         }
         
         cx.nav_list_item_push(codeflow_parent_id, NavItem::Child(self.draw_list.id()));
+        cx.access_list_item_push(codeflow_parent_id, AccessItem::Child(self.draw_list.id()));
         
         cx.cx.draw_lists[self.draw_list.id()].codeflow_parent_id = Some(codeflow_parent_id);
         if cx.passes[pass_id].main_draw_list_id.unwrap() == self.draw_list.id() {
@@ -118,6 +120,7 @@ impl DrawList2d {
         cx.cx.draw_lists[self.draw_list.id()].clear_draw_items(redraw_id);
         
         cx.nav_list_clear(self.draw_list.id());
+        cx.access_list_clear(self.draw_list.id());
         
         cx.draw_list_stack.push(self.draw_list.id());
     }
@@ -158,6 +161,7 @@ impl DrawList2d {
                 parent.append_sub_list(redraw_id, self.draw_list.id());
                 
                 cx.nav_list_item_push(parent_id, NavItem::Child(self.draw_list.id()));
+                cx.access_list_item_push(parent_id, AccessItem::Child(self.draw_list.id()));
             }
         }
         
@@ -184,6 +188,7 @@ impl DrawList2d {
         cx.cx.draw_lists[self.draw_list.id()].clear_draw_items(redraw_id);
         
         cx.nav_list_clear(self.draw_list.id());
+        cx.access_list_clear(self.draw_list.id());
         
         cx.draw_list_stack.push(self.draw_list.id());
         
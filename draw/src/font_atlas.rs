@@ -17,34 +17,48 @@ pub use {
     },
     crate::{
         makepad_platform::*,
+        color_glyph,
         cx_2d::Cx2d,
+        system_fonts::SystemFonts,
+        hyphenation::Hyphenator,
         turtle::{Walk, Layout},
         draw_list_2d::{ManyInstances, DrawList2d, RedrawingApi},
         geometry::GeometryQuad2D,
         makepad_vector::font::Glyph,
         makepad_vector::trapezoidator::Trapezoidator,
-        makepad_vector::geometry::{AffineTransformation, Transform, Vector},
+        makepad_vector::geometry::{AffineTransformation, Rectangle, Transform, Vector},
         makepad_vector::internal_iter::ExtendFromInternalIterator,
         makepad_vector::path::PathIterator,
     },
     fxhash::FxHashMap,
-    makepad_rustybuzz::{Direction, GlyphBuffer},
+    makepad_rustybuzz::{Direction, GlyphBuffer, Variation},
     makepad_vector::ttf_parser::GlyphId,
     unicode_segmentation::UnicodeSegmentation
 };
 
 pub(crate) const ATLAS_WIDTH: usize = 4096;
 pub(crate) const ATLAS_HEIGHT: usize = 4096;
+// color glyphs are few and stored at their drawn size, so their page can be smaller
+pub(crate) const COLOR_ATLAS_SIZE: usize = 2048;
 
 pub struct CxFontAtlas {
     pub fonts: Vec<Option<CxFont >>,
     pub path_to_font_id: HashMap<Rc<str>, usize>,
     pub font_id_to_path: HashMap<usize, Rc<str>>,
     pub texture_sdf: Texture,
-    pub texture_svg: Texture,
+    /// Premultiplied RGBA page for color glyphs, see `color_glyph`
+    pub texture_color: Texture,
     pub clear_buffer: bool,
     pub alloc: CxFontsAtlasAlloc,
+    pub color_alloc: CxFontsAtlasAlloc,
     pub font_cache: Option<FontCache>,
+    /// Fonts installed on the system that text falls back to after its own fonts, see
+    /// `Cx2d::enable_system_fonts`
+    pub system_fonts: Option<SystemFonts>,
+    // the atlas font loaded for each system font that got used
+    system_font_ids: HashMap<usize, Option<usize>>,
+    // the hyphenation dictionaries loaded by path, `None` if loading failed
+    hyphenators: HashMap<Rc<str>, Option<Rc<Hyphenator>>>,
 }
 
 /// A cache for rasterized glyph data.
@@ -254,19 +268,29 @@ impl CxShapeCache {
         }).collect()
     }
 
+    /// Forgets all shaped text, for when the fonts it was shaped with change
+    pub fn clear(&mut self) {
+        self.shape_keys.clear();
+        self.shapes.clear();
+    }
+
     fn shape_full<'a>(
         &'a mut self,
         direction: Direction,
         text: &str,
         font_ids: &[usize],
-        font_atlas: &CxFontAtlas,
+        font_atlas: &mut CxFontAtlas,
     ) -> &'a [GlyphInfo] {
         if !self.shapes.contains_key(&(direction, text, font_ids) as &(dyn ShapeKey)) {
             let shape_key = (direction, text.into(), font_ids.into());
             let mut glyph_infos = Vec::new();
-            let _ = self.shape_full_recursive(
+            let _ = Self::shape_full_recursive(
+                direction,
                 text,
+                0,
                 font_ids,
+                None,
+                true,
                 font_atlas,
                 &mut glyph_infos,
             );
@@ -280,66 +304,132 @@ impl CxShapeCache {
         &self.shapes[&(direction, text, font_ids) as &(dyn ShapeKey)]
     }
 
+    // Shapes `text` with the first font, then reshapes each run of clusters it has no glyphs for
+    // with the fonts after it, and with the system fonts after the last one. A cluster is taken
+    // whole from one font, so a base character and its marks never end up split across fonts.
+    // `offset` is where `text` starts in the text being shaped, clusters are relative to that.
+    // Clusters no font covers get the missing glyph of `notdef_font_id`, the first font by default.
     fn shape_full_recursive(
-        &mut self,
+        direction: Direction,
         text: &str,
+        offset: usize,
         font_ids: &[usize],
-        font_atlas: &CxFontAtlas,
+        notdef_font_id: Option<usize>,
+        system_fallback: bool,
+        font_atlas: &mut CxFontAtlas,
         glyph_infos: &mut Vec<GlyphInfo>,
     ) -> Result<(), ()> {
         let Some((&font_id, font_ids)) = font_ids.split_first() else {
             return Err(());
         };
         let Some(font) = &font_atlas.fonts[font_id] else {
-            return self.shape_full_recursive(text, font_ids, font_atlas, glyph_infos);
+            return Self::shape_full_recursive(direction, text, offset, font_ids, notdef_font_id, system_fallback, font_atlas, glyph_infos);
         };
+        let notdef_font_id = notdef_font_id.unwrap_or(font_id);
 
         let mut buffer = UnicodeBuffer::new();
+        buffer.set_direction(direction);
         buffer.push_str(text);
         let buffer = font.owned_font_face.with_ref(|face| {
             makepad_rustybuzz::shape(face, &[], buffer)
         });
-
         let infos = buffer.glyph_infos();
-        let mut info_iter = infos.iter();
-        let mut info_slot = info_iter.next();
-        while let Some(info) = info_slot {
-            if info.glyph_id != 0 {
-                info_slot = info_iter.next();
-                glyph_infos.push(GlyphInfo {
-                    font_id,
-                    glyph_id: info.glyph_id as usize,
-                    cluster: info.cluster as usize,
-                });
-            } else {
-                let start = info.cluster as usize;
-                let end = loop {
-                    info_slot = info_iter.next();
-                    if let Some(info) = info_slot {
-                        if info.glyph_id != 0 {
-                            break info.cluster as usize;
-                        }
-                    } else {
-                        break text.len()
-                    }
-                };
-                if self.shape_full_recursive(
-                    &text[start..end],
-                    font_ids,
-                    font_atlas,
-                    glyph_infos,
-                ).is_err() {
-                    glyph_infos.push(GlyphInfo {
-                        font_id,
-                        glyph_id: info.glyph_id as usize,
-                        cluster: info.cluster as usize,
-                    });
+
+        let cluster_end = |start: usize| {
+            let cluster = infos[start].cluster;
+            infos[start..].iter().position(|info| info.cluster != cluster).map_or(infos.len(), |len| start + len)
+        };
+        let push_glyphs = |glyph_infos: &mut Vec<GlyphInfo>, start: usize, end: usize| {
+            glyph_infos.extend(infos[start..end].iter().map(|info| GlyphInfo {
+                font_id: if info.glyph_id == 0 { notdef_font_id } else { font_id },
+                glyph_id: info.glyph_id as usize,
+                cluster: offset + info.cluster as usize,
+            }));
+        };
+
+        let mut start = 0;
+        while start < infos.len() {
+            let end = cluster_end(start);
+            if infos[start..end].iter().all(|info| info.glyph_id != 0) {
+                push_glyphs(glyph_infos, start, end);
+                start = end;
+                continue;
+            }
+            // take all the clusters up to the next one this font does have
+            let mut missing_end = end;
+            while missing_end < infos.len() {
+                let end = cluster_end(missing_end);
+                if infos[missing_end..end].iter().all(|info| info.glyph_id != 0) {
+                    break;
                 }
+                missing_end = end;
             }
+            // right to left runs come out in visual order, so clusters can decrease
+            let run_clusters = infos[start..missing_end].iter().map(|info| info.cluster as usize);
+            let text_start = run_clusters.clone().min().unwrap();
+            let run_last = run_clusters.max().unwrap();
+            let text_end = infos.iter()
+                .map(|info| info.cluster as usize)
+                .filter(|&cluster| cluster > run_last)
+                .min()
+                .unwrap_or(text.len());
+            let run = &text[text_start..text_end];
+            let shaped = if !font_ids.is_empty() {
+                Self::shape_full_recursive(direction, run, offset + text_start, font_ids, Some(notdef_font_id), system_fallback, font_atlas, glyph_infos)
+            } else if system_fallback {
+                Self::shape_system_fallback(direction, run, offset + text_start, notdef_font_id, font_atlas, glyph_infos)
+            } else {
+                Err(())
+            };
+            if shaped.is_err() {
+                // nothing after this font covers these either
+                push_glyphs(glyph_infos, start, missing_end);
+            }
+            start = missing_end;
         }
 
         Ok(())
     }
+
+    // Shapes runs of clusters with the system font that covers them, clusters no system font
+    // covers get the missing glyph of `notdef_font_id`.
+    fn shape_system_fallback(
+        direction: Direction,
+        text: &str,
+        offset: usize,
+        notdef_font_id: usize,
+        font_atlas: &mut CxFontAtlas,
+        glyph_infos: &mut Vec<GlyphInfo>,
+    ) -> Result<(), ()> {
+        if font_atlas.system_fonts.is_none() {
+            return Err(());
+        }
+        let mut runs: Vec<(usize, usize, Option<usize>)> = Vec::new();
+        for (index, cluster) in text.grapheme_indices(true) {
+            let font_id = font_atlas.system_font_for_cluster(cluster);
+            match runs.last_mut() {
+                Some((_, end, last_font_id)) if *last_font_id == font_id => *end = index + cluster.len(),
+                _ => runs.push((index, index + cluster.len(), font_id)),
+            }
+        }
+        if runs.iter().all(|(_, _, font_id)| font_id.is_none()) {
+            return Err(());
+        }
+        for (start, end, font_id) in runs {
+            let shaped = match font_id {
+                Some(font_id) => Self::shape_full_recursive(direction, &text[start..end], offset + start, &[font_id], Some(notdef_font_id), false, font_atlas, glyph_infos),
+                None => Err(()),
+            };
+            if shaped.is_err() {
+                glyph_infos.extend(text[start..end].grapheme_indices(true).map(|(index, _)| GlyphInfo {
+                    font_id: notdef_font_id,
+                    glyph_id: 0,
+                    cluster: offset + start + index,
+                }));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -431,13 +521,13 @@ pub struct CxFontsAtlasSdfConfig {
 }
 
 impl CxFontAtlas {
-    pub fn new(texture_sdf: Texture, texture_svg: Texture, os_type: &OsType) -> Self {
+    pub fn new(texture_sdf: Texture, texture_color: Texture, os_type: &OsType) -> Self {
         Self {
             fonts: Vec::new(),
             path_to_font_id: HashMap::new(),
             font_id_to_path: HashMap::new(),
             texture_sdf,
-            texture_svg,
+            texture_color,
             clear_buffer: false,
             alloc: CxFontsAtlasAlloc {
                 full: false,
@@ -459,11 +549,29 @@ impl CxFontAtlas {
                     },
                 })
             },
+            color_alloc: CxFontsAtlasAlloc {
+                texture_size: DVec2 {
+                    x: COLOR_ATLAS_SIZE as f64,
+                    y: COLOR_ATLAS_SIZE as f64
+                },
+                ..Default::default()
+            },
             font_cache: Some(FontCache::new(os_type.get_cache_dir())),
+            system_fonts: None,
+            system_font_ids: HashMap::new(),
+            hyphenators: HashMap::new(),
         }
     }
 }
 impl CxFontsAtlasAlloc {
+    pub fn reset(&mut self) {
+        self.todo.clear();
+        self.full = false;
+        self.xpos = 0;
+        self.ypos = 0;
+        self.hmax = 0;
+    }
+
     pub fn alloc_atlas_glyph(&mut self, w: f64, h: f64, todo: CxFontsAtlasTodo) -> CxFontAtlasGlyph {
         // In SDF mode, leave enough room around each glyph (i.e. padding).
         let pad = self.sdf.as_ref().map_or(0, |sdf| sdf.params.pad);
@@ -530,6 +638,23 @@ pub struct Font {
     #[live] pub path: LiveDependency
 }
 
+/// Whether text is drawn upright or slanted
+#[derive(Clone, Copy, Debug, Default, Live, LiveHook, PartialEq)]
+#[live_ignore]
+pub enum FontStyle {
+    #[pick] #[default] Normal,
+    Italic,
+}
+
+/// One font of a family, with the weight and style it was made for, see `TextStyle::font_family`
+#[derive(Debug, Clone, Live, LiveHook, LiveRegister)]
+#[live_ignore]
+pub struct FontFace {
+    #[live] pub font: Font,
+    #[live(400.0)] pub weight: f64,
+    #[live] pub style: FontStyle,
+}
+
 #[derive(Clone)]
 pub struct CxFontsAtlasRc(pub Rc<RefCell<CxFontAtlas>>);
 
@@ -576,17 +701,114 @@ impl CxFontAtlas {
         font_id
     }
     
+    /// The hyphenation dictionary at `path`, parsing it on first use
+    pub fn get_hyphenator_by_path(&mut self, cx: &mut Cx, path: &str) -> Option<Rc<Hyphenator>> {
+        if let Some(hyphenator) = self.hyphenators.get(path) {
+            return hyphenator.clone();
+        }
+        let hyphenator = match cx.take_dependency(path) {
+            Ok(data) => match std::str::from_utf8(&data) {
+                Ok(source) => Some(Rc::new(Hyphenator::parse(source))),
+                Err(err) => {
+                    error!("Error loading hyphenation dictionary {} {}", path, err);
+                    None
+                }
+            }
+            Err(err) => {
+                error!("get_hyphenator_by_path - {} {}", path, err);
+                None
+            }
+        };
+        self.hyphenators.insert(path.into(), hyphenator.clone());
+        hyphenator
+    }
+
+    /// The atlas font for the system font that covers `cluster`, loading it on first use
+    pub fn system_font_for_cluster(&mut self, cluster: &str) -> Option<usize> {
+        let system_fonts = self.system_fonts.as_mut()?;
+        let index = system_fonts.font_for_cluster(cluster)?;
+        if let Some(font_id) = self.system_font_ids.get(&index) {
+            return *font_id;
+        }
+        let path = system_fonts.path(index).to_path_buf();
+        let font_id = match std::fs::read(&path).map_err(|err| err.to_string()).and_then(|data| {
+            CxFont::load_from_ttf_bytes(Rc::new(data)).map_err(|err| err.to_string())
+        }) {
+            Ok(cxfont) => {
+                let font_id = self.fonts.len();
+                self.fonts.push(Some(cxfont));
+                let path: Rc<str> = path.to_string_lossy().into();
+                self.font_id_to_path.insert(font_id, path.clone());
+                self.path_to_font_id.insert(path, font_id);
+                Some(font_id)
+            }
+            Err(err) => {
+                error!("Error loading system font {} {}", path.display(), err);
+                None
+            }
+        };
+        self.system_font_ids.insert(index, font_id);
+        font_id
+    }
+
+    /// The font for `font_id` with its `wght` axis set to `weight` and, if `italic`, its `ital`
+    /// or else `slnt` axis set, loading it on first use. A font without these axes, or where they
+    /// are at their defaults already, is returned as it is.
+    pub fn font_variant(&mut self, font_id: usize, weight: f64, italic: bool) -> usize {
+        let Some(font) = self.fonts.get(font_id).and_then(|font| font.as_ref()) else {
+            return font_id;
+        };
+        let variations: Vec<Variation> = font.owned_font_face.with_ref(|face| {
+            let axes = face.variation_axes();
+            let has_ital = axes.into_iter().any(|axis| &axis.tag.to_bytes() == b"ital");
+            axes.into_iter().filter_map(|axis| {
+                let value = match &axis.tag.to_bytes() {
+                    b"wght" => weight as f32,
+                    b"ital" if italic => 1.0,
+                    // the slant of most oblique fonts, in degrees counter-clockwise
+                    b"slnt" if italic && !has_ital => -12.0,
+                    _ => return None,
+                };
+                let value = value.clamp(axis.min_value, axis.max_value);
+                (value != axis.def_value).then_some(Variation {tag: axis.tag, value})
+            }).collect()
+        });
+        if variations.is_empty() {
+            return font_id;
+        }
+        let Some(path) = self.font_id_to_path.get(&font_id) else {
+            return font_id;
+        };
+        // the path doubles as the key of the glyphs in the font cache, so every instance needs its own
+        let settings: Vec<String> = variations.iter().map(|variation| format!("{}={}", variation.tag, variation.value)).collect();
+        let path: Rc<str> = format!("{}#{}", path, settings.join(",")).into();
+        if let Some(variant_id) = self.path_to_font_id.get(&path) {
+            return *variant_id;
+        }
+        let bytes = font.owned_font_face.font_data();
+        match CxFont::load_from_ttf_bytes_with_variations(bytes, &variations) {
+            Ok(cxfont) => {
+                let variant_id = self.fonts.len();
+                self.fonts.push(Some(cxfont));
+                self.font_id_to_path.insert(variant_id, path.clone());
+                self.path_to_font_id.insert(path, variant_id);
+                variant_id
+            }
+            Err(err) => {
+                error!("Error loading font variant {} {}", path, err);
+                font_id
+            }
+        }
+    }
+
     pub fn reset_fonts_atlas(&mut self) {
         for cxfont in &mut self.fonts {
             if let Some(cxfont) = cxfont {
                 cxfont.atlas_pages.clear();
             }
         }
-        self.alloc.todo.clear();
-        self.alloc.full = false;
-        self.alloc.xpos = 0;
-        self.alloc.ypos = 0;
-        self.alloc.hmax = 0;
+        self.alloc.reset();
+        self.color_alloc.reset();
         self.clear_buffer = true;
     }
     
@@ -608,14 +830,14 @@ impl<'a> Cx2d<'a> {
                 updated: TextureUpdated::Empty,
             });
 
-            let texture_svg = Texture::new_with_format(cx, TextureFormat::VecBGRAu8_32 {
-                width: ATLAS_WIDTH,
-                height: ATLAS_HEIGHT,
+            let texture_color = Texture::new_with_format(cx, TextureFormat::VecBGRAu8_32 {
+                width: COLOR_ATLAS_SIZE,
+                height: COLOR_ATLAS_SIZE,
                 data: Some(vec![]),
-                updated: TextureUpdated::Full,
+                updated: TextureUpdated::Empty,
             });
             
-            let fonts_atlas = CxFontAtlas::new(texture_sdf, texture_svg, cx.os_type());
+            let fonts_atlas = CxFontAtlas::new(texture_sdf, texture_color, cx.os_type());
             cx.set_global(CxFontsAtlasRc(Rc::new(RefCell::new(fonts_atlas))));
         }
     }
@@ -625,6 +847,23 @@ impl<'a> Cx2d<'a> {
             cx.set_global(ShapeCacheRc(Rc::new(RefCell::new(CxShapeCache::new()))));
         }
     }
+
+    /// Lets text fall back to the fonts installed on the system for characters none of its own
+    /// fonts cover. Pass `None` to turn it off again.
+    pub fn set_system_fonts(cx: &mut Cx, system_fonts: Option<SystemFonts>) {
+        Self::lazy_construct_font_atlas(cx);
+        Self::lazy_construct_shape_cache(cx);
+        let font_atlas_rc = cx.get_global::<CxFontsAtlasRc>().clone();
+        let mut font_atlas = font_atlas_rc.0.borrow_mut();
+        font_atlas.system_fonts = system_fonts;
+        font_atlas.system_font_ids.clear();
+        cx.get_global::<ShapeCacheRc>().0.borrow_mut().clear();
+    }
+
+    /// Scans the standard font directories and falls back to the fonts found there
+    pub fn enable_system_fonts(cx: &mut Cx) {
+        Self::set_system_fonts(cx, Some(SystemFonts::scan()));
+    }
     
     pub fn reset_fonts_atlas(cx:&mut Cx){
         if cx.has_global::<CxFontsAtlasRc>() {
@@ -638,7 +877,7 @@ impl<'a> Cx2d<'a> {
         let mut fonts_atlas = fonts_atlas_rc.0.borrow_mut();
         let fonts_atlas = &mut*fonts_atlas;
 
-        if fonts_atlas.alloc.full {
+        if fonts_atlas.alloc.full || fonts_atlas.color_alloc.full {
             fonts_atlas.reset_fonts_atlas();
         }
 
@@ -648,6 +887,9 @@ impl<'a> Cx2d<'a> {
         for todo in mem::take(&mut fonts_atlas.alloc.todo) {
             self.swrast_atlas_todo(fonts_atlas, todo, &mut reuse_sdfer_bufs);
         }
+        for todo in mem::take(&mut fonts_atlas.color_alloc.todo) {
+            self.swrast_atlas_todo_color(fonts_atlas, todo);
+        }
     }
 
     fn swrast_atlas_todo(
@@ -734,6 +976,59 @@ impl<'a> Cx2d<'a> {
         font_atlas.font_cache = Some(font_cache);
     }
 
+    fn swrast_atlas_todo_color(&mut self, font_atlas: &mut CxFontAtlas, todo: CxFontsAtlasTodo) {
+        let font = font_atlas.fonts[todo.font_id].as_mut().unwrap();
+        let Some(bounds) = font.get_color_glyph_bounds(todo.glyph_id) else {
+            return;
+        };
+        let atlas_page = &font.atlas_pages[todo.atlas_page_id];
+        let atlas_glyph = *atlas_page.atlas_glyphs.get(&todo.glyph_id).unwrap();
+        let font_scale_pixels = atlas_page.font_size_in_device_pixels;
+        let texture_size = font_atlas.color_alloc.texture_size;
+
+        // Mapped into the allocation the same way `rasterize_sdf` maps outlines, there's just
+        // no SDF padding around it.
+        let render_pad_dpx = 2.0;
+        let render_wh = dvec2(
+            ((bounds.p_max.x - bounds.p_min.x) * font_scale_pixels).ceil() + render_pad_dpx * 2.0,
+            ((bounds.p_max.y - bounds.p_min.y) * font_scale_pixels).ceil() + render_pad_dpx * 2.0,
+        );
+        let atlas_alloc_wh = dvec2(
+            (atlas_glyph.t2.x - atlas_glyph.t1.x) as f64 * texture_size.x + 1.0,
+            (atlas_glyph.t2.y - atlas_glyph.t1.y) as f64 * texture_size.y + 1.0,
+        );
+        let atlas_scaling = atlas_alloc_wh / render_wh;
+        let transform = AffineTransformation::identity()
+            .translate(Vector::new(-bounds.p_min.x, -bounds.p_min.y))
+            .uniform_scale(font_scale_pixels)
+            .translate(Vector::new(render_pad_dpx, render_pad_dpx))
+            .scale(Vector::new(atlas_scaling.x, atlas_scaling.y));
+
+        let (width, height) = (atlas_alloc_wh.x.round() as usize, atlas_alloc_wh.y.round() as usize);
+        let Some(pixels) = font.owned_font_face.with_ref(|face| {
+            color_glyph::rasterize_color_glyph(face, GlyphId(todo.glyph_id as u16), transform, width, height)
+        }) else {
+            return;
+        };
+
+        let mut atlas_data = font_atlas.texture_color.take_vec_u32(self.cx);
+        let (atlas_w, atlas_h) = font_atlas.texture_color.get_format(self.cx).vec_width_height().unwrap();
+        if atlas_data.is_empty() {
+            atlas_data = vec![0; atlas_w * atlas_h];
+        }
+
+        let atlas_x0 = (atlas_glyph.t1.x as f64 * texture_size.x).round() as usize;
+        let atlas_y0 = (atlas_glyph.t1.y as f64 * texture_size.y).round() as usize;
+        for y in 0..height {
+            atlas_data[(atlas_h - atlas_y0 - 1 - y) * atlas_w + atlas_x0..][..width].copy_from_slice(&pixels[y * width..][..width]);
+        }
+
+        font_atlas.texture_color.put_back_vec_u32(self.cx, atlas_data, Some(RectUsize::new(
+            PointUsize::new(atlas_x0, atlas_h - atlas_y0 - height),
+            SizeUsize::new(width, height),
+        )));
+    }
+
     fn rasterize_sdf(
         &mut self,
         fonts_atlas: &mut CxFontAtlas,
@@ -853,6 +1148,8 @@ pub struct CxFont {
     pub glyph_ids: Box<[Option<GlyphId>]>,
     pub atlas_pages: Vec<CxFontAtlasPage>,
     pub shape_cache: OldShapeCache,
+    // the bounds of the color version of each glyph looked up, `None` for plain outlines
+    pub color_glyph_bounds: HashMap<usize, Option<Rectangle>>,
 }
 
 impl CxFont {
@@ -976,7 +1273,12 @@ pub struct CxFontsAtlasTodo {
 
 impl CxFont {
     pub fn load_from_ttf_bytes(bytes: Rc<Vec<u8>>) -> Result<Self, crate::owned_font_face::FaceParsingError> {
-        let owned_font_face = crate::owned_font_face::OwnedFace::parse(bytes, 0)?;
+        Self::load_from_ttf_bytes_with_variations(bytes, &[])
+    }
+
+    /// Loads an instance of a variable font, see `OwnedFace::parse_with_variations`
+    pub fn load_from_ttf_bytes_with_variations(bytes: Rc<Vec<u8>>, variations: &[Variation]) -> Result<Self, crate::owned_font_face::FaceParsingError> {
+        let owned_font_face = crate::owned_font_face::OwnedFace::parse_with_variations(bytes, 0, variations)?;
         let ttf_font = owned_font_face.with_ref(|face| makepad_vector::ttf_parser::from_ttf_parser_face(face));
         Ok(Self {
             ttf_font,
@@ -984,6 +1286,7 @@ impl CxFont {
             glyph_ids: vec![None; 0x10FFFF].into_boxed_slice(),
             atlas_pages: Vec::new(),
             shape_cache: OldShapeCache::new(),
+            color_glyph_bounds: HashMap::new(),
         })
     }
     
@@ -1021,6 +1324,17 @@ impl CxFont {
         }
     }
 
+    /// The bounds in font units of the color version of a glyph, from its `COLR` layers or its
+    /// `SVG ` document, `None` if the glyph is only an outline
+    pub fn get_color_glyph_bounds(&mut self, id: usize) -> Option<Rectangle> {
+        if let Some(bounds) = self.color_glyph_bounds.get(&id) {
+            return *bounds;
+        }
+        let bounds = self.owned_font_face.with_ref(|face| color_glyph::color_glyph_bounds(face, GlyphId(id as u16)));
+        self.color_glyph_bounds.insert(id, bounds);
+        bounds
+    }
+
     pub fn get_glyph_by_id(&mut self, id: usize) -> makepad_vector::ttf_parser::Result<&Glyph> {
         self.owned_font_face.with_ref(|face| self.ttf_font.get_glyph_by_id(face, id))
     }
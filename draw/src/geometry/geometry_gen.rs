@@ -12,6 +12,11 @@ live_design!{
         x2: 1.0;
         y2: 1.0;
     }
+    GeometryCube3D = {{GeometryCube3D}} {
+        width: 1.0;
+        height: 1.0;
+        depth: 1.0;
+    }
 }
 
 impl LiveHook for GeometryQuad2D {
@@ -61,6 +66,53 @@ pub struct GeometryQuad2D {
     #[live(1.0)] pub y2: f32,
 }
 
+impl LiveHook for GeometryCube3D {
+    fn after_apply(&mut self, cx: &mut Cx, _apply:&mut Apply, _index:usize, _nodes:&[LiveNode]) {
+        let mut fp = GeometryFingerprint::new(LiveType::of::<Self>());
+        fp.push(self.width);
+        fp.push(self.height);
+        fp.push(self.depth);
+        fp.push(self.segments as f32);
+        self.geometry_ref = Some(cx.get_geometry_ref(fp));
+        GeometryGen::from_cube_3d(
+            self.width,
+            self.height,
+            self.depth,
+            self.segments,
+            self.segments,
+            self.segments,
+        ).to_geometry(cx, &self.geometry_ref.as_ref().unwrap().0);
+    }
+}
+
+impl GeometryFields for GeometryCube3D {
+    fn geometry_fields(&self, fields: &mut Vec<GeometryField>) {
+        fields.push(GeometryField {id: live_id!(geom_pos), ty: ShaderTy::Vec3});
+        fields.push(GeometryField {id: live_id!(geom_id), ty: ShaderTy::Float});
+        fields.push(GeometryField {id: live_id!(geom_normal), ty: ShaderTy::Vec3});
+        fields.push(GeometryField {id: live_id!(geom_uv), ty: ShaderTy::Vec2});
+    }
+    
+    fn get_geometry_id(&self) -> Option<GeometryId> {
+        self.geometry_ref.as_ref().map( | gr | gr.0.geometry_id())
+    }
+    
+    fn live_type_check(&self) -> LiveType {
+        LiveType::of::<Self>()
+    }
+}
+
+/// A box around the origin, in the layout of `GeometryGen::add_cube_3d`, which other 3D
+/// geometries use too so they can be drawn with the same shaders.
+#[derive(Live, LiveRegister)]
+pub struct GeometryCube3D {
+    #[rust] pub geometry_ref: Option<GeometryRef>,
+    #[live(1.0)] pub width: f32,
+    #[live(1.0)] pub height: f32,
+    #[live(1.0)] pub depth: f32,
+    #[live(1usize)] pub segments: usize,
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct GeometryGen {
     pub vertices: Vec<f32>, // vec4 pos, vec3 normal, vec2 uv
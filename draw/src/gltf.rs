@@ -0,0 +1,333 @@
+//! Loading of glTF 2.0 scenes, from a `.glb` file or from a `.gltf` file with its buffers
+//! embedded as data uris. The meshes of the default scene are flattened into world space, with
+//! the factors of their materials; textures, skins and animations are not loaded.
+
+use {
+    crate::{
+        makepad_platform::*,
+        makepad_platform::makepad_micro_serde::{DeJson, JsonValue},
+        cx_3d::{Material3d, Mesh3d, Scene3d},
+    },
+    makepad_base64::base64_decode,
+};
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_CHUNK_JSON: u32 = 0x4E4F534A;
+const GLB_CHUNK_BIN: u32 = 0x004E4942;
+const MODE_TRIANGLES: usize = 4;
+// the most values an accessor may have, so a count that is far too large is an error rather
+// than an allocation that fails
+const MAX_ACCESSOR_VALUES: usize = 1 << 26;
+
+impl Scene3d {
+    /// Parses a glTF 2.0 file, either a binary `.glb` or the json of a `.gltf`.
+    pub fn parse_gltf(data: &[u8]) -> Result<Self, String> {
+        let (json, bin) = if data.starts_with(GLB_MAGIC) {
+            parse_glb(data)?
+        }
+        else {
+            (std::str::from_utf8(data).map_err( | _ | "gltf is not utf8".to_string())?, None)
+        };
+        let json = JsonValue::deserialize_json(json).map_err( | e | format!("gltf json: {:?}", e))?;
+        Gltf::new(&json, bin)?.scene()
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+// the json chunk and the binary chunk of a glb
+fn parse_glb(data: &[u8]) -> Result<(&str, Option<&[u8]>), String> {
+    if read_u32(data, 4) != Some(2) {
+        return Err("only version 2 of glb is supported".to_string())
+    }
+    let length = (read_u32(data, 8).unwrap_or(0) as usize).min(data.len());
+    let mut json = None;
+    let mut bin = None;
+    let mut offset = 12;
+    while offset + 8 <= length {
+        let chunk_length = read_u32(data, offset).unwrap() as usize;
+        let chunk_type = read_u32(data, offset + 4).unwrap();
+        let chunk = data.get(offset + 8..offset + 8 + chunk_length).ok_or("glb chunk out of bounds")?;
+        match chunk_type {
+            GLB_CHUNK_JSON => json = Some(std::str::from_utf8(chunk).map_err( | _ | "glb json is not utf8")?),
+            GLB_CHUNK_BIN => bin = Some(chunk),
+            _ => ()
+        }
+        offset += 8 + chunk_length;
+    }
+    Ok((json.ok_or("glb without a json chunk")?, bin))
+}
+
+fn number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::U64(v) => Some(*v as f64),
+        JsonValue::I64(v) => Some(*v as f64),
+        JsonValue::F64(v) => Some(*v),
+        _ => None
+    }
+}
+
+fn array<'a>(value: &'a JsonValue, key: &str) -> &'a [JsonValue] {
+    match value.key(key) {
+        Some(JsonValue::Array(array)) => array,
+        _ => &[]
+    }
+}
+
+fn index(value: &JsonValue, key: &str) -> Option<usize> {
+    value.key(key).and_then(number).map( | v | v as usize)
+}
+
+fn factor(value: &JsonValue, key: &str, default: f64) -> f32 {
+    value.key(key).and_then(number).unwrap_or(default) as f32
+}
+
+fn factors(value: &JsonValue, key: &str) -> Option<Vec<f32>> {
+    value.key(key)?;
+    Some(array(value, key).iter().map( | v | number(v).unwrap_or(0.0) as f32).collect())
+}
+
+fn get<'a>(values: &'a [JsonValue], index: usize, what: &str) -> Result<&'a JsonValue, String> {
+    values.get(index).ok_or_else( | | format!("gltf {} {} out of bounds", what, index))
+}
+
+struct Gltf<'a> {
+    json: &'a JsonValue,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl<'a> Gltf<'a> {
+    fn new(json: &'a JsonValue, bin: Option<&[u8]>) -> Result<Self, String> {
+        let mut buffers = Vec::new();
+        for buffer in array(json, "buffers") {
+            let length = index(buffer, "byteLength").unwrap_or(0);
+            let mut data = match buffer.key("uri").and_then( | uri | uri.string()) {
+                // in a glb the buffer without a uri is the binary chunk
+                None => bin.ok_or("gltf buffer without data")?.to_vec(),
+                Some(uri) if uri.starts_with("data:") => {
+                    let base64 = uri.split_once(";base64,").ok_or("gltf data uri is not base64")?.1;
+                    base64_decode(base64.as_bytes()).map_err( | e | format!("gltf data uri: {:?}", e))?
+                }
+                Some(uri) => return Err(format!("gltf buffer {} is external, only embedded buffers are supported", uri))
+            };
+            if data.len() < length {
+                return Err("gltf buffer is shorter than its byteLength".to_string())
+            }
+            data.truncate(length);
+            buffers.push(data);
+        }
+        Ok(Self {json, buffers})
+    }
+
+    // the elements of an accessor, with the components of each one after another
+    fn accessor(&self, accessor: usize) -> Result<(Vec<f64>, usize), String> {
+        let accessor = get(array(self.json, "accessors"), accessor, "accessor")?;
+        if accessor.key("sparse").is_some() {
+            return Err("gltf sparse accessors are not supported".to_string())
+        }
+        let count = index(accessor, "count").unwrap_or(0);
+        let components = match accessor.key("type").and_then( | t | t.string()).map( | t | t.as_str()) {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") => 4,
+            Some("MAT4") => 16,
+            t => return Err(format!("gltf accessor type {:?} is not supported", t))
+        };
+        let component_type = index(accessor, "componentType").unwrap_or(0);
+        let size = match component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            _ => return Err(format!("gltf component type {} is not supported", component_type))
+        };
+        let normalized = matches!(accessor.key("normalized"), Some(JsonValue::Bool(true)));
+        let len = count.checked_mul(components).filter( | len | *len <= MAX_ACCESSOR_VALUES)
+            .ok_or("gltf accessor count is too large")?;
+        let Some(view) = index(accessor, "bufferView") else {
+            return Ok((vec![0.0; len], components))
+        };
+        let view = get(array(self.json, "bufferViews"), view, "bufferView")?;
+        let buffer = &self.buffers.get(index(view, "buffer").unwrap_or(0)).ok_or("gltf buffer out of bounds")?;
+        let view_start = index(view, "byteOffset").unwrap_or(0);
+        let view_end = view_start + index(view, "byteLength").unwrap_or(0);
+        let buffer = buffer.get(view_start..view_end).ok_or("gltf bufferView out of bounds")?;
+        let stride = index(view, "byteStride").unwrap_or(components * size);
+        let offset = index(accessor, "byteOffset").unwrap_or(0);
+        if count > 0 && offset + (count - 1) * stride + components * size > buffer.len() {
+            return Err("gltf accessor out of bounds".to_string())
+        }
+        let mut values = Vec::with_capacity(len);
+        for element in 0..count {
+            for component in 0..components {
+                let b = &buffer[offset + element * stride + component * size..];
+                let value = match component_type {
+                    5120 if normalized => (b[0] as i8 as f64 / 127.0).max(-1.0),
+                    5121 if normalized => b[0] as f64 / 255.0,
+                    5122 if normalized => (i16::from_le_bytes([b[0], b[1]]) as f64 / 32767.0).max(-1.0),
+                    5123 if normalized => u16::from_le_bytes([b[0], b[1]]) as f64 / 65535.0,
+                    5120 => b[0] as i8 as f64,
+                    5121 => b[0] as f64,
+                    5122 => i16::from_le_bytes([b[0], b[1]]) as f64,
+                    5123 => u16::from_le_bytes([b[0], b[1]]) as f64,
+                    5125 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                };
+                values.push(value);
+            }
+        }
+        Ok((values, components))
+    }
+
+    // the material, or None when it is masked out entirely
+    fn material(&self, material: Option<usize>) -> Result<Option<Material3d>, String> {
+        let mut result = Material3d::default();
+        let Some(material) = material else {
+            return Ok(Some(result))
+        };
+        let material = get(array(self.json, "materials"), material, "material")?;
+        if let Some(pbr) = material.key("pbrMetallicRoughness") {
+            if let Some(c) = factors(pbr, "baseColorFactor").filter( | c | c.len() == 4) {
+                result.base_color = vec4(c[0], c[1], c[2], c[3]);
+            }
+            result.metallic = factor(pbr, "metallicFactor", 1.0);
+            result.roughness = factor(pbr, "roughnessFactor", 1.0);
+        }
+        if let Some(e) = factors(material, "emissiveFactor").filter( | e | e.len() == 3) {
+            result.emissive = vec3(e[0], e[1], e[2]);
+        }
+        match material.key("alphaMode").and_then( | m | m.string()).map( | m | m.as_str()) {
+            Some("BLEND") => result.is_transparent = true,
+            Some("MASK") => {
+                if result.base_color.w < factor(material, "alphaCutoff", 0.5) {
+                    return Ok(None)
+                }
+                result.base_color.w = 1.0;
+            }
+            _ => result.base_color.w = 1.0
+        }
+        Ok(Some(result))
+    }
+
+    fn local_matrix(node: &JsonValue) -> Mat4 {
+        if let Some(m) = factors(node, "matrix").filter( | m | m.len() == 16) {
+            return Mat4 {v: m.try_into().unwrap()}
+        }
+        let t = factors(node, "translation").filter( | t | t.len() == 3).unwrap_or(vec![0.0; 3]);
+        let r = factors(node, "rotation").filter( | r | r.len() == 4).unwrap_or(vec![0.0, 0.0, 0.0, 1.0]);
+        let s = factors(node, "scale").filter( | s | s.len() == 3).unwrap_or(vec![1.0; 3]);
+        let (x, y, z, w) = (r[0], r[1], r[2], r[3]);
+        // the columns of the rotation, scaled, and the translation
+        Mat4 {v: [
+            (1.0 - 2.0 * (y * y + z * z)) * s[0], 2.0 * (x * y + z * w) * s[0], 2.0 * (x * z - y * w) * s[0], 0.0,
+            2.0 * (x * y - z * w) * s[1], (1.0 - 2.0 * (x * x + z * z)) * s[1], 2.0 * (y * z + x * w) * s[1], 0.0,
+            2.0 * (x * z + y * w) * s[2], 2.0 * (y * z - x * w) * s[2], (1.0 - 2.0 * (x * x + y * y)) * s[2], 0.0,
+            t[0], t[1], t[2], 1.0,
+        ]}
+    }
+
+    fn scene(&self) -> Result<Scene3d, String> {
+        let nodes = array(self.json, "nodes");
+        let roots: Vec<usize> = match array(self.json, "scenes").get(index(self.json, "scene").unwrap_or(0)) {
+            Some(scene) => array(scene, "nodes").iter().filter_map(number).map( | n | n as usize).collect(),
+            // without scenes every node that is nobody's child is drawn
+            None => (0..nodes.len()).filter( | n | {
+                !nodes.iter().any( | node | array(node, "children").iter().any( | c | number(c) == Some(*n as f64)))
+            }).collect()
+        };
+        let mut scene = Scene3d::default();
+        let mut stack: Vec<(usize, Mat4, usize)> = roots.into_iter().rev().map( | n | (n, Mat4::identity(), 0)).collect();
+        while let Some((node_index, parent, depth)) = stack.pop() {
+            if depth > nodes.len() {
+                return Err("gltf nodes form a cycle".to_string())
+            }
+            let node = get(nodes, node_index, "node")?;
+            let world = Mat4::mul(&Self::local_matrix(node), &parent);
+            if let Some(mesh) = index(node, "mesh") {
+                let mesh = get(array(self.json, "meshes"), mesh, "mesh")?;
+                for primitive in array(mesh, "primitives") {
+                    if let Some(mesh) = self.primitive(primitive, &world)? {
+                        scene.meshes.push(mesh);
+                    }
+                }
+            }
+            for child in array(node, "children").iter().rev().filter_map(number) {
+                stack.push((child as usize, world, depth + 1));
+            }
+        }
+        Ok(scene)
+    }
+
+    // a primitive in world space, None for primitives that aren't triangles or are masked out
+    fn primitive(&self, primitive: &JsonValue, world: &Mat4) -> Result<Option<Mesh3d>, String> {
+        if index(primitive, "mode").unwrap_or(MODE_TRIANGLES) != MODE_TRIANGLES {
+            return Ok(None)
+        }
+        let Some(material) = self.material(index(primitive, "material"))? else {
+            return Ok(None)
+        };
+        let attributes = primitive.key("attributes").ok_or("gltf primitive without attributes")?;
+        let attribute = | name: &str, components: usize | -> Result<Option<Vec<f64>>, String> {
+            let Some(accessor) = index(attributes, name) else {return Ok(None)};
+            let (values, n) = self.accessor(accessor)?;
+            if n != components {
+                return Err(format!("gltf {} has {} components", name, n))
+            }
+            Ok(Some(values))
+        };
+        let positions = attribute("POSITION", 3)?.ok_or("gltf primitive without positions")?;
+        let normals = attribute("NORMAL", 3)?;
+        let uvs = attribute("TEXCOORD_0", 2)?;
+        let count = positions.len() / 3;
+        if normals.as_ref().is_some_and( | n | n.len() / 3 != count) || uvs.as_ref().is_some_and( | uv | uv.len() / 2 != count) {
+            return Err("gltf NORMAL/TEXCOORD_0 count differs from POSITION".to_string())
+        }
+        let indices: Vec<u32> = match index(primitive, "indices") {
+            Some(accessor) => self.accessor(accessor)?.0.into_iter().map( | i | i as u32).collect(),
+            None => (0..count as u32).collect()
+        };
+        if indices.iter().any( | i | *i as usize >= count) {
+            return Err("gltf index out of bounds".to_string())
+        }
+        // normals are transformed by the inverse transpose, so they stay perpendicular to
+        // surfaces that are scaled unevenly
+        let inverse = world.invert();
+        let transform_normal = | n: Vec3 | vec3(
+            inverse.v[0] * n.x + inverse.v[1] * n.y + inverse.v[2] * n.z,
+            inverse.v[4] * n.x + inverse.v[5] * n.y + inverse.v[6] * n.z,
+            inverse.v[8] * n.x + inverse.v[9] * n.y + inverse.v[10] * n.z,
+        ).normalize();
+        let position = | i: usize | {
+            let p = world.transform_vec4(vec4(positions[i * 3] as f32, positions[i * 3 + 1] as f32, positions[i * 3 + 2] as f32, 1.0));
+            vec3(p.x, p.y, p.z)
+        };
+        let uv = | i: usize | uvs.as_ref().map( | uv | (uv[i * 2] as f32, uv[i * 2 + 1] as f32)).unwrap_or((0.0, 0.0));
+        let mut vertices = Vec::new();
+        let mut push = | p: Vec3, n: Vec3, uv: (f32, f32) | {
+            vertices.extend_from_slice(&[p.x, p.y, p.z, 0.0, n.x, n.y, n.z, uv.0, uv.1]);
+        };
+        let indices = match normals {
+            Some(normals) => {
+                for i in 0..count {
+                    let n = vec3(normals[i * 3] as f32, normals[i * 3 + 1] as f32, normals[i * 3 + 2] as f32);
+                    push(position(i), transform_normal(n), uv(i));
+                }
+                indices
+            }
+            // without normals every triangle is flat, with vertices of its own
+            None => {
+                for triangle in indices.chunks_exact(3) {
+                    let [a, b, c] = [0, 1, 2].map( | i | position(triangle[i] as usize));
+                    let n = Vec3::cross(b - a, c - a).normalize();
+                    for (i, p) in [a, b, c].into_iter().enumerate() {
+                        push(p, n, uv(triangle[i] as usize));
+                    }
+                }
+                (0..indices.len() as u32 / 3 * 3).collect()
+            }
+        };
+        Ok(Some(Mesh3d::new(vertices, indices, material)))
+    }
+}
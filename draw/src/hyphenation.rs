@@ -0,0 +1,197 @@
+//! Hyphenation of words with Liang's algorithm, the one TeX uses. A dictionary is a list of
+//! patterns of letters with priorities between them, such as `hy3ph`: where patterns overlap the
+//! highest priority wins, and words can be hyphenated where it is odd. Dictionaries for most
+//! languages are available in the TeX format, and are loaded as they are.
+
+use {
+    crate::{
+        cx_2d::Cx2d,
+        font_atlas::CxFontsAtlasRc,
+        makepad_platform::*,
+    },
+    std::{collections::HashMap, rc::Rc},
+    unicode_segmentation::UnicodeSegmentation,
+};
+
+#[derive(Debug)]
+pub struct Hyphenator {
+    // the letters of every pattern, and the priorities before, between and after them
+    patterns: HashMap<Vec<char>, Vec<u8>>,
+    max_pattern_len: usize,
+    // words hyphenated by hand, with the char indices of their hyphens
+    exceptions: HashMap<String, Vec<usize>>,
+    /// The fewest letters of a word before a hyphen
+    pub left_min: usize,
+    /// The fewest letters of a word after a hyphen
+    pub right_min: usize,
+}
+
+impl Hyphenator {
+    /// Parses patterns in the format of TeX `\patterns`, separated by whitespace, with `.` for
+    /// the start or end of a word. Words with hyphens in them, such as `ta-ble`, are exceptions
+    /// that are hyphenated as given. `%` starts a comment, TeX commands and braces are skipped.
+    pub fn parse(source: &str) -> Self {
+        let mut hyphenator = Self {
+            patterns: HashMap::new(),
+            max_pattern_len: 0,
+            exceptions: HashMap::new(),
+            left_min: 2,
+            right_min: 3,
+        };
+        for line in source.lines() {
+            let line = line.split('%').next().unwrap_or("");
+            for token in line.split_whitespace() {
+                let token = token.trim_matches(|c| c == '{' || c == '}');
+                if token.is_empty() || token.starts_with('\\') {
+                    continue;
+                }
+                if token.contains('-') {
+                    let mut word = Vec::new();
+                    let mut hyphens = Vec::new();
+                    for c in token.chars() {
+                        if c == '-' {
+                            hyphens.push(word.len());
+                        } else {
+                            word.push(lowercase(c));
+                        }
+                    }
+                    hyphenator.exceptions.insert(word.into_iter().collect(), hyphens);
+                } else {
+                    let mut letters = Vec::new();
+                    let mut priorities = vec![0];
+                    for c in token.chars() {
+                        match c.to_digit(10) {
+                            Some(digit) => *priorities.last_mut().unwrap() = digit as u8,
+                            None => {
+                                letters.push(lowercase(c));
+                                priorities.push(0);
+                            }
+                        }
+                    }
+                    hyphenator.max_pattern_len = hyphenator.max_pattern_len.max(letters.len());
+                    hyphenator.patterns.insert(letters, priorities);
+                }
+            }
+        }
+        hyphenator
+    }
+
+    /// The byte indices in `text` where a hyphen can go. Every run of letters in it is
+    /// hyphenated as a word, and hyphens are only put between graphemes.
+    pub fn hyphenate(&self, text: &str) -> Vec<usize> {
+        let boundaries: Vec<usize> = text.grapheme_indices(true).map(|(index, _)| index).collect();
+        let mut hyphens = Vec::new();
+        let mut word: Vec<(usize, char)> = Vec::new();
+        for (index, c) in text.char_indices().chain(Some((text.len(), ' '))) {
+            if c.is_alphabetic() {
+                word.push((index, c));
+                continue;
+            }
+            hyphens.extend(self.hyphenate_word(&word).into_iter().filter(|index| boundaries.binary_search(index).is_ok()));
+            word.clear();
+        }
+        hyphens
+    }
+
+    fn hyphenate_word(&self, word: &[(usize, char)]) -> Vec<usize> {
+        let len = word.len();
+        if len < self.left_min + self.right_min {
+            return Vec::new();
+        }
+        let letters: Vec<char> = word.iter().map(|(_, c)| lowercase(*c)).collect();
+        let hyphens: Vec<usize> = match self.exceptions.get(&letters.iter().collect::<String>()) {
+            Some(hyphens) => hyphens.clone(),
+            None => {
+                let mut dotted = vec!['.'];
+                dotted.extend(&letters);
+                dotted.push('.');
+                // the priority before every letter of the dotted word
+                let mut priorities = vec![0u8; dotted.len() + 1];
+                for start in 0..dotted.len() {
+                    for end in start + 1..=(start + self.max_pattern_len).min(dotted.len()) {
+                        if let Some(pattern) = self.patterns.get(&dotted[start..end]) {
+                            for (offset, priority) in pattern.iter().enumerate() {
+                                let slot = &mut priorities[start + offset];
+                                *slot = (*slot).max(*priority);
+                            }
+                        }
+                    }
+                }
+                // the letter at `index` is at `index + 1` in the dotted word
+                (1..len).filter(|index| priorities[index + 1] % 2 == 1).collect()
+            }
+        };
+        hyphens.into_iter()
+            .filter(|index| *index >= self.left_min && len - *index >= self.right_min)
+            .map(|index| word[index].0)
+            .collect()
+    }
+}
+
+// the lowercase of a char, as one char so indices into a word stay the same
+fn lowercase(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => c,
+    }
+}
+
+/// The dictionary to hyphenate words with when they don't fit at the end of a line, see
+/// `Hyphenator`. Without a path, words aren't hyphenated.
+#[derive(Debug, Clone, Live, LiveRegister)]
+pub struct Hyphenation {
+    #[rust] pub hyphenator: Option<Rc<Hyphenator>>,
+    #[live] pub path: LiveDependency,
+}
+
+impl LiveHook for Hyphenation {
+    fn after_apply(&mut self, cx: &mut Cx, _apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        if self.path.as_str().is_empty() {
+            self.hyphenator = None;
+            return;
+        }
+        Cx2d::lazy_construct_font_atlas(cx);
+        let atlas = cx.get_global::<CxFontsAtlasRc>().clone();
+        self.hyphenator = atlas.0.borrow_mut().get_hyphenator_by_path(cx, self.path.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Liang's example from his thesis, which hyphenates "hy-phen-ation"
+    const PATTERNS: &str = ".hy3ph he2n hena4 hen5at 1na n2at 1tio 2io";
+
+    #[test]
+    fn patterns_hyphenate_where_the_highest_priority_is_odd() {
+        let hyphenator = Hyphenator::parse(PATTERNS);
+        assert_eq!(hyphenator.hyphenate("hyphenation"), [2, 6]);
+        // every word of a text, in byte indices, whatever its case
+        assert_eq!(hyphenator.hyphenate("Hyphenation, hyphenation!"), [2, 6, 15, 19]);
+        // é takes two bytes
+        assert_eq!(hyphenator.hyphenate("été hyphenation"), [8, 12]);
+    }
+
+    #[test]
+    fn hyphens_leave_the_fewest_letters_around_them() {
+        let mut hyphenator = Hyphenator::parse("a1b c1d e1f");
+        assert_eq!(hyphenator.hyphenate("abcdef"), [3]);
+        hyphenator.left_min = 1;
+        hyphenator.right_min = 1;
+        assert_eq!(hyphenator.hyphenate("abcdef"), [1, 3, 5]);
+        // a word shorter than both together isn't hyphenated at all
+        hyphenator.left_min = 3;
+        hyphenator.right_min = 4;
+        assert!(hyphenator.hyphenate("abcdef").is_empty());
+    }
+
+    #[test]
+    fn exceptions_comments_and_commands_are_read_from_tex() {
+        let hyphenator = Hyphenator::parse("% english\n\\patterns{ .hy3ph he2n } % more\n\\hyphenation{ hy-phen-at-ion ta-ble }");
+        assert_eq!(hyphenator.hyphenate("Hyphenation"), [2, 6, 8]);
+        assert_eq!(hyphenator.hyphenate("table"), [2]);
+        assert_eq!(hyphenator.hyphenate("hyphen"), [2]);
+    }
+}
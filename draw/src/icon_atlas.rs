@@ -5,6 +5,7 @@ pub use {
         cell::RefCell,
         io::prelude::*,
         fs::File,
+        mem,
         collections::HashMap,
     },
     makepad_html::*,
@@ -16,6 +17,7 @@ pub use {
         turtle::{Walk, Layout},
         draw_list_2d::{ManyInstances, DrawList2d, RedrawingApi},
         geometry::GeometryQuad2D,
+        svg::SvgDocument,
         makepad_vector::trapezoidator::Trapezoidator,
         makepad_vector::geometry::{AffineTransformation, Transform, Vector, Point},
         makepad_vector::internal_iter::*,
@@ -27,7 +29,9 @@ pub use {
 pub struct CxIconSlot {
     pub t1: Vec2,
     pub t2: Vec2,
-    pub chan: f32
+    pub chan: f32,
+    /// Whether the slot is on the color page, where svg documents are painted in software
+    pub color_page: bool,
 }
 
 #[derive(Clone)]
@@ -66,11 +70,14 @@ pub struct CxIconEntryHash(LiveId);
 
 pub struct CxIconAtlas {
     pub texture: Texture,
+    pub texture_color: Texture,
     pub clear_buffer: bool,
     svg_deps: HashMap<String, CxIconPathHash>,
     paths: HashMap<CxIconPathHash, Vec<CxIconPathCommands>>,
+    documents: HashMap<CxIconPathHash, SvgDocument>,
     entries: HashMap<CxIconEntryHash, CxIconEntry>,
-    alloc: CxIconAtlasAlloc
+    alloc: CxIconAtlasAlloc,
+    pub color_alloc: CxIconAtlasAlloc,
 }
 
 #[derive(Default)]
@@ -105,20 +112,31 @@ impl CxIconArgs {
     }
 }
 
+pub const ICON_COLOR_ATLAS_SIZE: usize = 2048;
+
 impl CxIconAtlas {
-    pub fn new(texture: Texture) -> Self {
+    pub fn new(texture: Texture, texture_color: Texture) -> Self {
         Self {
             texture,
+            texture_color,
             clear_buffer: false,
             entries: HashMap::new(),
             svg_deps: HashMap::new(),
             paths: HashMap::new(),
+            documents: HashMap::new(),
             alloc: CxIconAtlasAlloc {
                 texture_size: DVec2 {x: 2048.0, y: 2048.0},
                 xpos: 0.0,
                 ypos: 0.0,
                 hmax: 0.0,
                 todo: Vec::new(),
+            },
+            color_alloc: CxIconAtlasAlloc {
+                texture_size: DVec2 {x: ICON_COLOR_ATLAS_SIZE as f64, y: ICON_COLOR_ATLAS_SIZE as f64},
+                xpos: 0.0,
+                ypos: 0.0,
+                hmax: 0.0,
+                todo: Vec::new(),
             }
         }
     }
@@ -181,75 +199,40 @@ impl CxIconAtlas {
     }
    
 
-    pub fn get_icon_bounds(&mut self, cx: &Cx, path_str: &Arc<String>, svg_dep: &Arc<String>) -> Option<(CxIconPathHash, Rect)> {
+    /// The bounds an icon is fitted with. Svg files are fitted to what they paint, or to their
+    /// `viewBox` with `fit_view_box`, inline paths to their outline.
+    pub fn get_icon_bounds(&mut self, cx: &Cx, path_str: &Arc<String>, svg_dep: &Arc<String>, fit_view_box: bool) -> Option<(CxIconPathHash, Rect)> {
         if svg_dep.len() != 0 {
-            // alright so. lets see if we have a path hash
-            if let Some(path_hash) = self.svg_deps.get(svg_dep.as_str()) {
-                if let Some(path) = self.paths.get(&path_hash) {
-                    let mut bounds:Rect = path[0].bounds;
-                    for i in 1..path.len(){
-                        bounds = bounds.hull(path[i].bounds);
-                    }
-                    return Some((*path_hash, bounds))
-                }
-                return None
+            let path_hash = if let Some(path_hash) = self.svg_deps.get(svg_dep.as_str()) {
+                *path_hash
             }
-            let path_hash = CxIconPathHash(LiveId(self.svg_deps.len() as u64));
-            self.svg_deps.insert(svg_dep.as_str().to_string(), path_hash);
-            // lets parse the path range out of the svg file
-            match cx.get_dependency(svg_dep.as_str()) {
-                Ok(data)=>{        
-
-                    let mut errors = Some(Vec::new());
-                    let svg_string = std::str::from_utf8(&data).unwrap();
-                    let  doc = parse_html(svg_string, &mut errors, InternLiveId::No);
-
-                    if errors.as_ref().unwrap().len()>0{
-                        log!("SVG parser returned errors {:?}", errors)
-                    }
-                    let mut node = doc.new_walker();
-                    
-                    while !node.done(){
-                        match node.open_tag_lc() 
-                        {
-                            some_id!(g)=>{
-                                // do something with clipping/transform groups here.
-                            }                            
-                            some_id!(path)=>{
-                                self.parse_and_cache_path(path_hash, node.find_attr_lc(live_id!(d)).unwrap().as_bytes());   
-                                                        }         
-                                                            
-                            _=>()
-                        }
-                        match node.close_tag_lc() 
-                        {
-                            some_id!(g)=>
-                            {
-                                
+            else {
+                let path_hash = CxIconPathHash(LiveId(self.svg_deps.len() as u64));
+                self.svg_deps.insert(svg_dep.as_str().to_string(), path_hash);
+                match cx.get_dependency(svg_dep.as_str()) {
+                    Ok(data) => {
+                        let document = std::str::from_utf8(&data)
+                            .map_err( | err | err.to_string())
+                            .and_then(SvgDocument::parse);
+                        match document {
+                            Ok(document) => {
+                                self.documents.insert(path_hash, document);
                             }
-                            _=>()
-                        }
-                        node.walk();
-                    }
-                    
-                   
-                    if let Some(path) = self.paths.get(&path_hash) {
-                        let mut bounds:Rect = path[0].bounds;             
-                        for i in 1..path.len(){
-                            bounds = bounds.hull(path[i].bounds);
+                            Err(err) => error!("Error in SVG file {}: {}", svg_dep, err)
                         }
-                        return Some((path_hash, bounds));              
                     }
-
-                    error!("No SVG path tag found in svg file {}",path_str);
-                    return None
-                    
-                }
-                Err(_err)=>{
-                    error!("Error in SVG file {}: {}",path_str, _err);
-                    return None
+                    Err(err) => error!("Error in SVG file {}: {}", svg_dep, err)
                 }
+                path_hash
+            };
+            let document = self.documents.get(&path_hash)?;
+            let bounds = if fit_view_box {
+                document.view_box.or_else( || document.bounds())
             }
+            else {
+                document.bounds()
+            };
+            return bounds.map( | bounds | (path_hash, bounds))
         }
         if path_str.len() == 0 {
             return None
@@ -272,7 +255,12 @@ impl CxIconAtlas {
             return entry.slot
         }
         
-        let (slot,pos) = self.alloc.alloc_icon_slot(args.size.x as f64, args.size.y as f64);
+        // svg documents are painted on the color page, inline paths by the gpu on the mask page
+        let color_page = self.documents.contains_key(&path_hash);
+        let alloc = if color_page {&mut self.color_alloc} else {&mut self.alloc};
+        let (mut slot,pos) = alloc.alloc_icon_slot(args.size.x, args.size.y);
+        slot.color_page = color_page;
+        alloc.todo.push(entry_hash);
         self.entries.insert(
             entry_hash,
             CxIconEntry {
@@ -282,13 +270,19 @@ impl CxIconAtlas {
                 args
             }
         );
-        self.alloc.todo.push(entry_hash);
         
         return slot
     }
     
 }
 impl CxIconAtlasAlloc {
+    pub fn reset(&mut self) {
+        self.xpos = 0.;
+        self.ypos = 0.;
+        self.hmax = 0.;
+        self.todo.clear();
+    }
+    
     pub fn alloc_icon_slot(&mut self, w: f64, h: f64) -> (CxIconSlot,DVec2) {
         if w + self.xpos >= self.texture_size.x {
             self.xpos = 0.0;
@@ -312,6 +306,7 @@ impl CxIconAtlasAlloc {
         
         (CxIconSlot {
             chan: 0.0,
+            color_page: false,
             t1: dvec2(tx1, ty1).into(),
             t2: dvec2(tx1 + (w / self.texture_size.x), ty1 + (h / self.texture_size.y)).into()
         },dvec2(px, py).into())
@@ -324,9 +319,8 @@ pub struct CxIconAtlasRc(pub Rc<RefCell<CxIconAtlas >>);
 impl CxIconAtlas {
     pub fn reset_icon_atlas(&mut self) {
         self.entries.clear();
-        self.alloc.xpos = 0.;
-        self.alloc.ypos = 0.;
-        self.alloc.hmax = 0.;
+        self.alloc.reset();
+        self.color_alloc.reset();
         self.clear_buffer = true;
     }
     
@@ -412,7 +406,14 @@ impl<'a> Cx2d<'a> {
             let texture = draw_atlas.atlas_texture.clone();
             cx.set_global(CxDrawIconAtlasRc(Rc::new(RefCell::new(draw_atlas))));
             
-            let atlas = CxIconAtlas::new(texture);
+            let texture_color = Texture::new_with_format(cx, TextureFormat::VecBGRAu8_32 {
+                width: ICON_COLOR_ATLAS_SIZE,
+                height: ICON_COLOR_ATLAS_SIZE,
+                data: Some(vec![]),
+                updated: TextureUpdated::Empty,
+            });
+            
+            let atlas = CxIconAtlas::new(texture, texture_color);
             cx.set_global(CxIconAtlasRc(Rc::new(RefCell::new(atlas))));
         }
     }
@@ -431,6 +432,9 @@ impl<'a> Cx2d<'a> {
         let mut atlas = atlas_rc.0.borrow_mut();
         let atlas = &mut*atlas;
         //let start = Cx::profile_time_ns();
+        for todo in mem::take(&mut atlas.color_alloc.todo) {
+            self.swrast_icon_todo_color(atlas, todo);
+        }
         // we need to start a pass that just uses the texture
         if atlas.alloc.todo.len()>0 {
             self.begin_pass(&draw_atlas.atlas_pass, None);
@@ -470,7 +474,36 @@ impl<'a> Cx2d<'a> {
         }
     }
     
-    
+    // Paints the svg document of an entry into its slot on the color page, in the same place
+    // the gpu would put an outline on the mask page.
+    fn swrast_icon_todo_color(&mut self, atlas: &mut CxIconAtlas, todo: CxIconEntryHash) {
+        let entry = atlas.entries.get(&todo).unwrap();
+        let document = atlas.documents.get(&entry.path_hash).unwrap();
+        let transform = AffineTransformation::identity()
+            .translate(Vector::new(entry.args.translate.x, entry.args.translate.y))
+            .uniform_scale(entry.args.scale)
+            .translate(Vector::new(entry.args.subpixel.x, entry.args.subpixel.y));
+        let (width, height) = (entry.args.size.x as usize, entry.args.size.y as usize);
+        
+        let (atlas_w, atlas_h) = atlas.texture_color.get_format(self.cx).vec_width_height().unwrap();
+        let (atlas_x0, atlas_y0) = (entry.pos.x as usize, entry.pos.y as usize);
+        // a full page already logged an error when the slot was allocated
+        if atlas_x0 + width > atlas_w || atlas_y0 + height > atlas_h {
+            return
+        }
+        let mut atlas_data = atlas.texture_color.take_vec_u32(self.cx);
+        if atlas_data.is_empty() {
+            atlas_data = vec![0; atlas_w * atlas_h];
+        }
+        let pixels = document.rasterize(transform, width, height);
+        for y in 0..height {
+            atlas_data[(atlas_y0 + y) * atlas_w + atlas_x0..][..width].copy_from_slice(&pixels[y * width..][..width]);
+        }
+        atlas.texture_color.put_back_vec_u32(self.cx, atlas_data, Some(RectUsize::new(
+            PointUsize::new(atlas_x0, atlas_y0),
+            SizeUsize::new(width, height),
+        )));
+    }
 }
 
 pub fn parse_svg_path(path: &[u8]) -> Result<Vec<PathCommand>, String> {
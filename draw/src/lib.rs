@@ -5,27 +5,45 @@ pub use makepad_vector;
 pub mod match_event;
 pub mod overlay;
 pub mod cx_2d;
+pub mod cx_3d;
 pub mod draw_list_2d;
 pub mod shader;
 pub mod turtle;
 pub mod font_atlas;
+pub mod color_glyph;
+pub mod svg;
+pub mod stroke;
 pub mod geometry;
 pub mod nav;
+pub mod access;
 pub mod icon_atlas;
+pub mod system_fonts;
+pub mod hyphenation;
+pub mod gltf;
 mod owned_font_face;
  
 pub use crate::{
     match_event::MatchEvent, 
-    font_atlas::Font,
+    font_atlas::{Font, FontFace, FontStyle},
+    system_fonts::SystemFonts,
+    hyphenation::{Hyphenation, Hyphenator},
+    svg::SvgDocument,
+    stroke::{LineCap, LineJoin, StrokeStyle},
+    makepad_vector::trapezoidator::FillRule,
     turtle::{
         Layout,
         Walk,
         Align,
         Padding,
         Flow,
+        LayoutDirection,
         Size,
         TurtleAlignRange,
-        DeferWalk
+        DeferWalk,
+        GridLayout,
+        GridCell,
+        GridTrack,
+        GridTracks
     },
     overlay::{
         Overlay
@@ -35,8 +53,13 @@ pub use crate::{
         NavOrder,
         NavStop,
         NavItem,
+        NavAxis,
+        NavStopInfo,
         NavScrollIndex
     },
+    access::{
+        AccessItem,
+    },
     draw_list_2d::{
         DrawList2d,
         ManyInstances,
@@ -46,17 +69,26 @@ pub use crate::{
     cx_2d::{
         Cx2d
     },
+    cx_3d::{
+        Material3d,
+        Mesh3d,
+        Scene3d,
+        OrbitCamera,
+    },
     shader::{
         //draw_shape::{DrawShape, Shape, Fill},
         draw_icon::DrawIcon,
         draw_quad::DrawQuad,
         draw_line::DrawLine,
-        draw_text::{Affinity, DrawText, IndexAffinity},
+        draw_text::{is_rtl_paragraph, Affinity, DrawText, IndexAffinity, TextStyle},
         draw_color::DrawColor,
+        draw_vector::{DrawVector, VectorPaint, VectorPath},
+        draw_mesh::DrawMesh,
     },
     geometry::{
         GeometryGen,
         GeometryQuad2D,
+        GeometryCube3D,
     },
 };
 
@@ -69,4 +101,6 @@ pub fn live_design(cx: &mut Cx) {
     crate::geometry::geometry_gen::live_design(cx);
     crate::shader::std::live_design(cx);
     crate::shader::draw_trapezoid::live_design(cx);
+    crate::shader::draw_vector::live_design(cx);
+    crate::shader::draw_mesh::live_design(cx);
 }
\ No newline at end of file
@@ -4,10 +4,7 @@ use {
     std::cell::RefCell,
     crate::{
         cx_2d::Cx2d,
-        makepad_platform::Area,
-        makepad_platform::DrawListId,
-        makepad_platform::Margin,
-        makepad_platform::Cx,
+        makepad_platform::*,
     }
 };
 
@@ -37,12 +34,27 @@ impl std::ops::IndexMut<DrawListId> for CxNavTree {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Where a stop goes in the tab order. `Top` and `Bottom` stops come before and after all
+/// others, `Middle` stops come after the `Default` ones, each ordered by their number.
+/// Stops with the same order keep the order they were drawn in.
+#[derive(Copy, Clone, Debug, PartialEq, Live, LiveHook)]
+#[live_ignore]
 pub enum NavOrder {
-    Default,
-    Top(u64),
-    Middle(u64),
-    Bottom(u64),
+    #[pick] Default,
+    #[live(0)] Top(u64),
+    #[live(0)] Middle(u64),
+    #[live(0)] Bottom(u64),
+}
+
+impl NavOrder {
+    fn sort_key(&self) -> (u8, u64) {
+        match self {
+            Self::Top(n) => (0, *n),
+            Self::Default => (1, 0),
+            Self::Middle(n) => (2, *n),
+            Self::Bottom(n) => (3, *n),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -50,7 +62,36 @@ pub struct NavStop {
     pub role: NavRole,
     pub order: NavOrder,
     pub margin: Margin,
-    pub area: Area
+    pub area: Area,
+    /// the stop of a group that tab enters on, like the checked radio button or the open tab
+    pub selected: bool,
+}
+
+impl NavStop {
+    pub fn new(area: Area, role: NavRole) -> Self {
+        Self {
+            role,
+            order: NavOrder::Default,
+            margin: Margin::default(),
+            area,
+            selected: false,
+        }
+    }
+
+    pub fn with_order(mut self, order: NavOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_margin(mut self, margin: Margin) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -58,14 +99,48 @@ pub enum NavItem {
     Child(DrawListId),
     Stop(NavStop),
     BeginScroll(Area),
-    EndScroll(Area)
+    EndScroll(Area),
+    BeginGroup(NavAxis),
+    EndGroup,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum NavRole {
     TextInput,
     DropDown,
     Slider,
+    Button,
+    CheckBox,
+    RadioButton,
+    Tab,
+    ListItem,
+    TreeItem,
+    Grid,
+}
+
+impl NavRole {
+    /// Roles that use the arrow keys themselves, so they don't move the focus within a group
+    pub fn handles_arrow_keys(&self) -> bool {
+        matches!(self, Self::TextInput | Self::DropDown | Self::Slider | Self::Grid)
+    }
+}
+
+/// Which arrow keys move the focus between the stops of a group
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NavAxis {
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+/// A stop with everything needed to navigate to it, see `Cx2d::collect_nav_stops`
+#[derive(Debug, Clone)]
+pub struct NavStopInfo {
+    pub stop: NavStop,
+    /// the group the stop belongs to, an index into the groups returned alongside the stops
+    pub group: Option<usize>,
+    /// the scroll areas the stop is in, outermost first
+    pub scroll_stack: Vec<Area>,
 }
 
 impl<'a> Cx2d<'a> {
@@ -102,6 +177,7 @@ impl<'a> Cx2d<'a> {
                     NavItem::EndScroll(area)=>{
                         if *area != scroll_stack.pop().unwrap(){panic!()};
                     }
+                    NavItem::BeginGroup(_) | NavItem::EndGroup=>()
                 }
             }
             None
@@ -114,6 +190,101 @@ impl<'a> Cx2d<'a> {
         }
     }
     
+    /// Flattens the nav tree below `root` into its stops in draw order, together with the axis of
+    /// every group. Radio buttons drawn one after the other outside a group form a group of their own.
+    pub fn collect_nav_stops(cx: &mut Cx, root: DrawListId) -> (Vec<NavStopInfo>, Vec<NavAxis>) {
+        let nav_tree_rc = cx.get_global::<CxNavTreeRc>().clone();
+        let nav_tree = &*nav_tree_rc.0.borrow();
+        
+        struct State {
+            stops: Vec<NavStopInfo>,
+            groups: Vec<NavAxis>,
+            group_stack: Vec<usize>,
+            scroll_stack: Vec<Area>,
+            radio_group: Option<usize>,
+        }
+        
+        fn collect(nav_tree: &CxNavTree, draw_list_id: DrawListId, state: &mut State) {
+            if draw_list_id.index() >= nav_tree.nav_lists.len() {
+                return
+            }
+            for nav_item in &nav_tree[draw_list_id].nav_list {
+                match nav_item {
+                    NavItem::Child(draw_list_id) => {
+                        state.radio_group = None;
+                        collect(nav_tree, *draw_list_id, state);
+                        state.radio_group = None;
+                    }
+                    NavItem::Stop(stop) => {
+                        let group = if let Some(group) = state.group_stack.last() {
+                            Some(*group)
+                        }
+                        else if stop.role == NavRole::RadioButton {
+                            let groups = &mut state.groups;
+                            Some(*state.radio_group.get_or_insert_with( | | {
+                                groups.push(NavAxis::Both);
+                                groups.len() - 1
+                            }))
+                        }
+                        else {
+                            None
+                        };
+                        if stop.role != NavRole::RadioButton {
+                            state.radio_group = None;
+                        }
+                        state.stops.push(NavStopInfo {
+                            stop: stop.clone(),
+                            group,
+                            scroll_stack: state.scroll_stack.clone(),
+                        });
+                    }
+                    NavItem::BeginScroll(area) => {
+                        state.scroll_stack.push(*area);
+                    }
+                    NavItem::EndScroll(_) => {
+                        state.scroll_stack.pop();
+                    }
+                    NavItem::BeginGroup(axis) => {
+                        state.groups.push(*axis);
+                        state.group_stack.push(state.groups.len() - 1);
+                        state.radio_group = None;
+                    }
+                    NavItem::EndGroup => {
+                        state.group_stack.pop();
+                        state.radio_group = None;
+                    }
+                }
+            }
+        }
+        
+        let mut state = State {
+            stops: Vec::new(),
+            groups: Vec::new(),
+            group_stack: Vec::new(),
+            scroll_stack: Vec::new(),
+            radio_group: None,
+        };
+        collect(nav_tree, root, &mut state);
+        (state.stops, state.groups)
+    }
+    
+    /// The stops tab moves between, in tab order. Only one stop of every group is in there: the
+    /// focused one, else the selected one, else the first one.
+    pub fn tab_order(cx: &Cx, stops: &[NavStopInfo]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..stops.len()).filter( | index | {
+            let Some(group) = stops[*index].group else {
+                return true
+            };
+            let mut members = stops.iter().enumerate().filter( | (_, s) | s.group == Some(group));
+            let entry = members.clone().find( | (_, s) | cx.has_key_focus(s.stop.area))
+                .or_else( | | members.clone().find( | (_, s) | s.stop.selected))
+                .or_else( | | members.next());
+            entry.map( | (i, _) | i) == Some(*index)
+        }).collect();
+        order.sort_by_key( | index | stops[*index].stop.order.sort_key());
+        order
+    }
+    
     pub fn nav_list_clear(&mut self, draw_list_id: DrawListId) {
         let mut nav_tree = self.nav_tree_rc.0.borrow_mut();
         if draw_list_id.index() >= nav_tree.nav_lists.len() {
@@ -128,13 +299,23 @@ impl<'a> Cx2d<'a> {
     }
     
     pub fn add_nav_stop(&mut self, area: Area, role: NavRole, margin: Margin) {
+        self.add_nav_stop_with(NavStop::new(area, role).with_margin(margin));
+    }
+    
+    pub fn add_nav_stop_with(&mut self, stop: NavStop) {
         let draw_list_id = *self.draw_list_stack.last().unwrap();
-        self.nav_list_item_push(draw_list_id, NavItem::Stop(NavStop {
-            role,
-            area,
-            order: NavOrder::Default,
-            margin
-        }));
+        self.nav_list_item_push(draw_list_id, NavItem::Stop(stop));
+    }
+    
+    /// Arrow keys move the focus between the stops added until the matching `end_nav_group`
+    pub fn begin_nav_group(&mut self, axis: NavAxis) {
+        let draw_list_id = *self.draw_list_stack.last().unwrap();
+        self.nav_list_item_push(draw_list_id, NavItem::BeginGroup(axis));
+    }
+    
+    pub fn end_nav_group(&mut self) {
+        let draw_list_id = *self.draw_list_stack.last().unwrap();
+        self.nav_list_item_push(draw_list_id, NavItem::EndGroup);
     }
     
     pub fn add_begin_scroll(&mut self)->NavScrollIndex{
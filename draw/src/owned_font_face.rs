@@ -2,7 +2,7 @@
 //! to what the `owned_ttf_parser` crate offers for `ttf_parser::Face`, and also
 //! using `Rc<Vec<u8>>` instead of `Vec<u8>` (to avoid cloning any font bytes).
 
-use makepad_rustybuzz::Face;
+use makepad_rustybuzz::{Face, Variation};
 use std::marker::PhantomPinned;
 use std::pin::Pin;
 use std::rc::Rc;
@@ -15,6 +15,16 @@ impl OwnedFace {
     pub fn parse(
         font_data: Rc<Vec<u8>>,
         index_in_collection: u32,
+    ) -> Result<Self, FaceParsingError> {
+        Self::parse_with_variations(font_data, index_in_collection, &[])
+    }
+
+    /// Like `parse`, with the axes of a variable font set to the given values, which applies to
+    /// both shaping and outlines. Axes the font doesn't have are ignored.
+    pub fn parse_with_variations(
+        font_data: Rc<Vec<u8>>,
+        index_in_collection: u32,
+        variations: &[Variation],
     ) -> Result<Self, FaceParsingError> {
         let mut pinned_box = Box::pin(FaceWithFontData {
             face: None,
@@ -26,12 +36,20 @@ impl OwnedFace {
             .with_face_slot_mut_and_font_data(|face_slot, font_data| {
                 let ttf_parser_face =
                     makepad_rustybuzz::ttf_parser::Face::parse(font_data, index_in_collection)?;
-                *face_slot = Some(Face::from_face(ttf_parser_face));
+                let mut face = Face::from_face(ttf_parser_face);
+                if !variations.is_empty() {
+                    face.set_variations(variations);
+                }
+                *face_slot = Some(face);
                 Ok(())
             })?;
         Ok(Self(pinned_box))
     }
 
+    pub fn font_data(&self) -> Rc<Vec<u8>> {
+        self.0.font_data.clone()
+    }
+
     pub fn with_ref<R>(&self, f: impl for<'a> FnOnce(&Face<'a>) -> R) -> R {
         self.0.as_ref().with_face_ref(f)
     }
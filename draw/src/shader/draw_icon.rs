@@ -19,6 +19,7 @@ live_design!{
         uniform u_curve: float
         
         texture tex: texture2d
+        texture tex_color: texture2d
         varying pos: vec2,
         varying tex_coord1: vec2
         varying clipped: vec2
@@ -60,9 +61,16 @@ live_design!{
             
             // basic hardcoded mipmapping so it stops 'swimming' in VR
             // mipmaps are stored in red/green/blue channel
+            let col = self.get_color(); //color!(white);//get_color();
+            if self.color_mode > 1.5 {
+                // svg documents in their own colors are premultiplied already
+                return sample2d(self.tex_color, self.tex_coord1.xy) * col.a;
+            }
             let s = sample2d_rt(self.tex, self.tex_coord1.xy).x;
+            if self.color_mode > 0.5 {
+                s = sample2d(self.tex_color, self.tex_coord1.xy).w;
+            }
             s = pow(s, self.u_curve);
-            let col = self.get_color(); //color!(white);//get_color();
             return vec4(s * col.rgb * self.u_brightness * col.a, s * col.a);
         }
     }
@@ -79,6 +87,10 @@ pub struct DrawIcon {
     #[live] pub svg_path: ArcStringMut,
     #[live] pub translate: DVec2,
     #[live(1.0)] pub scale: f64,
+    /// Paints svg files in their own colors instead of tinting them with `color`
+    #[live] pub preserve_colors: bool,
+    /// Fits svg files by their `viewBox` instead of by what they paint
+    #[live] pub fit_view_box: bool,
     
     #[rust] pub many_instances: Option<ManyInstances>,
     #[live] pub geometry: GeometryQuad2D,
@@ -91,6 +103,7 @@ pub struct DrawIcon {
     #[live] pub color: Vec4,
     #[calc] pub icon_t1: Vec2,
     #[calc] pub icon_t2: Vec2,
+    #[calc] pub color_mode: f32,
 }
 
 impl LiveHook for DrawIcon{
@@ -133,7 +146,7 @@ impl DrawIcon {
         let icon_atlas = &mut*icon_atlas;
        
             
-        if let Some((path_hash, bounds)) = icon_atlas.get_icon_bounds(cx, &self.svg_path.as_arc(), self.svg_file.as_ref()) {
+        if let Some((path_hash, bounds)) = icon_atlas.get_icon_bounds(cx, &self.svg_path.as_arc(), self.svg_file.as_ref(), self.fit_view_box) {
             let width_is_fit = walk.width.is_fit();
             let height_is_fit = walk.height.is_fit();
             let peek_rect = cx.peek_walk_turtle(walk);
@@ -185,6 +198,11 @@ impl DrawIcon {
             
             self.icon_t1 = slot.t1;
             self.icon_t2 = slot.t2;
+            self.color_mode = match (slot.color_page, self.preserve_colors) {
+                (false, _) => 0.0,
+                (true, false) => 1.0,
+                (true, true) => 2.0,
+            };
             
             if let Some(mi) = &mut self.many_instances {
                 mi.instances.extend_from_slice(self.draw_vars.as_slice());
@@ -199,6 +217,7 @@ impl DrawIcon {
     
     pub fn update_draw_call_vars(&mut self, atlas: &CxIconAtlas) {
         self.draw_vars.texture_slots[0] = Some(atlas.texture.clone());
+        self.draw_vars.texture_slots[1] = Some(atlas.texture_color.clone());
         self.draw_vars.user_uniforms[0] = self.brightness;
         self.draw_vars.user_uniforms[1] = self.curve;
     }
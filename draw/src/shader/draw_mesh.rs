@@ -0,0 +1,118 @@
+use {
+    crate::{
+        makepad_platform::*,
+        cx_2d::Cx2d,
+        cx_3d::Mesh3d,
+        geometry::GeometryCube3D,
+    },
+};
+
+live_design!{
+    import makepad_draw::shader::std::*;
+
+    DrawMesh = {{DrawMesh}} {
+        // in world space, towards the light
+        uniform light_dir: vec3(0.4, 1.0, 0.6)
+        uniform light_color: vec3(3.0, 3.0, 3.0)
+        uniform ambient_color: vec3(0.3, 0.3, 0.35)
+        uniform camera_pos: vec3(0.0, 0.0, 1.0)
+
+        varying world_pos: vec3
+        varying world_normal: vec3
+
+        fn distribution_ggx(n_dot_h: float, roughness: float) -> float {
+            let a = roughness * roughness;
+            let a2 = a * a;
+            let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+            return a2 / (PI * d * d);
+        }
+
+        fn geometry_smith(n_dot_v: float, n_dot_l: float, roughness: float) -> float {
+            let k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+            return n_dot_v / (n_dot_v * (1.0 - k) + k) * n_dot_l / (n_dot_l * (1.0 - k) + k);
+        }
+
+        fn fresnel_schlick(cos_theta: float, f0: vec3) -> vec3 {
+            return f0 + (vec3(1.0, 1.0, 1.0) - f0) * pow(1.0 - cos_theta, 5.0);
+        }
+
+        fn vertex(self) -> vec4 {
+            // meshes are baked into world space when they are loaded
+            self.world_pos = self.geom_pos;
+            self.world_normal = self.geom_normal;
+            return self.camera_projection * (self.camera_view * vec4(self.geom_pos, 1.0));
+        }
+
+        fn pixel(self) -> vec4 {
+            let n = normalize(self.world_normal);
+            let v = normalize(self.camera_pos - self.world_pos);
+            // the back of a face is lit like its front
+            if dot(n, v) < 0.0 {
+                n = -n;
+            }
+            let l = normalize(self.light_dir);
+            let h = normalize(v + l);
+            let n_dot_v = max(dot(n, v), 0.0001);
+            let n_dot_l = max(dot(n, l), 0.0);
+            let roughness = clamp(self.roughness, 0.04, 1.0);
+
+            let albedo = pow(self.base_color.rgb, vec3(2.2, 2.2, 2.2));
+            let f0 = mix(vec3(0.04, 0.04, 0.04), albedo, self.metallic);
+            let f = fresnel_schlick(max(dot(h, v), 0.0), f0);
+            let specular = distribution_ggx(max(dot(n, h), 0.0), roughness)
+                * geometry_smith(n_dot_v, n_dot_l, roughness) * f
+                / (4.0 * n_dot_v * max(n_dot_l, 0.0001));
+            let diffuse = (vec3(1.0, 1.0, 1.0) - f) * (1.0 - self.metallic) * albedo / PI;
+            // the ambient light comes from the sky more than from the ground
+            let ambient = self.ambient_color * albedo * mix(0.5, 1.0, n.y * 0.5 + 0.5);
+
+            let color = (diffuse + specular) * self.light_color * n_dot_l + ambient + self.emissive;
+            color = pow(clamp(color, vec3(0.0, 0.0, 0.0), vec3(1.0, 1.0, 1.0)), vec3(1.0 / 2.2, 1.0 / 2.2, 1.0 / 2.2));
+            return vec4(color * self.base_color.a, self.base_color.a);
+        }
+    }
+}
+
+/// Draws the meshes of a `Scene3d` with the metallic-roughness shading of glTF, lit by one
+/// directional light and an ambient light. Every mesh is drawn with its own geometry, in a
+/// pass with a projection matrix and a depth texture.
+#[derive(Live, LiveRegister)]
+#[repr(C)]
+pub struct DrawMesh {
+    #[live] pub geometry: GeometryCube3D,
+    #[deref] pub draw_vars: DrawVars,
+    #[calc] pub base_color: Vec4,
+    #[calc] pub emissive: Vec3,
+    #[calc] pub metallic: f32,
+    #[calc] pub roughness: f32,
+}
+
+impl LiveHook for DrawMesh {
+    fn before_apply(&mut self, cx: &mut Cx, apply: &mut Apply, index: usize, nodes: &[LiveNode]) {
+        self.draw_vars.before_apply_init_shader(cx, apply, index, nodes, &self.geometry);
+    }
+    fn after_apply(&mut self, cx: &mut Cx, apply: &mut Apply, index: usize, nodes: &[LiveNode]) {
+        self.draw_vars.after_apply_update_self(cx, apply, index, nodes, &self.geometry);
+    }
+}
+
+impl DrawMesh {
+    /// Sets where the camera is, for the specular highlights
+    pub fn set_camera_pos(&mut self, cx: &Cx, eye: Vec3) {
+        self.draw_vars.set_uniform(cx, &[live_id!(camera_pos)], &[eye.x, eye.y, eye.z]);
+    }
+
+    pub fn draw_mesh(&mut self, cx: &mut Cx2d, mesh: &mut Mesh3d) {
+        if !self.draw_vars.can_instance() {
+            return
+        }
+        let material = &mesh.material;
+        self.base_color = material.base_color;
+        self.emissive = material.emissive;
+        self.metallic = material.metallic;
+        self.roughness = material.roughness;
+        self.draw_vars.set_geometry(mesh.geometry(cx));
+        let new_area = cx.add_instance(&self.draw_vars);
+        self.draw_vars.area = cx.update_area_refs(self.draw_vars.area, new_area);
+    }
+}
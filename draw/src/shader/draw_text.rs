@@ -1,14 +1,17 @@
 use {
     crate::{
-        cx_2d::Cx2d, draw_list_2d::ManyInstances, font_atlas::{self, CxFontAtlas, CxFontsAtlasTodo, CxShapeCache, Font}, geometry::GeometryQuad2D, makepad_platform::*, turtle::{Align, Flow, Size, Walk}
+        cx_2d::Cx2d, draw_list_2d::ManyInstances, font_atlas::{self, CxFontAtlas, CxFontsAtlasTodo, CxShapeCache, Font, FontFace, FontStyle}, geometry::GeometryQuad2D, hyphenation::{Hyphenation, Hyphenator}, makepad_platform::*, turtle::{Align, Flow, Size, Walk}
     },
     makepad_rustybuzz::Direction,
+    unicode_bidi::{Level, ParagraphBidiInfo},
     unicode_segmentation::UnicodeSegmentation,
-    std::borrow::Cow,
+    std::{borrow::Cow, ops::Range},
 };
 
 const ZBIAS_STEP: f32 = 0.00001;
 
+const SOFT_HYPHEN: char = '\u{AD}';
+
 live_design!{
     
     DrawText = {{DrawText}} {
@@ -21,6 +24,7 @@ live_design!{
         //uniform sdf_cutoff: float
         
         texture tex: texture2d
+        texture tex_color: texture2d
         
         varying tex_coord1: vec2
         varying tex_coord2: vec2
@@ -66,6 +70,15 @@ live_design!{
         }
         
         fn sample_color(self, scale:float, pos:vec2)->vec4{
+            if self.is_decoration > 0.5 {
+                // underlines and strikethroughs are solid quads
+                let col = self.get_color();
+                return self.blend_color(vec4(col.rgb * col.a, col.a));
+            }
+            if self.is_color > 0.5 {
+                // color glyphs are premultiplied already, only the alpha of the text color applies
+                return self.blend_color(sample2d(self.tex_color, pos) * self.get_color().a);
+            }
             let brightness = self.get_brightness();
             let sdf_radius = 8.0;
             let sdf_cutoff = 0.25;
@@ -150,6 +163,8 @@ const _: () = assert!(crate::font_atlas::ATLAS_HEIGHT == 4096);
 pub struct TextStyle {
     #[live()] pub font: Font,
     #[live()] pub font2: Font,
+    /// Fonts to take glyphs from when `font` and `font2` don't have them, in order
+    #[live] pub fallbacks: Vec<Font>,
     #[live(9.0)] pub font_size: f64,
     //#[live(1.0)] pub brightness: f32,
     //#[live(0.5)] pub curve: f32,
@@ -157,7 +172,82 @@ pub struct TextStyle {
     #[live(1.4)] pub line_spacing: f64,
     //#[live(1.1)] pub top_drop: f64,
     #[live(1.3)] pub height_factor: f64,
-    #[live] pub is_secret: bool
+    #[live] pub is_secret: bool,
+    /// More fonts of the family of `font` in other weights and styles. Of these and `font`, the
+    /// one closest to `font_weight` and `font_style` is drawn with
+    #[live] pub font_family: Vec<FontFace>,
+    /// From 100 (thin) over 400 (regular) and 700 (bold) to 900 (black). Variable fonts are
+    /// instanced at this weight
+    #[live(400.0)] pub font_weight: f64,
+    #[live] pub font_style: FontStyle,
+    /// Extra space after every character, in logical pixels
+    #[live] pub letter_spacing: f64,
+    #[live] pub underline: bool,
+    #[live] pub strikethrough: bool,
+    /// The color of the underline and strikethrough, the color of the text if not set
+    #[live] pub decoration_color: Option<Vec4>,
+    /// The thickness of the underline and strikethrough in logical pixels, taken from the font if 0
+    #[live] pub decoration_thickness: f64,
+    /// The dictionary that words which don't fit at the end of a wrapping line are hyphenated with
+    #[live] pub hyphenation: Hyphenation,
+}
+
+impl TextStyle {
+    /// The font closest to `font_weight` and `font_style` out of `font`, which is taken as the
+    /// regular upright face, and the faces of `font_family`, with its style. Fonts are matched
+    /// the way CSS does: faces in the wrong style are only used if there are none in the right
+    /// one, and of those the nearest weight wins, going lighter first for light text and heavier
+    /// first for bold text.
+    fn face(&self) -> Option<(&Font, FontStyle)> {
+        let faces = || std::iter::once((&self.font, 400.0, FontStyle::Normal))
+            .chain(self.font_family.iter().map(|face| (&face.font, face.weight, face.style)))
+            .filter(|(font, _, _)| font.font_id.is_some());
+        let in_style = faces().any(|(_, _, style)| style == self.font_style);
+        let desired = self.font_weight;
+        let rank = |weight: f64| -> (u8, f64) {
+            let lighter = (desired - weight).max(0.0);
+            let heavier = (weight - desired).max(0.0);
+            if weight == desired {
+                (0, 0.0)
+            } else if (400.0..=500.0).contains(&desired) {
+                if weight > desired && weight <= 500.0 {(1, heavier)} else if weight < desired {(2, lighter)} else {(3, heavier)}
+            } else if desired < 400.0 {
+                if weight < desired {(1, lighter)} else {(2, heavier)}
+            } else if weight > desired {
+                (1, heavier)
+            } else {
+                (2, lighter)
+            }
+        };
+        faces()
+            .filter(|(_, _, style)| !in_style || *style == self.font_style)
+            .min_by(|a, b| rank(a.1).partial_cmp(&rank(b.1)).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(font, _, style)| (font, style))
+    }
+
+    /// The font that text is drawn with, before any fallbacks
+    pub fn font_id(&self) -> Option<usize> {
+        self.face().and_then(|(font, _)| font.font_id)
+    }
+
+    /// The fonts that text is shaped with, in the order glyphs are looked up in them
+    pub fn font_ids(&self) -> Vec<usize> {
+        let font = self.face().map_or(&self.font, |(font, _)| font);
+        [font, &self.font2].into_iter()
+            .chain(&self.fallbacks)
+            .filter_map(|font| font.font_id)
+            .collect()
+    }
+
+    /// Like `font_ids`, with variable fonts instanced at `font_weight`, and slanted for italic
+    /// text if the family has no italic face
+    pub fn resolve_font_ids(&self, font_atlas: &mut CxFontAtlas) -> Vec<usize> {
+        let italic = self.font_style == FontStyle::Italic
+            && self.face().is_none_or(|(_, style)| style != FontStyle::Italic);
+        self.font_ids().into_iter().map(|font_id| {
+            font_atlas.font_variant(font_id, self.font_weight, italic)
+        }).collect()
+    }
 }
 
 #[derive(Clone, Live, LiveHook, PartialEq)]
@@ -193,6 +283,8 @@ pub struct DrawText {
     #[calc] pub rect_size: Vec2,
     #[calc] pub draw_clip: Vec4,
     #[calc] pub char_depth: f32,
+    #[calc] pub is_color: f32,
+    #[calc] pub is_decoration: f32,
 }
 
 impl LiveHook for DrawText {
@@ -252,6 +344,7 @@ impl DrawText {
     
     pub fn update_draw_call_vars(&mut self, font_atlas: &CxFontAtlas) {
         self.draw_vars.texture_slots[0] = Some(font_atlas.texture_sdf.clone());
+        self.draw_vars.texture_slots[1] = Some(font_atlas.texture_color.clone());
         // self.draw_vars.user_uniforms[0] = self.text_style.brightness;
         // self.draw_vars.user_uniforms[1] = self.text_style.curve;
         //let (sdf_radius, sdf_cutoff) = font_atlas.alloc.sdf.as_ref()
@@ -270,24 +363,17 @@ impl DrawText {
     
     pub fn get_monospace_base(&self, cx: &Cx2d) -> DVec2 {
         // If the font did not load, there is nothing to draw.
-        let Some(font_id) = self.text_style.font.font_id else {
+        if self.text_style.font_id().is_none() {
             return DVec2::default();
-        };
-        let mut font_ids = [0, 0];
-        let font_ids = if let Some(font2_id) = self.text_style.font2.font_id {
-            font_ids[0] = font_id;
-            font_ids[1] = font2_id;
-            &font_ids[..2]
-        } else {
-            font_ids[0] = font_id;
-            &font_ids[..1]
-        };
+        }
 
         // Borrow the font atlas from the context.
         let font_atlas_rc = cx.fonts_atlas_rc.clone();
         let mut font_atlas = font_atlas_rc.0.borrow_mut();
         let font_atlas = &mut *font_atlas;
 
+        let font_ids = &*self.text_style.resolve_font_ids(font_atlas);
+        let font_id = font_ids[0];
         if font_atlas.fonts[font_id].is_none() {
             return DVec2::default();
         }
@@ -311,23 +397,15 @@ impl DrawText {
 impl DrawText {
     pub fn line_height(&self, cx: &Cx2d) -> f64 {
         // If the font did not load, there is nothing to draw.
-        let Some(font_id) = self.text_style.font.font_id else {
+        if self.text_style.font_id().is_none() {
             return 0.0;
-        };
-        let mut font_ids = [0, 0];
-        let font_ids = if let Some(font2_id) = self.text_style.font2.font_id {
-            font_ids[0] = font_id;
-            font_ids[1] = font2_id;
-            &font_ids[..2]
-        } else {
-            font_ids[0] = font_id;
-            &font_ids[..1]
-        };
+        }
         
         // Borrow the font atlas from the context.
         let font_atlas_rc = cx.fonts_atlas_rc.clone();
         let mut font_atlas_ref = font_atlas_rc.0.borrow_mut();
         let font_atlas = &mut *font_atlas_ref;
+        let font_ids = &*self.text_style.resolve_font_ids(font_atlas);
 
         let font_size = self.text_style.font_size * self.font_scale;
         let line_height = compute_line_height(font_ids, font_size, font_atlas) * self.text_style.line_scale;
@@ -349,117 +427,44 @@ impl DrawText {
         start: IndexAffinity,
         end: IndexAffinity,
     ) -> Vec<Rect> {
-        // If the text is empty, there is nothing to draw.
-        if text.is_empty() {
-            return Vec::new();
-        }
-
-        // If the font did not load, there is nothing to draw.
-        let Some(font_id) = self.text_style.font.font_id else {
+        let Some((lines, line_height, _)) = self.caret_lines(cx, walk, width, text) else {
             return Vec::new();
         };
-        let mut font_ids = [0, 0];
-        let font_ids = if let Some(font2_id) = self.text_style.font2.font_id {
-            font_ids[0] = font_id;
-            font_ids[1] = font2_id;
-            &font_ids[..2]
-        } else {
-            font_ids[0] = font_id;
-            &font_ids[..1]
-        };
-
-        // Borrow the font atlas from the context.
-        let font_atlas_rc = cx.fonts_atlas_rc.clone();
-        let mut font_atlas_ref = font_atlas_rc.0.borrow_mut();
-        let font_atlas = &mut *font_atlas_ref;
-
-        // Borrow the shape cache from the context.
-        let shape_cache_rc = cx.shape_cache_rc.clone();
-        let mut shape_cache_ref = shape_cache_rc.0.borrow_mut();
-        let shape_cache = &mut *shape_cache_ref;
-
-        let font_size = self.text_style.font_size * self.font_scale;
-        let line_height = compute_line_height(font_ids, font_size, font_atlas) * self.text_style.line_scale;
-        let line_spacing = line_height * self.text_style.line_spacing;
-
-        let fixed_width = if !walk.width.is_fit() {
-            Some(width)
-        } else {
-            None
-        };
-
-        let wrap_width = if self.wrap == TextWrap::Word {
-            fixed_width
-        } else {
-            None
-        };
-
         let mut rects = Vec::new();
-        let mut prev_newline_index = 0;
-        let mut position = DVec2::new();
-        layout_text(
-            &mut position,
-            self.text_style.is_secret,
-            text,
-            font_ids,
-            font_size,
-            line_spacing,
-            wrap_width,
-            font_atlas,
-            shape_cache,
-            |position, index, event, _font_atlas| {
-                match event {
-                    LayoutEvent::Newline { .. } => {
-                        if start.index < index && end.index >= prev_newline_index {
-                            rects.push(Rect {
-                                pos: dvec2(0.0, position.y),
-                                size: dvec2(position.x, line_height),
-                            })
-                        }
-                        prev_newline_index = index;
+        for line in &lines {
+            if line.end < start.index || line.start > end.index {
+                continue;
+            }
+            // The x ranges of the selected graphemes. In mixed direction text a selection that
+            // is contiguous in the text can be split into several pieces on the screen.
+            let mut ranges = Vec::new();
+            for chunk in &line.chunks {
+                for carets in chunk.carets.windows(2) {
+                    let (index, x0) = carets[0];
+                    let x1 = carets[1].1;
+                    if index >= start.index && index < end.index {
+                        ranges.push((x0.min(x1), x0.max(x1)));
                     }
-                    _ => {}
                 }
-                false
             }
-        );
-        if end.index >= prev_newline_index {
-            rects.push(Rect {
-                pos: dvec2(0.0, position.y),
-                size: dvec2(position.x, line_height),
-            });
-        }
-        
-        drop(shape_cache_ref);
-        drop(font_atlas_ref);
-        
-        if let Some(rect) = rects.last_mut() {
-            let end_position = self.index_affinity_to_position(
-                cx,
-                walk,
-                _align,
-                width,
-                text,
-                end,
-            );
-
-            rect.size.x = end_position.x;
-        }
-
-        if let Some(rect) = rects.first_mut() {
-            let start_position = self.index_affinity_to_position(
-                cx,
-                walk,
-                _align,
-                width,
-                text,
-                start,
-            );
-
-            rect.pos.x = start_position.x;
-            rect.size.x -= start_position.x;
+            // Selecting past the end of a line selects the newline, shown as an empty rect.
+            if ranges.is_empty() && !line.is_soft_end && start.index <= line.end && end.index > line.end {
+                let x = line.x(line.end, Affinity::Before);
+                ranges.push((x, x));
+            }
+            ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut merged: Vec<(f64, f64)> = Vec::new();
+            for (x0, x1) in ranges {
+                match merged.last_mut() {
+                    Some(last) if x0 <= last.1 + 0.001 => last.1 = last.1.max(x1),
+                    _ => merged.push((x0, x1)),
+                }
+            }
+            rects.extend(merged.into_iter().map(|(x0, x1)| Rect {
+                pos: dvec2(x0, line.y),
+                size: dvec2(x1 - x0, line_height),
+            }));
         }
-
         rects
     }
 
@@ -472,115 +477,37 @@ impl DrawText {
         text: &str,
         target_position: DVec2,
     ) -> IndexAffinity {
-        // If the text is empty, there is nothing to draw.
-        if text.is_empty() {
-            return IndexAffinity::new(text.len(), Affinity::After);
-        }
-
-        // If the font did not load, there is nothing to draw.
-        let Some(font_id) = self.text_style.font.font_id else {
+        let Some((lines, _, line_spacing)) = self.caret_lines(cx, walk, width, text) else {
             return IndexAffinity::new(text.len(), Affinity::After);
         };
-        let mut font_ids = [0, 0];
-        let font_ids = if let Some(font2_id) = self.text_style.font2.font_id {
-            font_ids[0] = font_id;
-            font_ids[1] = font2_id;
-            &font_ids[..2]
-        } else {
-            font_ids[0] = font_id;
-            &font_ids[..1]
-        };
-
-        // Borrow the font atlas from the context.
-        let font_atlas_rc = cx.fonts_atlas_rc.clone();
-        let mut font_atlas_ref = font_atlas_rc.0.borrow_mut();
-        let font_atlas = &mut *font_atlas_ref;
-
-        // Borrow the shape cache from the context.
-        let shape_cache_rc = cx.shape_cache_rc.clone();
-        let mut shape_cache_ref = shape_cache_rc.0.borrow_mut();
-        let shape_cache = &mut *shape_cache_ref;
-
-        let font_size = self.text_style.font_size * self.font_scale;
-        let line_height = compute_line_height(font_ids, font_size, font_atlas) * self.text_style.line_scale;
-        let line_spacing = line_height * self.text_style.line_spacing;
-
-        let fixed_width = if !walk.width.is_fit() {
-            Some(width)
-        } else {
-            None
-        };
-
-        let wrap_width = if self.wrap == TextWrap::Word {
-            fixed_width
-        } else {
-            None
-        };
-
-        let mut closest = IndexAffinity::new(text.len(), Affinity::After);
-        let line_height = compute_line_height(font_ids, font_size, font_atlas);
-        let mut prev_glyph_end = 0;
-        let mut position = DVec2::new();
-        layout_text(
-            &mut position,
-            self.text_style.is_secret,
-            text,
-            font_ids,
-            font_size,
-            line_spacing,
-            wrap_width,
-            font_atlas,
-            shape_cache,
-            |position, start, event, font_atlas| {
-                match event {
-                    LayoutEvent::Chunk {
-                        string,
-                        glyph_infos,
-                        ..
-                    } => {
-                        let mut position = position;
-                        let mut iter = glyph_infos.iter().peekable();
-                        while let Some(glyph_info) = iter.next() {
-                            let glyph_start = start + glyph_info.cluster;
-                            let glyph_end = start + iter.peek().map_or(string.len(), |glyph_info| glyph_info.cluster);
-                            let glyph_width = compute_glyph_width(glyph_info.font_id, glyph_info.glyph_id, font_size, font_atlas);
-                            let grapheme_count = text[glyph_start..glyph_end].graphemes(true).count();
-                            let width_per_grapheme = glyph_width / (grapheme_count + 1) as f64;
-                            for index in 0..grapheme_count {
-                                let next_position_x = position.x + width_per_grapheme;
-                                if target_position.x < next_position_x && target_position.y < position.y + line_spacing {
-                                    closest = IndexAffinity::new(
-                                        glyph_start + text[glyph_start..glyph_end].grapheme_indices(true).nth(index).unwrap().0,
-                                        Affinity::After,
-                                    );
-                                    return true;
-                                }
-                                position.x = next_position_x;
-                            }
-                            position.x += width_per_grapheme;
-                            prev_glyph_end = glyph_end;
-                        }
-                    }
-                    LayoutEvent::Newline { is_soft } => {
-                        if target_position.y < position.y + line_height {
-                            closest = IndexAffinity::new(
-                                prev_glyph_end,
-                                if is_soft {
-                                    Affinity::Before
-                                } else {
-                                    Affinity::After
-                                }
-                            );
-                            return true;
-                        }
-                        prev_glyph_end += 1;
-                    }
+        let line = lines
+            .iter()
+            .find(|line| target_position.y < line.y + line_spacing)
+            .unwrap_or(lines.last().unwrap());
+
+        // Find the caret closest to the target. In right-to-left text carets are still ordered
+        // by index, so every caret on the line has to be looked at.
+        let mut closest = (line.start, f64::INFINITY, f64::INFINITY);
+        for chunk in &line.chunks {
+            for &(index, x) in &chunk.carets {
+                let distance = (x - target_position.x).abs();
+                if distance < closest.2 {
+                    closest = (index, x, distance);
                 }
-                false
             }
-        );
-
-        closest
+        }
+        let (index, x, _) = closest;
+
+        // At the end of a soft wrapped line, or where a run of the other direction starts, the
+        // same index is shown in two places: the affinity picks the one that was clicked.
+        let affinity = if line.is_soft_end && index == line.end {
+            Affinity::Before
+        } else if (line.x(index, Affinity::After) - x).abs() < 0.001 {
+            Affinity::After
+        } else {
+            Affinity::Before
+        };
+        IndexAffinity::new(index, affinity)
     }
 
     pub fn index_affinity_to_position(
@@ -592,29 +519,41 @@ impl DrawText {
         text: &str,
         target: IndexAffinity,
     ) -> DVec2 {
-        // If the text is empty, there is nothing to draw.
-        if text.is_empty() {
+        let Some((lines, _, _)) = self.caret_lines(cx, walk, width, text) else {
             return DVec2::new();
+        };
+        let line = lines
+            .iter()
+            .find(|line| {
+                target.index < line.end
+                    || target.index == line.end && !(line.is_soft_end && target.affinity == Affinity::After)
+            })
+            .unwrap_or(lines.last().unwrap());
+        dvec2(line.x(target.index, target.affinity), line.y)
+    }
+
+    /// Lays out the text the same way `draw_walk` does, and returns its lines with the carets in
+    /// each of them, together with the line height and line spacing.
+    fn caret_lines(
+        &self,
+        cx: &mut Cx2d,
+        walk: Walk,
+        width: f64,
+        text: &str,
+    ) -> Option<(Vec<CaretLine>, f64, f64)> {
+        // If the text is empty, there are no lines.
+        if text.is_empty() {
+            return None;
         }
 
-        // If the font did not load, there is nothing to draw.
-        let Some(font_id) = self.text_style.font.font_id else {
-            return DVec2::new();
-        };
-        let mut font_ids = [0, 0];
-        let font_ids = if let Some(font2_id) = self.text_style.font2.font_id {
-            font_ids[0] = font_id;
-            font_ids[1] = font2_id;
-            &font_ids[..2]
-        } else {
-            font_ids[0] = font_id;
-            &font_ids[..1]
-        };
+        // If the font did not load, there are no lines.
+        self.text_style.font_id()?;
 
         // Borrow the font atlas from the context.
         let font_atlas_rc = cx.fonts_atlas_rc.clone();
         let mut font_atlas_ref = font_atlas_rc.0.borrow_mut();
         let font_atlas = &mut *font_atlas_ref;
+        let font_ids = &*self.text_style.resolve_font_ids(font_atlas);
 
         // Borrow the shape cache from the context.
         let shape_cache_rc = cx.shape_cache_rc.clone();
@@ -622,6 +561,8 @@ impl DrawText {
         let shape_cache = &mut *shape_cache_ref;
 
         let font_size = self.text_style.font_size * self.font_scale;
+        let letter_spacing = self.text_style.letter_spacing * self.font_scale;
+        let hyphenator = self.text_style.hyphenation.hyphenator.clone();
         let line_height = compute_line_height(font_ids, font_size, font_atlas) * self.text_style.line_scale;
         let line_spacing = line_height * self.text_style.line_spacing;
 
@@ -637,8 +578,8 @@ impl DrawText {
             None
         };
 
-        let mut closest_position = None;
-        let mut prev_glyph_end = 0;
+        let mut lines = Vec::new();
+        let mut line = CaretLine::default();
         let mut position = DVec2::new();
         layout_text(
             &mut position,
@@ -646,8 +587,10 @@ impl DrawText {
             text,
             font_ids,
             font_size,
+            letter_spacing,
             line_spacing,
             wrap_width,
+            hyphenator.as_deref(),
             font_atlas,
             shape_cache,
             |position, start, event, font_atlas| {
@@ -655,39 +598,25 @@ impl DrawText {
                     LayoutEvent::Chunk {
                         string,
                         glyph_infos,
+                        is_rtl,
                         ..
                     } => {
-                        let mut position = position;
-                        let mut iter = glyph_infos.iter().peekable();
-                        while let Some(glyph_info) = iter.next() {
-                            let glyph_start = start + glyph_info.cluster;
-                            let glyph_end = start + iter.peek().map_or(string.len(), |glyph_info| glyph_info.cluster);
-                            let glyph_width = compute_glyph_width(glyph_info.font_id, glyph_info.glyph_id, font_size, font_atlas);
-                            let grapheme_count = text[glyph_start..glyph_end].graphemes(true).count();
-                            let glyph_width_per_grapheme = glyph_width / grapheme_count as f64;
-                            for (grapheme_start, grapheme) in text[glyph_start..glyph_end].grapheme_indices(true) {
-                                if target.index < glyph_start + grapheme_start + grapheme.len() {
-                                    closest_position = Some(position);
-                                    return true;
-                                }
-                                position.x += glyph_width_per_grapheme;
-                            }
-                            prev_glyph_end = glyph_end;
+                        let mut carets = chunk_carets(start, string, glyph_infos, is_rtl, font_size, letter_spacing, font_atlas);
+                        for caret in &mut carets {
+                            caret.1 += position.x;
                         }
+                        line.push(position.y, start, start + string.len(), carets);
                     }
                     LayoutEvent::Newline { is_soft } => {
-                        if target.index == prev_glyph_end && (!is_soft || target.affinity == Affinity::Before) {
-                            closest_position = Some(position);
-                            return true;
-                        }
-                        prev_glyph_end += 1;
+                        line.is_soft_end = is_soft;
+                        lines.push(std::mem::take(&mut line));
                     }
                 }
                 false
             }
         );
-
-        closest_position.unwrap_or(position)
+        lines.push(line);
+        Some((lines, line_height, line_spacing))
     }
 
     fn draw_inner(&mut self, cx: &mut Cx2d, position: DVec2, line: &str, font_atlas: &mut CxFontAtlas) {
@@ -699,18 +628,10 @@ impl DrawText {
         }
 
         // If the font did not load, there is nothing to draw.
-        let Some(font_id) = self.text_style.font.font_id else {
+        if self.text_style.font_id().is_none() {
             return;
-        };
-        let mut font_ids = [0, 0];
-        let font_ids = if let Some(font2_id) = self.text_style.font2.font_id {
-            font_ids[0] = font_id;
-            font_ids[1] = font2_id;
-            &font_ids[..2]
-        } else {
-            font_ids[0] = font_id;
-            &font_ids[..1]
-        };
+        }
+        let font_ids = &*self.text_style.resolve_font_ids(font_atlas);
 
         // Borrow the shape cache from the context.
         let shape_cache_rc = cx.shape_cache_rc.clone();
@@ -718,6 +639,8 @@ impl DrawText {
         let shape_cache = &mut *shape_cache_ref;
 
         let font_size = self.text_style.font_size * self.font_scale;
+        let letter_spacing = self.text_style.letter_spacing * self.font_scale;
+        let hyphenator = self.text_style.hyphenation.hyphenator.clone();
         let line_height = compute_line_height(font_ids, font_size, font_atlas) * self.text_style.line_scale;
         let line_spacing = line_height * self.text_style.line_spacing;
         
@@ -731,12 +654,15 @@ impl DrawText {
             line.len(),
             font_ids,
             font_size,
+            letter_spacing,
             line_spacing,
             None,
+            hyphenator.as_deref(),
             font_atlas,
             shape_cache,
             |position, _, event, font_atlas| {
                 if let LayoutEvent::Chunk {
+                    width,
                     glyph_infos,
                     ..
                 } = event {
@@ -747,6 +673,7 @@ impl DrawText {
                         &glyph_infos, 
                         font_atlas
                     );
+                    self.draw_decorations(cx, origin + position, width, font_ids[0], font_size, font_atlas);
                 }
                 false
             }
@@ -769,23 +696,15 @@ impl DrawText {
         }
         
         // If the font did not load, there is nothing to draw.
-        let Some(font_id) = self.text_style.font.font_id else {
+        if self.text_style.font_id().is_none() {
             return;
-        };
-        let mut font_ids = [0, 0];
-        let font_ids = if let Some(font2_id) = self.text_style.font2.font_id {
-            font_ids[0] = font_id;
-            font_ids[1] = font2_id;
-            &font_ids[..2]
-        } else {
-            font_ids[0] = font_id;
-            &font_ids[..1]
-        };
+        }
         
         // Borrow the font atlas from the context.
         let font_atlas_rc = cx.fonts_atlas_rc.clone();
         let mut font_atlas = font_atlas_rc.0.borrow_mut();
         let font_atlas = &mut *font_atlas;
+        let font_ids = &*self.text_style.resolve_font_ids(font_atlas);
 
         // Borrow the shape cache from the context.
         let shape_cache_rc = cx.shape_cache_rc.clone();
@@ -793,6 +712,8 @@ impl DrawText {
         let shape_cache = &mut *shape_cache;
 
         let font_size = self.text_style.font_size * self.font_scale;
+        let letter_spacing = self.text_style.letter_spacing * self.font_scale;
+        let hyphenator = self.text_style.hyphenation.hyphenator.clone();
         let line_height = compute_line_height(font_ids, font_size, font_atlas) * self.text_style.line_scale;
         let line_spacing = line_height * self.text_style.line_spacing;
 
@@ -826,8 +747,10 @@ impl DrawText {
             text,
             font_ids,
             font_size,
+            letter_spacing,
             line_spacing,
             wrap_width,
+            hyphenator.as_deref(),
             font_atlas,
             shape_cache,
             |position, _, event, _| {
@@ -859,6 +782,7 @@ impl DrawText {
             margin: walk.margin,
             width: Size::Fixed(width),
             height: Size::Fixed(height),
+            ..walk
         });
 
         // cx.cx.debug.rect(rect, vec4(1.0, 0.0, 0.0, 1.0));
@@ -871,12 +795,15 @@ impl DrawText {
             text,
             font_ids,
             font_size,
+            letter_spacing,
             line_spacing,
             wrap_width,
+            hyphenator.as_deref(),
             font_atlas,
             shape_cache,
             |position, _, event, font_atlas| {
                 if let LayoutEvent::Chunk {
+                    width,
                     glyph_infos,
                     ..
                     //string,
@@ -889,6 +816,7 @@ impl DrawText {
                         glyph_infos,
                         font_atlas,
                     );
+                    self.draw_decorations(cx, rect.pos + position, width, font_ids[0], font_size, font_atlas);
                 }
                 false
             }
@@ -912,7 +840,21 @@ impl DrawText {
         &mut self,
         cx: &mut Cx2d,
         text: &str,
-        mut f: impl FnMut(&mut Cx2d, Rect)
+        f: impl FnMut(&mut Cx2d, Rect)
+    ) {
+        self.draw_walk_resumable_with_chunks(cx, text, f, |_, _| {});
+    }
+
+    /// Like `draw_walk_resumable_with`, and also reports every piece of the text as it was
+    /// laid out: its rect, and the byte indices in `text` where a cursor can go with their x
+    /// offset from the left of the rect, from the start to the end of the piece. In right-to-left
+    /// pieces the offsets go from right to left.
+    pub fn draw_walk_resumable_with_chunks(
+        &mut self,
+        cx: &mut Cx2d,
+        text: &str,
+        mut f: impl FnMut(&mut Cx2d, Rect),
+        mut chunk: impl FnMut(Rect, &[(usize, f64)]),
     ) {
         self.char_depth = self.draw_depth;
         
@@ -922,23 +864,15 @@ impl DrawText {
         }
         
         // If the font did not load, there is nothing to draw.
-        let Some(font_id) = self.text_style.font.font_id else {
+        if self.text_style.font_id().is_none() {
             return
-        };
-        let mut font_ids = [0, 0];
-        let font_ids = if let Some(font2_id) = self.text_style.font2.font_id {
-            font_ids[0] = font_id;
-            font_ids[1] = font2_id;
-            &font_ids[..2]
-        } else {
-            font_ids[0] = font_id;
-            &font_ids[..1]
-        };
+        }
 
         // Borrow the font atlas from the context.
         let font_atlas_rc = cx.fonts_atlas_rc.clone();
         let mut font_atlas = font_atlas_rc.0.borrow_mut();
         let font_atlas = &mut *font_atlas;
+        let font_ids = &*self.text_style.resolve_font_ids(font_atlas);
 
         // Borrow the shape cache from the context.
         let shape_cache_rc = cx.shape_cache_rc.clone();
@@ -946,6 +880,8 @@ impl DrawText {
         let shape_cache = &mut *shape_cache;
 
         let font_size = self.text_style.font_size * self.font_scale;
+        let letter_spacing = self.text_style.letter_spacing * self.font_scale;
+        let hyphenator = self.text_style.hyphenation.hyphenator.clone();
         let line_height = compute_line_height(font_ids, font_size, font_atlas) * self.text_style.line_scale;
         let line_spacing = line_height * self.text_style.line_spacing;
 
@@ -969,23 +905,27 @@ impl DrawText {
             text,
             font_ids,
             font_size,
+            letter_spacing,
             line_spacing,
             wrap_width,
+            hyphenator.as_deref(),
             font_atlas,
             shape_cache,
-            |_, _, event, font_atlas| {
+            |_, start, event, font_atlas| {
                 match event {
                     LayoutEvent::Chunk {
                         width,
+                        string,
                         glyph_infos,
-                        ..
+                        is_rtl,
                     } => {
                         cx.set_turtle_wrap_spacing(line_spacing - line_height);
                         let rect = cx.walk_turtle(Walk {
                             abs_pos: None,
                             margin: Margin::default(),
                             width: Size::Fixed(width),
-                            height: Size::Fixed(line_height)
+                            height: Size::Fixed(line_height),
+                            ..Default::default()
                         });
 
                         self.draw_glyphs(
@@ -995,6 +935,10 @@ impl DrawText {
                             &glyph_infos,
                             font_atlas
                         );
+                        self.draw_decorations(cx, rect.pos, width, font_ids[0], font_size, font_atlas);
+
+                        let carets = chunk_carets(start, string, glyph_infos, is_rtl, font_size, letter_spacing, font_atlas);
+                        chunk(rect, &carets);
 
                         if let Some(prev_rect) = &mut prev_rect_slot {
                             if prev_rect.pos.y == rect.pos.y {
@@ -1066,27 +1010,33 @@ impl DrawText {
         let glyph_padding_lpx = glyph_padding_dpx / device_pixel_ratio;
 
         
+        let advances = compute_glyph_advances(glyph_infos, self.text_style.font_size, self.text_style.letter_spacing, font_atlas);
         let mut position = position;
-        for glyph_info in glyph_infos {
+        for (glyph_info, advance_width) in glyph_infos.iter().zip(advances) {
             let font = font_atlas.fonts[glyph_info.font_id].as_mut().unwrap();
             let units_per_em = font.ttf_font.units_per_em;
             let ascender = units_to_lpxs(font.ttf_font.ascender, units_per_em, font_size) * self.text_style.line_scale;
             
+            // Color glyphs are drawn from the color page of the atlas, over the bounds of what
+            // their layers paint rather than the bounds of their outline.
+            let color_bounds = font.get_color_glyph_bounds(glyph_info.glyph_id);
+
             // Use the glyph id to get the glyph from the font.
             let glyph = font.owned_font_face.with_ref(|face| {
                 font.ttf_font.get_glyph_by_id(face, glyph_info.glyph_id as usize).unwrap()
             });
+            let bounds = color_bounds.unwrap_or(glyph.bounds);
 
             // Compute the position of the glyph.
             let glyph_position = dvec2(
-                units_to_lpxs(glyph.bounds.p_min.x, units_per_em, font_size),
-                units_to_lpxs(glyph.bounds.p_min.y, units_per_em, font_size),
+                units_to_lpxs(bounds.p_min.x, units_per_em, font_size),
+                units_to_lpxs(bounds.p_min.y, units_per_em, font_size),
             );
             
             // Compute the size of the bounding box of the glyph in logical pixels.
             let glyph_size_lpx = dvec2(
-                units_to_lpxs(glyph.bounds.p_max.x - glyph.bounds.p_min.x, units_per_em, font_size),
-                units_to_lpxs(glyph.bounds.p_max.y - glyph.bounds.p_min.y, units_per_em, font_size),
+                units_to_lpxs(bounds.p_max.x - bounds.p_min.x, units_per_em, font_size),
+                units_to_lpxs(bounds.p_max.y - bounds.p_min.y, units_per_em, font_size),
             );
 
             // Compute the size of the bounding box of the glyph in device pixels.
@@ -1105,7 +1055,10 @@ impl DrawText {
             let padded_glyph_size_lpx = padded_glyph_size_dpx / device_pixel_ratio;
             
             // Compute the left side bearing.
-            let left_side_bearing = units_to_lpxs(glyph.horizontal_metrics.left_side_bearing, units_per_em, font_size);
+            let left_side_bearing = match color_bounds {
+                Some(bounds) => units_to_lpxs(bounds.p_min.x, units_per_em, font_size),
+                None => units_to_lpxs(glyph.horizontal_metrics.left_side_bearing, units_per_em, font_size),
+            };
 
             // Use the font size in device pixels to get the atlas page id from the font.
             let atlas_page_id = font.get_atlas_page_id(units_to_lpxs(1.0, units_per_em, font_size / self.font_scale) * device_pixel_ratio);
@@ -1115,8 +1068,8 @@ impl DrawText {
 
             // Use the padded glyph size in device pixels to get the atlas glyph from the atlas page.
             let atlas_glyph = *atlas_page.atlas_glyphs.entry(glyph_info.glyph_id as usize).or_insert_with(|| {
-                font_atlas
-                    .alloc
+                let alloc = if color_bounds.is_some() {&mut font_atlas.color_alloc} else {&mut font_atlas.alloc};
+                alloc
                     .alloc_atlas_glyph(
                         padded_glyph_size_dpx.x / self.font_scale,
                         padded_glyph_size_dpx.y / self.font_scale,
@@ -1134,12 +1087,10 @@ impl DrawText {
                 ascender - glyph_position.y + glyph_padding_lpx
             );
 
-            // Compute the advance width.
-            let advance_width = compute_glyph_width(glyph_info.font_id, glyph_info.glyph_id, self.text_style.font_size, font_atlas);
-            
             // Emit the instance data.
             self.font_t1 = atlas_glyph.t1;
             self.font_t2 = atlas_glyph.t2;
+            self.is_color = if color_bounds.is_some() {1.0} else {0.0};
             self.rect_pos = (position + delta).into();
             self.rect_size = padded_glyph_size_lpx.into();
             mi.instances.extend_from_slice(self.draw_vars.as_slice());
@@ -1150,6 +1101,88 @@ impl DrawText {
             position.x += advance_width;
         }
     }
+
+    /// Draws the underline and strikethrough of a piece of laid out text of the given width, at
+    /// the position and thickness the font asks for unless the style overrides them.
+    fn draw_decorations(
+        &mut self,
+        cx: &mut Cx2d,
+        position: DVec2,
+        width: f64,
+        font_id: usize,
+        font_size: f64,
+        font_atlas: &mut CxFontAtlas,
+    ) {
+        // If there are no decorations, or nowhere to draw them, there is nothing to draw.
+        if !self.text_style.underline && !self.text_style.strikethrough {
+            return;
+        }
+        if position.x.is_infinite() || position.x.is_nan() || width <= 0.0 {
+            return;
+        }
+        if !self.draw_vars.can_instance() {
+            return;
+        }
+        let Some(font) = font_atlas.fonts[font_id].as_ref() else {
+            return;
+        };
+        let units_per_em = font.ttf_font.units_per_em;
+        let ascender = units_to_lpxs(font.ttf_font.ascender, units_per_em, font_size) * self.text_style.line_scale;
+        let (underline, strikeout) = font.owned_font_face.with_ref(|face| {
+            (face.underline_metrics(), face.strikeout_metrics())
+        });
+        // fonts without the metrics get lines a tenth of an em below and three tenths above the baseline
+        let em = units_per_em;
+        let metrics = |metrics: Option<makepad_rustybuzz::ttf_parser::LineMetrics>, position: f64| {
+            metrics.map_or((position * em, 0.05 * em), |metrics| (metrics.position as f64, metrics.thickness as f64))
+        };
+        let mut lines = Vec::new();
+        if self.text_style.underline {
+            lines.push(metrics(underline, -0.1));
+        }
+        if self.text_style.strikethrough {
+            lines.push(metrics(strikeout, 0.3));
+        }
+
+        // Lock the instance buffer.
+        if self.many_instances.is_none() {
+            self.begin_many_instances_internal(cx, font_atlas);
+        }
+        let Some(mi) = &mut self.many_instances else {
+            return;
+        };
+
+        let min_thickness = 1.0 / cx.current_dpi_factor();
+        let color = self.color;
+        if let Some(decoration_color) = self.text_style.decoration_color {
+            self.color = decoration_color;
+        }
+        self.is_color = 0.0;
+        self.is_decoration = 1.0;
+        for (offset, thickness) in lines {
+            let thickness = if self.text_style.decoration_thickness > 0.0 {
+                self.text_style.decoration_thickness
+            } else {
+                units_to_lpxs(thickness, units_per_em, font_size).max(min_thickness)
+            };
+            // the offset is up from the baseline, and quads extend up from their position
+            let center = position.y + ascender - units_to_lpxs(offset, units_per_em, font_size);
+            self.rect_pos = dvec2(position.x, center + thickness / 2.0).into();
+            self.rect_size = dvec2(width, thickness).into();
+            mi.instances.extend_from_slice(self.draw_vars.as_slice());
+            self.char_depth += ZBIAS_STEP;
+        }
+        self.is_decoration = 0.0;
+        self.color = color;
+    }
+}
+
+/// Whether the paragraph of `text` that contains `index` reads from right to left, which is
+/// decided by its first strong character.
+pub fn is_rtl_paragraph(text: &str, index: usize) -> bool {
+    let start = text[..index].rfind('\n').map_or(0, |newline| newline + 1);
+    let end = text[index..].find('\n').map_or(text.len(), |newline| index + newline);
+    unicode_bidi::get_base_direction(&text[start..end]) == unicode_bidi::Direction::Rtl
 }
 
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -1182,8 +1215,10 @@ fn layout_text(
     text: &str,
     font_ids: &[usize],
     font_size: f64,
+    letter_spacing: f64,
     line_spacing: f64,
     wrap_width: Option<f64>,
+    hyphenator: Option<&Hyphenator>,
     font_atlas: &mut CxFontAtlas,
     shape_cache: &mut CxShapeCache,
     mut f: impl FnMut(DVec2, usize, LayoutEvent, &mut CxFontAtlas) -> bool,
@@ -1206,8 +1241,10 @@ fn layout_text(
             line_end,
             font_ids,
             font_size,
+            letter_spacing,
             line_spacing,
             wrap_width,
+            hyphenator,
             font_atlas,
             shape_cache,
             &mut f,
@@ -1218,6 +1255,9 @@ fn layout_text(
     false
 }
 
+/// Lays out a paragraph. The chunks of each line are collected first, and only reported once the
+/// line is complete, because the order they are shown in depends on the bidi levels of the whole
+/// line.
 fn layout_line(
     position: &mut DVec2,
     is_secret: bool,
@@ -1226,27 +1266,32 @@ fn layout_line(
     line_end: usize,
     font_ids: &[usize],
     font_size: f64,
+    letter_spacing: f64,
     line_spacing: f64,
     wrap_width: Option<f64>,
+    hyphenator: Option<&Hyphenator>,
     font_atlas: &mut CxFontAtlas,
     shape_cache: &mut CxShapeCache,
     mut f: impl FnMut(DVec2, usize, LayoutEvent, &mut CxFontAtlas) -> bool,
 ) -> bool {
     let line = &text[line_start..line_end];
+    let mut line_layout = LineLayout::new(is_secret, line_start, line);
     for (index, word) in words(line).enumerate() {
         let word_start = word.as_ptr() as usize - text.as_ptr() as usize;
         let word_end = word_start + word.len();
         if layout_word(
             position,
-            is_secret,
+            &mut line_layout,
             index == 0,
             text,
             word_start,
             word_end,
             font_ids,
             font_size,
+            letter_spacing,
             line_spacing,
             wrap_width,
+            hyphenator,
             font_atlas, 
             shape_cache,
             &mut f,
@@ -1254,49 +1299,106 @@ fn layout_line(
             return true;
         }
     }
-    false
+    line_layout.flush(position, text, wrap_width, font_atlas, &mut f)
 }
 
 fn layout_word(
     position: &mut DVec2,
-    is_secret: bool,
+    line_layout: &mut LineLayout,
     is_first: bool,
     text: &str,
     word_start: usize,
     word_end: usize,
     font_ids: &[usize],
     font_size: f64,
+    letter_spacing: f64,
     line_spacing: f64,
     wrap_width: Option<f64>,
+    hyphenator: Option<&Hyphenator>,
     font_atlas: &mut CxFontAtlas,
     shape_cache: &mut CxShapeCache,
     mut f: impl FnMut(DVec2, usize, LayoutEvent, &mut CxFontAtlas) -> bool,
 ) -> bool {
-    let word = &text[word_start..word_end];
-    let glyph_infos = shape(is_secret, word, font_ids, font_atlas, shape_cache);
-    let width: f64 = glyph_infos.iter().map(|glyph_info| {
-        compute_glyph_width(glyph_info.font_id, glyph_info.glyph_id, font_size, font_atlas)
-    }).sum();
-    if wrap_width.map_or(false, |wrap_width| position.x + width > wrap_width) && !is_first {
-        if f(*position, word_start, LayoutEvent::Newline { is_soft: true }, font_atlas) {
-            return true;
+    let mut is_first = is_first;
+    let mut word_start = word_start;
+    // where the word can be hyphenated, found the first time it doesn't fit
+    let mut hyphens: Option<Vec<usize>> = None;
+    loop {
+        // A word that mixes directions is shaped as one run per direction.
+        let chunks: Vec<LineChunk> = line_layout.level_runs(word_start, word_end).into_iter().map(|run| {
+            line_layout.chunk(text, run, font_ids, font_size, letter_spacing, font_atlas, shape_cache)
+        }).collect();
+        let width: f64 = chunks.iter().map(|chunk| chunk.width).sum();
+        // Spaces at the end of a line hang past its end, so they don't count for whether the
+        // word fits. A soft hyphen at the end of the word shows as a hyphen if the line wraps
+        // after it, so that has to fit as well.
+        let hanging = chunks.last().map_or(0.0, |chunk| chunk.hanging);
+        let soft_hyphen_width = if text[word_start..word_end].ends_with(SOFT_HYPHEN) {
+            line_layout.hyphen(word_end, font_ids, font_size, letter_spacing, font_atlas, shape_cache).width
+        } else {
+            0.0
+        };
+        if wrap_width.is_none_or(|wrap_width| position.x + width - hanging + soft_hyphen_width <= wrap_width) {
+            position.x += width;
+            line_layout.chunks.extend(chunks);
+            return false;
         }
-        position.x = 0.0;
-        position.y += line_spacing;
-    }
-    if wrap_width.map_or(false, |wrap_width| position.x + width > wrap_width) {
+
+        // A word that doesn't fit is hyphenated if the dictionary allows it, with as much of
+        // it on the line as fits, and the rest wrapped to the next line.
+        let hyphens = hyphens.get_or_insert_with(|| match hyphenator {
+            Some(hyphenator) if !line_layout.is_secret => {
+                hyphenator.hyphenate(&text[word_start..word_end]).into_iter().map(|hyphen| word_start + hyphen).collect()
+            }
+            _ => Vec::new(),
+        });
+        if let Some((split, chunks)) = line_layout.hyphenate(
+            position.x,
+            text,
+            word_start,
+            hyphens,
+            font_ids,
+            font_size,
+            letter_spacing,
+            wrap_width,
+            font_atlas,
+            shape_cache,
+        ) {
+            position.x += chunks.iter().map(|chunk| chunk.width).sum::<f64>();
+            line_layout.chunks.extend(chunks);
+            if wrap_line(position, line_layout, text, split, line_spacing, wrap_width, font_atlas, &mut f) {
+                return true;
+            }
+            word_start = split;
+            is_first = true;
+            continue;
+        }
+
+        // Otherwise it goes on the next line.
+        if !is_first {
+            line_layout.end_with_soft_hyphen(position, text, font_ids, font_size, letter_spacing, font_atlas, shape_cache);
+            if wrap_line(position, line_layout, text, word_start, line_spacing, wrap_width, font_atlas, &mut f) {
+                return true;
+            }
+            is_first = true;
+            continue;
+        }
+
+        // A word that doesn't fit on a line of its own is broken between any two graphemes.
+        let word = &text[word_start..word_end];
         for (index, grapheme) in graphemes(word).enumerate() {
             let grapheme_start = grapheme.as_ptr() as usize - text.as_ptr() as usize;
             let grapheme_end = grapheme_start + grapheme.len();
             if layout_grapheme(
                 position,
-                is_secret,
+                line_layout,
                 index == 0,
                 text,
                 grapheme_start,
                 grapheme_end,
                 font_ids,
                 font_size,
+                letter_spacing,
                 line_spacing,
                 wrap_width,
                 font_atlas,
@@ -1306,62 +1408,385 @@ fn layout_word(
                 return true;
             }
         }
-    } else {
-        if f(*position, word_start, LayoutEvent::Chunk {
-            width,
-            string: word,
-            glyph_infos: &glyph_infos,
-        }, font_atlas) {
-            return true;
-        }
-        position.x += width;
+        return false;
+    }
+}
+
+/// Reports the line so far and starts a new one at `index`, because the text wraps there.
+#[allow(clippy::too_many_arguments)]
+fn wrap_line(
+    position: &mut DVec2,
+    line_layout: &mut LineLayout,
+    text: &str,
+    index: usize,
+    line_spacing: f64,
+    wrap_width: Option<f64>,
+    font_atlas: &mut CxFontAtlas,
+    mut f: impl FnMut(DVec2, usize, LayoutEvent, &mut CxFontAtlas) -> bool,
+) -> bool {
+    if line_layout.flush(position, text, wrap_width, font_atlas, &mut f) {
+        return true;
     }
+    if f(*position, index, LayoutEvent::Newline { is_soft: true }, font_atlas) {
+        return true;
+    }
+    position.x = 0.0;
+    position.y += line_spacing;
     false
 }
 
 fn layout_grapheme(
     position: &mut DVec2,
-    is_secret: bool,
+    line_layout: &mut LineLayout,
     is_first: bool,
     text: &str,
     grapheme_start: usize,
     grapheme_end: usize,
     font_ids: &[usize],
     font_size: f64,
+    letter_spacing: f64,
     line_spacing: f64,
     wrap_width: Option<f64>,
     font_atlas: &mut CxFontAtlas,
     shape_cache: &mut CxShapeCache, 
     mut f: impl FnMut(DVec2, usize, LayoutEvent, &mut CxFontAtlas) -> bool,
 ) -> bool {
-    let grapheme = &text[grapheme_start..grapheme_end];
-    let glyph_infos = shape(is_secret, grapheme, font_ids, font_atlas, shape_cache);
-    let width: f64 = glyph_infos.iter().map(|glyph_info| {
-        compute_glyph_width(glyph_info.font_id, glyph_info.glyph_id, font_size, font_atlas)
-    }).sum();
-    if wrap_width.map_or(false, |wrap_width| position.x + width > wrap_width) && !is_first {
-        if f(*position, grapheme_start, LayoutEvent::Newline { is_soft: true }, font_atlas) {
-            return true;
-        }
-        position.x = 0.0;
-        position.y += line_spacing;
-    }
-    if f(*position, grapheme_start, LayoutEvent::Chunk {
-        width,
-        string: grapheme,
-        glyph_infos: &glyph_infos
-    }, font_atlas) {
+    let chunk = line_layout.chunk(text, grapheme_start..grapheme_end, font_ids, font_size, letter_spacing, font_atlas, shape_cache);
+    if wrap_width.is_some_and(|wrap_width| position.x + chunk.width - chunk.hanging > wrap_width)
+        && !is_first
+        && wrap_line(position, line_layout, text, grapheme_start, line_spacing, wrap_width, font_atlas, &mut f)
+    {
         return true;
     }
-    position.x += width;
+    position.x += chunk.width;
+    line_layout.chunks.push(chunk);
     false
 }
 
+/// The line that is being laid out, and the bidi levels of the paragraph it is part of.
+struct LineLayout {
+    is_secret: bool,
+    paragraph_start: usize,
+    paragraph_level: Level,
+    // the embedding level of every byte of the paragraph, empty if it is all left-to-right
+    levels: Vec<Level>,
+    // the chunks of the line so far, in logical order
+    chunks: Vec<LineChunk>,
+}
+
+impl LineLayout {
+    fn new(is_secret: bool, paragraph_start: usize, paragraph: &str) -> Self {
+        // secret text is shown as a row of identical glyphs, which doesn't need reordering
+        let bidi_info = (!is_secret).then(|| ParagraphBidiInfo::new(paragraph, None));
+        let (paragraph_level, levels) = match bidi_info {
+            Some(bidi_info) if !bidi_info.is_pure_ltr => (bidi_info.paragraph_level, bidi_info.levels),
+            _ => (Level::ltr(), Vec::new()),
+        };
+        Self {
+            is_secret,
+            paragraph_start,
+            paragraph_level,
+            levels,
+            chunks: Vec::new(),
+        }
+    }
+
+    fn level(&self, index: usize) -> Level {
+        self.levels.get(index - self.paragraph_start).copied().unwrap_or(self.paragraph_level)
+    }
+
+    /// Splits the given range of the text into runs with the same embedding level.
+    fn level_runs(&self, start: usize, end: usize) -> Vec<Range<usize>> {
+        let mut runs: Vec<Range<usize>> = Vec::new();
+        for index in start..end {
+            match runs.last_mut() {
+                Some(run) if self.level(run.start) == self.level(index) => run.end = index + 1,
+                _ => runs.push(index..index + 1),
+            }
+        }
+        if runs.is_empty() {
+            runs.push(start..end);
+        }
+        runs
+    }
+
+    /// Shapes a run of text with one embedding level, in the direction of that level.
+    fn chunk(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        font_ids: &[usize],
+        font_size: f64,
+        letter_spacing: f64,
+        font_atlas: &mut CxFontAtlas,
+        shape_cache: &mut CxShapeCache,
+    ) -> LineChunk {
+        let level = self.level(range.start);
+        let string = &text[range.clone()];
+        let glyph_infos = shape(self.is_secret, level.is_rtl(), string, font_ids, font_atlas, shape_cache).into_owned();
+        let advances = compute_glyph_advances(&glyph_infos, font_size, letter_spacing, font_atlas);
+        let trimmed_len = string.trim_end_matches([' ', '\t', '\u{3000}']).len();
+        let hanging = glyph_infos.iter().zip(&advances)
+            .filter(|(glyph_info, _)| glyph_info.cluster >= trimmed_len)
+            .map(|(_, advance)| advance)
+            .sum();
+        LineChunk {
+            start: range.start,
+            end: range.end,
+            level,
+            width: advances.into_iter().sum(),
+            hanging,
+            glyph_infos,
+        }
+    }
+
+    /// The hyphen shown where a word is broken at `index`, in the direction of the text before it.
+    fn hyphen(
+        &self,
+        index: usize,
+        font_ids: &[usize],
+        font_size: f64,
+        letter_spacing: f64,
+        font_atlas: &mut CxFontAtlas,
+        shape_cache: &mut CxShapeCache,
+    ) -> LineChunk {
+        let level = self.level(index.saturating_sub(1).max(self.paragraph_start));
+        let glyph_infos = shape(false, level.is_rtl(), "-", font_ids, font_atlas, shape_cache).into_owned();
+        LineChunk {
+            start: index,
+            end: index,
+            level,
+            width: compute_glyph_advances(&glyph_infos, font_size, letter_spacing, font_atlas).into_iter().sum(),
+            hanging: 0.0,
+            glyph_infos,
+        }
+    }
+
+    /// Splits what is left of a word that doesn't fit on the line at `x` at the last of its
+    /// `hyphens` where the part before it still fits with a hyphen. Returns where the rest of the
+    /// word starts, and the chunks of the part before it ending in the hyphen.
+    #[allow(clippy::too_many_arguments)]
+    fn hyphenate(
+        &self,
+        x: f64,
+        text: &str,
+        word_start: usize,
+        hyphens: &[usize],
+        font_ids: &[usize],
+        font_size: f64,
+        letter_spacing: f64,
+        wrap_width: Option<f64>,
+        font_atlas: &mut CxFontAtlas,
+        shape_cache: &mut CxShapeCache,
+    ) -> Option<(usize, Vec<LineChunk>)> {
+        let wrap_width = wrap_width?;
+        for &split in hyphens.iter().rev().take_while(|split| **split > word_start) {
+            let mut chunks: Vec<LineChunk> = self.level_runs(word_start, split).into_iter().map(|run| {
+                self.chunk(text, run, font_ids, font_size, letter_spacing, font_atlas, shape_cache)
+            }).collect();
+            chunks.push(self.hyphen(split, font_ids, font_size, letter_spacing, font_atlas, shape_cache));
+            if x + chunks.iter().map(|chunk| chunk.width).sum::<f64>() <= wrap_width {
+                return Some((split, chunks));
+            }
+        }
+        None
+    }
+
+    /// Ends the line with a hyphen if its last word ends in a soft hyphen, as it wraps.
+    #[allow(clippy::too_many_arguments)]
+    fn end_with_soft_hyphen(
+        &mut self,
+        position: &mut DVec2,
+        text: &str,
+        font_ids: &[usize],
+        font_size: f64,
+        letter_spacing: f64,
+        font_atlas: &mut CxFontAtlas,
+        shape_cache: &mut CxShapeCache,
+    ) {
+        let Some(last) = self.chunks.last() else {
+            return;
+        };
+        if !text[last.start..last.end].ends_with(SOFT_HYPHEN) {
+            return;
+        }
+        let hyphen = self.hyphen(last.end, font_ids, font_size, letter_spacing, font_atlas, shape_cache);
+        position.x += hyphen.width;
+        self.chunks.push(hyphen);
+    }
+
+    /// Reports the chunks of the line in the order they are shown in, from left to right, and
+    /// starts a new line. Lines of right-to-left paragraphs are aligned to the right if the text
+    /// wraps.
+    fn flush(
+        &mut self,
+        position: &mut DVec2,
+        text: &str,
+        wrap_width: Option<f64>,
+        font_atlas: &mut CxFontAtlas,
+        mut f: impl FnMut(DVec2, usize, LayoutEvent, &mut CxFontAtlas) -> bool,
+    ) -> bool {
+        let width: f64 = self.chunks.iter().map(|chunk| chunk.width).sum();
+        let mut x = position.x - width;
+        if let Some(wrap_width) = wrap_width.filter(|_| self.paragraph_level.is_rtl()) {
+            x += (wrap_width - position.x).max(0.0);
+        }
+        let levels: Vec<Level> = self.chunks.iter().map(|chunk| chunk.level).collect();
+        for index in visual_order(&levels) {
+            let chunk = &self.chunks[index];
+            if f(dvec2(x, position.y), chunk.start, LayoutEvent::Chunk {
+                width: chunk.width,
+                string: &text[chunk.start..chunk.end],
+                glyph_infos: &chunk.glyph_infos,
+                is_rtl: chunk.level.is_rtl(),
+            }, font_atlas) {
+                return true;
+            }
+            x += chunk.width;
+        }
+        position.x = x;
+        self.chunks.clear();
+        false
+    }
+}
+
+/// A run of text with one direction, shaped.
+struct LineChunk {
+    start: usize,
+    end: usize,
+    level: Level,
+    width: f64,
+    // the width of the spaces at the end, which may hang past the end of the line
+    hanging: f64,
+    glyph_infos: Vec<font_atlas::GlyphInfo>,
+}
+
+/// The order to show runs with the given embedding levels in, following rule L2 of the Unicode
+/// Bidirectional Algorithm: from the highest level down to the lowest odd level, every sequence
+/// of runs at that level or higher is reversed.
+fn visual_order(levels: &[Level]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..levels.len()).collect();
+    let (Some(min), Some(max)) = (
+        levels.iter().map(|level| level.number()).min(),
+        levels.iter().map(|level| level.number()).max(),
+    ) else {
+        return order;
+    };
+    let lowest_odd = min | 1;
+    for level in (lowest_odd..=max).rev() {
+        let mut index = 0;
+        while index < order.len() {
+            if levels[order[index]].number() < level {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while index < order.len() && levels[order[index]].number() >= level {
+                index += 1;
+            }
+            order[start..index].reverse();
+        }
+    }
+    order
+}
+
+/// The byte indices in a laid out chunk where a cursor can go, from the start to the end of the
+/// chunk, with their x offset from the left of the chunk. In right-to-left chunks the offsets go
+/// from right to left. A ligature is one cluster, and is split evenly over its graphemes.
+fn chunk_carets(
+    start: usize,
+    string: &str,
+    glyph_infos: &[font_atlas::GlyphInfo],
+    is_rtl: bool,
+    font_size: f64,
+    letter_spacing: f64,
+    font_atlas: &mut CxFontAtlas,
+) -> Vec<(usize, f64)> {
+    // the x range of every cluster, glyphs come in visual order and clusters in logical order
+    let mut clusters: Vec<(usize, f64, f64)> = Vec::new();
+    let mut x = 0.0;
+    let advances = compute_glyph_advances(glyph_infos, font_size, letter_spacing, font_atlas);
+    for (glyph_info, width) in glyph_infos.iter().zip(advances) {
+        let cluster = glyph_info.cluster.min(string.len());
+        match clusters.iter_mut().find(|(other, _, _)| *other == cluster) {
+            Some((_, left, right)) => {
+                *left = left.min(x);
+                *right = right.max(x + width);
+            }
+            None => clusters.push((cluster, x, x + width)),
+        }
+        x += width;
+    }
+    clusters.sort_by_key(|(cluster, _, _)| *cluster);
+
+    let mut carets = Vec::new();
+    for (index, &(cluster, left, right)) in clusters.iter().enumerate() {
+        let cluster_end = clusters.get(index + 1).map_or(string.len(), |(next, _, _)| *next);
+        let cluster_string = &string[cluster..cluster_end];
+        let step = (right - left) / cluster_string.graphemes(true).count().max(1) as f64;
+        for (index, (grapheme_start, _)) in cluster_string.grapheme_indices(true).enumerate() {
+            let offset = step * index as f64;
+            carets.push((start + cluster + grapheme_start, if is_rtl { right - offset } else { left + offset }));
+        }
+    }
+    if carets.first().is_none_or(|(index, _)| *index > start) {
+        carets.insert(0, (start, if is_rtl { x } else { 0.0 }));
+    }
+    carets.push((start + string.len(), if is_rtl { 0.0 } else { x }));
+    carets
+}
+
+/// A line of laid out text with the carets of its chunks, in the order they are shown in.
+#[derive(Default)]
+struct CaretLine {
+    y: f64,
+    start: usize,
+    end: usize,
+    is_soft_end: bool,
+    chunks: Vec<CaretChunk>,
+}
+
+struct CaretChunk {
+    start: usize,
+    end: usize,
+    carets: Vec<(usize, f64)>,
+}
+
+impl CaretLine {
+    fn push(&mut self, y: f64, start: usize, end: usize, carets: Vec<(usize, f64)>) {
+        if self.chunks.is_empty() {
+            self.y = y;
+            self.start = start;
+            self.end = end;
+        } else {
+            self.start = self.start.min(start);
+            self.end = self.end.max(end);
+        }
+        self.chunks.push(CaretChunk { start, end, carets });
+    }
+
+    /// The x of the caret at the given index. Where two chunks meet, the caret can be at the
+    /// end of one or the start of the other, which in mixed direction text are in different
+    /// places: `Before` picks the chunk that ends at the index, `After` the one that starts there.
+    fn x(&self, index: usize, affinity: Affinity) -> f64 {
+        let chunk = self.chunks.iter().find(|chunk| match affinity {
+            Affinity::Before => chunk.start < index && index <= chunk.end,
+            Affinity::After => chunk.start <= index && index < chunk.end,
+        }).or_else(|| {
+            self.chunks.iter().find(|chunk| chunk.start <= index && index <= chunk.end)
+        }).or(self.chunks.last());
+        chunk.and_then(|chunk| {
+            chunk.carets.iter().rev().find(|(caret, _)| *caret <= index).or(chunk.carets.first())
+        }).map_or(0.0, |(_, x)| *x)
+    }
+}
+
 enum LayoutEvent<'a> {
     Chunk {
         width: f64,
         string: &'a str,
         glyph_infos: &'a [font_atlas::GlyphInfo],
+        is_rtl: bool,
     },
     Newline {
         is_soft: bool
@@ -1406,12 +1831,11 @@ fn compute_line_height(
     font_size: f64,
     font_atlas: &CxFontAtlas,
 ) -> f64 {
-    font_ids.iter().map(|&font_id| {
-        let font = font_atlas.fonts[font_id].as_ref().unwrap();
+    font_ids.iter().filter_map(|&font_id| font_atlas.fonts[font_id].as_ref()).map(|font| {
         let units_per_em = font.ttf_font.units_per_em;
         let line_height = font.ttf_font.ascender - font.ttf_font.descender;
         units_to_lpxs(line_height, units_per_em, font_size)
-    }).reduce(|a, b| a.max(b)).unwrap()
+    }).reduce(|a, b| a.max(b)).unwrap_or(0.0)
 }
 
 fn compute_glyph_width(
@@ -1429,6 +1853,21 @@ fn compute_glyph_width(
     units_to_lpxs(glyph_width, units_per_em, font_size)
 }
 
+/// The advance of every glyph: its advance width, and the letter spacing after the last glyph of
+/// every cluster.
+fn compute_glyph_advances(
+    glyph_infos: &[font_atlas::GlyphInfo],
+    font_size: f64,
+    letter_spacing: f64,
+    font_atlas: &mut CxFontAtlas,
+) -> Vec<f64> {
+    glyph_infos.iter().enumerate().map(|(index, glyph_info)| {
+        let width = compute_glyph_width(glyph_info.font_id, glyph_info.glyph_id, font_size, font_atlas);
+        let ends_cluster = glyph_infos.get(index + 1).is_none_or(|next| next.cluster != glyph_info.cluster);
+        if ends_cluster {width + letter_spacing} else {width}
+    }).collect()
+}
+
 fn units_to_lpxs(units: f64, units_per_em: f64, font_size: f64) -> f64 {
     const LPXS_PER_IN: f64 = 96.0;
     const PTS_PER_IN: f64 = 72.0;
@@ -1442,6 +1881,7 @@ fn units_to_lpxs(units: f64, units_per_em: f64, font_size: f64) -> f64 {
 
 fn shape<'a>(
     is_secret: bool,
+    is_rtl: bool,
     string: &str,
     font_ids: &[usize],
     font_atlas: &mut CxFontAtlas,
@@ -1449,9 +1889,136 @@ fn shape<'a>(
 ) -> Cow<'a, [font_atlas::GlyphInfo]> {
     shape_cache.shape(
         is_secret,
-        Direction::LeftToRight,
+        if is_rtl { Direction::RightToLeft } else { Direction::LeftToRight },
         string,
         font_ids,
         font_atlas
     )
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use {super::*, std::rc::Rc};
+
+    const FONT_SIZE: f64 = 10.0;
+
+    // lays out text in a monospaced font, as the strings of the chunks of every line and where
+    // each line ended
+    struct Layout {
+        // owns the textures of the atlas
+        _cx: Cx,
+        font_atlas: CxFontAtlas,
+        shape_cache: CxShapeCache,
+    }
+
+    impl Layout {
+        fn new() -> Self {
+            let mut cx = Cx::new(Box::new(|_, _| {}));
+            let texture_sdf = Texture::new(&mut cx);
+            let texture_color = Texture::new(&mut cx);
+            let mut font_atlas = CxFontAtlas::new(texture_sdf, texture_color, cx.os_type());
+            let font = include_bytes!("../../../widgets/resources/LiberationMono-Regular.ttf");
+            font_atlas.fonts.push(Some(font_atlas::CxFont::load_from_ttf_bytes(Rc::new(font.to_vec())).unwrap()));
+            Self {_cx: cx, font_atlas, shape_cache: CxShapeCache::new()}
+        }
+
+        // the advance of every glyph of the font
+        fn advance(&mut self) -> f64 {
+            let mut width = 0.0;
+            self.layout("m", None, None, |_, event| if let LayoutEvent::Chunk {width: w, ..} = event {width = w});
+            width
+        }
+
+        fn layout(&mut self, text: &str, wrap_width: Option<f64>, hyphenator: Option<&Hyphenator>, mut f: impl FnMut(DVec2, LayoutEvent)) {
+            layout_text(
+                &mut DVec2::default(),
+                false,
+                text,
+                &[0],
+                FONT_SIZE,
+                0.0,
+                FONT_SIZE,
+                wrap_width,
+                hyphenator,
+                &mut self.font_atlas,
+                &mut self.shape_cache,
+                |position, _, event, _| {
+                    f(position, event);
+                    false
+                },
+            );
+        }
+
+        fn lines(&mut self, text: &str, wrap_width: f64, hyphenator: Option<&Hyphenator>) -> Vec<(Vec<String>, f64)> {
+            let mut lines = vec![(Vec::new(), 0.0)];
+            self.layout(text, Some(wrap_width), hyphenator, |position, event| match event {
+                // a hyphen shown where a word is broken is a chunk of no text
+                LayoutEvent::Chunk {string, glyph_infos, ..} if string.is_empty() && !glyph_infos.is_empty() => {
+                    lines.last_mut().unwrap().0.push("-".to_string())
+                }
+                LayoutEvent::Chunk {string, ..} => lines.last_mut().unwrap().0.push(string.to_string()),
+                LayoutEvent::Newline {..} => {
+                    lines.last_mut().unwrap().1 = position.x;
+                    lines.push((Vec::new(), 0.0));
+                }
+            });
+            lines
+        }
+    }
+
+    fn strings(line: &[&str]) -> Vec<String> {
+        line.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn soft_hyphens_only_show_where_the_line_breaks() {
+        let mut layout = Layout::new();
+        let a = layout.advance();
+        let lines = layout.lines("abc\u{AD}def", 5.5 * a, None);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].0, strings(&["abc\u{AD}", "-"]));
+        assert_eq!(lines[1].0, strings(&["def"]));
+
+        let lines = layout.lines("abc\u{AD}def", 10.0 * a, None);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].0, strings(&["abc\u{AD}", "def"]));
+
+        // the hyphen has to fit as well, so the word before it goes on the next line
+        let lines = layout.lines("ab abc\u{AD}def", 6.5 * a, None);
+        assert_eq!(lines.iter().map(|line| line.0.clone()).collect::<Vec<_>>(), [
+            strings(&["ab "]),
+            strings(&["abc\u{AD}", "-"]),
+            strings(&["def"]),
+        ]);
+    }
+
+    #[test]
+    fn trailing_spaces_hang_past_the_wrap_width() {
+        let mut layout = Layout::new();
+        let a = layout.advance();
+        // the spaces don't count for whether the word fits, so it isn't broken up
+        let lines = layout.lines("aaa   bbb", 3.5 * a, None);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].0, strings(&["aaa   "]));
+        assert_eq!(lines[1].0, strings(&["bbb"]));
+        // the line still ends after its spaces, past the wrap width
+        assert!((lines[0].1 - 6.0 * a).abs() < 1e-6, "{} {}", lines[0].1, a);
+    }
+
+    #[test]
+    fn words_that_dont_fit_are_hyphenated() {
+        let mut layout = Layout::new();
+        let a = layout.advance();
+        let hyphenator = Hyphenator::parse(".hy3ph he2n hena4 hen5at 1na n2at 1tio 2io");
+        let lines = layout.lines("a hyphenation", 9.5 * a, Some(&hyphenator));
+        assert_eq!(lines.iter().map(|line| line.0.clone()).collect::<Vec<_>>(), [
+            strings(&["a ", "hyphen", "-"]),
+            strings(&["ation"]),
+        ]);
+        let lines = layout.lines("a hyphenation", 5.5 * a, Some(&hyphenator));
+        assert_eq!(lines.iter().map(|line| line.0.clone()).collect::<Vec<_>>(), [
+            strings(&["a ", "hy", "-"]),
+            strings(&["phen", "-"]),
+            strings(&["ation"]),
+        ]);
+    }
+}
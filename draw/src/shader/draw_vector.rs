@@ -0,0 +1,482 @@
+use {
+    std::{collections::HashSet, f64::consts::PI},
+    crate::{
+        makepad_platform::*,
+        color_glyph::invert,
+        cx_2d::Cx2d,
+        geometry::GeometryQuad2D,
+        stroke::{flatten, stroke_polygons, tolerance, StrokeStyle},
+        makepad_vector::{
+            geometry::{AffineTransformation, Point, Transform, Trapezoid},
+            path::{LinePathCommand, PathCommand},
+            trapezoidator::{FillRule, Trapezoidator},
+        },
+    },
+};
+
+live_design!{
+    DrawVector = {{DrawVector}} {
+        texture ramp: texture2d
+
+        varying v_p0: vec2;
+        varying v_p1: vec2;
+        varying v_p2: vec2;
+        varying v_p3: vec2;
+        varying v_pixel: vec2;
+
+        fn intersect_line_segment_with_vertical_line(p0: vec2, p1: vec2, x: float) -> vec2 {
+            return vec2(
+                x,
+                mix(p0.y, p1.y, (x - p0.x) / (p1.x - p0.x))
+            );
+        }
+
+        fn intersect_line_segment_with_horizontal_line(p0: vec2, p1: vec2, y: float) -> vec2 {
+            return vec2(
+                mix(p0.x, p1.x, (y - p0.y) / (p1.y - p0.y)),
+                y
+            );
+        }
+
+        fn compute_clamped_right_trapezoid_area(p0: vec2, p1: vec2, p_min: vec2, p_max: vec2) -> float {
+            let x0 = clamp(p0.x, p_min.x, p_max.x);
+            let x1 = clamp(p1.x, p_min.x, p_max.x);
+            if (p0.x < p_min.x && p_min.x < p1.x) {
+                p0 = intersect_line_segment_with_vertical_line(p0, p1, p_min.x);
+            }
+            if (p0.x < p_max.x && p_max.x < p1.x) {
+                p1 = intersect_line_segment_with_vertical_line(p0, p1, p_max.x);
+            }
+            if (p0.y < p_min.y && p_min.y < p1.y) {
+                p0 = intersect_line_segment_with_horizontal_line(p0, p1, p_min.y);
+            }
+            if (p1.y < p_min.y && p_min.y < p0.y) {
+                p1 = intersect_line_segment_with_horizontal_line(p1, p0, p_min.y);
+            }
+            if (p0.y < p_max.y && p_max.y < p1.y) {
+                p1 = intersect_line_segment_with_horizontal_line(p0, p1, p_max.y);
+            }
+            if (p1.y < p_max.y && p_max.y < p0.y) {
+                p0 = intersect_line_segment_with_horizontal_line(p1, p0, p_max.y);
+            }
+            p0 = clamp(p0, p_min, p_max);
+            p1 = clamp(p1, p_min, p_max);
+            let h0 = p_max.y - p0.y;
+            let h1 = p_max.y - p1.y;
+            let a0 = (p0.x - x0) * h0;
+            let a1 = (p1.x - p0.x) * (h0 + h1) * 0.5;
+            let a2 = (x1 - p1.x) * h1;
+            return a0 + a1 + a2;
+        }
+
+        fn compute_clamped_trapezoid_area(self, p_min: vec2, p_max: vec2) -> float {
+            let a0 = compute_clamped_right_trapezoid_area(self.v_p0, self.v_p1, p_min, p_max);
+            let a1 = compute_clamped_right_trapezoid_area(self.v_p2, self.v_p3, p_min, p_max);
+            return a0 - a1;
+        }
+
+        fn paint_color(self) -> vec4 {
+            if self.a_paint.x < 0.5 {
+                return self.a_color;
+            }
+            let g = vec2(dot(self.a_gradient.xy, self.v_pixel), dot(self.a_gradient.zw, self.v_pixel)) + self.a_paint.zw;
+            let t = g.x;
+            if self.a_paint.x > 1.5 {
+                t = length(g);
+            }
+            return sample2d(self.ramp, vec2((clamp(t, 0.0, 1.0) * 255.0 + 0.5) / 256.0, self.a_paint.y));
+        }
+
+        fn pixel(self) -> vec4 {
+            // columns shared with a neighbouring trapezoid are painted by only one of the two
+            if self.v_pixel.x < self.a_own.x || self.v_pixel.x >= self.a_own.y {
+                return vec4(0.0, 0.0, 0.0, 0.0);
+            }
+            let area = self.compute_clamped_trapezoid_area(self.v_pixel - 0.5, self.v_pixel + 0.5);
+            return self.paint_color() * clamp(area, 0.0, 1.0);
+        }
+
+        fn vertex(self) -> vec4 {
+            let pos_min = vec2(self.a_xs.x, min(self.a_ys.x, self.a_ys.y));
+            let pos_max = vec2(self.a_xs.y, max(self.a_ys.z, self.a_ys.w));
+            let origin = self.rect_pos * self.dpi_factor;
+            let clipped = clamp(
+                mix(pos_min - 1.0, pos_max + 1.0, self.geom_pos),
+                self.draw_clip.xy * self.dpi_factor - origin,
+                self.draw_clip.zw * self.dpi_factor - origin
+            );
+
+            // set the varyings
+            self.v_p0 = vec2(self.a_xs.x, self.a_ys.x);
+            self.v_p1 = vec2(self.a_xs.y, self.a_ys.y);
+            self.v_p2 = vec2(self.a_xs.x, self.a_ys.z);
+            self.v_p3 = vec2(self.a_xs.y, self.a_ys.w);
+            self.v_pixel = clipped;
+            let pos = (clipped + origin) / self.dpi_factor;
+            return self.camera_projection * (self.camera_view * (self.view_transform * vec4(
+                pos.x,
+                pos.y,
+                self.draw_depth + self.draw_zbias,
+                1.
+            )));
+        }
+    }
+}
+
+/// The number of gradients a `DrawVector` can paint in one redraw.
+pub const VECTOR_RAMP_ROWS: usize = 256;
+const VECTOR_RAMP_WIDTH: usize = 256;
+
+/// How the inside of a filled or stroked path is painted. Colors are not premultiplied, and
+/// gradient geometry is in path coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VectorPaint {
+    Color(Vec4),
+    /// Runs from the first stop at `start` to the last one at `end`, and pads beyond them.
+    LinearGradient {start: DVec2, end: DVec2, stops: Vec<(f32, Vec4)>},
+    /// Runs from the first stop at `center` to the last one at `radius` from it.
+    RadialGradient {center: DVec2, radius: f64, stops: Vec<(f32, Vec4)>},
+}
+
+impl From<Vec4> for VectorPaint {
+    fn from(color: Vec4) -> Self {
+        VectorPaint::Color(color)
+    }
+}
+
+/// A path of lines, curves and arcs for `DrawVector` to fill or stroke.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VectorPath {
+    commands: Vec<PathCommand>,
+}
+
+fn point(p: DVec2) -> Point {
+    Point::new(p.x, p.y)
+}
+
+impl VectorPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new contour at `p`.
+    pub fn move_to(&mut self, p: DVec2) -> &mut Self {
+        self.commands.push(PathCommand::MoveTo(point(p)));
+        self
+    }
+
+    pub fn line_to(&mut self, p: DVec2) -> &mut Self {
+        self.commands.push(PathCommand::LineTo(point(p)));
+        self
+    }
+
+    pub fn quad_to(&mut self, control: DVec2, p: DVec2) -> &mut Self {
+        self.commands.push(PathCommand::QuadraticTo(point(control), point(p)));
+        self
+    }
+
+    pub fn cubic_to(&mut self, control1: DVec2, control2: DVec2, p: DVec2) -> &mut Self {
+        self.commands.push(PathCommand::CubicTo(point(control1), point(control2), point(p)));
+        self
+    }
+
+    /// An elliptical arc to `p` the way svg paths describe them: which of the four arcs with
+    /// these radii is taken is picked by `large_arc` and `sweep`.
+    pub fn arc_to(&mut self, radii: DVec2, x_axis_rotation: f64, large_arc: bool, sweep: bool, p: DVec2) -> &mut Self {
+        self.commands.push(PathCommand::ArcTo(point(p), point(radii), x_axis_rotation, large_arc, sweep));
+        self
+    }
+
+    /// A circular arc around `center` from `start_angle` to `end_angle` in radians, clockwise on
+    /// screen when the end angle is the larger one. The contour is continued to the start of the
+    /// arc with a line, or started there when the path is empty.
+    pub fn arc(&mut self, center: DVec2, radius: f64, start_angle: f64, end_angle: f64) -> &mut Self {
+        let at = | angle: f64 | center + dvec2(angle.cos(), angle.sin()) * radius;
+        if self.commands.is_empty() {
+            self.move_to(at(start_angle));
+        }
+        else {
+            self.line_to(at(start_angle));
+        }
+        let sweep = end_angle > start_angle;
+        let span = (end_angle - start_angle).abs().min(2.0 * PI);
+        let pieces = (span / (PI / 2.0)).ceil().max(1.0) as usize;
+        let step = span / pieces as f64 * if sweep {1.0} else {-1.0};
+        for i in 1..=pieces {
+            self.arc_to(dvec2(radius, radius), 0.0, false, sweep, at(start_angle + step * i as f64));
+        }
+        self
+    }
+
+    /// Closes the current contour with a line back to where it started.
+    pub fn close(&mut self) -> &mut Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    pub fn rect(&mut self, rect: Rect) -> &mut Self {
+        self.move_to(rect.pos)
+            .line_to(rect.pos + dvec2(rect.size.x, 0.0))
+            .line_to(rect.pos + rect.size)
+            .line_to(rect.pos + dvec2(0.0, rect.size.y))
+            .close()
+    }
+
+    pub fn circle(&mut self, center: DVec2, radius: f64) -> &mut Self {
+        let start = center + dvec2(radius, 0.0);
+        let radii = dvec2(radius, radius);
+        self.move_to(start)
+            .arc_to(radii, 0.0, false, true, center - dvec2(radius, 0.0))
+            .arc_to(radii, 0.0, false, true, start)
+            .close()
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+}
+
+/// Fills and strokes `VectorPath`s with antialiased coverage. Paths are flattened, cut into
+/// trapezoids by the `Trapezoidator` and drawn as one instance per trapezoid, gradients read
+/// their colors from a ramp texture with a row per gradient.
+#[derive(Live, LiveRegister)]
+#[repr(C)]
+pub struct DrawVector {
+    #[rust] pub trapezoidator: Trapezoidator,
+    #[rust(AffineTransformation::identity())] pub transform: AffineTransformation,
+    #[rust] pub ramp: Option<Texture>,
+    #[rust] ramp_rows: Vec<Vec<(f32, Vec4)>>,
+    #[rust] ramp_redraw_id: u64,
+    #[live] pub geometry: GeometryQuad2D,
+    #[deref] pub draw_vars: DrawVars,
+    #[calc] pub rect_pos: Vec2,
+    #[calc] pub draw_clip: Vec4,
+    #[live(1.0)] pub draw_depth: f32,
+    #[calc] pub a_xs: Vec2,
+    #[calc] pub a_ys: Vec4,
+    #[calc] pub a_own: Vec2,
+    #[calc] pub a_paint: Vec4,
+    #[calc] pub a_gradient: Vec4,
+    #[calc] pub a_color: Vec4,
+}
+
+impl LiveHook for DrawVector{
+    fn before_apply(&mut self, cx: &mut Cx, apply: &mut Apply, index: usize, nodes: &[LiveNode]){
+        self.draw_vars.before_apply_init_shader(cx, apply, index, nodes, &self.geometry);
+    }
+    fn after_apply(&mut self, cx: &mut Cx, apply: &mut Apply, index: usize, nodes: &[LiveNode]) {
+        self.draw_vars.after_apply_update_self(cx, apply, index, nodes, &self.geometry);
+    }
+}
+
+impl DrawVector {
+    /// Maps path coordinates to logical pixels for the following fills and strokes.
+    pub fn set_transform(&mut self, transform: AffineTransformation) {
+        self.transform = transform;
+    }
+
+    /// Fills the inside of the path, as decided by `fill_rule`. Open contours are closed.
+    pub fn fill(&mut self, cx: &mut Cx2d, path: &VectorPath, paint: impl Into<VectorPaint>, fill_rule: FillRule) {
+        let transform = self.device_transform(cx);
+        let contours = flatten(path.commands(), tolerance(transform));
+        let polygons: Vec<Vec<Point>> = contours.into_iter().map( | (points, _) | points).collect();
+        self.draw_polygons(cx, &polygons, transform, &paint.into(), fill_rule);
+    }
+
+    /// Strokes the path. The width and dashes are in path coordinates, so they scale with the
+    /// transform.
+    pub fn stroke(&mut self, cx: &mut Cx2d, path: &VectorPath, paint: impl Into<VectorPaint>, stroke: &StrokeStyle) {
+        let transform = self.device_transform(cx);
+        let tolerance = tolerance(transform);
+        let polygons = stroke_polygons(&flatten(path.commands(), tolerance), stroke, tolerance);
+        self.draw_polygons(cx, &polygons, transform, &paint.into(), FillRule::NonZero);
+    }
+
+    fn device_transform(&self, cx: &Cx2d) -> AffineTransformation {
+        self.transform.uniform_scale(cx.current_dpi_factor())
+    }
+
+    fn draw_polygons(&mut self, cx: &mut Cx2d, polygons: &[Vec<Point>], transform: AffineTransformation, paint: &VectorPaint, fill_rule: FillRule) {
+        let commands = polygons.iter().filter( | polygon | polygon.len() > 2).flat_map( | polygon | {
+            std::iter::once(LinePathCommand::MoveTo(polygon[0].transform(&transform)))
+                .chain(polygon[1..].iter().map( | p | LinePathCommand::LineTo(p.transform(&transform))))
+                .chain(std::iter::once(LinePathCommand::Close))
+        });
+        let mut trapezoids: Vec<Trapezoid> = Vec::new();
+        self.trapezoidator.set_fill_rule(fill_rule);
+        if let Some(trapezoidate) = self.trapezoidator.trapezoidate(commands) {
+            crate::makepad_vector::internal_iter::InternalIterator::for_each(trapezoidate, &mut | trapezoid | {
+                // regions inside with different windings come out stacked, join them so no
+                // edge is antialiased against the other side of itself
+                match trapezoids.last_mut() {
+                    Some(last) if last.xs == trapezoid.xs && last.ys[2..] == trapezoid.ys[..2] => {
+                        last.ys[2] = trapezoid.ys[2];
+                        last.ys[3] = trapezoid.ys[3];
+                    }
+                    _ => trapezoids.push(trapezoid)
+                }
+                true
+            });
+        }
+        if trapezoids.is_empty() {
+            return
+        }
+
+        self.set_paint(cx, paint, transform);
+        let ramp = self.ramp_texture(cx);
+        self.draw_vars.texture_slots[0] = Some(ramp);
+        let Some(mut mi) = cx.begin_many_aligned_instances(&self.draw_vars) else {
+            return
+        };
+
+        // where two trapezoids share a vertical side, the pixel column across it is painted by
+        // the one owning its center, with its edges extended over the other side
+        let key = | x: f32, y0: f32, y1: f32 | ((x * 256.0) as i64, (y0 * 256.0) as i64, (y1 * 256.0) as i64);
+        let lefts: HashSet<_> = trapezoids.iter().map( | t | key(t.xs[0], t.ys[0], t.ys[2])).collect();
+        let rights: HashSet<_> = trapezoids.iter().map( | t | key(t.xs[1], t.ys[1], t.ys[3])).collect();
+        self.rect_pos = vec2(0.0, 0.0);
+        self.draw_clip = vec4(-1e6, -1e6, 1e6, 1e6);
+        for t in &trapezoids {
+            let [x0, x1] = t.xs;
+            let width = x1 - x0;
+            let slope = | y0: f32, y1: f32 | if width < 1e-3 {0.0} else {(y1 - y0) / width};
+            let (lower, upper) = (slope(t.ys[0], t.ys[1]), slope(t.ys[2], t.ys[3]));
+            let mut xs = t.xs;
+            let mut ys = t.ys;
+            let mut own = vec2(x0 - 2.0, x1 + 2.0);
+            if rights.contains(&key(x0, t.ys[0], t.ys[2])) {
+                xs[0] -= 1.0;
+                ys[0] -= lower;
+                ys[2] -= upper;
+                own.x = x0;
+            }
+            if lefts.contains(&key(x1, t.ys[1], t.ys[3])) {
+                xs[1] += 1.0;
+                ys[1] += lower;
+                ys[3] += upper;
+                own.y = x1;
+            }
+            self.a_xs = vec2(xs[0], xs[1]);
+            self.a_ys = vec4(ys[0], ys[1], ys[2], ys[3]);
+            self.a_own = own;
+            mi.instances.extend_from_slice(self.draw_vars.as_slice());
+        }
+        let new_area = cx.end_many_instances(mi);
+        self.draw_vars.area = cx.update_area_refs(self.draw_vars.area, new_area);
+    }
+
+    fn set_paint(&mut self, cx: &mut Cx2d, paint: &VectorPaint, transform: AffineTransformation) {
+        let (stops, gradient) = match paint {
+            VectorPaint::Color(color) => {
+                self.a_paint = vec4(0.0, 0.0, 0.0, 0.0);
+                self.a_color = premultiply(*color);
+                return
+            }
+            VectorPaint::LinearGradient {start, end, stops} => {
+                // t is the projection onto the gradient line, in device pixels
+                let d = *end - *start;
+                let len2 = d.x * d.x + d.y * d.y;
+                let d = if len2 > 0.0 {d / len2} else {dvec2(0.0, 0.0)};
+                (stops, (1.0, [d.x, d.y, 0.0, 0.0], -(start.x * d.x + start.y * d.y), 0.0))
+            }
+            VectorPaint::RadialGradient {center, radius, stops} => {
+                // t is the distance to the center over the radius, in device pixels
+                let r = if *radius > 0.0 {1.0 / radius} else {0.0};
+                (stops, (2.0, [r, 0.0, 0.0, r], -center.x * r, -center.y * r))
+            }
+        };
+        let Some(row) = self.ramp_row(cx, stops) else {
+            self.a_paint = vec4(0.0, 0.0, 0.0, 0.0);
+            self.a_color = premultiply(stops.first().map( | s | s.1).unwrap_or_default());
+            return
+        };
+        // the gradient maps path coordinates, so map back from device pixels first
+        let (kind, [a, b, c, d], ox, oy) = gradient;
+        let Some(inverse) = invert(transform) else {
+            self.a_paint = vec4(0.0, 0.0, 0.0, 0.0);
+            self.a_color = vec4(0.0, 0.0, 0.0, 0.0);
+            return
+        };
+        let (m, z) = (inverse.xy, inverse.z);
+        let mat = [
+            a * m.x.x + b * m.x.y,
+            a * m.y.x + b * m.y.y,
+            c * m.x.x + d * m.x.y,
+            c * m.y.x + d * m.y.y,
+        ];
+        let offset = dvec2(a * z.x + b * z.y + ox, c * z.x + d * z.y + oy);
+        self.a_gradient = vec4(mat[0] as f32, mat[1] as f32, mat[2] as f32, mat[3] as f32);
+        self.a_paint = vec4(kind, (row as f32 + 0.5) / VECTOR_RAMP_ROWS as f32, offset.x as f32, offset.y as f32);
+    }
+
+    fn ramp_texture(&mut self, cx: &mut Cx2d) -> Texture {
+        self.ramp.get_or_insert_with( || Texture::new_with_format(cx, TextureFormat::VecBGRAu8_32 {
+            width: VECTOR_RAMP_WIDTH,
+            height: VECTOR_RAMP_ROWS,
+            data: Some(vec![0; VECTOR_RAMP_WIDTH * VECTOR_RAMP_ROWS]),
+            updated: TextureUpdated::Empty,
+        })).clone()
+    }
+
+    // The ramp texture row holding the colors of these stops, written when it is new
+    fn ramp_row(&mut self, cx: &mut Cx2d, stops: &[(f32, Vec4)]) -> Option<usize> {
+        if self.ramp_redraw_id != cx.redraw_id() {
+            self.ramp_redraw_id = cx.redraw_id();
+            self.ramp_rows.clear();
+        }
+        if let Some(row) = self.ramp_rows.iter().position( | row | row == stops) {
+            return Some(row)
+        }
+        if self.ramp_rows.len() >= VECTOR_RAMP_ROWS {
+            error!("DrawVector: more than {} gradients in one redraw", VECTOR_RAMP_ROWS);
+            return None
+        }
+        let row = self.ramp_rows.len();
+        self.ramp_rows.push(stops.to_vec());
+
+        let ramp = self.ramp_texture(cx);
+        let mut stops = stops.to_vec();
+        stops.sort_by( | a, b | a.0.total_cmp(&b.0));
+        let mut data = ramp.take_vec_u32(cx);
+        if data.is_empty() {
+            data = vec![0; VECTOR_RAMP_WIDTH * VECTOR_RAMP_ROWS];
+        }
+        for (i, pixel) in data[row * VECTOR_RAMP_WIDTH..][..VECTOR_RAMP_WIDTH].iter_mut().enumerate() {
+            let t = i as f32 / (VECTOR_RAMP_WIDTH - 1) as f32;
+            *pixel = pack(ramp_color(&stops, t));
+        }
+        ramp.put_back_vec_u32(cx, data, Some(RectUsize::new(
+            PointUsize::new(0, row),
+            SizeUsize::new(VECTOR_RAMP_WIDTH, 1),
+        )));
+        Some(row)
+    }
+}
+
+fn premultiply(color: Vec4) -> Vec4 {
+    vec4(color.x * color.w, color.y * color.w, color.z * color.w, color.w)
+}
+
+// The premultiplied color at `t` between sorted stops, padded beyond the first and the last
+fn ramp_color(stops: &[(f32, Vec4)], t: f32) -> Vec4 {
+    let Some(first) = stops.first() else {
+        return vec4(0.0, 0.0, 0.0, 0.0)
+    };
+    if t <= first.0 {
+        return premultiply(first.1)
+    }
+    for pair in stops.windows(2) {
+        let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+        if t <= t1 {
+            let f = if t1 > t0 {(t - t0) / (t1 - t0)} else {1.0};
+            let (c0, c1) = (premultiply(c0), premultiply(c1));
+            return c0 + (c1 - c0) * f
+        }
+    }
+    premultiply(stops.last().unwrap().1)
+}
+
+fn pack(color: Vec4) -> u32 {
+    let byte = | v: f32 | (v.clamp(0.0, 1.0) * 255.0).round() as u32;
+    byte(color.w) << 24 | byte(color.x) << 16 | byte(color.y) << 8 | byte(color.z)
+}
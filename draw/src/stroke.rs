@@ -0,0 +1,299 @@
+//! Turning polylines into the polygons that cover their stroke, with joins, caps and dashes.
+//! The svg renderer and the vector canvas both stroke this way.
+
+use {
+    std::f64::consts::PI,
+    crate::makepad_vector::{
+        geometry::{AffineTransformation, Point, Vector},
+        path::{LinePathCommand, PathCommand, PathIterator},
+    },
+};
+
+/// The shape of the outside corner where two segments of a stroke meet.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// The shape of the ends of an open stroke.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+/// How a path is stroked. Miters longer than `miter_limit` times the width become bevels.
+/// `dash` alternates the lengths of dashes and gaps, starting `dash_offset` into the pattern,
+/// an empty one draws a solid line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f64,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub miter_limit: f64,
+    pub dash: Vec<f64>,
+    pub dash_offset: f64,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f64) -> Self {
+        Self {width, ..Default::default()}
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f64) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    pub fn with_dash(mut self, dash: Vec<f64>, dash_offset: f64) -> Self {
+        self.dash = dash;
+        self.dash_offset = dash_offset;
+        self
+    }
+}
+
+/// The flattening tolerance in path units that keeps curves within a fifth of a pixel once
+/// `transform` is applied.
+pub fn tolerance(transform: AffineTransformation) -> f64 {
+    let det = transform.xy.x.x * transform.xy.y.y - transform.xy.y.x * transform.xy.x.y;
+    0.2 / det.abs().sqrt().max(1e-9)
+}
+
+/// The contours of a path as polylines, with whether each one was closed. Curves are split into
+/// lines that are at most `tolerance` off.
+pub fn flatten(commands: &[PathCommand], tolerance: f64) -> Vec<(Vec<Point>, bool)> {
+    let mut contours = Vec::new();
+    // curves need a point to start from
+    if !matches!(commands.first(), Some(PathCommand::MoveTo(_))) {
+        return contours
+    }
+    let mut current: Vec<Point> = Vec::new();
+    let mut start = Point::origin();
+    crate::makepad_vector::internal_iter::InternalIterator::for_each(commands.iter().copied().linearize(tolerance), &mut | command | {
+        match command {
+            LinePathCommand::MoveTo(p) => {
+                if current.len() > 1 {
+                    contours.push((std::mem::take(&mut current), false));
+                }
+                current = vec![p];
+                start = p;
+            }
+            LinePathCommand::LineTo(p) => {
+                // after a close the next contour starts where that one did
+                if current.is_empty() {
+                    current.push(start);
+                }
+                current.push(p);
+            }
+            LinePathCommand::Close => {
+                if !current.is_empty() {
+                    contours.push((std::mem::take(&mut current), true));
+                }
+            }
+        }
+        true
+    });
+    if current.len() > 1 {
+        contours.push((current, false));
+    }
+    contours
+}
+
+/// Polygons whose nonzero union is the stroke of the contours, in the same space.
+pub fn stroke_polygons(contours: &[(Vec<Point>, bool)], stroke: &StrokeStyle, tolerance: f64) -> Vec<Vec<Point>> {
+    let half = stroke.width / 2.0;
+    let mut polygons = Vec::new();
+    let dashed = dash(contours, &stroke.dash, stroke.dash_offset);
+    for (points, closed) in dashed.as_deref().unwrap_or(contours) {
+        let mut points = points.clone();
+        points.dedup();
+        if *closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 2 {
+            // a lone point only shows with round caps
+            if let (Some(&p), LineCap::Round) = (points.first(), stroke.cap) {
+                polygons.push(circle(p, half, tolerance));
+            }
+            continue;
+        }
+        let count = points.len();
+        let segments: Vec<(Point, Point)> = (0..if *closed {count} else {count - 1})
+            .map( | i | (points[i], points[(i + 1) % count]))
+            .collect();
+        for &(a, b) in &segments {
+            let Some(d) = (b - a).normalize() else {
+                continue
+            };
+            let n = Vector::new(-d.y, d.x) * half;
+            polygons.push(vec![a + n, b + n, b - n, a - n]);
+        }
+        let joins = if *closed {segments.len()} else {segments.len() - 1};
+        for i in 0..joins {
+            let (a, v) = segments[i];
+            let (_, b) = segments[(i + 1) % segments.len()];
+            join(&mut polygons, a, v, b, stroke, tolerance);
+        }
+        if !*closed {
+            cap(&mut polygons, points[0], points[0] - points[1], stroke, tolerance);
+            cap(&mut polygons, points[count - 1], points[count - 1] - points[count - 2], stroke, tolerance);
+        }
+    }
+    // turned all the same way so overlapping pieces add up instead of cancelling out
+    for polygon in &mut polygons {
+        let area: f64 = (0..polygon.len()).map( | i | {
+            let (p, q) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            p.x * q.y - q.x * p.y
+        }).sum();
+        if area < 0.0 {
+            polygon.reverse();
+        }
+    }
+    polygons
+}
+
+// The piece filling the outside of the turn from `a` over `v` to `b`
+fn join(polygons: &mut Vec<Vec<Point>>, a: Point, v: Point, b: Point, stroke: &StrokeStyle, tolerance: f64) {
+    let (Some(d0), Some(d1)) = ((v - a).normalize(), (b - v).normalize()) else {
+        return
+    };
+    let cross = d0.cross(d1);
+    if cross.abs() < 1e-9 && d0.dot(d1) > 0.0 {
+        return
+    }
+    let half = stroke.width / 2.0;
+    if stroke.join == LineJoin::Round {
+        polygons.push(circle(v, half, tolerance));
+        return
+    }
+    let side = if cross > 0.0 {-half} else {half};
+    let n0 = Vector::new(-d0.y, d0.x) * side;
+    let n1 = Vector::new(-d1.y, d1.x) * side;
+    let cos = d0.dot(d1);
+    // the miter length relative to the stroke width
+    let ratio = (2.0 / (1.0 + cos).max(1e-12)).sqrt();
+    if stroke.join == LineJoin::Miter && ratio <= stroke.miter_limit {
+        polygons.push(vec![v, v + n0, v + (n0 + n1) * (1.0 / (1.0 + cos)), v + n1]);
+    }
+    else {
+        polygons.push(vec![v, v + n0, v + n1]);
+    }
+}
+
+// The cap at the end `p` of an open contour, `outward` points away from the contour
+fn cap(polygons: &mut Vec<Vec<Point>>, p: Point, outward: Vector, stroke: &StrokeStyle, tolerance: f64) {
+    let half = stroke.width / 2.0;
+    match stroke.cap {
+        LineCap::Butt => (),
+        LineCap::Round => polygons.push(circle(p, half, tolerance)),
+        LineCap::Square => {
+            let Some(d) = outward.normalize() else {
+                return
+            };
+            let (n, e) = (Vector::new(-d.y, d.x) * half, d * half);
+            polygons.push(vec![p + n, p + n + e, p - n + e, p - n]);
+        }
+    }
+}
+
+pub(crate) fn circle(center: Point, radius: f64, tolerance: f64) -> Vec<Point> {
+    let steps = (PI / (1.0 - (tolerance / radius).min(1.0)).acos()).ceil().clamp(8.0, 256.0) as usize;
+    (0..steps).map( | i | {
+        let (sin, cos) = (i as f64 / steps as f64 * 2.0 * PI).sin_cos();
+        Point::new(center.x + cos * radius, center.y + sin * radius)
+    }).collect()
+}
+
+
+// The dashes of the contours as open polylines, `None` if the pattern draws a solid line. A
+// pattern with an odd number of lengths repeats to make it even, the way svg does.
+fn dash(contours: &[(Vec<Point>, bool)], pattern: &[f64], offset: f64) -> Option<Vec<(Vec<Point>, bool)>> {
+    if pattern.iter().any( | length | *length < 0.0 || !length.is_finite()) {
+        return None
+    }
+    let pattern: Vec<f64> = if pattern.len() % 2 == 1 {
+        pattern.iter().chain(pattern).copied().collect()
+    }
+    else {
+        pattern.to_vec()
+    };
+    let period: f64 = pattern.iter().sum();
+    if period <= 0.0 {
+        return None
+    }
+    let mut dashes = Vec::new();
+    for (points, closed) in contours {
+        let mut points = points.clone();
+        if points.is_empty() {
+            continue
+        }
+        if *closed {
+            points.push(points[0]);
+        }
+        // where in the pattern the contour starts
+        let mut index = 0;
+        let mut left = pattern[0];
+        let mut phase = offset.rem_euclid(period);
+        while phase >= left {
+            phase -= left;
+            index = (index + 1) % pattern.len();
+            left = pattern[index];
+        }
+        left -= phase;
+        let mut current = if index % 2 == 0 {vec![points[0]]} else {Vec::new()};
+        for pair in points.windows(2) {
+            let (mut a, b) = (pair[0], pair[1]);
+            let mut length = (b - a).length();
+            while length > left {
+                let p = a + (b - a) * (left / length);
+                if index % 2 == 0 {
+                    current.push(p);
+                    dashes.push((std::mem::take(&mut current), false));
+                }
+                else {
+                    current = vec![p];
+                }
+                length -= left;
+                a = p;
+                index = (index + 1) % pattern.len();
+                left = pattern[index];
+            }
+            left -= length;
+            if index % 2 == 0 {
+                current.push(b);
+            }
+        }
+        if current.len() > 1 {
+            dashes.push((current, false));
+        }
+    }
+    Some(dashes)
+}
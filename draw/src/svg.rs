@@ -0,0 +1,859 @@
+//! Rendering of SVG documents for icons and illustrations. The supported subset is what design
+//! tools export: paths and the basic shapes, filled and stroked (with joins, caps and miter
+//! limits) in solid colors or linear and radial gradients, inside groups with transforms, opacity
+//! and clip paths, all in the coordinates of the `viewBox`. Text, filters, masks, patterns and
+//! `use` references are skipped.
+//!
+//! Shapes are flattened into trapezoids by the `Trapezoidator`, whose exact pixel coverage is
+//! computed the same way the trapezoid shader does it, and painted in software into premultiplied
+//! RGBA images.
+
+use {
+    std::collections::HashMap,
+    crate::{
+        color_glyph::{command_points, concat, conical_t, invert, linear, Canvas, Color, ColorLine},
+        icon_atlas::parse_svg_path,
+        makepad_platform::*,
+        stroke::{flatten, stroke_polygons, tolerance, LineCap, LineJoin, StrokeStyle},
+        makepad_vector::{
+            geometry::{AffineTransformation, LinearTransformation, Point, Transform, Trapezoid, Vector},
+            path::{LinePathCommand, PathCommand},
+            trapezoidator::{FillRule, Trapezoidator},
+        },
+    },
+    makepad_html::*,
+    makepad_rustybuzz::ttf_parser::colr::{CompositeMode, GradientExtend},
+};
+
+/// A parsed SVG document that can be painted at any scale.
+pub struct SvgDocument {
+    /// The `viewBox` of the root element, or its `width` and `height` when it has none.
+    pub view_box: Option<Rect>,
+    items: Vec<SvgItem>,
+}
+
+enum SvgItem {
+    // a group with an opacity or a clip path, which are applied when its `EndGroup` is reached
+    BeginGroup {opacity: f32, clip: Option<Vec<SvgClipShape>>},
+    EndGroup,
+    Shape(Box<SvgShape>),
+}
+
+// One of the shapes a clip path is the union of, `transform` maps it to user units
+#[derive(Clone)]
+struct SvgClipShape {
+    transform: AffineTransformation,
+    commands: Vec<PathCommand>,
+    rule: FillRule,
+}
+
+struct SvgShape {
+    transform: AffineTransformation,
+    commands: Vec<PathCommand>,
+    fill: Option<SvgPaint>,
+    fill_rule: FillRule,
+    stroke: Option<SvgPaint>,
+    stroke_style: StrokeStyle,
+}
+
+// Paints with their opacity already applied
+enum SvgPaint {
+    Color(Color),
+    Gradient(SvgGradient),
+}
+
+struct SvgGradient {
+    kind: SvgGradientKind,
+    // maps the coordinates of the gradient to the user units of the shape it paints
+    space: AffineTransformation,
+    spread: GradientExtend,
+    stops: Vec<(f32, Color)>,
+}
+
+#[derive(Clone, Copy)]
+enum SvgGradientKind {
+    Linear {p1: Point, p2: Point},
+    Radial {center: Point, radius: f64, focus: Point},
+}
+
+// The inherited properties of an element, paints are kept as written until a shape uses them
+#[derive(Clone)]
+struct SvgStyle {
+    transform: AffineTransformation,
+    fill: String,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke: String,
+    stroke_opacity: f32,
+    stroke_style: StrokeStyle,
+    clip_rule: FillRule,
+    color: String,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            transform: AffineTransformation::identity(),
+            fill: "black".to_string(),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: "none".to_string(),
+            stroke_opacity: 1.0,
+            stroke_style: StrokeStyle::default(),
+            clip_rule: FillRule::NonZero,
+            color: "black".to_string(),
+        }
+    }
+}
+
+impl SvgStyle {
+    fn inherit(&self, node: &HtmlWalker) -> Self {
+        let mut style = self.clone();
+        if let Some(transform) = attr(node, live_id!(transform)) {
+            style.transform = concat(style.transform, parse_svg_transform(transform));
+        }
+        let property = | name: &str | property(node, name).filter( | value | *value != "inherit");
+        if let Some(fill) = property("fill") {
+            style.fill = fill.to_string();
+        }
+        if let Some(stroke) = property("stroke") {
+            style.stroke = stroke.to_string();
+        }
+        if let Some(color) = property("color") {
+            style.color = color.to_string();
+        }
+        if let Some(opacity) = property("fill-opacity").and_then(parse_number) {
+            style.fill_opacity = opacity as f32;
+        }
+        if let Some(opacity) = property("stroke-opacity").and_then(parse_number) {
+            style.stroke_opacity = opacity as f32;
+        }
+        let rule = | value: &str | if value == "evenodd" {FillRule::EvenOdd} else {FillRule::NonZero};
+        if let Some(value) = property("fill-rule") {
+            style.fill_rule = rule(value);
+        }
+        if let Some(value) = property("clip-rule") {
+            style.clip_rule = rule(value);
+        }
+        if let Some(width) = property("stroke-width").and_then(parse_number) {
+            style.stroke_style.width = width.max(0.0);
+        }
+        if let Some(limit) = property("stroke-miterlimit").and_then(parse_number) {
+            style.stroke_style.miter_limit = limit.max(1.0);
+        }
+        match property("stroke-linejoin") {
+            Some("round") => style.stroke_style.join = LineJoin::Round,
+            Some("bevel") => style.stroke_style.join = LineJoin::Bevel,
+            Some(_) => style.stroke_style.join = LineJoin::Miter,
+            None => (),
+        }
+        match property("stroke-linecap") {
+            Some("round") => style.stroke_style.cap = LineCap::Round,
+            Some("square") => style.stroke_style.cap = LineCap::Square,
+            Some(_) => style.stroke_style.cap = LineCap::Butt,
+            None => (),
+        }
+        if let Some(value) = property("stroke-dasharray") {
+            style.stroke_style.dash = value
+                .split( | c: char | c == ',' || c.is_whitespace())
+                .filter( | length | !length.is_empty())
+                .map( | length | parse_number(length).unwrap_or(0.0))
+                .collect();
+        }
+        if let Some(offset) = property("stroke-dashoffset").and_then(parse_number) {
+            style.stroke_style.dash_offset = offset;
+        }
+        style
+    }
+}
+
+// A gradient element as written, attributes it doesn't have come from the one it references
+struct SvgGradientDef {
+    radial: bool,
+    attrs: HashMap<LiveId, String>,
+    stops: Vec<(f32, Color)>,
+    href: Option<String>,
+}
+
+// The elements that are only painted where they are referenced
+#[derive(Default)]
+struct SvgDefs {
+    gradients: HashMap<String, SvgGradientDef>,
+    clips: HashMap<String, Vec<SvgClipShape>>,
+    view_box: Option<Rect>,
+}
+
+impl SvgDefs {
+    fn collect(doc: &HtmlDoc) -> Self {
+        let mut defs = Self::default();
+        let mut node = doc.new_walker();
+        while !node.done() {
+            match node.open_tag_lc() {
+                Some(live_id!(lineargradient)) | Some(live_id!(radialgradient)) => {
+                    let radial = node.open_tag_lc() == Some(live_id!(radialgradient));
+                    let id = attr(&node, live_id!(id)).map(str::to_string);
+                    let href = attr(&node, live_id!(href))
+                        .or_else( | | attr(&node, LiveId::from_str_lc("xlink:href")))
+                        .map( | href | href.trim().trim_start_matches('#').to_string());
+                    let mut attrs = HashMap::new();
+                    for name in ["x1", "y1", "x2", "y2", "cx", "cy", "r", "fx", "fy", "gradientUnits", "gradientTransform", "spreadMethod"] {
+                        let name = LiveId::from_str_lc(name);
+                        if let Some(value) = attr(&node, name) {
+                            attrs.insert(name, value.to_string());
+                        }
+                    }
+                    let mut stops: Vec<(f32, Color)> = Vec::new();
+                    node.walk();
+                    while !node.done() && !matches!(node.close_tag_lc(), Some(live_id!(lineargradient)) | Some(live_id!(radialgradient))) {
+                        if node.open_tag_lc() == Some(live_id!(stop)) {
+                            let offset = attr(&node, live_id!(offset)).and_then(parse_number).unwrap_or(0.0) as f32;
+                            // offsets never go back, a stop before the previous one is moved onto it
+                            let offset = offset.clamp(stops.last().map_or(0.0, | stop | stop.0), 1.0);
+                            let [r, g, b] = property(&node, "stop-color").and_then(parse_svg_color).unwrap_or([0.0; 3]);
+                            let a = property(&node, "stop-opacity").and_then(parse_number).unwrap_or(1.0).clamp(0.0, 1.0) as f32;
+                            stops.push((offset, [r * a, g * a, b * a, a]));
+                        }
+                        node.walk();
+                    }
+                    if let Some(id) = id {
+                        defs.gradients.insert(id, SvgGradientDef {radial, attrs, stops, href});
+                    }
+                }
+                Some(live_id!(clippath)) => {
+                    let id = attr(&node, live_id!(id)).map(str::to_string);
+                    let mut styles = vec![SvgStyle::default().inherit(&node)];
+                    let mut shapes = Vec::new();
+                    node.walk();
+                    while !node.done() && !styles.is_empty() {
+                        if let Some(tag) = node.open_tag_lc() {
+                            let style = styles.last().unwrap().inherit(&node);
+                            if let Some(commands) = shape_commands(tag, &node) {
+                                shapes.push(SvgClipShape {transform: style.transform, commands, rule: style.clip_rule});
+                            }
+                            styles.push(style);
+                        }
+                        if node.close_tag_lc().is_some() {
+                            styles.pop();
+                        }
+                        node.walk();
+                    }
+                    if let Some(id) = id {
+                        defs.clips.insert(id, shapes);
+                    }
+                    continue;
+                }
+                _ => (),
+            }
+            node.walk();
+        }
+        defs
+    }
+
+    // The value of a gradient attribute, looked up through the gradients it references
+    fn gradient_attr(&self, id: &str, name: &str) -> Option<&str> {
+        let name = LiveId::from_str_lc(name);
+        let mut gradient = self.gradients.get(id);
+        // references can go in circles
+        for _ in 0..16 {
+            let def = gradient?;
+            if let Some(value) = def.attrs.get(&name) {
+                return Some(value)
+            }
+            gradient = def.href.as_ref().and_then( | href | self.gradients.get(href));
+        }
+        None
+    }
+
+    fn gradient_stops(&self, id: &str) -> Vec<(f32, Color)> {
+        let mut gradient = self.gradients.get(id);
+        for _ in 0..16 {
+            let Some(def) = gradient else {
+                break
+            };
+            if !def.stops.is_empty() {
+                return def.stops.clone()
+            }
+            gradient = def.href.as_ref().and_then( | href | self.gradients.get(href));
+        }
+        Vec::new()
+    }
+
+    fn gradient(&self, id: &str, opacity: f32, commands: &[PathCommand]) -> Option<SvgPaint> {
+        let def = self.gradients.get(id)?;
+        let stops: Vec<(f32, Color)> = self.gradient_stops(id).into_iter()
+            .map( | (offset, color) | (offset, color.map( | c | c * opacity)))
+            .collect();
+        let last = stops.last()?.1;
+        if stops.len() == 1 {
+            return Some(SvgPaint::Color(last))
+        }
+        let bounding_box = self.gradient_attr(id, "gradientUnits") != Some("userSpaceOnUse");
+        let mut space = AffineTransformation::identity();
+        if let Some(transform) = self.gradient_attr(id, "gradientTransform") {
+            space = parse_svg_transform(transform);
+        }
+        // percentages are of the bounding box, or of the view box in user units
+        let (width, height) = if bounding_box {
+            let bounds = commands_bounds(commands, AffineTransformation::identity(), 0.0)?;
+            if bounds.size.x <= 0.0 || bounds.size.y <= 0.0 {
+                return None
+            }
+            space = concat(
+                AffineTransformation::new(
+                    LinearTransformation::new(Vector::new(bounds.size.x, 0.0), Vector::new(0.0, bounds.size.y)),
+                    Vector::new(bounds.pos.x, bounds.pos.y),
+                ),
+                space,
+            );
+            (1.0, 1.0)
+        }
+        else {
+            self.view_box.map_or((100.0, 100.0), | view_box | (view_box.size.x, view_box.size.y))
+        };
+        let diagonal = (width * width + height * height).sqrt() / 2f64.sqrt();
+        let length = | name: &str, default: &str, reference: f64 | {
+            parse_length(self.gradient_attr(id, name).unwrap_or(default), reference).unwrap_or(0.0)
+        };
+        let kind = if def.radial {
+            let center = Point::new(length("cx", "50%", width), length("cy", "50%", height));
+            let radius = length("r", "50%", diagonal);
+            if radius <= 0.0 {
+                return Some(SvgPaint::Color(last))
+            }
+            let mut focus = Point::new(
+                self.gradient_attr(id, "fx").and_then( | v | parse_length(v, width)).unwrap_or(center.x),
+                self.gradient_attr(id, "fy").and_then( | v | parse_length(v, height)).unwrap_or(center.y),
+            );
+            // a focus outside the circle is moved onto its edge
+            let offset = focus - center;
+            if offset.length() > radius * 0.99 {
+                focus = center + offset * (radius * 0.99 / offset.length());
+            }
+            SvgGradientKind::Radial {center, radius, focus}
+        }
+        else {
+            SvgGradientKind::Linear {
+                p1: Point::new(length("x1", "0%", width), length("y1", "0%", height)),
+                p2: Point::new(length("x2", "100%", width), length("y2", "0%", height)),
+            }
+        };
+        let spread = match self.gradient_attr(id, "spreadMethod") {
+            Some("reflect") => GradientExtend::Reflect,
+            Some("repeat") => GradientExtend::Repeat,
+            _ => GradientExtend::Pad,
+        };
+        Some(SvgPaint::Gradient(SvgGradient {kind, space, spread, stops}))
+    }
+
+    fn paint(&self, value: &str, opacity: f32, style: &SvgStyle, commands: &[PathCommand]) -> Option<SvgPaint> {
+        let value = value.trim();
+        if let Some(url) = value.strip_prefix("url(") {
+            let (url, fallback) = url.split_once(')').unwrap_or((url, ""));
+            let id = url.trim().trim_matches( | c | c == '\'' || c == '"').trim_start_matches('#');
+            if self.gradients.contains_key(id) {
+                return self.gradient(id, opacity, commands)
+            }
+            if fallback.trim().is_empty() {
+                return None
+            }
+            return self.paint(fallback, opacity, style, commands)
+        }
+        let value = if value == "currentColor" {style.color.as_str()} else {value};
+        let [r, g, b] = parse_svg_color(value)?;
+        let a = opacity.clamp(0.0, 1.0);
+        Some(SvgPaint::Color([r * a, g * a, b * a, a]))
+    }
+}
+
+impl SvgDocument {
+    /// Parses a document.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Self::parse_from(text, | node | node.open_tag_lc() == Some(live_id!(svg)))
+            .ok_or_else( | | "No svg element found".to_string())
+    }
+
+    /// Parses only the element with `id` of a document, the way several glyphs of the `SVG `
+    /// table of a font share one.
+    pub fn parse_element(text: &str, id: &str) -> Result<Self, String> {
+        Self::parse_from(text, | node | node.open_tag_lc().is_some() && attr(node, live_id!(id)) == Some(id))
+            .ok_or_else( | | format!("No element with id {} found", id))
+    }
+
+    fn parse_from(text: &str, is_root: impl Fn(&HtmlWalker) -> bool) -> Option<Self> {
+        let doc = parse_html(text, &mut None, InternLiveId::No);
+        let mut defs = SvgDefs::collect(&doc);
+        let mut node = doc.new_walker();
+        while !is_root(&node) {
+            if node.done() {
+                return None
+            }
+            node.walk();
+        }
+
+        if node.open_tag_lc() == Some(live_id!(svg)) {
+            let number = | name: LiveId | attr(&node, name).and_then( | v | v.trim().trim_end_matches("px").parse::<f64>().ok());
+            let view_box: Vec<f64> = attr(&node, LiveId::from_str_lc("viewBox")).unwrap_or("")
+                .split( | c: char | c == ',' || c.is_whitespace())
+                .filter_map( | v | v.parse().ok())
+                .collect();
+            defs.view_box = if let [x, y, width, height] = view_box[..] {
+                Some(Rect {pos: dvec2(x, y), size: dvec2(width, height)})
+            }
+            else if let (Some(width), Some(height)) = (number(live_id!(width)), number(live_id!(height))) {
+                Some(Rect {pos: dvec2(0.0, 0.0), size: dvec2(width, height)})
+            }
+            else {
+                None
+            };
+        }
+
+        // the style of each open element, and whether it began a group
+        let mut stack = vec![(SvgStyle::default(), false)];
+        let mut items = Vec::new();
+        while !node.done() {
+            if let Some(tag) = node.open_tag_lc() {
+                let skip = matches!(tag,
+                    live_id!(defs) | live_id!(clippath) | live_id!(mask) | live_id!(lineargradient) | live_id!(radialgradient) |
+                    live_id!(symbol) | live_id!(pattern) | live_id!(marker) | live_id!(filter) | live_id!(style) |
+                    live_id!(title) | live_id!(desc) | live_id!(metadata) | live_id!(text) | live_id!(script)
+                );
+                if skip || property(&node, "display") == Some("none") {
+                    node.jump_to_close();
+                    node.walk();
+                    continue;
+                }
+                let style = stack.last().unwrap().0.inherit(&node);
+                let opacity = property(&node, "opacity").and_then(parse_number).unwrap_or(1.0).clamp(0.0, 1.0) as f32;
+                let clip = property(&node, "clip-path")
+                    .and_then( | value | value.trim().strip_prefix("url(")?.split_once(')'))
+                    .and_then( | (url, _) | defs.clips.get(url.trim().trim_matches( | c | c == '\'' || c == '"').trim_start_matches('#')))
+                    .map( | shapes | shapes.iter().map( | shape | SvgClipShape {
+                        transform: concat(style.transform, shape.transform),
+                        ..shape.clone()
+                    }).collect::<Vec<_>>());
+                let group = opacity < 1.0 || clip.is_some();
+                if group {
+                    items.push(SvgItem::BeginGroup {opacity, clip});
+                }
+                if let Some(commands) = shape_commands(tag, &node) {
+                    let fill = defs.paint(&style.fill, style.fill_opacity, &style, &commands);
+                    let stroke = defs.paint(&style.stroke, style.stroke_opacity, &style, &commands)
+                        .filter( | _ | style.stroke_style.width > 0.0);
+                    if fill.is_some() || stroke.is_some() {
+                        items.push(SvgItem::Shape(Box::new(SvgShape {
+                            transform: style.transform,
+                            commands,
+                            fill,
+                            fill_rule: style.fill_rule,
+                            stroke,
+                            stroke_style: style.stroke_style.clone(),
+                        })));
+                    }
+                }
+                stack.push((style, group));
+            }
+            if node.close_tag_lc().is_some() {
+                if let Some((_, true)) = stack.pop() {
+                    items.push(SvgItem::EndGroup);
+                }
+                // the root element closed
+                if stack.len() <= 1 {
+                    break
+                }
+            }
+            node.walk();
+        }
+        Some(Self {view_box: defs.view_box, items})
+    }
+
+    /// The bounds of everything the document paints in its user units, the ones its `viewBox`
+    /// is given in, ignoring clip paths. `None` if it paints nothing.
+    pub fn bounds(&self) -> Option<Rect> {
+        let mut bounds: Option<Rect> = None;
+        for item in &self.items {
+            let SvgItem::Shape(shape) = item else {
+                continue
+            };
+            let grow = if shape.stroke.is_some() {shape.stroke_style.width / 2.0} else {0.0};
+            if let Some(shape_bounds) = commands_bounds(&shape.commands, shape.transform, grow) {
+                bounds = Some(bounds.map_or(shape_bounds, | bounds | bounds.hull(shape_bounds)));
+            }
+        }
+        bounds
+    }
+
+    /// Paints the document into a `width` by `height` image of premultiplied `0xAARRGGBB` pixels,
+    /// the first row being the top one. `transform` maps the user units of the document to pixels.
+    pub fn rasterize(&self, transform: AffineTransformation, width: usize, height: usize) -> Vec<u32> {
+        let mut canvas = Canvas::new(width, height);
+        let mut trapezoidator = Trapezoidator::new();
+        // whether each open group pushed a clip, and its opacity
+        let mut groups = Vec::new();
+        for item in &self.items {
+            match item {
+                SvgItem::BeginGroup {opacity, clip} => {
+                    if let Some(clip) = clip {
+                        let mut union = vec![0.0f32; width * height];
+                        for shape in clip {
+                            let shape_transform = concat(transform, shape.transform);
+                            let polygons = flatten(&shape.commands, tolerance(shape_transform));
+                            let polygons: Vec<Vec<Point>> = polygons.into_iter().map( | (points, _) | points).collect();
+                            let mask = coverage(&mut trapezoidator, &polygons, shape_transform, shape.rule, width, height);
+                            for (u, m) in union.iter_mut().zip(mask) {
+                                *u = 1.0 - (1.0 - *u) * (1.0 - m.min(1.0));
+                            }
+                        }
+                        canvas.push_clip(union);
+                    }
+                    if *opacity < 1.0 {
+                        canvas.push_layer(CompositeMode::SourceOver);
+                    }
+                    groups.push((clip.is_some(), *opacity));
+                }
+                SvgItem::EndGroup => {
+                    let Some((clipped, opacity)) = groups.pop() else {
+                        continue
+                    };
+                    if opacity < 1.0 {
+                        canvas.fade_layer(opacity);
+                        canvas.pop_layer();
+                    }
+                    if clipped {
+                        canvas.pop_clip();
+                    }
+                }
+                SvgItem::Shape(shape) => {
+                    let shape_transform = concat(transform, shape.transform);
+                    let tolerance = tolerance(shape_transform);
+                    let contours = flatten(&shape.commands, tolerance);
+                    if let Some(fill) = &shape.fill {
+                        let polygons: Vec<Vec<Point>> = contours.iter().map( | (points, _) | points.clone()).collect();
+                        let mask = coverage(&mut trapezoidator, &polygons, shape_transform, shape.fill_rule, width, height);
+                        paint(&mut canvas, &mask, fill, shape_transform);
+                    }
+                    if let Some(stroke) = &shape.stroke {
+                        let polygons = stroke_polygons(&contours, &shape.stroke_style, tolerance);
+                        let mask = coverage(&mut trapezoidator, &polygons, shape_transform, FillRule::NonZero, width, height);
+                        paint(&mut canvas, &mask, stroke, shape_transform);
+                    }
+                }
+            }
+        }
+        canvas.into_pixels()
+    }
+}
+
+fn paint(canvas: &mut Canvas, mask: &[f32], paint: &SvgPaint, transform: AffineTransformation) {
+    let mask: Vec<f32> = mask.iter().map( | m | m.min(1.0)).collect();
+    let gradient = match paint {
+        SvgPaint::Color(color) => {
+            canvas.fill(Some(&mask), | _ | *color);
+            return
+        }
+        SvgPaint::Gradient(gradient) => gradient,
+    };
+    let last = gradient.stops.last().map_or([0.0; 4], | stop | stop.1);
+    let colors = ColorLine::from_stops(gradient.stops.clone(), gradient.spread);
+    let Some(inverse) = invert(concat(transform, gradient.space)) else {
+        canvas.fill(Some(&mask), | _ | last);
+        return
+    };
+    match gradient.kind {
+        SvgGradientKind::Linear {p1, p2} => {
+            let d = p2 - p1;
+            let length = d.dot(d);
+            if length <= 0.0 {
+                canvas.fill(Some(&mask), | _ | last);
+                return
+            }
+            canvas.fill(Some(&mask), | p | colors.color(((p.transform(&inverse) - p1).dot(d) / length) as f32));
+        }
+        SvgGradientKind::Radial {center, radius, focus} => {
+            canvas.fill(Some(&mask), | p | {
+                conical_t(p.transform(&inverse), focus, 0.0, center, radius).map_or([0.0; 4], | t | colors.color(t as f32))
+            });
+        }
+    }
+}
+
+// How far off flattened curves may be in the space `transform` maps to pixels
+fn commands_bounds(commands: &[PathCommand], transform: AffineTransformation, grow: f64) -> Option<Rect> {
+    let (mut min, mut max) = (dvec2(f64::INFINITY, f64::INFINITY), dvec2(f64::NEG_INFINITY, f64::NEG_INFINITY));
+    for command in commands {
+        for point in command_points(command) {
+            for corner in [Vector::new(-grow, -grow), Vector::new(grow, -grow), Vector::new(grow, grow), Vector::new(-grow, grow)] {
+                let p = (point + corner).transform(&transform);
+                min = dvec2(min.x.min(p.x), min.y.min(p.y));
+                max = dvec2(max.x.max(p.x), max.y.max(p.y));
+            }
+        }
+    }
+    (min.x <= max.x).then(|| Rect {pos: min, size: max - min})
+}
+
+// The coverage of the polygons mapped through `transform` in a `width` by `height` image
+fn coverage(
+    trapezoidator: &mut Trapezoidator,
+    polygons: &[Vec<Point>],
+    transform: AffineTransformation,
+    rule: FillRule,
+    width: usize,
+    height: usize,
+) -> Vec<f32> {
+    let mut mask = vec![0.0; width * height];
+    let commands = polygons.iter().filter( | polygon | polygon.len() > 2).flat_map( | polygon | {
+        std::iter::once(LinePathCommand::MoveTo(polygon[0].transform(&transform)))
+            .chain(polygon[1..].iter().map( | p | LinePathCommand::LineTo(p.transform(&transform))))
+            .chain(std::iter::once(LinePathCommand::Close))
+    });
+    trapezoidator.set_fill_rule(rule);
+    if let Some(trapezoids) = trapezoidator.trapezoidate(commands) {
+        crate::makepad_vector::internal_iter::InternalIterator::for_each(trapezoids, &mut | trapezoid | {
+            add_trapezoid(&mut mask, trapezoid, width, height);
+            true
+        });
+    }
+    mask
+}
+
+fn add_trapezoid(mask: &mut [f32], trapezoid: Trapezoid, width: usize, height: usize) {
+    let [x0, x1] = trapezoid.xs.map( | x | x as f64);
+    let [y0, y1, y2, y3] = trapezoid.ys.map( | y | y as f64);
+    let lower = (Point::new(x0, y0), Point::new(x1, y1));
+    let upper = (Point::new(x0, y2), Point::new(x1, y3));
+    let columns = (x0.floor().max(0.0) as usize)..(x1.ceil().min(width as f64).max(0.0) as usize);
+    let rows = (y0.min(y1).floor().max(0.0) as usize)..(y2.max(y3).ceil().min(height as f64).max(0.0) as usize);
+    for y in rows {
+        for x in columns.clone() {
+            let p_min = Point::new(x as f64, y as f64);
+            let p_max = Point::new(x as f64 + 1.0, y as f64 + 1.0);
+            mask[y * width + x] += (clamped_area(lower, p_min, p_max) - clamped_area(upper, p_min, p_max)) as f32;
+        }
+    }
+}
+
+// The area of the pixel from `p_min` to `p_max` between the line from `p0` to `p1` (left to
+// right) and the bottom of the pixel, the way the trapezoid shader computes it
+fn clamped_area((mut p0, mut p1): (Point, Point), p_min: Point, p_max: Point) -> f64 {
+    let x0 = p0.x.clamp(p_min.x, p_max.x);
+    let x1 = p1.x.clamp(p_min.x, p_max.x);
+    let at_x = | p0: Point, p1: Point, x: f64 | Point::new(x, p0.y + (p1.y - p0.y) * (x - p0.x) / (p1.x - p0.x));
+    let at_y = | p0: Point, p1: Point, y: f64 | Point::new(p0.x + (p1.x - p0.x) * (y - p0.y) / (p1.y - p0.y), y);
+    if p0.x < p_min.x && p_min.x < p1.x {
+        p0 = at_x(p0, p1, p_min.x);
+    }
+    if p0.x < p_max.x && p_max.x < p1.x {
+        p1 = at_x(p0, p1, p_max.x);
+    }
+    if p0.y < p_min.y && p_min.y < p1.y {
+        p0 = at_y(p0, p1, p_min.y);
+    }
+    if p1.y < p_min.y && p_min.y < p0.y {
+        p1 = at_y(p1, p0, p_min.y);
+    }
+    if p0.y < p_max.y && p_max.y < p1.y {
+        p1 = at_y(p0, p1, p_max.y);
+    }
+    if p1.y < p_max.y && p_max.y < p0.y {
+        p0 = at_y(p1, p0, p_max.y);
+    }
+    let clamp = | p: Point | Point::new(p.x.clamp(p_min.x, p_max.x), p.y.clamp(p_min.y, p_max.y));
+    let (p0, p1) = (clamp(p0), clamp(p1));
+    let h0 = p_max.y - p0.y;
+    let h1 = p_max.y - p1.y;
+    (p0.x - x0) * h0 + (p1.x - p0.x) * (h0 + h1) * 0.5 + (x1 - p1.x) * h1
+}
+
+// The outline of a shape element in its own user units
+fn shape_commands(tag: LiveId, node: &HtmlWalker) -> Option<Vec<PathCommand>> {
+    let number = | name: LiveId | attr(node, name).and_then(parse_number).unwrap_or(0.0);
+    match tag {
+        live_id!(path) => attr(node, live_id!(d)).and_then( | d | parse_svg_path(d.trim().as_bytes()).ok()),
+        live_id!(rect) => {
+            let (x, y, w, h) = (number(live_id!(x)), number(live_id!(y)), number(live_id!(width)), number(live_id!(height)));
+            if w <= 0.0 || h <= 0.0 {
+                return None
+            }
+            let (rx, ry) = match (attr(node, live_id!(rx)).and_then(parse_number), attr(node, live_id!(ry)).and_then(parse_number)) {
+                (Some(rx), Some(ry)) => (rx, ry),
+                (Some(r), None) | (None, Some(r)) => (r, r),
+                (None, None) => (0.0, 0.0),
+            };
+            let (rx, ry) = (rx.clamp(0.0, w / 2.0), ry.clamp(0.0, h / 2.0));
+            if rx <= 0.0 || ry <= 0.0 {
+                return Some(vec![
+                    PathCommand::MoveTo(Point::new(x, y)),
+                    PathCommand::LineTo(Point::new(x + w, y)),
+                    PathCommand::LineTo(Point::new(x + w, y + h)),
+                    PathCommand::LineTo(Point::new(x, y + h)),
+                    PathCommand::Close,
+                ])
+            }
+            let r = Point::new(rx, ry);
+            Some(vec![
+                PathCommand::MoveTo(Point::new(x + rx, y)),
+                PathCommand::LineTo(Point::new(x + w - rx, y)),
+                PathCommand::ArcTo(Point::new(x + w, y + ry), r, 0.0, false, true),
+                PathCommand::LineTo(Point::new(x + w, y + h - ry)),
+                PathCommand::ArcTo(Point::new(x + w - rx, y + h), r, 0.0, false, true),
+                PathCommand::LineTo(Point::new(x + rx, y + h)),
+                PathCommand::ArcTo(Point::new(x, y + h - ry), r, 0.0, false, true),
+                PathCommand::LineTo(Point::new(x, y + ry)),
+                PathCommand::ArcTo(Point::new(x + rx, y), r, 0.0, false, true),
+                PathCommand::Close,
+            ])
+        }
+        live_id!(circle) | live_id!(ellipse) => {
+            let (cx, cy) = (number(live_id!(cx)), number(live_id!(cy)));
+            let r = match tag {
+                live_id!(circle) => Point::new(number(live_id!(r)), number(live_id!(r))),
+                _ => Point::new(number(live_id!(rx)), number(live_id!(ry))),
+            };
+            if r.x <= 0.0 || r.y <= 0.0 {
+                return None
+            }
+            Some(vec![
+                PathCommand::MoveTo(Point::new(cx + r.x, cy)),
+                PathCommand::ArcTo(Point::new(cx - r.x, cy), r, 0.0, false, true),
+                PathCommand::ArcTo(Point::new(cx + r.x, cy), r, 0.0, false, true),
+                PathCommand::Close,
+            ])
+        }
+        live_id!(line) => Some(vec![
+            PathCommand::MoveTo(Point::new(number(live_id!(x1)), number(live_id!(y1)))),
+            PathCommand::LineTo(Point::new(number(live_id!(x2)), number(live_id!(y2)))),
+        ]),
+        live_id!(polyline) | live_id!(polygon) => {
+            let numbers: Vec<f64> = attr(node, live_id!(points))?
+                .split( | c: char | c == ',' || c.is_whitespace())
+                .filter_map( | v | v.parse().ok())
+                .collect();
+            let mut points = numbers.chunks_exact(2).map( | p | Point::new(p[0], p[1]));
+            let mut commands = vec![PathCommand::MoveTo(points.next()?)];
+            commands.extend(points.map(PathCommand::LineTo));
+            if tag == live_id!(polygon) {
+                commands.push(PathCommand::Close);
+            }
+            Some(commands)
+        }
+        _ => None,
+    }
+}
+
+// The value of the attribute of the tag the walker is on
+pub(crate) fn attr<'a>(node: &HtmlWalker<'a>, name: LiveId) -> Option<&'a str> {
+    let own = node.nodes[node.index + 1..].iter()
+        .take_while( | n | matches!(n, HtmlNode::Attribute {..}))
+        .any( | n | matches!(n, HtmlNode::Attribute {lc, ..} if *lc == name));
+    if own {node.find_attr_lc(name)} else {None}
+}
+
+// A presentation property, declarations in the `style` attribute win over attributes
+fn property<'a>(node: &HtmlWalker<'a>, name: &str) -> Option<&'a str> {
+    attr(node, live_id!(style))
+        .and_then( | style | {
+            style.split(';')
+                .filter_map( | declaration | declaration.split_once(':'))
+                .find( | (property, _) | property.trim() == name)
+                .map( | (_, value) | value.trim())
+        })
+        .or_else( | | attr(node, LiveId::from_str_lc(name)).map(str::trim))
+}
+
+// Numbers with an optional unit, percentages become fractions
+fn parse_number(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Some(percent) = value.strip_suffix('%') {
+        return percent.trim().parse::<f64>().ok().map( | v | v / 100.0)
+    }
+    value.trim_end_matches("px").trim().parse().ok()
+}
+
+// Lengths in user units, percentages are of `reference`
+fn parse_length(value: &str, reference: f64) -> Option<f64> {
+    let value = value.trim();
+    if value.ends_with('%') {
+        return parse_number(value).map( | v | v * reference)
+    }
+    parse_number(value)
+}
+
+pub(crate) fn parse_svg_transform(value: &str) -> AffineTransformation {
+    let mut transform = AffineTransformation::identity();
+    for part in value.split(')') {
+        let Some((name, args)) = part.split_once('(') else {
+            continue
+        };
+        let args: Vec<f64> = args.split( | c: char | c == ',' || c.is_whitespace())
+            .filter_map( | arg | arg.parse().ok())
+            .collect();
+        let arg = | index: usize, default: f64 | args.get(index).copied().unwrap_or(default);
+        let next = match name.trim().trim_start_matches(',').trim() {
+            "matrix" if args.len() == 6 => AffineTransformation::new(
+                LinearTransformation::new(Vector::new(args[0], args[1]), Vector::new(args[2], args[3])),
+                Vector::new(args[4], args[5]),
+            ),
+            "translate" => AffineTransformation::translation(Vector::new(arg(0, 0.0), arg(1, 0.0))),
+            "scale" => AffineTransformation::scaling(Vector::new(arg(0, 1.0), arg(1, arg(0, 1.0)))),
+            "rotate" => {
+                let (sin, cos) = arg(0, 0.0).to_radians().sin_cos();
+                let center = Vector::new(arg(1, 0.0), arg(2, 0.0));
+                concat(
+                    AffineTransformation::translation(center),
+                    concat(linear(cos, sin, -sin, cos), AffineTransformation::translation(-center)),
+                )
+            }
+            "skewX" => linear(1.0, 0.0, arg(0, 0.0).to_radians().tan(), 1.0),
+            "skewY" => linear(1.0, arg(0, 0.0).to_radians().tan(), 0.0, 1.0),
+            _ => continue,
+        };
+        transform = concat(transform, next);
+    }
+    transform
+}
+
+// `None` for paints that paint nothing, colors it can't parse are black
+pub(crate) fn parse_svg_color(value: &str) -> Option<[f32; 3]> {
+    let value = value.trim();
+    let hex = | s: &str | u8::from_str_radix(s, 16).ok().map( | v | v as f32 / 255.0);
+    if let Some(digits) = value.strip_prefix('#') {
+        let short = | i: usize | hex(&digits[i..i + 1].repeat(2));
+        let color = match digits.len() {
+            3 => [short(0), short(1), short(2)],
+            6 => [hex(&digits[0..2]), hex(&digits[2..4]), hex(&digits[4..6])],
+            _ => [None; 3],
+        };
+        return Some(color.map( | c | c.unwrap_or(0.0)))
+    }
+    if let Some(args) = value.strip_prefix("rgb(").and_then( | v | v.strip_suffix(')')) {
+        let mut channels = args.split(',').map( | arg | {
+            let arg = arg.trim();
+            match arg.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().unwrap_or(0.0) / 100.0,
+                None => arg.parse::<f32>().unwrap_or(0.0) / 255.0,
+            }
+        });
+        return Some([0; 3].map( | _ | channels.next().unwrap_or(0.0).clamp(0.0, 1.0)))
+    }
+    let rgb = | r: u8, g: u8, b: u8 | Some([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]);
+    match value {
+        "none" | "transparent" => None,
+        "white" => rgb(255, 255, 255),
+        "silver" => rgb(192, 192, 192),
+        "gray" | "grey" => rgb(128, 128, 128),
+        "red" => rgb(255, 0, 0),
+        "maroon" => rgb(128, 0, 0),
+        "orange" => rgb(255, 165, 0),
+        "yellow" => rgb(255, 255, 0),
+        "olive" => rgb(128, 128, 0),
+        "lime" => rgb(0, 255, 0),
+        "green" => rgb(0, 128, 0),
+        "cyan" | "aqua" => rgb(0, 255, 255),
+        "teal" => rgb(0, 128, 128),
+        "blue" => rgb(0, 0, 255),
+        "navy" => rgb(0, 0, 128),
+        "magenta" | "fuchsia" => rgb(255, 0, 255),
+        "purple" => rgb(128, 0, 128),
+        _ => Some([0.0; 3]),
+    }
+}
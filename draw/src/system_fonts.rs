@@ -0,0 +1,176 @@
+//! Discovery of the fonts installed on the system, so text can fall back to them for characters
+//! none of its own fonts cover. The standard font directories are scanned for font files, which
+//! are read with `ttf-parser` to find out which characters they cover. There's no dependency on
+//! fontconfig, and nothing is parsed until a character actually needs a fallback.
+
+use {
+    std::{
+        collections::HashMap,
+        env,
+        fs,
+        path::{Path, PathBuf},
+    },
+    makepad_rustybuzz::ttf_parser::Face,
+};
+
+pub struct SystemFonts {
+    fonts: Vec<SystemFont>,
+    // the font picked for each character that was looked up, `None` if no font covers it
+    picks: HashMap<char, Option<usize>>,
+}
+
+struct SystemFont {
+    path: PathBuf,
+    // sorted, non overlapping ranges of the codepoints the font has glyphs for, `None` until parsed
+    coverage: Option<Vec<(u32, u32)>>,
+}
+
+impl SystemFonts {
+    /// Scans the standard Linux font directories: the XDG data directories, `~/.fonts`,
+    /// `/usr/local/share/fonts` and `/usr/share/fonts`
+    pub fn scan() -> Self {
+        Self::scan_dirs(&Self::standard_dirs())
+    }
+
+    pub fn standard_dirs() -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        let home = env::var_os("HOME").map(PathBuf::from);
+        match env::var_os("XDG_DATA_HOME") {
+            Some(data_home) => dirs.push(PathBuf::from(data_home).join("fonts")),
+            None => dirs.extend(home.as_ref().map( | home | home.join(".local/share/fonts")))
+        }
+        dirs.extend(home.as_ref().map( | home | home.join(".fonts")));
+        let data_dirs = env::var("XDG_DATA_DIRS").unwrap_or_default();
+        let data_dirs = if data_dirs.is_empty() {"/usr/local/share:/usr/share"} else {data_dirs.as_str()};
+        for dir in data_dirs.split(':').filter( | dir | !dir.is_empty()) {
+            dirs.push(Path::new(dir).join("fonts"));
+        }
+        for dir in ["/usr/local/share/fonts", "/usr/share/fonts"] {
+            let dir = PathBuf::from(dir);
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        dirs
+    }
+
+    /// Finds the font files in `dirs` and their subdirectories. Regular styles are preferred over
+    /// bold, italic and other variants when several fonts cover a character.
+    pub fn scan_dirs(dirs: &[PathBuf]) -> Self {
+        let mut paths = Vec::new();
+        for dir in dirs {
+            collect_font_files(dir, &mut paths, 0);
+        }
+        paths.sort_by_cached_key( | path | (is_variant(path), path.clone()));
+        paths.dedup();
+        Self {
+            fonts: paths.into_iter().map( | path | SystemFont {path, coverage: None}).collect(),
+            picks: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fonts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+
+    pub fn path(&self, index: usize) -> &Path {
+        &self.fonts[index].path
+    }
+
+    /// The first font that covers every character of `cluster`, parsing fonts as needed.
+    /// Joiners and variation selectors don't count, fonts that handle emoji sequences often
+    /// leave them out of their character map.
+    pub fn font_for_cluster(&mut self, cluster: &str) -> Option<usize> {
+        let chars: Vec<char> = cluster.chars().filter( | &c | !is_invisible_joiner(c)).collect();
+        let index = self.font_for_char(*chars.first()?)?;
+        if chars.iter().all( | &c | self.fonts[index].covers(c)) {
+            return Some(index)
+        }
+        (0..self.fonts.len()).find( | &index | chars.iter().all( | &c | self.fonts[index].covers(c)))
+    }
+
+    pub fn font_for_char(&mut self, c: char) -> Option<usize> {
+        if let Some(pick) = self.picks.get(&c) {
+            return *pick
+        }
+        let pick = self.fonts.iter_mut().position( | font | font.covers(c));
+        self.picks.insert(c, pick);
+        pick
+    }
+}
+
+impl SystemFont {
+    fn covers(&mut self, c: char) -> bool {
+        let coverage = self.coverage.get_or_insert_with( | | {
+            fs::read(&self.path).ok().map( | data | parse_coverage(&data)).unwrap_or_default()
+        });
+        let c = c as u32;
+        let index = coverage.partition_point( | &(_, end) | end < c);
+        coverage.get(index).is_some_and( | &(start, _) | start <= c)
+    }
+}
+
+fn parse_coverage(data: &[u8]) -> Vec<(u32, u32)> {
+    // collections are covered by the first face, which is the one that gets loaded
+    let Ok(face) = Face::parse(data, 0) else {
+        return Vec::new()
+    };
+    let Some(cmap) = face.tables().cmap else {
+        return Vec::new()
+    };
+    let mut codepoints = Vec::new();
+    for subtable in cmap.subtables {
+        if subtable.is_unicode() {
+            subtable.codepoints( | c | {
+                if subtable.glyph_index(c).is_some_and( | id | id.0 != 0) {
+                    codepoints.push(c);
+                }
+            });
+        }
+    }
+    codepoints.sort_unstable();
+    codepoints.dedup();
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for c in codepoints {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == c => *end = c,
+            _ => ranges.push((c, c))
+        }
+    }
+    ranges
+}
+
+fn collect_font_files(dir: &Path, paths: &mut Vec<PathBuf>, depth: usize) {
+    // font directories are shallow, the limit guards against symlink loops
+    if depth > 8 {
+        return
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_font_files(&path, paths, depth + 1);
+        }
+        else if path.extension().and_then( | ext | ext.to_str()).is_some_and( | ext | {
+            ["ttf", "otf", "ttc", "otc"].iter().any( | known | ext.eq_ignore_ascii_case(known))
+        }) {
+            paths.push(path);
+        }
+    }
+}
+
+// zero width (non) joiners, variation selectors and emoji tag characters
+fn is_invisible_joiner(c: char) -> bool {
+    matches!(c, '\u{200C}' | '\u{200D}' | '\u{FE00}'..='\u{FE0F}' | '\u{E0020}'..='\u{E007F}')
+}
+
+fn is_variant(path: &Path) -> bool {
+    let name = path.file_stem().and_then( | name | name.to_str()).unwrap_or_default().to_ascii_lowercase();
+    ["bold", "italic", "oblique", "light", "thin", "black", "medium", "condensed"].iter().any( | style | name.contains(style))
+}
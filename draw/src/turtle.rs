@@ -15,6 +15,8 @@ pub struct Layout {
     #[live] pub align: Align,
     #[live] pub flow: Flow,
     #[live] pub spacing: f64,
+    #[live] pub direction: LayoutDirection,
+    #[live] pub grid: GridLayout,
     //#[live] pub line_spacing: f64
 }
 
@@ -28,18 +30,50 @@ impl Default for Layout{
             align: Align{x:0.0,y:0.0},
             flow: Flow::Right,
             spacing: 0.0,
+            direction: LayoutDirection::LeftToRight,
+            grid: GridLayout::default(),
             //line_spacing: 0.0
         }
     }
 }
 
-#[derive(Copy, Clone, Default, Debug, Live, LiveHook, LiveRegister)]
+#[derive(Copy, Clone, Debug, Live, LiveHook, LiveRegister)]
 #[live_ignore]
 pub struct Walk {
     #[live] pub abs_pos: Option<DVec2>,
     #[live] pub margin: Margin,
     #[live] pub width: Size,
     #[live] pub height: Size,
+    #[live] pub cell: GridCell,
+    /// Bounds for the width and height once `Fill` and `Fit` are resolved.
+    #[live] pub width_min: Option<f64>,
+    #[live] pub width_max: Option<f64>,
+    #[live] pub height_min: Option<f64>,
+    #[live] pub height_max: Option<f64>,
+    /// The share of the space left along the flow a `Fill` child gets, relative to its `Fill`
+    /// siblings.
+    #[live(1.0)] pub flex: f64,
+    /// Width over height. When one axis fits its content it follows the other, when both are
+    /// known the one that is too long shrinks.
+    #[live] pub aspect_ratio: Option<f64>,
+}
+
+impl Default for Walk {
+    fn default() -> Self {
+        Self {
+            abs_pos: None,
+            margin: Margin::default(),
+            width: Size::default(),
+            height: Size::default(),
+            cell: GridCell::default(),
+            width_min: None,
+            width_max: None,
+            height_min: None,
+            height_max: None,
+            flex: 1.0,
+            aspect_ratio: None,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Default, Debug, Live, LiveHook, LiveRegister)]
@@ -79,7 +113,77 @@ pub enum Flow {
     //Left,
     //Up,
     Overlay, 
-    RightWrap
+    RightWrap,
+    /// Places children in the cells of `Layout::grid`, see `GridLayout`.
+    Grid
+}
+
+/// The maximum number of columns or rows a `GridLayout` can define, more are implicit `Fit` tracks.
+pub const MAX_GRID_TRACKS: usize = 16;
+
+/// The size of a column or row of a `Flow::Grid`. A plain number is a `Fixed` track, and a
+/// `Fit` track is as big as the biggest child in it. `Fr` tracks share what the other tracks
+/// leave, in proportion to their fractions; when the grid itself fits its children, they fit
+/// theirs.
+#[derive(Copy, Clone, Debug, Default, Live, PartialEq)]
+#[live_ignore]
+pub enum GridTrack {
+    #[pick] #[default] Fit,
+    #[live(100.0)] Fixed(f64),
+    #[live(1.0)] Fr(f64),
+}
+
+/// The columns or rows of a `GridLayout`, written as an array: `[100, Fr(1.0), Fit]`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GridTracks {
+    tracks: [GridTrack; MAX_GRID_TRACKS],
+    len: usize,
+}
+
+/// The tracks of a `Flow::Grid`. Children without a `cell` go into the next free cells row by
+/// row, wrapping after the last column, and rows that aren't defined fit their children.
+/// Children are aligned within their cells by the layout's `align`.
+#[derive(Copy, Clone, Debug, Default, Live, LiveHook, LiveRegister)]
+#[live_ignore]
+pub struct GridLayout {
+    #[live] pub columns: GridTracks,
+    #[live] pub rows: GridTracks,
+    #[live] pub column_gap: f64,
+    #[live] pub row_gap: f64,
+}
+
+/// Where a child goes in a `Flow::Grid`, counting from 0. Without a column or a row it goes
+/// into the next free cell.
+#[derive(Copy, Clone, Debug, Live, LiveHook, LiveRegister)]
+#[live_ignore]
+pub struct GridCell {
+    #[live] pub column: Option<usize>,
+    #[live] pub row: Option<usize>,
+    #[live(1usize)] pub column_span: usize,
+    #[live(1usize)] pub row_span: usize,
+    /// Aligns the child within its cell instead of the grid's layout.
+    #[live] pub align: Option<Align>,
+}
+
+impl Default for GridCell {
+    fn default() -> Self {
+        Self {
+            column: None,
+            row: None,
+            column_span: 1,
+            row_span: 1,
+            align: None,
+        }
+    }
+}
+
+/// The direction children are placed in. `RightToLeft` mirrors the layout, for right-to-left
+/// languages: the first child goes on the right, and alignment is mirrored as well.
+#[derive(Copy, Clone, Debug, Live, LiveHook, PartialEq)]
+#[live_ignore]
+pub enum LayoutDirection {
+    #[pick] LeftToRight,
+    RightToLeft,
 }
 
 #[derive(Copy, Clone, Debug, Live)]
@@ -95,10 +199,12 @@ pub enum Size {
 pub enum DeferWalk{
     Unresolved{
         defer_index: usize,
-        margin: Margin,
-        other_axis: Size,
+        walk: Walk,
         pos: DVec2
     },
+    /// A child filling a cell of a `Flow::Grid` with fractional tracks, whose cell is known but
+    /// not its size. The walk has the cell filled in.
+    UnresolvedCell(Walk),
     Resolved(Walk)
 }
 
@@ -116,9 +222,37 @@ pub enum AlignEntry{
 pub struct TurtleWalk {
     align_start: usize,
     defer_index: usize,
+    is_abs: bool,
+    cell: Option<GridPlacement>,
     rect: Rect,
 }
 
+// The flex and the bounds along the flow of a deferred `Fill` child.
+#[derive(Clone, Copy, Debug)]
+struct DeferFill {
+    flex: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct GridPlacement {
+    column: usize,
+    row: usize,
+    column_span: usize,
+    row_span: usize,
+    align: Option<Align>,
+}
+
+#[derive(Clone, Default, Debug)]
+struct GridState {
+    // where the next child without a cell starts looking for a free one
+    cursor: (usize, usize),
+    taken: Vec<(usize, usize)>,
+    // the children placed so far with their size, margins included
+    items: Vec<(GridPlacement, DVec2)>,
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct Turtle {
     walk: Walk,
@@ -126,7 +260,7 @@ pub struct Turtle {
     wrap_spacing: f64,
     align_start: usize,
     turtle_walks_start: usize,
-    defer_count: usize,
+    defer_fills: Vec<DeferFill>,
     shift: DVec2,
     pos: DVec2,
     origin: DVec2,
@@ -134,6 +268,7 @@ pub struct Turtle {
     height: f64,
     width_used: f64,
     height_used: f64,
+    grid: GridState,
     guard_area: Area
 }
 
@@ -155,12 +290,9 @@ impl<'a> Cx2d<'a> {
             return None
         }
         let turtle = self.turtles.last_mut().unwrap();
-        let defer_index = turtle.defer_count;
+        let defer_index = turtle.defer_fills.len();
         let pos = turtle.pos;
-        let size = dvec2(
-            turtle.eval_width(walk.width, walk.margin, turtle.layout.flow),
-            turtle.eval_height(walk.height, walk.margin, turtle.layout.flow)
-        );
+        let size = turtle.eval_walk(&walk);
         let margin_size = walk.margin.size();
         match turtle.layout.flow {
             Flow::Right if walk.width.is_fill() => {
@@ -168,11 +300,10 @@ impl<'a> Cx2d<'a> {
                 turtle.pos.x += margin_size.x + spacing.x;
                 turtle.update_width_max(turtle.pos.x, 0.0);
                 turtle.update_height_max(turtle.pos.y, size.y + margin_size.y);
-                turtle.defer_count += 1;
+                turtle.defer_fills.push(DeferFill {flex: walk.flex, min: walk.width_min, max: walk.width_max});
                 Some(DeferWalk::Unresolved{
                     defer_index,
-                    margin: walk.margin,
-                    other_axis: walk.height,
+                    walk,
                     pos: pos + spacing
                 })
             },
@@ -181,11 +312,10 @@ impl<'a> Cx2d<'a> {
                 turtle.pos.y += margin_size.y + spacing.y;
                 turtle.update_width_max(turtle.pos.x, size.x + margin_size.x);
                 turtle.update_height_max(turtle.pos.y, 0.0);
-                turtle.defer_count += 1;
+                turtle.defer_fills.push(DeferFill {flex: walk.flex, min: walk.height_min, max: walk.height_max});
                 Some(DeferWalk::Unresolved {
                     defer_index,
-                    margin: walk.margin,
-                    other_axis: walk.width,
+                    walk,
                     pos: pos + spacing
                 })
             },
@@ -193,6 +323,30 @@ impl<'a> Cx2d<'a> {
                 error!("flow RightWrap does not support fill childnodes");
                 None
             },
+            // fractional tracks are only known once the fit tracks have seen all other children
+            Flow::Grid if walk.width.is_fill() || walk.height.is_fill() => {
+                let place = turtle.grid_place(walk.cell);
+                let spans_fr = | tracks: &GridTracks, start: usize, span: usize | {
+                    (start..start + span).any( | i | matches!(tracks.get(i), GridTrack::Fr(_)))
+                };
+                if walk.width.is_fill() && spans_fr(&turtle.layout.grid.columns, place.column, place.column_span) ||
+                    walk.height.is_fill() && spans_fr(&turtle.layout.grid.rows, place.row, place.row_span) {
+                    turtle.grid_take(walk.cell, &place);
+                    Some(DeferWalk::UnresolvedCell(Walk {
+                        cell: GridCell {
+                            column: Some(place.column),
+                            row: Some(place.row),
+                            column_span: place.column_span,
+                            row_span: place.row_span,
+                            align: place.align,
+                        },
+                        ..walk
+                    }))
+                }
+                else {
+                    None
+                }
+            },
             _ => {
                 None
             }
@@ -212,7 +366,7 @@ impl<'a> Cx2d<'a> {
             layout,
             align_start: self.align_list.len() - 1,
             turtle_walks_start: self.turtle_walks.len(),
-            defer_count: 0,
+            defer_fills: Vec::new(),
             pos: DVec2 {
                 x: layout.padding.left,
                 y: layout.padding.top
@@ -224,6 +378,7 @@ impl<'a> Cx2d<'a> {
             shift: dvec2(0.0, 0.0),
             width_used: layout.padding.left,
             height_used: layout.padding.top,
+            grid: GridState::default(),
             guard_area: Area::Empty,
         };
         self.turtles.push(turtle);
@@ -267,13 +422,21 @@ impl<'a> Cx2d<'a> {
     pub fn begin_turtle_with_guard(&mut self, walk: Walk, layout: Layout, guard_area: Area) {
         let (origin, width, height, draw_clip) = if let Some(parent) = self.turtles.last() {
             
-            let o = walk.margin.left_top() + if let Some(pos) = walk.abs_pos {pos} else {
-                parent.pos + parent.child_spacing(self.turtle_walks.len())
+            let (o, w, h) = if parent.layout.flow == Flow::Grid && walk.abs_pos.is_none() {
+                // the cell is taken when the turtle ends and walks its parent
+                let cell = parent.grid_cell_rect(&parent.grid_place(walk.cell));
+                let size = parent.grid_walk_size(&walk, cell);
+                (walk.margin.left_top() + cell.pos, size.x, size.y)
+            }
+            else {
+                let o = walk.margin.left_top() + if let Some(pos) = walk.abs_pos {pos} else {
+                    parent.pos + parent.child_spacing(self.turtle_walks.len())
+                };
+                
+                let size = parent.eval_walk(&walk);
+                (o, size.x, size.y)
             };
             
-            let w = parent.eval_width(walk.width, walk.margin, parent.layout.flow);
-            let h = parent.eval_height(walk.height, walk.margin, parent.layout.flow);
-            
             // figure out new clipping rect
             let (x0, x1) = if layout.clip_x {
                 (/*parent.draw_clip.0.x.max(*/o.x/*)*/, if w.is_nan() {
@@ -309,7 +472,7 @@ impl<'a> Cx2d<'a> {
             layout,
             align_start: self.align_list.len()-1,
             turtle_walks_start: self.turtle_walks.len(),
-            defer_count: 0,
+            defer_fills: Vec::new(),
             wrap_spacing: 0.0,
             pos: DVec2 {
                 x: origin.x + layout.padding.left,
@@ -321,6 +484,7 @@ impl<'a> Cx2d<'a> {
             shift: dvec2(0.0,0.0),
             width_used: layout.padding.left,
             height_used: layout.padding.top,
+            grid: GridState::default(),
             guard_area,
         };
         
@@ -349,12 +513,19 @@ impl<'a> Cx2d<'a> {
         let turtle_align_start = turtle.align_start;
         let turtle_abs_pos = turtle.walk.abs_pos;
         let turtle_margin = turtle.walk.margin;
+        let turtle_cell = turtle.walk.cell;
         let turtle_walks_start = turtle.turtle_walks_start;
         let turtle_shift = turtle.shift;
+        
+        if turtle.layout.flow == Flow::Grid {
+            self.end_grid_turtle();
+        }
+        let turtle = self.turtles.last().unwrap();
                 
         // computed width / height
         let w = if turtle.width.is_nan() {
             let w = turtle.width_used + turtle.layout.padding.right - turtle.layout.scroll.x;
+            let w = clamp_keep_nan(w, turtle.walk.width_min, turtle.walk.width_max);
             // we should update the clip pos
             if let AlignEntry::BeginTurtle(p1,p2) = &mut self.align_list[turtle_align_start]{
                 p2.x = p1.x + w;
@@ -367,6 +538,7 @@ impl<'a> Cx2d<'a> {
         
         let h = if turtle.height.is_nan() {
             let h =  turtle.height_used + turtle.layout.padding.bottom - turtle.layout.scroll.y;
+            let h = clamp_keep_nan(h, turtle.walk.height_min, turtle.walk.height_max);
             // we should update the clip pos
             if let AlignEntry::BeginTurtle(p1,p2) = &mut self.align_list[turtle_align_start]{
                 p2.y = p1.y + h;
@@ -379,18 +551,15 @@ impl<'a> Cx2d<'a> {
                 
         match turtle.layout.flow {
             Flow::Right => {
-                if turtle.defer_count > 0 {
-                    let left = turtle.width_left();
-                    let part = left / turtle.defer_count as f64;
+                if !turtle.defer_fills.is_empty() {
+                    let sizes = turtle.defer_fill_sizes(turtle.width_left());
                     let align_y = turtle.layout.align.y;
                     let padded_height_or_used = turtle.padded_height_or_used();
                     for i in turtle_walks_start..self.turtle_walks.len() {
                         let walk = &self.turtle_walks[i];
-                        let shift_x = walk.defer_index as f64 * part;
+                        let shift_x = sizes[..walk.defer_index].iter().sum::<f64>();
                         let shift_y = align_y * (padded_height_or_used - walk.rect.size.y);
-                        let align_start = walk.align_start;
-                        let align_end = self.get_turtle_walk_align_end(i);
-                        self.move_align_list(shift_x, shift_y, align_start, align_end, false, turtle_shift);
+                        self.move_turtle_walk(i, shift_x, shift_y, turtle_shift);
                     }
                 }
                 else {
@@ -403,30 +572,28 @@ impl<'a> Cx2d<'a> {
                             let walk = &self.turtle_walks[i];
                             let shift_x = align_x * width_left;
                             let shift_y = align_y * (padded_height_or_used - walk.rect.size.y);
-                            let align_start = walk.align_start;
-                            let align_end = self.get_turtle_walk_align_end(i);
-                            self.move_align_list(shift_x, shift_y, align_start, align_end, false, turtle_shift);
+                            self.move_turtle_walk(i, shift_x, shift_y, turtle_shift);
                         }
                     }
                 }
             },
             Flow::RightWrap=>{
-                if turtle.defer_count > 0{panic!()}
+                if !turtle.defer_fills.is_empty() {panic!()}
                 // for now we only support align:0,0
             }
+            Flow::Grid => {
+                // aligned within their cells by end_grid_turtle
+            }
             Flow::Down => {
-                if turtle.defer_count > 0 {
-                    let left = turtle.height_left();
-                    let part = left / turtle.defer_count as f64;
+                if !turtle.defer_fills.is_empty() {
+                    let sizes = turtle.defer_fill_sizes(turtle.height_left());
                     let padded_width_or_used = turtle.padded_width_or_used();
                     let align_x = turtle.layout.align.x;
                     for i in turtle_walks_start..self.turtle_walks.len() {
                         let walk = &self.turtle_walks[i];
                         let shift_x = align_x * (padded_width_or_used- walk.rect.size.x);
-                        let shift_y = walk.defer_index as f64 * part;
-                        let align_start = walk.align_start;
-                        let align_end = self.get_turtle_walk_align_end(i);
-                        self.move_align_list(shift_x, shift_y, align_start, align_end, false, turtle_shift);
+                        let shift_y = sizes[..walk.defer_index].iter().sum::<f64>();
+                        self.move_turtle_walk(i, shift_x, shift_y, turtle_shift);
                     }
                 }
                 else {
@@ -439,9 +606,7 @@ impl<'a> Cx2d<'a> {
                             let walk = &self.turtle_walks[i];
                             let shift_x = align_x * (padded_width_or_used - walk.rect.size.x);
                             let shift_y = align_y * height_left;
-                            let align_start = walk.align_start;
-                            let align_end = self.get_turtle_walk_align_end(i);
-                            self.move_align_list(shift_x, shift_y, align_start, align_end, false, turtle_shift);
+                            self.move_turtle_walk(i, shift_x, shift_y, turtle_shift);
                         }
                     }
                 }
@@ -456,13 +621,29 @@ impl<'a> Cx2d<'a> {
                         let walk = &self.turtle_walks[i];
                         let shift_x = align_x * (padded_width_or_used - walk.rect.size.x);
                         let shift_y = align_y * (padded_height_or_used - walk.rect.size.y);
-                        let align_start = walk.align_start;
-                        let align_end = self.get_turtle_walk_align_end(i);
-                        self.move_align_list(shift_x, shift_y, align_start, align_end, false, turtle_shift);
+                        self.move_turtle_walk(i, shift_x, shift_y, turtle_shift);
                     }
                 }
             }
         }
+        let turtle = self.turtles.last().unwrap();
+        if turtle.layout.direction == LayoutDirection::RightToLeft {
+            // mirror the children within the padded area, so the first one ends up on the right
+            let left = turtle.origin.x + turtle.layout.padding.left;
+            let right = if turtle.width.is_nan() {
+                turtle.origin.x + turtle.width_used
+            }
+            else {
+                turtle.origin.x + turtle.width - turtle.layout.padding.right
+            };
+            for i in turtle_walks_start..self.turtle_walks.len() {
+                let walk = &self.turtle_walks[i];
+                if !walk.is_abs {
+                    let shift_x = left + right - 2.0 * walk.rect.pos.x - walk.rect.size.x;
+                    self.move_turtle_walk(i, shift_x, 0.0, dvec2(0.0, 0.0));
+                }
+            }
+        }
         self.turtles.pop();
         self.turtle_walks.truncate(turtle_walks_start);
         self.align_list.push(AlignEntry::EndTurtle);
@@ -472,7 +653,7 @@ impl<'a> Cx2d<'a> {
                 size: dvec2(w.fixed_or_zero(), h.fixed_or_zero())
             }
         }
-        let rect = self.walk_turtle_move(Walk {width: w, height: h, abs_pos:turtle_abs_pos, margin:turtle_margin}, turtle_align_start);
+        let rect = self.walk_turtle_move(Walk {width: w, height: h, abs_pos:turtle_abs_pos, margin:turtle_margin, cell:turtle_cell, ..Default::default()}, turtle_align_start);
         rect
     }
     
@@ -526,22 +707,21 @@ impl<'a> Cx2d<'a> {
      fn walk_turtle_move(&mut self, walk: Walk, align_start: usize) -> Rect {
         
         let turtle = self.turtles.last_mut().unwrap();
-        let size = dvec2(
-            turtle.eval_width(walk.width, walk.margin, turtle.layout.flow),
-            turtle.eval_height(walk.height, walk.margin, turtle.layout.flow)
-        );
+        let size = turtle.eval_walk(&walk);
         
         if let Some(pos) = walk.abs_pos {
             self.turtle_walks.push(TurtleWalk {
                 align_start,
                 defer_index: 0,
+                is_abs: true,
+                cell: None,
                 rect: Rect {pos, size: size + walk.margin.size()}
             });
             
             match turtle.layout.flow {
                 Flow::Right=>turtle.update_height_max(pos.y, size.y + walk.margin.size().y),
                 Flow::Down=>turtle.update_width_max(pos.x, size.x + walk.margin.size().x),
-                Flow::Overlay => { // do not walk
+                Flow::Overlay | Flow::Grid => { // do not walk
                     turtle.update_width_max(pos.x, size.x);
                     turtle.update_height_max(pos.y,size.y);
                 }
@@ -551,11 +731,30 @@ impl<'a> Cx2d<'a> {
             }
             Rect {pos: pos + walk.margin.left_top(), size}
         }
+        else if turtle.layout.flow == Flow::Grid {
+            let place = turtle.grid_place(walk.cell);
+            turtle.grid_take(walk.cell, &place);
+            let cell = turtle.grid_cell_rect(&place);
+            let size = turtle.grid_walk_size(&walk, cell);
+            let margin_size = walk.margin.size();
+            let used = dvec2(nan_to_zero(size.x), nan_to_zero(size.y)) + margin_size;
+            turtle.grid.items.push((place, used));
+            turtle.update_width_max(cell.pos.x, used.x);
+            turtle.update_height_max(cell.pos.y, used.y);
+            self.turtle_walks.push(TurtleWalk {
+                align_start,
+                defer_index: 0,
+                is_abs: false,
+                cell: Some(place),
+                rect: Rect {pos: cell.pos, size: used}
+            });
+            Rect {pos: cell.pos + walk.margin.left_top(), size}
+        }
         else {
             let spacing = turtle.child_spacing(self.turtle_walks.len());
             let mut pos = turtle.pos;
             let margin_size = walk.margin.size();
-            let defer_index = turtle.defer_count;
+            let defer_index = turtle.defer_fills.len();
             match turtle.layout.flow {
                 Flow::Right => {
                     turtle.pos.x = pos.x + size.x + margin_size.x + spacing.x;
@@ -608,7 +807,7 @@ impl<'a> Cx2d<'a> {
                         turtle.update_height_max(turtle.pos.y,0.0);
                     }
                 },
-                Flow::Overlay => { // do not walk
+                Flow::Overlay | Flow::Grid => { // do not walk
                     turtle.update_width_max(turtle.pos.x, size.x);
                     turtle.update_height_max(turtle.pos.y,size.y);
                 }
@@ -617,7 +816,9 @@ impl<'a> Cx2d<'a> {
             self.turtle_walks.push(TurtleWalk {
                 align_start,
                 defer_index,
-                rect: Rect {pos, size: size + margin_size}
+                is_abs: false,
+                cell: None,
+                rect: Rect {pos: pos + spacing, size: size + margin_size}
             });
             Rect {pos: pos + walk.margin.left_top() + spacing, size}
         }
@@ -628,14 +829,15 @@ impl<'a> Cx2d<'a> {
             return Rect::default()
         }
         let turtle = self.turtles.last().unwrap();
-        let size = dvec2(
-            turtle.eval_width(walk.width, walk.margin, turtle.layout.flow),
-            turtle.eval_height(walk.height, walk.margin, turtle.layout.flow)
-        );
+        let size = turtle.eval_walk(&walk);
         
         if let Some(pos) = walk.abs_pos {
             Rect {pos: pos + walk.margin.left_top(), size}
         }
+        else if turtle.layout.flow == Flow::Grid {
+            let cell = turtle.grid_cell_rect(&turtle.grid_place(walk.cell));
+            Rect {pos: cell.pos + walk.margin.left_top(), size: turtle.grid_walk_size(&walk, cell)}
+        }
         else {
             let spacing = turtle.child_spacing(self.turtle_walks.len());
             let pos = turtle.pos;
@@ -667,6 +869,46 @@ impl<'a> Cx2d<'a> {
         }
     }
     
+    // Sizes the tracks of the current grid turtle from all its children, and moves each child
+    // into its cell, aligned.
+    fn end_grid_turtle(&mut self) {
+        let turtle = self.turtles.last().unwrap();
+        let columns = turtle.grid_track_sizes(false);
+        let rows = turtle.grid_track_sizes(true);
+        let mut moves = Vec::new();
+        for i in turtle.turtle_walks_start..self.turtle_walks.len() {
+            let walk = &self.turtle_walks[i];
+            if let Some(place) = walk.cell {
+                let cell = turtle.grid_cell_rect_in(&columns, &rows, &place);
+                let align = place.align.unwrap_or(turtle.layout.align);
+                let target = cell.pos + dvec2(
+                    align.x * (cell.size.x - walk.rect.size.x),
+                    align.y * (cell.size.y - walk.rect.size.y)
+                );
+                moves.push((i, target - walk.rect.pos));
+            }
+        }
+        let extent = | sizes: &[f64], gap: f64 | sizes.iter().sum::<f64>() + gap * sizes.len().saturating_sub(1) as f64;
+        let grid = turtle.layout.grid;
+        let turtle_shift = turtle.shift;
+        let turtle = self.turtles.last_mut().unwrap();
+        turtle.width_used = turtle.layout.padding.left + extent(&columns, grid.column_gap);
+        turtle.height_used = turtle.layout.padding.top + extent(&rows, grid.row_gap);
+        for (i, delta) in moves {
+            self.move_turtle_walk(i, delta.x, delta.y, turtle_shift);
+        }
+    }
+    
+    /// Moves everything a child of the current turtle drew, and keeps its rect in step.
+    fn move_turtle_walk(&mut self, i: usize, dx: f64, dy: f64, turtle_shift: DVec2) {
+        let align_start = self.turtle_walks[i].align_start;
+        let align_end = self.get_turtle_walk_align_end(i);
+        self.move_align_list(dx, dy, align_start, align_end, false, turtle_shift);
+        let rect = &mut self.turtle_walks[i].rect;
+        rect.pos.x += if dx.is_nan() {0.0} else {dx};
+        rect.pos.y += if dy.is_nan() {0.0} else {dy};
+    }
+    
     fn move_align_list(&mut self, dx: f64, dy: f64, align_start: usize, align_end: usize, shift_clip: bool, turtle_shift:DVec2) {
         //let current_dpi_factor = self.current_dpi_factor();
         let dx = if dx.is_nan() {0.0}else {dx} + turtle_shift.x;
@@ -866,8 +1108,153 @@ impl Turtle {
         self.pos = pos
     }
     
+    // The cell a child with this walk cell goes into next, without taking it.
+    fn grid_place(&self, cell: GridCell) -> GridPlacement {
+        let columns = self.layout.grid.columns.len().max(1);
+        let column_span = cell.column_span.clamp(1, columns);
+        let row_span = cell.row_span.max(1);
+        let taken = &self.grid.taken;
+        let free = | column: usize, row: usize | {
+            (column..column + column_span).all( | c | (row..row + row_span).all( | r | !taken.contains(&(c, r))))
+        };
+        let (column, row) = match (cell.column, cell.row) {
+            (Some(column), Some(row)) => (column, row),
+            (Some(column), None) => {
+                let mut row = self.grid.cursor.1;
+                while !free(column, row) {
+                    row += 1;
+                }
+                (column, row)
+            }
+            (None, Some(row)) => {
+                ((0..=columns - column_span).find( | column | free(*column, row)).unwrap_or(0), row)
+            }
+            (None, None) => {
+                let (mut column, mut row) = self.grid.cursor;
+                loop {
+                    if column + column_span > columns {
+                        column = 0;
+                        row += 1;
+                    }
+                    else if free(column, row) {
+                        break (column, row)
+                    }
+                    else {
+                        column += 1;
+                    }
+                }
+            }
+        };
+        GridPlacement {column, row, column_span, row_span, align: cell.align}
+    }
+    
+    fn grid_take(&mut self, cell: GridCell, place: &GridPlacement) {
+        for column in place.column..place.column + place.column_span {
+            for row in place.row..place.row + place.row_span {
+                self.grid.taken.push((column, row));
+            }
+        }
+        if cell.column.is_none() && cell.row.is_none() {
+            self.grid.cursor = (place.column + place.column_span, place.row);
+        }
+    }
+    
+    // The sizes of the columns or rows with the children placed so far.
+    fn grid_track_sizes(&self, rows: bool) -> Vec<f64> {
+        let grid = &self.layout.grid;
+        let (tracks, gap, available) = if rows {
+            (&grid.rows, grid.row_gap, self.height - self.layout.padding.height())
+        }
+        else {
+            (&grid.columns, grid.column_gap, self.width - self.layout.padding.width())
+        };
+        let span = | place: &GridPlacement | if rows {(place.row, place.row_span)} else {(place.column, place.column_span)};
+        let count = self.grid.items.iter().map( | (place, _) | {
+            let (start, span) = span(place);
+            start + span
+        }).chain(self.grid.taken.iter().map( | (column, row) | if rows {row + 1} else {column + 1}))
+            .max().unwrap_or(0).max(tracks.len());
+        // fractional tracks fit their children when there is nothing to share
+        let flexible = | i: usize | match tracks.get(i) {
+            GridTrack::Fit => true,
+            GridTrack::Fixed(_) => false,
+            GridTrack::Fr(_) => available.is_nan(),
+        };
+        let mut sizes: Vec<f64> = (0..count).map( | i | match tracks.get(i) {
+            GridTrack::Fixed(v) => v.max(0.0),
+            _ => 0.0
+        }).collect();
+        // children spanning one track first, the others only grow their tracks where too small
+        for (place, size) in &self.grid.items {
+            let (start, span) = span(place);
+            if span == 1 && flexible(start) {
+                sizes[start] = sizes[start].max(if rows {size.y} else {size.x});
+            }
+        }
+        for (place, size) in &self.grid.items {
+            let (start, span) = span(place);
+            if span > 1 {
+                let needed = (if rows {size.y} else {size.x}) - gap * (span - 1) as f64;
+                let have: f64 = sizes[start..start + span].iter().sum();
+                let grow: Vec<usize> = (start..start + span).filter( | i | flexible(*i)).collect();
+                if needed > have && !grow.is_empty() {
+                    for i in &grow {
+                        sizes[*i] += (needed - have) / grow.len() as f64;
+                    }
+                }
+            }
+        }
+        if !available.is_nan() {
+            let fraction = | i: usize | if let GridTrack::Fr(fr) = tracks.get(i) {Some(fr.max(0.0))} else {None};
+            let total: f64 = (0..count).filter_map(fraction).sum();
+            if total > 0.0 {
+                let used: f64 = (0..count).filter( | i | fraction(*i).is_none()).map( | i | sizes[i]).sum();
+                let free = (available - used - gap * count.saturating_sub(1) as f64).max(0.0);
+                for (i, size) in sizes.iter_mut().enumerate() {
+                    if let Some(fr) = fraction(i) {
+                        *size = free * fr / total;
+                    }
+                }
+            }
+        }
+        sizes
+    }
+    
+    fn grid_cell_rect(&self, place: &GridPlacement) -> Rect {
+        self.grid_cell_rect_in(&self.grid_track_sizes(false), &self.grid_track_sizes(true), place)
+    }
+    
+    fn grid_cell_rect_in(&self, columns: &[f64], rows: &[f64], place: &GridPlacement) -> Rect {
+        let track = | sizes: &[f64], gap: f64, start: usize, span: usize | {
+            let size = | i: usize | sizes.get(i).copied().unwrap_or(0.0);
+            (
+                (0..start).map(size).sum::<f64>() + gap * start as f64,
+                (start..start + span).map(size).sum::<f64>() + gap * (span - 1) as f64
+            )
+        };
+        let (x, width) = track(columns, self.layout.grid.column_gap, place.column, place.column_span);
+        let (y, height) = track(rows, self.layout.grid.row_gap, place.row, place.row_span);
+        Rect {
+            pos: self.origin + self.layout.padding.left_top() + dvec2(x, y),
+            size: dvec2(width, height)
+        }
+    }
+    
+    fn grid_walk_size(&self, walk: &Walk, cell: Rect) -> DVec2 {
+        let size = | size: Size, cell: f64, margin: f64, all: f64 | match size {
+            Size::Fill => (cell - margin).max(0.0),
+            Size::Fixed(v) => max_zero_keep_nan(v),
+            Size::Fit => f64::NAN,
+            Size::All => all,
+        };
+        walk.constrain_size(dvec2(
+            size(walk.width, cell.size.x, walk.margin.width(), self.width),
+            size(walk.height, cell.size.y, walk.margin.height(), self.height)
+        ))
+    }
+    
     fn child_spacing(&self, walks_len: usize) -> DVec2 {
-        if self.turtle_walks_start < walks_len || self.defer_count > 0 {
+        if self.turtle_walks_start < walks_len || !self.defer_fills.is_empty() {
             match self.layout.flow {
                 Flow::Right => {
                     dvec2(self.layout.spacing, 0.0)
@@ -875,7 +1262,7 @@ impl Turtle {
                 Flow::Down => {
                     dvec2(0.0, self.layout.spacing)
                 }
-                Flow::Overlay => {
+                Flow::Overlay | Flow::Grid => {
                     dvec2(0.0, 0.0)
                 }
                 Flow::RightWrap=>{
@@ -920,6 +1307,49 @@ impl Turtle {
         self.layout.scroll
     }
     
+    /// The size of a child with this walk, `eval_width` and `eval_height` within the walk's
+    /// bounds and aspect ratio. An axis that fits its content is NaN.
+    pub fn eval_walk(&self, walk: &Walk) -> DVec2 {
+        walk.constrain_size(dvec2(
+            self.eval_width(walk.width, walk.margin, self.layout.flow),
+            self.eval_height(walk.height, walk.margin, self.layout.flow)
+        ))
+    }
+    
+    // Shares the space left along the flow between the deferred `Fill` children by their flex.
+    // Children whose bounds clamp their share keep the clamped size; the others split what
+    // remains, repeating until no share is clamped.
+    fn defer_fill_sizes(&self, left: f64) -> Vec<f64> {
+        let fills = &self.defer_fills;
+        let left = nan_to_zero(left).max(0.0);
+        let mut sizes: Vec<Option<f64>> = vec![None; fills.len()];
+        loop {
+            let open: Vec<usize> = (0..fills.len()).filter( | i | sizes[*i].is_none()).collect();
+            if open.is_empty() {
+                break
+            }
+            let free = (left - sizes.iter().flatten().sum::<f64>()).max(0.0);
+            let total: f64 = open.iter().map( | i | fills[*i].flex.max(0.0)).sum();
+            let share = | i: usize | if total > 0.0 {free * fills[i].flex.max(0.0) / total} else {0.0};
+            let clamped = | i: usize | clamp_keep_nan(share(i), fills[i].min, fills[i].max);
+            // only the children clamped in the direction of the total error are final
+            let error: f64 = open.iter().map( | i | clamped(*i) - share(*i)).sum();
+            let frozen: Vec<usize> = open.iter().copied().filter( | i | {
+                error > 0.0 && clamped(*i) > share(*i) || error < 0.0 && clamped(*i) < share(*i)
+            }).collect();
+            if frozen.is_empty() {
+                for i in open {
+                    sizes[i] = Some(clamped(i));
+                }
+                break
+            }
+            for i in frozen {
+                sizes[i] = Some(clamped(i));
+            }
+        }
+        sizes.into_iter().map( | size | size.unwrap_or(0.0)).collect()
+    }
+    
     pub fn eval_width(&self, width: Size, margin: Margin, flow: Flow) -> f64 {
         return match width {
             Size::Fit => std::f64::NAN,
@@ -932,7 +1362,7 @@ impl Turtle {
                     Flow::Right => {
                         max_zero_keep_nan(self.width_left() - margin.width())
                     },
-                    Flow::Down | Flow::Overlay => {
+                    Flow::Down | Flow::Overlay | Flow::Grid => {
                         let r = max_zero_keep_nan(self.width - self.layout.padding.width() - margin.width());
                         if r.is_nan() {
                             return self.width_used - margin.width() - self.layout.padding.right
@@ -951,7 +1381,7 @@ impl Turtle {
             Size::Fixed(v) => max_zero_keep_nan(v),
             Size::Fill => {
                 match flow {
-                    Flow::RightWrap | Flow::Right | Flow::Overlay => {
+                    Flow::RightWrap | Flow::Right | Flow::Overlay | Flow::Grid => {
                         let r = max_zero_keep_nan(self.height - self.layout.padding.height() - margin.height());
                         if r.is_nan() {
                             return self.height_used - margin.height() - self.layout.padding.bottom
@@ -1039,33 +1469,41 @@ impl DeferWalk {
     pub fn resolve(&mut self, cx: &Cx2d) -> Walk {
         match self{
             Self::Resolved(walk)=>{*walk},
-            Self::Unresolved{pos, defer_index, margin, other_axis}=>{
+            Self::UnresolvedCell(walk)=>{
+                let turtle = cx.turtles.last().unwrap();
+                let cell = turtle.grid_cell_rect(&turtle.grid_place(walk.cell));
+                let size = turtle.grid_walk_size(walk, cell);
+                let walk = Walk {
+                    width: if walk.width.is_fill() {Size::Fixed(size.x)} else {walk.width},
+                    height: if walk.height.is_fill() {Size::Fixed(size.y)} else {walk.height},
+                    ..*walk
+                };
+                *self = DeferWalk::Resolved(walk);
+                walk
+            }
+            Self::Unresolved{pos, defer_index, walk}=>{
                 let turtle = cx.turtles.last().unwrap();
                 let walk = match turtle.layout.flow {
                     Flow::Right => {
-                        let left = turtle.width_left();
-                        let part = left / turtle.defer_count as f64;
+                        let sizes = turtle.defer_fill_sizes(turtle.width_left());
                         Walk {
-                            abs_pos: Some(*pos + dvec2(part * *defer_index as f64, 0.)),
-                            margin: *margin,
-                            width: Size::Fixed(part),
-                            height: *other_axis
+                            abs_pos: Some(*pos + dvec2(sizes[..*defer_index].iter().sum(), 0.)),
+                            width: Size::Fixed(sizes[*defer_index]),
+                            ..*walk
                         }
                     },
                     Flow::RightWrap => {
                         panic!()
                     }
                     Flow::Down => { 
-                        let left = turtle.height_left();
-                        let part = left / turtle.defer_count as f64;
+                        let sizes = turtle.defer_fill_sizes(turtle.height_left());
                         Walk {
-                            abs_pos: Some(*pos + dvec2(0., part * *defer_index as f64)),
-                            margin: *margin,
-                            height: Size::Fixed(part),
-                            width: *other_axis
+                            abs_pos: Some(*pos + dvec2(0., sizes[..*defer_index].iter().sum())),
+                            height: Size::Fixed(sizes[*defer_index]),
+                            ..*walk
                         }
                     }
-                    Flow::Overlay => panic!()
+                    Flow::Overlay | Flow::Grid => panic!()
                 };
                 *self = DeferWalk::Resolved(walk);
                 walk
@@ -1096,6 +1534,14 @@ impl Layout {
             ..Self::default()
         }
     }
+    
+    pub fn flow_grid(grid: GridLayout) -> Self {
+        Self {
+            flow: Flow::Grid,
+            grid,
+            ..Self::default()
+        }
+    }
 
     pub fn with_scroll(mut self, v: DVec2) -> Self {
         self.scroll = v;
@@ -1156,6 +1602,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fixed(0.0),
             height: Size::Fixed(0.0),
+            ..Self::default()
         }
     }
     
@@ -1165,6 +1612,7 @@ impl Walk {
             margin: Margin::default(),
             width: w,
             height: h,
+            ..Self::default()
         }
     }
 
@@ -1174,6 +1622,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fixed(rect.size.x),
             height: Size::Fixed(rect.size.y),
+            ..Self::default()
         }
     }
     
@@ -1183,6 +1632,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fixed(w),
             height: Size::Fixed(h),
+            ..Self::default()
         }
     }
         
@@ -1192,6 +1642,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fixed(size.x),
             height: Size::Fixed(size.y),
+            ..Self::default()
         }
     }
     
@@ -1201,6 +1652,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fit,
             height: Size::Fit,
+            ..Self::default()
         }
     }
     
@@ -1210,6 +1662,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fill,
             height: Size::Fill,
+            ..Self::default()
         }
     }
     
@@ -1219,6 +1672,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fill,
             height: Size::Fit,
+            ..Self::default()
         }
     }
     
@@ -1226,6 +1680,59 @@ impl Walk {
         self.abs_pos = Some(v);
         self
     }
+    
+    pub fn with_width_bounds(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.width_min = min;
+        self.width_max = max;
+        self
+    }
+    
+    pub fn with_height_bounds(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.height_min = min;
+        self.height_max = max;
+        self
+    }
+    
+    pub fn with_flex(mut self, v: f64) -> Self {
+        self.flex = v;
+        self
+    }
+    
+    pub fn with_aspect_ratio(mut self, v: f64) -> Self {
+        self.aspect_ratio = Some(v);
+        self
+    }
+    
+    /// Clamps a size evaluated for this walk to its bounds, the minimum wins over the maximum.
+    /// NaN axes fit their content and stay NaN.
+    pub fn clamp_size(&self, size: DVec2) -> DVec2 {
+        dvec2(
+            clamp_keep_nan(size.x, self.width_min, self.width_max),
+            clamp_keep_nan(size.y, self.height_min, self.height_max)
+        )
+    }
+    
+    /// Clamps a size evaluated for this walk and applies its aspect ratio.
+    pub fn constrain_size(&self, size: DVec2) -> DVec2 {
+        let size = self.clamp_size(size);
+        let ratio = match self.aspect_ratio {
+            Some(ratio) if ratio > 0.0 => ratio,
+            _ => return size
+        };
+        match (size.x.is_nan(), size.y.is_nan()) {
+            (false, true) => dvec2(size.x, clamp_keep_nan(size.x / ratio, self.height_min, self.height_max)),
+            (true, false) => dvec2(clamp_keep_nan(size.y * ratio, self.width_min, self.width_max), size.y),
+            (false, false) if size.x > size.y * ratio => dvec2(size.y * ratio, size.y),
+            (false, false) => dvec2(size.x, size.x / ratio),
+            (true, true) => size
+        }
+    }
+    
+    pub fn with_cell(mut self, v: GridCell) -> Self {
+        self.cell = v;
+        self
+    }
+    
     pub fn with_margin_all(mut self, v: f64) -> Self {
         self.margin = Margin {left: v, right: v, top: v, bottom: v};
         self
@@ -1299,6 +1806,90 @@ impl Default for Flow {
     fn default() -> Self {Self::Down}
 }
 
+impl LiveHook for GridTrack {
+    fn skip_apply(&mut self, _cx: &mut Cx, _apply: &mut Apply, index: usize, nodes: &[LiveNode]) -> Option<usize> {
+        match &nodes[index].value {
+            LiveValue::Float64(v) => {
+                *self = Self::Fixed(*v);
+                Some(index + 1)
+            }
+            LiveValue::Int64(v) => {
+                *self = Self::Fixed(*v as f64);
+                Some(index + 1)
+            }
+            _ => None
+        }
+    }
+}
+
+impl GridTracks {
+    /// Only the first `MAX_GRID_TRACKS` tracks are kept.
+    pub fn new(tracks: &[GridTrack]) -> Self {
+        let mut ret = Self::default();
+        for track in tracks.iter().take(MAX_GRID_TRACKS) {
+            ret.tracks[ret.len] = *track;
+            ret.len += 1;
+        }
+        ret
+    }
+    
+    /// The track at `index`, tracks past the defined ones fit their children.
+    pub fn get(&self, index: usize) -> GridTrack {
+        self.as_slice().get(index).copied().unwrap_or(GridTrack::Fit)
+    }
+    
+    pub fn as_slice(&self) -> &[GridTrack] {
+        &self.tracks[..self.len]
+    }
+    
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl LiveHook for GridTracks {}
+impl LiveApply for GridTracks {
+    fn apply(&mut self, cx: &mut Cx, apply: &mut Apply, index: usize, nodes: &[LiveNode]) -> usize {
+        if !nodes[index].is_array() {
+            cx.apply_error_expected_array(live_error_origin!(), index, nodes);
+            return nodes.skip_node(index)
+        }
+        *self = Self::default();
+        let mut index = index + 1;
+        while !nodes[index].is_close() {
+            if self.len < MAX_GRID_TRACKS {
+                index = self.tracks[self.len].apply(cx, apply, index, nodes);
+                self.len += 1;
+            }
+            else {
+                error!("a grid has at most {} columns and rows", MAX_GRID_TRACKS);
+                index = nodes.skip_node(index);
+            }
+        }
+        index + 1
+    }
+}
+
+impl LiveNew for GridTracks {
+    fn new(_cx: &mut Cx) -> Self {
+        Self::default()
+    }
+    
+    fn new_apply(cx: &mut Cx, apply: &mut Apply, index: usize, nodes: &[LiveNode]) -> Self {
+        let mut ret = Self::default();
+        ret.apply(cx, apply, index, nodes);
+        ret
+    }
+    
+    fn live_type_info(cx: &mut Cx) -> LiveTypeInfo {
+        GridTrack::live_type_info(cx)
+    }
+}
+
 
 impl LiveHook for Size {
     fn skip_apply(&mut self, cx: &mut Cx, _apply: &mut Apply, index: usize, nodes: &[LiveNode]) -> Option<usize> {
@@ -1397,6 +1988,18 @@ impl Size {
     }
 }
 
+fn clamp_keep_nan(v: f64, min: Option<f64>, max: Option<f64>) -> f64 {
+    if v.is_nan() {
+        return v
+    }
+    let v = max.map_or(v, | max | v.min(max));
+    min.map_or(v, | min | v.max(min))
+}
+
+fn nan_to_zero(v: f64) -> f64 {
+    if v.is_nan() {0.0} else {v}
+}
+
 fn max_zero_keep_nan(v: f64) -> f64 {
     if v.is_nan() {
         v
@@ -1405,3 +2008,67 @@ fn max_zero_keep_nan(v: f64) -> f64 {
         f64::max(v, 0.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill_sizes(fills: &[(f64, Option<f64>, Option<f64>)], left: f64) -> Vec<f64> {
+        let turtle = Turtle {
+            defer_fills: fills.iter().map( | (flex, min, max) | DeferFill {flex: *flex, min: *min, max: *max}).collect(),
+            ..Default::default()
+        };
+        turtle.defer_fill_sizes(left)
+    }
+
+    #[test]
+    fn fills_share_the_space_by_flex() {
+        assert_eq!(fill_sizes(&[(1.0, None, None), (2.0, None, None), (1.0, None, None)], 400.0), [100.0, 200.0, 100.0]);
+        // a child without flex gets nothing, and neither does anyone when nobody has flex
+        assert_eq!(fill_sizes(&[(0.0, None, None), (1.0, None, None)], 300.0), [0.0, 300.0]);
+        assert_eq!(fill_sizes(&[(0.0, None, None), (-1.0, None, None)], 300.0), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn clamped_fills_leave_the_rest_to_the_others() {
+        assert_eq!(fill_sizes(&[(1.0, None, Some(50.0)), (1.0, None, None)], 300.0), [50.0, 250.0]);
+        assert_eq!(fill_sizes(&[(1.0, Some(200.0), None), (1.0, None, None), (1.0, None, None)], 300.0), [200.0, 50.0, 50.0]);
+        // the first clamps down by more than the second clamps up, so only the first is final
+        // in the first round, and the second clamps up again in the next one
+        assert_eq!(
+            fill_sizes(&[(1.0, None, Some(20.0)), (1.0, Some(150.0), None), (1.0, None, None)], 300.0),
+            [20.0, 150.0, 130.0]
+        );
+        // when every child is clamped they can overflow or underfill
+        assert_eq!(fill_sizes(&[(1.0, Some(200.0), None), (1.0, Some(200.0), None)], 300.0), [200.0, 200.0]);
+        assert_eq!(fill_sizes(&[(1.0, None, Some(50.0)), (1.0, None, Some(50.0))], 300.0), [50.0, 50.0]);
+    }
+
+    #[test]
+    fn fills_without_space_left_keep_their_minimum() {
+        for left in [0.0, -20.0, f64::NAN] {
+            assert_eq!(fill_sizes(&[(1.0, None, None), (1.0, Some(30.0), None)], left), [0.0, 30.0]);
+        }
+    }
+
+    #[test]
+    fn walks_are_clamped_and_keep_their_aspect_ratio() {
+        let walk = Walk::fit().with_width_bounds(Some(20.0), Some(200.0));
+        assert_eq!(walk.constrain_size(dvec2(300.0, 10.0)), dvec2(200.0, 10.0));
+        assert_eq!(walk.constrain_size(dvec2(10.0, 10.0)), dvec2(20.0, 10.0));
+
+        // the axis that fits its content follows the other one
+        let walk = Walk::fit().with_aspect_ratio(2.0);
+        assert_eq!(walk.constrain_size(dvec2(100.0, f64::NAN)), dvec2(100.0, 50.0));
+        assert_eq!(walk.constrain_size(dvec2(f64::NAN, 40.0)), dvec2(80.0, 40.0));
+        // with both axes given the ratio fits inside them, with neither it can't apply
+        assert_eq!(walk.constrain_size(dvec2(100.0, 100.0)), dvec2(100.0, 50.0));
+        assert_eq!(walk.constrain_size(dvec2(100.0, 20.0)), dvec2(40.0, 20.0));
+        let size = walk.constrain_size(dvec2(f64::NAN, f64::NAN));
+        assert!(size.x.is_nan() && size.y.is_nan());
+
+        // the bounds of the derived axis still apply
+        let walk = walk.with_height_bounds(None, Some(30.0));
+        assert_eq!(walk.constrain_size(dvec2(100.0, f64::NAN)), dvec2(100.0, 30.0));
+    }
+}
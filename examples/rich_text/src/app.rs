@@ -0,0 +1,108 @@
+use makepad_widgets::*;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+
+    ToolButton = <Button> {
+        padding: {left: 8, right: 8, top: 4, bottom: 4}
+    }
+
+    App = {{App}} {
+        ui: <Window>{
+            show_bg: true
+            width: Fill,
+            height: Fill
+
+            body = <View>{
+                flow: Down,
+                spacing: 10,
+                padding: 10,
+
+                toolbar = <View>{
+                    width: Fill,
+                    height: Fit,
+                    flow: RightWrap,
+                    spacing: 4,
+                    bold = <ToolButton> {text: "Bold"}
+                    italic = <ToolButton> {text: "Italic"}
+                    underline = <ToolButton> {text: "Underline"}
+                    strikethrough = <ToolButton> {text: "Strike"}
+                    code = <ToolButton> {text: "Code"}
+                    link = <ToolButton> {text: "Link"}
+                    heading1 = <ToolButton> {text: "H1"}
+                    heading2 = <ToolButton> {text: "H2"}
+                    bullets = <ToolButton> {text: "List"}
+                    numbers = <ToolButton> {text: "Numbers"}
+                    quote = <ToolButton> {text: "Quote"}
+                    code_block = <ToolButton> {text: "Code block"}
+                    export_markdown = <ToolButton> {text: "As Markdown"}
+                    export_html = <ToolButton> {text: "As HTML"}
+                }
+
+                editor = <RichTextInput>{
+                    height: Fill,
+                    body: "# Notes\n\nSelect some text and use the toolbar, or **ctrl+b**, *ctrl+i* and ctrl+u.\n\n- copy and paste keep the styles\n- paste html from a browser"
+                }
+
+                exported = <Label>{
+                    width: Fill,
+                    draw_text: {wrap: Word}
+                    text: ""
+                }
+            }
+        }
+    }
+}
+
+app_main!(App);
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        crate::makepad_widgets::live_design(cx);
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions) {
+        let editor = self.ui.rich_text_input(id!(editor));
+        let formats = [
+            (id!(bold), RichFormat::Bold),
+            (id!(italic), RichFormat::Italic),
+            (id!(underline), RichFormat::Underline),
+            (id!(strikethrough), RichFormat::Strikethrough),
+            (id!(code), RichFormat::Code),
+            (id!(link), RichFormat::Link(Some("https://makepad.dev".to_string()))),
+            (id!(heading1), RichFormat::Block(RichBlockKind::Heading(1))),
+            (id!(heading2), RichFormat::Block(RichBlockKind::Heading(2))),
+            (id!(bullets), RichFormat::Block(RichBlockKind::Bullet)),
+            (id!(numbers), RichFormat::Block(RichBlockKind::Numbered)),
+            (id!(quote), RichFormat::Block(RichBlockKind::Quote)),
+            (id!(code_block), RichFormat::Block(RichBlockKind::Code)),
+        ];
+        for (button, format) in formats {
+            if self.ui.button(button).clicked(actions) {
+                editor.apply_format(cx, format);
+            }
+        }
+        let exported = self.ui.label(id!(exported));
+        if self.ui.button(id!(export_markdown)).clicked(actions) {
+            exported.set_text_and_redraw(cx, &editor.to_markdown());
+        }
+        if self.ui.button(id!(export_html)).clicked(actions) {
+            exported.set_text_and_redraw(cx, &editor.to_html());
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
@@ -12,6 +12,7 @@ pub mod live_registry;
 pub mod live_expander;
 pub mod live_ptr;
 pub mod live_eval;
+pub mod live_env;
 pub mod live_component;
 pub mod live_node_cbor;
 //pub mod live_node_cbor;
@@ -38,6 +39,7 @@ pub use {
         live_eval::{
             live_eval_value,
         },
+        live_env::LiveEnv,
         live_registry::{
             LiveFileChange,
             LiveRegistry,
@@ -4,6 +4,7 @@ use {
         span::{TextPos, TextSpan},
         live_token::{TokenWithSpan,LiveTokenId},
         live_node::LiveNode,
+        live_node::LiveValue,
         live_node::LiveDesignInfo,
     }
 };
@@ -19,12 +20,15 @@ pub struct LiveOriginal {
 #[derive(Default)]
 pub struct LiveExpanded {
     pub nodes: Vec<LiveNode >,
+    // expressions in the original that read from the live env, with the value they had at expansion
+    pub env_exprs: Vec<(usize, LiveValue)>,
 }
 
 impl LiveExpanded {
     pub fn new() -> Self {
         Self {
             nodes: Vec::new(),
+            env_exprs: Vec::new(),
         }
     }
 
@@ -0,0 +1,147 @@
+use {
+    std::cell::Cell,
+    crate::{
+        makepad_live_id::*,
+        live_node::LiveValue,
+    }
+};
+
+// The environment that responsive expressions and if-blocks in the DSL read from.
+// For instance:
+//
+//     flow: (window_width < 600.0 ? Down : Right)
+//     if (is_mobile || window_width < 600.0) {
+//         flow: Down
+//         padding: 5
+//     }
+//
+// window_width, window_height and dpi_factor follow the oldest window that is still
+// open, other windows don't drive the env.
+// Overrides replace the value of top level definitions of the same name in any file,
+// a theme uses them to change a few of its constants without copying the rest.
+// Any expression that reads from the env is remembered by the expander
+// so that when the env changes we only reexpand the files whose results flipped.
+
+pub struct LiveEnv {
+    values: LiveIdMap<LiveId, LiveValue>,
+    overrides: LiveIdMap<LiveId, LiveValue>,
+    changed_overrides: Vec<LiveId>,
+    accessed: Cell<bool>,
+    dirty: bool,
+}
+
+impl Default for LiveEnv {
+    fn default() -> Self {
+        let mut env = Self {
+            values: Default::default(),
+            overrides: Default::default(),
+            changed_overrides: Vec::new(),
+            accessed: Cell::new(false),
+            dirty: false,
+        };
+        env.set(live_id!(window_width), LiveValue::Float64(0.0));
+        env.set(live_id!(window_height), LiveValue::Float64(0.0));
+        env.set(live_id!(dpi_factor), LiveValue::Float64(1.0));
+        env.set(live_id!(dark_mode), LiveValue::Bool(true));
+        env.set(live_id!(high_contrast), LiveValue::Bool(false));
+        env.set(live_id!(os_windows), LiveValue::Bool(cfg!(target_os = "windows")));
+        env.set(live_id!(os_macos), LiveValue::Bool(cfg!(target_os = "macos")));
+        env.set(live_id!(os_linux), LiveValue::Bool(cfg!(all(target_os = "linux", not(target_env = "ohos")))));
+        env.set(live_id!(os_ios), LiveValue::Bool(cfg!(target_os = "ios")));
+        env.set(live_id!(os_android), LiveValue::Bool(cfg!(target_os = "android")));
+        env.set(live_id!(os_open_harmony), LiveValue::Bool(cfg!(target_env = "ohos")));
+        env.set(live_id!(os_web), LiveValue::Bool(cfg!(target_arch = "wasm32")));
+        let is_mobile = cfg!(any(target_os = "ios", target_os = "android", target_env = "ohos"));
+        env.set(live_id!(is_mobile), LiveValue::Bool(is_mobile));
+        env.set(live_id!(is_desktop), LiveValue::Bool(!is_mobile));
+        env.dirty = false;
+        env
+    }
+}
+
+impl LiveEnv {
+    pub fn is_env_id(&self, id: LiveId) -> bool {
+        self.values.get(&id).is_some()
+    }
+
+    pub fn get(&self, id: LiveId) -> Option<LiveValue> {
+        if let Some(value) = self.values.get(&id) {
+            self.accessed.set(true);
+            return Some(value.clone())
+        }
+        None
+    }
+
+    // returns true if the value changed
+    pub fn set(&mut self, id: LiveId, value: LiveValue) -> bool {
+        if let Some(old) = self.values.get(&id) {
+            if *old == value {
+                return false
+            }
+        }
+        self.values.insert(id, value);
+        self.dirty = true;
+        true
+    }
+
+    pub fn set_window_size(&mut self, width: f64, height: f64, dpi_factor: f64) -> bool {
+        let mut changed = false;
+        changed |= self.set(live_id!(window_width), LiveValue::Float64(width));
+        changed |= self.set(live_id!(window_height), LiveValue::Float64(height));
+        changed |= self.set(live_id!(dpi_factor), LiveValue::Float64(dpi_factor));
+        changed
+    }
+
+    pub fn set_dark_mode(&mut self, dark_mode: bool) -> bool {
+        self.set(live_id!(dark_mode), LiveValue::Bool(dark_mode))
+    }
+
+    pub fn set_high_contrast(&mut self, high_contrast: bool) -> bool {
+        self.set(live_id!(high_contrast), LiveValue::Bool(high_contrast))
+    }
+
+    // replaces all overrides, returns true if any of them changed
+    pub fn set_overrides(&mut self, overrides: impl IntoIterator<Item = (LiveId, LiveValue)>) -> bool {
+        let mut next: LiveIdMap<LiveId, LiveValue> = Default::default();
+        for (id, value) in overrides {
+            next.insert(id, value);
+        }
+        for (id, value) in next.iter() {
+            if self.overrides.get(id) != Some(value) {
+                self.changed_overrides.push(*id);
+            }
+        }
+        for id in self.overrides.keys() {
+            if next.get(id).is_none() {
+                self.changed_overrides.push(*id);
+            }
+        }
+        self.overrides = next;
+        if self.changed_overrides.is_empty() {
+            return false
+        }
+        self.dirty = true;
+        true
+    }
+
+    pub fn get_override(&self, id: LiveId) -> Option<&LiveValue> {
+        self.overrides.get(&id)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub(crate) fn clear_dirty(&mut self) -> Vec<LiveId> {
+        self.dirty = false;
+        std::mem::take(&mut self.changed_overrides)
+    }
+
+    pub(crate) fn clear_accessed(&self) {
+        self.accessed.set(false);
+    }
+
+    pub(crate) fn was_accessed(&self) -> bool {
+        self.accessed.get()
+    }
+}
@@ -56,6 +56,23 @@ impl LiveError {
     }
 }
 
+// the branches of a conditional expression can also be bare enums like Down or Fill
+fn live_eval_cond_branch(live_registry: &LiveRegistry, index: &mut usize, nodes: &[LiveNode], scope_nodes: &[LiveNode]) -> Result<LiveValue,LiveError> {
+    let start = *index;
+    match live_eval_value(live_registry, index, nodes, scope_nodes) {
+        Err(err) => {
+            if let LiveValue::Id(id) = nodes[start].value {
+                if id.as_string(|v| v.and_then(|v| v.chars().next()).map_or(false, char::is_uppercase)) {
+                    *index = start + 1;
+                    return Ok(LiveValue::BareEnum(id))
+                }
+            }
+            Err(err)
+        }
+        result => result
+    }
+}
+
 pub fn live_eval_value(live_registry: &LiveRegistry, index: &mut usize, nodes: &[LiveNode], scope_nodes: &[LiveNode]) -> Result<LiveValue,LiveError> {
     let v = &nodes[*index].value;
     Ok(match v {
@@ -117,6 +134,9 @@ pub fn live_eval_value(live_registry: &LiveRegistry, index: &mut usize, nodes: &
                     }
                 }
             }
+            if let Some(value) = live_registry.env.get(*id){
+                return Ok(value)
+            }
             return Err(LiveError::eval_error_cant_find_target(live_error_origin!(), *index, nodes, *id))
         },
         LiveValue::ExprUnOp(op) => {
@@ -147,6 +167,14 @@ pub fn live_eval_value(live_registry: &LiveRegistry, index: &mut usize, nodes: &
                         }
                     }
                 }
+                live_id!(cond) if *args == 3 => {
+                    let c = live_eval_value(live_registry, index, nodes, scope_nodes)?;
+                    let a = live_eval_cond_branch(live_registry, index, nodes, scope_nodes)?;
+                    let b = live_eval_cond_branch(live_registry, index, nodes, scope_nodes)?;
+                    if let LiveValue::Bool(vc) = c {
+                        return Ok(if vc {a} else {b})
+                    }
+                }
                 live_id!(blend) if *args == 2 => {
                     let a = live_eval_value(live_registry, index, nodes, scope_nodes)?;
                     let b = live_eval_value(live_registry, index, nodes, scope_nodes)?;
@@ -45,6 +45,19 @@ impl<'a> LiveExpander<'a> {
         }
     }
     
+    // evaluates an expression and remembers its result if it read from the live env
+    fn eval_tracked(&mut self, index: &mut usize, in_doc: &LiveOriginal, out_doc: &mut LiveExpanded) -> Result<LiveValue, LiveError> {
+        let start = *index;
+        self.live_registry.env.clear_accessed();
+        let result = live_eval_value(self.live_registry, index, &in_doc.nodes, &out_doc.nodes);
+        if self.live_registry.env.was_accessed() {
+            if let Ok(v) = &result {
+                out_doc.env_exprs.push((start, v.clone()));
+            }
+        }
+        result
+    }
+    
     pub fn expand(&mut self, in_doc: &LiveOriginal, out_doc: &mut LiveExpanded, generation: LiveFileGeneration) {
          
         //out_doc.nodes.push(in_doc.nodes[0].clone());
@@ -54,6 +67,8 @@ impl<'a> LiveExpander<'a> {
             value: LiveValue::Root {id_resolve: Box::default()}
         });
         let mut current_parent = vec![(LiveId(0), 0usize)];
+        // the result of the last if-block per parent depth, used by a following else-block
+        let mut last_if_result: Vec<bool> = Vec::new();
         let mut in_index = 1;
         let mut lazy_define_value = None;
         loop {
@@ -75,9 +90,50 @@ impl<'a> LiveExpander<'a> {
                 
                 LiveValue::Close => {
                     current_parent.pop();
+                    last_if_result.truncate(current_parent.len());
                     in_index += 1;
                     continue;
                 }
+                LiveValue::Object if in_node.id == live_id!(if) && in_node.origin.prop_type() == LivePropType::Nameless => {
+                    // conditional block, its children are applied to the current parent when the condition holds
+                    let mut index = in_index + 1;
+                    let cond = match self.eval_tracked(&mut index, in_doc, out_doc) {
+                        Ok(LiveValue::Bool(cond)) => cond,
+                        Ok(v) => {
+                            self.errors.push(LiveError {
+                                origin: live_error_origin!(),
+                                span: in_doc.token_id_to_span(in_node.origin.token_id().unwrap()).into(),
+                                message: format!("Condition of if block should be a bool, got {:?}", v)
+                            });
+                            false
+                        }
+                        Err(e) => {
+                            self.errors.push(e);
+                            false
+                        }
+                    };
+                    last_if_result.resize(current_parent.len(), false);
+                    last_if_result[current_parent.len() - 1] = cond;
+                    if cond {
+                        current_parent.push(*current_parent.last().unwrap());
+                        in_index = in_doc.nodes.skip_node(in_index + 1);
+                    }
+                    else {
+                        in_index = in_doc.nodes.skip_node(in_index);
+                    }
+                    continue;
+                }
+                LiveValue::Object if in_node.id == live_id!(else) && in_node.origin.prop_type() == LivePropType::Nameless => {
+                    last_if_result.resize(current_parent.len(), false);
+                    if !last_if_result[current_parent.len() - 1] {
+                        current_parent.push(*current_parent.last().unwrap());
+                        in_index += 1;
+                    }
+                    else {
+                        in_index = in_doc.nodes.skip_node(in_index);
+                    }
+                    continue;
+                }
                 LiveValue::Import(live_import) => {
                     // lets verify it points anywhere
                     let mut found = false;
@@ -119,10 +175,18 @@ impl<'a> LiveExpander<'a> {
                 _ => ()
             }
             
+            // a top level definition with an override in the env takes the value of the override
+            let mut root_override = if current_parent.last().unwrap().1 == 0 && (in_value.is_expr() || !in_value.is_open()) {
+                self.live_registry.env.get_override(in_node.id).cloned()
+            }
+            else {
+                None
+            };
+            
             //// determine node overwrite rules
             let out_index = match out_doc.nodes.child_or_append_index_by_name(current_parent.last().unwrap().1, in_node.prop()) {
                 Ok(overwrite) => {
-                    if current_parent.len() == 1 {
+                    if current_parent.last().unwrap().1 == 0 {
                         lazy_define_value = Some((in_node.id, LiveScopeTarget::LocalPtr(overwrite)));
                     }
                     let out_value = &out_doc.nodes[overwrite].value;
@@ -143,14 +207,18 @@ impl<'a> LiveExpander<'a> {
                     if in_value.is_object() && (out_value.is_clone() || out_value.is_class() || out_value.is_object()) { // lets set the target ptr
                         // do nothing
                     }
-                    else if in_value.is_expr(){
+                    else if in_value.is_expr() || root_override.is_some(){
                         
                         if !out_value.is_single_node(){
                             panic!("overriding is_expr on not is_single_node ");
                         }
                         // lets expand it and output a single LiveValue instead
                         let mut index = in_index;
-                        match live_eval_value(&self.live_registry, &mut index, &in_doc.nodes, &out_doc.nodes){
+                        let result = match root_override.take() {
+                            Some(v) => Ok(v),
+                            None => self.eval_tracked(&mut index, in_doc, out_doc)
+                        };
+                        match result{
                             Ok(v)=>{
                                 out_doc.nodes[overwrite] = in_node.clone();
                                 out_doc.nodes[overwrite].value = v;
@@ -211,18 +279,21 @@ impl<'a> LiveExpander<'a> {
                     overwrite
                 }
                 Err(insert_point) => {
-                    if current_parent.len() == 1 {
+                    if current_parent.last().unwrap().1 == 0 {
                         lazy_define_value = Some((in_node.id, LiveScopeTarget::LocalPtr(insert_point)));
                     }
                     
                     // ok so. if we are inserting an expression, just do the whole thing in one go.
-                    if in_node.is_expr() {
+                    if in_node.is_expr() || root_override.is_some() {
                         
                         // lets eval the expression
                         let mut index = in_index;
                         let old_len = out_doc.nodes.len();
-                                                
-                        match live_eval_value(&self.live_registry, &mut index, &in_doc.nodes, &out_doc.nodes){
+                        let result = match root_override.take() {
+                            Some(v) => Ok(v),
+                            None => self.eval_tracked(&mut index, in_doc, out_doc)
+                        };
+                        match result{
                             Ok(v)=>{
                                 out_doc.nodes.insert(insert_point, in_node.clone());
                                 out_doc.nodes[insert_point].value = v; 
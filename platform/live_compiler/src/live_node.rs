@@ -643,6 +643,15 @@ impl LiveValue {
             _ => None
         }
     }    
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(v) => Some(v),
+            Self::String(v) => Some(v.as_str()),
+            Self::InlineString(v) => Some(v.as_str()),
+            _ => None
+        }
+    }
+
     pub fn as_vec2(&self) -> Option<Vec2> {
         match self {
             Self::Vec2(v) => Some(*v),
@@ -886,6 +886,9 @@ impl<'a> LiveParser<'a> {
                     nameless_id += 1;
                     self.expect_live_class(false, prop_id, ld) ?;
                 }
+                LiveToken::Ident(live_id!(if)) => {
+                    self.expect_if_block(ld) ?;
+                }
                 LiveToken::Ident(prop_id) => {
                     let token_id = self.get_token_id();
                     self.skip_token();
@@ -941,6 +944,49 @@ impl<'a> LiveParser<'a> {
         Err(self.error("Eof in class body".to_string(), live_error_origin!()))
     }
     
+    // if (cond) {...} else if (cond) {...} else {...}
+    // the condition is stored as an expression child named `if`, else if is nested as an if inside the else block
+    fn expect_if_block(&mut self, ld: &mut LiveOriginal) -> Result<(), LiveError> {
+        let token_id = self.get_token_id();
+        self.expect_token(LiveToken::Ident(live_id!(if))) ?;
+        if self.peek_token() != LiveToken::Open(Delim::Paren) {
+            return Err(self.error("Expected ( after if".to_string(), live_error_origin!()))
+        }
+        ld.nodes.push(LiveNode {
+            origin: LiveNodeOrigin::from_token_id(token_id).with_prop_type(LivePropType::Nameless),
+            id: live_id!(if),
+            value: LiveValue::Object
+        });
+        let origin = LiveNodeOrigin::from_token_id(self.get_token_id()).with_prop_type(LivePropType::Nameless);
+        self.expect_expression(live_id!(if), origin, ld) ?;
+        self.expect_token(LiveToken::Open(Delim::Brace)) ?;
+        self.expect_live_class(false, live_id!(if), ld) ?;
+        
+        if self.peek_token() == LiveToken::Ident(live_id!(else)) {
+            let token_id = self.get_token_id();
+            self.skip_token();
+            ld.nodes.push(LiveNode {
+                origin: LiveNodeOrigin::from_token_id(token_id).with_prop_type(LivePropType::Nameless),
+                id: live_id!(else),
+                value: LiveValue::Object
+            });
+            if self.peek_token() == LiveToken::Ident(live_id!(if)) {
+                self.expect_if_block(ld) ?;
+                ld.nodes.push(LiveNode {
+                    origin: LiveNodeOrigin::from_token_id(self.get_token_id()),
+                    id: live_id!(else),
+                    value: LiveValue::Close
+                });
+            }
+            else {
+                self.expect_token(LiveToken::Open(Delim::Brace)) ?;
+                self.expect_live_class(false, live_id!(else), ld) ?;
+            }
+        }
+        self.accept_optional_delim();
+        Ok(())
+    }
+    
     pub fn expect_prop_type(&mut self)->Result<LivePropType, LiveError>{
         Ok(if self.accept_token(LiveToken::Punct(live_id!(:))){
             LivePropType::Field
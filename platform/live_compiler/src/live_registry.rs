@@ -15,6 +15,8 @@ use {
         live_token::{LiveToken, LiveTokenId, TokenWithSpan},
         span::{TextSpan, TextPos},
         live_expander::{LiveExpander},
+        live_env::LiveEnv,
+        live_eval::live_eval_value,
         live_component::{LiveComponentRegistries}
     }
 };
@@ -46,7 +48,8 @@ pub struct LiveRegistry {
     //pub ignore_no_dsl: HashSet<LiveId>,
     pub main_module: Option<LiveTypeInfo>,
     pub components: LiveComponentRegistries,
-    pub package_root: Option<String>
+    pub package_root: Option<String>,
+    pub env: LiveEnv,
 }
 
 impl Default for LiveRegistry {
@@ -58,7 +61,8 @@ impl Default for LiveRegistry {
             live_files: Vec::new(),
             live_type_infos: Default::default(),
             components: LiveComponentRegistries::default(),
-            package_root: None
+            package_root: None,
+            env: LiveEnv::default(),
         }
     }
 }
@@ -721,6 +725,47 @@ impl LiveRegistry {
         }
     }
 
+    // call after changing the env, reexpands only the files where an env dependent expression changed value
+    pub fn process_env_changes(&mut self, errors:&mut Vec<LiveError >) -> bool {
+        let changed_overrides = self.env.clear_dirty();
+        let mut any_changes = false;
+        for i in 0..self.live_files.len() {
+            let live_file = &self.live_files[i];
+            let changed = live_file.expanded.env_exprs.iter().any(|(index, old_value)|{
+                let mut index = *index;
+                match live_eval_value(self, &mut index, &live_file.original.nodes, &live_file.expanded.nodes) {
+                    Ok(value) => value != *old_value,
+                    Err(_) => true
+                }
+            }) || Self::defines_any(&live_file.expanded.nodes, &changed_overrides);
+            if changed {
+                let live_file = &mut self.live_files[i];
+                live_file.reexpand = true;
+                live_file.generation.next_gen();
+                any_changes = true;
+            }
+        }
+        if any_changes {
+            self.expand_all_documents(errors);
+        }
+        any_changes
+    }
+
+    // true if any of the ids is a top level definition in the nodes
+    fn defines_any(nodes: &[LiveNode], ids: &[LiveId]) -> bool {
+        if ids.is_empty() || nodes.is_empty() {
+            return false
+        }
+        let mut node_iter = nodes.first_child(0);
+        while let Some(index) = node_iter {
+            if ids.contains(&nodes[index].id) {
+                return true
+            }
+            node_iter = nodes.next_child(index);
+        }
+        false
+    }
+
     pub fn register_live_file(
         &mut self,
         file_name: &str,
@@ -900,6 +945,7 @@ impl LiveRegistry {
             std::mem::swap(&mut out_doc, &mut self.live_files[file_id.to_index()].expanded);
             
             out_doc.nodes.clear();
+            out_doc.env_exprs.clear();
             
             let in_doc = &self.live_files[file_id.to_index()].original;
             
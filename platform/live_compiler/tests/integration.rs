@@ -1,4 +1,130 @@
+use makepad_live_compiler::*;
+use makepad_live_compiler::makepad_live_id::*;
+
 #[test]
 fn main() {
     // todo :)
-}
\ No newline at end of file
+}
+
+fn expand_with_env(source: &str, env: impl FnOnce(&mut LiveEnv)) -> (LiveRegistry, LiveModuleId) {
+    let (live_registry, module_id, errors) = expand_with_errors(source, env);
+    assert!(errors.is_empty(), "{:?}", errors);
+    (live_registry, module_id)
+}
+
+fn expand_with_errors(source: &str, env: impl FnOnce(&mut LiveEnv)) -> (LiveRegistry, LiveModuleId, Vec<LiveError>) {
+    let mut live_registry = LiveRegistry::default();
+    env(&mut live_registry.env);
+    let module_id = LiveModuleId(live_id!(test), live_id!(responsive));
+    if live_registry.register_live_file("test.rs", "", module_id, source.to_string(), vec![], TextPos::default()).is_err() {
+        panic!("could not parse live file")
+    }
+    let mut errors = Vec::new();
+    live_registry.expand_all_documents(&mut errors);
+    (live_registry, module_id, errors)
+}
+
+fn value_of(live_registry: &LiveRegistry, module_id: LiveModuleId, path: &[LiveId]) -> LiveValue {
+    let nodes = live_registry.module_id_to_expanded_nodes(module_id).unwrap();
+    let mut index = nodes.child_by_name(0, path[0].as_instance()).unwrap();
+    for id in &path[1..] {
+        index = nodes.child_by_name(index, id.as_field()).unwrap();
+    }
+    nodes[index].value.clone()
+}
+
+const RESPONSIVE: &str = r#"
+    Panel = {
+        flow: Right
+        width: (window_width < 600.0 ? 100.0 : 300.0)
+        if (window_width < 600.0) {
+            flow: Down
+        }
+        else if (is_mobile) {
+            flow: Overlay
+        }
+        else {
+            padding: 10.0
+        }
+    }
+"#;
+
+#[test]
+fn responsive_if_blocks() {
+    let (live_registry, module_id) = expand_with_env(RESPONSIVE, |env| {
+        env.set_window_size(400.0, 800.0, 1.0);
+    });
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(flow)]), LiveValue::BareEnum(live_id!(Down)));
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(width)]), LiveValue::Float64(100.0));
+    
+    let (live_registry, module_id) = expand_with_env(RESPONSIVE, |env| {
+        env.set_window_size(1200.0, 800.0, 1.0);
+        env.set(live_id!(is_mobile), LiveValue::Bool(false));
+    });
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(flow)]), LiveValue::BareEnum(live_id!(Right)));
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(padding)]), LiveValue::Float64(10.0));
+}
+
+#[test]
+fn responsive_env_change_reexpands() {
+    let (mut live_registry, module_id) = expand_with_env(RESPONSIVE, |env| {
+        env.set_window_size(1200.0, 800.0, 1.0);
+    });
+    let mut errors = Vec::new();
+    // a change that doesnt flip any expression leaves the documents alone
+    live_registry.env.set_window_size(1000.0, 800.0, 1.0);
+    assert!(!live_registry.process_env_changes(&mut errors));
+    
+    live_registry.env.set_window_size(500.0, 800.0, 1.0);
+    assert!(live_registry.process_env_changes(&mut errors));
+    assert!(errors.is_empty());
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(flow)]), LiveValue::BareEnum(live_id!(Down)));
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(width)]), LiveValue::Float64(100.0));
+}
+
+#[test]
+fn bare_enums_only_in_conditional_branches() {
+    let (live_registry, module_id) = expand_with_env("Panel = {flow: (window_width < 600.0 ? Down : Right)}", |env| {
+        env.set_window_size(1200.0, 800.0, 1.0);
+    });
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(flow)]), LiveValue::BareEnum(live_id!(Right)));
+    
+    // a misspelled reference elsewhere is still an error
+    let (_, _, errors) = expand_with_errors("Panel = {width: (Widht * 2.0)}", |_| {});
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].message.contains("cant find target"), "{:?}", errors);
+}
+
+const THEMED: &str = r#"
+    SIZE = 10.0
+    BIG = (SIZE * 2.0)
+    Panel = {width: (BIG), height: (dark_mode ? SIZE : BIG)}
+"#;
+
+#[test]
+fn overrides_replace_top_level_definitions() {
+    let (mut live_registry, module_id) = expand_with_env(THEMED, |_| {});
+    let mut errors = Vec::new();
+    live_registry.env.set_overrides([(live_id!(SIZE), LiveValue::Float64(4.0))]);
+    assert!(live_registry.process_env_changes(&mut errors));
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(SIZE)]), LiveValue::Float64(4.0));
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(width)]), LiveValue::Float64(8.0));
+    
+    // expressions can be overridden as well
+    live_registry.env.set_overrides([(live_id!(BIG), LiveValue::Float64(50.0))]);
+    assert!(live_registry.process_env_changes(&mut errors));
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(SIZE)]), LiveValue::Float64(10.0));
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(width)]), LiveValue::Float64(50.0));
+    
+    // setting the same overrides again changes nothing
+    live_registry.env.set_overrides([(live_id!(BIG), LiveValue::Float64(50.0))]);
+    assert!(!live_registry.process_env_changes(&mut errors));
+    
+    // dropping them reexpands with the values of the file, the env still applies
+    live_registry.env.set_overrides([]);
+    live_registry.env.set_dark_mode(false);
+    assert!(live_registry.process_env_changes(&mut errors));
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(BIG)]), LiveValue::Float64(20.0));
+    assert_eq!(value_of(&live_registry, module_id, &[live_id!(Panel), live_id!(height)]), LiveValue::Float64(20.0));
+}
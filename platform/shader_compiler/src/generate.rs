@@ -77,6 +77,53 @@ pub trait BackendWriter {
     fn write_ty_lit(&self, string: &mut String, ty_lit: TyLit);
     fn write_builtin_call_ident(&self, string: &mut String, ident: Ident, arg_exprs: &[Expr]);
     
+    // the hooks below default to the C-like syntax shared by glsl, metal and hlsl
+    
+    fn write_fn_def_header(&self, string: &mut String, fn_name: &dyn fmt::Display, return_ty: &Ty) {
+        self.write_var_decl(string, "", false, false, fn_name, return_ty);
+        write!(string, "(").unwrap();
+    }
+    
+    fn write_fn_def_header_end(&self, string: &mut String, _return_ty: &Ty) {
+        write!(string, ")").unwrap();
+    }
+    
+    fn write_param_decl(&self, string: &mut String, sep: &'static str, is_inout: bool, ident: &dyn fmt::Display, ty: &Ty) -> bool {
+        self.write_var_decl(string, sep, is_inout, false, ident, ty)
+    }
+    
+    fn write_local_var_decl(&self, string: &mut String, ident: &dyn fmt::Display, ty: &Ty) {
+        self.write_var_decl(string, "", false, false, ident, ty);
+    }
+    
+    // if true, params are passed in as DisplayParamIn and copied into a mutable local
+    fn params_are_immutable(&self) -> bool {
+        false
+    }
+    
+    // if true, inout params are pointers which need explicit address-of and deref
+    fn inout_is_pointer(&self) -> bool {
+        false
+    }
+    
+    fn use_select_for_cond_expr(&self) -> bool {
+        false
+    }
+    
+    // if true, assignments are statements and can't be wrapped in parens
+    fn assign_is_statement(&self) -> bool {
+        false
+    }
+    
+    // builtins like lessThan and not that map onto an operator
+    fn builtin_as_operator(&self, _ident: Ident) -> Option<&'static str> {
+        None
+    }
+    
+    // builtins like clamp(vec3, float, float) that need their scalar args splatted to the vector type
+    fn builtin_splats_scalar_args(&self, _ident: Ident) -> bool {
+        false
+    }
 }
 
 pub struct BlockGenerator<'a> {
@@ -100,21 +147,21 @@ pub fn generate_cons_fn(backend_writer:&dyn BackendWriter, string: &mut String,
     if !backend_writer.use_cons_fn(&cons_name) {
         return
     }
-    backend_writer.write_ty_lit(string, ty_lit);
-    write!(string, " {}(", cons_name).unwrap();
+    backend_writer.write_fn_def_header(string, &cons_name, &ty_lit.to_ty());
     
     let mut sep = "";
     if param_tys.len() == 1 {
-        backend_writer.write_var_decl(string, sep, false, false, &Ident(live_id!(x)), &param_tys[0]);
+        backend_writer.write_param_decl(string, sep, false, &Ident(live_id!(x)), &param_tys[0]);
     } else {
         for (index, param_ty) in param_tys.iter().enumerate() {
             //write!(string, "{}", sep).unwrap();
-            backend_writer.write_var_decl(string, sep, false, false,&DisplaConstructorArg(index), param_ty);
+            backend_writer.write_param_decl(string, sep, false, &DisplaConstructorArg(index), param_ty);
             sep = ", ";
         }
     }
     
-    writeln!(string, ") {{").unwrap();
+    backend_writer.write_fn_def_header_end(string, &ty_lit.to_ty());
+    writeln!(string, " {{").unwrap();
     write!(string, "    return ").unwrap();
     backend_writer.write_ty_lit(string, ty_lit);
     write!(string, "(").unwrap();
@@ -185,6 +232,25 @@ pub fn generate_cons_fn(backend_writer:&dyn BackendWriter, string: &mut String,
 }
 
 impl<'a> BlockGenerator<'a> {
+    pub fn generate_fn_body(&mut self, param_copies: &[(DisplayVarName, Ty)], block: &Block) {
+        if param_copies.is_empty() {
+            return self.generate_block(block);
+        }
+        writeln!(self.string, "{{").unwrap();
+        self.indent_level += 1;
+        for (var_name, ty) in param_copies {
+            self.write_indent();
+            self.backend_writer.write_local_var_decl(self.string, var_name, ty);
+            writeln!(self.string, " = {};", DisplayParamIn(var_name.0, var_name.1)).unwrap();
+        }
+        self.write_indent();
+        self.generate_block(block);
+        writeln!(self.string).unwrap();
+        self.indent_level -= 1;
+        self.write_indent();
+        write!(self.string, "}}").unwrap();
+    }
+    
     pub fn generate_block(&mut self, block: &Block) {
         write!(self.string, "{{\n").unwrap();
         self.write_indent();
@@ -288,9 +354,11 @@ impl<'a> BlockGenerator<'a> {
         } else {
             -1
         };
+        write!(self.string, "for (").unwrap();
+        self.backend_writer.write_local_var_decl(self.string, &DisplayVarName(ident, ScopeSymShadow(0)), &Ty::Int);
         write!(
             self.string,
-            "for (int {0} = {1}; {0} {2} {3}; {0} {4} {5}) ",
+            " = {1}; {0} {2} {3}; {0} {4} {5}) ",
             &DisplayVarName(ident, ScopeSymShadow(0)),
             if from <= to {from} else {from - 1},
             if from <= to {"<"} else {">="},
@@ -361,11 +429,8 @@ impl<'a> BlockGenerator<'a> {
         expr: &Option<Expr>,
         shadow: &Cell<Option<ScopeSymShadow >>
     ) {
-        self.backend_writer.write_var_decl(
+        self.backend_writer.write_local_var_decl(
             &mut self.string,
-            "",
-            false, 
-            false,
             &DisplayVarName(ident, shadow.get().unwrap()),
             ty.borrow().as_ref().unwrap()
         );
@@ -569,6 +634,16 @@ impl<'a> ExprGenerator<'a> {
         expr_if_true: &Expr,
         expr_if_false: &Expr,
     ) {
+        if self.backend_writer.use_select_for_cond_expr() {
+            write!(self.string, "select(").unwrap();
+            self.generate_expr(expr_if_false);
+            write!(self.string, ", ").unwrap();
+            self.generate_expr(expr_if_true);
+            write!(self.string, ", ").unwrap();
+            self.generate_expr(expr);
+            write!(self.string, ")").unwrap();
+            return
+        }
         write!(self.string, "(").unwrap();
         self.generate_expr(expr);
         write!(self.string, " ? ").unwrap();
@@ -653,6 +728,23 @@ impl<'a> ExprGenerator<'a> {
             }
         }
         
+        let is_assign = matches!(op, BinOp::Assign | BinOp::AddAssign | BinOp::SubAssign | BinOp::MulAssign | BinOp::DivAssign);
+        if is_assign && self.backend_writer.assign_is_statement() {
+            // chained assignments like a = b = c are split into b = c; a = b
+            if let ExprKind::Bin {op: BinOp::Assign, left_expr: inner_left_expr, ..} = &right_expr.kind {
+                self.generate_expr(right_expr);
+                write!(self.string, "; ").unwrap();
+                self.generate_expr(left_expr);
+                write!(self.string, " {} ", op).unwrap();
+                self.generate_expr(inner_left_expr);
+                return
+            }
+            self.generate_expr(left_expr);
+            write!(self.string, " {} ", op).unwrap();
+            self.generate_expr(right_expr);
+            return
+        }
+        
         write!(self.string, "(").unwrap();
         self.generate_expr(left_expr);
         write!(self.string, " {} ", op).unwrap();
@@ -714,7 +806,7 @@ impl<'a> ExprGenerator<'a> {
             )).unwrap();
             
             let mut sep = "";
-            for arg_expr in arg_exprs {
+            for (arg_index, arg_expr) in arg_exprs.iter().enumerate() {
                 // check if the args is a closure, ifso skip it
                 match arg_expr.ty.borrow().as_ref().unwrap(){
                     Ty::ClosureDef(_)=>{
@@ -724,7 +816,7 @@ impl<'a> ExprGenerator<'a> {
                 }
                 
                 write!(self.string, "{}", sep).unwrap();
-                self.generate_expr(arg_expr);
+                self.generate_call_arg(fn_def, arg_index, arg_exprs.len(), arg_expr);
                 sep = ", ";
             }
             // and now the closed over values
@@ -751,9 +843,9 @@ impl<'a> ExprGenerator<'a> {
         else {
             write!(self.string, "{}_{} (", fn_def.fn_ptr, fn_def.ident).unwrap();
             let mut sep = "";
-            for arg_expr in arg_exprs {
+            for (arg_index, arg_expr) in arg_exprs.iter().enumerate() {
                 write!(self.string, "{}", sep).unwrap();
-                self.generate_expr(arg_expr);
+                self.generate_call_arg(fn_def, arg_index, arg_exprs.len(), arg_expr);
                 sep = ", ";
             }
 
@@ -763,6 +855,15 @@ impl<'a> ExprGenerator<'a> {
         }
     }
     
+    fn generate_call_arg(&mut self, fn_def: &FnDef, arg_index: usize, arg_count: usize, arg_expr: &Expr) {
+        // draw shader methods get called without their self arg
+        let param = &fn_def.params[arg_index + fn_def.params.len() - arg_count];
+        if param.is_inout && self.backend_writer.inout_is_pointer() {
+            write!(self.string, "&").unwrap();
+        }
+        self.generate_expr(arg_expr);
+    }
+    
     fn generate_field_expr(&mut self, _span: TokenSpan, expr: &Expr, field_ident: Ident, ty:&Ty) {
         match expr.ty.borrow().as_ref() {
             Some(Ty::DrawShader(_)) => {
@@ -823,15 +924,49 @@ impl<'a> ExprGenerator<'a> {
     
     fn generate_builtin_call_expr(&mut self, _span: TokenSpan, ident: Ident, arg_exprs: &[Expr]) {
         // lets create a fn name for this thing.
+        if let Some(op) = self.backend_writer.builtin_as_operator(ident) {
+            write!(self.string, "(").unwrap();
+            if arg_exprs.len() == 1 {
+                write!(self.string, "{}", op).unwrap();
+                self.generate_expr(&arg_exprs[0]);
+            }
+            else {
+                self.generate_expr(&arg_exprs[0]);
+                write!(self.string, " {} ", op).unwrap();
+                self.generate_expr(&arg_exprs[1]);
+            }
+            write!(self.string, ")").unwrap();
+            return
+        }
         
         self.backend_writer.write_builtin_call_ident(&mut self.string, ident, arg_exprs);
         
+        let splat_ty_lit = if self.backend_writer.builtin_splats_scalar_args(ident) {
+            arg_exprs.iter().find_map( | arg_expr | match arg_expr.ty.borrow().as_ref().unwrap() {
+                Ty::Vec2 => Some(TyLit::Vec2),
+                Ty::Vec3 => Some(TyLit::Vec3),
+                Ty::Vec4 => Some(TyLit::Vec4),
+                _ => None
+            })
+        }
+        else {
+            None
+        };
+        
         write!(self.string, "(").unwrap();
         let mut sep = "";
         for arg_expr in arg_exprs {
             write!(self.string, "{}", sep).unwrap();
             
-            self.generate_expr(arg_expr);
+            match (splat_ty_lit, arg_expr.ty.borrow().as_ref().unwrap()) {
+                (Some(ty_lit), Ty::Float) => {
+                    self.write_ty_lit(ty_lit);
+                    write!(self.string, "(").unwrap();
+                    self.generate_expr(arg_expr);
+                    write!(self.string, ")").unwrap();
+                }
+                _ => self.generate_expr(arg_expr)
+            }
             
             sep = ", ";
         }
@@ -925,11 +1060,13 @@ impl<'a> ExprGenerator<'a> {
     fn generate_var_expr(&mut self, _span: TokenSpan, kind: &Cell<Option<VarKind >>, _ty: &Option<Ty>) {
         // ok so we have a few varkinds
         match kind.get().unwrap() {
-            VarKind::Local {ident, shadow} => {
-                write!(self.string, "{}", DisplayVarName(ident, shadow)).unwrap();
-            }
-            VarKind::MutLocal {ident, shadow} => {
-                write!(self.string, "{}", DisplayVarName(ident, shadow)).unwrap();
+            VarKind::Local {ident, shadow} | VarKind::MutLocal {ident, shadow} => {
+                if self.is_inout_pointer_param(ident, shadow) {
+                    write!(self.string, "(*{})", DisplayVarName(ident, shadow)).unwrap();
+                }
+                else {
+                    write!(self.string, "{}", DisplayVarName(ident, shadow)).unwrap();
+                }
             }
             VarKind::LiveValue(value_node_ptr) => {
                 // this is a live value.. also prefix needed
@@ -939,6 +1076,18 @@ impl<'a> ExprGenerator<'a> {
         }
     }
     
+    fn is_inout_pointer_param(&self, ident: Ident, shadow: ScopeSymShadow) -> bool {
+        if !self.backend_writer.inout_is_pointer() {
+            return false
+        }
+        if let Some(fn_def) = self.fn_def {
+            return fn_def.params.iter().any( | param | {
+                param.is_inout && param.ident == ident && param.shadow.get() == Some(shadow)
+            })
+        }
+        false
+    }
+    
     fn generate_lit_expr(&mut self, _span: TokenSpan, lit: Lit) {
         write!(self.string, "{}", lit).unwrap();
     }
@@ -963,24 +1112,23 @@ pub struct FnDefGenerator<'a> {
 impl<'a> FnDefGenerator<'a> {
     pub fn generate_fn_def(&mut self) {
         
-        self.backend_writer.write_var_decl(
+        let return_ty = self.fn_def.return_ty.borrow();
+        self.backend_writer.write_fn_def_header(
             &mut self.string,
-            "",
-            false,
-            false,
             &DisplayFnName(self.fn_def.fn_ptr, self.fn_def.ident), // here we must expand IdentPath to something
-            self.fn_def.return_ty.borrow().as_ref().unwrap()
+            return_ty.as_ref().unwrap()
         );
-        write!(self.string, "(").unwrap();
         let mut sep = "";
+        let mut param_copies = Vec::new();
         for param in &self.fn_def.params {
             if !param.shadow.get().is_none() {
-                if self.backend_writer.write_var_decl(
+                if write_fn_def_param(
+                    self.backend_writer,
                     &mut self.string,
+                    &mut param_copies,
                     sep,
                     param.is_inout,
-                    false,
-                    &DisplayVarName(param.ident, param.shadow.get().unwrap()),
+                    DisplayVarName(param.ident, param.shadow.get().unwrap()),
                     param.ty_expr.ty.borrow().as_ref().unwrap(),
                 ) {
                     sep = ", ";
@@ -988,14 +1136,9 @@ impl<'a> FnDefGenerator<'a> {
             }
         }
         self.backend_writer.write_fn_def_hidden_params(self.string, self.fn_def.hidden_args.borrow().as_ref().unwrap(), sep);
-        write!(self.string, ") ").unwrap();
-        self.generate_block(&self.fn_def.block);
-        writeln!(self.string).unwrap();
-        //self.visited.insert(self.decl.ident_path);
-    }
-    
-    fn generate_block(&mut self, block: &Block) {
-        BlockGenerator {
+        self.backend_writer.write_fn_def_header_end(&mut self.string, return_ty.as_ref().unwrap());
+        write!(self.string, " ").unwrap();
+        let mut block_gen = BlockGenerator {
             shader_registry: self.shader_registry,
             closure_site_info: None,
             //env: self.env,
@@ -1004,9 +1147,32 @@ impl<'a> FnDefGenerator<'a> {
             const_table_offset: self.const_table_offset,
             indent_level: 0,
             string: self.string,
+        };
+        block_gen.generate_fn_body(&param_copies, &self.fn_def.block);
+        writeln!(self.string).unwrap();
+        //self.visited.insert(self.decl.ident_path);
+    }
+}
+
+// writes a single fn param, on backends with immutable params it is passed in under
+// another name and remembered so the body can copy it into a mutable local
+fn write_fn_def_param(
+    backend_writer: &dyn BackendWriter,
+    string: &mut String,
+    param_copies: &mut Vec<(DisplayVarName, Ty)>,
+    sep: &'static str,
+    is_inout: bool,
+    var_name: DisplayVarName,
+    ty: &Ty
+) -> bool {
+    if !is_inout && backend_writer.params_are_immutable() {
+        if backend_writer.write_param_decl(string, sep, false, &DisplayParamIn(var_name.0, var_name.1), ty) {
+            param_copies.push((var_name, ty.clone()));
+            return true
         }
-        .generate_block(block)
+        return false
     }
+    backend_writer.write_param_decl(string, sep, is_inout, &var_name, ty)
 }
 
 pub struct FnDefWithClosureArgsGenerator<'a> {
@@ -1080,28 +1246,27 @@ impl<'a> FnDefWithClosureArgsGenerator<'a> {
     
     pub fn generate_fn_def_with_closure_args(&mut self) {
         
-        self.backend_writer.write_var_decl(
+        let return_ty = self.fn_def.return_ty.borrow();
+        self.backend_writer.write_fn_def_header(
             &mut self.string,
-            "",
-            false,
-            false,
             &DisplayFnNameWithClosureArgs(
                 self.closure_site_info.site_index,
                 self.call_def.fn_ptr,
                 self.fn_def.ident
             ), // here we must expand IdentPath to something
-            self.fn_def.return_ty.borrow().as_ref().unwrap()
+            return_ty.as_ref().unwrap()
         );
-        write!(self.string, "(").unwrap();
         let mut sep = "";
+        let mut param_copies = Vec::new();
         for param in &self.fn_def.params {
             if !param.shadow.get().is_none() {
-                if self.backend_writer.write_var_decl(
+                if write_fn_def_param(
+                    self.backend_writer,
                     &mut self.string,
+                    &mut param_copies,
                     sep,
                     param.is_inout,
-                    false,
-                    &DisplayVarName(param.ident, param.shadow.get().unwrap()),
+                    DisplayVarName(param.ident, param.shadow.get().unwrap()),
                     param.ty_expr.ty.borrow().as_ref().unwrap(),
                 ) {
                     sep = ", ";
@@ -1111,11 +1276,10 @@ impl<'a> FnDefWithClosureArgsGenerator<'a> {
         // now we iterate over the closures in our site,
         // and we need to merge the set of closed over args.
         for sym in &self.closure_site_info.closure_site.all_closed_over {
-            if self.backend_writer.write_var_decl(
+            if self.backend_writer.write_param_decl(
                 &mut self.string,
                 sep,
                 false,
-                false,
                 &DisplayClosedOverArg(sym.ident, sym.shadow),
                 &sym.ty,
             ) {
@@ -1128,17 +1292,10 @@ impl<'a> FnDefWithClosureArgsGenerator<'a> {
         merged_hidden_args.extend(self.call_def.hidden_args.borrow().as_ref().unwrap().iter().cloned());
         self.backend_writer.write_fn_def_hidden_params(self.string, &merged_hidden_args, sep);
         
-        write!(self.string, ") ").unwrap();
+        self.backend_writer.write_fn_def_header_end(&mut self.string, return_ty.as_ref().unwrap());
+        write!(self.string, " ").unwrap();
         // alright so here the block is generated.. however
         // we need to know the names and the closed-over-args passthrough
-        self.generate_block(&self.fn_def.block);
-        
-        
-        writeln!(self.string).unwrap();
-        //self.visited.insert(self.decl.ident_path);
-    }
-    
-    fn generate_block(&mut self, block: &Block) {
         BlockGenerator {
             shader_registry: self.shader_registry,
             closure_site_info: Some(self.closure_site_info.clone()),
@@ -1149,7 +1306,10 @@ impl<'a> FnDefWithClosureArgsGenerator<'a> {
             indent_level: 0,
             string: self.string,
         }
-        .generate_block(block)
+        .generate_fn_body(&param_copies, &self.fn_def.block);
+        
+        writeln!(self.string).unwrap();
+        //self.visited.insert(self.decl.ident_path);
     }
 }
 
@@ -1171,46 +1331,46 @@ impl<'a> ClosureDefGenerator<'a> {
         
         let mut sep = "";
         
-        if let TyExprKind::ClosureDecl {params, return_ty, ..} = &fn_param.ty_expr.kind {
+        let mut param_copies = Vec::new();
+        let return_ty = if let TyExprKind::ClosureDecl {params, return_ty, ..} = &fn_param.ty_expr.kind {
             
-            self.backend_writer.write_var_decl(
+            self.backend_writer.write_fn_def_header(
                 &mut self.string,
-                "",
-                false,
-                false,
                 &DisplayClosureName(self.call_def.fn_ptr, self.closure_site_arg.closure_def_index), // here we must expand IdentPath to something
                 return_ty.borrow().as_ref().unwrap(),
             );
-            write!(self.string, "(").unwrap();
             
             // ok we have now params and names
             for (param_index, param) in params.iter().enumerate() {
                 // lets fetch the name of this thing
                 let closure_param = &self.closure_def.params[param_index];
                 let shadow = closure_param.shadow.get().unwrap();
-                if self.backend_writer.write_var_decl(
+                if write_fn_def_param(
+                    self.backend_writer,
                     &mut self.string,
+                    &mut param_copies,
                     sep,
                     param.is_inout,
-                    false,
-                    &DisplayVarName(closure_param.ident, shadow),
+                    DisplayVarName(closure_param.ident, shadow),
                     param.ty_expr.ty.borrow().as_ref().unwrap(),
                 ) {
                     sep = ", ";
                 }
             }
+            return_ty.borrow().clone().unwrap()
         }
         else {
             panic!()
-        }
+        };
         
         for sym in self.closure_def.closed_over_syms.borrow().as_ref().unwrap() {
-            if self.backend_writer.write_var_decl(
+            if write_fn_def_param(
+                self.backend_writer,
                 &mut self.string,
+                &mut param_copies,
                 sep,
                 false,
-                false,
-                &DisplayVarName(sym.ident, sym.shadow),
+                DisplayVarName(sym.ident, sym.shadow),
                 &sym.ty,
             ) {
                 sep = ", ";
@@ -1222,7 +1382,14 @@ impl<'a> ClosureDefGenerator<'a> {
         merged_hidden_args.extend(self.call_def.hidden_args.borrow().as_ref().unwrap().iter().cloned());
         self.backend_writer.write_fn_def_hidden_params(self.string, &merged_hidden_args, sep);
         
-        writeln!(self.string, ") {{").unwrap();
+        self.backend_writer.write_fn_def_header_end(&mut self.string, &return_ty);
+        writeln!(self.string, " {{").unwrap();
+        
+        for (var_name, ty) in &param_copies {
+            write!(self.string, "    ").unwrap();
+            self.backend_writer.write_local_var_decl(&mut self.string, var_name, ty);
+            writeln!(self.string, " = {};", DisplayParamIn(var_name.0, var_name.1)).unwrap();
+        }
         
         match &self.closure_def.kind {
             ClosureDefKind::Expr(expr) => {
@@ -1234,6 +1401,7 @@ impl<'a> ClosureDefGenerator<'a> {
             ClosureDefKind::Block(block) => {
                 self.generate_block(block);
                 writeln!(self.string).unwrap();
+                writeln!(self.string, "}}").unwrap();
             }
         }
         //self.visited.insert(self.decl.ident_path);
@@ -1324,6 +1492,14 @@ impl fmt::Display for DisplayVarName {
     }
 }
 
+pub struct DisplayParamIn(pub Ident, pub ScopeSymShadow);
+impl fmt::Display for DisplayParamIn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "in_{}_{}", self.0, self.1.0);
+        fmt::Result::Ok(())
+    }
+}
+
 pub struct DisplayClosedOverArg(pub Ident, pub ScopeSymShadow);
 impl fmt::Display for DisplayClosedOverArg {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -0,0 +1,718 @@
+use {
+    std::{
+        fmt::Write,
+        fmt,
+        collections::{BTreeMap, BTreeSet}
+    },
+    crate::{
+        makepad_live_compiler::*,
+        makepad_live_id::*,
+        shader_ast::*,
+        generate::*,
+        shader_registry::ShaderRegistry,
+    }
+};
+
+// WGSL has no way to pass the geometries/instances/varyings/uniforms around as hidden params
+// like the other backends do, so they all live at module scope. The bindings are laid out as:
+//
+//     @group(0) @binding(0)   LiveUniforms
+//     @group(0) @binding(1)   const table, as array<vec4<f32>, N>
+//     @group(0) @binding(2..) one binding per uniform block, in fields_as_uniform_blocks order
+//     @group(1) @binding(0..) the textures in field order, followed by the sampler
+//
+// The vertex attributes use @location 0.. for the geometries, followed by the instances
+// with mat3/mat4 split up in columns just like on metal.
+//
+// WGSL aligns vec3 and vec4 uniforms to 16 bytes, so the uniform buffers have to be
+// packed with DrawShaderInputPacking::UniformsWGSL rather than the tight GLSL layout.
+
+pub struct WgslGeneratedShader {
+    pub wgsl: String,
+    pub fields_as_uniform_blocks: BTreeMap<Ident, Vec<(usize, Ident) >>
+}
+
+pub const WGSL_UNIFORM_GROUP: usize = 0;
+pub const WGSL_TEXTURE_GROUP: usize = 1;
+pub const WGSL_LIVE_UNIFORMS_BINDING: usize = 0;
+pub const WGSL_CONST_TABLE_BINDING: usize = 1;
+pub const WGSL_UNIFORM_BLOCK_BINDING_BASE: usize = 2;
+
+pub fn generate_shader(draw_shader_def: &DrawShaderDef, const_table: &DrawShaderConstTable, shader_registry: &ShaderRegistry) -> WgslGeneratedShader {
+    let mut string = String::new();
+    let fields_as_uniform_blocks = draw_shader_def.fields_as_uniform_blocks();
+    DrawShaderGenerator {
+        draw_shader_def,
+        shader_registry,
+        const_table,
+        string: &mut string,
+        fields_as_uniform_blocks: &fields_as_uniform_blocks,
+        backend_writer: &WgslBackendWriter {shader_registry, draw_shader_def, const_table}
+    }
+    .generate_shader();
+    WgslGeneratedShader {
+        wgsl: string,
+        fields_as_uniform_blocks
+    }
+}
+
+struct DrawShaderGenerator<'a> {
+    draw_shader_def: &'a DrawShaderDef,
+    shader_registry: &'a ShaderRegistry,
+    string: &'a mut String,
+    fields_as_uniform_blocks: &'a BTreeMap<Ident, Vec<(usize, Ident) >>,
+    backend_writer: &'a WgslBackendWriter<'a>,
+    const_table: &'a DrawShaderConstTable
+}
+
+impl<'a> DrawShaderGenerator<'a> {
+    fn generate_shader(&mut self) {
+        let mut all_constructor_fns = BTreeSet::new();
+        let mut all_builtins = BTreeSet::new();
+        for fn_iter in self.draw_shader_def.all_fns.borrow().iter() {
+            let fn_def = self.shader_registry.all_fns.get(fn_iter).unwrap();
+            all_constructor_fns.extend(fn_def.constructor_fn_deps.borrow().as_ref().unwrap().iter().cloned());
+            all_builtins.extend(fn_def.builtin_deps.borrow().as_ref().unwrap().iter().cloned());
+        }
+
+        self.generate_struct_defs();
+        self.generate_uniforms();
+        self.generate_textures(&all_builtins);
+        self.generate_geometry_struct();
+        self.generate_instance_struct();
+        self.generate_varying_struct();
+        self.generate_builtin_helpers(&all_builtins);
+
+        for (ty_lit, ref param_tys) in all_constructor_fns {
+            generate_cons_fn(self.backend_writer, self.string, ty_lit, &param_tys);
+        }
+
+        let all_fns = self.draw_shader_def.all_fns.borrow();
+        for fn_iter in all_fns.iter().rev() {
+            let const_table_offset = self.const_table.offsets.get(fn_iter).cloned();
+            let fn_def = self.shader_registry.all_fns.get(fn_iter).unwrap();
+            if fn_def.has_closure_args() {
+                for call_iter in all_fns.iter().rev() {
+                    // any function that depends on us, will have the closures we need
+                    let call_def = self.shader_registry.all_fns.get(call_iter).unwrap();
+                    if call_def.callees.borrow().as_ref().unwrap().contains(&fn_iter) {
+                        FnDefWithClosureArgsGenerator::generate_fn_def_with_all_closures(
+                            &mut self.string,
+                            self.shader_registry,
+                            fn_def,
+                            call_def,
+                            self.backend_writer,
+                            const_table_offset
+                        );
+                    }
+                }
+                continue
+            }
+            FnDefGenerator {
+                fn_def,
+                const_table_offset,
+                shader_registry: self.shader_registry,
+                backend_writer: self.backend_writer,
+                string: self.string,
+            }
+            .generate_fn_def()
+        }
+        self.generate_vertex_main();
+        self.generate_pixel_main();
+    }
+
+    fn generate_struct_defs(&mut self) {
+        // we have all the structs already from analyse
+        for struct_ptr in self.draw_shader_def.all_structs.borrow().iter().rev() {
+            let struct_def = self.shader_registry.structs.get(struct_ptr).unwrap();
+            writeln!(self.string, "struct {} {{", struct_ptr).unwrap();
+            for field in &struct_def.fields {
+                write!(self.string, "    {}: ", DisplayStructField(field.ident)).unwrap();
+                self.backend_writer.write_ty(self.string, field.ty_expr.ty.borrow().as_ref().unwrap());
+                writeln!(self.string, ",").unwrap();
+            }
+            writeln!(self.string, "}};").unwrap();
+        }
+    }
+
+    fn generate_uniforms(&mut self) {
+        let live_refs = self.draw_shader_def.all_live_refs.borrow();
+        if !live_refs.is_empty() {
+            writeln!(self.string, "struct LiveUniforms {{").unwrap();
+            for (value_node_ptr, ty) in live_refs.iter() {
+                write!(self.string, "    {}: ", value_node_ptr).unwrap();
+                self.backend_writer.write_ty_lit(self.string, ty.maybe_ty_lit().unwrap());
+                writeln!(self.string, ",").unwrap();
+            }
+            writeln!(self.string, "}};").unwrap();
+            writeln!(
+                self.string,
+                "@group({}) @binding({}) var<uniform> live_uniforms: LiveUniforms;",
+                WGSL_UNIFORM_GROUP,
+                WGSL_LIVE_UNIFORMS_BINDING
+            ).unwrap();
+        }
+
+        if !self.const_table.table.is_empty() {
+            writeln!(
+                self.string,
+                "@group({}) @binding({}) var<uniform> const_table: array<vec4<f32>, {}>;",
+                WGSL_UNIFORM_GROUP,
+                WGSL_CONST_TABLE_BINDING,
+                (self.const_table.table.len() + 3) >> 2
+            ).unwrap();
+        }
+
+        for (binding, (ident, vec)) in self.fields_as_uniform_blocks.iter().enumerate() {
+            writeln!(self.string, "struct Uniforms_{} {{", ident).unwrap();
+            for (index, _item) in vec {
+                let field = &self.draw_shader_def.fields[*index];
+                write!(self.string, "    {}: ", DisplayDsIdent(field.ident)).unwrap();
+                self.backend_writer.write_ty(self.string, field.ty_expr.ty.borrow().as_ref().unwrap());
+                writeln!(self.string, ",").unwrap();
+            }
+            writeln!(self.string, "}};").unwrap();
+            writeln!(
+                self.string,
+                "@group({0}) @binding({1}) var<uniform> uniforms_{2}: Uniforms_{2};",
+                WGSL_UNIFORM_GROUP,
+                WGSL_UNIFORM_BLOCK_BINDING_BASE + binding,
+                ident
+            ).unwrap();
+        }
+    }
+
+    fn generate_textures(&mut self, all_builtins: &BTreeSet<Ident>) {
+        let mut binding = 0;
+        for field in &self.draw_shader_def.fields {
+            match field.kind {
+                DrawShaderFieldKind::Texture {..} => {
+                    assert_ne!(*field.ty_expr.ty.borrow().as_ref().unwrap(), Ty::TextureOES, "TextureOES is only available on Android");
+                    assert_eq!(*field.ty_expr.ty.borrow().as_ref().unwrap(), Ty::Texture2D);
+                    writeln!(
+                        self.string,
+                        "@group({}) @binding({}) var {}: texture_2d<f32>;",
+                        WGSL_TEXTURE_GROUP,
+                        binding,
+                        DisplayDsIdent(field.ident)
+                    ).unwrap();
+                    binding += 1;
+                }
+                _ => {}
+            }
+        }
+        let uses_sampling = all_builtins.contains(&Ident(live_id!(sample2d)))
+            || all_builtins.contains(&Ident(live_id!(sample2d_rt)));
+        if binding > 0 || uses_sampling {
+            writeln!(
+                self.string,
+                "@group({}) @binding({}) var default_sampler: sampler;",
+                WGSL_TEXTURE_GROUP,
+                binding
+            ).unwrap();
+        }
+    }
+
+    fn generate_geometry_struct(&mut self) {
+        if !self.has_fields( | kind | matches!(kind, DrawShaderFieldKind::Geometry {..})) {
+            return
+        }
+        let mut location = 0;
+        writeln!(self.string, "struct Geometries {{").unwrap();
+        for field in &self.draw_shader_def.fields {
+            match field.kind {
+                DrawShaderFieldKind::Geometry {..} => {
+                    self.write_io_field(&mut location, &DisplayDsIdent(field.ident), field.ty_expr.ty.borrow().as_ref().unwrap());
+                }
+                _ => ()
+            }
+        }
+        writeln!(self.string, "}};").unwrap();
+        writeln!(self.string, "var<private> geometries: Geometries;").unwrap();
+    }
+
+    fn generate_instance_struct(&mut self) {
+        if !self.has_fields( | kind | matches!(kind, DrawShaderFieldKind::Instance {..})) {
+            return
+        }
+        let mut location = self.draw_shader_def.fields.iter().filter( | field | {
+            matches!(field.kind, DrawShaderFieldKind::Geometry {..})
+        }).count();
+        let mut padding = 0;
+        writeln!(self.string, "struct Instances {{").unwrap();
+        for field in &self.draw_shader_def.fields {
+            match field.kind {
+                DrawShaderFieldKind::Instance {..} => {
+                    let ty = field.ty_expr.ty.borrow();
+                    match ty.as_ref().unwrap() {
+                        Ty::Float | Ty::Vec2 | Ty::Vec3 | Ty::Vec4 | Ty::Enum(_) => {
+                            if field.ident == Ident(LiveId(0)) {
+                                self.write_io_field(&mut location, &DisplayPadding(padding), ty.as_ref().unwrap());
+                                padding += 1;
+                            }
+                            else {
+                                self.write_io_field(&mut location, &DisplayDsIdent(field.ident), ty.as_ref().unwrap());
+                            }
+                        },
+                        Ty::Mat4 => {
+                            for i in 0..4 {
+                                self.write_io_field(&mut location, &format!("{}{}", DisplayDsIdent(field.ident), i), &Ty::Vec4);
+                            }
+                        },
+                        Ty::Mat3 => {
+                            for i in 0..3 {
+                                self.write_io_field(&mut location, &format!("{}{}", DisplayDsIdent(field.ident), i), &Ty::Vec3);
+                            }
+                        },
+                        Ty::Mat2 => {
+                            self.write_io_field(&mut location, &DisplayDsIdent(field.ident), &Ty::Vec4);
+                        },
+                        _ => panic!("unsupported type in generate_instance_struct")
+                    }
+                }
+                _ => ()
+            }
+        }
+        writeln!(self.string, "}};").unwrap();
+        writeln!(self.string, "var<private> instances: Instances;").unwrap();
+    }
+
+    fn generate_varying_struct(&mut self) {
+        let mut location = 0;
+        writeln!(self.string, "struct Varyings {{").unwrap();
+        writeln!(self.string, "    @builtin(position) position: vec4<f32>,").unwrap();
+        for field in &self.draw_shader_def.fields {
+            let ty = field.ty_expr.ty.borrow();
+            match &field.kind {
+                DrawShaderFieldKind::Geometry {is_used_in_pixel_shader, ..} if is_used_in_pixel_shader.get() => {
+                    self.write_io_field(&mut location, &DisplayDsIdent(field.ident), ty.as_ref().unwrap());
+                }
+                DrawShaderFieldKind::Instance {is_used_in_pixel_shader, ..} if is_used_in_pixel_shader.get() => {
+                    match ty.as_ref().unwrap() {
+                        Ty::Float | Ty::Vec2 | Ty::Vec3 | Ty::Vec4 | Ty::Enum(_) => {
+                            self.write_io_field(&mut location, &DisplayDsIdent(field.ident), ty.as_ref().unwrap());
+                        },
+                        Ty::Mat4 => {
+                            for i in 0..4 {
+                                self.write_io_field(&mut location, &format!("{}{}", DisplayDsIdent(field.ident), i), &Ty::Vec4);
+                            }
+                        },
+                        Ty::Mat3 => {
+                            for i in 0..3 {
+                                self.write_io_field(&mut location, &format!("{}{}", DisplayDsIdent(field.ident), i), &Ty::Vec3);
+                            }
+                        },
+                        Ty::Mat2 => {
+                            self.write_io_field(&mut location, &DisplayDsIdent(field.ident), &Ty::Vec4);
+                        },
+                        _ => panic!("unsupported type in generate_varying_struct")
+                    }
+                }
+                DrawShaderFieldKind::Varying {..} => {
+                    self.write_io_field(&mut location, &DisplayDsIdent(field.ident), ty.as_ref().unwrap());
+                }
+                _ => {}
+            }
+        }
+        writeln!(self.string, "}};").unwrap();
+        writeln!(self.string, "var<private> varyings: Varyings;").unwrap();
+    }
+
+    fn write_io_field(&mut self, location: &mut usize, ident: &dyn fmt::Display, ty: &Ty) {
+        // integers can't be interpolated
+        let interpolate = if let Ty::Enum(_) | Ty::Int = ty {" @interpolate(flat)"} else {""};
+        write!(self.string, "    @location({}){} {}: ", location, interpolate, ident).unwrap();
+        self.backend_writer.write_ty(self.string, ty);
+        writeln!(self.string, ",").unwrap();
+        *location += 1;
+    }
+
+    fn has_fields(&self, filter: impl Fn(&DrawShaderFieldKind) -> bool) -> bool {
+        self.draw_shader_def.fields.iter().any( | field | filter(&field.kind))
+    }
+
+    fn generate_builtin_helpers(&mut self, all_builtins: &BTreeSet<Ident>) {
+        if all_builtins.contains(&Ident(live_id!(sample2d))) {
+            writeln!(self.string, "fn sample2d(tex: texture_2d<f32>, pos: vec2<f32>) -> vec4<f32> {{return textureSampleLevel(tex, default_sampler, pos, 0.0);}}").unwrap();
+        }
+        if all_builtins.contains(&Ident(live_id!(sample2d_rt))) {
+            writeln!(self.string, "fn sample2d_rt(tex: texture_2d<f32>, pos: vec2<f32>) -> vec4<f32> {{return textureSampleLevel(tex, default_sampler, pos, 0.0);}}").unwrap();
+        }
+        if all_builtins.contains(&Ident(live_id!(mod))) {
+            // glsl mod semantics, the % operator truncates instead of floors
+            for ty in ["f32", "vec2<f32>", "vec3<f32>", "vec4<f32>"] {
+                let name = match ty {
+                    "f32" => "float",
+                    _ => &ty[0..4]
+                };
+                writeln!(self.string, "fn mod_{0}(x: {1}, y: {1}) -> {1} {{return x - y * floor(x / y);}}", name, ty).unwrap();
+            }
+        }
+        if all_builtins.contains(&Ident(live_id!(matrixCompMult))) {
+            for size in 2..=4 {
+                write!(self.string, "fn matrixCompMult_mat{0}(x: mat{0}x{0}<f32>, y: mat{0}x{0}<f32>) -> mat{0}x{0}<f32> {{return mat{0}x{0}<f32>(", size).unwrap();
+                for col in 0..size {
+                    write!(self.string, "{}x[{1}] * y[{1}]", if col != 0 {", "} else {""}, col).unwrap();
+                }
+                writeln!(self.string, ");}}").unwrap();
+            }
+        }
+        if all_builtins.contains(&Ident(live_id!(inverse))) {
+            writeln!(self.string, "{}", WGSL_INVERSE_HELPERS).unwrap();
+        }
+    }
+
+    fn generate_vertex_main(&mut self) {
+        let has_geometries = self.has_fields( | kind | matches!(kind, DrawShaderFieldKind::Geometry {..}));
+        let has_instances = self.has_fields( | kind | matches!(kind, DrawShaderFieldKind::Instance {..}));
+
+        writeln!(self.string, "@vertex").unwrap();
+        write!(self.string, "fn vertex_main(").unwrap();
+        let mut sep = "";
+        if has_geometries {
+            write!(self.string, "in_geometries: Geometries").unwrap();
+            sep = ", ";
+        }
+        if has_instances {
+            write!(self.string, "{}in_instances: Instances", sep).unwrap();
+        }
+        writeln!(self.string, ") -> Varyings {{").unwrap();
+        if has_geometries {
+            writeln!(self.string, "    geometries = in_geometries;").unwrap();
+        }
+        if has_instances {
+            writeln!(self.string, "    instances = in_instances;").unwrap();
+        }
+
+        for decl in &self.draw_shader_def.fields {
+            match &decl.kind {
+                DrawShaderFieldKind::Geometry {is_used_in_pixel_shader, ..} if is_used_in_pixel_shader.get() => {
+                    writeln!(self.string, "    varyings.{0} = geometries.{0};", DisplayDsIdent(decl.ident)).unwrap();
+                }
+                DrawShaderFieldKind::Instance {is_used_in_pixel_shader, ..} if is_used_in_pixel_shader.get() => {
+                    match decl.ty_expr.ty.borrow().as_ref().unwrap() {
+                        Ty::Mat4 => {
+                            for i in 0..4 {
+                                writeln!(self.string, "    varyings.{0}{1} = instances.{0}{1};", DisplayDsIdent(decl.ident), i).unwrap();
+                            }
+                        }
+                        Ty::Mat3 => {
+                            for i in 0..3 {
+                                writeln!(self.string, "    varyings.{0}{1} = instances.{0}{1};", DisplayDsIdent(decl.ident), i).unwrap();
+                            }
+                        }
+                        _ => {
+                            writeln!(self.string, "    varyings.{0} = instances.{0};", DisplayDsIdent(decl.ident)).unwrap();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let vertex_def = self.shader_registry.draw_shader_method_decl_from_ident(self.draw_shader_def, Ident(live_id!(vertex))).unwrap();
+        writeln!(self.string, "    varyings.position = {}();", DisplayFnName(vertex_def.fn_ptr, vertex_def.ident)).unwrap();
+        writeln!(self.string, "    return varyings;").unwrap();
+        writeln!(self.string, "}}").unwrap();
+    }
+
+    fn generate_pixel_main(&mut self) {
+        writeln!(self.string, "@fragment").unwrap();
+        writeln!(self.string, "fn fragment_main(in_varyings: Varyings) -> @location(0) vec4<f32> {{").unwrap();
+        writeln!(self.string, "    varyings = in_varyings;").unwrap();
+        let pixel_def = self.shader_registry.draw_shader_method_decl_from_ident(self.draw_shader_def, Ident(live_id!(pixel))).unwrap();
+        writeln!(self.string, "    return {}();", DisplayFnName(pixel_def.fn_ptr, pixel_def.ident)).unwrap();
+        writeln!(self.string, "}}").unwrap();
+    }
+}
+
+const WGSL_INVERSE_HELPERS: &str = "fn inverse_mat2(m: mat2x2<f32>) -> mat2x2<f32> {
+    return mat2x2<f32>(m[1][1], -m[0][1], -m[1][0], m[0][0]) * (1.0 / determinant(m));
+}
+fn inverse_mat3(m: mat3x3<f32>) -> mat3x3<f32> {
+    let c0 = cross(m[1], m[2]);
+    let c1 = cross(m[2], m[0]);
+    let c2 = cross(m[0], m[1]);
+    return transpose(mat3x3<f32>(c0, c1, c2)) * (1.0 / dot(m[0], c0));
+}
+fn inverse_mat4(m: mat4x4<f32>) -> mat4x4<f32> {
+    let a = m[0]; let b = m[1]; let c = m[2]; let d = m[3];
+    let s0 = a.x * b.y - b.x * a.y; let s1 = a.x * b.z - b.x * a.z;
+    let s2 = a.x * b.w - b.x * a.w; let s3 = a.y * b.z - b.y * a.z;
+    let s4 = a.y * b.w - b.y * a.w; let s5 = a.z * b.w - b.z * a.w;
+    let c5 = c.z * d.w - d.z * c.w; let c4 = c.y * d.w - d.y * c.w;
+    let c3 = c.y * d.z - d.y * c.z; let c2 = c.x * d.w - d.x * c.w;
+    let c1 = c.x * d.z - d.x * c.z; let c0 = c.x * d.y - d.x * c.y;
+    let inv_det = 1.0 / (s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0);
+    return mat4x4<f32>(
+        vec4<f32>(b.y * c5 - b.z * c4 + b.w * c3, -a.y * c5 + a.z * c4 - a.w * c3, d.y * s5 - d.z * s4 + d.w * s3, -c.y * s5 + c.z * s4 - c.w * s3),
+        vec4<f32>(-b.x * c5 + b.z * c2 - b.w * c1, a.x * c5 - a.z * c2 + a.w * c1, -d.x * s5 + d.z * s2 - d.w * s1, c.x * s5 - c.z * s2 + c.w * s1),
+        vec4<f32>(b.x * c4 - b.y * c2 + b.w * c0, -a.x * c4 + a.y * c2 - a.w * c0, d.x * s4 - d.y * s2 + d.w * s0, -c.x * s4 + c.y * s2 - c.w * s0),
+        vec4<f32>(-b.x * c3 + b.y * c1 - b.z * c0, a.x * c3 - a.y * c1 + a.z * c0, -d.x * s3 + d.y * s1 - d.z * s0, c.x * s3 - c.y * s1 + c.z * s0)
+    ) * inv_det;
+}";
+
+struct WgslBackendWriter<'a> {
+    pub shader_registry: &'a ShaderRegistry,
+    pub draw_shader_def: &'a DrawShaderDef,
+    pub const_table: &'a DrawShaderConstTable,
+}
+
+impl<'a> WgslBackendWriter<'a> {
+    fn write_ty(&self, string: &mut String, ty: &Ty) -> bool {
+        match ty {
+            Ty::Void => return false,
+            Ty::Texture2D => write!(string, "texture_2d<f32>").unwrap(),
+            Ty::TextureOES => panic!("TextureOES is only available on Android"),
+            Ty::Array {elem_ty, len} => {
+                write!(string, "array<").unwrap();
+                self.write_ty(string, elem_ty);
+                write!(string, ", {}>", len).unwrap();
+            }
+            Ty::Struct(struct_ptr) => write!(string, "{}", struct_ptr).unwrap(),
+            Ty::Enum(_) => write!(string, "u32").unwrap(),
+            Ty::DrawShader(_) | Ty::ClosureDef {..} | Ty::ClosureDecl => return false,
+            _ => self.write_ty_lit(string, ty.maybe_ty_lit().unwrap())
+        }
+        true
+    }
+}
+
+impl<'a> BackendWriter for WgslBackendWriter<'a> {
+
+    fn get_struct_cons_type(&self) -> StructConsType {
+        StructConsType::Paren
+    }
+
+    fn needs_mul_fn_for_matrix_multiplication(&self) -> bool {
+        false
+    }
+
+    fn needs_unpack_for_matrix_multiplication(&self) -> bool {
+        false
+    }
+
+    fn enum_is_float(&self) -> bool {
+        false
+    }
+
+    fn const_table_is_vec4(&self) -> bool {
+        true
+    }
+
+    fn use_cons_fn(&self, what: &str) -> bool {
+        match what {
+            "consfn_mat3_mat4" => true,
+            "consfn_mat2_mat4" => true,
+            "consfn_mat2_mat3" => true,
+            _ => false
+        }
+    }
+
+    fn params_are_immutable(&self) -> bool {
+        true
+    }
+
+    fn inout_is_pointer(&self) -> bool {
+        true
+    }
+
+    fn use_select_for_cond_expr(&self) -> bool {
+        true
+    }
+
+    fn assign_is_statement(&self) -> bool {
+        true
+    }
+
+    fn builtin_as_operator(&self, ident: Ident) -> Option<&'static str> {
+        match ident {
+            Ident(live_id!(lessThan)) => Some("<"),
+            Ident(live_id!(lessThanEqual)) => Some("<="),
+            Ident(live_id!(greaterThan)) => Some(">"),
+            Ident(live_id!(greaterThanEqual)) => Some(">="),
+            Ident(live_id!(equal)) => Some("=="),
+            Ident(live_id!(notEqual)) => Some("!="),
+            Ident(live_id!(not)) => Some("!"),
+            _ => None
+        }
+    }
+
+    fn builtin_splats_scalar_args(&self, ident: Ident) -> bool {
+        match ident {
+            Ident(live_id!(clamp)) | Ident(live_id!(min)) | Ident(live_id!(max)) |
+            Ident(live_id!(step)) | Ident(live_id!(smoothstep)) | Ident(live_id!(mod)) => true,
+            _ => false
+        }
+    }
+
+    fn write_fn_def_header(&self, string: &mut String, fn_name: &dyn fmt::Display, _return_ty: &Ty) {
+        write!(string, "fn {}(", fn_name).unwrap();
+    }
+
+    fn write_fn_def_header_end(&self, string: &mut String, return_ty: &Ty) {
+        write!(string, ")").unwrap();
+        if *return_ty != Ty::Void {
+            write!(string, " -> ").unwrap();
+            self.write_ty(string, return_ty);
+        }
+    }
+
+    fn write_param_decl(&self, string: &mut String, sep: &'static str, is_inout: bool, ident: &dyn fmt::Display, ty: &Ty) -> bool {
+        let mut ty_string = String::new();
+        if !self.write_ty(&mut ty_string, ty) {
+            return false
+        }
+        if is_inout {
+            write!(string, "{}{}: ptr<function, {}>", sep, ident, ty_string).unwrap();
+        }
+        else {
+            write!(string, "{}{}: {}", sep, ident, ty_string).unwrap();
+        }
+        true
+    }
+
+    fn write_local_var_decl(&self, string: &mut String, ident: &dyn fmt::Display, ty: &Ty) {
+        write!(string, "var {}: ", ident).unwrap();
+        self.write_ty(string, ty);
+    }
+
+    fn write_var_decl(
+        &self,
+        string: &mut String,
+        sep: &'static str,
+        is_inout: bool,
+        _is_packed: bool,
+        ident: &dyn fmt::Display,
+        ty: &Ty,
+    ) -> bool {
+        self.write_param_decl(string, sep, is_inout, ident, ty)
+    }
+
+    // everything the other backends pass around as hidden args lives at module scope
+    fn write_call_expr_hidden_args(&self, _string: &mut String, _hidden_args: &BTreeSet<HiddenArgKind >, _sep: &str) {
+    }
+
+    fn write_fn_def_hidden_params(&self, _string: &mut String, _hidden_args: &BTreeSet<HiddenArgKind >, _sep: &str) {
+    }
+
+    fn generate_live_value_prefix(&self, string: &mut String) {
+        write!(string, "live_uniforms.").unwrap();
+    }
+
+    fn generate_draw_shader_field_expr(&self, string: &mut String, field_ident: Ident, ty: &Ty) {
+        let field_def = self.draw_shader_def.find_field(field_ident).unwrap();
+
+        match &field_def.kind {
+            DrawShaderFieldKind::Geometry {is_used_in_pixel_shader, ..} => {
+                if is_used_in_pixel_shader.get() {
+                    write!(string, "varyings.").unwrap()
+                }
+                else {
+                    write!(string, "geometries.").unwrap()
+                }
+            }
+            DrawShaderFieldKind::Instance {is_used_in_pixel_shader, ..} => {
+                let prefix = if is_used_in_pixel_shader.get() {
+                    "varyings"
+                }
+                else {
+                    "instances"
+                };
+
+                match ty {
+                    Ty::Mat4 | Ty::Mat3 => {
+                        let size = if let Ty::Mat4 = ty {4} else {3};
+                        write!(string, "mat{0}x{0}<f32>(", size).unwrap();
+                        for i in 0..size {
+                            for j in 0..size {
+                                if i != 0 || j != 0 {
+                                    write!(string, ",").unwrap();
+                                }
+                                write!(string, "{}.{}{}.{}", prefix, DisplayDsIdent(field_ident), j, ["x", "y", "z", "w"][i]).unwrap();
+                            }
+                        }
+                        write!(string, ")").unwrap();
+                        return
+                    },
+                    Ty::Mat2 => {
+                        write!(string, "mat2x2<f32>({0}.{1}.x, {0}.{1}.y, {0}.{1}.z, {0}.{1}.w)", prefix, DisplayDsIdent(field_ident)).unwrap();
+                        return
+                    },
+                    _ => {
+                        write!(string, "{}.", prefix).unwrap();
+                    }
+                }
+            }
+            DrawShaderFieldKind::Varying {..} => {
+                write!(string, "varyings.").unwrap()
+            }
+            DrawShaderFieldKind::Texture {..} => {
+            }
+            DrawShaderFieldKind::Uniform {block_ident, ..} => {
+                write!(string, "uniforms_{}.", block_ident).unwrap()
+            }
+        }
+        write!(string, "{}", &DisplayDsIdent(field_ident)).unwrap();
+    }
+
+    fn write_ty_lit(&self, string: &mut String, ty_lit: TyLit) {
+        write!(
+            string,
+            "{}",
+            match ty_lit {
+                TyLit::Bool => "bool",
+                TyLit::Int => "i32",
+                TyLit::Float => "f32",
+                TyLit::Bvec2 => "vec2<bool>",
+                TyLit::Bvec3 => "vec3<bool>",
+                TyLit::Bvec4 => "vec4<bool>",
+                TyLit::Ivec2 => "vec2<i32>",
+                TyLit::Ivec3 => "vec3<i32>",
+                TyLit::Ivec4 => "vec4<i32>",
+                TyLit::Vec2 => "vec2<f32>",
+                TyLit::Vec3 => "vec3<f32>",
+                TyLit::Vec4 => "vec4<f32>",
+                TyLit::Mat2 => "mat2x2<f32>",
+                TyLit::Mat3 => "mat3x3<f32>",
+                TyLit::Mat4 => "mat4x4<f32>",
+                TyLit::Texture2D => "texture_2d<f32>",
+                TyLit::TextureOES => panic!("TextureOES is only available on Android"),
+            }
+        )
+            .unwrap();
+    }
+
+    fn write_builtin_call_ident(&self, string: &mut String, ident: Ident, arg_exprs: &[Expr]) {
+        match ident {
+            Ident(live_id!(atan)) => {
+                if arg_exprs.len() == 2 {
+                    write!(string, "atan2").unwrap();
+                }
+                else {
+                    write!(string, "atan").unwrap();
+                }
+            }
+            Ident(live_id!(mod)) | Ident(live_id!(inverse)) | Ident(live_id!(matrixCompMult)) => {
+                // these are generated helpers, one per type
+                let ty = arg_exprs.iter().map( | arg_expr | arg_expr.ty.borrow().clone().unwrap()).find( | ty | *ty != Ty::Float);
+                write!(string, "{}_{}", ident, ty.unwrap_or(Ty::Float)).unwrap();
+            }
+            Ident(live_id!(dFdx)) => {
+                write!(string, "dpdx").unwrap();
+            }
+            Ident(live_id!(dFdy)) => {
+                write!(string, "dpdy").unwrap();
+            }
+            Ident(live_id!(inversesqrt)) => {
+                write!(string, "inverseSqrt").unwrap();
+            }
+            Ident(live_id!(faceforward)) => {
+                write!(string, "faceForward").unwrap();
+            }
+            _ => {
+                write!(string, "{}", ident).unwrap()
+            }
+        }
+    }
+}
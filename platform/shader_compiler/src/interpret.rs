@@ -0,0 +1,1463 @@
+use {
+    std::{
+        rc::Rc,
+        collections::BTreeMap,
+        ops::{Add, Sub, Mul, Div, Neg},
+    },
+    crate::{
+        makepad_live_compiler::*,
+        makepad_live_id::*,
+        makepad_math::*,
+        shader_ast::*,
+        shader_registry::ShaderRegistry,
+        swizzle::Swizzle,
+    }
+};
+
+// A tree walking interpreter over the analysed shader AST, so what a DrawShaderDef renders
+// can be tested on machines without a GPU. It evaluates the same vertex and pixel methods
+// the generators translate, and takes the same inputs:
+//
+//     geometries/instances  packed like DrawShaderInputPacking::Attribute
+//     uniform blocks        packed like DrawShaderInputPacking::UniformsGLSL, one buffer per block
+//     live uniforms         packed like UniformsGLSL in all_live_refs order
+//     const table           the DrawShaderConstTable the GPU paths upload
+//
+// Every float carries its screen space derivatives along (forward mode dual numbers), the
+// rasterizer seeds the varyings with theirs, which is how dFdx/dFdy work without having
+// to run pixels in 2x2 quads.
+
+pub trait InterpretTexture {
+    fn sample(&self, pos: Vec2) -> Vec4;
+}
+
+#[derive(Default)]
+pub struct InterpretInputs<'a> {
+    pub uniform_blocks: BTreeMap<Ident, Vec<f32>>,
+    pub live_uniforms: Vec<f32>,
+    pub textures: Vec<Option<&'a dyn InterpretTexture>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct InterpretVertex {
+    pub position: Vec4,
+    pub varyings: Vec<f32>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct InterpretVaryings {
+    pub values: Vec<f32>,
+    pub dx: Vec<f32>,
+    pub dy: Vec<f32>,
+}
+
+pub struct ShaderInterpreter<'a> {
+    pub draw_shader_def: &'a DrawShaderDef,
+    pub const_table: &'a DrawShaderConstTable,
+    pub shader_registry: &'a ShaderRegistry,
+    live_offsets: BTreeMap<ValuePtr, usize>,
+    geometry_slots: usize,
+    instance_slots: usize,
+    varying_slots: usize,
+}
+
+impl<'a> ShaderInterpreter<'a> {
+    pub fn new(draw_shader_def: &'a DrawShaderDef, const_table: &'a DrawShaderConstTable, shader_registry: &'a ShaderRegistry) -> Self {
+        let mut live_offsets = BTreeMap::new();
+        let mut slots = 0;
+        for (value_ptr, ty) in draw_shader_def.all_live_refs.borrow().iter() {
+            live_offsets.insert(*value_ptr, slots);
+            slots += ty.slots();
+        }
+        let mut geometry_slots = 0;
+        let mut instance_slots = 0;
+        let mut varying_slots = 0;
+        for field in &draw_shader_def.fields {
+            let ty = field.ty_expr.ty.borrow();
+            match field.kind {
+                DrawShaderFieldKind::Geometry {..} => {
+                    geometry_slots += ty.as_ref().unwrap().slots();
+                    varying_slots += ty.as_ref().unwrap().slots();
+                }
+                DrawShaderFieldKind::Instance {..} => {
+                    instance_slots += ty.as_ref().unwrap().slots();
+                }
+                DrawShaderFieldKind::Varying {..} => {
+                    varying_slots += ty.as_ref().unwrap().slots();
+                }
+                _ => ()
+            }
+        }
+        Self {
+            draw_shader_def,
+            const_table,
+            shader_registry,
+            live_offsets,
+            geometry_slots,
+            instance_slots,
+            varying_slots,
+        }
+    }
+
+    pub fn geometry_slots(&self) -> usize {self.geometry_slots}
+
+    pub fn instance_slots(&self) -> usize {self.instance_slots}
+
+    // the geometries followed by the varyings, the vertex stage hands these to the pixel stage
+    pub fn varying_slots(&self) -> usize {self.varying_slots}
+
+    // zero filled inputs of the right size for this shader
+    pub fn new_inputs<'b>(&self) -> InterpretInputs<'b> {
+        let mut inputs = InterpretInputs::default();
+        for (block_ident, fields) in self.draw_shader_def.fields_as_uniform_blocks() {
+            let slots = fields.iter().map( | (index, _) | self.field_ty(*index).slots()).sum();
+            inputs.uniform_blocks.insert(block_ident, vec![0.0; slots]);
+        }
+        inputs.live_uniforms = vec![0.0; self.draw_shader_def.all_live_refs.borrow().values().map( | ty | ty.slots()).sum()];
+        for field in &self.draw_shader_def.fields {
+            if let DrawShaderFieldKind::Texture {..} = field.kind {
+                inputs.textures.push(None);
+            }
+        }
+        inputs
+    }
+
+    pub fn set_uniform(&self, inputs: &mut InterpretInputs, ident: Ident, values: &[f32]) {
+        for (block_ident, fields) in self.draw_shader_def.fields_as_uniform_blocks() {
+            let mut offset = 0;
+            for (index, field_ident) in fields {
+                let slots = self.field_ty(index).slots();
+                if field_ident == ident {
+                    let block = inputs.uniform_blocks.entry(block_ident).or_default();
+                    if block.len() < offset + slots {
+                        block.resize(offset + slots, 0.0);
+                    }
+                    let len = slots.min(values.len());
+                    block[offset..offset + len].copy_from_slice(&values[..len]);
+                    return
+                }
+                offset += slots;
+            }
+        }
+    }
+
+    // writes an instance field into instance data packed in shader order
+    pub fn set_instance(&self, instance: &mut [f32], ident: Ident, values: &[f32]) {
+        let mut offset = 0;
+        for (index, field) in self.draw_shader_def.fields.iter().enumerate() {
+            if let DrawShaderFieldKind::Instance {..} = field.kind {
+                let slots = self.field_ty(index).slots();
+                if field.ident == ident {
+                    let len = slots.min(values.len()).min(instance.len().saturating_sub(offset));
+                    instance[offset..offset + len].copy_from_slice(&values[..len]);
+                    return
+                }
+                offset += slots;
+            }
+        }
+    }
+
+    pub fn set_live_uniform(&self, inputs: &mut InterpretInputs, value_ptr: ValuePtr, values: &[f32]) {
+        if let Some(offset) = self.live_offsets.get(&value_ptr) {
+            let slots = self.draw_shader_def.all_live_refs.borrow().get(&value_ptr).unwrap().slots();
+            if inputs.live_uniforms.len() < offset + slots {
+                inputs.live_uniforms.resize(offset + slots, 0.0);
+            }
+            let len = slots.min(values.len());
+            inputs.live_uniforms[*offset..*offset + len].copy_from_slice(&values[..len]);
+        }
+    }
+
+    pub fn vertex(&self, inputs: &InterpretInputs, geometry: &[f32], instance: &[f32]) -> Result<InterpretVertex, LiveError> {
+        let mut exec = Exec::new(self, inputs);
+        let mut geometry_offset = 0;
+        let mut instance_offset = 0;
+        for (index, field) in self.draw_shader_def.fields.iter().enumerate() {
+            let ty = self.field_ty(index);
+            match field.kind {
+                DrawShaderFieldKind::Geometry {..} => {
+                    exec.fields[index] = value_from_slots(&ty, geometry, &mut geometry_offset, None);
+                }
+                DrawShaderFieldKind::Instance {..} => {
+                    exec.fields[index] = value_from_slots(&ty, instance, &mut instance_offset, None);
+                }
+                _ => ()
+            }
+        }
+        let position = exec.call_draw_shader_method(Ident(live_id!(vertex)))?;
+        let position = position.comps();
+        let mut varyings = Vec::with_capacity(self.varying_slots);
+        for (index, field) in self.draw_shader_def.fields.iter().enumerate() {
+            match field.kind {
+                DrawShaderFieldKind::Geometry {..} | DrawShaderFieldKind::Varying {..} => {
+                    exec.fields[index].write_slots(&mut varyings);
+                }
+                _ => ()
+            }
+        }
+        Ok(InterpretVertex {
+            position: vec4(position[0].v, position[1].v, position[2].v, position[3].v),
+            varyings
+        })
+    }
+
+    pub fn pixel(&self, inputs: &InterpretInputs, instance: &[f32], varyings: &InterpretVaryings) -> Result<Vec4, LiveError> {
+        let mut exec = Exec::new(self, inputs);
+        let mut instance_offset = 0;
+        let mut varying_offset = 0;
+        for (index, field) in self.draw_shader_def.fields.iter().enumerate() {
+            let ty = self.field_ty(index);
+            match field.kind {
+                DrawShaderFieldKind::Geometry {..} | DrawShaderFieldKind::Varying {..} => {
+                    exec.fields[index] = value_from_slots(&ty, &varyings.values, &mut varying_offset, Some((&varyings.dx, &varyings.dy)));
+                }
+                DrawShaderFieldKind::Instance {..} => {
+                    exec.fields[index] = value_from_slots(&ty, instance, &mut instance_offset, None);
+                }
+                _ => ()
+            }
+        }
+        let color = exec.call_draw_shader_method(Ident(live_id!(pixel)))?;
+        let color = color.comps();
+        Ok(vec4(color[0].v, color[1].v, color[2].v, color[3].v))
+    }
+
+    // Runs the vertex stage over the indexed geometry and shades every pixel center the
+    // triangles cover, reporting them as (x, y, depth, color). Blending and depth testing
+    // are up to the caller. Triangles with vertices behind the camera are skipped instead
+    // of clipped, shared edges follow a tie-break rule so quads don't shade their diagonal twice.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_triangles(
+        &self,
+        inputs: &InterpretInputs,
+        geometries: &[f32],
+        indices: &[u32],
+        instance: &[f32],
+        width: usize,
+        height: usize,
+        put_pixel: &mut dyn FnMut(usize, usize, f32, Vec4)
+    ) -> Result<(), LiveError> {
+        let mut vertices: Vec<Option<InterpretVertex >> = Vec::new();
+        for triangle in indices.chunks_exact(3) {
+            let mut screen = [RasterVertex::default(); 3];
+            for (i, index) in triangle.iter().enumerate() {
+                let index = *index as usize;
+                if vertices.len() <= index {
+                    vertices.resize(index + 1, None);
+                }
+                if vertices[index].is_none() {
+                    let start = index * self.geometry_slots;
+                    let geometry = geometries.get(start..start + self.geometry_slots).unwrap_or(&[]);
+                    vertices[index] = Some(self.vertex(inputs, geometry, instance)?);
+                }
+                let pos = vertices[index].as_ref().unwrap().position;
+                screen[i] = RasterVertex {
+                    x: (pos.x / pos.w * 0.5 + 0.5) * width as f32,
+                    y: (0.5 - pos.y / pos.w * 0.5) * height as f32,
+                    z: pos.z / pos.w,
+                    inv_w: 1.0 / pos.w,
+                    index,
+                };
+            }
+            if screen.iter().any( | v | v.inv_w.is_nan() || v.inv_w <= 0.0 || !v.x.is_finite() || !v.y.is_finite()) {
+                continue;
+            }
+            let mut area = edge(&screen[0], &screen[1], screen[2].x, screen[2].y);
+            if area == 0.0 {
+                continue;
+            }
+            if area < 0.0 {
+                screen.swap(1, 2);
+                area = -area;
+            }
+            let min_x = screen.iter().map( | v | v.x).fold(f32::INFINITY, f32::min).floor().max(0.0) as usize;
+            let min_y = screen.iter().map( | v | v.y).fold(f32::INFINITY, f32::min).floor().max(0.0) as usize;
+            let max_x = (screen.iter().map( | v | v.x).fold(f32::NEG_INFINITY, f32::max).ceil().max(0.0) as usize).min(width);
+            let max_y = (screen.iter().map( | v | v.y).fold(f32::NEG_INFINITY, f32::max).ceil().max(0.0) as usize).min(height);
+
+            let varyings = [
+                &vertices[screen[0].index].as_ref().unwrap().varyings,
+                &vertices[screen[1].index].as_ref().unwrap().varyings,
+                &vertices[screen[2].index].as_ref().unwrap().varyings,
+            ];
+            let mut pixel_varyings = InterpretVaryings {
+                values: vec![0.0; self.varying_slots],
+                dx: vec![0.0; self.varying_slots],
+                dy: vec![0.0; self.varying_slots],
+            };
+            for y in min_y..max_y {
+                for x in min_x..max_x {
+                    let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                    if !covers(&screen, px, py) {
+                        continue;
+                    }
+                    let weights = perspective_weights(&screen, area, px, py);
+                    let weights_dx = perspective_weights(&screen, area, px + 1.0, py);
+                    let weights_dy = perspective_weights(&screen, area, px, py + 1.0);
+                    for slot in 0..self.varying_slots {
+                        let value = interpolate(&varyings, slot, &weights);
+                        pixel_varyings.values[slot] = value;
+                        pixel_varyings.dx[slot] = interpolate(&varyings, slot, &weights_dx) - value;
+                        pixel_varyings.dy[slot] = interpolate(&varyings, slot, &weights_dy) - value;
+                    }
+                    let (b0, b1, b2) = barycentrics(&screen, area, px, py);
+                    let depth = b0 * screen[0].z + b1 * screen[1].z + b2 * screen[2].z;
+                    let color = self.pixel(inputs, instance, &pixel_varyings)?;
+                    put_pixel(x, y, depth, color);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn field_ty(&self, index: usize) -> Ty {
+        self.draw_shader_def.fields[index].ty_expr.ty.borrow().as_ref().unwrap().clone()
+    }
+
+    fn zero_value(&self, ty: &Ty) -> Value {
+        match ty {
+            Ty::Array {elem_ty, len} => Value::Array((0..*len).map( | _ | self.zero_value(elem_ty)).collect()),
+            Ty::Struct(struct_ptr) => {
+                let struct_def = self.shader_registry.structs.get(struct_ptr).unwrap();
+                Value::Struct(struct_def.fields.iter().map( | field | {
+                    self.zero_value(field.ty_expr.ty.borrow().as_ref().unwrap())
+                }).collect())
+            }
+            Ty::DrawShader(_) => Value::DrawShader,
+            Ty::Void | Ty::ClosureDecl | Ty::ClosureDef(_) => Value::Void,
+            Ty::Texture2D | Ty::TextureOES => Value::Texture(usize::MAX),
+            _ => Value::Num(ty.clone(), vec![Dual::default(); ty.slots()]),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct RasterVertex {
+    x: f32,
+    y: f32,
+    z: f32,
+    inv_w: f32,
+    index: usize,
+}
+
+fn edge(a: &RasterVertex, b: &RasterVertex, px: f32, py: f32) -> f32 {
+    (px - a.x) * (b.y - a.y) - (py - a.y) * (b.x - a.x)
+}
+
+// a pixel center exactly on an edge belongs to only one of the two triangles sharing it
+fn covers(v: &[RasterVertex; 3], px: f32, py: f32) -> bool {
+    for (a, b) in [(&v[1], &v[2]), (&v[2], &v[0]), (&v[0], &v[1])] {
+        let e = edge(a, b, px, py);
+        if e < 0.0 {
+            return false
+        }
+        if e == 0.0 {
+            let (dx, dy) = (b.x - a.x, b.y - a.y);
+            if !(dy > 0.0 || (dy == 0.0 && dx < 0.0)) {
+                return false
+            }
+        }
+    }
+    true
+}
+
+fn barycentrics(v: &[RasterVertex; 3], area: f32, px: f32, py: f32) -> (f32, f32, f32) {
+    (edge(&v[1], &v[2], px, py) / area, edge(&v[2], &v[0], px, py) / area, edge(&v[0], &v[1], px, py) / area)
+}
+
+fn perspective_weights(v: &[RasterVertex; 3], area: f32, px: f32, py: f32) -> [f32; 3] {
+    let (b0, b1, b2) = barycentrics(v, area, px, py);
+    let (w0, w1, w2) = (b0 * v[0].inv_w, b1 * v[1].inv_w, b2 * v[2].inv_w);
+    let sum = w0 + w1 + w2;
+    [w0 / sum, w1 / sum, w2 / sum]
+}
+
+fn interpolate(varyings: &[&Vec<f32>; 3], slot: usize, weights: &[f32; 3]) -> f32 {
+    varyings[0][slot] * weights[0] + varyings[1][slot] * weights[1] + varyings[2][slot] * weights[2]
+}
+
+// enums are written into the instances as float values on GL and as integer bits elsewhere
+fn decode_enum(slot: f32) -> f32 {
+    if slot >= 0.5 {
+        slot.round()
+    }
+    else {
+        slot.to_bits() as f32
+    }
+}
+
+fn value_from_slots(ty: &Ty, slots: &[f32], offset: &mut usize, derivatives: Option<(&[f32], &[f32])>) -> Value {
+    match ty {
+        Ty::Array {elem_ty, len} => {
+            Value::Array((0..*len).map( | _ | value_from_slots(elem_ty, slots, offset, derivatives)).collect())
+        }
+        _ => {
+            let count = ty.slots();
+            let mut comps = Vec::with_capacity(count);
+            for i in *offset..*offset + count {
+                let v = slots.get(i).cloned().unwrap_or(0.0);
+                comps.push(match ty {
+                    Ty::Enum(_) => Dual::new(decode_enum(v)),
+                    Ty::Bool | Ty::Bvec2 | Ty::Bvec3 | Ty::Bvec4 => Dual::new(if v != 0.0 {1.0} else {0.0}),
+                    _ => match derivatives {
+                        Some((dx, dy)) => Dual {
+                            v,
+                            dx: dx.get(i).cloned().unwrap_or(0.0),
+                            dy: dy.get(i).cloned().unwrap_or(0.0)
+                        },
+                        None => Dual::new(v)
+                    }
+                });
+            }
+            *offset += count;
+            Value::Num(ty.clone(), comps)
+        }
+    }
+}
+
+// a float together with its screen space derivatives
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Dual {
+    v: f32,
+    dx: f32,
+    dy: f32,
+}
+
+impl Dual {
+    fn new(v: f32) -> Self {
+        Self {v, dx: 0.0, dy: 0.0}
+    }
+
+    fn bool(b: bool) -> Self {
+        Self::new(if b {1.0} else {0.0})
+    }
+
+    fn is_true(self) -> bool {
+        self.v != 0.0
+    }
+
+    // applies the chain rule for a function with value v and derivative d at self
+    fn chain(self, v: f32, d: f32) -> Self {
+        Self {v, dx: self.dx * d, dy: self.dy * d}
+    }
+
+    fn abs(self) -> Self {
+        if self.v < 0.0 {-self} else {self}
+    }
+
+    fn floor(self) -> Self {Self::new(self.v.floor())}
+    fn ceil(self) -> Self {Self::new(self.v.ceil())}
+    fn sign(self) -> Self {Self::new(if self.v > 0.0 {1.0} else if self.v < 0.0 {-1.0} else {0.0})}
+    fn fract(self) -> Self {Self {v: self.v - self.v.floor(), ..self}}
+    fn trunc(self) -> Self {Self::new(self.v.trunc())}
+
+    fn sqrt(self) -> Self {
+        let v = self.v.sqrt();
+        self.chain(v, 0.5 / v)
+    }
+
+    fn inverse_sqrt(self) -> Self {
+        let v = 1.0 / self.v.sqrt();
+        self.chain(v, -0.5 * v / self.v)
+    }
+
+    fn sin(self) -> Self {self.chain(self.v.sin(), self.v.cos())}
+    fn cos(self) -> Self {self.chain(self.v.cos(), -self.v.sin())}
+    fn tan(self) -> Self {
+        let v = self.v.tan();
+        self.chain(v, 1.0 + v * v)
+    }
+    fn asin(self) -> Self {self.chain(self.v.asin(), 1.0 / (1.0 - self.v * self.v).sqrt())}
+    fn acos(self) -> Self {self.chain(self.v.acos(), -1.0 / (1.0 - self.v * self.v).sqrt())}
+    fn atan(self) -> Self {self.chain(self.v.atan(), 1.0 / (1.0 + self.v * self.v))}
+
+    fn atan2(self, x: Self) -> Self {
+        let r2 = x.v * x.v + self.v * self.v;
+        Self {
+            v: self.v.atan2(x.v),
+            dx: (x.v * self.dx - self.v * x.dx) / r2,
+            dy: (x.v * self.dy - self.v * x.dy) / r2,
+        }
+    }
+
+    fn exp(self) -> Self {
+        let v = self.v.exp();
+        self.chain(v, v)
+    }
+    fn exp2(self) -> Self {
+        let v = self.v.exp2();
+        self.chain(v, v * std::f32::consts::LN_2)
+    }
+    fn ln(self) -> Self {self.chain(self.v.ln(), 1.0 / self.v)}
+    fn log2(self) -> Self {self.chain(self.v.log2(), 1.0 / (self.v * std::f32::consts::LN_2))}
+
+    fn pow(self, y: Self) -> Self {
+        let v = self.v.powf(y.v);
+        let dv = y.v * self.v.powf(y.v - 1.0);
+        let dy = if self.v > 0.0 {v * self.v.ln()} else {0.0};
+        Self {
+            v,
+            dx: dv * self.dx + dy * y.dx,
+            dy: dv * self.dy + dy * y.dy,
+        }
+    }
+
+    fn min(self, other: Self) -> Self {if other.v < self.v {other} else {self}}
+    fn max(self, other: Self) -> Self {if other.v > self.v {other} else {self}}
+}
+
+impl Add for Dual {
+    type Output = Dual;
+    fn add(self, o: Dual) -> Dual {Dual {v: self.v + o.v, dx: self.dx + o.dx, dy: self.dy + o.dy}}
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+    fn sub(self, o: Dual) -> Dual {Dual {v: self.v - o.v, dx: self.dx - o.dx, dy: self.dy - o.dy}}
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+    fn mul(self, o: Dual) -> Dual {
+        Dual {v: self.v * o.v, dx: self.dx * o.v + self.v * o.dx, dy: self.dy * o.v + self.v * o.dy}
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+    fn div(self, o: Dual) -> Dual {
+        let d = o.v * o.v;
+        Dual {v: self.v / o.v, dx: (self.dx * o.v - self.v * o.dx) / d, dy: (self.dy * o.v - self.v * o.dy) / d}
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+    fn neg(self) -> Dual {Dual {v: -self.v, dx: -self.dx, dy: -self.dy}}
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Void,
+    // bools, ints, floats, vectors, matrices (column major) and enums
+    Num(Ty, Vec<Dual>),
+    Struct(Vec<Value>),
+    Array(Vec<Value>),
+    Texture(usize),
+    DrawShader,
+    Closure(Rc<Closure>),
+}
+
+impl Value {
+    fn comps(&self) -> &[Dual] {
+        match self {
+            Value::Num(_, comps) => comps,
+            _ => &[]
+        }
+    }
+
+    fn scalar(&self) -> Dual {
+        self.comps().first().cloned().unwrap_or_default()
+    }
+
+    fn num(ty: &Ty, comps: Vec<Dual>) -> Value {
+        Value::Num(ty.clone(), comps)
+    }
+
+    fn write_slots(&self, out: &mut Vec<f32>) {
+        match self {
+            Value::Num(_, comps) => out.extend(comps.iter().map( | c | c.v)),
+            Value::Array(elems) => for elem in elems {elem.write_slots(out)},
+            _ => ()
+        }
+    }
+}
+
+type Local = (Ident, ScopeSymShadow, Value);
+
+#[derive(Debug)]
+struct Closure {
+    owner: FnPtr,
+    index: ClosureDefIndex,
+    captured: Vec<Local>,
+    closure_args: Vec<Option<Rc<Closure >> >,
+}
+
+struct Frame<'a> {
+    // the fn whose body or closures are running, it owns the consts and closure defs
+    fn_def: &'a FnDef,
+    locals: Vec<Local>,
+    closure_args: Vec<Option<Rc<Closure >> >,
+}
+
+impl<'a> Frame<'a> {
+    fn get_local(&self, ident: Ident, shadow: ScopeSymShadow) -> Option<&Value> {
+        self.locals.iter().rev().find( | (i, s, _) | *i == ident && *s == shadow).map( | (_, _, v) | v)
+    }
+
+    fn set_local(&mut self, ident: Ident, shadow: ScopeSymShadow, value: Value) {
+        if let Some(local) = self.locals.iter_mut().rev().find( | (i, s, _) | *i == ident && *s == shadow) {
+            local.2 = value;
+        }
+        else {
+            self.locals.push((ident, shadow, value));
+        }
+    }
+}
+
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+fn interpret_error(span: TokenSpan, message: String) -> LiveError {
+    LiveError {
+        origin: live_error_origin!(),
+        span: span.into(),
+        message,
+    }
+}
+
+struct Exec<'a, 'b> {
+    interp: &'b ShaderInterpreter<'a>,
+    inputs: &'b InterpretInputs<'b>,
+    fields: Vec<Value>,
+}
+
+impl<'a, 'b> Exec<'a, 'b> {
+    fn new(interp: &'b ShaderInterpreter<'a>, inputs: &'b InterpretInputs<'b>) -> Self {
+        let mut fields = Vec::new();
+        let mut block_offsets: BTreeMap<Ident, usize> = BTreeMap::new();
+        let mut texture_index = 0;
+        for (index, field) in interp.draw_shader_def.fields.iter().enumerate() {
+            let ty = interp.field_ty(index);
+            fields.push(match &field.kind {
+                DrawShaderFieldKind::Uniform {block_ident, ..} => {
+                    let offset = block_offsets.entry(*block_ident).or_insert(0);
+                    let block = inputs.uniform_blocks.get(block_ident).map( | b | b.as_slice()).unwrap_or(&[]);
+                    value_from_slots(&ty, block, offset, None)
+                }
+                DrawShaderFieldKind::Texture {..} => {
+                    texture_index += 1;
+                    Value::Texture(texture_index - 1)
+                }
+                _ => interp.zero_value(&ty)
+            });
+        }
+        Self {interp, inputs, fields}
+    }
+
+    fn call_draw_shader_method(&mut self, ident: Ident) -> Result<Value, LiveError> {
+        let fn_def = self.interp.shader_registry.draw_shader_method_decl_from_ident(self.interp.draw_shader_def, ident).ok_or_else( || {
+            interpret_error(TokenSpan::default(), format!("draw shader has no {} method", ident))
+        }) ?;
+        let args = fn_def.params.iter().map( | _ | Value::DrawShader).collect();
+        Ok(self.call_fn(fn_def, args) ?.0)
+    }
+
+    fn call_fn(&mut self, fn_def: &'a FnDef, args: Vec<Value>) -> Result<(Value, Vec<Value>), LiveError> {
+        let mut frame = Frame {
+            fn_def,
+            locals: Vec::new(),
+            closure_args: vec![None; fn_def.params.len()],
+        };
+        for (param_index, (param, arg)) in fn_def.params.iter().zip(args).enumerate() {
+            match arg {
+                Value::Closure(closure) => frame.closure_args[param_index] = Some(closure),
+                arg => if let Some(shadow) = param.shadow.get() {
+                    frame.locals.push((param.ident, shadow, arg));
+                }
+            }
+        }
+        let ret = match self.exec_block(&mut frame, &fn_def.block) ? {
+            Flow::Return(value) => value,
+            _ => Value::Void
+        };
+        // hand back the inout params for copy-out
+        let outs = fn_def.params.iter().map( | param | {
+            match param.shadow.get() {
+                Some(shadow) if param.is_inout => frame.get_local(param.ident, shadow).cloned().unwrap_or(Value::Void),
+                _ => Value::Void
+            }
+        }).collect();
+        Ok((ret, outs))
+    }
+
+    fn exec_block(&mut self, frame: &mut Frame<'a>, block: &'a Block) -> Result<Flow, LiveError> {
+        for stmt in &block.stmts {
+            match self.exec_stmt(frame, stmt) ? {
+                Flow::Next => (),
+                flow => return Ok(flow)
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn exec_stmt(&mut self, frame: &mut Frame<'a>, stmt: &'a Stmt) -> Result<Flow, LiveError> {
+        match *stmt {
+            Stmt::Break {..} => Ok(Flow::Break),
+            Stmt::Continue {..} => Ok(Flow::Continue),
+            Stmt::For {
+                ident,
+                ref from_expr,
+                ref to_expr,
+                ref step_expr,
+                ref block,
+                ..
+            } => self.exec_for_stmt(frame, ident, from_expr, to_expr, step_expr, block),
+            Stmt::If {
+                ref expr,
+                ref block_if_true,
+                ref block_if_false,
+                ..
+            } => {
+                if self.eval_expr(frame, expr) ?.scalar().is_true() {
+                    self.exec_block(frame, block_if_true)
+                }
+                else if let Some(block_if_false) = block_if_false {
+                    self.exec_block(frame, block_if_false)
+                }
+                else {
+                    Ok(Flow::Next)
+                }
+            }
+            Stmt::Match {
+                ref expr,
+                ref matches,
+                ..
+            } => {
+                let value = self.eval_expr(frame, expr) ?.scalar().v;
+                for match_item in matches {
+                    if match_item.enum_value.get().map( | v | v as f32) == Some(value) {
+                        return self.exec_block(frame, &match_item.block)
+                    }
+                }
+                Ok(Flow::Next)
+            }
+            Stmt::Let {
+                ref ty,
+                ref shadow,
+                ident,
+                ref expr,
+                ..
+            } => {
+                let value = if let Some(expr) = expr {
+                    self.eval_expr(frame, expr) ?
+                }
+                else {
+                    self.interp.zero_value(ty.borrow().as_ref().unwrap())
+                };
+                frame.set_local(ident, shadow.get().unwrap(), value);
+                Ok(Flow::Next)
+            }
+            Stmt::Return {ref expr, ..} => {
+                let value = if let Some(expr) = expr {
+                    self.eval_expr(frame, expr) ?
+                }
+                else {
+                    Value::Void
+                };
+                Ok(Flow::Return(value))
+            }
+            Stmt::Block {ref block, ..} => self.exec_block(frame, block),
+            Stmt::Expr {ref expr, ..} => {
+                self.eval_expr(frame, expr) ?;
+                Ok(Flow::Next)
+            }
+        }
+    }
+
+    fn exec_for_stmt(
+        &mut self,
+        frame: &mut Frame<'a>,
+        ident: Ident,
+        from_expr: &Expr,
+        to_expr: &Expr,
+        step_expr: &Option<Expr>,
+        block: &'a Block
+    ) -> Result<Flow, LiveError> {
+        // same bounds as generate_for_stmt, the loop vars are always const
+        let const_int = | expr: &Expr | -> Result<i32, LiveError> {
+            expr.const_val.borrow().as_ref().and_then( | v | v.as_ref()).and_then( | v | v.to_int()).ok_or_else( || {
+                interpret_error(expr.span, String::from("for loop bounds are not const"))
+            })
+        };
+        let from = const_int(from_expr) ?;
+        let to = const_int(to_expr) ?;
+        let step = if let Some(step_expr) = step_expr {
+            const_int(step_expr) ?
+        } else if from < to {
+            1
+        } else {
+            -1
+        };
+        let mut i = if from <= to {from} else {from - 1};
+        loop {
+            if (from <= to && i >= to) || (from > to && i < to) {
+                break
+            }
+            frame.set_local(ident, ScopeSymShadow(0), Value::Num(Ty::Int, vec![Dual::new(i as f32)]));
+            match self.exec_block(frame, block) ? {
+                Flow::Break => break,
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Next | Flow::Continue => ()
+            }
+            if step > 0 {i += step} else {i -= step.abs()}
+        }
+        Ok(Flow::Next)
+    }
+
+    fn eval_expr(&mut self, frame: &mut Frame<'a>, expr: &'a Expr) -> Result<Value, LiveError> {
+        if let Some(Some(val)) = expr.const_val.borrow().as_ref() {
+            return Ok(self.eval_const(frame, val, expr.const_index.get()))
+        }
+        let ty = expr.ty.borrow().as_ref().cloned().unwrap_or(Ty::Void);
+        match expr.kind {
+            ExprKind::Cond {
+                expr: ref cond_expr,
+                ref expr_if_true,
+                ref expr_if_false,
+                ..
+            } => {
+                if self.eval_expr(frame, cond_expr) ?.scalar().is_true() {
+                    self.eval_expr(frame, expr_if_true)
+                }
+                else {
+                    self.eval_expr(frame, expr_if_false)
+                }
+            }
+            ExprKind::Bin {
+                span,
+                op,
+                ref left_expr,
+                ref right_expr,
+            } => self.eval_bin_expr(frame, span, &ty, op, left_expr, right_expr),
+            ExprKind::Un {op, expr: ref un_expr, ..} => {
+                let value = self.eval_expr(frame, un_expr) ?;
+                Ok(match op {
+                    UnOp::Not => Value::num(&ty, value.comps().iter().map( | c | Dual::bool(!c.is_true())).collect()),
+                    UnOp::Neg => Value::num(&ty, value.comps().iter().map( | c | -*c).collect()),
+                })
+            }
+            ExprKind::Field {
+                span,
+                expr: ref field_expr,
+                field_ident,
+            } => self.eval_field_expr(frame, span, &ty, field_expr, field_ident),
+            ExprKind::Index {
+                span,
+                expr: ref index_expr_base,
+                ref index_expr,
+            } => {
+                let base = self.eval_expr(frame, index_expr_base) ?;
+                let index = self.eval_expr(frame, index_expr) ?.scalar().v as usize;
+                get_index(&base, index).ok_or_else( || interpret_error(span, format!("index {} out of bounds", index)))
+            }
+            ExprKind::MethodCall {
+                span,
+                ident,
+                ref arg_exprs,
+                ..
+            } => {
+                let receiver_ty = arg_exprs[0].ty.borrow().as_ref().cloned();
+                match receiver_ty {
+                    Some(Ty::Struct(struct_ptr)) => {
+                        let fn_def = self.interp.shader_registry.struct_method_decl_from_ident(
+                            self.interp.shader_registry.structs.get(&struct_ptr).unwrap(),
+                            ident
+                        ).unwrap();
+                        self.eval_call(frame, fn_def, arg_exprs)
+                    }
+                    Some(Ty::DrawShader(shader_ptr)) => {
+                        let fn_def = self.interp.shader_registry.draw_shader_method_decl_from_ident(
+                            self.interp.shader_registry.draw_shader_defs.get(&shader_ptr).unwrap(),
+                            ident
+                        ).unwrap();
+                        self.eval_call(frame, fn_def, &arg_exprs[1..])
+                    }
+                    _ => Err(interpret_error(span, format!("cannot call method {} here", ident)))
+                }
+            }
+            ExprKind::PlainCall {
+                span,
+                fn_ptr,
+                ref arg_exprs,
+                ref param_index,
+                ..
+            } => {
+                if let Some(param_index) = param_index.get() {
+                    let closure = frame.closure_args.get(param_index).cloned().flatten().ok_or_else( || {
+                        interpret_error(span, String::from("closure argument is missing"))
+                    }) ?;
+                    let mut args = Vec::new();
+                    for arg_expr in arg_exprs {
+                        args.push(self.eval_expr(frame, arg_expr) ?);
+                    }
+                    self.call_closure(&closure, args)
+                }
+                else {
+                    let fn_def = self.interp.shader_registry.all_fns.get(&fn_ptr.unwrap()).unwrap();
+                    self.eval_call(frame, fn_def, arg_exprs)
+                }
+            }
+            ExprKind::BuiltinCall {
+                span,
+                ident,
+                ref arg_exprs,
+            } => {
+                let mut args = Vec::new();
+                for arg_expr in arg_exprs {
+                    args.push(self.eval_expr(frame, arg_expr) ?);
+                }
+                self.eval_builtin(span, &ty, ident, &args)
+            }
+            ExprKind::ClosureDef(index) => {
+                let closure_def = &frame.fn_def.closure_defs[index.0];
+                let mut captured = Vec::new();
+                for sym in closure_def.closed_over_syms.borrow().as_ref().unwrap() {
+                    if let Ty::DrawShader(_) = sym.ty {
+                        continue;
+                    }
+                    if let Some(value) = frame.get_local(sym.ident, sym.shadow) {
+                        captured.push((sym.ident, sym.shadow, value.clone()));
+                    }
+                }
+                Ok(Value::Closure(Rc::new(Closure {
+                    owner: frame.fn_def.fn_ptr,
+                    index,
+                    captured,
+                    closure_args: frame.closure_args.clone()
+                })))
+            }
+            ExprKind::ConsCall {
+                ty_lit,
+                ref arg_exprs,
+                ..
+            } => {
+                let mut args = Vec::new();
+                for arg_expr in arg_exprs {
+                    args.push(self.eval_expr(frame, arg_expr) ?);
+                }
+                Ok(cons(ty_lit.to_ty(), &args))
+            }
+            ExprKind::StructCons {
+                struct_ptr,
+                ref args,
+                ..
+            } => {
+                let struct_def = self.interp.shader_registry.structs.get(&struct_ptr).unwrap();
+                let mut fields = Vec::new();
+                for field in &struct_def.fields {
+                    let arg = args.iter().find( | (ident, _) | field.ident == *ident).unwrap();
+                    fields.push(self.eval_expr(frame, &arg.1) ?);
+                }
+                Ok(Value::Struct(fields))
+            }
+            ExprKind::Var {
+                span,
+                ref kind,
+                ..
+            } => {
+                if let Ty::DrawShader(_) = ty {
+                    return Ok(Value::DrawShader)
+                }
+                match kind.get() {
+                    Some(VarKind::Local {ident, shadow}) | Some(VarKind::MutLocal {ident, shadow}) => {
+                        frame.get_local(ident, shadow).cloned().ok_or_else( || {
+                            interpret_error(span, format!("variable {} is not defined", ident))
+                        })
+                    }
+                    Some(VarKind::LiveValue(value_ptr)) => {
+                        let mut offset = *self.interp.live_offsets.get(&value_ptr).unwrap();
+                        Ok(value_from_slots(&ty, &self.inputs.live_uniforms, &mut offset, None))
+                    }
+                    None => Err(interpret_error(span, String::from("variable is not resolved")))
+                }
+            }
+            ExprKind::Lit {lit, ..} => Ok(self.eval_const(frame, &lit.to_val(), None)),
+        }
+    }
+
+    // reads consts from the const table when they were gathered into it, like the GPU does
+    fn eval_const(&self, frame: &Frame<'a>, val: &Val, const_index: Option<usize>) -> Value {
+        let table_slots = | slots: usize | -> Option<Vec<Dual >> {
+            let offset = self.interp.const_table.offsets.get(&frame.fn_def.fn_ptr) ? + const_index ?;
+            let table = self.interp.const_table.table.get(offset..offset + slots) ?;
+            Some(table.iter().map( | v | Dual::new(*v)).collect())
+        };
+        match val {
+            Val::Bool(v) => Value::Num(Ty::Bool, vec![Dual::bool(*v)]),
+            Val::Int(v) => Value::Num(Ty::Int, vec![Dual::new(*v as f32)]),
+            Val::Float(v) => Value::Num(Ty::Float, table_slots(1).unwrap_or_else( || vec![Dual::new(*v)])),
+            Val::Vec4(v) => Value::Num(Ty::Vec4, table_slots(4).unwrap_or_else( || {
+                vec![Dual::new(v.x), Dual::new(v.y), Dual::new(v.z), Dual::new(v.w)]
+            })),
+        }
+    }
+
+    fn eval_call(&mut self, frame: &mut Frame<'a>, fn_def: &'a FnDef, arg_exprs: &'a [Expr]) -> Result<Value, LiveError> {
+        // draw shader methods get called without their self arg
+        let skip = fn_def.params.len() - arg_exprs.len();
+        let mut args = vec![Value::DrawShader; skip];
+        for arg_expr in arg_exprs {
+            args.push(self.eval_expr(frame, arg_expr) ?);
+        }
+        let (ret, outs) = self.call_fn(fn_def, args) ?;
+        for (arg_index, arg_expr) in arg_exprs.iter().enumerate() {
+            if fn_def.params[arg_index + skip].is_inout {
+                self.store(frame, arg_expr, outs[arg_index + skip].clone()) ?;
+            }
+        }
+        Ok(ret)
+    }
+
+    fn call_closure(&mut self, closure: &Closure, args: Vec<Value>) -> Result<Value, LiveError> {
+        let owner = self.interp.shader_registry.all_fns.get(&closure.owner).unwrap();
+        let closure_def = &owner.closure_defs[closure.index.0];
+        let mut frame = Frame {
+            fn_def: owner,
+            locals: closure.captured.clone(),
+            closure_args: closure.closure_args.clone(),
+        };
+        for (param, arg) in closure_def.params.iter().zip(args) {
+            if let Some(shadow) = param.shadow.get() {
+                frame.locals.push((param.ident, shadow, arg));
+            }
+        }
+        match &closure_def.kind {
+            ClosureDefKind::Expr(expr) => self.eval_expr(&mut frame, expr),
+            ClosureDefKind::Block(block) => match self.exec_block(&mut frame, block) ? {
+                Flow::Return(value) => Ok(value),
+                _ => Ok(Value::Void)
+            }
+        }
+    }
+
+    fn eval_field_expr(&mut self, frame: &mut Frame<'a>, span: TokenSpan, ty: &Ty, expr: &'a Expr, field_ident: Ident) -> Result<Value, LiveError> {
+        let expr_ty = expr.ty.borrow().as_ref().cloned();
+        match expr_ty {
+            Some(Ty::DrawShader(_)) => {
+                let index = self.draw_shader_field_index(span, field_ident) ?;
+                Ok(self.fields[index].clone())
+            }
+            Some(Ty::Struct(struct_ptr)) => {
+                let index = self.struct_field_index(struct_ptr, field_ident);
+                match self.eval_expr(frame, expr) ? {
+                    Value::Struct(mut fields) => Ok(fields.swap_remove(index)),
+                    _ => Err(interpret_error(span, String::from("field access on a non struct value")))
+                }
+            }
+            _ => {
+                let value = self.eval_expr(frame, expr) ?;
+                let swizzle = Swizzle::parse(field_ident).unwrap();
+                let comps = value.comps();
+                Ok(Value::num(ty, swizzle.into_iter().map( | i | comps[*i]).collect()))
+            }
+        }
+    }
+
+    fn draw_shader_field_index(&self, span: TokenSpan, field_ident: Ident) -> Result<usize, LiveError> {
+        self.interp.draw_shader_def.fields.iter().position( | field | field.ident == field_ident).ok_or_else( || {
+            interpret_error(span, format!("draw shader has no field {}", field_ident))
+        })
+    }
+
+    fn struct_field_index(&self, struct_ptr: StructPtr, field_ident: Ident) -> usize {
+        let struct_def = self.interp.shader_registry.structs.get(&struct_ptr).unwrap();
+        struct_def.fields.iter().position( | field | field.ident == field_ident).unwrap()
+    }
+
+    // writes a value back into whatever an lvalue expression refers to
+    fn store(&mut self, frame: &mut Frame<'a>, expr: &'a Expr, value: Value) -> Result<(), LiveError> {
+        match expr.kind {
+            ExprKind::Var {span, ref kind, ..} => match kind.get() {
+                Some(VarKind::Local {ident, shadow}) | Some(VarKind::MutLocal {ident, shadow}) => {
+                    frame.set_local(ident, shadow, value);
+                    Ok(())
+                }
+                _ => Err(interpret_error(span, String::from("cannot assign to this variable")))
+            }
+            ExprKind::Field {span, expr: ref field_expr, field_ident} => {
+                let expr_ty = field_expr.ty.borrow().as_ref().cloned();
+                match expr_ty {
+                    Some(Ty::DrawShader(_)) => {
+                        let index = self.draw_shader_field_index(span, field_ident) ?;
+                        self.fields[index] = value;
+                        Ok(())
+                    }
+                    Some(Ty::Struct(struct_ptr)) => {
+                        let index = self.struct_field_index(struct_ptr, field_ident);
+                        let mut parent = self.eval_expr(frame, field_expr) ?;
+                        if let Value::Struct(fields) = &mut parent {
+                            fields[index] = value;
+                        }
+                        self.store(frame, field_expr, parent)
+                    }
+                    _ => {
+                        let mut parent = self.eval_expr(frame, field_expr) ?;
+                        let swizzle = Swizzle::parse(field_ident).unwrap();
+                        if let Value::Num(_, comps) = &mut parent {
+                            for (src, dst) in swizzle.into_iter().enumerate() {
+                                comps[*dst] = value.comps()[src];
+                            }
+                        }
+                        self.store(frame, field_expr, parent)
+                    }
+                }
+            }
+            ExprKind::Index {span, expr: ref base_expr, ref index_expr} => {
+                let mut parent = self.eval_expr(frame, base_expr) ?;
+                let index = self.eval_expr(frame, index_expr) ?.scalar().v as usize;
+                if !set_index(&mut parent, index, value) {
+                    return Err(interpret_error(span, format!("index {} out of bounds", index)))
+                }
+                self.store(frame, base_expr, parent)
+            }
+            _ => Err(interpret_error(expr.span, String::from("expression is not assignable")))
+        }
+    }
+
+    fn eval_bin_expr(&mut self, frame: &mut Frame<'a>, span: TokenSpan, ty: &Ty, op: BinOp, left_expr: &'a Expr, right_expr: &'a Expr) -> Result<Value, LiveError> {
+        let arith_op = match op {
+            BinOp::Assign => {
+                let value = self.eval_expr(frame, right_expr) ?;
+                self.store(frame, left_expr, value.clone()) ?;
+                return Ok(value)
+            }
+            BinOp::AddAssign => Some(BinOp::Add),
+            BinOp::SubAssign => Some(BinOp::Sub),
+            BinOp::MulAssign => Some(BinOp::Mul),
+            BinOp::DivAssign => Some(BinOp::Div),
+            BinOp::Or | BinOp::And => {
+                let left = self.eval_expr(frame, left_expr) ?.scalar().is_true();
+                if let (BinOp::Or, true) | (BinOp::And, false) = (op, left) {
+                    return Ok(Value::num(ty, vec![Dual::bool(left)]))
+                }
+                let right = self.eval_expr(frame, right_expr) ?.scalar().is_true();
+                return Ok(Value::num(ty, vec![Dual::bool(right)]))
+            }
+            _ => None
+        };
+        let left = self.eval_expr(frame, left_expr) ?;
+        let right = self.eval_expr(frame, right_expr) ?;
+        if let Some(arith_op) = arith_op {
+            // compound assignments have the type of their left side
+            let left_ty = left_expr.ty.borrow().as_ref().cloned().unwrap();
+            let value = arith(span, &left_ty, arith_op, &left, &right) ?;
+            self.store(frame, left_expr, value.clone()) ?;
+            return Ok(value)
+        }
+        match op {
+            BinOp::Eq | BinOp::Ne => {
+                let equal = left.comps().iter().zip(right.comps()).all( | (a, b) | a.v == b.v);
+                Ok(Value::num(ty, vec![Dual::bool(equal == matches!(op, BinOp::Eq))]))
+            }
+            BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => {
+                let (a, b) = (left.scalar().v, right.scalar().v);
+                Ok(Value::num(ty, vec![Dual::bool(match op {
+                    BinOp::Lt => a < b,
+                    BinOp::Le => a <= b,
+                    BinOp::Gt => a > b,
+                    _ => a >= b,
+                })]))
+            }
+            _ => arith(span, ty, op, &left, &right)
+        }
+    }
+
+    fn eval_builtin(&mut self, span: TokenSpan, ty: &Ty, ident: Ident, args: &[Value]) -> Result<Value, LiveError> {
+        let comps = match ident.0 {
+            live_id!(abs) => map1(args, Dual::abs),
+            live_id!(acos) => map1(args, Dual::acos),
+            live_id!(asin) => map1(args, Dual::asin),
+            live_id!(atan) if args.len() == 2 => map2(args, Dual::atan2),
+            live_id!(atan) => map1(args, Dual::atan),
+            live_id!(ceil) => map1(args, Dual::ceil),
+            live_id!(cos) => map1(args, Dual::cos),
+            live_id!(degrees) => map1(args, | x | x * Dual::new(180.0 / std::f32::consts::PI)),
+            live_id!(exp) => map1(args, Dual::exp),
+            live_id!(exp2) => map1(args, Dual::exp2),
+            live_id!(floor) => map1(args, Dual::floor),
+            live_id!(fract) => map1(args, Dual::fract),
+            live_id!(inversesqrt) => map1(args, Dual::inverse_sqrt),
+            live_id!(log) => map1(args, Dual::ln),
+            live_id!(log2) => map1(args, Dual::log2),
+            live_id!(radians) => map1(args, | x | x * Dual::new(std::f32::consts::PI / 180.0)),
+            live_id!(sign) => map1(args, Dual::sign),
+            live_id!(sin) => map1(args, Dual::sin),
+            live_id!(sqrt) => map1(args, Dual::sqrt),
+            live_id!(tan) => map1(args, Dual::tan),
+            live_id!(max) => map2(args, Dual::max),
+            live_id!(min) => map2(args, Dual::min),
+            live_id!(mod) => map2(args, | x, y | x - y * (x / y).floor()),
+            live_id!(pow) => map2(args, Dual::pow),
+            live_id!(step) => map2(args, | edge, x | Dual::bool(x.v >= edge.v)),
+            live_id!(clamp) => map3(args, | x, lo, hi | x.max(lo).min(hi)),
+            live_id!(mix) => map3(args, | a, b, t | a + (b - a) * t),
+            live_id!(smoothstep) => map3(args, | e0, e1, x | {
+                let t = ((x - e0) / (e1 - e0)).max(Dual::new(0.0)).min(Dual::new(1.0));
+                t * t * (Dual::new(3.0) - Dual::new(2.0) * t)
+            }),
+            live_id!(dot) => vec![dot(args[0].comps(), args[1].comps())],
+            live_id!(length) => vec![dot(args[0].comps(), args[0].comps()).sqrt()],
+            live_id!(distance) => {
+                let d: Vec<Dual> = args[0].comps().iter().zip(args[1].comps()).map( | (a, b) | *a - *b).collect();
+                vec![dot(&d, &d).sqrt()]
+            }
+            live_id!(normalize) => {
+                let v = args[0].comps();
+                let len = dot(v, v).sqrt();
+                v.iter().map( | c | *c / len).collect()
+            }
+            live_id!(cross) => {
+                let (a, b) = (args[0].comps(), args[1].comps());
+                vec![
+                    a[1] * b[2] - a[2] * b[1],
+                    a[2] * b[0] - a[0] * b[2],
+                    a[0] * b[1] - a[1] * b[0],
+                ]
+            }
+            live_id!(reflect) => {
+                let (i, n) = (args[0].comps(), args[1].comps());
+                let d = Dual::new(2.0) * dot(n, i);
+                i.iter().zip(n).map( | (i, n) | *i - d * *n).collect()
+            }
+            live_id!(refract) => {
+                let (i, n, eta) = (args[0].comps(), args[1].comps(), args[2].scalar());
+                let d = dot(n, i);
+                let k = Dual::new(1.0) - eta * eta * (Dual::new(1.0) - d * d);
+                if k.v < 0.0 {
+                    vec![Dual::default(); i.len()]
+                }
+                else {
+                    let f = eta * d + k.sqrt();
+                    i.iter().zip(n).map( | (i, n) | eta * *i - f * *n).collect()
+                }
+            }
+            live_id!(faceforward) => {
+                let (n, i, nref) = (args[0].comps(), args[1].comps(), args[2].comps());
+                if dot(nref, i).v < 0.0 {n.to_vec()} else {n.iter().map( | c | -*c).collect()}
+            }
+            live_id!(lessThan) => map2(args, | a, b | Dual::bool(a.v < b.v)),
+            live_id!(lessThanEqual) => map2(args, | a, b | Dual::bool(a.v <= b.v)),
+            live_id!(greaterThan) => map2(args, | a, b | Dual::bool(a.v > b.v)),
+            live_id!(greaterThanEqual) => map2(args, | a, b | Dual::bool(a.v >= b.v)),
+            live_id!(equal) => map2(args, | a, b | Dual::bool(a.v == b.v)),
+            live_id!(notEqual) => map2(args, | a, b | Dual::bool(a.v != b.v)),
+            live_id!(not) => map1(args, | a | Dual::bool(!a.is_true())),
+            live_id!(all) => vec![Dual::bool(args[0].comps().iter().all( | c | c.is_true()))],
+            live_id!(any) => vec![Dual::bool(args[0].comps().iter().any( | c | c.is_true()))],
+            live_id!(matrixCompMult) => map2(args, | a, b | a * b),
+            live_id!(transpose) => {
+                let m = args[0].comps();
+                let n = matrix_dim(m.len());
+                (0..n * n).map( | i | m[(i % n) * n + i / n]).collect()
+            }
+            live_id!(inverse) => invert(args[0].comps()),
+            live_id!(dFdx) => map1(args, | a | Dual::new(a.dx)),
+            live_id!(dFdy) => map1(args, | a | Dual::new(a.dy)),
+            live_id!(sample2d) | live_id!(sample2d_rt) | live_id!(sample2dOES) => {
+                let pos = args[1].comps();
+                let texture = match args[0] {
+                    Value::Texture(index) => self.inputs.textures.get(index).cloned().flatten(),
+                    _ => None
+                };
+                // like an unbound texture, sampling nothing gives transparent black
+                let color = match texture {
+                    Some(texture) => texture.sample(vec2(pos[0].v, pos[1].v)),
+                    None => Vec4::default()
+                };
+                vec![Dual::new(color.x), Dual::new(color.y), Dual::new(color.z), Dual::new(color.w)]
+            }
+            _ => return Err(interpret_error(span, format!("builtin {} is not supported by the interpreter", ident)))
+        };
+        Ok(Value::num(ty, comps))
+    }
+}
+
+fn arith(span: TokenSpan, ty: &Ty, op: BinOp, left: &Value, right: &Value) -> Result<Value, LiveError> {
+    let (l, r) = (left.comps(), right.comps());
+    let left_ty = match left {Value::Num(ty, _) => ty.clone(), _ => Ty::Void};
+    let right_ty = match right {Value::Num(ty, _) => ty.clone(), _ => Ty::Void};
+    if let BinOp::Mul = op {
+        // the linear algebra cases, everything else is component wise
+        match (left_ty.is_matrix(), right_ty.is_matrix()) {
+            (true, true) => {
+                let n = matrix_dim(l.len());
+                return Ok(Value::num(ty, (0..n * n).map( | i | {
+                    let (col, row) = (i / n, i % n);
+                    (0..n).fold(Dual::default(), | acc, k | acc + l[k * n + row] * r[col * n + k])
+                }).collect()))
+            }
+            (true, false) if right_ty.is_vector() => {
+                let n = r.len();
+                return Ok(Value::num(ty, (0..n).map( | row | {
+                    (0..n).fold(Dual::default(), | acc, k | acc + l[k * n + row] * r[k])
+                }).collect()))
+            }
+            (false, true) if left_ty.is_vector() => {
+                let n = l.len();
+                return Ok(Value::num(ty, (0..n).map( | col | {
+                    (0..n).fold(Dual::default(), | acc, k | acc + l[k] * r[col * n + k])
+                }).collect()))
+            }
+            _ => ()
+        }
+    }
+    let is_int = matches!(ty, Ty::Int | Ty::Ivec2 | Ty::Ivec3 | Ty::Ivec4);
+    let len = l.len().max(r.len());
+    if l.is_empty() || r.is_empty() || (l.len() != len && l.len() != 1) || (r.len() != len && r.len() != 1) {
+        return Err(interpret_error(span, format!("cannot apply {} to these operands", op)))
+    }
+    Ok(Value::num(ty, (0..len).map( | i | {
+        let (a, b) = (l[if l.len() == 1 {0} else {i}], r[if r.len() == 1 {0} else {i}]);
+        match op {
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            _ if is_int => (a / b).trunc(),
+            _ => a / b,
+        }
+    }).collect()))
+}
+
+fn broadcast(args: &[Value], i: usize, arg: usize) -> Dual {
+    let comps = args[arg].comps();
+    if comps.len() == 1 {comps[0]} else {comps.get(i).cloned().unwrap_or_default()}
+}
+
+fn broadcast_len(args: &[Value]) -> usize {
+    args.iter().map( | arg | arg.comps().len()).max().unwrap_or(0)
+}
+
+fn map1(args: &[Value], f: impl Fn(Dual) -> Dual) -> Vec<Dual> {
+    args[0].comps().iter().map( | c | f(*c)).collect()
+}
+
+fn map2(args: &[Value], f: impl Fn(Dual, Dual) -> Dual) -> Vec<Dual> {
+    (0..broadcast_len(args)).map( | i | f(broadcast(args, i, 0), broadcast(args, i, 1))).collect()
+}
+
+fn map3(args: &[Value], f: impl Fn(Dual, Dual, Dual) -> Dual) -> Vec<Dual> {
+    (0..broadcast_len(args)).map( | i | f(broadcast(args, i, 0), broadcast(args, i, 1), broadcast(args, i, 2))).collect()
+}
+
+fn dot(a: &[Dual], b: &[Dual]) -> Dual {
+    a.iter().zip(b).fold(Dual::default(), | acc, (a, b) | acc + *a * *b)
+}
+
+fn matrix_dim(len: usize) -> usize {
+    match len {
+        4 => 2,
+        9 => 3,
+        _ => 4
+    }
+}
+
+// Gauss-Jordan elimination, the derivatives are dropped
+fn invert(m: &[Dual]) -> Vec<Dual> {
+    let n = matrix_dim(m.len());
+    let mut a: Vec<f64> = m.iter().map( | c | c.v as f64).collect();
+    let mut inv: Vec<f64> = (0..n * n).map( | i | if i / n == i % n {1.0} else {0.0}).collect();
+    // a[col * n + row], eliminate per column
+    for col in 0..n {
+        let pivot = (col..n).max_by( | x, y | a[col * n + x].abs().total_cmp(&a[col * n + y].abs())).unwrap();
+        for c in 0..n {
+            a.swap(c * n + col, c * n + pivot);
+            inv.swap(c * n + col, c * n + pivot);
+        }
+        let p = a[col * n + col];
+        for c in 0..n {
+            a[c * n + col] /= p;
+            inv[c * n + col] /= p;
+        }
+        for row in 0..n {
+            if row != col {
+                let f = a[col * n + row];
+                for c in 0..n {
+                    a[c * n + row] -= f * a[c * n + col];
+                    inv[c * n + row] -= f * inv[c * n + col];
+                }
+            }
+        }
+    }
+    inv.iter().map( | v | Dual::new(*v as f32)).collect()
+}
+
+fn get_index(value: &Value, index: usize) -> Option<Value> {
+    match value {
+        Value::Array(elems) => elems.get(index).cloned(),
+        Value::Num(ty, comps) if ty.is_matrix() => {
+            let n = matrix_dim(comps.len());
+            let ty = match n {2 => Ty::Vec2, 3 => Ty::Vec3, _ => Ty::Vec4};
+            comps.get(index * n..index * n + n).map( | col | Value::Num(ty, col.to_vec()))
+        }
+        Value::Num(ty, comps) => {
+            let ty = match ty {
+                Ty::Ivec2 | Ty::Ivec3 | Ty::Ivec4 => Ty::Int,
+                Ty::Bvec2 | Ty::Bvec3 | Ty::Bvec4 => Ty::Bool,
+                _ => Ty::Float
+            };
+            comps.get(index).map( | c | Value::Num(ty, vec![*c]))
+        }
+        _ => None
+    }
+}
+
+fn set_index(value: &mut Value, index: usize, new_value: Value) -> bool {
+    match value {
+        Value::Array(elems) if index < elems.len() => {
+            elems[index] = new_value;
+            true
+        }
+        Value::Num(ty, comps) if ty.is_matrix() => {
+            let n = matrix_dim(comps.len());
+            if index >= n {
+                return false
+            }
+            comps[index * n..index * n + n].copy_from_slice(&new_value.comps()[..n]);
+            true
+        }
+        Value::Num(_, comps) if index < comps.len() => {
+            comps[index] = new_value.scalar();
+            true
+        }
+        _ => false
+    }
+}
+
+// the vector/matrix constructors, with the same splatting and resizing rules as GLSL
+fn cons(ty: Ty, args: &[Value]) -> Value {
+    let slots = ty.slots();
+    let arg_ty = match args.first() {Some(Value::Num(ty, _)) => ty.clone(), _ => Ty::Void};
+    let mut comps: Vec<Dual> = if args.len() == 1 && arg_ty.is_scalar() {
+        let c = args[0].scalar();
+        if ty.is_matrix() {
+            let n = matrix_dim(slots);
+            (0..slots).map( | i | if i / n == i % n {c} else {Dual::default()}).collect()
+        }
+        else {
+            vec![c; slots]
+        }
+    }
+    else if args.len() == 1 && arg_ty.is_matrix() && ty.is_matrix() {
+        let (src, n, m) = (args[0].comps(), matrix_dim(slots), matrix_dim(args[0].comps().len()));
+        (0..slots).map( | i | {
+            let (col, row) = (i / n, i % n);
+            if col < m && row < m {src[col * m + row]}
+            else {Dual::new(if col == row {1.0} else {0.0})}
+        }).collect()
+    }
+    else {
+        args.iter().flat_map( | arg | arg.comps().iter().cloned()).take(slots).collect()
+    };
+    comps.resize(slots, Dual::default());
+    match ty {
+        Ty::Int | Ty::Ivec2 | Ty::Ivec3 | Ty::Ivec4 => for c in &mut comps {*c = c.trunc()},
+        Ty::Bool | Ty::Bvec2 | Ty::Bvec3 | Ty::Bvec4 => for c in &mut comps {*c = Dual::bool(c.is_true())},
+        _ => ()
+    }
+    Value::Num(ty, comps)
+}
@@ -21,6 +21,8 @@ pub mod generate_glsl;
 pub mod generate_metal;
 #[cfg(any(target_os = "windows"))]
 pub mod generate_hlsl;
+pub mod generate_wgsl;
+pub mod interpret;
 
 pub use makepad_live_compiler;
 pub use makepad_live_compiler::makepad_math;
@@ -0,0 +1,283 @@
+use {
+    std::sync::{Arc, Mutex, mpsc::{channel, Sender, Receiver}},
+    crate::{
+        makepad_math::Rect,
+        area::Area,
+        cx::Cx,
+        window::WindowId,
+        event::{Event, AccessActionEvent},
+    }
+};
+
+/// Identifies an accessibility node across redraws, widgets use their widget uid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AccessNodeId(pub u64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessRole {
+    Group,
+    Label,
+    Button,
+    CheckBox,
+    RadioButton,
+    TextInput,
+    Slider,
+    SpinButton,
+    DropDown,
+    DateEditor,
+    List,
+    ListItem,
+    Image,
+    Link,
+    TabList,
+    Tab,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AccessStates {
+    pub disabled: bool,
+    pub focusable: bool,
+    pub focused: bool,
+    /// `None` for nodes that can't be checked at all
+    pub checked: Option<bool>,
+    pub selected: bool,
+    /// `None` for nodes that can't be expanded at all
+    pub expanded: Option<bool>,
+    pub editable: bool,
+    pub multiline: bool,
+    pub password: bool,
+    /// The value was rejected, the description says why
+    pub invalid: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccessValue {
+    Text(String),
+    Number {value: f64, min: f64, max: f64, step: f64},
+}
+
+/// The actions a node says it supports
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessActionKind {
+    Press,
+    Focus,
+    Increment,
+    Decrement,
+    SetValue,
+}
+
+/// An action requested by an assistive technology
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccessAction {
+    Press,
+    Focus,
+    Increment,
+    Decrement,
+    SetText(String),
+    SetNumber(f64),
+}
+
+impl AccessAction {
+    pub fn kind(&self) -> AccessActionKind {
+        match self {
+            Self::Press => AccessActionKind::Press,
+            Self::Focus => AccessActionKind::Focus,
+            Self::Increment => AccessActionKind::Increment,
+            Self::Decrement => AccessActionKind::Decrement,
+            Self::SetText(_) | Self::SetNumber(_) => AccessActionKind::SetValue,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessNode {
+    pub id: AccessNodeId,
+    pub role: AccessRole,
+    pub name: String,
+    pub description: String,
+    pub value: Option<AccessValue>,
+    pub states: AccessStates,
+    pub actions: Vec<AccessActionKind>,
+    pub area: Area,
+    // filled in when the node is added to a tree
+    pub rect: Rect,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+impl AccessNode {
+    pub fn new(id: AccessNodeId, role: AccessRole) -> Self {
+        Self {
+            id,
+            role,
+            name: String::new(),
+            description: String::new(),
+            value: None,
+            states: AccessStates::default(),
+            actions: Vec::new(),
+            area: Area::Empty,
+            rect: Rect::default(),
+            parent: None,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn with_value(mut self, value: AccessValue) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn with_states(mut self, states: AccessStates) -> Self {
+        self.states = states;
+        self
+    }
+
+    pub fn with_action(mut self, action: AccessActionKind) -> Self {
+        if !self.actions.contains(&action) {
+            self.actions.push(action);
+        }
+        self
+    }
+
+    pub fn with_area(mut self, area: Area) -> Self {
+        self.area = area;
+        self
+    }
+
+    pub fn has_action(&self, action: AccessActionKind) -> bool {
+        self.actions.contains(&action)
+    }
+}
+
+/// The accessibility nodes of one window, stored flat with their parent/child links
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessTree {
+    pub window_id: WindowId,
+    pub title: String,
+    /// the window on screen, node rects are relative to it
+    pub rect: Rect,
+    pub nodes: Vec<AccessNode>,
+    pub roots: Vec<usize>,
+}
+
+impl AccessTree {
+    pub fn new(window_id: WindowId, title: impl Into<String>) -> Self {
+        Self {
+            window_id,
+            title: title.into(),
+            rect: Rect::default(),
+            nodes: Vec::new(),
+            roots: Vec::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, parent: Option<usize>, mut node: AccessNode) -> usize {
+        let index = self.nodes.len();
+        node.parent = parent;
+        node.children.clear();
+        self.nodes.push(node);
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(index);
+        }
+        else {
+            self.roots.push(index);
+        }
+        index
+    }
+
+    pub fn index_of(&self, id: AccessNodeId) -> Option<usize> {
+        self.nodes.iter().position( | node | node.id == id)
+    }
+
+    pub fn find(&self, id: AccessNodeId) -> Option<&AccessNode> {
+        self.nodes.iter().find( | node | node.id == id)
+    }
+
+    pub fn find_by_name(&self, role: AccessRole, name: &str) -> Option<&AccessNode> {
+        self.nodes.iter().find( | node | node.role == role && node.name == name)
+    }
+
+    pub fn focused(&self) -> Option<&AccessNode> {
+        self.nodes.iter().find( | node | node.states.focused)
+    }
+}
+
+/// The trees of all windows, shared with the platform bridge that exposes them
+#[derive(Debug, Default)]
+pub struct AccessTrees {
+    /// bumped every time a tree changes
+    pub generation: u64,
+    pub trees: Vec<AccessTree>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AccessRequest {
+    pub window_id: WindowId,
+    pub node_id: AccessNodeId,
+    pub action: AccessAction,
+}
+
+pub struct CxAccess {
+    pub (crate) trees: Arc<Mutex<AccessTrees>>,
+    pub (crate) request_sender: Sender<AccessRequest>,
+    pub (crate) request_receiver: Receiver<AccessRequest>,
+}
+
+impl Default for CxAccess {
+    fn default() -> Self {
+        let (request_sender, request_receiver) = channel();
+        Self {
+            trees: Default::default(),
+            request_sender,
+            request_receiver,
+        }
+    }
+}
+
+impl Cx {
+    /// Replaces the accessibility trees of all windows, called at the end of every draw
+    pub fn set_access_trees(&mut self, trees: Vec<AccessTree>) {
+        let mut shared = self.access.trees.lock().unwrap();
+        if shared.trees != trees {
+            shared.trees = trees;
+            shared.generation += 1;
+        }
+    }
+
+    pub fn access_tree(&self, window_id: WindowId) -> Option<AccessTree> {
+        self.access.trees.lock().unwrap().trees.iter().find( | tree | tree.window_id == window_id).cloned()
+    }
+
+    /// Queues an action as if it came from an assistive technology
+    pub fn access_request(&mut self, request: AccessRequest) {
+        let _ = self.access.request_sender.send(request);
+    }
+
+    pub (crate) fn handle_access_requests(&mut self) {
+        while let Ok(request) = self.access.request_receiver.try_recv() {
+            let area = self.access.trees.lock().unwrap().trees.iter()
+                .find( | tree | tree.window_id == request.window_id)
+                .and_then( | tree | tree.find(request.node_id))
+                .filter( | node | node.has_action(request.action.kind()) && !node.states.disabled)
+                .map( | node | node.area);
+            if let Some(area) = area {
+                self.call_event_handler(&Event::AccessAction(AccessActionEvent {
+                    window_id: request.window_id,
+                    node_id: request.node_id,
+                    area,
+                    action: request.action,
+                }));
+            }
+        }
+    }
+}
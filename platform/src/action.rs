@@ -86,6 +86,8 @@ impl Cx{
         while let Ok(action) = self.action_receiver.try_recv(){
             self.new_actions.push(action);
         }
+        self.handle_access_requests();
+        self.handle_appearance_changes();
         self.handle_actions();
     }
     
@@ -0,0 +1,74 @@
+use {
+    std::sync::mpsc::{channel, Sender, Receiver},
+    crate::{
+        cx::Cx,
+        thread::SignalToUI,
+    }
+};
+
+/// The light or dark preference of the OS. The platform layers report it from any thread,
+/// the event loop picks it up and, when asked to, copies it into the `dark_mode` env variable
+pub struct CxAppearance {
+    pub (crate) follow_system: bool,
+    pub (crate) system_dark_mode: Option<bool>,
+    pub (crate) sender: Sender<bool>,
+    pub (crate) receiver: Receiver<bool>,
+}
+
+impl Default for CxAppearance {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            follow_system: false,
+            system_dark_mode: None,
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Cx {
+    /// Makes the `dark_mode` env variable follow the preference of the OS, on the platforms that report one
+    pub fn set_follow_system_dark_mode(&mut self, follow: bool) {
+        self.appearance.follow_system = follow;
+        if let (true, Some(dark_mode)) = (follow, self.appearance.system_dark_mode) {
+            self.set_dark_mode(dark_mode);
+        }
+    }
+    
+    pub fn follow_system_dark_mode(&self) -> bool {
+        self.appearance.follow_system
+    }
+    
+    /// The preference of the OS, `None` until the platform reported one
+    pub fn system_dark_mode(&self) -> Option<bool> {
+        self.appearance.system_dark_mode
+    }
+    
+    /// Reports the preference of the OS from any thread, for the platform layers
+    /// and for apps that learn about it some other way
+    pub fn system_dark_mode_sender(&self) -> SystemDarkModeSender {
+        SystemDarkModeSender(self.appearance.sender.clone())
+    }
+    
+    // platform threads send the preference and wake up the ui thread, which ends up here
+    pub (crate) fn handle_appearance_changes(&mut self) {
+        while let Ok(dark_mode) = self.appearance.receiver.try_recv() {
+            self.appearance.system_dark_mode = Some(dark_mode);
+            if self.appearance.follow_system {
+                self.set_dark_mode(dark_mode);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SystemDarkModeSender(Sender<bool>);
+
+impl SystemDarkModeSender {
+    pub fn send(&self, dark_mode: bool) {
+        if self.0.send(dark_mode).is_ok() {
+            SignalToUI::set_ui_signal();
+        }
+    }
+}
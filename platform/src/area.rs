@@ -8,6 +8,8 @@ use {
             LiveId,
         },
         draw_list::DrawListId,
+        pass::CxPassParent,
+        window::WindowId,
         cx::Cx
     }
 };
@@ -140,6 +142,18 @@ impl Area {
         }
     }
 
+    /// The window the area is drawn in, None when it is drawn into a pass no window shows
+    pub fn window_id(&self, cx: &Cx) -> Option<WindowId> {
+        let mut pass_id = cx.draw_lists.checked_index(self.draw_list_id()?)?.pass_id?;
+        loop {
+            match cx.passes[pass_id].parent {
+                CxPassParent::Window(window_id) => return Some(window_id),
+                CxPassParent::Pass(parent_id) => pass_id = parent_id,
+                CxPassParent::None => return None
+            }
+        }
+    }
+    
     // returns the final screen rect
     pub fn clipped_rect(&self, cx: &Cx) -> Rect {
         
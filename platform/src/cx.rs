@@ -34,6 +34,8 @@ use {
         action::ActionsBuf,
         cx_api::CxOsOp,
         area::Area,
+        access::CxAccess,
+        appearance::CxAppearance,
         gpu_info::GpuInfo,
         window::CxWindowPool,
         draw_list::CxDrawListPool,
@@ -82,6 +84,8 @@ pub struct Cx {
     pub fingers: CxFingers,
     pub (crate) ime_area: Area,
     pub (crate) drag_drop: CxDragDrop,
+    pub (crate) access: CxAccess,
+    pub (crate) appearance: CxAppearance,
     
     pub (crate) platform_ops: Vec<CxOsOp>,
     
@@ -264,6 +268,8 @@ impl Cx {
             keyboard: Default::default(),
             fingers: Default::default(),
             drag_drop: Default::default(),
+            access: Default::default(),
+            appearance: Default::default(),
             ime_area: Default::default(),
             platform_ops: Default::default(),
             studio_web_socket: None,
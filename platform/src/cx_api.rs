@@ -449,6 +449,10 @@ impl Cx {
         self.keyboard.has_key_focus(focus_area)
     }
 
+    pub fn key_focus(&self) -> Area {
+        self.keyboard.key_focus()
+    }
+
     pub fn new_next_frame(&mut self) -> NextFrame {
         let res = NextFrame(self.next_frame_id);
         self.next_frame_id += 1;
@@ -170,7 +170,8 @@ pub enum DrawShaderInputPacking {
     #[allow(dead_code)]
     UniformsHLSL,
     #[allow(dead_code)]
-    UniformsMetal
+    UniformsMetal,
+    UniformsWGSL
 }
 
 
@@ -250,6 +251,18 @@ impl DrawShaderInputs {
                 });
                 self.total_slots += aligned_slots;
             }
+            DrawShaderInputPacking::UniformsWGSL => {
+                let (align, aligned_slots) = wgsl_uniform_layout(&ty);
+                self.total_slots = self.total_slots.next_multiple_of(align);
+                self.inputs.push(DrawShaderInput {
+                    id,
+                    offset: self.total_slots,
+                    slots,
+                    ty,
+                    live_ptr
+                });
+                self.total_slots += aligned_slots;
+            }
         }
     }
     
@@ -263,7 +276,31 @@ impl DrawShaderInputs {
                     self.total_slots += 4 - (self.total_slots & 3);
                 }
             }
+            DrawShaderInputPacking::UniformsWGSL => {
+                // a struct is as big as a multiple of its widest alignment
+                let align = self.inputs.iter().map( | input | wgsl_uniform_layout(&input.ty).0).max().unwrap_or(1);
+                self.total_slots = self.total_slots.next_multiple_of(align);
+            }
+        }
+    }
+}
+
+// alignment and size in slots of a type in a WGSL uniform buffer. vec3 aligns like a vec4,
+// matrix columns are vec-aligned so mat3 columns are 4 slots apart, and array elements are
+// 4 slots apart
+fn wgsl_uniform_layout(ty: &ShaderTy) -> (usize, usize) {
+    match ty {
+        ShaderTy::Vec2 | ShaderTy::Ivec2 | ShaderTy::Bvec2 => (2, 2),
+        ShaderTy::Vec3 | ShaderTy::Ivec3 | ShaderTy::Bvec3 => (4, 3),
+        ShaderTy::Vec4 | ShaderTy::Ivec4 | ShaderTy::Bvec4 => (4, 4),
+        ShaderTy::Mat2 => (2, 4),
+        ShaderTy::Mat3 => (4, 12),
+        ShaderTy::Mat4 => (4, 16),
+        ShaderTy::Array {elem_ty, len} => {
+            let (_, elem_slots) = wgsl_uniform_layout(elem_ty);
+            (4, elem_slots.next_multiple_of(4) * len)
         }
+        ty => (1, ty.slots())
     }
 }
 
@@ -372,7 +409,6 @@ impl CxDrawShaderMapping {
         instances.finalize();
         var_instances.finalize();
         user_uniforms.finalize();
-        live_uniforms.finalize();
         draw_uniforms.finalize();
         view_uniforms.finalize();
         pass_uniforms.finalize();
@@ -384,6 +420,7 @@ impl CxDrawShaderMapping {
         for (value_node_ptr, ty) in draw_shader_def.all_live_refs.borrow().iter() {
             live_uniforms.push(LiveId(0), ty.clone(), Some(value_node_ptr.0));
         }
+        live_uniforms.finalize();
         
         CxDrawShaderMapping {
             const_table,
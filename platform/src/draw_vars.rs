@@ -20,7 +20,7 @@ use {
         texture::{Texture},
         geometry::GeometryId,
         area::Area,
-        geometry::{GeometryFields},
+        geometry::{Geometry, GeometryFields},
         live_traits::*,
         draw_shader::*
     },
@@ -139,6 +139,11 @@ impl DrawVars {
         self.texture_slots[slot] = None;
     }
 
+    // draws with another geometry than the one of the geometry fields, with the same layout
+    pub fn set_geometry(&mut self, geometry: &Geometry) {
+        self.geometry_id = Some(geometry.geometry_id());
+    }
+
     pub fn redraw(&self, cx: &mut Cx) {
         self.area.redraw(cx);
     }
@@ -0,0 +1,16 @@
+use {
+    crate::{
+        area::Area,
+        window::WindowId,
+        access::{AccessNodeId, AccessAction},
+    }
+};
+
+/// An assistive technology asked the widget that drew `area` to perform an action
+#[derive(Clone, Debug)]
+pub struct AccessActionEvent {
+    pub window_id: WindowId,
+    pub node_id: AccessNodeId,
+    pub area: Area,
+    pub action: AccessAction,
+}
@@ -15,6 +15,7 @@ use {
             xr::*,
             drag_drop::*,
             designer::*,
+            access::*,
             network::*,
             video_playback::*,
         },
@@ -180,6 +181,9 @@ pub enum Event {
     ToWasmMsg(ToWasmMsgEvent),
     
     DesignerPick(DesignerPickEvent),
+
+    /// An assistive technology requested an action on a widget, see [`crate::AccessTree`].
+    AccessAction(AccessActionEvent),
 }
 
 impl Event{
@@ -254,6 +258,7 @@ impl Event{
             51=>"ToWasmMsg",
             
             52=>"DesignerPick",            
+            53=>"AccessAction",
             _=>panic!()
         }
     }
@@ -325,6 +330,7 @@ impl Event{
             Self::ToWasmMsg(_)=>51,
             
             Self::DesignerPick(_) =>52,
+            Self::AccessAction(_) =>53,
         }
     }
 }
@@ -349,6 +355,8 @@ pub enum Hit{
     FingerUp(FingerUpEvent),
     
     DesignerPick(DesignerPickEvent),
+    
+    AccessAction(AccessActionEvent),
 
     BackPressed,
 
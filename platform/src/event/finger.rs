@@ -41,7 +41,7 @@ pub struct KeyModifiers {
 }
 
 impl KeyModifiers{
-    fn any(&self)->bool{
+    pub fn any(&self)->bool{
         self.shift || self.control || self.alt || self.logo
     }
 }
@@ -642,6 +642,17 @@ impl Event {
         if !area.is_valid(cx) {
             return Hit::Nothing
         }
+        // pointer positions are relative to their window, an area only answers to the one it is in
+        let event_window_id = match self {
+            Event::MouseDown(e) => Some(e.window_id),
+            Event::MouseMove(e) => Some(e.window_id),
+            Event::MouseUp(e) => Some(e.window_id),
+            Event::Scroll(e) => Some(e.window_id),
+            Event::TouchUpdate(e) => Some(e.window_id),
+            _ => None
+        };
+        let in_window = event_window_id.is_none_or( | window_id | area.window_id(cx).is_none_or( | area_window_id | area_window_id == window_id));
+        let hit_test = | abs: DVec2, rect: &Rect, margin: &Option<Margin> | in_window && hit_test(abs, rect, margin);
         match self {
             Event::KeyFocus(kf) => {
                 if area == kf.prev {
@@ -1080,6 +1091,11 @@ impl Event {
                 // but how will we communicate the widget?
                 return Hit::DesignerPick(e.clone())
             },
+            Event::AccessAction(e) => {
+                if e.area == area {
+                    return Hit::AccessAction(e.clone())
+                }
+            },
             _ => ()
         };
         Hit::Nothing
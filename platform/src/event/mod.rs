@@ -7,10 +7,12 @@ pub mod drag_drop;
 pub mod network;
 pub mod video_playback;
 pub mod designer;
+pub mod access;
 
 pub use event::*;
 pub use finger::*;
 pub use designer::*;
+pub use access::*;
 pub use keyboard::*;
 pub use window::*;
 pub use xr::*;
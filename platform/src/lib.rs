@@ -30,6 +30,8 @@ mod draw_vars;
 
 mod id_pool;
 pub mod event;
+pub mod access;
+mod appearance;
 mod area;
 mod window;
 mod pass;
@@ -147,6 +149,8 @@ pub use {
             RectArea,
             InstanceArea
         },
+        access::*,
+        appearance::SystemDarkModeSender,
         midi::*,
         audio::*,
         thread::*,
@@ -206,6 +210,7 @@ pub use {
             DragHitEvent,
             DropHitEvent,
             DesignerPickEvent,
+            AccessActionEvent,
             HitDesigner,
         },
         action::{
@@ -220,14 +225,15 @@ pub use {
         cursor::MouseCursor,
         macos_menu::MacosMenu,
         draw_matrix::DrawMatrix,
-        window::{WindowHandle,CxWindowPool},
+        window::{WindowHandle,WindowId,CxWindowPool},
         pass::{
             PassId,
             CxPassParent,
             CxPassRect,
             Pass,
             PassClearColor,
-            PassClearDepth
+            PassClearDepth,
+            PassMatrixMode
         },
         texture::{
             Texture,
@@ -266,6 +272,11 @@ pub use {
             shader_enum,
             DrawVars
         },
+        draw_shader::{
+            CxDrawShaderMapping,
+            DrawShaderInputs,
+            DrawShaderInputPacking,
+        },
         geometry::{
             GeometryFingerprint,
             GeometryField,
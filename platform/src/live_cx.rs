@@ -1,6 +1,7 @@
 use {
     crate::{
         makepad_micro_serde::*,
+        makepad_live_id::live_id,
         makepad_live_tokenizer::{LiveErrorOrigin, live_error_origin},
         makepad_live_compiler::{
             LiveFileChange,
@@ -262,6 +263,9 @@ impl Cx {
         while let Ok(changes) = self.live_file_change_receiver.try_recv(){
             all_changes.extend(changes);
         }
+        if self.live_registry.borrow().env.is_dirty() && all_changes.len() == 0{
+            return self.process_live_env_changes()
+        }
         if all_changes.len()>0{
             let mut live_registry = self.live_registry.borrow_mut();
             let mut errs = Vec::new();
@@ -292,6 +296,44 @@ impl Cx {
         }
     }
     
+    /// Sets the `dark_mode` variable that responsive expressions in the DSL can test,
+    /// the UI is reapplied on the next live edit poll if any of them changed
+    pub fn set_dark_mode(&mut self, dark_mode: bool) {
+        self.live_registry.borrow_mut().env.set_dark_mode(dark_mode);
+    }
+    
+    pub fn dark_mode(&self) -> bool {
+        self.live_registry.borrow().env.get(live_id!(dark_mode)) == Some(LiveValue::Bool(true))
+    }
+    
+    /// Sets the `high_contrast` variable, like `set_dark_mode`
+    pub fn set_high_contrast(&mut self, high_contrast: bool) {
+        self.live_registry.borrow_mut().env.set_high_contrast(high_contrast);
+    }
+    
+    pub fn high_contrast(&self) -> bool {
+        self.live_registry.borrow().env.get(live_id!(high_contrast)) == Some(LiveValue::Bool(true))
+    }
+    
+    /// Replaces the values of top level definitions with these, in every file that defines them.
+    /// The UI is reapplied on the next live edit poll, an empty list goes back to the values of the files
+    pub fn set_live_overrides(&mut self, overrides: impl IntoIterator<Item = (LiveId, LiveValue)>) {
+        self.live_registry.borrow_mut().env.set_overrides(overrides);
+    }
+    
+    pub (crate) fn process_live_env_changes(&mut self)->bool{
+        let mut live_registry = self.live_registry.borrow_mut();
+        let mut errs = Vec::new();
+        let changed = live_registry.process_env_changes(&mut errs);
+        for err in errs {
+            error!("process_live_env_changes: Error expanding live file {}", live_registry.live_error_to_live_file_error(err));
+        }
+        if changed{
+            self.draw_shaders.reset_for_live_reload();
+        }
+        changed
+    }
+    
     // ok so now what. now we should run the expansion
     pub fn live_expand(&mut self) {
         let mut errs = Vec::new();
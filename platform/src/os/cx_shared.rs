@@ -17,6 +17,7 @@ use {
             NextFrameEvent,
         },
         studio::{AppToStudio,EventSample},
+        window::WindowId,
     }
 };
 
@@ -186,6 +187,23 @@ impl Cx {
         self.inner_key_focus_change();
         self.handle_triggers();
         self.handle_actions();
+        // responsive DSL expressions follow the oldest window that is still open
+        let env_geom = match event {
+            Event::WindowGeomChange(e) if self.live_env_window(None) == Some(e.window_id) => Some(e.new_geom.clone()),
+            Event::WindowClosed(e) => self.live_env_window(Some(e.window_id)).map( | id | self.windows[id].window_geom.clone()),
+            _ => None
+        };
+        if let Some(geom) = env_geom {
+            let env_changed = self.live_registry.borrow_mut().env.set_window_size(geom.inner_size.x, geom.inner_size.y, geom.dpi_factor);
+            if env_changed && self.process_live_env_changes() {
+                self.call_event_handler(&Event::LiveEdit);
+                self.redraw_all();
+            }
+        }
+    }
+    
+    fn live_env_window(&self, closing: Option<WindowId>) -> Option<WindowId> {
+        self.windows.id_iter().find( | id | Some(*id) != closing && self.windows[*id].is_created)
     }
 
     // helpers
@@ -0,0 +1,813 @@
+//! Exposes the accessibility trees of the app to screen readers over AT-SPI,
+//! the D-Bus protocol assistive technologies use on the Linux desktop.
+
+use {
+    std::{
+        io,
+        sync::{Arc, Mutex, mpsc::Sender},
+        time::Duration,
+    },
+    self::super::dbus::{DBusConnection, DBusMessage, DBusMessageType, DBusValue},
+    crate::{
+        cx::Cx,
+        thread::SignalToUI,
+        makepad_math::{DVec2, Rect},
+        access::{AccessTrees, AccessTree, AccessNode, AccessRole, AccessValue, AccessAction, AccessActionKind, AccessRequest},
+    },
+};
+
+const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+const NULL_PATH: &str = "/org/a11y/atspi/null";
+const OBJECT_PREFIX: &str = "/org/a11y/atspi/accessible/";
+
+const ATSPI_ROLE_CHECK_BOX: u32 = 7;
+const ATSPI_ROLE_COMBO_BOX: u32 = 11;
+const ATSPI_ROLE_DATE_EDITOR: u32 = 12;
+const ATSPI_ROLE_FRAME: u32 = 23;
+const ATSPI_ROLE_IMAGE: u32 = 27;
+const ATSPI_ROLE_LABEL: u32 = 29;
+const ATSPI_ROLE_LIST: u32 = 31;
+const ATSPI_ROLE_LIST_ITEM: u32 = 32;
+const ATSPI_ROLE_PAGE_TAB: u32 = 37;
+const ATSPI_ROLE_PAGE_TAB_LIST: u32 = 38;
+const ATSPI_ROLE_PANEL: u32 = 39;
+const ATSPI_ROLE_PASSWORD_TEXT: u32 = 40;
+const ATSPI_ROLE_PUSH_BUTTON: u32 = 43;
+const ATSPI_ROLE_RADIO_BUTTON: u32 = 44;
+const ATSPI_ROLE_SLIDER: u32 = 51;
+const ATSPI_ROLE_SPIN_BUTTON: u32 = 52;
+const ATSPI_ROLE_APPLICATION: u32 = 75;
+const ATSPI_ROLE_ENTRY: u32 = 79;
+const ATSPI_ROLE_LINK: u32 = 88;
+
+const ATSPI_STATE_ACTIVE: u32 = 1;
+const ATSPI_STATE_CHECKED: u32 = 4;
+const ATSPI_STATE_COLLAPSED: u32 = 5;
+const ATSPI_STATE_EDITABLE: u32 = 7;
+const ATSPI_STATE_ENABLED: u32 = 8;
+const ATSPI_STATE_EXPANDABLE: u32 = 9;
+const ATSPI_STATE_EXPANDED: u32 = 10;
+const ATSPI_STATE_FOCUSABLE: u32 = 11;
+const ATSPI_STATE_FOCUSED: u32 = 12;
+const ATSPI_STATE_MULTI_LINE: u32 = 17;
+const ATSPI_STATE_RESIZABLE: u32 = 21;
+const ATSPI_STATE_SELECTED: u32 = 23;
+const ATSPI_STATE_SENSITIVE: u32 = 24;
+const ATSPI_STATE_SHOWING: u32 = 25;
+const ATSPI_STATE_SINGLE_LINE: u32 = 26;
+const ATSPI_STATE_VISIBLE: u32 = 30;
+const ATSPI_STATE_INVALID_ENTRY: u32 = 36;
+const ATSPI_STATE_CHECKABLE: u32 = 41;
+
+const ATSPI_COORD_TYPE_SCREEN: u32 = 0;
+const ATSPI_COORD_TYPE_WINDOW: u32 = 1;
+
+const ATSPI_LAYER_WIDGET: u32 = 3;
+const ATSPI_LAYER_WINDOW: u32 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    Root,
+    Window(usize),
+    Node(usize, usize),
+}
+
+pub struct AtspiBridge {
+    conn: DBusConnection,
+    shared: Arc<Mutex<AccessTrees>>,
+    requests: Sender<AccessRequest>,
+    generation: u64,
+    trees: Vec<AccessTree>,
+    app_id: i32,
+    desktop: Option<(String, String)>,
+}
+
+impl Cx {
+    // only called by the windowed event loops, and only spawns the bridge thread when an
+    // accessibility bus is running, most desktops don't start one until an AT needs it
+    pub (crate) fn start_atspi_bridge(&mut self) {
+        // the same opt-out GTK and Qt honour
+        if std::env::var("NO_AT_BRIDGE").as_deref() == Ok("1") {
+            return
+        }
+        if let Some(address) = AtspiBridge::running_bus_address() {
+            self.start_atspi_bridge_on(address);
+        }
+    }
+
+    /// Exposes the accessibility trees on the given bus, regardless of the environment
+    pub fn start_atspi_bridge_on(&mut self, address: String) {
+        let shared = self.access.trees.clone();
+        let requests = self.access.request_sender.clone();
+        std::thread::spawn(move || {
+            match DBusConnection::connect(&address) {
+                Ok(conn) => if let Err(e) = AtspiBridge::new(conn, shared, requests).run() {
+                    crate::error!("AT-SPI bridge stopped: {}", e);
+                }
+                Err(e) => {
+                    crate::error!("Could not connect to the accessibility bus: {}", e);
+                }
+            }
+        });
+    }
+}
+
+impl AtspiBridge {
+    /// The address of an accessibility bus that is already running, found without talking
+    /// to D-Bus: the launcher exports it, or leaves its socket in the runtime dir
+    pub fn running_bus_address() -> Option<String> {
+        if let Ok(address) = std::env::var("AT_SPI_BUS_ADDRESS") {
+            return Some(address)
+        }
+        let dir = std::path::Path::new(&std::env::var("XDG_RUNTIME_DIR").ok()?).join("at-spi");
+        let socket = std::fs::read_dir(dir).ok()?.filter_map( | entry | entry.ok()).find( | entry | {
+            entry.file_name().to_string_lossy().starts_with("bus")
+        })?;
+        Some(format!("unix:path={}", socket.path().display()))
+    }
+
+    pub fn new(conn: DBusConnection, shared: Arc<Mutex<AccessTrees>>, requests: Sender<AccessRequest>) -> Self {
+        Self {
+            conn,
+            shared,
+            requests,
+            generation: 0,
+            trees: Vec::new(),
+            app_id: 0,
+            desktop: None,
+        }
+    }
+
+    pub fn run(mut self) -> io::Result<()> {
+        self.embed();
+        loop {
+            self.sync_trees()?;
+            let msg = match self.conn.read_message(Some(Duration::from_millis(50))) {
+                Ok(msg) => msg,
+                // the bus went away, nobody is listening anymore
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e)
+            };
+            if let Some(msg) = msg {
+                if msg.message_type == DBusMessageType::MethodCall {
+                    self.sync_trees()?;
+                    self.handle_call(msg)?;
+                }
+            }
+        }
+    }
+
+    // registers the app with the registry daemon, which makes it show up on the desktop;
+    // without one (a private bus in tests) clients can still talk to us directly
+    fn embed(&mut self) {
+        let msg = DBusMessage::method_call(
+            "org.a11y.atspi.Registry",
+            ROOT_PATH,
+            "org.a11y.atspi.Socket",
+            "Embed",
+            vec![DBusValue::Struct(vec![DBusValue::string(self.conn.unique_name.clone()), DBusValue::object_path(ROOT_PATH)])]
+        );
+        if let Ok(reply) = self.conn.call(msg, Duration::from_secs(2)) {
+            if let Some([bus, path]) = reply.body.first().and_then( | v | v.as_slice()) {
+                if let (Some(bus), Some(path)) = (bus.as_str(), path.as_str()) {
+                    self.desktop = Some((bus.to_string(), path.to_string()));
+                }
+            }
+        }
+    }
+
+    fn sync_trees(&mut self) -> io::Result<()> {
+        let trees = {
+            let shared = self.shared.lock().unwrap();
+            if shared.generation == self.generation {
+                return Ok(())
+            }
+            self.generation = shared.generation;
+            shared.trees.clone()
+        };
+        let old = std::mem::replace(&mut self.trees, trees);
+        self.emit_changes(&old)
+    }
+
+    // paths
+
+    fn window_path(tree: &AccessTree) -> String {
+        format!("{}w{}", OBJECT_PREFIX, tree.window_id.id())
+    }
+
+    fn node_path(node: &AccessNode) -> String {
+        format!("{}n{:x}", OBJECT_PREFIX, node.id.0)
+    }
+
+    fn path(&self, target: Target) -> String {
+        match target {
+            Target::Root => ROOT_PATH.to_string(),
+            Target::Window(t) => Self::window_path(&self.trees[t]),
+            Target::Node(t, n) => Self::node_path(&self.trees[t].nodes[n]),
+        }
+    }
+
+    fn resolve(&self, path: &str) -> Option<Target> {
+        if path == ROOT_PATH {
+            return Some(Target::Root)
+        }
+        let name = path.strip_prefix(OBJECT_PREFIX)?;
+        if let Some(window) = name.strip_prefix('w') {
+            let window = window.parse::<usize>().ok()?;
+            return self.trees.iter().position( | tree | tree.window_id.id() == window).map(Target::Window)
+        }
+        let id = u64::from_str_radix(name.strip_prefix('n')?, 16).ok()?;
+        self.trees.iter().enumerate().find_map( | (t, tree) | {
+            tree.nodes.iter().position( | node | node.id.0 == id).map( | n | Target::Node(t, n))
+        })
+    }
+
+    fn reference(&self, target: Option<Target>) -> DBusValue {
+        let (bus, path) = match target {
+            Some(target) => (self.conn.unique_name.clone(), self.path(target)),
+            None => (String::new(), NULL_PATH.to_string())
+        };
+        DBusValue::Struct(vec![DBusValue::string(bus), DBusValue::object_path(path)])
+    }
+
+    // the tree
+
+    fn node(&self, target: Target) -> Option<&AccessNode> {
+        match target {
+            Target::Node(t, n) => Some(&self.trees[t].nodes[n]),
+            _ => None
+        }
+    }
+
+    fn parent(&self, target: Target) -> Option<Target> {
+        match target {
+            Target::Root => None,
+            Target::Window(_) => Some(Target::Root),
+            Target::Node(t, n) => Some(match self.trees[t].nodes[n].parent {
+                Some(parent) => Target::Node(t, parent),
+                None => Target::Window(t)
+            })
+        }
+    }
+
+    fn children(&self, target: Target) -> Vec<Target> {
+        match target {
+            Target::Root => (0..self.trees.len()).map(Target::Window).collect(),
+            Target::Window(t) => self.trees[t].roots.iter().map( | n | Target::Node(t, *n)).collect(),
+            Target::Node(t, n) => self.trees[t].nodes[n].children.iter().map( | c | Target::Node(t, *c)).collect(),
+        }
+    }
+
+    fn index_in_parent(&self, target: Target) -> i32 {
+        self.parent(target).and_then( | parent | self.children(parent).iter().position( | c | *c == target)).map_or(-1, | i | i as i32)
+    }
+
+    fn name(&self, target: Target) -> String {
+        match target {
+            Target::Root => std::env::current_exe().ok()
+                .and_then( | exe | exe.file_stem().map( | s | s.to_string_lossy().into_owned()))
+                .unwrap_or_default(),
+            Target::Window(t) => self.trees[t].title.clone(),
+            Target::Node(t, n) => self.trees[t].nodes[n].name.clone(),
+        }
+    }
+
+    fn role(&self, target: Target) -> (u32, &'static str) {
+        let node = match target {
+            Target::Root => return (ATSPI_ROLE_APPLICATION, "application"),
+            Target::Window(_) => return (ATSPI_ROLE_FRAME, "frame"),
+            Target::Node(t, n) => &self.trees[t].nodes[n]
+        };
+        match node.role {
+            AccessRole::Group => (ATSPI_ROLE_PANEL, "panel"),
+            AccessRole::Label => (ATSPI_ROLE_LABEL, "label"),
+            AccessRole::Button => (ATSPI_ROLE_PUSH_BUTTON, "push button"),
+            AccessRole::CheckBox => (ATSPI_ROLE_CHECK_BOX, "check box"),
+            AccessRole::RadioButton => (ATSPI_ROLE_RADIO_BUTTON, "radio button"),
+            AccessRole::TextInput if node.states.password => (ATSPI_ROLE_PASSWORD_TEXT, "password text"),
+            AccessRole::TextInput => (ATSPI_ROLE_ENTRY, "entry"),
+            AccessRole::Slider => (ATSPI_ROLE_SLIDER, "slider"),
+            AccessRole::SpinButton => (ATSPI_ROLE_SPIN_BUTTON, "spin button"),
+            AccessRole::DropDown => (ATSPI_ROLE_COMBO_BOX, "combo box"),
+            AccessRole::DateEditor => (ATSPI_ROLE_DATE_EDITOR, "date editor"),
+            AccessRole::List => (ATSPI_ROLE_LIST, "list"),
+            AccessRole::ListItem => (ATSPI_ROLE_LIST_ITEM, "list item"),
+            AccessRole::Image => (ATSPI_ROLE_IMAGE, "image"),
+            AccessRole::Link => (ATSPI_ROLE_LINK, "link"),
+            AccessRole::TabList => (ATSPI_ROLE_PAGE_TAB_LIST, "page tab list"),
+            AccessRole::Tab => (ATSPI_ROLE_PAGE_TAB, "page tab"),
+        }
+    }
+
+    fn states(&self, target: Target) -> Vec<u32> {
+        let mut states = Vec::new();
+        match target {
+            Target::Root => (),
+            Target::Window(_) => states.extend([
+                ATSPI_STATE_ACTIVE,
+                ATSPI_STATE_ENABLED,
+                ATSPI_STATE_SENSITIVE,
+                ATSPI_STATE_SHOWING,
+                ATSPI_STATE_VISIBLE,
+                ATSPI_STATE_RESIZABLE
+            ]),
+            Target::Node(t, n) => {
+                let node = &self.trees[t].nodes[n];
+                let s = &node.states;
+                states.push(ATSPI_STATE_VISIBLE);
+                if node.rect.size.x > 0.0 && node.rect.size.y > 0.0 {
+                    states.push(ATSPI_STATE_SHOWING);
+                }
+                if !s.disabled {
+                    states.extend([ATSPI_STATE_ENABLED, ATSPI_STATE_SENSITIVE]);
+                }
+                if s.focusable {states.push(ATSPI_STATE_FOCUSABLE)}
+                if s.focused {states.push(ATSPI_STATE_FOCUSED)}
+                if let Some(checked) = s.checked {
+                    states.push(ATSPI_STATE_CHECKABLE);
+                    if checked {states.push(ATSPI_STATE_CHECKED)}
+                }
+                if s.selected {states.push(ATSPI_STATE_SELECTED)}
+                if let Some(expanded) = s.expanded {
+                    states.push(ATSPI_STATE_EXPANDABLE);
+                    states.push(if expanded {ATSPI_STATE_EXPANDED} else {ATSPI_STATE_COLLAPSED});
+                }
+                if s.editable {states.push(ATSPI_STATE_EDITABLE)}
+                if s.invalid {states.push(ATSPI_STATE_INVALID_ENTRY)}
+                if node.role == AccessRole::TextInput {
+                    states.push(if s.multiline {ATSPI_STATE_MULTI_LINE} else {ATSPI_STATE_SINGLE_LINE});
+                }
+            }
+        }
+        let mut bits = [0u32; 2];
+        for state in states {
+            bits[(state / 32) as usize] |= 1 << (state % 32);
+        }
+        bits.to_vec()
+    }
+
+    fn text(&self, target: Target) -> Option<&str> {
+        match &self.node(target)?.value {
+            Some(AccessValue::Text(text)) => Some(text),
+            _ => None
+        }
+    }
+
+    fn number(&self, target: Target) -> Option<(f64, f64, f64, f64)> {
+        match self.node(target)?.value {
+            Some(AccessValue::Number {value, min, max, step}) => Some((value, min, max, step)),
+            _ => None
+        }
+    }
+
+    // the actions that show up in the Action interface, values are set through Value and EditableText
+    fn actions(&self, target: Target) -> Vec<(AccessActionKind, &'static str)> {
+        let Some(node) = self.node(target) else {
+            return Vec::new()
+        };
+        node.actions.iter().filter_map( | action | match action {
+            AccessActionKind::Press => Some((*action, "click")),
+            AccessActionKind::Focus => Some((*action, "focus")),
+            AccessActionKind::Increment => Some((*action, "increment")),
+            AccessActionKind::Decrement => Some((*action, "decrement")),
+            AccessActionKind::SetValue => None,
+        }).collect()
+    }
+
+    fn interfaces(&self, target: Target) -> Vec<&'static str> {
+        let mut interfaces = vec!["org.a11y.atspi.Accessible"];
+        match target {
+            Target::Root => interfaces.push("org.a11y.atspi.Application"),
+            Target::Window(_) => interfaces.push("org.a11y.atspi.Component"),
+            Target::Node(_, _) => {
+                interfaces.push("org.a11y.atspi.Component");
+                if !self.actions(target).is_empty() {
+                    interfaces.push("org.a11y.atspi.Action");
+                }
+                if self.number(target).is_some() {
+                    interfaces.push("org.a11y.atspi.Value");
+                }
+                if self.text(target).is_some() {
+                    interfaces.push("org.a11y.atspi.Text");
+                    if self.node(target).is_some_and( | node | node.states.editable && node.has_action(AccessActionKind::SetValue)) {
+                        interfaces.push("org.a11y.atspi.EditableText");
+                    }
+                }
+            }
+        }
+        interfaces
+    }
+
+    fn rect(&self, target: Target, coord_type: u32) -> Rect {
+        let (t, rect) = match target {
+            Target::Root => return Rect::default(),
+            Target::Window(t) => (t, Rect {pos: DVec2::default(), size: self.trees[t].rect.size}),
+            Target::Node(t, n) => (t, self.trees[t].nodes[n].rect)
+        };
+        match coord_type {
+            ATSPI_COORD_TYPE_SCREEN => Rect {pos: rect.pos + self.trees[t].rect.pos, size: rect.size},
+            ATSPI_COORD_TYPE_WINDOW => rect,
+            _ => {
+                let parent = self.parent(target).map_or(Rect::default(), | parent | self.rect(parent, ATSPI_COORD_TYPE_WINDOW));
+                Rect {pos: rect.pos - parent.pos, size: rect.size}
+            }
+        }
+    }
+
+    fn accessible_at_point(&self, target: Target, pos: DVec2, coord_type: u32) -> Option<Target> {
+        // the topmost child wins, children drawn later are on top
+        for child in self.children(target).into_iter().rev() {
+            if self.rect(child, coord_type).contains(pos) {
+                return Some(self.accessible_at_point(child, pos, coord_type).unwrap_or(child))
+            }
+        }
+        None
+    }
+
+    fn request(&self, target: Target, action: AccessAction) -> bool {
+        let Target::Node(t, n) = target else {
+            return false
+        };
+        let tree = &self.trees[t];
+        let node = &tree.nodes[n];
+        if !node.has_action(action.kind()) || node.states.disabled {
+            return false
+        }
+        let sent = self.requests.send(AccessRequest {
+            window_id: tree.window_id,
+            node_id: node.id,
+            action
+        }).is_ok();
+        SignalToUI::set_ui_signal();
+        sent
+    }
+
+    // properties
+
+    fn properties(&self, target: Target, interface: &str) -> Vec<(&'static str, DBusValue)> {
+        match interface {
+            "org.a11y.atspi.Accessible" => vec![
+                ("Name", DBusValue::string(self.name(target))),
+                ("Description", DBusValue::string(self.node(target).map(| node | node.description.clone()).unwrap_or_default())),
+                ("Parent", match (target, &self.desktop) {
+                    (Target::Root, Some((bus, path))) => DBusValue::Struct(vec![DBusValue::string(bus.clone()), DBusValue::object_path(path.clone())]),
+                    _ => self.reference(self.parent(target))
+                }),
+                ("ChildCount", DBusValue::Int32(self.children(target).len() as i32)),
+                ("Locale", DBusValue::string(locale())),
+                ("AccessibleId", DBusValue::string(self.node(target).map(| node | format!("{:x}", node.id.0)).unwrap_or_default())),
+                ("HelpText", DBusValue::string("")),
+            ],
+            "org.a11y.atspi.Application" if target == Target::Root => vec![
+                ("ToolkitName", DBusValue::string("Makepad")),
+                ("Version", DBusValue::string(env!("CARGO_PKG_VERSION"))),
+                ("AtspiVersion", DBusValue::string("2.1")),
+                ("Id", DBusValue::Int32(self.app_id)),
+            ],
+            "org.a11y.atspi.Action" => vec![
+                ("NActions", DBusValue::Int32(self.actions(target).len() as i32)),
+            ],
+            "org.a11y.atspi.Value" => match self.number(target) {
+                Some((value, min, max, step)) => vec![
+                    ("MinimumValue", DBusValue::Double(min)),
+                    ("MaximumValue", DBusValue::Double(max)),
+                    ("MinimumIncrement", DBusValue::Double(step)),
+                    ("CurrentValue", DBusValue::Double(value)),
+                    ("Text", DBusValue::string(format!("{}", value))),
+                ],
+                None => Vec::new()
+            },
+            "org.a11y.atspi.Text" => match self.text(target) {
+                Some(text) => {
+                    // we don't track the caret of text inputs, screen readers start reading at the end
+                    let len = text.chars().count() as i32;
+                    vec![
+                        ("CharacterCount", DBusValue::Int32(len)),
+                        ("CaretOffset", DBusValue::Int32(len)),
+                    ]
+                }
+                None => Vec::new()
+            },
+            _ => Vec::new()
+        }
+    }
+
+    fn set_property(&mut self, target: Target, interface: &str, name: &str, value: &DBusValue) -> bool {
+        match (interface, name) {
+            ("org.a11y.atspi.Application", "Id") if target == Target::Root => {
+                self.app_id = value.as_i64().unwrap_or(0) as i32;
+                true
+            }
+            ("org.a11y.atspi.Value", "CurrentValue") => match value.as_f64() {
+                Some(value) => self.request(target, AccessAction::SetNumber(value)),
+                None => false
+            },
+            _ => false
+        }
+    }
+
+    // method calls
+
+    fn handle_call(&mut self, msg: DBusMessage) -> io::Result<()> {
+        let Some(target) = msg.path.as_deref().and_then( | path | self.resolve(path)) else {
+            return self.reply(&msg, Err(("org.freedesktop.DBus.Error.UnknownObject", "No such accessible")))
+        };
+        let interface = msg.interface.clone().unwrap_or_default();
+        let member = msg.member.clone().unwrap_or_default();
+        let args = &msg.body;
+        let arg_str = | i: usize | args.get(i).and_then( | v | v.as_str()).unwrap_or_default().to_string();
+        let arg_i64 = | i: usize | args.get(i).and_then( | v | v.as_i64()).unwrap_or(0);
+
+        if !self.interfaces(target).contains(&interface.as_str()) && !interface.starts_with("org.freedesktop.DBus.") {
+            return self.reply(&msg, Err(("org.freedesktop.DBus.Error.UnknownInterface", "Interface not supported by this accessible")))
+        }
+
+        let body = match (interface.as_str(), member.as_str()) {
+            ("org.freedesktop.DBus.Properties", "Get") => {
+                let name = arg_str(1);
+                match self.properties(target, &arg_str(0)).into_iter().find( | (n, _) | *n == name) {
+                    Some((_, value)) => Some(vec![DBusValue::variant(value)]),
+                    None => return self.reply(&msg, Err(("org.freedesktop.DBus.Error.UnknownProperty", "No such property")))
+                }
+            }
+            ("org.freedesktop.DBus.Properties", "GetAll") => Some(vec![DBusValue::Array(
+                "{sv}".into(),
+                self.properties(target, &arg_str(0)).into_iter()
+                    .map( | (name, value) | DBusValue::dict_entry(DBusValue::string(name), DBusValue::variant(value)))
+                    .collect()
+            )]),
+            ("org.freedesktop.DBus.Properties", "Set") => {
+                let value = args.get(2).cloned().unwrap_or(DBusValue::Int32(0));
+                if !self.set_property(target, &arg_str(0), &arg_str(1), &value) {
+                    return self.reply(&msg, Err(("org.freedesktop.DBus.Error.PropertyReadOnly", "Property can't be set")))
+                }
+                Some(vec![])
+            }
+            ("org.freedesktop.DBus.Introspectable", "Introspect") => {
+                let mut xml = String::from("<node>");
+                for interface in self.interfaces(target) {
+                    xml.push_str(&format!("<interface name=\"{}\"/>", interface));
+                }
+                xml.push_str("</node>");
+                Some(vec![DBusValue::string(xml)])
+            }
+            ("org.freedesktop.DBus.Peer", "Ping") => Some(vec![]),
+
+            ("org.a11y.atspi.Accessible", "GetChildAtIndex") => {
+                let child = self.children(target).get(arg_i64(0) as usize).cloned();
+                Some(vec![self.reference(child)])
+            }
+            ("org.a11y.atspi.Accessible", "GetChildren") => Some(vec![DBusValue::Array(
+                "(so)".into(),
+                self.children(target).into_iter().map( | child | self.reference(Some(child))).collect()
+            )]),
+            ("org.a11y.atspi.Accessible", "GetIndexInParent") => Some(vec![DBusValue::Int32(self.index_in_parent(target))]),
+            ("org.a11y.atspi.Accessible", "GetRelationSet") => Some(vec![DBusValue::Array("(ua(so))".into(), vec![])]),
+            ("org.a11y.atspi.Accessible", "GetRole") => Some(vec![DBusValue::Uint32(self.role(target).0)]),
+            ("org.a11y.atspi.Accessible", "GetRoleName") | ("org.a11y.atspi.Accessible", "GetLocalizedRoleName") => {
+                Some(vec![DBusValue::string(self.role(target).1)])
+            }
+            ("org.a11y.atspi.Accessible", "GetState") => Some(vec![DBusValue::Array(
+                "u".into(),
+                self.states(target).into_iter().map(DBusValue::Uint32).collect()
+            )]),
+            ("org.a11y.atspi.Accessible", "GetAttributes") => Some(vec![DBusValue::Array(
+                "{ss}".into(),
+                vec![DBusValue::dict_entry(DBusValue::string("toolkit"), DBusValue::string("Makepad"))]
+            )]),
+            ("org.a11y.atspi.Accessible", "GetApplication") => Some(vec![self.reference(Some(Target::Root))]),
+            ("org.a11y.atspi.Accessible", "GetInterfaces") => Some(vec![DBusValue::Array(
+                "s".into(),
+                self.interfaces(target).into_iter().map(DBusValue::string).collect()
+            )]),
+
+            ("org.a11y.atspi.Application", "GetLocale") => Some(vec![DBusValue::string(locale())]),
+
+            ("org.a11y.atspi.Component", "GetExtents") => {
+                let rect = self.rect(target, arg_i64(0) as u32);
+                Some(vec![DBusValue::Struct(vec![
+                    DBusValue::Int32(rect.pos.x as i32),
+                    DBusValue::Int32(rect.pos.y as i32),
+                    DBusValue::Int32(rect.size.x as i32),
+                    DBusValue::Int32(rect.size.y as i32),
+                ])])
+            }
+            ("org.a11y.atspi.Component", "GetPosition") => {
+                let rect = self.rect(target, arg_i64(0) as u32);
+                Some(vec![DBusValue::Int32(rect.pos.x as i32), DBusValue::Int32(rect.pos.y as i32)])
+            }
+            ("org.a11y.atspi.Component", "GetSize") => {
+                let rect = self.rect(target, ATSPI_COORD_TYPE_WINDOW);
+                Some(vec![DBusValue::Int32(rect.size.x as i32), DBusValue::Int32(rect.size.y as i32)])
+            }
+            ("org.a11y.atspi.Component", "Contains") => {
+                let pos = DVec2 {x: arg_i64(0) as f64, y: arg_i64(1) as f64};
+                Some(vec![DBusValue::Bool(self.rect(target, arg_i64(2) as u32).contains(pos))])
+            }
+            ("org.a11y.atspi.Component", "GetAccessibleAtPoint") => {
+                let pos = DVec2 {x: arg_i64(0) as f64, y: arg_i64(1) as f64};
+                Some(vec![self.reference(self.accessible_at_point(target, pos, arg_i64(2) as u32))])
+            }
+            ("org.a11y.atspi.Component", "GetLayer") => Some(vec![DBusValue::Uint32(
+                if let Target::Window(_) = target {ATSPI_LAYER_WINDOW} else {ATSPI_LAYER_WIDGET}
+            )]),
+            ("org.a11y.atspi.Component", "GetMDIZOrder") => Some(vec![DBusValue::Int16(0)]),
+            ("org.a11y.atspi.Component", "GetAlpha") => Some(vec![DBusValue::Double(1.0)]),
+            ("org.a11y.atspi.Component", "GrabFocus") => Some(vec![DBusValue::Bool(self.request(target, AccessAction::Focus))]),
+
+            ("org.a11y.atspi.Action", "GetName") | ("org.a11y.atspi.Action", "GetLocalizedName") | ("org.a11y.atspi.Action", "GetDescription") => {
+                let name = self.actions(target).get(arg_i64(0) as usize).map_or("", | (_, name) | name);
+                Some(vec![DBusValue::string(name)])
+            }
+            ("org.a11y.atspi.Action", "GetKeyBinding") => Some(vec![DBusValue::string("")]),
+            ("org.a11y.atspi.Action", "GetActions") => Some(vec![DBusValue::Array(
+                "(sss)".into(),
+                self.actions(target).into_iter().map( | (_, name) | DBusValue::Struct(vec![
+                    DBusValue::string(name),
+                    DBusValue::string(name),
+                    DBusValue::string(""),
+                ])).collect()
+            )]),
+            ("org.a11y.atspi.Action", "DoAction") => {
+                let action = self.actions(target).get(arg_i64(0) as usize).map( | (kind, _) | match kind {
+                    AccessActionKind::Focus => AccessAction::Focus,
+                    AccessActionKind::Increment => AccessAction::Increment,
+                    AccessActionKind::Decrement => AccessAction::Decrement,
+                    _ => AccessAction::Press,
+                });
+                Some(vec![DBusValue::Bool(action.is_some_and( | action | self.request(target, action)))])
+            }
+
+            ("org.a11y.atspi.Text", "GetText") => {
+                let text = self.text(target).unwrap_or_default();
+                let (start, end) = char_range(text, arg_i64(0), arg_i64(1));
+                Some(vec![DBusValue::string(text.chars().skip(start).take(end - start).collect::<String>())])
+            }
+            ("org.a11y.atspi.Text", "GetCharacterAtOffset") => {
+                let c = self.text(target).unwrap_or_default().chars().nth(arg_i64(0).max(0) as usize);
+                Some(vec![DBusValue::Int32(c.map_or(0, | c | c as i32))])
+            }
+
+            ("org.a11y.atspi.EditableText", "SetTextContents") => {
+                Some(vec![DBusValue::Bool(self.request(target, AccessAction::SetText(arg_str(0))))])
+            }
+            ("org.a11y.atspi.EditableText", "InsertText") => {
+                let text = self.text(target).unwrap_or_default();
+                let (pos, _) = char_range(text, arg_i64(0), arg_i64(0));
+                let insert: String = arg_str(1).chars().take(arg_i64(2).max(0) as usize).collect();
+                let new_text: String = text.chars().take(pos).chain(insert.chars()).chain(text.chars().skip(pos)).collect();
+                Some(vec![DBusValue::Bool(self.request(target, AccessAction::SetText(new_text)))])
+            }
+            ("org.a11y.atspi.EditableText", "DeleteText") => {
+                let text = self.text(target).unwrap_or_default();
+                let (start, end) = char_range(text, arg_i64(0), arg_i64(1));
+                let new_text: String = text.chars().take(start).chain(text.chars().skip(end)).collect();
+                Some(vec![DBusValue::Bool(self.request(target, AccessAction::SetText(new_text)))])
+            }
+            _ => None
+        };
+        match body {
+            Some(body) => self.reply(&msg, Ok(body)),
+            None => self.reply(&msg, Err(("org.freedesktop.DBus.Error.UnknownMethod", "Unknown method")))
+        }
+    }
+
+    fn reply(&mut self, msg: &DBusMessage, result: Result<Vec<DBusValue>, (&str, &str)>) -> io::Result<()> {
+        if !msg.expects_reply() {
+            return Ok(())
+        }
+        let reply = match result {
+            Ok(body) => msg.method_return(body),
+            Err((name, text)) => msg.error(name, text)
+        };
+        self.conn.send(reply).map( | _ | ())
+    }
+
+    // events
+
+    fn emit(&mut self, path: &str, interface: &str, member: &str, kind: &str, detail1: i32, data: DBusValue) -> io::Result<()> {
+        self.conn.send(DBusMessage::signal(path, interface, member, vec![
+            DBusValue::string(kind),
+            DBusValue::Int32(detail1),
+            DBusValue::Int32(0),
+            DBusValue::variant(data),
+            DBusValue::Array("{sv}".into(), vec![]),
+        ])).map( | _ | ())
+    }
+
+    fn emit_object(&mut self, path: &str, member: &str, kind: &str, detail1: i32, data: DBusValue) -> io::Result<()> {
+        self.emit(path, "org.a11y.atspi.Event.Object", member, kind, detail1, data)
+    }
+
+    // tells listeners what changed between the last tree we exposed and the current one
+    fn emit_changes(&mut self, old_trees: &[AccessTree]) -> io::Result<()> {
+        for (i, old) in old_trees.iter().enumerate() {
+            if !self.trees.iter().any( | tree | tree.window_id == old.window_id) {
+                let child = DBusValue::Struct(vec![DBusValue::string(self.conn.unique_name.clone()), DBusValue::object_path(Self::window_path(old))]);
+                self.emit_object(ROOT_PATH, "ChildrenChanged", "remove", i as i32, child)?;
+            }
+        }
+        for t in 0..self.trees.len() {
+            let Some(old) = old_trees.iter().find( | old | old.window_id == self.trees[t].window_id) else {
+                let path = Self::window_path(&self.trees[t]);
+                self.emit_object(ROOT_PATH, "ChildrenChanged", "add", t as i32, self.reference(Some(Target::Window(t))))?;
+                self.emit(&path, "org.a11y.atspi.Event.Window", "Activate", "", 0, DBusValue::string(""))?;
+                continue
+            };
+            if old.title != self.trees[t].title {
+                let path = Self::window_path(&self.trees[t]);
+                self.emit_object(&path, "PropertyChange", "accessible-name", 0, DBusValue::string(self.trees[t].title.clone()))?;
+            }
+            // nodes that went away, reported on their parent if that is still around
+            for old_node in &old.nodes {
+                if self.trees[t].find(old_node.id).is_some() {
+                    continue
+                }
+                let old_parent = old_node.parent.map(| p | &old.nodes[p]);
+                let parent_path = match old_parent {
+                    Some(parent) if self.trees[t].find(parent.id).is_some() => Self::node_path(parent),
+                    None => Self::window_path(old),
+                    Some(_) => continue
+                };
+                let siblings = old_parent.map_or(&old.roots, | parent | &parent.children);
+                let index = siblings.iter().position( | s | old.nodes[*s].id == old_node.id).unwrap_or(0);
+                let child = DBusValue::Struct(vec![DBusValue::string(self.conn.unique_name.clone()), DBusValue::object_path(Self::node_path(old_node))]);
+                self.emit_object(&parent_path, "ChildrenChanged", "remove", index as i32, child)?;
+            }
+            for n in 0..self.trees[t].nodes.len() {
+                let target = Target::Node(t, n);
+                let node = self.trees[t].nodes[n].clone();
+                let path = Self::node_path(&node);
+                let Some(old_node) = old.find(node.id) else {
+                    // only the topmost new node is announced, its children come along with it
+                    let parent = self.parent(target).unwrap();
+                    let parent_is_new = matches!(parent, Target::Node(_, _)) && self.node(parent).is_some_and( | p | old.find(p.id).is_none());
+                    if !parent_is_new {
+                        let parent_path = self.path(parent);
+                        self.emit_object(&parent_path, "ChildrenChanged", "add", self.index_in_parent(target), self.reference(Some(target)))?;
+                    }
+                    continue
+                };
+                if old_node.name != node.name {
+                    self.emit_object(&path, "PropertyChange", "accessible-name", 0, DBusValue::string(node.name.clone()))?;
+                }
+                if old_node.description != node.description {
+                    self.emit_object(&path, "PropertyChange", "accessible-description", 0, DBusValue::string(node.description.clone()))?;
+                }
+                if old_node.value != node.value {
+                    match &node.value {
+                        Some(AccessValue::Number {value, ..}) => {
+                            self.emit_object(&path, "PropertyChange", "accessible-value", 0, DBusValue::Double(*value))?;
+                        }
+                        Some(AccessValue::Text(text)) => {
+                            let old_len = match &old_node.value {
+                                Some(AccessValue::Text(old_text)) => old_text.chars().count(),
+                                _ => 0
+                            };
+                            // we don't diff the text, it's reported as replaced wholesale
+                            if old_len > 0 {
+                                self.emit_object(&path, "TextChanged", "delete", 0, DBusValue::string(""))?;
+                            }
+                            self.emit_object(&path, "TextChanged", "insert", 0, DBusValue::string(text.clone()))?;
+                        }
+                        None => ()
+                    }
+                }
+                let (old_states, states) = (old_node.states, node.states);
+                if old_states.focused != states.focused {
+                    self.emit_object(&path, "StateChanged", "focused", states.focused as i32, DBusValue::Int32(0))?;
+                }
+                if old_states.checked != states.checked {
+                    self.emit_object(&path, "StateChanged", "checked", (states.checked == Some(true)) as i32, DBusValue::Int32(0))?;
+                }
+                if old_states.expanded != states.expanded {
+                    self.emit_object(&path, "StateChanged", "expanded", (states.expanded == Some(true)) as i32, DBusValue::Int32(0))?;
+                }
+                if old_states.selected != states.selected {
+                    self.emit_object(&path, "StateChanged", "selected", states.selected as i32, DBusValue::Int32(0))?;
+                }
+                if old_states.disabled != states.disabled {
+                    self.emit_object(&path, "StateChanged", "enabled", !states.disabled as i32, DBusValue::Int32(0))?;
+                    self.emit_object(&path, "StateChanged", "sensitive", !states.disabled as i32, DBusValue::Int32(0))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// clamps an AT-SPI character range, where an end of -1 means the end of the text
+fn char_range(text: &str, start: i64, end: i64) -> (usize, usize) {
+    let len = text.chars().count() as i64;
+    let end = if end < 0 {len} else {end.min(len)};
+    let start = start.clamp(0, end);
+    (start as usize, end as usize)
+}
+
+fn locale() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+        .filter_map( | var | std::env::var(var).ok())
+        .find( | v | !v.is_empty())
+        .map( | v | v.split('.').next().unwrap_or_default().to_string())
+        .unwrap_or_else( | | "C".to_string())
+}
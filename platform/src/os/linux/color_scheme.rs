@@ -0,0 +1,83 @@
+//! Follows the color scheme the desktop prefers through the settings interface of the
+//! XDG desktop portal, which GNOME, KDE and most other desktops implement.
+
+use {
+    std::{
+        io,
+        time::Duration,
+    },
+    self::super::dbus::{DBusConnection, DBusMessage, DBusMessageType, DBusValue},
+    crate::{
+        cx::Cx,
+        appearance::SystemDarkModeSender,
+    },
+};
+
+const PORTAL_DEST: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+
+impl Cx {
+    // only called by the windowed event loops, does nothing without a session bus
+    pub (crate) fn start_color_scheme_watcher(&mut self) {
+        let Some(address) = DBusConnection::session_bus_address() else {
+            return
+        };
+        let sender = self.system_dark_mode_sender();
+        std::thread::spawn(move || {
+            if let Err(e) = watch_color_scheme(&address, &sender) {
+                crate::log!("Not following the desktop color scheme: {}", e);
+            }
+        });
+    }
+}
+
+// 1 is prefer dark, 2 prefer light and 0 no preference, which we read as light like GTK does
+fn color_scheme_to_dark_mode(value: &DBusValue) -> Option<bool> {
+    value.as_i64().map( | scheme | scheme == 1)
+}
+
+fn watch_color_scheme(address: &str, sender: &SystemDarkModeSender) -> io::Result<()> {
+    let mut conn = DBusConnection::connect(address)?;
+    // subscribe before reading so a change in between isn't lost
+    conn.call(DBusMessage::method_call(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus",
+        "AddMatch",
+        vec![DBusValue::string(format!(
+            "type='signal',interface='{}',member='SettingChanged',arg0='{}',arg1='{}'",
+            SETTINGS_INTERFACE,
+            APPEARANCE_NAMESPACE,
+            COLOR_SCHEME_KEY
+        ))]
+    ), Duration::from_secs(5))?;
+    // Read wraps the value in a second variant, as_i64 looks through both
+    let reply = conn.call(DBusMessage::method_call(
+        PORTAL_DEST,
+        PORTAL_PATH,
+        SETTINGS_INTERFACE,
+        "Read",
+        vec![DBusValue::string(APPEARANCE_NAMESPACE), DBusValue::string(COLOR_SCHEME_KEY)]
+    ), Duration::from_secs(5))?;
+    if let Some(dark_mode) = reply.body.first().and_then(color_scheme_to_dark_mode) {
+        sender.send(dark_mode);
+    }
+    loop {
+        let Some(msg) = conn.read_message(None)? else {
+            continue
+        };
+        if msg.message_type != DBusMessageType::Signal || msg.member.as_deref() != Some("SettingChanged") {
+            continue
+        }
+        if let [namespace, key, value] = msg.body.as_slice() {
+            if namespace.as_str() == Some(APPEARANCE_NAMESPACE) && key.as_str() == Some(COLOR_SCHEME_KEY) {
+                if let Some(dark_mode) = color_scheme_to_dark_mode(value) {
+                    sender.send(dark_mode);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,690 @@
+//! A minimal D-Bus client that speaks the wire protocol directly over a unix socket,
+//! just enough for the accessibility bridge (and its tests) without depending on libdbus.
+
+use {
+    std::{
+        collections::VecDeque,
+        io::{self, Read, Write},
+        os::unix::net::{UnixStream, SocketAddr},
+        time::{Duration, Instant},
+    },
+    self::super::libc_sys,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DBusValue {
+    Byte(u8),
+    Bool(bool),
+    Int16(i16),
+    Uint16(u16),
+    Int32(i32),
+    Uint32(u32),
+    Int64(i64),
+    Uint64(u64),
+    Double(f64),
+    String(String),
+    ObjectPath(String),
+    Signature(String),
+    /// the element signature is kept so empty arrays can be marshalled
+    Array(String, Vec<DBusValue>),
+    Struct(Vec<DBusValue>),
+    DictEntry(Box<DBusValue>, Box<DBusValue>),
+    Variant(Box<DBusValue>),
+}
+
+impl DBusValue {
+    pub fn string(s: impl Into<String>) -> Self {
+        Self::String(s.into())
+    }
+
+    pub fn object_path(s: impl Into<String>) -> Self {
+        Self::ObjectPath(s.into())
+    }
+
+    pub fn variant(v: DBusValue) -> Self {
+        Self::Variant(Box::new(v))
+    }
+
+    pub fn dict_entry(k: DBusValue, v: DBusValue) -> Self {
+        Self::DictEntry(Box::new(k), Box::new(v))
+    }
+
+    pub fn signature(&self) -> String {
+        match self {
+            Self::Byte(_) => "y".into(),
+            Self::Bool(_) => "b".into(),
+            Self::Int16(_) => "n".into(),
+            Self::Uint16(_) => "q".into(),
+            Self::Int32(_) => "i".into(),
+            Self::Uint32(_) => "u".into(),
+            Self::Int64(_) => "x".into(),
+            Self::Uint64(_) => "t".into(),
+            Self::Double(_) => "d".into(),
+            Self::String(_) => "s".into(),
+            Self::ObjectPath(_) => "o".into(),
+            Self::Signature(_) => "g".into(),
+            Self::Array(sig, _) => format!("a{}", sig),
+            Self::Struct(fields) => format!("({})", fields.iter().map( | f | f.signature()).collect::<String>()),
+            Self::DictEntry(k, v) => format!("{{{}{}}}", k.signature(), v.signature()),
+            Self::Variant(_) => "v".into(),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) | Self::ObjectPath(s) | Self::Signature(s) => Some(s),
+            Self::Variant(v) => v.as_str(),
+            _ => None
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Byte(v) => Some(*v as i64),
+            Self::Int16(v) => Some(*v as i64),
+            Self::Uint16(v) => Some(*v as i64),
+            Self::Int32(v) => Some(*v as i64),
+            Self::Uint32(v) => Some(*v as i64),
+            Self::Int64(v) => Some(*v),
+            Self::Uint64(v) => Some(*v as i64),
+            Self::Variant(v) => v.as_i64(),
+            _ => None
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Double(v) => Some(*v),
+            Self::Variant(v) => v.as_f64(),
+            v => v.as_i64().map( | v | v as f64)
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(v) => Some(*v),
+            Self::Variant(v) => v.as_bool(),
+            _ => None
+        }
+    }
+
+    pub fn as_slice(&self) -> Option<&[DBusValue]> {
+        match self {
+            Self::Array(_, items) | Self::Struct(items) => Some(items),
+            Self::Variant(v) => v.as_slice(),
+            _ => None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DBusMessageType {
+    MethodCall = 1,
+    MethodReturn = 2,
+    Error = 3,
+    Signal = 4,
+}
+
+pub const DBUS_FLAG_NO_REPLY_EXPECTED: u8 = 1;
+
+#[derive(Clone, Debug)]
+pub struct DBusMessage {
+    pub message_type: DBusMessageType,
+    pub flags: u8,
+    pub serial: u32,
+    pub path: Option<String>,
+    pub interface: Option<String>,
+    pub member: Option<String>,
+    pub error_name: Option<String>,
+    pub reply_serial: Option<u32>,
+    pub destination: Option<String>,
+    pub sender: Option<String>,
+    pub body: Vec<DBusValue>,
+}
+
+impl DBusMessage {
+    fn new(message_type: DBusMessageType) -> Self {
+        Self {
+            message_type,
+            flags: 0,
+            serial: 0,
+            path: None,
+            interface: None,
+            member: None,
+            error_name: None,
+            reply_serial: None,
+            destination: None,
+            sender: None,
+            body: Vec::new(),
+        }
+    }
+
+    pub fn method_call(destination: &str, path: &str, interface: &str, member: &str, body: Vec<DBusValue>) -> Self {
+        Self {
+            destination: Some(destination.into()),
+            path: Some(path.into()),
+            interface: Some(interface.into()),
+            member: Some(member.into()),
+            body,
+            ..Self::new(DBusMessageType::MethodCall)
+        }
+    }
+
+    pub fn signal(path: &str, interface: &str, member: &str, body: Vec<DBusValue>) -> Self {
+        Self {
+            path: Some(path.into()),
+            interface: Some(interface.into()),
+            member: Some(member.into()),
+            body,
+            ..Self::new(DBusMessageType::Signal)
+        }
+    }
+
+    pub fn method_return(&self, body: Vec<DBusValue>) -> Self {
+        Self {
+            reply_serial: Some(self.serial),
+            destination: self.sender.clone(),
+            body,
+            ..Self::new(DBusMessageType::MethodReturn)
+        }
+    }
+
+    pub fn error(&self, error_name: &str, message: &str) -> Self {
+        Self {
+            reply_serial: Some(self.serial),
+            destination: self.sender.clone(),
+            error_name: Some(error_name.into()),
+            body: vec![DBusValue::string(message)],
+            ..Self::new(DBusMessageType::Error)
+        }
+    }
+
+    pub fn expects_reply(&self) -> bool {
+        self.message_type == DBusMessageType::MethodCall && self.flags & DBUS_FLAG_NO_REPLY_EXPECTED == 0
+    }
+
+    pub fn is_method_call(&self, interface: &str, member: &str) -> bool {
+        self.message_type == DBusMessageType::MethodCall
+            && self.interface.as_deref() == Some(interface)
+            && self.member.as_deref() == Some(member)
+    }
+
+    pub fn signature(&self) -> String {
+        self.body.iter().map( | v | v.signature()).collect()
+    }
+
+    fn marshal(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.buf.extend_from_slice(&[b'l', self.message_type as u8, self.flags, 1]);
+        w.buf.extend_from_slice(&[0; 4]);
+        w.buf.extend_from_slice(&self.serial.to_le_bytes());
+
+        let mut fields = Vec::new();
+        let mut field = | code: u8, value: DBusValue | {
+            fields.push(DBusValue::Struct(vec![DBusValue::Byte(code), DBusValue::variant(value)]));
+        };
+        if let Some(v) = &self.path {field(1, DBusValue::object_path(v.clone()))}
+        if let Some(v) = &self.interface {field(2, DBusValue::string(v.clone()))}
+        if let Some(v) = &self.member {field(3, DBusValue::string(v.clone()))}
+        if let Some(v) = &self.error_name {field(4, DBusValue::string(v.clone()))}
+        if let Some(v) = self.reply_serial {field(5, DBusValue::Uint32(v))}
+        if let Some(v) = &self.destination {field(6, DBusValue::string(v.clone()))}
+        if let Some(v) = &self.sender {field(7, DBusValue::string(v.clone()))}
+        if !self.body.is_empty() {field(8, DBusValue::Signature(self.signature()))}
+        w.write(&DBusValue::Array("(yv)".into(), fields));
+        w.align(8);
+
+        let body_start = w.buf.len();
+        for value in &self.body {
+            w.write(value);
+        }
+        let body_len = (w.buf.len() - body_start) as u32;
+        w.buf[4..8].copy_from_slice(&body_len.to_le_bytes());
+        w.buf
+    }
+
+    /// Returns the total length of the message at the start of `buf` once its fixed header is in
+    fn message_len(buf: &[u8]) -> io::Result<Option<usize>> {
+        if buf.len() < 16 {
+            return Ok(None)
+        }
+        let r = Reader::new(buf)?;
+        let body_len = r.u32_at(4) as usize;
+        let fields_len = r.u32_at(12) as usize;
+        let len = (16 + fields_len).div_ceil(8) * 8 + body_len;
+        if len > 1 << 27 {
+            return Err(invalid_data("message too large"))
+        }
+        Ok(Some(len))
+    }
+
+    fn unmarshal(buf: &[u8]) -> io::Result<Self> {
+        let mut r = Reader::new(buf)?;
+        let message_type = match buf[1] {
+            1 => DBusMessageType::MethodCall,
+            2 => DBusMessageType::MethodReturn,
+            3 => DBusMessageType::Error,
+            4 => DBusMessageType::Signal,
+            _ => return Err(invalid_data("unknown message type"))
+        };
+        let mut msg = Self::new(message_type);
+        msg.flags = buf[2];
+        msg.serial = r.u32_at(8);
+        r.pos = 12;
+        let fields = r.read("a(yv)")?;
+        let mut signature = String::new();
+        for field in fields.as_slice().unwrap_or(&[]) {
+            let (Some(DBusValue::Byte(code)), Some(value)) = (field.as_slice().and_then( | f | f.first()), field.as_slice().and_then( | f | f.get(1))) else {
+                continue
+            };
+            let s = value.as_str().map( | s | s.to_string());
+            match code {
+                1 => msg.path = s,
+                2 => msg.interface = s,
+                3 => msg.member = s,
+                4 => msg.error_name = s,
+                5 => msg.reply_serial = value.as_i64().map( | v | v as u32),
+                6 => msg.destination = s,
+                7 => msg.sender = s,
+                8 => signature = s.unwrap_or_default(),
+                _ => ()
+            }
+        }
+        r.align(8)?;
+        let mut sig = signature.as_str();
+        while !sig.is_empty() {
+            let (ty, rest) = split_type(sig)?;
+            msg.body.push(r.read(ty)?);
+            sig = rest;
+        }
+        Ok(msg)
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+// splits the first complete type off a signature
+fn split_type(sig: &str) -> io::Result<(&str, &str)> {
+    let bytes = sig.as_bytes();
+    let len = match bytes.first() {
+        None => return Err(invalid_data("empty signature")),
+        Some(b'a') => 1 + split_type(&sig[1..])?.0.len(),
+        Some(open @ (b'(' | b'{')) => {
+            let close = if *open == b'(' {b')'} else {b'}'};
+            let mut depth = 0;
+            let mut end = None;
+            for (i, c) in bytes.iter().enumerate() {
+                if *c == *open {
+                    depth += 1
+                }
+                else if *c == close {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(i + 1);
+                        break
+                    }
+                }
+            }
+            end.ok_or_else( | | invalid_data("unbalanced signature"))?
+        }
+        Some(_) => 1
+    };
+    Ok((&sig[..len], &sig[len..]))
+}
+
+fn alignment(sig: &str) -> usize {
+    match sig.as_bytes().first() {
+        Some(b'n' | b'q') => 2,
+        Some(b'b' | b'i' | b'u' | b's' | b'o' | b'a' | b'h') => 4,
+        Some(b'x' | b't' | b'd' | b'(' | b'{') => 8,
+        _ => 1
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn align(&mut self, n: usize) {
+        while !self.buf.len().is_multiple_of(n) {
+            self.buf.push(0);
+        }
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.align(4);
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn write(&mut self, value: &DBusValue) {
+        match value {
+            DBusValue::Byte(v) => self.buf.push(*v),
+            DBusValue::Bool(v) => self.u32(*v as u32),
+            DBusValue::Int16(v) => {self.align(2); self.buf.extend_from_slice(&v.to_le_bytes())}
+            DBusValue::Uint16(v) => {self.align(2); self.buf.extend_from_slice(&v.to_le_bytes())}
+            DBusValue::Int32(v) => self.u32(*v as u32),
+            DBusValue::Uint32(v) => self.u32(*v),
+            DBusValue::Int64(v) => {self.align(8); self.buf.extend_from_slice(&v.to_le_bytes())}
+            DBusValue::Uint64(v) => {self.align(8); self.buf.extend_from_slice(&v.to_le_bytes())}
+            DBusValue::Double(v) => {self.align(8); self.buf.extend_from_slice(&v.to_le_bytes())}
+            DBusValue::String(s) | DBusValue::ObjectPath(s) => {
+                self.u32(s.len() as u32);
+                self.buf.extend_from_slice(s.as_bytes());
+                self.buf.push(0);
+            }
+            DBusValue::Signature(s) => {
+                self.buf.push(s.len() as u8);
+                self.buf.extend_from_slice(s.as_bytes());
+                self.buf.push(0);
+            }
+            DBusValue::Array(sig, items) => {
+                self.u32(0);
+                let len_pos = self.buf.len() - 4;
+                // the padding to the first element doesn't count towards the length
+                self.align(alignment(sig));
+                let start = self.buf.len();
+                for item in items {
+                    self.write(item);
+                }
+                let len = (self.buf.len() - start) as u32;
+                self.buf[len_pos..len_pos + 4].copy_from_slice(&len.to_le_bytes());
+            }
+            DBusValue::Struct(fields) => {
+                self.align(8);
+                for field in fields {
+                    self.write(field);
+                }
+            }
+            DBusValue::DictEntry(k, v) => {
+                self.align(8);
+                self.write(k);
+                self.write(v);
+            }
+            DBusValue::Variant(v) => {
+                self.write(&DBusValue::Signature(v.signature()));
+                self.write(v);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> io::Result<Self> {
+        let big_endian = match buf.first() {
+            Some(b'l') => false,
+            Some(b'B') => true,
+            _ => return Err(invalid_data("bad endianness marker"))
+        };
+        Ok(Self {buf, pos: 0, big_endian})
+    }
+
+    fn u32_at(&self, pos: usize) -> u32 {
+        let bytes = [self.buf[pos], self.buf[pos + 1], self.buf[pos + 2], self.buf[pos + 3]];
+        if self.big_endian {u32::from_be_bytes(bytes)} else {u32::from_le_bytes(bytes)}
+    }
+
+    fn align(&mut self, n: usize) -> io::Result<()> {
+        self.pos = self.pos.div_ceil(n) * n;
+        if self.pos > self.buf.len() {
+            return Err(invalid_data("message truncated"))
+        }
+        Ok(())
+    }
+
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        self.align(N)?;
+        let bytes = self.buf.get(self.pos..self.pos + N).ok_or_else( | | invalid_data("message truncated"))?;
+        self.pos += N;
+        let mut out: [u8; N] = bytes.try_into().unwrap();
+        if self.big_endian {
+            out.reverse();
+        }
+        Ok(out)
+    }
+
+    fn string(&mut self, len: usize) -> io::Result<String> {
+        let bytes = self.buf.get(self.pos..self.pos + len).ok_or_else( | | invalid_data("message truncated"))?;
+        // skip the nul terminator
+        self.pos += len + 1;
+        String::from_utf8(bytes.to_vec()).map_err( | _ | invalid_data("invalid utf8"))
+    }
+
+    fn read(&mut self, sig: &str) -> io::Result<DBusValue> {
+        Ok(match sig.as_bytes()[0] {
+            b'y' => DBusValue::Byte(self.bytes::<1>()?[0]),
+            b'b' => DBusValue::Bool(u32::from_le_bytes(self.bytes()?) != 0),
+            b'n' => DBusValue::Int16(i16::from_le_bytes(self.bytes()?)),
+            b'q' => DBusValue::Uint16(u16::from_le_bytes(self.bytes()?)),
+            b'i' => DBusValue::Int32(i32::from_le_bytes(self.bytes()?)),
+            b'u' | b'h' => DBusValue::Uint32(u32::from_le_bytes(self.bytes()?)),
+            b'x' => DBusValue::Int64(i64::from_le_bytes(self.bytes()?)),
+            b't' => DBusValue::Uint64(u64::from_le_bytes(self.bytes()?)),
+            b'd' => DBusValue::Double(f64::from_le_bytes(self.bytes()?)),
+            b's' | b'o' => {
+                let len = u32::from_le_bytes(self.bytes()?) as usize;
+                let s = self.string(len)?;
+                if sig.as_bytes()[0] == b's' {DBusValue::String(s)} else {DBusValue::ObjectPath(s)}
+            }
+            b'g' => {
+                let len = self.bytes::<1>()?[0] as usize;
+                DBusValue::Signature(self.string(len)?)
+            }
+            b'v' => {
+                let DBusValue::Signature(inner) = self.read("g")? else {unreachable!()};
+                let (ty, _) = split_type(&inner)?;
+                DBusValue::variant(self.read(ty)?)
+            }
+            b'a' => {
+                let len = u32::from_le_bytes(self.bytes()?) as usize;
+                let elem = split_type(&sig[1..])?.0;
+                self.align(alignment(elem))?;
+                let end = self.pos + len;
+                if end > self.buf.len() {
+                    return Err(invalid_data("message truncated"))
+                }
+                let mut items = Vec::new();
+                while self.pos < end {
+                    items.push(self.read(elem)?);
+                }
+                DBusValue::Array(elem.to_string(), items)
+            }
+            b'(' | b'{' => {
+                self.align(8)?;
+                let mut inner = &sig[1..sig.len() - 1];
+                let mut fields = Vec::new();
+                while !inner.is_empty() {
+                    let (ty, rest) = split_type(inner)?;
+                    fields.push(self.read(ty)?);
+                    inner = rest;
+                }
+                if sig.as_bytes()[0] == b'{' && fields.len() == 2 {
+                    let v = fields.pop().unwrap();
+                    DBusValue::dict_entry(fields.pop().unwrap(), v)
+                }
+                else {
+                    DBusValue::Struct(fields)
+                }
+            }
+            _ => return Err(invalid_data("unsupported type in signature"))
+        })
+    }
+}
+
+pub struct DBusConnection {
+    stream: UnixStream,
+    read_buf: Vec<u8>,
+    serial: u32,
+    pub unique_name: String,
+    // messages that came in while we were waiting for a reply
+    queued: VecDeque<DBusMessage>,
+}
+
+impl DBusConnection {
+    pub fn session_bus_address() -> Option<String> {
+        if let Ok(address) = std::env::var("DBUS_SESSION_BUS_ADDRESS") {
+            return Some(address)
+        }
+        let path = std::path::Path::new(&std::env::var("XDG_RUNTIME_DIR").ok()?).join("bus");
+        path.exists().then( | | format!("unix:path={}", path.display()))
+    }
+
+    /// Connects to the first reachable server of a `;` separated address list
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no usable D-Bus address");
+        for address in address.split(';').filter( | a | !a.is_empty()) {
+            match Self::connect_one(address) {
+                Ok(conn) => return Ok(conn),
+                Err(e) => last_error = e
+            }
+        }
+        Err(last_error)
+    }
+
+    fn connect_one(address: &str) -> io::Result<Self> {
+        let Some(params) = address.strip_prefix("unix:") else {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "only unix D-Bus transports are supported"))
+        };
+        let mut stream = None;
+        for param in params.split(',') {
+            let Some((key, value)) = param.split_once('=') else {continue};
+            let value = unescape_address_value(value);
+            match key {
+                "path" => stream = Some(UnixStream::connect(value)?),
+                "abstract" => {
+                    use std::os::linux::net::SocketAddrExt;
+                    stream = Some(UnixStream::connect_addr(&SocketAddr::from_abstract_name(value.as_bytes())?)?)
+                }
+                _ => ()
+            }
+        }
+        let stream = stream.ok_or_else( | | io::Error::new(io::ErrorKind::Unsupported, "unix D-Bus address without a path"))?;
+        let mut conn = Self {
+            stream,
+            read_buf: Vec::new(),
+            serial: 0,
+            unique_name: String::new(),
+            queued: VecDeque::new(),
+        };
+        conn.authenticate()?;
+        let reply = conn.call(DBusMessage::method_call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "Hello",
+            vec![]
+        ), Duration::from_secs(5))?;
+        conn.unique_name = reply.body.first().and_then( | v | v.as_str()).unwrap_or_default().to_string();
+        Ok(conn)
+    }
+
+    fn authenticate(&mut self) -> io::Result<()> {
+        let uid = unsafe {libc_sys::getuid()}.to_string();
+        let hex_uid: String = uid.bytes().map( | b | format!("{:02x}", b)).collect();
+        self.stream.write_all(format!("\0AUTH EXTERNAL {}\r\n", hex_uid).as_bytes())?;
+        self.stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        // read byte by byte, the server sends nothing past this line before BEGIN
+        let mut line = Vec::new();
+        let mut byte = [0u8];
+        while !line.ends_with(b"\r\n") {
+            if self.stream.read(&mut byte)? == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "D-Bus server closed the connection"))
+            }
+            line.push(byte[0]);
+        }
+        if !line.starts_with(b"OK ") {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "D-Bus authentication rejected"))
+        }
+        self.stream.write_all(b"BEGIN\r\n")
+    }
+
+    /// Sends a message and returns the serial it was sent with
+    pub fn send(&mut self, mut msg: DBusMessage) -> io::Result<u32> {
+        self.serial = self.serial.wrapping_add(1).max(1);
+        msg.serial = self.serial;
+        self.stream.write_all(&msg.marshal())?;
+        Ok(msg.serial)
+    }
+
+    /// Sends a method call and waits for its reply, error replies are turned into an `Err`
+    pub fn call(&mut self, msg: DBusMessage, timeout: Duration) -> io::Result<DBusMessage> {
+        let serial = self.send(msg)?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "D-Bus call timed out"))
+            }
+            let Some(reply) = self.read_from_socket(Some(deadline - now))? else {
+                continue
+            };
+            if reply.reply_serial != Some(serial) {
+                self.queued.push_back(reply);
+                continue
+            }
+            if reply.message_type == DBusMessageType::Error {
+                let name = reply.error_name.unwrap_or_default();
+                let text = reply.body.first().and_then( | v | v.as_str()).unwrap_or_default().to_string();
+                return Err(io::Error::other(format!("{}: {}", name, text)))
+            }
+            return Ok(reply)
+        }
+    }
+
+    /// Returns the next incoming message, or `None` if none arrived within `timeout`
+    pub fn read_message(&mut self, timeout: Option<Duration>) -> io::Result<Option<DBusMessage>> {
+        if let Some(msg) = self.queued.pop_front() {
+            return Ok(Some(msg))
+        }
+        self.read_from_socket(timeout)
+    }
+
+    fn read_from_socket(&mut self, timeout: Option<Duration>) -> io::Result<Option<DBusMessage>> {
+        loop {
+            if let Some(len) = DBusMessage::message_len(&self.read_buf)? {
+                if self.read_buf.len() >= len {
+                    let msg = DBusMessage::unmarshal(&self.read_buf[..len]);
+                    self.read_buf.drain(..len);
+                    return msg.map(Some)
+                }
+            }
+            // a zero timeout would block forever
+            self.stream.set_read_timeout(timeout.map( | t | t.max(Duration::from_millis(1))))?;
+            let mut chunk = [0u8; 4096];
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "D-Bus connection closed")),
+                Ok(n) => self.read_buf.extend_from_slice(&chunk[..n]),
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => return Ok(None),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e)
+            }
+        }
+    }
+}
+
+fn unescape_address_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(b) = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then( | hex | u8::from_str_radix(hex, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
@@ -10,7 +10,8 @@ use {
     self::super::super::{
         gl_sys,
         select_timer::SelectTimers,
-        linux_media::CxLinuxMedia
+        linux_media::CxLinuxMedia,
+        headless::CxHeadless,
     },
     crate::{
         cx_api::{CxOsOp, CxOsApi, OpenUrlInPlace},
@@ -76,6 +77,10 @@ impl Cx {
         cx.os_type = OsType::LinuxDirect;
         cx.gpu_info.performance = GpuPerformance::Tier1;
         
+        if std::env::args().any(|v| v=="--headless") {
+            return cx.headless_event_loop();
+        }
+        
         cx.call_event_handler(&Event::Startup);
         cx.redraw_all();
         
@@ -338,13 +343,15 @@ impl CxOsApi for Cx {
 pub struct CxOs {
     pub (crate) media: CxLinuxMedia,
     pub (crate) start_time: Instant,
+    pub (crate) headless: CxHeadless,
 }
 
 impl Default for CxOs {
     fn default() -> Self {
         Self {
             start_time: Instant::now(),
-            media: Default::default()
+            media: Default::default(),
+            headless: Default::default(),
         }
     }
 }
\ No newline at end of file
@@ -33,6 +33,7 @@ pub const EGL_GL_TEXTURE_2D_KHR: u32 = 12465;
 
 pub const EGL_PLATFORM_X11_EXT: u32 = 12757;
 pub const EGL_PLATFORM_GBM_KHR: u32 = 12759;
+pub const EGL_PLATFORM_WAYLAND_KHR: u32 = 12760;
 
 pub const EGL_LINUX_DMA_BUF_EXT: u32 = 12912;
 pub const EGL_LINUX_DRM_FOURCC_EXT: u32 = 12913;
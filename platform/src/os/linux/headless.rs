@@ -0,0 +1,913 @@
+use {
+    std::{
+        cell::RefCell,
+        collections::{BTreeMap, HashMap},
+        io,
+        path::Path,
+        rc::Rc,
+    },
+    crate::{
+        makepad_live_id::*,
+        makepad_math::*,
+        makepad_shader_compiler::{
+            interpret::{ShaderInterpreter, InterpretTexture},
+            shader_ast::{DrawShaderPtr, Ident},
+        },
+        event::{
+            Event,
+            HttpError,
+            NetworkResponse,
+            NetworkResponseItem,
+            VideoDecodingErrorEvent,
+            KeyCode,
+            KeyEvent,
+            KeyModifiers,
+            TextClipboardEvent,
+            TextInputEvent,
+            TimerEvent,
+            WindowGeom,
+            WindowGeomChangeEvent,
+        },
+        thread::SignalToUI,
+        cursor::MouseCursor,
+        cx_api::CxOsOp,
+        cx::{Cx, OsType, LinuxWindowParams},
+        os::cx_stdin::{StdinKeyModifiers, StdinMouseDown, StdinMouseMove, StdinMouseUp, StdinScroll},
+        pass::{CxPassParent, PassClearColor, PassClearDepth, PassId},
+        draw_list::DrawListId,
+        texture::{Texture, TextureFormat},
+        window::WindowId,
+    }
+};
+
+// The headless backend runs the normal event loop without a display or a GPU. Time only moves
+// when the loop is stepped, passes are rendered by running the draw shaders through the shader
+// interpreter and every window paints into an in-memory framebuffer. Apps pick it at runtime
+// with --headless, tests drive it directly with headless_start/headless_step and the input
+// helpers below, and read back what got rendered with headless_framebuffer.
+
+// A BGRA framebuffer with the same packing as TextureFormat::VecBGRAu8_32, top row first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HeadlessImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u32>,
+}
+
+impl HeadlessImage {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width * height]
+        }
+    }
+
+    pub fn clear(&mut self, color: Vec4) {
+        let color = vec4_to_bgra(color);
+        self.pixels.iter_mut().for_each( | p | *p = color);
+    }
+
+    // the pixel as rgba in 0..1
+    pub fn pixel(&self, x: usize, y: usize) -> Vec4 {
+        bgra_to_vec4(self.pixels[y * self.width + x])
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        // every scanline gets the 'none' filter, and the image data goes into stored deflate blocks
+        let mut raw = Vec::with_capacity((self.width * 4 + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            for pixel in row {
+                raw.extend_from_slice(&[(pixel >> 16) as u8, (pixel >> 8) as u8, *pixel as u8, (pixel >> 24) as u8]);
+            }
+        }
+        let mut zlib = vec![0x78, 0x01];
+        let mut blocks = raw.chunks(0xffff).peekable();
+        if blocks.peek().is_none() {
+            zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+        }
+        while let Some(block) = blocks.next() {
+            let len = block.len() as u16;
+            zlib.push(if blocks.peek().is_none() {1} else {0});
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&(self.width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // 8 bits per channel, rgba, no interlacing
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        png_chunk(&mut png, b"IHDR", &ihdr);
+        png_chunk(&mut png, b"IDAT", &zlib);
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_png())
+    }
+
+    // render targets are sampled like the GPU paths set them up, unfiltered
+    fn sample_nearest(&self, pos: Vec2) -> Vec4 {
+        if self.width == 0 || self.height == 0 {
+            return Vec4::default()
+        }
+        let x = ((pos.x * self.width as f32).floor().max(0.0) as usize).min(self.width - 1);
+        let y = ((pos.y * self.height as f32).floor().max(0.0) as usize).min(self.height - 1);
+        self.pixel(x, y)
+    }
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {(crc >> 1) ^ 0xedb8_8320} else {crc >> 1};
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+fn vec4_to_bgra(color: Vec4) -> u32 {
+    let c = | v: f32 | (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u32;
+    (c(color.w) << 24) | (c(color.x) << 16) | (c(color.y) << 8) | c(color.z)
+}
+
+fn bgra_to_vec4(pixel: u32) -> Vec4 {
+    let c = | shift: u32 | ((pixel >> shift) & 0xff) as f32 / 255.0;
+    vec4(c(16), c(8), c(0), c(24))
+}
+
+// samples the vec textures with bilinear filtering and clamped edges, like the GL backend
+struct VecTextureSampler<'a> {
+    format: &'a TextureFormat,
+}
+
+impl<'a> VecTextureSampler<'a> {
+    fn size(&self) -> (usize, usize) {
+        match self.format {
+            TextureFormat::VecBGRAu8_32 {width, height, ..} |
+            TextureFormat::VecMipBGRAu8_32 {width, height, ..} |
+            TextureFormat::VecRGBAf32 {width, height, ..} |
+            TextureFormat::VecRu8 {width, height, ..} |
+            TextureFormat::VecRGu8 {width, height, ..} |
+            TextureFormat::VecRf32 {width, height, ..} => (*width, *height),
+            _ => (0, 0)
+        }
+    }
+
+    fn texel(&self, x: usize, y: usize) -> Vec4 {
+        let (width, _) = self.size();
+        let index = y * width + x;
+        match self.format {
+            TextureFormat::VecBGRAu8_32 {data: Some(data), ..} |
+            TextureFormat::VecMipBGRAu8_32 {data: Some(data), ..} => {
+                data.get(index).map( | p | bgra_to_vec4(*p)).unwrap_or_default()
+            }
+            TextureFormat::VecRGBAf32 {data: Some(data), ..} => {
+                data.get(index * 4..index * 4 + 4).map( | p | vec4(p[0], p[1], p[2], p[3])).unwrap_or_default()
+            }
+            TextureFormat::VecRu8 {data: Some(data), unpack_row_length, ..} => {
+                let index = y * unpack_row_length.unwrap_or(width) + x;
+                vec4(data.get(index).cloned().unwrap_or(0) as f32 / 255.0, 0.0, 0.0, 1.0)
+            }
+            TextureFormat::VecRGu8 {data: Some(data), unpack_row_length, ..} => {
+                let index = (y * unpack_row_length.unwrap_or(width) + x) * 2;
+                let c = | i: usize | data.get(i).cloned().unwrap_or(0) as f32 / 255.0;
+                vec4(c(index), c(index + 1), 0.0, 1.0)
+            }
+            TextureFormat::VecRf32 {data: Some(data), ..} => {
+                vec4(data.get(index).cloned().unwrap_or(0.0), 0.0, 0.0, 1.0)
+            }
+            _ => Vec4::default()
+        }
+    }
+}
+
+impl<'a> InterpretTexture for VecTextureSampler<'a> {
+    fn sample(&self, pos: Vec2) -> Vec4 {
+        let (width, height) = self.size();
+        if width == 0 || height == 0 {
+            return Vec4::default()
+        }
+        let x = pos.x * width as f32 - 0.5;
+        let y = pos.y * height as f32 - 0.5;
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+        let clamp_x = | x: f32 | (x.max(0.0) as usize).min(width - 1);
+        let clamp_y = | y: f32 | (y.max(0.0) as usize).min(height - 1);
+        let (x0, x1) = (clamp_x(x.floor()), clamp_x(x.floor() + 1.0));
+        let (y0, y1) = (clamp_y(y.floor()), clamp_y(y.floor() + 1.0));
+        let top = self.texel(x0, y0) * (1.0 - fx) + self.texel(x1, y0) * fx;
+        let bottom = self.texel(x0, y1) * (1.0 - fx) + self.texel(x1, y1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+}
+
+impl InterpretTexture for HeadlessImage {
+    fn sample(&self, pos: Vec2) -> Vec4 {
+        self.sample_nearest(pos)
+    }
+}
+
+enum HeadlessSampler<'a> {
+    Vec(VecTextureSampler<'a>),
+    Image(&'a HeadlessImage),
+}
+
+impl<'a> HeadlessSampler<'a> {
+    fn as_texture(&self) -> &dyn InterpretTexture {
+        match self {
+            HeadlessSampler::Vec(sampler) => sampler,
+            HeadlessSampler::Image(image) => *image,
+        }
+    }
+}
+
+struct HeadlessTimer {
+    interval: f64,
+    next_due: f64,
+    repeats: bool,
+}
+
+struct HeadlessWindow {
+    window_id: WindowId,
+    framebuffer: HeadlessImage,
+    depth: Vec<f32>,
+}
+
+// the color and depth buffers a pass renders into
+struct HeadlessTarget<'a> {
+    color: &'a mut HeadlessImage,
+    depth: Option<&'a mut Vec<f32>>,
+}
+
+#[derive(Default)]
+pub struct CxHeadless {
+    time: f64,
+    quit: bool,
+    timers: BTreeMap<u64, HeadlessTimer>,
+    shader_defs: HashMap<usize, DrawShaderPtr>,
+    windows: Vec<HeadlessWindow>,
+    render_targets: HashMap<usize, HeadlessImage>,
+    depth_targets: HashMap<usize, Vec<f32>>,
+    clipboard: String,
+    cursor: MouseCursor,
+    text_ime: Option<DVec2>,
+    skip_painting: bool,
+}
+
+impl Cx {
+
+    pub fn headless_event_loop(&mut self) {
+        let frames = std::env::var("MAKEPAD_HEADLESS_FRAMES").ok().and_then( | v | v.parse().ok()).unwrap_or(60);
+        self.headless_start();
+        for _ in 0..frames {
+            if self.os.headless.quit {
+                break
+            }
+            self.headless_step(1.0 / 60.0);
+        }
+        if let Ok(dir) = std::env::var("MAKEPAD_HEADLESS_OUT") {
+            for (index, window) in self.os.headless.windows.iter().enumerate() {
+                let path = Path::new(&dir).join(format!("window_{}.png", index));
+                if let Err(err) = window.framebuffer.save_png(&path) {
+                    crate::error!("headless: cannot write {:?}: {}", path, err);
+                }
+            }
+        }
+        self.call_event_handler(&Event::Shutdown);
+    }
+
+    // sends Startup and creates the windows, the first frame is drawn by the first step
+    pub fn headless_start(&mut self) {
+        self.os_type = OsType::LinuxWindow(LinuxWindowParams {
+            custom_window_chrome: false
+        });
+        self.os.headless = CxHeadless::default();
+        self.call_event_handler(&Event::Startup);
+        self.redraw_all();
+        self.headless_handle_platform_ops();
+    }
+
+    // advances the synthetic clock by dt seconds and runs one turn of the event loop
+    pub fn headless_step(&mut self, dt: f64) {
+        self.os.headless.time += dt;
+        let time = self.os.headless.time;
+
+        if SignalToUI::check_and_clear_ui_signal() {
+            self.handle_media_signals();
+            self.call_event_handler(&Event::Signal);
+        }
+        self.handle_action_receiver();
+
+        let mut due = Vec::new();
+        self.os.headless.timers.retain( | timer_id, timer | {
+            if timer.next_due > time {
+                return true
+            }
+            due.push(*timer_id);
+            timer.next_due += timer.interval.max(dt);
+            timer.repeats
+        });
+        for timer_id in due {
+            self.call_event_handler(&Event::Timer(TimerEvent {timer_id, time: Some(time)}));
+        }
+        if self.handle_live_edit() {
+            self.call_event_handler(&Event::LiveEdit);
+            self.redraw_all();
+        }
+        self.headless_handle_platform_ops();
+
+        if !self.new_next_frames.is_empty() {
+            self.call_next_frame_event(time);
+        }
+        if self.need_redrawing() {
+            self.call_draw_event();
+            self.headless_compile_shaders();
+        }
+        self.headless_handle_platform_ops();
+        self.headless_handle_repaint();
+    }
+
+    // runs steps until nothing is animating or waiting to be drawn, or max_steps ran out
+    pub fn headless_settle(&mut self, dt: f64, max_steps: usize) {
+        for _ in 0..max_steps {
+            self.headless_step(dt);
+            if self.new_next_frames.is_empty() && !self.need_redrawing() && !self.any_passes_dirty() {
+                break
+            }
+        }
+    }
+
+    // passes are laid out but not drawn into the framebuffers and textures, for tests that don't
+    // look at pixels, which saves running every pixel shader on the cpu
+    pub fn headless_set_painting(&mut self, painting: bool) {
+        self.os.headless.skip_painting = !painting;
+    }
+
+    pub fn headless_time(&self) -> f64 {
+        self.os.headless.time
+    }
+
+    pub fn headless_framebuffer(&self, window_id: WindowId) -> Option<&HeadlessImage> {
+        self.os.headless.windows.iter().find( | w | w.window_id == window_id).map( | w | &w.framebuffer)
+    }
+
+    // the framebuffers of all open windows, in creation order
+    pub fn headless_framebuffers(&self) -> Vec<(WindowId, &HeadlessImage)> {
+        self.os.headless.windows.iter().map( | w | (w.window_id, &w.framebuffer)).collect()
+    }
+
+    pub fn headless_clipboard(&self) -> &str {
+        &self.os.headless.clipboard
+    }
+
+    pub fn headless_cursor(&self) -> MouseCursor {
+        self.os.headless.cursor
+    }
+
+    // where the text IME would be shown, None while it is hidden
+    pub fn headless_text_ime(&self) -> Option<DVec2> {
+        self.os.headless.text_ime
+    }
+
+    pub fn headless_resize_window(&mut self, window_id: WindowId, inner_size: DVec2) {
+        let old_geom = self.windows[window_id].window_geom.clone();
+        let new_geom = WindowGeom {
+            inner_size,
+            outer_size: inner_size,
+            ..old_geom.clone()
+        };
+        self.windows[window_id].window_geom = new_geom.clone();
+        if let Some(main_pass_id) = self.windows[window_id].main_pass_id {
+            self.redraw_pass_and_child_passes(main_pass_id);
+        }
+        self.call_event_handler(&Event::WindowGeomChange(WindowGeomChangeEvent {window_id, old_geom, new_geom}));
+    }
+
+    // mouse positions are in screen coordinates, a window sits where it was created
+    pub fn headless_mouse_down(&mut self, pos: DVec2, button: usize, modifiers: KeyModifiers) {
+        let e = StdinMouseDown {
+            button,
+            x: pos.x,
+            y: pos.y,
+            time: self.os.headless.time,
+            modifiers: StdinKeyModifiers::from_key_modifiers(&modifiers),
+        };
+        self.fingers.process_tap_count(pos, e.time);
+        let (window_id, pos) = self.windows.window_id_contains(pos);
+        self.fingers.mouse_down(button, window_id);
+        self.call_event_handler(&Event::MouseDown(e.into_event(window_id, pos)));
+    }
+
+    pub fn headless_mouse_move(&mut self, pos: DVec2, modifiers: KeyModifiers) {
+        let e = StdinMouseMove {
+            x: pos.x,
+            y: pos.y,
+            time: self.os.headless.time,
+            modifiers: StdinKeyModifiers::from_key_modifiers(&modifiers),
+        };
+        let (window_id, pos) = self.headless_mouse_window(pos);
+        self.call_event_handler(&Event::MouseMove(e.into_event(window_id, pos)));
+        self.fingers.cycle_hover_area(live_id!(mouse).into());
+        self.fingers.switch_captures();
+    }
+
+    pub fn headless_mouse_up(&mut self, pos: DVec2, button: usize, modifiers: KeyModifiers) {
+        let e = StdinMouseUp {
+            button,
+            x: pos.x,
+            y: pos.y,
+            time: self.os.headless.time,
+            modifiers: StdinKeyModifiers::from_key_modifiers(&modifiers),
+        };
+        let (window_id, pos) = self.headless_mouse_window(pos);
+        self.call_event_handler(&Event::MouseUp(e.into_event(window_id, pos)));
+        self.fingers.mouse_up(button);
+        self.fingers.cycle_hover_area(live_id!(mouse).into());
+    }
+
+    // a left button press and release at the same spot
+    pub fn headless_click(&mut self, pos: DVec2) {
+        self.headless_mouse_move(pos, KeyModifiers::default());
+        self.headless_mouse_down(pos, 0, KeyModifiers::default());
+        self.headless_mouse_up(pos, 0, KeyModifiers::default());
+    }
+
+    pub fn headless_scroll(&mut self, pos: DVec2, scroll: DVec2, is_mouse: bool) {
+        let e = StdinScroll {
+            sx: scroll.x,
+            sy: scroll.y,
+            x: pos.x,
+            y: pos.y,
+            is_mouse,
+            time: self.os.headless.time,
+            modifiers: StdinKeyModifiers::default(),
+        };
+        let (window_id, pos) = self.windows.window_id_contains(pos);
+        self.call_event_handler(&Event::Scroll(e.into_event(window_id, pos)));
+    }
+
+    pub fn headless_key_down(&mut self, key_code: KeyCode, modifiers: KeyModifiers) {
+        let e = KeyEvent {
+            key_code,
+            is_repeat: false,
+            modifiers,
+            time: self.os.headless.time
+        };
+        self.keyboard.process_key_down(e);
+        self.call_event_handler(&Event::KeyDown(e));
+    }
+
+    pub fn headless_key_up(&mut self, key_code: KeyCode, modifiers: KeyModifiers) {
+        let e = KeyEvent {
+            key_code,
+            is_repeat: false,
+            modifiers,
+            time: self.os.headless.time
+        };
+        self.keyboard.process_key_up(e);
+        self.call_event_handler(&Event::KeyUp(e));
+    }
+
+    pub fn headless_text_input(&mut self, input: &str) {
+        self.call_event_handler(&Event::TextInput(TextInputEvent {
+            input: input.to_string(),
+            replace_last: false,
+            was_paste: false
+        }));
+    }
+
+    // text from an input method that replaces what it sent last, the way a composition updates
+    pub fn headless_ime_input(&mut self, input: &str) {
+        self.call_event_handler(&Event::TextInput(TextInputEvent {
+            input: input.to_string(),
+            replace_last: true,
+            was_paste: false
+        }));
+    }
+
+    // what ctrl+v does with the clipboard
+    pub fn headless_paste(&mut self) {
+        let input = self.os.headless.clipboard.clone();
+        self.call_event_handler(&Event::TextInput(TextInputEvent {
+            input,
+            replace_last: false,
+            was_paste: true
+        }));
+    }
+
+    pub fn headless_set_clipboard(&mut self, text: &str) {
+        self.os.headless.clipboard = text.to_string();
+    }
+
+    // what ctrl+c does: the widget with the key focus answers, and the answer lands on the clipboard
+    // as if the desktop switched its color scheme, picked up by the next step
+    pub fn headless_set_system_dark_mode(&mut self, dark_mode: bool) {
+        self.system_dark_mode_sender().send(dark_mode);
+    }
+
+    pub fn headless_copy(&mut self) {
+        let response = Rc::new(RefCell::new(None));
+        self.call_event_handler(&Event::TextCopy(TextClipboardEvent {
+            response: response.clone()
+        }));
+        let response = response.borrow_mut().take();
+        if let Some(response) = response {
+            self.os.headless.clipboard = response;
+        }
+    }
+
+    fn headless_mouse_window(&self, pos: DVec2) -> (WindowId, DVec2) {
+        if let Some((_, window_id)) = self.fingers.first_mouse_button {
+            (window_id, self.windows[window_id].window_geom.position)
+        }
+        else {
+            self.windows.window_id_contains(pos)
+        }
+    }
+
+    // the interpreter runs the analysed shader defs directly, so compiling only remembers which
+    // def each shader id came from. shaders that share a fingerprint have no def of their own
+    fn headless_compile_shaders(&mut self) {
+        for draw_shader_ptr in &self.draw_shaders.compile_set {
+            if let Some(item) = self.draw_shaders.ptr_to_item.get(draw_shader_ptr) {
+                if self.shader_registry.draw_shader_defs.contains_key(draw_shader_ptr) {
+                    self.os.headless.shader_defs.entry(item.draw_shader_id).or_insert(*draw_shader_ptr);
+                }
+            }
+        }
+        self.draw_shaders.compile_set.clear();
+    }
+
+    fn headless_handle_platform_ops(&mut self) {
+        let mut network_errors = Vec::new();
+        let mut video_errors = Vec::new();
+        while let Some(op) = self.platform_ops.pop() {
+            match op {
+                CxOsOp::CreateWindow(window_id) => {
+                    let window = &mut self.windows[window_id];
+                    let inner_size = window.create_inner_size.unwrap_or(dvec2(800., 600.));
+                    window.window_geom = WindowGeom {
+                        dpi_factor: window.dpi_override.unwrap_or(1.0),
+                        position: window.create_position.unwrap_or_default(),
+                        inner_size,
+                        outer_size: inner_size,
+                        ..Default::default()
+                    };
+                    window.is_created = true;
+                    self.os.headless.windows.push(HeadlessWindow {
+                        window_id,
+                        framebuffer: HeadlessImage::default(),
+                        depth: Vec::new(),
+                    });
+                }
+                CxOsOp::CloseWindow(window_id) => {
+                    self.windows[window_id].is_created = false;
+                    self.os.headless.windows.retain( | w | w.window_id != window_id);
+                    if self.os.headless.windows.is_empty() {
+                        self.os.headless.quit = true;
+                    }
+                }
+                CxOsOp::Quit => {
+                    self.os.headless.quit = true;
+                }
+                CxOsOp::CopyToClipboard(content) => {
+                    self.os.headless.clipboard = content;
+                }
+                CxOsOp::SetCursor(cursor) => {
+                    self.os.headless.cursor = cursor;
+                }
+                CxOsOp::StartTimer {timer_id, interval, repeats} => {
+                    self.os.headless.timers.insert(timer_id, HeadlessTimer {
+                        interval,
+                        next_due: self.os.headless.time + interval,
+                        repeats
+                    });
+                }
+                CxOsOp::StopTimer(timer_id) => {
+                    self.os.headless.timers.remove(&timer_id);
+                }
+                CxOsOp::ShowTextIME(area, pos) => {
+                    self.os.headless.text_ime = Some(area.rect(self).pos + pos);
+                }
+                CxOsOp::HideTextIME => {
+                    self.os.headless.text_ime = None;
+                }
+                // there is no window manager, windows keep their geometry and stacking
+                CxOsOp::MinimizeWindow(_) |
+                CxOsOp::MaximizeWindow(_) |
+                CxOsOp::FullscreenWindow(_) |
+                CxOsOp::NormalizeWindow(_) |
+                CxOsOp::RestoreWindow(_) |
+                CxOsOp::SetTopmost(_, _) => (),
+                // macos and mobile only
+                CxOsOp::UpdateMacosMenu(_) |
+                CxOsOp::ShowClipboardActions(_) => (),
+                CxOsOp::HttpRequest {request_id, request} => {
+                    network_errors.push(NetworkResponseItem {
+                        request_id,
+                        response: NetworkResponse::HttpRequestError(HttpError {
+                            message: "the headless backend has no network access".to_string(),
+                            metadata_id: request.metadata_id
+                        })
+                    });
+                }
+                // requests fail right away, so there is never one to cancel
+                CxOsOp::CancelHttpRequest {..} => (),
+                CxOsOp::PrepareVideoPlayback(video_id, ..) => {
+                    video_errors.push(VideoDecodingErrorEvent {
+                        video_id,
+                        error: "the headless backend cannot play video".to_string()
+                    });
+                }
+                // playback never got prepared
+                CxOsOp::BeginVideoPlayback(_) |
+                CxOsOp::PauseVideoPlayback(_) |
+                CxOsOp::ResumeVideoPlayback(_) |
+                CxOsOp::MuteVideoPlayback(_) |
+                CxOsOp::UnmuteVideoPlayback(_) |
+                CxOsOp::CleanupVideoPlaybackResources(_) |
+                CxOsOp::UpdateVideoSurfaceTexture(_) => (),
+                CxOsOp::XrStartPresenting |
+                CxOsOp::XrStopPresenting => {
+                    crate::error!("headless: XR is not available");
+                }
+                CxOsOp::StartDragging(_) => {
+                    crate::error!("headless: drag and drop is not available");
+                }
+                CxOsOp::SaveFileDialog(_) |
+                CxOsOp::SelectFileDialog(_) |
+                CxOsOp::SaveFolderDialog(_) |
+                CxOsOp::SelectFolderDialog(_) => {
+                    crate::error!("headless: file dialogs are not available");
+                }
+            }
+        }
+        if !network_errors.is_empty() {
+            self.call_event_handler(&Event::NetworkResponses(network_errors));
+        }
+        for e in video_errors {
+            self.call_event_handler(&Event::VideoDecodingError(e));
+        }
+    }
+
+    fn headless_handle_repaint(&mut self) {
+        let mut passes_todo = Vec::new();
+        self.compute_pass_repaint_order(&mut passes_todo);
+        self.repaint_id += 1;
+        for pass_id in passes_todo {
+            if self.os.headless.skip_painting {
+                self.setup_render_pass(pass_id);
+                continue
+            }
+            self.passes[pass_id].set_time(self.os.headless.time as f32);
+            match self.passes[pass_id].parent.clone() {
+                CxPassParent::Window(window_id) => {
+                    if let Some(index) = self.os.headless.windows.iter().position( | w | w.window_id == window_id) {
+                        self.headless_draw_pass_to_window(pass_id, index);
+                    }
+                }
+                CxPassParent::Pass(_) | CxPassParent::None => {
+                    self.headless_draw_pass_to_texture(pass_id);
+                }
+            }
+        }
+    }
+
+    fn headless_draw_pass_to_window(&mut self, pass_id: PassId, index: usize) {
+        let window_id = self.os.headless.windows[index].window_id;
+        if self.setup_render_pass(pass_id).is_none() {
+            return
+        }
+        let geom = &self.windows[window_id].window_geom;
+        let width = (geom.inner_size.x * geom.dpi_factor).floor() as usize;
+        let height = (geom.inner_size.y * geom.dpi_factor).floor() as usize;
+
+        let mut window = std::mem::replace(&mut self.os.headless.windows[index], HeadlessWindow {
+            window_id,
+            framebuffer: HeadlessImage::default(),
+            depth: Vec::new()
+        });
+        if window.framebuffer.width != width || window.framebuffer.height != height {
+            window.framebuffer = HeadlessImage::new(width, height);
+            window.depth = vec![1.0; width * height];
+        }
+        let pass = &self.passes[pass_id];
+        if !pass.dont_clear {
+            let clear_color = match pass.color_textures.first().map( | c | &c.clear_color) {
+                None => pass.clear_color,
+                Some(PassClearColor::InitWith(color)) | Some(PassClearColor::ClearWith(color)) => *color
+            };
+            let clear_depth = match pass.clear_depth {
+                PassClearDepth::InitWith(depth) | PassClearDepth::ClearWith(depth) => depth
+            };
+            window.framebuffer.clear(clear_color);
+            window.depth.iter_mut().for_each( | d | *d = clear_depth);
+        }
+
+        let draw_list_id = self.passes[pass_id].main_draw_list_id.unwrap();
+        let mut zbias = 0.0;
+        let zbias_step = self.passes[pass_id].zbias_step;
+        let mut target = HeadlessTarget {
+            color: &mut window.framebuffer,
+            depth: Some(&mut window.depth)
+        };
+        self.headless_render_view(pass_id, draw_list_id, &mut zbias, zbias_step, &mut target);
+        self.os.headless.windows[index] = window;
+    }
+
+    fn headless_draw_pass_to_texture(&mut self, pass_id: PassId) {
+        let pass_size = if let Some(pass_size) = self.setup_render_pass(pass_id) {
+            pass_size
+        }
+        else {
+            return
+        };
+        let dpi_factor = self.passes[pass_id].dpi_factor.unwrap();
+        let width = (pass_size.x * dpi_factor) as usize;
+        let height = (pass_size.y * dpi_factor) as usize;
+
+        // like the GL backend only the first color texture gets drawn into
+        let color_texture = match self.passes[pass_id].color_textures.first() {
+            Some(color_texture) => color_texture.clone(),
+            None => return
+        };
+        let texture_index = color_texture.texture.texture_id().0;
+        let mut color = self.os.headless.render_targets.remove(&texture_index).unwrap_or_default();
+        if color.width != width || color.height != height {
+            color = HeadlessImage::new(width, height);
+        }
+        let cxtexture = &mut self.textures[color_texture.texture.texture_id()];
+        match color_texture.clear_color {
+            PassClearColor::InitWith(clear_color) => if cxtexture.take_initial() {
+                color.clear(clear_color);
+            }
+            PassClearColor::ClearWith(clear_color) => color.clear(clear_color)
+        }
+
+        let depth_texture: Option<Texture> = self.passes[pass_id].depth_texture.clone();
+        let mut depth = None;
+        if let Some(depth_texture) = &depth_texture {
+            let depth_index = depth_texture.texture_id().0;
+            let mut buffer = self.os.headless.depth_targets.remove(&depth_index).unwrap_or_default();
+            if buffer.len() != width * height {
+                buffer = vec![1.0; width * height];
+            }
+            let cxtexture = &mut self.textures[depth_texture.texture_id()];
+            match self.passes[pass_id].clear_depth {
+                PassClearDepth::InitWith(clear_depth) => if cxtexture.take_initial() {
+                    buffer.iter_mut().for_each( | d | *d = clear_depth);
+                }
+                PassClearDepth::ClearWith(clear_depth) => buffer.iter_mut().for_each( | d | *d = clear_depth)
+            }
+            depth = Some((depth_index, buffer));
+        }
+
+        let draw_list_id = self.passes[pass_id].main_draw_list_id.unwrap();
+        let mut zbias = 0.0;
+        let zbias_step = self.passes[pass_id].zbias_step;
+        let mut target = HeadlessTarget {
+            color: &mut color,
+            depth: depth.as_mut().map( | (_, buffer) | buffer)
+        };
+        self.headless_render_view(pass_id, draw_list_id, &mut zbias, zbias_step, &mut target);
+
+        self.os.headless.render_targets.insert(texture_index, color);
+        if let Some((depth_index, buffer)) = depth {
+            self.os.headless.depth_targets.insert(depth_index, buffer);
+        }
+    }
+
+    fn headless_render_view(
+        &mut self,
+        pass_id: PassId,
+        draw_list_id: DrawListId,
+        zbias: &mut f32,
+        zbias_step: f32,
+        target: &mut HeadlessTarget,
+    ) {
+        let draw_items_len = self.draw_lists[draw_list_id].draw_items.len();
+        self.draw_lists[draw_list_id].uniform_view_transform(&Mat4::identity());
+
+        for draw_item_id in 0..draw_items_len {
+            if let Some(sub_list_id) = self.draw_lists[draw_list_id].draw_items[draw_item_id].kind.sub_list() {
+                self.headless_render_view(pass_id, sub_list_id, zbias, zbias_step, target);
+                continue;
+            }
+            if let Some(draw_call) = self.draw_lists[draw_list_id].draw_items[draw_item_id].kind.draw_call_mut() {
+                draw_call.instance_dirty = false;
+                draw_call.uniforms_dirty = false;
+                draw_call.draw_uniforms.set_zbias(*zbias);
+                *zbias += zbias_step;
+            }
+            else {
+                continue;
+            }
+            if let Err(err) = self.headless_draw_item(pass_id, draw_list_id, draw_item_id, target) {
+                crate::error!("headless: {}", err);
+            }
+        }
+    }
+
+    fn headless_draw_item(
+        &self,
+        pass_id: PassId,
+        draw_list_id: DrawListId,
+        draw_item_id: usize,
+        target: &mut HeadlessTarget,
+    ) -> Result<(), String> {
+        let draw_list = &self.draw_lists[draw_list_id];
+        let draw_item = &draw_list.draw_items[draw_item_id];
+        let draw_call = draw_item.kind.draw_call().unwrap();
+        let sh = &self.draw_shaders.shaders[draw_call.draw_shader.draw_shader_id];
+        let draw_shader_def = match self.os.headless.shader_defs.get(&draw_call.draw_shader.draw_shader_id)
+            .and_then( | ptr | self.shader_registry.draw_shader_defs.get(ptr)) {
+            Some(draw_shader_def) => draw_shader_def,
+            None => return Ok(())
+        };
+        let instances = match &draw_item.instances {
+            Some(instances) if sh.mapping.instances.total_slots > 0 => instances,
+            _ => return Ok(())
+        };
+        let geometry = match draw_call.geometry_id {
+            Some(geometry_id) => &self.geometries[geometry_id],
+            None => return Ok(())
+        };
+
+        let interp = ShaderInterpreter::new(draw_shader_def, &sh.mapping.const_table, &self.shader_registry);
+        let mut inputs = interp.new_inputs();
+        for (block, values) in [
+            (live_id!(pass), &self.passes[pass_id].pass_uniforms.as_slice()[..]),
+            (live_id!(view), &draw_list.draw_list_uniforms.as_slice()[..]),
+            (live_id!(draw), &draw_call.draw_uniforms.as_slice()[..]),
+            (live_id!(user), &draw_call.user_uniforms[..]),
+        ] {
+            if let Some(block) = inputs.uniform_blocks.get_mut(&Ident(block)) {
+                let len = block.len().min(values.len());
+                block[..len].copy_from_slice(&values[..len]);
+            }
+        }
+        let len = inputs.live_uniforms.len().min(sh.mapping.live_uniforms_buf.len());
+        inputs.live_uniforms[..len].copy_from_slice(&sh.mapping.live_uniforms_buf[..len]);
+
+        let samplers: Vec<Option<HeadlessSampler>> = (0..sh.mapping.textures.len()).map( | i | {
+            let texture = draw_call.texture_slots.get(i)?.as_ref()?;
+            let texture_id = texture.texture_id();
+            if let Some(image) = self.os.headless.render_targets.get(&texture_id.0) {
+                return Some(HeadlessSampler::Image(image))
+            }
+            Some(HeadlessSampler::Vec(VecTextureSampler {format: &self.textures[texture_id].format}))
+        }).collect();
+        for (slot, sampler) in inputs.textures.iter_mut().zip(samplers.iter()) {
+            *slot = sampler.as_ref().map( | s | s.as_texture());
+        }
+
+        let (width, height) = (target.color.width, target.color.height);
+        for instance in instances.chunks_exact(sh.mapping.instances.total_slots) {
+            interp.draw_triangles(&inputs, &geometry.vertices, &geometry.indices, instance, width, height, &mut | x, y, z, color | {
+                let index = y * width + x;
+                // depth test LEQUAL with depth writes, then premultiplied alpha blending
+                if let Some(depth) = &mut target.depth {
+                    let z = z * 0.5 + 0.5;
+                    if z > depth[index] {
+                        return
+                    }
+                    depth[index] = z;
+                }
+                let dst = bgra_to_vec4(target.color.pixels[index]);
+                target.color.pixels[index] = vec4_to_bgra(color + dst * (1.0 - color.w));
+            }).map_err( | err | err.message) ?;
+        }
+        Ok(())
+    }
+}
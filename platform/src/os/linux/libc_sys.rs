@@ -21,21 +21,33 @@ pub type time_t = c_ulong;
 pub type suseconds_t = c_ulong;
 
 type c_int =  std::os::raw::c_int;
-//type c_uint =  std::os::raw::c_uint;
+type c_short =  std::os::raw::c_short;
+type c_uint =  std::os::raw::c_uint;
 type c_ulong = std::os::raw::c_ulong;
+type c_long = std::os::raw::c_long;
 type c_void = std::os::raw::c_void;
 type c_char = std::os::raw::c_char;
 type size_t = usize;
+type ssize_t = isize;
+pub type off_t = c_long;
 
 pub const FD_SETSIZE: usize = 1024;
 pub const EPIPE: c_int = 32;
 pub const O_RDWR: c_int = 2;
+pub const POLLIN: c_short = 1;
 
 #[repr(C)]
 pub struct fd_set {
     fds_bits: [c_ulong; FD_SETSIZE / ULONG_SIZE],
 }
 
+#[repr(C)]
+pub struct pollfd {
+    pub fd: c_int,
+    pub events: c_short,
+    pub revents: c_short,
+}
+
 pub const RTLD_LAZY: c_int = 1;
 pub const RTLD_LOCAL: c_int = 0;
     
@@ -55,6 +67,9 @@ extern "C"{
         timeout: *mut timeval,
     ) -> c_int;
     pub fn read(fd: c_int, buf: *mut c_void, count: size_t) -> c_int;
+    pub fn poll(fds: *mut pollfd, nfds: c_ulong, timeout: c_int) -> c_int;
+    pub fn pread(fd: c_int, buf: *mut c_void, count: size_t, offset: off_t) -> ssize_t;
+    pub fn getuid() -> c_uint;
 }
 
 pub unsafe fn FD_SET(fd: c_int, set: *mut fd_set) -> () {
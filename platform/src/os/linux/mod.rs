@@ -1,6 +1,9 @@
 #[cfg(not(any(linux_direct, target_env="ohos", target_os="android")))]
 pub mod x11; 
 
+#[cfg(not(any(linux_direct, target_env="ohos", target_os="android")))]
+pub mod wayland;
+
 #[cfg(linux_direct)]
 pub mod direct;
 
@@ -13,10 +16,18 @@ pub mod libc_sys;
 pub mod opengl;
 pub mod module_loader;
 
+#[cfg(not(any(target_env="ohos", target_os="android")))]
+pub mod headless;
 #[cfg(not(any(target_env="ohos", target_os="android")))]
 pub mod dma_buf;
 #[cfg(not(any(target_env="ohos", target_os="android")))]
 pub mod ipc;
+#[cfg(not(any(target_env="ohos", target_os="android")))]
+pub mod dbus;
+#[cfg(not(any(target_env="ohos", target_os="android")))]
+pub mod atspi;
+#[cfg(not(any(target_env="ohos", target_os="android")))]
+pub mod color_scheme;
 
 #[cfg(not(any(target_env="ohos", target_os="android")))]
 pub mod alsa_sys;
@@ -55,6 +66,9 @@ pub(crate) use self::direct::linux_direct::*;
 
 pub(crate) use self::opengl::*;
 
+#[cfg(not(any(target_os="android", target_env="ohos")))]
+pub use self::headless::HeadlessImage;
+
 #[cfg(not(any(target_os="android", target_env="ohos")))]
 pub(crate) use self::alsa_midi::{OsMidiInput, OsMidiOutput};
 
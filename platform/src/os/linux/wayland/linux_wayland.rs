@@ -0,0 +1,400 @@
+use {
+    std::cell::RefCell,
+    std::ffi::{CStr, CString},
+    std::rc::Rc,
+    self::super::{
+        wayland_app::WaylandApp,
+        wayland_display::WaylandDisplay,
+        wayland_event::WaylandEvent,
+        wayland_window::WaylandWindow,
+    },
+    self::super::super::{
+        egl_sys,
+        x11::opengl_x11::OpenglCx,
+    },
+    crate::{
+        cx_api::CxOsOp,
+        makepad_math::dvec2,
+        makepad_live_id::*,
+        thread::SignalToUI,
+        event::{
+            Event,
+            HttpError,
+            NetworkResponse,
+            NetworkResponseItem,
+            VideoDecodingErrorEvent,
+            WindowClosedEvent,
+        },
+        pass::CxPassParent,
+        cx::Cx,
+        os::cx_native::EventFlow,
+    }
+};
+
+impl Cx {
+    // runs the app on the given Wayland display instead of the one in the environment,
+    // returns false when it can't be used, there is no fallback to X11 here
+    pub fn wayland_event_loop_on(cx: Rc<RefCell<Cx>>, display: &str) -> bool {
+        let Ok(display) = CString::new(display) else {
+            return false
+        };
+        Cx::init_linux_window_os(&cx);
+        Cx::wayland_event_loop(cx, Some(&display))
+    }
+
+    // runs the app on a Wayland compositor, returns false when none could be used
+    pub(crate) fn wayland_event_loop(cx: Rc<RefCell<Cx>>, display: Option<&CStr>) -> bool {
+        let Some(display) = WaylandDisplay::connect(display) else {
+            return false
+        };
+        let egl_display = display.display;
+        let Some(mut wayland_app) = WaylandApp::new(display, Box::new({
+            let cx = cx.clone();
+            move | wayland_app, event | {
+                cx.borrow_mut().wayland_event_callback(wayland_app, event)
+            }
+        })) else {
+            crate::error!("libxkbcommon is needed to run on Wayland");
+            return false
+        };
+
+        cx.borrow_mut().os.opengl_cx = Some(unsafe {
+            OpenglCx::from_egl_platform_display(
+                egl_sys::EGL_PLATFORM_WAYLAND_KHR,
+                egl_display,
+            )
+        });
+
+        cx.borrow_mut().call_event_handler(&Event::Startup);
+        cx.borrow_mut().redraw_all();
+        wayland_app.start_timer(0, 0.008, true);
+        wayland_app.event_loop();
+        true
+    }
+
+    fn wayland_event_callback(
+        &mut self,
+        wayland_app: &mut WaylandApp,
+        event: WaylandEvent,
+    ) -> EventFlow {
+        if let EventFlow::Exit = self.handle_wayland_platform_ops(wayland_app) {
+            return EventFlow::Exit
+        }
+
+        let mut paint_dirty = false;
+
+        match event {
+            WaylandEvent::AppGotFocus => {
+                for window in wayland_app.windows.iter() {
+                    if let Some(main_pass_id) = self.windows[window.window_id].main_pass_id {
+                        self.repaint_pass(main_pass_id);
+                    }
+                }
+                paint_dirty = true;
+                self.call_event_handler(&Event::AppGotFocus);
+            }
+            WaylandEvent::AppLostFocus => {
+                self.call_event_handler(&Event::AppLostFocus);
+            }
+            WaylandEvent::WindowGeomChange(mut re) => {
+                if let Some(window) = wayland_app.windows.iter_mut().find( | w | w.window_id == re.window_id) {
+                    if let Some(dpi_override) = self.windows[re.window_id].dpi_override {
+                        re.new_geom.inner_size *= re.new_geom.dpi_factor / dpi_override;
+                        re.new_geom.dpi_factor = dpi_override;
+                    }
+
+                    window.window_geom = re.new_geom.clone();
+                    self.windows[re.window_id].window_geom = re.new_geom.clone();
+                    // the whole window is redrawn when the pixel size changes, also for a new scale
+                    if re.old_geom.inner_size != re.new_geom.inner_size || re.old_geom.dpi_factor != re.new_geom.dpi_factor {
+                        if let Some(main_pass_id) = self.windows[re.window_id].main_pass_id {
+                            self.redraw_pass_and_child_passes(main_pass_id);
+                        }
+                    }
+                }
+                self.call_event_handler(&Event::WindowGeomChange(re));
+            }
+            WaylandEvent::WindowClosed(wc) => {
+                let window_id = wc.window_id;
+                self.call_event_handler(&Event::WindowClosed(wc));
+                self.windows[window_id].is_created = false;
+                if let Some(index) = wayland_app.windows.iter().position( | w | w.window_id == window_id) {
+                    let mut window = wayland_app.windows.remove(index);
+                    window.close_window(&wayland_app.display, self.os.opengl_cx.as_ref().unwrap());
+                    if wayland_app.windows.is_empty() {
+                        wayland_app.terminate_event_loop();
+                        self.call_event_handler(&Event::Shutdown);
+                        return EventFlow::Exit
+                    }
+                }
+            }
+            WaylandEvent::Paint => {
+                if self.new_next_frames.len() != 0 {
+                    self.call_next_frame_event(wayland_app.time_now());
+                }
+                if self.need_redrawing() {
+                    self.call_draw_event();
+                    self.os.opengl_cx.as_ref().unwrap().make_current();
+                    self.opengl_compile_shaders();
+                }
+                self.handle_wayland_repaint(wayland_app);
+            }
+            WaylandEvent::MouseDown(e) => {
+                self.fingers.process_tap_count(
+                    e.abs,
+                    e.time
+                );
+                self.fingers.mouse_down(e.button, e.window_id);
+                self.call_event_handler(&Event::MouseDown(e.into()))
+            }
+            WaylandEvent::MouseMove(e) => {
+                self.call_event_handler(&Event::MouseMove(e.into()));
+                self.fingers.cycle_hover_area(live_id!(mouse).into());
+                self.fingers.switch_captures();
+            }
+            WaylandEvent::MouseUp(e) => {
+                let button = e.button;
+                self.call_event_handler(&Event::MouseUp(e.into()));
+                self.fingers.mouse_up(button);
+                self.fingers.cycle_hover_area(live_id!(mouse).into());
+            }
+            WaylandEvent::MouseLeave(e) => {
+                self.call_event_handler(&Event::MouseLeave(e.into()));
+                self.fingers.cycle_hover_area(live_id!(mouse).into());
+                self.fingers.switch_captures();
+            }
+            WaylandEvent::Scroll(e) => {
+                self.call_event_handler(&Event::Scroll(e.into()))
+            }
+            WaylandEvent::TouchUpdate(e) => {
+                self.fingers.process_touch_update_start(e.time, &e.touches);
+                let e = Event::TouchUpdate(e);
+                self.call_event_handler(&e);
+                let e = if let Event::TouchUpdate(e) = e {e} else {panic!()};
+                self.fingers.process_touch_update_end(&e.touches);
+            }
+            WaylandEvent::WindowDragQuery(e) => {
+                self.call_event_handler(&Event::WindowDragQuery(e))
+            }
+            WaylandEvent::WindowCloseRequested(e) => {
+                self.call_event_handler(&Event::WindowCloseRequested(e))
+            }
+            WaylandEvent::TextInput(e) => {
+                self.call_event_handler(&Event::TextInput(e))
+            }
+            WaylandEvent::Drag(e) => {
+                self.call_event_handler(&Event::Drag(e))
+            }
+            WaylandEvent::Drop(e) => {
+                self.call_event_handler(&Event::Drop(e))
+            }
+            WaylandEvent::DragEnd => {
+                self.call_event_handler(&Event::DragEnd)
+            }
+            WaylandEvent::KeyDown(e) => {
+                self.keyboard.process_key_down(e.clone());
+                self.call_event_handler(&Event::KeyDown(e))
+            }
+            WaylandEvent::KeyUp(e) => {
+                self.keyboard.process_key_up(e.clone());
+                self.call_event_handler(&Event::KeyUp(e))
+            }
+            WaylandEvent::TextCopy(e) => {
+                self.call_event_handler(&Event::TextCopy(e))
+            }
+            WaylandEvent::TextCut(e) => {
+                self.call_event_handler(&Event::TextCut(e))
+            }
+            WaylandEvent::Timer(e) => {
+                if e.timer_id == 0 {
+                    if SignalToUI::check_and_clear_ui_signal() {
+                        self.handle_media_signals();
+                        self.call_event_handler(&Event::Signal);
+                    }
+                    self.handle_action_receiver();
+                }
+                else {
+                    self.call_event_handler(&Event::Timer(e))
+                }
+
+                if self.handle_live_edit() {
+                    self.call_event_handler(&Event::LiveEdit);
+                    self.redraw_all();
+                }
+            }
+        }
+
+        // dirty window passes only keep the loop polling once the compositor is ready for a frame,
+        // otherwise the frame callback wakes us up
+        let can_paint = wayland_app.windows.iter().any( | window | window.can_paint());
+        if (self.any_passes_dirty() && can_paint) || self.need_redrawing() || paint_dirty {
+            EventFlow::Poll
+        } else {
+            EventFlow::Wait
+        }
+    }
+
+    fn handle_wayland_repaint(&mut self, wayland_app: &mut WaylandApp) {
+        self.os.opengl_cx.as_ref().unwrap().make_current();
+        let mut passes_todo = Vec::new();
+        self.compute_pass_repaint_order(&mut passes_todo);
+        self.repaint_id += 1;
+        let time = wayland_app.time_now() as f32;
+        for pass_id in &passes_todo {
+            self.passes[*pass_id].set_time(time);
+            match self.passes[*pass_id].parent.clone() {
+                CxPassParent::Window(window_id) => {
+                    if let Some(window) = wayland_app.windows.iter_mut().find( | w | w.window_id == window_id) {
+                        // the pass stays dirty until the compositor asks for the next frame
+                        if !window.can_paint() {
+                            continue;
+                        }
+                        window.request_frame(&wayland_app.display);
+                        self.draw_pass_to_egl_surface(*pass_id, window.egl_surface, window.buffer_size);
+                    }
+                }
+                CxPassParent::Pass(_) => {
+                    self.draw_pass_to_magic_texture(*pass_id);
+                },
+                CxPassParent::None => {
+                    self.draw_pass_to_magic_texture(*pass_id);
+                }
+            }
+        }
+        wayland_app.display.flush();
+    }
+
+    fn handle_wayland_platform_ops(&mut self, wayland_app: &mut WaylandApp) -> EventFlow {
+        let mut ret = EventFlow::Poll;
+        let mut network_errors = Vec::new();
+        let mut video_errors = Vec::new();
+        while let Some(op) = self.platform_ops.pop() {
+            match op {
+                CxOsOp::CreateWindow(window_id) => {
+                    let window = &mut self.windows[window_id];
+                    let wayland_window = WaylandWindow::new(
+                        window_id,
+                        &wayland_app.display,
+                        self.os.opengl_cx.as_ref().unwrap(),
+                        window.create_inner_size.unwrap_or(dvec2(800., 600.)),
+                        &window.create_title,
+                    );
+                    window.window_geom = wayland_window.window_geom.clone();
+                    wayland_app.windows.push(wayland_window);
+                    window.is_created = true;
+                },
+                CxOsOp::CloseWindow(window_id) => {
+                    if let Some(index) = wayland_app.windows.iter().position( | w | w.window_id == window_id) {
+                        self.windows[window_id].is_created = false;
+                        let mut window = wayland_app.windows.remove(index);
+                        window.close_window(&wayland_app.display, self.os.opengl_cx.as_ref().unwrap());
+                        self.call_event_handler(&Event::WindowClosed(WindowClosedEvent {window_id}));
+                        if wayland_app.windows.is_empty() {
+                            ret = EventFlow::Exit
+                        }
+                    }
+                },
+                CxOsOp::Quit => {
+                    ret = EventFlow::Exit
+                }
+                CxOsOp::MinimizeWindow(window_id) => {
+                    if let Some(window) = wayland_app.windows.iter().find( | w | w.window_id == window_id) {
+                        window.minimize(&wayland_app.display);
+                    }
+                },
+                CxOsOp::MaximizeWindow(window_id) => {
+                    if let Some(window) = wayland_app.windows.iter().find( | w | w.window_id == window_id) {
+                        window.maximize(&wayland_app.display);
+                    }
+                },
+                CxOsOp::RestoreWindow(window_id) => {
+                    if let Some(window) = wayland_app.windows.iter().find( | w | w.window_id == window_id) {
+                        window.restore(&wayland_app.display);
+                    }
+                },
+                CxOsOp::FullscreenWindow(window_id) => {
+                    if let Some(window) = wayland_app.windows.iter().find( | w | w.window_id == window_id) {
+                        window.fullscreen(&wayland_app.display);
+                    }
+                },
+                CxOsOp::NormalizeWindow(window_id) => {
+                    if let Some(window) = wayland_app.windows.iter().find( | w | w.window_id == window_id) {
+                        window.unfullscreen(&wayland_app.display);
+                    }
+                }
+                CxOsOp::SetTopmost(_window_id, _is_topmost) => {
+                    // xdg-shell leaves stacking to the compositor
+                }
+                CxOsOp::ShowClipboardActions(_) => {
+                },
+                CxOsOp::CopyToClipboard(content) => {
+                    wayland_app.copy_to_clipboard(&content);
+                }
+                CxOsOp::XrStartPresenting => {
+                },
+                CxOsOp::XrStopPresenting => {
+                },
+                CxOsOp::ShowTextIME(area, pos) => {
+                    let pos = area.clipped_rect(self).pos + pos;
+                    wayland_app.show_text_ime(pos);
+                }
+                CxOsOp::HideTextIME => {
+                    wayland_app.hide_text_ime();
+                },
+                CxOsOp::SetCursor(cursor) => {
+                    wayland_app.set_mouse_cursor(cursor);
+                },
+                CxOsOp::StartTimer {timer_id, interval, repeats} => {
+                    wayland_app.start_timer(timer_id, interval, repeats);
+                },
+                CxOsOp::StopTimer(timer_id) => {
+                    wayland_app.stop_timer(timer_id);
+                },
+                CxOsOp::StartDragging(_dragged_item) => {
+                },
+                CxOsOp::UpdateMacosMenu(_menu) => {
+                },
+                CxOsOp::HttpRequest {request_id, request} => {
+                    network_errors.push(NetworkResponseItem {
+                        request_id,
+                        response: NetworkResponse::HttpRequestError(HttpError {
+                            message: "http requests are not supported on Wayland yet".to_string(),
+                            metadata_id: request.metadata_id
+                        })
+                    });
+                },
+                CxOsOp::CancelHttpRequest {..} => {
+                    // requests fail right away, so there is never one to cancel
+                }
+                CxOsOp::PrepareVideoPlayback(video_id, ..) => {
+                    video_errors.push(VideoDecodingErrorEvent {
+                        video_id,
+                        error: "video playback is not supported on Wayland yet".to_string()
+                    });
+                },
+                CxOsOp::BeginVideoPlayback(_) |
+                CxOsOp::PauseVideoPlayback(_) |
+                CxOsOp::ResumeVideoPlayback(_) |
+                CxOsOp::MuteVideoPlayback(_) |
+                CxOsOp::UnmuteVideoPlayback(_) |
+                CxOsOp::CleanupVideoPlaybackResources(_) |
+                CxOsOp::UpdateVideoSurfaceTexture(_) => {
+                    // playback never got prepared
+                },
+                CxOsOp::SaveFileDialog(_) |
+                CxOsOp::SelectFileDialog(_) |
+                CxOsOp::SaveFolderDialog(_) |
+                CxOsOp::SelectFolderDialog(_) => {
+                    crate::error!("file dialogs are not supported on Wayland yet");
+                },
+            }
+        }
+        if !network_errors.is_empty() {
+            self.call_event_handler(&Event::NetworkResponses(network_errors));
+        }
+        for e in video_errors {
+            self.call_event_handler(&Event::VideoDecodingError(e));
+        }
+        ret
+    }
+}
@@ -0,0 +1,8 @@
+pub mod wayland_sys;
+pub mod wayland_protocols;
+pub mod xkb_sys;
+pub mod wayland_display;
+pub mod wayland_event;
+pub mod wayland_window;
+pub mod wayland_app;
+pub mod linux_wayland;
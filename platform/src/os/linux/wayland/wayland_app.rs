@@ -0,0 +1,1373 @@
+use {
+    std::{
+        cell::{Cell, RefCell},
+        ffi::CString,
+        fs::File,
+        io::{ErrorKind, Read, Write},
+        os::{
+            fd::{AsRawFd, FromRawFd, OwnedFd},
+            raw::{c_char, c_int},
+        },
+        ptr,
+        rc::Rc,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    self::super::{
+        wayland_sys::*,
+        wayland_protocols::*,
+        xkb_sys::*,
+        wayland_display::{WaylandDisplay, WaylandMessage},
+        wayland_event::WaylandEvent,
+        wayland_window::WaylandWindow,
+    },
+    self::super::super::{
+        libc_sys,
+        select_timer::SelectTimers,
+        x11::xlib_app::keysym_to_key_code,
+    },
+    crate::{
+        area::Area,
+        cursor::MouseCursor,
+        event::*,
+        makepad_math::{DVec2, dvec2},
+        os::cx_native::EventFlow,
+        window::WindowId,
+    },
+};
+
+// timer ids handed out by Cx count up from 1, so key repeat can never collide with them
+const KEY_REPEAT_TIMER_ID: u64 = u64::MAX;
+
+const TEXT_MIME_TYPES: [&str; 3] = ["text/plain;charset=utf-8", "text/plain", "UTF8_STRING"];
+const URI_LIST_MIME_TYPE: &str = "text/uri-list";
+// a paste or drop blocks the event loop while it reads, a stuck sender must not freeze us
+const RECEIVE_OFFER_TIMEOUT: Duration = Duration::from_millis(500);
+
+// axis events are collected until the pointer frame that ends them
+#[derive(Default)]
+struct PendingScroll {
+    delta: DVec2,
+    steps: DVec2,
+    source: Option<u32>,
+}
+
+struct DataOffer {
+    proxy: *mut wl_proxy,
+    mime_types: Vec<String>,
+}
+
+// the wl_cursor fallback for compositors without the cursor shape protocol
+struct CursorTheme {
+    lib: LibWaylandCursor,
+    theme: *mut wl_cursor_theme,
+    surface: *mut wl_proxy,
+}
+
+impl CursorTheme {
+    fn load(display: &WaylandDisplay) -> Option<CursorTheme> {
+        if display.shm.is_null() {
+            return None
+        }
+        let lib = LibWaylandCursor::try_load()?;
+        let size = std::env::var("XCURSOR_SIZE").ok().and_then( | size | size.parse().ok()).unwrap_or(24);
+        let name = std::env::var("XCURSOR_THEME").ok().and_then( | name | CString::new(name).ok());
+        unsafe {
+            let theme = (lib.wl_cursor_theme_load)(name.as_ref().map_or(ptr::null(), | name | name.as_ptr()), size, display.shm);
+            if theme.is_null() {
+                return None
+            }
+            let surface = display.create(display.compositor, WL_COMPOSITOR_CREATE_SURFACE, display.lib.wl_surface_interface, &mut [wl_argument::new_id()]);
+            Some(CursorTheme {lib, theme, surface})
+        }
+    }
+
+    fn set_cursor(&self, display: &WaylandDisplay, pointer: *mut wl_proxy, serial: u32, names: &[&str]) {
+        unsafe {
+            for name in names {
+                let name = CString::new(*name).unwrap();
+                let cursor = (self.lib.wl_cursor_theme_get_cursor)(self.theme, name.as_ptr());
+                if cursor.is_null() || (*cursor).image_count == 0 {
+                    continue;
+                }
+                let image = *(*cursor).images;
+                let buffer = (self.lib.wl_cursor_image_get_buffer)(image);
+                display.request(self.surface, WL_SURFACE_ATTACH, &mut [
+                    wl_argument::object(buffer),
+                    wl_argument::int(0),
+                    wl_argument::int(0)
+                ]);
+                display.request(self.surface, WL_SURFACE_DAMAGE, &mut [
+                    wl_argument::int(0),
+                    wl_argument::int(0),
+                    wl_argument::int((*image).width as i32),
+                    wl_argument::int((*image).height as i32)
+                ]);
+                display.request(self.surface, WL_SURFACE_COMMIT, &mut []);
+                display.request(pointer, WL_POINTER_SET_CURSOR, &mut [
+                    wl_argument::uint(serial),
+                    wl_argument::object(self.surface),
+                    wl_argument::int((*image).hotspot_x as i32),
+                    wl_argument::int((*image).hotspot_y as i32)
+                ]);
+                return
+            }
+        }
+    }
+}
+
+pub struct WaylandApp {
+    pub display: WaylandDisplay,
+    pub windows: Vec<Box<WaylandWindow>>,
+    pub timers: SelectTimers,
+    pub event_loop_running: bool,
+    pub event_callback: Option<Box<dyn FnMut(&mut WaylandApp, WaylandEvent) -> EventFlow>>,
+    pub event_flow: EventFlow,
+    pub current_cursor: MouseCursor,
+    pub last_scroll_time: f64,
+    pub last_click_time: f64,
+    pub last_click_pos: DVec2,
+
+    xkb: LibXkbCommon,
+    xkb_context: *mut xkb_context,
+    xkb_keymap: *mut xkb_keymap,
+    xkb_state: *mut xkb_state,
+    repeat_rate: i32,
+    repeat_delay: i32,
+    repeat_key: Option<u32>,
+
+    pointer: *mut wl_proxy,
+    keyboard: *mut wl_proxy,
+    touch: *mut wl_proxy,
+    cursor_shape_device: *mut wl_proxy,
+    cursor_theme: Option<CursorTheme>,
+
+    last_serial: u32,
+    pointer_window: Option<WindowId>,
+    pointer_pos: DVec2,
+    pointer_enter_serial: u32,
+    pending_scroll: PendingScroll,
+    touch_window: Option<WindowId>,
+    touches: Vec<TouchPoint>,
+
+    data_device: *mut wl_proxy,
+    data_source: *mut wl_proxy,
+    clipboard: String,
+    data_offers: Vec<DataOffer>,
+    selection_offer: *mut wl_proxy,
+    drag_offer: *mut wl_proxy,
+    drag_serial: u32,
+    drag_pos: DVec2,
+    drag_items: Arc<Vec<DragItem>>,
+
+    text_input: *mut wl_proxy,
+    text_input_window: Option<WindowId>,
+    text_input_wanted: bool,
+    text_input_active: bool,
+    ime_pos: DVec2,
+    pending_preedit: Option<String>,
+    pending_commit: Option<String>,
+    preedit_shown: bool,
+}
+
+impl WaylandApp {
+    pub fn new(display: WaylandDisplay, event_callback: Box<dyn FnMut(&mut WaylandApp, WaylandEvent) -> EventFlow>) -> Option<WaylandApp> {
+        let xkb = LibXkbCommon::try_load()?;
+        let xkb_context = unsafe {(xkb.xkb_context_new)(XKB_CONTEXT_NO_FLAGS)};
+        if xkb_context.is_null() {
+            return None
+        }
+        let (data_device, text_input) = unsafe {(
+            if !display.seat.is_null() && !display.data_device_manager.is_null() {
+                display.create(display.data_device_manager, WL_DATA_DEVICE_MANAGER_GET_DATA_DEVICE, display.lib.wl_data_device_interface, &mut [
+                    wl_argument::new_id(),
+                    wl_argument::object(display.seat)
+                ])
+            } else {ptr::null_mut()},
+            if !display.seat.is_null() && !display.text_input_manager.is_null() {
+                display.create(display.text_input_manager, ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT, &zwp_text_input_v3_interface, &mut [
+                    wl_argument::new_id(),
+                    wl_argument::object(display.seat)
+                ])
+            } else {ptr::null_mut()},
+        )};
+        Some(WaylandApp {
+            display,
+            windows: Vec::new(),
+            timers: SelectTimers::new(),
+            event_loop_running: true,
+            event_callback: Some(event_callback),
+            event_flow: EventFlow::Poll,
+            current_cursor: MouseCursor::Default,
+            last_scroll_time: 0.0,
+            last_click_time: 0.0,
+            last_click_pos: DVec2::default(),
+
+            xkb,
+            xkb_context,
+            xkb_keymap: ptr::null_mut(),
+            xkb_state: ptr::null_mut(),
+            repeat_rate: 25,
+            repeat_delay: 600,
+            repeat_key: None,
+
+            pointer: ptr::null_mut(),
+            keyboard: ptr::null_mut(),
+            touch: ptr::null_mut(),
+            cursor_shape_device: ptr::null_mut(),
+            cursor_theme: None,
+
+            last_serial: 0,
+            pointer_window: None,
+            pointer_pos: DVec2::default(),
+            pointer_enter_serial: 0,
+            pending_scroll: PendingScroll::default(),
+            touch_window: None,
+            touches: Vec::new(),
+
+            data_device,
+            data_source: ptr::null_mut(),
+            clipboard: String::new(),
+            data_offers: Vec::new(),
+            selection_offer: ptr::null_mut(),
+            drag_offer: ptr::null_mut(),
+            drag_serial: 0,
+            drag_pos: DVec2::default(),
+            drag_items: Arc::new(Vec::new()),
+
+            text_input,
+            text_input_window: None,
+            text_input_wanted: false,
+            text_input_active: false,
+            ime_pos: DVec2::default(),
+            pending_preedit: None,
+            pending_commit: None,
+            preedit_shown: false,
+        })
+    }
+
+    pub fn event_loop(&mut self) {
+        self.do_callback(WaylandEvent::Paint);
+
+        let mut timer_ids = Vec::new();
+        while self.event_loop_running {
+            match self.event_flow {
+                EventFlow::Exit => {
+                    break;
+                }
+                EventFlow::Wait => {
+                    self.fire_timers(&mut timer_ids);
+                    if let EventFlow::Wait = self.event_flow {
+                        let timeout = self.timers.next_timeout();
+                        self.read_events(timeout);
+                    }
+                    self.event_flow = EventFlow::Poll;
+                }
+                EventFlow::Poll => {
+                    self.fire_timers(&mut timer_ids);
+                    self.event_loop_poll();
+                }
+            }
+        }
+    }
+
+    fn event_loop_poll(&mut self) {
+        self.read_events(Some(0.0));
+        for message in self.display.take_messages() {
+            if !self.event_loop_running {
+                return
+            }
+            self.handle_message(message);
+        }
+        self.do_callback(WaylandEvent::Paint);
+    }
+
+    fn read_events(&mut self, timeout: Option<f64>) {
+        if !self.display.wait_for_events(timeout) {
+            crate::error!("Lost the connection to the Wayland compositor");
+            self.terminate_event_loop();
+        }
+    }
+
+    fn fire_timers(&mut self, timer_ids: &mut Vec<u64>) {
+        let time = self.time_now();
+        self.timers.update_timers(timer_ids);
+        for timer_id in timer_ids.iter() {
+            if *timer_id == KEY_REPEAT_TIMER_ID {
+                if let Some(keycode) = self.repeat_key {
+                    self.timers.start_timer(KEY_REPEAT_TIMER_ID, 1.0 / self.repeat_rate as f64, false);
+                    self.key_down(keycode, true);
+                }
+            }
+            else {
+                self.do_callback(WaylandEvent::Timer(TimerEvent {
+                    timer_id: *timer_id,
+                    time: Some(time)
+                }));
+            }
+        }
+    }
+
+    pub fn do_callback(&mut self, event: WaylandEvent) {
+        if let Some(mut callback) = self.event_callback.take() {
+            self.event_flow = callback(self, event);
+            if let EventFlow::Exit = self.event_flow {
+                self.terminate_event_loop();
+            }
+            self.event_callback = Some(callback);
+        }
+    }
+
+    pub fn terminate_event_loop(&mut self) {
+        self.event_loop_running = false;
+    }
+
+    pub fn start_timer(&mut self, id: u64, timeout: f64, repeats: bool) {
+        self.timers.start_timer(id, timeout, repeats);
+    }
+
+    pub fn stop_timer(&mut self, id: u64) {
+        self.timers.stop_timer(id);
+    }
+
+    pub fn time_now(&self) -> f64 {
+        self.timers.time_now()
+    }
+
+    fn window_id_for_surface(&self, surface: *mut wl_proxy) -> Option<WindowId> {
+        self.windows.iter().find( | window | window.surface == surface).map( | window | window.window_id)
+    }
+
+    fn handle_message(&mut self, mut message: WaylandMessage) {
+        let proxy = message.proxy;
+        if proxy == self.display.registry {
+            self.display.handle_registry_message(&message);
+            if message.opcode == WL_REGISTRY_GLOBAL_REMOVE {
+                let outputs = &self.display.outputs;
+                for window in &mut self.windows {
+                    window.outputs.retain( | proxy | outputs.iter().any( | output | output.proxy == *proxy));
+                }
+                self.update_all_window_geoms();
+            }
+        }
+        else if proxy == self.display.xdg_wm_base {
+            if message.opcode == XDG_WM_BASE_PING {
+                unsafe {self.display.request(proxy, XDG_WM_BASE_PONG, &mut [wl_argument::uint(message.uint(0))])};
+            }
+        }
+        else if proxy == self.display.seat {
+            if message.opcode == WL_SEAT_CAPABILITIES {
+                self.update_seat_capabilities(message.uint(0));
+            }
+        }
+        else if proxy == self.pointer {
+            self.handle_pointer_message(&message);
+        }
+        else if proxy == self.keyboard {
+            self.handle_keyboard_message(&mut message);
+        }
+        else if proxy == self.touch {
+            self.handle_touch_message(&message);
+        }
+        else if proxy == self.data_device {
+            self.handle_data_device_message(&message);
+        }
+        else if proxy == self.data_source {
+            self.handle_data_source_message(&mut message);
+        }
+        else if proxy == self.text_input {
+            self.handle_text_input_message(&message);
+        }
+        else if let Some(offer) = self.data_offers.iter_mut().find( | offer | offer.proxy == proxy) {
+            if message.opcode == WL_DATA_OFFER_OFFER {
+                if let Some(mime_type) = message.string(0) {
+                    offer.mime_types.push(mime_type.to_string());
+                }
+            }
+        }
+        else if let Some(output) = self.display.outputs.iter_mut().find( | output | output.proxy == proxy) {
+            if message.opcode == WL_OUTPUT_SCALE {
+                output.scale = message.int(0);
+                self.update_all_window_geoms();
+            }
+        }
+        else if let Some(index) = self.windows.iter().position( | window | window.owns_proxy(proxy)) {
+            self.handle_window_message(index, &message);
+        }
+    }
+
+    fn update_seat_capabilities(&mut self, capabilities: u32) {
+        let display = &self.display;
+        let seat = display.seat;
+        unsafe {
+            if capabilities & WL_SEAT_CAPABILITY_POINTER != 0 && self.pointer.is_null() {
+                self.pointer = display.create(seat, WL_SEAT_GET_POINTER, display.lib.wl_pointer_interface, &mut [wl_argument::new_id()]);
+                if !display.cursor_shape_manager.is_null() {
+                    self.cursor_shape_device = display.create(display.cursor_shape_manager, WP_CURSOR_SHAPE_MANAGER_V1_GET_POINTER, &wp_cursor_shape_device_v1_interface, &mut [
+                        wl_argument::new_id(),
+                        wl_argument::object(self.pointer)
+                    ]);
+                }
+                else if self.cursor_theme.is_none() {
+                    self.cursor_theme = CursorTheme::load(display);
+                }
+            }
+            else if capabilities & WL_SEAT_CAPABILITY_POINTER == 0 && !self.pointer.is_null() {
+                display.destroy(self.cursor_shape_device, WP_CURSOR_SHAPE_DEVICE_V1_DESTROY);
+                self.release(self.pointer, WL_POINTER_RELEASE);
+                self.cursor_shape_device = ptr::null_mut();
+                self.pointer = ptr::null_mut();
+                self.pointer_window = None;
+            }
+
+            if capabilities & WL_SEAT_CAPABILITY_KEYBOARD != 0 && self.keyboard.is_null() {
+                self.keyboard = display.create(seat, WL_SEAT_GET_KEYBOARD, display.lib.wl_keyboard_interface, &mut [wl_argument::new_id()]);
+            }
+            else if capabilities & WL_SEAT_CAPABILITY_KEYBOARD == 0 && !self.keyboard.is_null() {
+                self.release(self.keyboard, WL_KEYBOARD_RELEASE);
+                self.keyboard = ptr::null_mut();
+                self.repeat_key = None;
+            }
+
+            if capabilities & WL_SEAT_CAPABILITY_TOUCH != 0 && self.touch.is_null() {
+                self.touch = display.create(seat, WL_SEAT_GET_TOUCH, display.lib.wl_touch_interface, &mut [wl_argument::new_id()]);
+            }
+            else if capabilities & WL_SEAT_CAPABILITY_TOUCH == 0 && !self.touch.is_null() {
+                self.release(self.touch, WL_TOUCH_RELEASE);
+                self.touch = ptr::null_mut();
+                self.touches.clear();
+            }
+        }
+    }
+
+    // input devices only got a release request in version 3 of the seat
+    unsafe fn release(&self, proxy: *mut wl_proxy, opcode: u32) {
+        if self.display.version(proxy) >= 3 {
+            self.display.destroy(proxy, opcode);
+        }
+        else {
+            self.display.destroy_proxy(proxy);
+        }
+    }
+
+    fn modifiers(&self) -> KeyModifiers {
+        if self.xkb_state.is_null() {
+            return KeyModifiers::default()
+        }
+        let is_active = | name: &[u8] | unsafe {
+            (self.xkb.xkb_state_mod_name_is_active)(self.xkb_state, name.as_ptr() as *const c_char, XKB_STATE_MODS_EFFECTIVE) > 0
+        };
+        KeyModifiers {
+            shift: is_active(XKB_MOD_NAME_SHIFT),
+            control: is_active(XKB_MOD_NAME_CTRL),
+            alt: is_active(XKB_MOD_NAME_ALT),
+            logo: is_active(XKB_MOD_NAME_LOGO),
+        }
+    }
+
+    // pointer
+
+    fn handle_pointer_message(&mut self, message: &WaylandMessage) {
+        match message.opcode {
+            WL_POINTER_ENTER => {
+                self.pointer_enter_serial = message.uint(0);
+                self.pointer_window = self.window_id_for_surface(message.object(1));
+                self.apply_mouse_cursor();
+                self.send_mouse_move(dvec2(message.fixed(2), message.fixed(3)));
+            }
+            WL_POINTER_LEAVE => {
+                if let Some(window_id) = self.pointer_window.take() {
+                    self.do_callback(WaylandEvent::MouseLeave(MouseLeaveEvent {
+                        abs: self.pointer_pos,
+                        window_id,
+                        modifiers: self.modifiers(),
+                        time: self.time_now(),
+                        handled: Cell::new(Area::Empty),
+                    }));
+                }
+            }
+            WL_POINTER_MOTION => {
+                self.send_mouse_move(dvec2(message.fixed(1), message.fixed(2)));
+            }
+            WL_POINTER_BUTTON => {
+                let serial = message.uint(0);
+                self.last_serial = serial;
+                let Some(window_id) = self.pointer_window else {return};
+                let button = match message.uint(2) {
+                    BTN_LEFT => 0,
+                    BTN_RIGHT => 1,
+                    BTN_MIDDLE => 2,
+                    button => button.saturating_sub(BTN_LEFT) as usize
+                };
+                let time = self.time_now();
+                if message.uint(3) == WL_POINTER_BUTTON_STATE_PRESSED {
+                    if button == 0 && self.handle_caption_press(window_id, serial, time) {
+                        return
+                    }
+                    self.do_callback(WaylandEvent::MouseDown(MouseDownEvent {
+                        button,
+                        modifiers: self.modifiers(),
+                        window_id,
+                        abs: self.pointer_pos,
+                        time,
+                        handled: Cell::new(Area::Empty),
+                    }));
+                }
+                else {
+                    self.do_callback(WaylandEvent::MouseUp(MouseUpEvent {
+                        button,
+                        modifiers: self.modifiers(),
+                        window_id,
+                        abs: self.pointer_pos,
+                        time,
+                    }));
+                }
+            }
+            WL_POINTER_AXIS => {
+                let value = message.fixed(2);
+                match message.uint(1) {
+                    WL_POINTER_AXIS_VERTICAL_SCROLL => self.pending_scroll.delta.y += value,
+                    WL_POINTER_AXIS_HORIZONTAL_SCROLL => self.pending_scroll.delta.x += value,
+                    _ => ()
+                }
+            }
+            WL_POINTER_AXIS_SOURCE => {
+                self.pending_scroll.source = Some(message.uint(0));
+            }
+            WL_POINTER_AXIS_DISCRETE => {
+                let steps = message.int(1) as f64;
+                match message.uint(0) {
+                    WL_POINTER_AXIS_VERTICAL_SCROLL => self.pending_scroll.steps.y += steps,
+                    WL_POINTER_AXIS_HORIZONTAL_SCROLL => self.pending_scroll.steps.x += steps,
+                    _ => ()
+                }
+            }
+            WL_POINTER_FRAME => {
+                self.send_scroll();
+            }
+            _ => ()
+        }
+    }
+
+    fn send_mouse_move(&mut self, pos: DVec2) {
+        self.pointer_pos = pos;
+        let Some(window_id) = self.pointer_window else {return};
+        self.do_callback(WaylandEvent::MouseMove(MouseMoveEvent {
+            window_id,
+            abs: pos,
+            modifiers: self.modifiers(),
+            time: self.time_now(),
+            handled: Cell::new(Area::Empty),
+        }));
+    }
+
+    // returns true when the press landed on the caption and was turned into a window move
+    fn handle_caption_press(&mut self, window_id: WindowId, serial: u32, time: f64) -> bool {
+        let response = Rc::new(Cell::new(WindowDragQueryResponse::NoAnswer));
+        self.do_callback(WaylandEvent::WindowDragQuery(WindowDragQueryEvent {
+            window_id,
+            abs: self.pointer_pos,
+            response: response.clone()
+        }));
+        if !matches!(response.get(), WindowDragQueryResponse::Caption) {
+            return false
+        }
+        let pos = self.pointer_pos;
+        let is_double_click = time - self.last_click_time < 0.35
+            && (pos.x - self.last_click_pos.x).abs() < 5.0
+            && (pos.y - self.last_click_pos.y).abs() < 5.0;
+        self.last_click_time = time;
+        self.last_click_pos = pos;
+        if let Some(window) = self.windows.iter().find( | window | window.window_id == window_id) {
+            if is_double_click {
+                if window.is_maximized {
+                    window.restore(&self.display);
+                }
+                else {
+                    window.maximize(&self.display);
+                }
+            }
+            else {
+                window.start_move(&self.display, serial);
+            }
+        }
+        true
+    }
+
+    fn send_scroll(&mut self) {
+        let pending = std::mem::take(&mut self.pending_scroll);
+        let Some(window_id) = self.pointer_window else {return};
+        if pending.delta == DVec2::default() && pending.steps == DVec2::default() {
+            return
+        }
+        let time = self.time_now();
+        let is_mouse = match pending.source {
+            Some(source) => source == WL_POINTER_AXIS_SOURCE_WHEEL,
+            None => pending.steps != DVec2::default()
+        };
+        let scroll = if is_mouse {
+            let last_scroll_time = self.last_scroll_time;
+            self.last_scroll_time = time;
+            // the same acceleration curve as the wheel clicks on X11
+            let speed = 1200.0 * (0.2 - 2. * (time - last_scroll_time)).max(0.01);
+            let steps = if pending.steps != DVec2::default() {
+                pending.steps
+            }
+            else {
+                dvec2(sign(pending.delta.x), sign(pending.delta.y))
+            };
+            steps * speed
+        }
+        else {
+            pending.delta
+        };
+        self.do_callback(WaylandEvent::Scroll(ScrollEvent {
+            window_id,
+            scroll,
+            abs: self.pointer_pos,
+            modifiers: self.modifiers(),
+            is_mouse,
+            handled_x: Cell::new(false),
+            handled_y: Cell::new(false),
+            time
+        }));
+    }
+
+    pub fn set_mouse_cursor(&mut self, cursor: MouseCursor) {
+        if self.current_cursor != cursor {
+            self.current_cursor = cursor;
+            self.apply_mouse_cursor();
+        }
+    }
+
+    fn apply_mouse_cursor(&mut self) {
+        if self.pointer.is_null() || self.pointer_window.is_none() {
+            return
+        }
+        let serial = self.pointer_enter_serial;
+        unsafe {
+            if let MouseCursor::Hidden = self.current_cursor {
+                self.display.request(self.pointer, WL_POINTER_SET_CURSOR, &mut [
+                    wl_argument::uint(serial),
+                    wl_argument::object(ptr::null_mut()),
+                    wl_argument::int(0),
+                    wl_argument::int(0)
+                ]);
+                return
+            }
+            let (shape, names) = cursor_shape(self.current_cursor);
+            if !self.cursor_shape_device.is_null() {
+                self.display.request(self.cursor_shape_device, WP_CURSOR_SHAPE_DEVICE_V1_SET_SHAPE, &mut [
+                    wl_argument::uint(serial),
+                    wl_argument::uint(shape)
+                ]);
+            }
+            else if let Some(cursor_theme) = &self.cursor_theme {
+                cursor_theme.set_cursor(&self.display, self.pointer, serial, names);
+            }
+        }
+    }
+
+    // keyboard
+
+    fn handle_keyboard_message(&mut self, message: &mut WaylandMessage) {
+        match message.opcode {
+            WL_KEYBOARD_KEYMAP => {
+                if message.uint(0) == WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1 {
+                    let size = message.uint(2) as usize;
+                    if let Some(fd) = message.take_fd(1) {
+                        self.load_keymap(fd, size);
+                    }
+                }
+            }
+            WL_KEYBOARD_ENTER => {
+                self.last_serial = message.uint(0);
+                self.do_callback(WaylandEvent::AppGotFocus);
+            }
+            WL_KEYBOARD_LEAVE => {
+                self.repeat_key = None;
+                self.timers.stop_timer(KEY_REPEAT_TIMER_ID);
+                self.do_callback(WaylandEvent::AppLostFocus);
+            }
+            WL_KEYBOARD_KEY => {
+                self.last_serial = message.uint(0);
+                let keycode = message.uint(2) + EVDEV_OFFSET;
+                if message.uint(3) == WL_KEYBOARD_KEY_STATE_PRESSED {
+                    self.key_down(keycode, false);
+                }
+                else {
+                    self.key_up(keycode);
+                }
+            }
+            WL_KEYBOARD_MODIFIERS => {
+                if !self.xkb_state.is_null() {
+                    unsafe {(self.xkb.xkb_state_update_mask)(
+                        self.xkb_state,
+                        message.uint(1),
+                        message.uint(2),
+                        message.uint(3),
+                        0,
+                        0,
+                        message.uint(4)
+                    )};
+                }
+            }
+            WL_KEYBOARD_REPEAT_INFO => {
+                self.repeat_rate = message.int(0);
+                self.repeat_delay = message.int(1);
+            }
+            _ => ()
+        }
+    }
+
+    fn load_keymap(&mut self, fd: OwnedFd, size: usize) {
+        let mut buffer = vec![0u8; size];
+        // the keymap fd can be shared between clients, so we read it without moving its offset
+        let read = unsafe {libc_sys::pread(fd.as_raw_fd(), buffer.as_mut_ptr() as *mut _, size, 0)};
+        if read <= 0 {
+            return
+        }
+        buffer.truncate(read as usize);
+        if buffer.last() != Some(&0) {
+            buffer.push(0);
+        }
+        unsafe {
+            let keymap = (self.xkb.xkb_keymap_new_from_string)(
+                self.xkb_context,
+                buffer.as_ptr() as *const c_char,
+                XKB_KEYMAP_FORMAT_TEXT_V1,
+                XKB_KEYMAP_COMPILE_NO_FLAGS
+            );
+            if keymap.is_null() {
+                crate::error!("Could not compile the keymap of the Wayland compositor");
+                return
+            }
+            if !self.xkb_state.is_null() {
+                (self.xkb.xkb_state_unref)(self.xkb_state);
+            }
+            if !self.xkb_keymap.is_null() {
+                (self.xkb.xkb_keymap_unref)(self.xkb_keymap);
+            }
+            self.xkb_keymap = keymap;
+            self.xkb_state = (self.xkb.xkb_state_new)(keymap);
+        }
+    }
+
+    fn key_down(&mut self, keycode: u32, is_repeat: bool) {
+        if self.xkb_state.is_null() {
+            return
+        }
+        let keysym = unsafe {(self.xkb.xkb_state_key_get_one_sym)(self.xkb_state, keycode)};
+        let key_code = keysym_to_key_code(keysym);
+        let modifiers = self.modifiers();
+        let time = self.time_now();
+
+        let block_text = modifiers.control || modifiers.logo || modifiers.alt;
+        if modifiers.control || modifiers.logo {
+            match key_code {
+                KeyCode::KeyV => {
+                    self.paste();
+                }
+                KeyCode::KeyX | KeyCode::KeyC => {
+                    let response = Rc::new(RefCell::new(None));
+                    let event = TextClipboardEvent {
+                        response: response.clone()
+                    };
+                    self.do_callback(if key_code == KeyCode::KeyX {
+                        WaylandEvent::TextCut(event)
+                    } else {
+                        WaylandEvent::TextCopy(event)
+                    });
+                    let response = response.borrow().clone();
+                    if let Some(response) = response {
+                        self.copy_to_clipboard(&response);
+                    }
+                }
+                _ => ()
+            }
+        }
+
+        self.do_callback(WaylandEvent::KeyDown(KeyEvent {
+            key_code,
+            is_repeat,
+            modifiers,
+            time,
+        }));
+
+        if !block_text {
+            let mut buffer = [0u8; 64];
+            let len = unsafe {(self.xkb.xkb_state_key_get_utf8)(self.xkb_state, keycode, buffer.as_mut_ptr() as *mut c_char, buffer.len())};
+            if len > 0 && (len as usize) < buffer.len() {
+                if let Ok(input) = std::str::from_utf8(&buffer[..len as usize]) {
+                    let char_code = input.chars().next().unwrap_or('\0');
+                    if char_code >= ' ' && char_code != 127 as char {
+                        self.do_callback(WaylandEvent::TextInput(TextInputEvent {
+                            input: input.to_string(),
+                            was_paste: false,
+                            replace_last: false
+                        }));
+                    }
+                }
+            }
+        }
+
+        if !is_repeat && self.repeat_rate > 0 && unsafe {(self.xkb.xkb_keymap_key_repeats)(self.xkb_keymap, keycode)} != 0 {
+            self.repeat_key = Some(keycode);
+            self.timers.start_timer(KEY_REPEAT_TIMER_ID, self.repeat_delay as f64 / 1000.0, false);
+        }
+    }
+
+    fn key_up(&mut self, keycode: u32) {
+        if self.repeat_key == Some(keycode) {
+            self.repeat_key = None;
+            self.timers.stop_timer(KEY_REPEAT_TIMER_ID);
+        }
+        if self.xkb_state.is_null() {
+            return
+        }
+        let keysym = unsafe {(self.xkb.xkb_state_key_get_one_sym)(self.xkb_state, keycode)};
+        self.do_callback(WaylandEvent::KeyUp(KeyEvent {
+            key_code: keysym_to_key_code(keysym),
+            is_repeat: false,
+            modifiers: self.modifiers(),
+            time: self.time_now(),
+        }));
+    }
+
+    // touch
+
+    fn handle_touch_message(&mut self, message: &WaylandMessage) {
+        let time = self.time_now();
+        match message.opcode {
+            WL_TOUCH_DOWN => {
+                self.last_serial = message.uint(0);
+                if let Some(window_id) = self.window_id_for_surface(message.object(2)) {
+                    self.touch_window = Some(window_id);
+                }
+                self.touches.push(TouchPoint {
+                    state: TouchState::Start,
+                    abs: dvec2(message.fixed(4), message.fixed(5)),
+                    time,
+                    uid: message.int(3) as u64,
+                    rotation_angle: 0.0,
+                    force: 0.0,
+                    radius: DVec2::default(),
+                    handled: Cell::new(Area::Empty),
+                    sweep_lock: Cell::new(Area::Empty),
+                });
+            }
+            WL_TOUCH_UP => {
+                let uid = message.int(2) as u64;
+                if let Some(touch) = self.touches.iter_mut().find( | touch | touch.uid == uid) {
+                    touch.state = TouchState::Stop;
+                }
+            }
+            WL_TOUCH_MOTION => {
+                let uid = message.int(1) as u64;
+                if let Some(touch) = self.touches.iter_mut().find( | touch | touch.uid == uid) {
+                    touch.abs = dvec2(message.fixed(2), message.fixed(3));
+                    if !matches!(touch.state, TouchState::Start) {
+                        touch.state = TouchState::Move;
+                    }
+                }
+            }
+            WL_TOUCH_FRAME => {
+                self.send_touch_update();
+            }
+            WL_TOUCH_CANCEL => {
+                for touch in &mut self.touches {
+                    touch.state = TouchState::Stop;
+                }
+                self.send_touch_update();
+            }
+            _ => ()
+        }
+    }
+
+    fn send_touch_update(&mut self) {
+        let Some(window_id) = self.touch_window else {return};
+        if self.touches.is_empty() {
+            return
+        }
+        let time = self.time_now();
+        for touch in &mut self.touches {
+            touch.time = time;
+        }
+        self.do_callback(WaylandEvent::TouchUpdate(TouchUpdateEvent {
+            time,
+            window_id,
+            modifiers: self.modifiers(),
+            touches: self.touches.clone(),
+        }));
+        self.touches.retain( | touch | !matches!(touch.state, TouchState::Stop));
+        for touch in &mut self.touches {
+            touch.state = TouchState::Stable;
+        }
+        if self.touches.is_empty() {
+            self.touch_window = None;
+        }
+    }
+
+    // clipboard and drag and drop
+
+    fn handle_data_device_message(&mut self, message: &WaylandMessage) {
+        match message.opcode {
+            WL_DATA_DEVICE_DATA_OFFER => {
+                self.data_offers.push(DataOffer {
+                    proxy: message.object(0),
+                    mime_types: Vec::new()
+                });
+            }
+            WL_DATA_DEVICE_ENTER => {
+                self.drag_serial = message.uint(0);
+                self.drag_pos = dvec2(message.fixed(2), message.fixed(3));
+                let offer = message.object(4);
+                if offer != self.drag_offer {
+                    self.end_drag();
+                }
+                self.drag_offer = offer;
+                self.drag_items = Arc::new(self.receive_drag_items(offer));
+                self.send_drag();
+            }
+            WL_DATA_DEVICE_MOTION => {
+                self.drag_pos = dvec2(message.fixed(1), message.fixed(2));
+                self.send_drag();
+            }
+            WL_DATA_DEVICE_DROP => {
+                if !self.drag_items.is_empty() {
+                    self.do_callback(WaylandEvent::Drop(DropEvent {
+                        modifiers: self.modifiers(),
+                        handled: Arc::new(Mutex::new(false)),
+                        abs: self.drag_pos,
+                        items: self.drag_items.clone(),
+                    }));
+                    unsafe {
+                        if self.display.version(self.drag_offer) >= 3 {
+                            self.display.request(self.drag_offer, WL_DATA_OFFER_FINISH, &mut []);
+                        }
+                    }
+                }
+                self.end_drag();
+            }
+            WL_DATA_DEVICE_LEAVE => {
+                self.end_drag();
+            }
+            WL_DATA_DEVICE_SELECTION => {
+                let offer = message.object(0);
+                if offer != self.selection_offer {
+                    self.destroy_data_offer(self.selection_offer);
+                }
+                self.selection_offer = offer;
+            }
+            _ => ()
+        }
+    }
+
+    fn handle_data_source_message(&mut self, message: &mut WaylandMessage) {
+        match message.opcode {
+            WL_DATA_SOURCE_SEND => {
+                if let Some(fd) = message.take_fd(1) {
+                    let _ = File::from(fd).write_all(self.clipboard.as_bytes());
+                }
+            }
+            WL_DATA_SOURCE_CANCELLED => {
+                unsafe {self.display.destroy(self.data_source, WL_DATA_SOURCE_DESTROY)};
+                self.data_source = ptr::null_mut();
+            }
+            _ => ()
+        }
+    }
+
+    pub fn copy_to_clipboard(&mut self, text: &str) {
+        if self.data_device.is_null() {
+            return
+        }
+        self.clipboard = text.to_string();
+        unsafe {
+            self.display.destroy(self.data_source, WL_DATA_SOURCE_DESTROY);
+            self.data_source = self.display.create(self.display.data_device_manager, WL_DATA_DEVICE_MANAGER_CREATE_DATA_SOURCE, self.display.lib.wl_data_source_interface, &mut [wl_argument::new_id()]);
+            for mime_type in TEXT_MIME_TYPES {
+                let mime_type = CString::new(mime_type).unwrap();
+                self.display.request(self.data_source, WL_DATA_SOURCE_OFFER, &mut [wl_argument::string(mime_type.as_ptr())]);
+            }
+            self.display.request(self.data_device, WL_DATA_DEVICE_SET_SELECTION, &mut [
+                wl_argument::object(self.data_source),
+                wl_argument::uint(self.last_serial)
+            ]);
+        }
+    }
+
+    fn paste(&mut self) {
+        // while we own the selection the compositor would only route it back to us
+        let input = if !self.data_source.is_null() {
+            Some(self.clipboard.clone())
+        }
+        else if let Some(mime_type) = self.offer_mime_type(self.selection_offer, &TEXT_MIME_TYPES) {
+            self.receive_offer(self.selection_offer, mime_type).map( | data | String::from_utf8_lossy(&data).into_owned())
+        }
+        else {
+            None
+        };
+        if let Some(input) = input {
+            if !input.is_empty() {
+                self.do_callback(WaylandEvent::TextInput(TextInputEvent {
+                    input,
+                    was_paste: true,
+                    replace_last: false
+                }));
+            }
+        }
+    }
+
+    fn offer_mime_type(&self, proxy: *mut wl_proxy, mime_types: &[&'static str]) -> Option<&'static str> {
+        let offer = self.data_offers.iter().find( | offer | offer.proxy == proxy)?;
+        mime_types.iter().find( | mime_type | offer.mime_types.iter().any( | offered | offered == *mime_type)).copied()
+    }
+
+    // reads the offered data through a pipe that the sending client writes into
+    fn receive_offer(&self, offer: *mut wl_proxy, mime_type: &str) -> Option<Vec<u8>> {
+        let mut fds: [c_int; 2] = [0; 2];
+        unsafe {
+            if libc_sys::pipe(fds.as_mut_ptr()) != 0 {
+                return None
+            }
+            let read_fd = OwnedFd::from_raw_fd(fds[0]);
+            let write_fd = OwnedFd::from_raw_fd(fds[1]);
+            let mime_type = CString::new(mime_type).ok()?;
+            self.display.request(offer, WL_DATA_OFFER_RECEIVE, &mut [
+                wl_argument::string(mime_type.as_ptr()),
+                wl_argument::fd(write_fd.as_raw_fd())
+            ]);
+            self.display.flush();
+            drop(write_fd);
+            // the sending client may be slow or never close its end, so we only wait so long
+            let deadline = Instant::now() + RECEIVE_OFFER_TIMEOUT;
+            let mut file = File::from(read_fd);
+            let mut data = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let mut pfd = libc_sys::pollfd {fd: file.as_raw_fd(), events: libc_sys::POLLIN, revents: 0};
+                let timeout = deadline.saturating_duration_since(Instant::now()).as_millis() as c_int;
+                match libc_sys::poll(&mut pfd, 1, timeout) {
+                    0 => {
+                        crate::error!("wayland: gave up waiting for {} data from the other client", mime_type.to_string_lossy());
+                        return None
+                    }
+                    n if n < 0 => {
+                        if std::io::Error::last_os_error().kind() != ErrorKind::Interrupted {
+                            return None
+                        }
+                        continue
+                    }
+                    _ => ()
+                }
+                match file.read(&mut buf) {
+                    Ok(0) => return Some(data),
+                    Ok(n) => data.extend_from_slice(&buf[..n]),
+                    Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                    Err(_) => return None
+                }
+            }
+        }
+    }
+
+    fn receive_drag_items(&self, offer: *mut wl_proxy) -> Vec<DragItem> {
+        if self.offer_mime_type(offer, &[URI_LIST_MIME_TYPE]).is_none() {
+            return Vec::new()
+        }
+        let Some(data) = self.receive_offer(offer, URI_LIST_MIME_TYPE) else {return Vec::new()};
+        String::from_utf8_lossy(&data).lines().filter_map( | line | {
+            let line = line.trim();
+            let path = line.strip_prefix("file://")?;
+            // skip the host part, local files either have none or localhost
+            let path = &path[path.find('/')?..];
+            Some(DragItem::FilePath {
+                path: percent_decode(path),
+                internal_id: None
+            })
+        }).collect()
+    }
+
+    fn send_drag(&mut self) {
+        if self.drag_offer.is_null() {
+            return
+        }
+        let accepted = if self.drag_items.is_empty() {
+            false
+        }
+        else {
+            let response = Arc::new(Mutex::new(DragResponse::None));
+            self.do_callback(WaylandEvent::Drag(DragEvent {
+                modifiers: self.modifiers(),
+                handled: Arc::new(Mutex::new(false)),
+                abs: self.drag_pos,
+                items: self.drag_items.clone(),
+                response: response.clone(),
+            }));
+            let response = *response.lock().unwrap();
+            !matches!(response, DragResponse::None)
+        };
+        let mime_type = CString::new(URI_LIST_MIME_TYPE).unwrap();
+        unsafe {
+            self.display.request(self.drag_offer, WL_DATA_OFFER_ACCEPT, &mut [
+                wl_argument::uint(self.drag_serial),
+                wl_argument::string(if accepted {mime_type.as_ptr()} else {ptr::null()})
+            ]);
+            if self.display.version(self.drag_offer) >= 3 {
+                let actions = if accepted {WL_DATA_DEVICE_MANAGER_DND_ACTION_COPY} else {0};
+                self.display.request(self.drag_offer, WL_DATA_OFFER_SET_ACTIONS, &mut [
+                    wl_argument::uint(actions),
+                    wl_argument::uint(actions)
+                ]);
+            }
+        }
+    }
+
+    fn end_drag(&mut self) {
+        if self.drag_offer.is_null() {
+            return
+        }
+        if !self.drag_items.is_empty() {
+            self.do_callback(WaylandEvent::DragEnd);
+        }
+        if self.drag_offer != self.selection_offer {
+            self.destroy_data_offer(self.drag_offer);
+        }
+        self.drag_offer = ptr::null_mut();
+        self.drag_items = Arc::new(Vec::new());
+    }
+
+    fn destroy_data_offer(&mut self, proxy: *mut wl_proxy) {
+        if proxy.is_null() {
+            return
+        }
+        self.data_offers.retain( | offer | offer.proxy != proxy);
+        unsafe {self.display.destroy(proxy, WL_DATA_OFFER_DESTROY)};
+    }
+
+    // text input
+
+    fn handle_text_input_message(&mut self, message: &WaylandMessage) {
+        match message.opcode {
+            ZWP_TEXT_INPUT_V3_ENTER => {
+                self.text_input_window = self.window_id_for_surface(message.object(0));
+                if self.text_input_wanted {
+                    self.show_text_ime(self.ime_pos);
+                }
+            }
+            ZWP_TEXT_INPUT_V3_LEAVE => {
+                // leaving the surface implicitly disables the text input
+                self.text_input_window = None;
+                self.text_input_active = false;
+            }
+            ZWP_TEXT_INPUT_V3_PREEDIT_STRING => {
+                self.pending_preedit = message.string(0).map( | preedit | preedit.to_string());
+            }
+            ZWP_TEXT_INPUT_V3_COMMIT_STRING => {
+                self.pending_commit = message.string(0).map( | commit | commit.to_string());
+            }
+            ZWP_TEXT_INPUT_V3_DONE => {
+                self.apply_text_input();
+            }
+            _ => ()
+        }
+    }
+
+    // the preedit text is shown inline and replaced by the next preedit or the committed text
+    fn apply_text_input(&mut self) {
+        let preedit = self.pending_preedit.take().unwrap_or_default();
+        if let Some(input) = self.pending_commit.take() {
+            let replace_last = std::mem::take(&mut self.preedit_shown);
+            self.do_callback(WaylandEvent::TextInput(TextInputEvent {
+                input,
+                was_paste: false,
+                replace_last
+            }));
+        }
+        if !preedit.is_empty() || self.preedit_shown {
+            let replace_last = self.preedit_shown;
+            self.preedit_shown = !preedit.is_empty();
+            self.do_callback(WaylandEvent::TextInput(TextInputEvent {
+                input: preedit,
+                was_paste: false,
+                replace_last
+            }));
+        }
+    }
+
+    pub fn show_text_ime(&mut self, pos: DVec2) {
+        self.text_input_wanted = true;
+        self.ime_pos = pos;
+        if self.text_input.is_null() || self.text_input_window.is_none() {
+            return
+        }
+        unsafe {
+            if !self.text_input_active {
+                self.text_input_active = true;
+                self.display.request(self.text_input, ZWP_TEXT_INPUT_V3_ENABLE, &mut []);
+            }
+            self.display.request(self.text_input, ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE, &mut [
+                wl_argument::int(pos.x as i32),
+                wl_argument::int(pos.y as i32),
+                wl_argument::int(1),
+                wl_argument::int(16)
+            ]);
+            self.display.request(self.text_input, ZWP_TEXT_INPUT_V3_COMMIT, &mut []);
+        }
+    }
+
+    pub fn hide_text_ime(&mut self) {
+        self.text_input_wanted = false;
+        if self.text_input_active {
+            self.text_input_active = false;
+            unsafe {
+                self.display.request(self.text_input, ZWP_TEXT_INPUT_V3_DISABLE, &mut []);
+                self.display.request(self.text_input, ZWP_TEXT_INPUT_V3_COMMIT, &mut []);
+            }
+        }
+    }
+
+    // windows
+
+    fn handle_window_message(&mut self, index: usize, message: &WaylandMessage) {
+        let window = &mut self.windows[index];
+        let proxy = message.proxy;
+        if proxy == window.frame_callback {
+            window.frame_done(&self.display);
+        }
+        else if proxy == window.surface {
+            match message.opcode {
+                WL_SURFACE_ENTER => window.outputs.push(message.object(0)),
+                WL_SURFACE_LEAVE => {
+                    let output = message.object(0);
+                    window.outputs.retain( | proxy | *proxy != output);
+                }
+                WL_SURFACE_PREFERRED_BUFFER_SCALE => window.preferred_buffer_scale = Some(message.int(0)),
+                _ => return
+            }
+            self.update_window_geom(index);
+        }
+        else if proxy == window.fractional_scale {
+            if message.opcode == WP_FRACTIONAL_SCALE_V1_PREFERRED_SCALE {
+                window.preferred_fractional_scale = Some(message.uint(0) as f64 / 120.0);
+                self.update_window_geom(index);
+            }
+        }
+        else if proxy == window.xdg_toplevel {
+            match message.opcode {
+                XDG_TOPLEVEL_CONFIGURE => {
+                    let (width, height) = (message.int(0), message.int(1));
+                    // a zero size leaves the size up to us
+                    window.pending_size = if width > 0 && height > 0 {
+                        Some(dvec2(width as f64, height as f64))
+                    } else {
+                        None
+                    };
+                    let states: Vec<u32> = message.array(2).chunks_exact(4).map( | state | u32::from_ne_bytes([state[0], state[1], state[2], state[3]])).collect();
+                    window.pending_maximized = states.contains(&XDG_TOPLEVEL_STATE_MAXIMIZED);
+                    window.pending_fullscreen = states.contains(&XDG_TOPLEVEL_STATE_FULLSCREEN);
+                }
+                XDG_TOPLEVEL_CLOSE => {
+                    let window_id = window.window_id;
+                    let accept_close = Rc::new(Cell::new(true));
+                    self.do_callback(WaylandEvent::WindowCloseRequested(WindowCloseRequestedEvent {
+                        window_id,
+                        accept_close: accept_close.clone()
+                    }));
+                    if accept_close.get() {
+                        self.do_callback(WaylandEvent::WindowClosed(WindowClosedEvent {
+                            window_id
+                        }));
+                    }
+                }
+                _ => ()
+            }
+        }
+        else if proxy == window.xdg_surface {
+            if message.opcode == XDG_SURFACE_CONFIGURE {
+                unsafe {self.display.request(window.xdg_surface, XDG_SURFACE_ACK_CONFIGURE, &mut [wl_argument::uint(message.uint(0))])};
+                window.is_maximized = window.pending_maximized;
+                window.is_fullscreen = window.pending_fullscreen;
+                window.is_configured = true;
+                self.update_window_geom(index);
+            }
+        }
+    }
+
+    fn update_all_window_geoms(&mut self) {
+        for index in 0..self.windows.len() {
+            self.update_window_geom(index);
+        }
+    }
+
+    // windows only get a size once the compositor configured them, geometry changes before
+    // that are picked up by the first configure
+    fn update_window_geom(&mut self, index: usize) {
+        let window = &mut self.windows[index];
+        if !window.is_configured {
+            return
+        }
+        let new_geom = window.get_window_geom(&self.display.outputs);
+        window.pending_size = None;
+        let old_geom = std::mem::replace(&mut window.window_geom, new_geom.clone());
+        window.resize_buffers(&self.display);
+        if old_geom != new_geom {
+            let window_id = window.window_id;
+            self.do_callback(WaylandEvent::WindowGeomChange(WindowGeomChangeEvent {
+                window_id,
+                old_geom,
+                new_geom
+            }));
+        }
+    }
+}
+
+fn sign(value: f64) -> f64 {
+    if value > 0.0 {1.0} else if value < 0.0 {-1.0} else {0.0}
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then( | hex | u8::from_str_radix(hex, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// the cursor shape protocol value, and the xcursor names for the wl_cursor fallback
+fn cursor_shape(cursor: MouseCursor) -> (u32, &'static [&'static str]) {
+    match cursor {
+        MouseCursor::Hidden | MouseCursor::Default | MouseCursor::Arrow => (WP_CURSOR_SHAPE_DEFAULT, &["default", "left_ptr"]),
+        MouseCursor::Crosshair => (WP_CURSOR_SHAPE_CROSSHAIR, &["crosshair"]),
+        MouseCursor::Hand => (WP_CURSOR_SHAPE_POINTER, &["pointer", "hand2", "hand1"]),
+        MouseCursor::Move => (WP_CURSOR_SHAPE_MOVE, &["move", "fleur"]),
+        MouseCursor::NotAllowed => (WP_CURSOR_SHAPE_NOT_ALLOWED, &["not-allowed", "crossed_circle"]),
+        MouseCursor::Text => (WP_CURSOR_SHAPE_TEXT, &["text", "xterm"]),
+        MouseCursor::Wait => (WP_CURSOR_SHAPE_WAIT, &["wait", "watch"]),
+        MouseCursor::Help => (WP_CURSOR_SHAPE_HELP, &["help", "question_arrow"]),
+        MouseCursor::NResize => (WP_CURSOR_SHAPE_N_RESIZE, &["n-resize", "top_side"]),
+        MouseCursor::NeResize => (WP_CURSOR_SHAPE_NE_RESIZE, &["ne-resize", "top_right_corner"]),
+        MouseCursor::EResize => (WP_CURSOR_SHAPE_E_RESIZE, &["e-resize", "right_side"]),
+        MouseCursor::SeResize => (WP_CURSOR_SHAPE_SE_RESIZE, &["se-resize", "bottom_right_corner"]),
+        MouseCursor::SResize => (WP_CURSOR_SHAPE_S_RESIZE, &["s-resize", "bottom_side"]),
+        MouseCursor::SwResize => (WP_CURSOR_SHAPE_SW_RESIZE, &["sw-resize", "bottom_left_corner"]),
+        MouseCursor::WResize => (WP_CURSOR_SHAPE_W_RESIZE, &["w-resize", "left_side"]),
+        MouseCursor::NwResize => (WP_CURSOR_SHAPE_NW_RESIZE, &["nw-resize", "top_left_corner"]),
+        MouseCursor::NsResize => (WP_CURSOR_SHAPE_NS_RESIZE, &["ns-resize", "v_double_arrow"]),
+        MouseCursor::NeswResize => (WP_CURSOR_SHAPE_NESW_RESIZE, &["nesw-resize", "fd_double_arrow"]),
+        MouseCursor::EwResize => (WP_CURSOR_SHAPE_EW_RESIZE, &["ew-resize", "h_double_arrow"]),
+        MouseCursor::NwseResize => (WP_CURSOR_SHAPE_NWSE_RESIZE, &["nwse-resize", "bd_double_arrow"]),
+        MouseCursor::ColResize => (WP_CURSOR_SHAPE_COL_RESIZE, &["col-resize", "split_h"]),
+        MouseCursor::RowResize => (WP_CURSOR_SHAPE_ROW_RESIZE, &["row-resize", "split_v"]),
+    }
+}
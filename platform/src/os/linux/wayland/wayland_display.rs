@@ -0,0 +1,361 @@
+use {
+    std::{
+        cell::RefCell,
+        ffi::CStr,
+        os::{
+            fd::{FromRawFd, OwnedFd},
+            raw::{c_int, c_void},
+        },
+        ptr,
+    },
+    self::super::{
+        wayland_sys::*,
+        wayland_protocols::*,
+    },
+    self::super::super::libc_sys,
+};
+
+// Events are copied out of libwayland into owned messages and queued, so they can be handled
+// by the app after dispatching with full mutable access to its state.
+pub enum WaylandArg {
+    Int(i32),
+    Uint(u32),
+    Fixed(f64),
+    Str(Option<String>),
+    Object(*mut wl_proxy),
+    NewId(*mut wl_proxy),
+    Array(Vec<u8>),
+    Fd(OwnedFd),
+}
+
+pub struct WaylandMessage {
+    pub proxy: *mut wl_proxy,
+    pub opcode: u32,
+    pub args: Vec<WaylandArg>,
+}
+
+impl WaylandMessage {
+    pub fn int(&self, index: usize) -> i32 {
+        if let Some(WaylandArg::Int(v)) = self.args.get(index) {*v} else {0}
+    }
+
+    pub fn uint(&self, index: usize) -> u32 {
+        if let Some(WaylandArg::Uint(v)) = self.args.get(index) {*v} else {0}
+    }
+
+    pub fn fixed(&self, index: usize) -> f64 {
+        if let Some(WaylandArg::Fixed(v)) = self.args.get(index) {*v} else {0.0}
+    }
+
+    pub fn string(&self, index: usize) -> Option<&str> {
+        if let Some(WaylandArg::Str(v)) = self.args.get(index) {v.as_deref()} else {None}
+    }
+
+    pub fn object(&self, index: usize) -> *mut wl_proxy {
+        match self.args.get(index) {
+            Some(WaylandArg::Object(v)) | Some(WaylandArg::NewId(v)) => *v,
+            _ => ptr::null_mut()
+        }
+    }
+
+    pub fn array(&self, index: usize) -> &[u8] {
+        if let Some(WaylandArg::Array(v)) = self.args.get(index) {v} else {&[]}
+    }
+
+    pub fn take_fd(&mut self, index: usize) -> Option<OwnedFd> {
+        match self.args.get_mut(index).map( | arg | std::mem::replace(arg, WaylandArg::Int(-1))) {
+            Some(WaylandArg::Fd(fd)) => Some(fd),
+            _ => None
+        }
+    }
+}
+
+struct DispatchQueue {
+    add_dispatcher: unsafe extern "C" fn(*mut wl_proxy, wl_dispatcher_func_t, *const c_void, *mut c_void) -> c_int,
+    messages: RefCell<Vec<WaylandMessage >>,
+}
+
+unsafe extern "C" fn dispatch_to_queue(
+    dispatcher_data: *const c_void,
+    target: *mut c_void,
+    opcode: u32,
+    msg: *const wl_message,
+    args: *mut wl_argument,
+) -> c_int {
+    let queue = &*(dispatcher_data as *const DispatchQueue);
+    let signature = CStr::from_ptr((*msg).signature).to_bytes();
+    let mut out = Vec::new();
+    let mut index = 0;
+    for c in signature {
+        let arg = *args.add(index);
+        out.push(match c {
+            b'i' => WaylandArg::Int(arg.i),
+            b'u' => WaylandArg::Uint(arg.u),
+            b'f' => WaylandArg::Fixed(wl_fixed_to_f64(arg.f)),
+            b's' => WaylandArg::Str(if arg.s.is_null() {None} else {
+                Some(CStr::from_ptr(arg.s).to_string_lossy().into_owned())
+            }),
+            b'o' => WaylandArg::Object(arg.o),
+            b'n' => {
+                // objects created by the server (data offers) need to be listened to right away
+                if !arg.o.is_null() {
+                    (queue.add_dispatcher)(arg.o, dispatch_to_queue, dispatcher_data, ptr::null_mut());
+                }
+                WaylandArg::NewId(arg.o)
+            }
+            b'a' => WaylandArg::Array(if arg.a.is_null() || (*arg.a).size == 0 {Vec::new()} else {
+                std::slice::from_raw_parts((*arg.a).data as *const u8, (*arg.a).size).to_vec()
+            }),
+            b'h' => WaylandArg::Fd(OwnedFd::from_raw_fd(arg.h)),
+            // the version prefix and nullability markers
+            _ => continue
+        });
+        index += 1;
+    }
+    queue.messages.borrow_mut().push(WaylandMessage {
+        proxy: target as *mut wl_proxy,
+        opcode,
+        args: out
+    });
+    0
+}
+
+pub struct WaylandOutput {
+    pub proxy: *mut wl_proxy,
+    pub name: u32,
+    pub scale: i32,
+}
+
+pub struct WaylandDisplay {
+    pub lib: LibWaylandClient,
+    pub lib_egl: LibWaylandEgl,
+    pub display: *mut wl_display,
+    pub fd: c_int,
+    pub registry: *mut wl_proxy,
+    pub compositor: *mut wl_proxy,
+    pub shm: *mut wl_proxy,
+    pub seat: *mut wl_proxy,
+    pub data_device_manager: *mut wl_proxy,
+    pub xdg_wm_base: *mut wl_proxy,
+    pub decoration_manager: *mut wl_proxy,
+    pub fractional_scale_manager: *mut wl_proxy,
+    pub viewporter: *mut wl_proxy,
+    pub text_input_manager: *mut wl_proxy,
+    pub cursor_shape_manager: *mut wl_proxy,
+    pub outputs: Vec<WaylandOutput>,
+    queue: Box<DispatchQueue>,
+}
+
+impl WaylandDisplay {
+    // name is like WAYLAND_DISPLAY, a socket in XDG_RUNTIME_DIR or an absolute path,
+    // None uses the environment
+    pub fn connect(name: Option<&CStr>) -> Option<WaylandDisplay> {
+        let lib = LibWaylandClient::try_load()?;
+        let lib_egl = LibWaylandEgl::try_load()?;
+        unsafe {
+            let display = (lib.wl_display_connect)(name.map_or(ptr::null(), | name | name.as_ptr()));
+            if display.is_null() {
+                return None
+            }
+            let queue = Box::new(DispatchQueue {
+                add_dispatcher: lib.wl_proxy_add_dispatcher,
+                messages: RefCell::new(Vec::new()),
+            });
+            let mut wd = WaylandDisplay {
+                fd: (lib.wl_display_get_fd)(display),
+                lib,
+                lib_egl,
+                display,
+                registry: ptr::null_mut(),
+                compositor: ptr::null_mut(),
+                shm: ptr::null_mut(),
+                seat: ptr::null_mut(),
+                data_device_manager: ptr::null_mut(),
+                xdg_wm_base: ptr::null_mut(),
+                decoration_manager: ptr::null_mut(),
+                fractional_scale_manager: ptr::null_mut(),
+                viewporter: ptr::null_mut(),
+                text_input_manager: ptr::null_mut(),
+                cursor_shape_manager: ptr::null_mut(),
+                outputs: Vec::new(),
+                queue,
+            };
+            wd.registry = wd.create(display, WL_DISPLAY_GET_REGISTRY, wd.lib.wl_registry_interface, &mut [wl_argument::new_id()]);
+            (wd.lib.wl_display_roundtrip)(display);
+            for message in wd.take_messages() {
+                wd.handle_registry_message(&message);
+            }
+            if wd.compositor.is_null() || wd.xdg_wm_base.is_null() {
+                (wd.lib.wl_display_disconnect)(display);
+                return None
+            }
+            // collects the seat capabilities and output scales, these stay queued for the app
+            (wd.lib.wl_display_roundtrip)(display);
+            Some(wd)
+        }
+    }
+
+    pub fn handle_registry_message(&mut self, message: &WaylandMessage) {
+        match message.opcode {
+            WL_REGISTRY_GLOBAL => {
+                let name = message.uint(0);
+                let version = message.uint(2);
+                unsafe {
+                    match message.string(1).unwrap_or("") {
+                        "wl_compositor" if self.compositor.is_null() => {
+                            self.compositor = self.bind(name, self.lib.wl_compositor_interface, version, 6);
+                        }
+                        "wl_shm" if self.shm.is_null() => {
+                            self.shm = self.bind(name, self.lib.wl_shm_interface, version, 1);
+                        }
+                        "wl_seat" if self.seat.is_null() => {
+                            self.seat = self.bind(name, self.lib.wl_seat_interface, version, 7);
+                        }
+                        "wl_output" => {
+                            let proxy = self.bind(name, self.lib.wl_output_interface, version, 2);
+                            self.outputs.push(WaylandOutput {proxy, name, scale: 1});
+                        }
+                        "wl_data_device_manager" if self.data_device_manager.is_null() => {
+                            self.data_device_manager = self.bind(name, self.lib.wl_data_device_manager_interface, version, 3);
+                        }
+                        "xdg_wm_base" if self.xdg_wm_base.is_null() => {
+                            self.xdg_wm_base = self.bind(name, &xdg_wm_base_interface, version, 2);
+                        }
+                        "zxdg_decoration_manager_v1" if self.decoration_manager.is_null() => {
+                            self.decoration_manager = self.bind(name, &zxdg_decoration_manager_v1_interface, version, 1);
+                        }
+                        "wp_fractional_scale_manager_v1" if self.fractional_scale_manager.is_null() => {
+                            self.fractional_scale_manager = self.bind(name, &wp_fractional_scale_manager_v1_interface, version, 1);
+                        }
+                        "wp_viewporter" if self.viewporter.is_null() => {
+                            self.viewporter = self.bind(name, &wp_viewporter_interface, version, 1);
+                        }
+                        "zwp_text_input_manager_v3" if self.text_input_manager.is_null() => {
+                            self.text_input_manager = self.bind(name, &zwp_text_input_manager_v3_interface, version, 1);
+                        }
+                        "wp_cursor_shape_manager_v1" if self.cursor_shape_manager.is_null() => {
+                            self.cursor_shape_manager = self.bind(name, &wp_cursor_shape_manager_v1_interface, version, 1);
+                        }
+                        _ => ()
+                    }
+                }
+            }
+            WL_REGISTRY_GLOBAL_REMOVE => {
+                let name = message.uint(0);
+                if let Some(index) = self.outputs.iter().position( | output | output.name == name) {
+                    let output = self.outputs.remove(index);
+                    unsafe {(self.lib.wl_proxy_destroy)(output.proxy)};
+                }
+            }
+            _ => ()
+        }
+    }
+
+    pub fn take_messages(&self) -> Vec<WaylandMessage> {
+        std::mem::take(&mut *self.queue.messages.borrow_mut())
+    }
+
+    // binds a global at the highest version both sides and we support
+    unsafe fn bind(&self, name: u32, interface: *const wl_interface, version: u32, max_version: u32) -> *mut wl_proxy {
+        let version = version.min(max_version).min((*interface).version as u32);
+        let proxy = (self.lib.wl_proxy_marshal_array_flags)(
+            self.registry,
+            WL_REGISTRY_BIND,
+            interface,
+            version,
+            0,
+            [
+                wl_argument::uint(name),
+                wl_argument::string((*interface).name),
+                wl_argument::uint(version),
+                wl_argument::new_id()
+            ].as_mut_ptr()
+        );
+        self.listen(proxy);
+        proxy
+    }
+
+    fn listen(&self, proxy: *mut wl_proxy) {
+        if !proxy.is_null() {
+            unsafe {
+                (self.lib.wl_proxy_add_dispatcher)(
+                    proxy,
+                    dispatch_to_queue,
+                    &*self.queue as *const DispatchQueue as *const c_void,
+                    ptr::null_mut()
+                );
+            }
+        }
+    }
+
+    pub unsafe fn version(&self, proxy: *mut wl_proxy) -> u32 {
+        (self.lib.wl_proxy_get_version)(proxy)
+    }
+
+    // sends a request which creates a new object, of the same version as its parent
+    pub unsafe fn create(&self, proxy: *mut wl_proxy, opcode: u32, interface: *const wl_interface, args: &mut [wl_argument]) -> *mut wl_proxy {
+        let new_proxy = (self.lib.wl_proxy_marshal_array_flags)(
+            proxy,
+            opcode,
+            interface,
+            self.version(proxy),
+            0,
+            args.as_mut_ptr()
+        );
+        self.listen(new_proxy);
+        new_proxy
+    }
+
+    pub unsafe fn request(&self, proxy: *mut wl_proxy, opcode: u32, args: &mut [wl_argument]) {
+        if !proxy.is_null() {
+            (self.lib.wl_proxy_marshal_array_flags)(proxy, opcode, ptr::null(), self.version(proxy), 0, args.as_mut_ptr());
+        }
+    }
+
+    // sends the destructor request of the object and frees the proxy
+    pub unsafe fn destroy(&self, proxy: *mut wl_proxy, opcode: u32) {
+        if !proxy.is_null() {
+            (self.lib.wl_proxy_marshal_array_flags)(proxy, opcode, ptr::null(), self.version(proxy), WL_MARSHAL_FLAG_DESTROY, [wl_argument::new_id()].as_mut_ptr());
+        }
+    }
+
+    // frees a proxy for objects without a destructor request, like wl_callback
+    pub unsafe fn destroy_proxy(&self, proxy: *mut wl_proxy) {
+        if !proxy.is_null() {
+            (self.lib.wl_proxy_destroy)(proxy);
+        }
+    }
+
+    pub fn flush(&self) {
+        unsafe {(self.lib.wl_display_flush)(self.display);}
+    }
+
+    pub fn roundtrip(&self) {
+        unsafe {(self.lib.wl_display_roundtrip)(self.display);}
+    }
+
+    // reads and dispatches events, waiting up to timeout seconds (forever if None) for them to arrive.
+    // returns false when the connection to the compositor is gone
+    pub fn wait_for_events(&self, timeout: Option<f64>) -> bool {
+        unsafe {
+            while (self.lib.wl_display_prepare_read)(self.display) != 0 {
+                (self.lib.wl_display_dispatch_pending)(self.display);
+            }
+            (self.lib.wl_display_flush)(self.display);
+            let mut pollfd = libc_sys::pollfd {
+                fd: self.fd,
+                events: libc_sys::POLLIN,
+                revents: 0
+            };
+            let timeout_ms = timeout.map( | timeout | (timeout * 1000.0).ceil() as c_int).unwrap_or(-1);
+            if libc_sys::poll(&mut pollfd, 1, timeout_ms) > 0 {
+                (self.lib.wl_display_read_events)(self.display);
+            }
+            else {
+                (self.lib.wl_display_cancel_read)(self.display);
+            }
+            (self.lib.wl_display_dispatch_pending)(self.display);
+            (self.lib.wl_display_get_error)(self.display) == 0
+        }
+    }
+}
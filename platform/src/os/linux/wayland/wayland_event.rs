@@ -0,0 +1,50 @@
+use {
+    crate::{
+        event::{
+            MouseDownEvent,
+            MouseUpEvent,
+            MouseMoveEvent,
+            MouseLeaveEvent,
+            ScrollEvent,
+            TouchUpdateEvent,
+            WindowGeomChangeEvent,
+            WindowDragQueryEvent,
+            WindowCloseRequestedEvent,
+            WindowClosedEvent,
+            TextInputEvent,
+            KeyEvent,
+            DragEvent,
+            DropEvent,
+            TextClipboardEvent,
+            TimerEvent,
+        },
+    }
+};
+
+#[derive(Debug)]
+pub enum WaylandEvent {
+    AppGotFocus,
+    AppLostFocus,
+    WindowGeomChange(WindowGeomChangeEvent),
+    WindowClosed(WindowClosedEvent),
+    Paint,
+
+    MouseDown(MouseDownEvent),
+    MouseUp(MouseUpEvent),
+    MouseMove(MouseMoveEvent),
+    MouseLeave(MouseLeaveEvent),
+    Scroll(ScrollEvent),
+    TouchUpdate(TouchUpdateEvent),
+
+    WindowDragQuery(WindowDragQueryEvent),
+    WindowCloseRequested(WindowCloseRequestedEvent),
+    TextInput(TextInputEvent),
+    Drag(DragEvent),
+    Drop(DropEvent),
+    DragEnd,
+    KeyDown(KeyEvent),
+    KeyUp(KeyEvent),
+    TextCopy(TextClipboardEvent),
+    TextCut(TextClipboardEvent),
+    Timer(TimerEvent),
+}
@@ -0,0 +1,313 @@
+#![allow(non_upper_case_globals, dead_code)]
+
+use {
+    std::os::raw::c_char,
+    self::super::wayland_sys::{wl_interface, wl_message},
+};
+
+// The core protocol interfaces come from libwayland-client itself, the stable and staging
+// extensions we use are described here. Argument types are left untyped (null), which
+// libwayland accepts, since we pass the interface of every new object explicitly.
+
+#[repr(transparent)]
+struct NullTypes([*const wl_interface; 8]);
+unsafe impl Sync for NullTypes {}
+
+static NULL_TYPES: NullTypes = NullTypes([std::ptr::null(); 8]);
+
+macro_rules!wl_interface {
+    ($iface: ident, $name: literal, $version: literal,
+    requests: [$($req: literal $req_sig: literal), *],
+    events: [$($ev: literal $ev_sig: literal), *]) => {
+        pub static $iface: wl_interface = {
+            const REQUEST_COUNT: usize = <[&str]>::len(&[$($req), *]);
+            const EVENT_COUNT: usize = <[&str]>::len(&[$($ev), *]);
+            static REQUESTS: [wl_message; REQUEST_COUNT] = [$(wl_message {
+                name: concat!($req, "\0").as_ptr() as *const c_char,
+                signature: concat!($req_sig, "\0").as_ptr() as *const c_char,
+                types: &NULL_TYPES as *const NullTypes as *const *const wl_interface,
+            }), *];
+            static EVENTS: [wl_message; EVENT_COUNT] = [$(wl_message {
+                name: concat!($ev, "\0").as_ptr() as *const c_char,
+                signature: concat!($ev_sig, "\0").as_ptr() as *const c_char,
+                types: &NULL_TYPES as *const NullTypes as *const *const wl_interface,
+            }), *];
+            wl_interface {
+                name: concat!($name, "\0").as_ptr() as *const c_char,
+                version: $version,
+                method_count: REQUEST_COUNT as i32,
+                methods: &REQUESTS as *const [wl_message; REQUEST_COUNT] as *const wl_message,
+                event_count: EVENT_COUNT as i32,
+                events: &EVENTS as *const [wl_message; EVENT_COUNT] as *const wl_message,
+            }
+        };
+    }
+}
+
+wl_interface!(xdg_wm_base_interface, "xdg_wm_base", 2,
+    requests: ["destroy" "", "create_positioner" "n", "get_xdg_surface" "no", "pong" "u"],
+    events: ["ping" "u"]
+);
+
+wl_interface!(xdg_surface_interface, "xdg_surface", 2,
+    requests: ["destroy" "", "get_toplevel" "n", "get_popup" "n?oo", "set_window_geometry" "iiii", "ack_configure" "u"],
+    events: ["configure" "u"]
+);
+
+wl_interface!(xdg_toplevel_interface, "xdg_toplevel", 2,
+    requests: [
+        "destroy" "",
+        "set_parent" "?o",
+        "set_title" "s",
+        "set_app_id" "s",
+        "show_window_menu" "ouii",
+        "move" "ou",
+        "resize" "ouu",
+        "set_max_size" "ii",
+        "set_min_size" "ii",
+        "set_maximized" "",
+        "unset_maximized" "",
+        "set_fullscreen" "?o",
+        "unset_fullscreen" "",
+        "set_minimized" ""
+    ],
+    events: ["configure" "iia", "close" ""]
+);
+
+wl_interface!(zxdg_decoration_manager_v1_interface, "zxdg_decoration_manager_v1", 1,
+    requests: ["destroy" "", "get_toplevel_decoration" "no"],
+    events: []
+);
+
+wl_interface!(zxdg_toplevel_decoration_v1_interface, "zxdg_toplevel_decoration_v1", 1,
+    requests: ["destroy" "", "set_mode" "u", "unset_mode" ""],
+    events: ["configure" "u"]
+);
+
+wl_interface!(wp_fractional_scale_manager_v1_interface, "wp_fractional_scale_manager_v1", 1,
+    requests: ["destroy" "", "get_fractional_scale" "no"],
+    events: []
+);
+
+wl_interface!(wp_fractional_scale_v1_interface, "wp_fractional_scale_v1", 1,
+    requests: ["destroy" ""],
+    events: ["preferred_scale" "u"]
+);
+
+wl_interface!(wp_viewporter_interface, "wp_viewporter", 1,
+    requests: ["destroy" "", "get_viewport" "no"],
+    events: []
+);
+
+wl_interface!(wp_viewport_interface, "wp_viewport", 1,
+    requests: ["destroy" "", "set_source" "ffff", "set_destination" "ii"],
+    events: []
+);
+
+wl_interface!(zwp_text_input_manager_v3_interface, "zwp_text_input_manager_v3", 1,
+    requests: ["destroy" "", "get_text_input" "no"],
+    events: []
+);
+
+wl_interface!(zwp_text_input_v3_interface, "zwp_text_input_v3", 1,
+    requests: [
+        "destroy" "",
+        "enable" "",
+        "disable" "",
+        "set_surrounding_text" "sii",
+        "set_text_change_cause" "u",
+        "set_content_type" "uu",
+        "set_cursor_rectangle" "iiii",
+        "commit" ""
+    ],
+    events: [
+        "enter" "o",
+        "leave" "o",
+        "preedit_string" "?sii",
+        "commit_string" "?s",
+        "delete_surrounding_text" "uu",
+        "done" "u"
+    ]
+);
+
+wl_interface!(wp_cursor_shape_manager_v1_interface, "wp_cursor_shape_manager_v1", 1,
+    requests: ["destroy" "", "get_pointer" "no", "get_tablet_tool_v2" "no"],
+    events: []
+);
+
+wl_interface!(wp_cursor_shape_device_v1_interface, "wp_cursor_shape_device_v1", 1,
+    requests: ["destroy" "", "set_shape" "uu"],
+    events: []
+);
+
+// core protocol opcodes
+pub const WL_DISPLAY_GET_REGISTRY: u32 = 1;
+
+pub const WL_REGISTRY_BIND: u32 = 0;
+pub const WL_REGISTRY_GLOBAL: u32 = 0;
+pub const WL_REGISTRY_GLOBAL_REMOVE: u32 = 1;
+
+pub const WL_COMPOSITOR_CREATE_SURFACE: u32 = 0;
+pub const WL_COMPOSITOR_CREATE_REGION: u32 = 1;
+
+pub const WL_REGION_DESTROY: u32 = 0;
+pub const WL_REGION_ADD: u32 = 1;
+
+pub const WL_SURFACE_DESTROY: u32 = 0;
+pub const WL_SURFACE_ATTACH: u32 = 1;
+pub const WL_SURFACE_DAMAGE: u32 = 2;
+pub const WL_SURFACE_FRAME: u32 = 3;
+pub const WL_SURFACE_SET_OPAQUE_REGION: u32 = 4;
+pub const WL_SURFACE_COMMIT: u32 = 6;
+pub const WL_SURFACE_SET_BUFFER_SCALE: u32 = 8;
+pub const WL_SURFACE_ENTER: u32 = 0;
+pub const WL_SURFACE_LEAVE: u32 = 1;
+pub const WL_SURFACE_PREFERRED_BUFFER_SCALE: u32 = 2;
+
+pub const WL_SEAT_GET_POINTER: u32 = 0;
+pub const WL_SEAT_GET_KEYBOARD: u32 = 1;
+pub const WL_SEAT_GET_TOUCH: u32 = 2;
+pub const WL_SEAT_RELEASE: u32 = 3;
+pub const WL_SEAT_CAPABILITIES: u32 = 0;
+
+pub const WL_POINTER_SET_CURSOR: u32 = 0;
+pub const WL_POINTER_RELEASE: u32 = 1;
+pub const WL_POINTER_ENTER: u32 = 0;
+pub const WL_POINTER_LEAVE: u32 = 1;
+pub const WL_POINTER_MOTION: u32 = 2;
+pub const WL_POINTER_BUTTON: u32 = 3;
+pub const WL_POINTER_AXIS: u32 = 4;
+pub const WL_POINTER_FRAME: u32 = 5;
+pub const WL_POINTER_AXIS_SOURCE: u32 = 6;
+pub const WL_POINTER_AXIS_STOP: u32 = 7;
+pub const WL_POINTER_AXIS_DISCRETE: u32 = 8;
+pub const WL_POINTER_AXIS_VALUE120: u32 = 9;
+
+pub const WL_KEYBOARD_RELEASE: u32 = 0;
+pub const WL_KEYBOARD_KEYMAP: u32 = 0;
+pub const WL_KEYBOARD_ENTER: u32 = 1;
+pub const WL_KEYBOARD_LEAVE: u32 = 2;
+pub const WL_KEYBOARD_KEY: u32 = 3;
+pub const WL_KEYBOARD_MODIFIERS: u32 = 4;
+pub const WL_KEYBOARD_REPEAT_INFO: u32 = 5;
+
+pub const WL_TOUCH_RELEASE: u32 = 0;
+pub const WL_TOUCH_DOWN: u32 = 0;
+pub const WL_TOUCH_UP: u32 = 1;
+pub const WL_TOUCH_MOTION: u32 = 2;
+pub const WL_TOUCH_FRAME: u32 = 3;
+pub const WL_TOUCH_CANCEL: u32 = 4;
+
+pub const WL_OUTPUT_SCALE: u32 = 3;
+
+pub const WL_DATA_DEVICE_MANAGER_CREATE_DATA_SOURCE: u32 = 0;
+pub const WL_DATA_DEVICE_MANAGER_GET_DATA_DEVICE: u32 = 1;
+
+pub const WL_DATA_DEVICE_SET_SELECTION: u32 = 1;
+pub const WL_DATA_DEVICE_DATA_OFFER: u32 = 0;
+pub const WL_DATA_DEVICE_ENTER: u32 = 1;
+pub const WL_DATA_DEVICE_LEAVE: u32 = 2;
+pub const WL_DATA_DEVICE_MOTION: u32 = 3;
+pub const WL_DATA_DEVICE_DROP: u32 = 4;
+pub const WL_DATA_DEVICE_SELECTION: u32 = 5;
+
+pub const WL_DATA_OFFER_ACCEPT: u32 = 0;
+pub const WL_DATA_OFFER_RECEIVE: u32 = 1;
+pub const WL_DATA_OFFER_DESTROY: u32 = 2;
+pub const WL_DATA_OFFER_FINISH: u32 = 3;
+pub const WL_DATA_OFFER_SET_ACTIONS: u32 = 4;
+pub const WL_DATA_OFFER_OFFER: u32 = 0;
+
+pub const WL_DATA_SOURCE_OFFER: u32 = 0;
+pub const WL_DATA_SOURCE_DESTROY: u32 = 1;
+pub const WL_DATA_SOURCE_SEND: u32 = 1;
+pub const WL_DATA_SOURCE_CANCELLED: u32 = 2;
+
+// extension protocol opcodes
+pub const XDG_WM_BASE_DESTROY: u32 = 0;
+pub const XDG_WM_BASE_GET_XDG_SURFACE: u32 = 2;
+pub const XDG_WM_BASE_PONG: u32 = 3;
+pub const XDG_WM_BASE_PING: u32 = 0;
+
+pub const XDG_SURFACE_DESTROY: u32 = 0;
+pub const XDG_SURFACE_GET_TOPLEVEL: u32 = 1;
+pub const XDG_SURFACE_ACK_CONFIGURE: u32 = 4;
+pub const XDG_SURFACE_CONFIGURE: u32 = 0;
+
+pub const XDG_TOPLEVEL_DESTROY: u32 = 0;
+pub const XDG_TOPLEVEL_SET_TITLE: u32 = 2;
+pub const XDG_TOPLEVEL_SET_APP_ID: u32 = 3;
+pub const XDG_TOPLEVEL_MOVE: u32 = 5;
+pub const XDG_TOPLEVEL_RESIZE: u32 = 6;
+pub const XDG_TOPLEVEL_SET_MAXIMIZED: u32 = 9;
+pub const XDG_TOPLEVEL_UNSET_MAXIMIZED: u32 = 10;
+pub const XDG_TOPLEVEL_SET_FULLSCREEN: u32 = 11;
+pub const XDG_TOPLEVEL_UNSET_FULLSCREEN: u32 = 12;
+pub const XDG_TOPLEVEL_SET_MINIMIZED: u32 = 13;
+pub const XDG_TOPLEVEL_CONFIGURE: u32 = 0;
+pub const XDG_TOPLEVEL_CLOSE: u32 = 1;
+
+pub const XDG_TOPLEVEL_STATE_MAXIMIZED: u32 = 1;
+pub const XDG_TOPLEVEL_STATE_FULLSCREEN: u32 = 2;
+pub const XDG_TOPLEVEL_STATE_ACTIVATED: u32 = 4;
+
+pub const XDG_TOPLEVEL_RESIZE_EDGE_TOP: u32 = 1;
+pub const XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM: u32 = 2;
+pub const XDG_TOPLEVEL_RESIZE_EDGE_LEFT: u32 = 4;
+pub const XDG_TOPLEVEL_RESIZE_EDGE_TOP_LEFT: u32 = 5;
+pub const XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM_LEFT: u32 = 6;
+pub const XDG_TOPLEVEL_RESIZE_EDGE_RIGHT: u32 = 8;
+pub const XDG_TOPLEVEL_RESIZE_EDGE_TOP_RIGHT: u32 = 9;
+pub const XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM_RIGHT: u32 = 10;
+
+pub const ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION: u32 = 1;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_DESTROY: u32 = 0;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE: u32 = 1;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE: u32 = 2;
+
+pub const WP_FRACTIONAL_SCALE_MANAGER_V1_GET_FRACTIONAL_SCALE: u32 = 1;
+pub const WP_FRACTIONAL_SCALE_V1_DESTROY: u32 = 0;
+pub const WP_FRACTIONAL_SCALE_V1_PREFERRED_SCALE: u32 = 0;
+
+pub const WP_VIEWPORTER_GET_VIEWPORT: u32 = 1;
+pub const WP_VIEWPORT_DESTROY: u32 = 0;
+pub const WP_VIEWPORT_SET_DESTINATION: u32 = 2;
+
+pub const ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT: u32 = 1;
+pub const ZWP_TEXT_INPUT_V3_ENABLE: u32 = 1;
+pub const ZWP_TEXT_INPUT_V3_DISABLE: u32 = 2;
+pub const ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE: u32 = 6;
+pub const ZWP_TEXT_INPUT_V3_COMMIT: u32 = 7;
+pub const ZWP_TEXT_INPUT_V3_ENTER: u32 = 0;
+pub const ZWP_TEXT_INPUT_V3_LEAVE: u32 = 1;
+pub const ZWP_TEXT_INPUT_V3_PREEDIT_STRING: u32 = 2;
+pub const ZWP_TEXT_INPUT_V3_COMMIT_STRING: u32 = 3;
+pub const ZWP_TEXT_INPUT_V3_DELETE_SURROUNDING_TEXT: u32 = 4;
+pub const ZWP_TEXT_INPUT_V3_DONE: u32 = 5;
+
+pub const WP_CURSOR_SHAPE_MANAGER_V1_GET_POINTER: u32 = 1;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_DESTROY: u32 = 0;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SET_SHAPE: u32 = 1;
+
+pub const WP_CURSOR_SHAPE_DEFAULT: u32 = 1;
+pub const WP_CURSOR_SHAPE_HELP: u32 = 3;
+pub const WP_CURSOR_SHAPE_POINTER: u32 = 4;
+pub const WP_CURSOR_SHAPE_WAIT: u32 = 6;
+pub const WP_CURSOR_SHAPE_CROSSHAIR: u32 = 8;
+pub const WP_CURSOR_SHAPE_TEXT: u32 = 9;
+pub const WP_CURSOR_SHAPE_MOVE: u32 = 13;
+pub const WP_CURSOR_SHAPE_NOT_ALLOWED: u32 = 15;
+pub const WP_CURSOR_SHAPE_E_RESIZE: u32 = 18;
+pub const WP_CURSOR_SHAPE_N_RESIZE: u32 = 19;
+pub const WP_CURSOR_SHAPE_NE_RESIZE: u32 = 20;
+pub const WP_CURSOR_SHAPE_NW_RESIZE: u32 = 21;
+pub const WP_CURSOR_SHAPE_S_RESIZE: u32 = 22;
+pub const WP_CURSOR_SHAPE_SE_RESIZE: u32 = 23;
+pub const WP_CURSOR_SHAPE_SW_RESIZE: u32 = 24;
+pub const WP_CURSOR_SHAPE_W_RESIZE: u32 = 25;
+pub const WP_CURSOR_SHAPE_EW_RESIZE: u32 = 26;
+pub const WP_CURSOR_SHAPE_NS_RESIZE: u32 = 27;
+pub const WP_CURSOR_SHAPE_NESW_RESIZE: u32 = 28;
+pub const WP_CURSOR_SHAPE_NWSE_RESIZE: u32 = 29;
+pub const WP_CURSOR_SHAPE_COL_RESIZE: u32 = 30;
+pub const WP_CURSOR_SHAPE_ROW_RESIZE: u32 = 31;
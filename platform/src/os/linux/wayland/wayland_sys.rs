@@ -0,0 +1,265 @@
+#![allow(non_camel_case_types, non_snake_case, dead_code)]
+
+use {
+    std::os::raw::{c_char, c_int, c_void},
+    self::super::super::module_loader::ModuleLoader,
+};
+
+// libwayland-client, libwayland-egl and libwayland-cursor are loaded at runtime, so binaries that
+// only ever run on X11 do not need them installed.
+
+#[repr(C)]
+pub struct wl_proxy {
+    _unused: [u8; 0],
+}
+
+pub type wl_display = wl_proxy;
+pub type wl_object = wl_proxy;
+pub type wl_fixed_t = i32;
+
+#[repr(C)]
+pub struct wl_egl_window {
+    _unused: [u8; 0],
+}
+
+#[repr(C)]
+pub struct wl_cursor_theme {
+    _unused: [u8; 0],
+}
+
+#[repr(C)]
+pub struct wl_message {
+    pub name: *const c_char,
+    pub signature: *const c_char,
+    pub types: *const *const wl_interface,
+}
+
+#[repr(C)]
+pub struct wl_interface {
+    pub name: *const c_char,
+    pub version: c_int,
+    pub method_count: c_int,
+    pub methods: *const wl_message,
+    pub event_count: c_int,
+    pub events: *const wl_message,
+}
+
+// the protocol descriptions are immutable tables
+unsafe impl Sync for wl_message {}
+unsafe impl Sync for wl_interface {}
+
+#[repr(C)]
+pub struct wl_array {
+    pub size: usize,
+    pub alloc: usize,
+    pub data: *mut c_void,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union wl_argument {
+    pub i: i32,
+    pub u: u32,
+    pub f: wl_fixed_t,
+    pub s: *const c_char,
+    pub o: *mut wl_object,
+    pub n: u32,
+    pub a: *mut wl_array,
+    pub h: i32,
+}
+
+impl wl_argument {
+    pub fn int(i: i32) -> Self {wl_argument {i}}
+    pub fn uint(u: u32) -> Self {wl_argument {u}}
+    pub fn fixed(f: f64) -> Self {wl_argument {f: (f * 256.0) as wl_fixed_t}}
+    pub fn string(s: *const c_char) -> Self {wl_argument {s}}
+    pub fn object(o: *mut wl_proxy) -> Self {wl_argument {o}}
+    // placeholder for the object the request creates
+    pub fn new_id() -> Self {wl_argument {o: std::ptr::null_mut()}}
+    pub fn fd(h: c_int) -> Self {wl_argument {h}}
+}
+
+#[repr(C)]
+pub struct wl_cursor_image {
+    pub width: u32,
+    pub height: u32,
+    pub hotspot_x: u32,
+    pub hotspot_y: u32,
+    pub delay: u32,
+}
+
+#[repr(C)]
+pub struct wl_cursor {
+    pub image_count: u32,
+    pub images: *mut *mut wl_cursor_image,
+    pub name: *mut c_char,
+}
+
+pub type wl_dispatcher_func_t = unsafe extern "C" fn(
+    dispatcher_data: *const c_void,
+    target: *mut c_void,
+    opcode: u32,
+    msg: *const wl_message,
+    args: *mut wl_argument,
+) -> c_int;
+
+pub const WL_MARSHAL_FLAG_DESTROY: u32 = 1 << 0;
+
+pub const WL_SEAT_CAPABILITY_POINTER: u32 = 1;
+pub const WL_SEAT_CAPABILITY_KEYBOARD: u32 = 2;
+pub const WL_SEAT_CAPABILITY_TOUCH: u32 = 4;
+
+pub const WL_POINTER_BUTTON_STATE_PRESSED: u32 = 1;
+pub const WL_POINTER_AXIS_VERTICAL_SCROLL: u32 = 0;
+pub const WL_POINTER_AXIS_HORIZONTAL_SCROLL: u32 = 1;
+pub const WL_POINTER_AXIS_SOURCE_WHEEL: u32 = 0;
+pub const WL_POINTER_AXIS_SOURCE_FINGER: u32 = 1;
+
+pub const WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1: u32 = 1;
+pub const WL_KEYBOARD_KEY_STATE_PRESSED: u32 = 1;
+
+pub const WL_DATA_DEVICE_MANAGER_DND_ACTION_COPY: u32 = 1;
+
+// linux input event codes for the pointer buttons
+pub const BTN_LEFT: u32 = 0x110;
+pub const BTN_RIGHT: u32 = 0x111;
+pub const BTN_MIDDLE: u32 = 0x112;
+
+pub fn wl_fixed_to_f64(f: wl_fixed_t) -> f64 {
+    f as f64 / 256.0
+}
+
+pub struct LibWaylandClient {
+    pub wl_display_connect: unsafe extern "C" fn(name: *const c_char) -> *mut wl_display,
+    pub wl_display_disconnect: unsafe extern "C" fn(display: *mut wl_display),
+    pub wl_display_get_fd: unsafe extern "C" fn(display: *mut wl_display) -> c_int,
+    pub wl_display_dispatch_pending: unsafe extern "C" fn(display: *mut wl_display) -> c_int,
+    pub wl_display_roundtrip: unsafe extern "C" fn(display: *mut wl_display) -> c_int,
+    pub wl_display_flush: unsafe extern "C" fn(display: *mut wl_display) -> c_int,
+    pub wl_display_prepare_read: unsafe extern "C" fn(display: *mut wl_display) -> c_int,
+    pub wl_display_read_events: unsafe extern "C" fn(display: *mut wl_display) -> c_int,
+    pub wl_display_cancel_read: unsafe extern "C" fn(display: *mut wl_display),
+    pub wl_display_get_error: unsafe extern "C" fn(display: *mut wl_display) -> c_int,
+    pub wl_proxy_marshal_array_flags: unsafe extern "C" fn(
+        proxy: *mut wl_proxy,
+        opcode: u32,
+        interface: *const wl_interface,
+        version: u32,
+        flags: u32,
+        args: *mut wl_argument,
+    ) -> *mut wl_proxy,
+    pub wl_proxy_add_dispatcher: unsafe extern "C" fn(
+        proxy: *mut wl_proxy,
+        dispatcher: wl_dispatcher_func_t,
+        dispatcher_data: *const c_void,
+        data: *mut c_void,
+    ) -> c_int,
+    pub wl_proxy_destroy: unsafe extern "C" fn(proxy: *mut wl_proxy),
+    pub wl_proxy_get_version: unsafe extern "C" fn(proxy: *mut wl_proxy) -> u32,
+    pub wl_proxy_get_user_data: unsafe extern "C" fn(proxy: *mut wl_proxy) -> *mut c_void,
+
+    pub wl_registry_interface: *const wl_interface,
+    pub wl_callback_interface: *const wl_interface,
+    pub wl_compositor_interface: *const wl_interface,
+    pub wl_surface_interface: *const wl_interface,
+    pub wl_region_interface: *const wl_interface,
+    pub wl_shm_interface: *const wl_interface,
+    pub wl_seat_interface: *const wl_interface,
+    pub wl_pointer_interface: *const wl_interface,
+    pub wl_keyboard_interface: *const wl_interface,
+    pub wl_touch_interface: *const wl_interface,
+    pub wl_output_interface: *const wl_interface,
+    pub wl_data_device_manager_interface: *const wl_interface,
+    pub wl_data_device_interface: *const wl_interface,
+    pub wl_data_source_interface: *const wl_interface,
+    pub wl_data_offer_interface: *const wl_interface,
+
+    _keep_module_alive: ModuleLoader,
+}
+
+impl LibWaylandClient {
+    pub fn try_load() -> Option<LibWaylandClient> {
+        let module = ModuleLoader::load("libwayland-client.so.0").or_else( | _ | ModuleLoader::load("libwayland-client.so")).ok()?;
+        Some(LibWaylandClient {
+            wl_display_connect: module.get_symbol("wl_display_connect").ok()?,
+            wl_display_disconnect: module.get_symbol("wl_display_disconnect").ok()?,
+            wl_display_get_fd: module.get_symbol("wl_display_get_fd").ok()?,
+            wl_display_dispatch_pending: module.get_symbol("wl_display_dispatch_pending").ok()?,
+            wl_display_roundtrip: module.get_symbol("wl_display_roundtrip").ok()?,
+            wl_display_flush: module.get_symbol("wl_display_flush").ok()?,
+            wl_display_prepare_read: module.get_symbol("wl_display_prepare_read").ok()?,
+            wl_display_read_events: module.get_symbol("wl_display_read_events").ok()?,
+            wl_display_cancel_read: module.get_symbol("wl_display_cancel_read").ok()?,
+            wl_display_get_error: module.get_symbol("wl_display_get_error").ok()?,
+            // wl_proxy_marshal_array_flags is available since libwayland 1.20
+            wl_proxy_marshal_array_flags: module.get_symbol("wl_proxy_marshal_array_flags").ok()?,
+            wl_proxy_add_dispatcher: module.get_symbol("wl_proxy_add_dispatcher").ok()?,
+            wl_proxy_destroy: module.get_symbol("wl_proxy_destroy").ok()?,
+            wl_proxy_get_version: module.get_symbol("wl_proxy_get_version").ok()?,
+            wl_proxy_get_user_data: module.get_symbol("wl_proxy_get_user_data").ok()?,
+
+            wl_registry_interface: module.get_symbol("wl_registry_interface").ok()?,
+            wl_callback_interface: module.get_symbol("wl_callback_interface").ok()?,
+            wl_compositor_interface: module.get_symbol("wl_compositor_interface").ok()?,
+            wl_surface_interface: module.get_symbol("wl_surface_interface").ok()?,
+            wl_region_interface: module.get_symbol("wl_region_interface").ok()?,
+            wl_shm_interface: module.get_symbol("wl_shm_interface").ok()?,
+            wl_seat_interface: module.get_symbol("wl_seat_interface").ok()?,
+            wl_pointer_interface: module.get_symbol("wl_pointer_interface").ok()?,
+            wl_keyboard_interface: module.get_symbol("wl_keyboard_interface").ok()?,
+            wl_touch_interface: module.get_symbol("wl_touch_interface").ok()?,
+            wl_output_interface: module.get_symbol("wl_output_interface").ok()?,
+            wl_data_device_manager_interface: module.get_symbol("wl_data_device_manager_interface").ok()?,
+            wl_data_device_interface: module.get_symbol("wl_data_device_interface").ok()?,
+            wl_data_source_interface: module.get_symbol("wl_data_source_interface").ok()?,
+            wl_data_offer_interface: module.get_symbol("wl_data_offer_interface").ok()?,
+
+            _keep_module_alive: module,
+        })
+    }
+}
+
+pub struct LibWaylandEgl {
+    pub wl_egl_window_create: unsafe extern "C" fn(surface: *mut wl_proxy, width: c_int, height: c_int) -> *mut wl_egl_window,
+    pub wl_egl_window_destroy: unsafe extern "C" fn(egl_window: *mut wl_egl_window),
+    pub wl_egl_window_resize: unsafe extern "C" fn(egl_window: *mut wl_egl_window, width: c_int, height: c_int, dx: c_int, dy: c_int),
+
+    _keep_module_alive: ModuleLoader,
+}
+
+impl LibWaylandEgl {
+    pub fn try_load() -> Option<LibWaylandEgl> {
+        let module = ModuleLoader::load("libwayland-egl.so.1").or_else( | _ | ModuleLoader::load("libwayland-egl.so")).ok()?;
+        Some(LibWaylandEgl {
+            wl_egl_window_create: module.get_symbol("wl_egl_window_create").ok()?,
+            wl_egl_window_destroy: module.get_symbol("wl_egl_window_destroy").ok()?,
+            wl_egl_window_resize: module.get_symbol("wl_egl_window_resize").ok()?,
+
+            _keep_module_alive: module,
+        })
+    }
+}
+
+// only used when the compositor does not support the cursor-shape protocol
+pub struct LibWaylandCursor {
+    pub wl_cursor_theme_load: unsafe extern "C" fn(name: *const c_char, size: c_int, shm: *mut wl_proxy) -> *mut wl_cursor_theme,
+    pub wl_cursor_theme_destroy: unsafe extern "C" fn(theme: *mut wl_cursor_theme),
+    pub wl_cursor_theme_get_cursor: unsafe extern "C" fn(theme: *mut wl_cursor_theme, name: *const c_char) -> *mut wl_cursor,
+    pub wl_cursor_image_get_buffer: unsafe extern "C" fn(image: *mut wl_cursor_image) -> *mut wl_proxy,
+
+    _keep_module_alive: ModuleLoader,
+}
+
+impl LibWaylandCursor {
+    pub fn try_load() -> Option<LibWaylandCursor> {
+        let module = ModuleLoader::load("libwayland-cursor.so.0").or_else( | _ | ModuleLoader::load("libwayland-cursor.so")).ok()?;
+        Some(LibWaylandCursor {
+            wl_cursor_theme_load: module.get_symbol("wl_cursor_theme_load").ok()?,
+            wl_cursor_theme_destroy: module.get_symbol("wl_cursor_theme_destroy").ok()?,
+            wl_cursor_theme_get_cursor: module.get_symbol("wl_cursor_theme_get_cursor").ok()?,
+            wl_cursor_image_get_buffer: module.get_symbol("wl_cursor_image_get_buffer").ok()?,
+
+            _keep_module_alive: module,
+        })
+    }
+}
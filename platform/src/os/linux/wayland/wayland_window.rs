@@ -0,0 +1,279 @@
+use {
+    std::{
+        ffi::CString,
+        ptr,
+    },
+    self::super::{
+        wayland_sys::*,
+        wayland_protocols::*,
+        wayland_display::{WaylandDisplay, WaylandOutput},
+    },
+    self::super::super::{
+        egl_sys,
+        x11::opengl_x11::OpenglCx,
+    },
+    crate::{
+        window::WindowId,
+        makepad_math::{DVec2, dvec2},
+        event::WindowGeom,
+    },
+};
+
+pub struct WaylandWindow {
+    pub window_id: WindowId,
+    pub surface: *mut wl_proxy,
+    pub xdg_surface: *mut wl_proxy,
+    pub xdg_toplevel: *mut wl_proxy,
+    pub decoration: *mut wl_proxy,
+    pub fractional_scale: *mut wl_proxy,
+    pub viewport: *mut wl_proxy,
+    pub frame_callback: *mut wl_proxy,
+    pub egl_window: *mut wl_egl_window,
+    pub egl_surface: egl_sys::EGLSurface,
+    pub window_geom: WindowGeom,
+    pub buffer_size: DVec2,
+    pub is_configured: bool,
+    pub is_maximized: bool,
+    pub is_fullscreen: bool,
+    pub pending_size: Option<DVec2>,
+    pub pending_maximized: bool,
+    pub pending_fullscreen: bool,
+    pub outputs: Vec<*mut wl_proxy>,
+    pub preferred_buffer_scale: Option<i32>,
+    pub preferred_fractional_scale: Option<f64>,
+}
+
+impl WaylandWindow {
+    pub fn new(
+        window_id: WindowId,
+        display: &WaylandDisplay,
+        opengl_cx: &OpenglCx,
+        inner_size: DVec2,
+        title: &str
+    ) -> Box<WaylandWindow> {
+        unsafe {
+            let surface = display.create(display.compositor, WL_COMPOSITOR_CREATE_SURFACE, display.lib.wl_surface_interface, &mut [wl_argument::new_id()]);
+            let xdg_surface = display.create(display.xdg_wm_base, XDG_WM_BASE_GET_XDG_SURFACE, &xdg_surface_interface, &mut [
+                wl_argument::new_id(),
+                wl_argument::object(surface)
+            ]);
+            let xdg_toplevel = display.create(xdg_surface, XDG_SURFACE_GET_TOPLEVEL, &xdg_toplevel_interface, &mut [wl_argument::new_id()]);
+
+            let title = CString::new(title).unwrap_or_default();
+            display.request(xdg_toplevel, XDG_TOPLEVEL_SET_TITLE, &mut [wl_argument::string(title.as_ptr())]);
+            // the app id lets the desktop match the window to its .desktop file
+            if let Some(app_id) = std::env::current_exe().ok().and_then( | exe | exe.file_stem().map( | stem | stem.to_string_lossy().into_owned())) {
+                let app_id = CString::new(app_id).unwrap_or_default();
+                display.request(xdg_toplevel, XDG_TOPLEVEL_SET_APP_ID, &mut [wl_argument::string(app_id.as_ptr())]);
+            }
+
+            let decoration = if !display.decoration_manager.is_null() {
+                let decoration = display.create(display.decoration_manager, ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION, &zxdg_toplevel_decoration_v1_interface, &mut [
+                    wl_argument::new_id(),
+                    wl_argument::object(xdg_toplevel)
+                ]);
+                display.request(decoration, ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE, &mut [wl_argument::uint(ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE)]);
+                decoration
+            }
+            else {
+                ptr::null_mut()
+            };
+
+            // fractional scaling renders at the exact pixel size and lets the viewport map it
+            // back onto the logical size of the surface
+            let (fractional_scale, viewport) = if !display.fractional_scale_manager.is_null() && !display.viewporter.is_null() {(
+                display.create(display.fractional_scale_manager, WP_FRACTIONAL_SCALE_MANAGER_V1_GET_FRACTIONAL_SCALE, &wp_fractional_scale_v1_interface, &mut [
+                    wl_argument::new_id(),
+                    wl_argument::object(surface)
+                ]),
+                display.create(display.viewporter, WP_VIEWPORTER_GET_VIEWPORT, &wp_viewport_interface, &mut [
+                    wl_argument::new_id(),
+                    wl_argument::object(surface)
+                ]),
+            )}
+            else {
+                (ptr::null_mut(), ptr::null_mut())
+            };
+
+            let egl_window = (display.lib_egl.wl_egl_window_create)(surface, inner_size.x as i32, inner_size.y as i32);
+            assert!(!egl_window.is_null(), "wl_egl_window_create failed");
+            let egl_surface = opengl_cx.create_window_surface(egl_window as egl_sys::EGLNativeWindowType);
+            // we pace frames with frame callbacks, a blocking swap would stall the event loop
+            opengl_cx.set_swap_interval(egl_surface, 0);
+            opengl_cx.make_current();
+
+            // the initial commit without a buffer asks the compositor for the first configure
+            display.request(surface, WL_SURFACE_COMMIT, &mut []);
+            display.flush();
+
+            Box::new(WaylandWindow {
+                window_id,
+                surface,
+                xdg_surface,
+                xdg_toplevel,
+                decoration,
+                fractional_scale,
+                viewport,
+                frame_callback: ptr::null_mut(),
+                egl_window,
+                egl_surface,
+                window_geom: WindowGeom {
+                    inner_size,
+                    outer_size: inner_size,
+                    dpi_factor: 1.0,
+                    ..Default::default()
+                },
+                buffer_size: inner_size,
+                is_configured: false,
+                is_maximized: false,
+                is_fullscreen: false,
+                pending_size: None,
+                pending_maximized: false,
+                pending_fullscreen: false,
+                outputs: Vec::new(),
+                preferred_buffer_scale: None,
+                preferred_fractional_scale: None,
+            })
+        }
+    }
+
+    pub fn owns_proxy(&self, proxy: *mut wl_proxy) -> bool {
+        proxy == self.surface
+            || proxy == self.xdg_surface
+            || proxy == self.xdg_toplevel
+            || proxy == self.fractional_scale
+            || proxy == self.frame_callback
+    }
+
+    // a window can be drawn once it is configured and the compositor consumed the last frame
+    pub fn can_paint(&self) -> bool {
+        self.is_configured && self.frame_callback.is_null()
+    }
+
+    pub fn request_frame(&mut self, display: &WaylandDisplay) {
+        unsafe {
+            self.frame_callback = display.create(self.surface, WL_SURFACE_FRAME, display.lib.wl_callback_interface, &mut [wl_argument::new_id()]);
+        }
+    }
+
+    pub fn frame_done(&mut self, display: &WaylandDisplay) {
+        unsafe {display.destroy_proxy(self.frame_callback)};
+        self.frame_callback = ptr::null_mut();
+    }
+
+    pub fn scale_factor(&self, outputs: &[WaylandOutput]) -> f64 {
+        if let Some(scale) = self.preferred_fractional_scale {
+            return scale
+        }
+        if let Some(scale) = self.preferred_buffer_scale {
+            return scale as f64
+        }
+        // older compositors only tell us which outputs the surface is on
+        self.outputs.iter().filter_map( | proxy | outputs.iter().find( | output | output.proxy == *proxy))
+            .map( | output | output.scale)
+            .max()
+            .unwrap_or(1) as f64
+    }
+
+    pub fn get_window_geom(&self, outputs: &[WaylandOutput]) -> WindowGeom {
+        let inner_size = self.pending_size.unwrap_or(self.window_geom.inner_size);
+        WindowGeom {
+            xr_is_presenting: false,
+            can_fullscreen: true,
+            is_topmost: false,
+            is_fullscreen: self.is_maximized || self.is_fullscreen,
+            inner_size,
+            outer_size: inner_size,
+            dpi_factor: self.scale_factor(outputs),
+            position: DVec2::default(),
+        }
+    }
+
+    // sizes the EGL buffer to the pixel size of the window and tells the compositor how to scale it
+    pub fn resize_buffers(&mut self, display: &WaylandDisplay) {
+        let size = self.window_geom.inner_size;
+        let scale = self.window_geom.dpi_factor;
+        let buffer_size = if self.viewport.is_null() {
+            size * scale.round()
+        }
+        else {
+            dvec2((size.x * scale).round(), (size.y * scale).round())
+        };
+        unsafe {
+            if buffer_size != self.buffer_size {
+                self.buffer_size = buffer_size;
+                (display.lib_egl.wl_egl_window_resize)(self.egl_window, buffer_size.x as i32, buffer_size.y as i32, 0, 0);
+            }
+            if self.viewport.is_null() {
+                display.request(self.surface, WL_SURFACE_SET_BUFFER_SCALE, &mut [wl_argument::int(scale.round() as i32)]);
+            }
+            else {
+                display.request(self.viewport, WP_VIEWPORT_SET_DESTINATION, &mut [
+                    wl_argument::int(size.x as i32),
+                    wl_argument::int(size.y as i32)
+                ]);
+            }
+            // we draw opaque windows, which saves the compositor from blending them
+            let region = display.create(display.compositor, WL_COMPOSITOR_CREATE_REGION, display.lib.wl_region_interface, &mut [wl_argument::new_id()]);
+            display.request(region, WL_REGION_ADD, &mut [
+                wl_argument::int(0),
+                wl_argument::int(0),
+                wl_argument::int(size.x as i32),
+                wl_argument::int(size.y as i32)
+            ]);
+            display.request(self.surface, WL_SURFACE_SET_OPAQUE_REGION, &mut [wl_argument::object(region)]);
+            display.destroy(region, WL_REGION_DESTROY);
+        }
+    }
+
+    pub fn maximize(&self, display: &WaylandDisplay) {
+        unsafe {display.request(self.xdg_toplevel, XDG_TOPLEVEL_SET_MAXIMIZED, &mut [])};
+    }
+
+    pub fn restore(&self, display: &WaylandDisplay) {
+        unsafe {
+            if self.is_fullscreen {
+                display.request(self.xdg_toplevel, XDG_TOPLEVEL_UNSET_FULLSCREEN, &mut []);
+            }
+            display.request(self.xdg_toplevel, XDG_TOPLEVEL_UNSET_MAXIMIZED, &mut []);
+        }
+    }
+
+    pub fn minimize(&self, display: &WaylandDisplay) {
+        unsafe {display.request(self.xdg_toplevel, XDG_TOPLEVEL_SET_MINIMIZED, &mut [])};
+    }
+
+    pub fn fullscreen(&self, display: &WaylandDisplay) {
+        unsafe {display.request(self.xdg_toplevel, XDG_TOPLEVEL_SET_FULLSCREEN, &mut [wl_argument::object(ptr::null_mut())])};
+    }
+
+    pub fn unfullscreen(&self, display: &WaylandDisplay) {
+        unsafe {display.request(self.xdg_toplevel, XDG_TOPLEVEL_UNSET_FULLSCREEN, &mut [])};
+    }
+
+    pub fn start_move(&self, display: &WaylandDisplay, serial: u32) {
+        unsafe {display.request(self.xdg_toplevel, XDG_TOPLEVEL_MOVE, &mut [
+            wl_argument::object(display.seat),
+            wl_argument::uint(serial)
+        ])};
+    }
+
+    pub fn close_window(&mut self, display: &WaylandDisplay, opengl_cx: &OpenglCx) {
+        unsafe {
+            opengl_cx.make_current();
+            opengl_cx.destroy_window_surface(self.egl_surface);
+            (display.lib_egl.wl_egl_window_destroy)(self.egl_window);
+            display.destroy_proxy(self.frame_callback);
+            display.destroy(self.viewport, WP_VIEWPORT_DESTROY);
+            display.destroy(self.fractional_scale, WP_FRACTIONAL_SCALE_V1_DESTROY);
+            display.destroy(self.decoration, ZXDG_TOPLEVEL_DECORATION_V1_DESTROY);
+            display.destroy(self.xdg_toplevel, XDG_TOPLEVEL_DESTROY);
+            display.destroy(self.xdg_surface, XDG_SURFACE_DESTROY);
+            display.destroy(self.surface, WL_SURFACE_DESTROY);
+            display.flush();
+        }
+        self.frame_callback = ptr::null_mut();
+        self.egl_window = ptr::null_mut();
+        self.surface = ptr::null_mut();
+    }
+}
@@ -11,7 +11,8 @@ use {
         x11::xlib_event::*,
         x11::xlib_app::*,
         x11::x11_sys,
-        linux_media::CxLinuxMedia
+        linux_media::CxLinuxMedia,
+        headless::CxHeadless,
     },
     crate::{
         cx_api::{CxOsOp, CxOsApi, OpenUrlInPlace}, 
@@ -28,15 +29,33 @@ use {
 };
 
 impl Cx {
-    pub fn event_loop(cx:Rc<RefCell<Cx>>) {
+    pub (crate) fn init_linux_window_os(cx: &Rc<RefCell<Cx>>) {
         cx.borrow_mut().self_ref = Some(cx.clone());
         cx.borrow_mut().os_type = OsType::LinuxWindow(LinuxWindowParams{
             custom_window_chrome: false
         });
         cx.borrow_mut().gpu_info.performance = GpuPerformance::Tier1;
+    }
 
+    pub fn event_loop(cx:Rc<RefCell<Cx>>) {
+        Cx::init_linux_window_os(&cx);
+
+        if std::env::args().any(|v| v=="--headless") {
+            return cx.borrow_mut().headless_event_loop();
+        }
+
+        cx.borrow_mut().start_atspi_bridge();
+        cx.borrow_mut().start_color_scheme_watcher();
         let opengl_windows = Rc::new(RefCell::new(Vec::new()));
         let is_stdin_loop = std::env::args().find(|v| v=="--stdin-loop").is_some();
+        
+        // prefer a native Wayland session, --x11 forces XWayland
+        if !is_stdin_loop && std::env::var_os("WAYLAND_DISPLAY").is_some() && !std::env::args().any(|v| v=="--x11") {
+            if Cx::wayland_event_loop(cx.clone(), None) {
+                return
+            }
+            crate::log!("Could not use the Wayland compositor, falling back to X11");
+        }
         if is_stdin_loop {
             cx.borrow_mut().in_makepad_studio = true;
         }
@@ -400,6 +419,7 @@ pub struct CxOs {
     pub (crate) stdin_timers: PollTimers,
     pub (crate) start_time: Option<Instant>,
     // HACK(eddyb) generalize this to EGL, properly.
-    pub(super) opengl_cx: Option<OpenglCx>,
+    pub(crate) opengl_cx: Option<OpenglCx>,
+    pub (crate) headless: CxHeadless,
 }
 
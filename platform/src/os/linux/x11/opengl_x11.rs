@@ -30,17 +30,26 @@ impl Cx {
         &mut self,
         pass_id: PassId,
         opengl_window: &mut OpenglWindow,
+    ) {
+        let pix_size = opengl_window.window_geom.inner_size * opengl_window.window_geom.dpi_factor;
+        self.draw_pass_to_egl_surface(pass_id, opengl_window.egl_surface, pix_size);
+    }
+    
+    // shared by the X11 and Wayland windows, which only differ in how the EGL surface is made
+    pub(crate) fn draw_pass_to_egl_surface(
+        &mut self,
+        pass_id: PassId,
+        egl_surface: egl_sys::EGLSurface,
+        pix_size: DVec2,
     ) {
         let draw_list_id = self.passes[pass_id].main_draw_list_id.unwrap();
         
         self.setup_render_pass(pass_id);
         
-        let egl_surface = opengl_window.egl_surface;
-        
         self.passes[pass_id].paint_dirty = false;
 
-        let pix_width = opengl_window.window_geom.inner_size.x * opengl_window.window_geom.dpi_factor;
-        let pix_height = opengl_window.window_geom.inner_size.y * opengl_window.window_geom.dpi_factor;
+        let pix_width = pix_size.x;
+        let pix_height = pix_size.y;
         unsafe {
             let opengl_cx = self.os.opengl_cx.as_ref().unwrap();
             (opengl_cx.libegl.eglMakeCurrent.unwrap())(opengl_cx.egl_display, egl_surface, egl_surface, opengl_cx.egl_context);
@@ -391,6 +400,27 @@ impl OpenglCx {
             );
         }
     }
+    
+    pub unsafe fn create_window_surface(&self, native_window: egl_sys::EGLNativeWindowType) -> egl_sys::EGLSurface {
+        let egl_surface = (self.libegl.eglCreateWindowSurface.unwrap())(
+            self.egl_display,
+            self.egl_config,
+            native_window,
+            std::ptr::null(),
+        );
+        assert!(!egl_surface.is_null(), "eglCreateWindowSurface failed");
+        egl_surface
+    }
+    
+    pub unsafe fn destroy_window_surface(&self, egl_surface: egl_sys::EGLSurface) {
+        (self.libegl.eglDestroySurface.unwrap())(self.egl_display, egl_surface);
+    }
+    
+    // binds the surface, as eglSwapInterval applies to the current draw surface
+    pub unsafe fn set_swap_interval(&self, egl_surface: egl_sys::EGLSurface, interval: i32) {
+        (self.libegl.eglMakeCurrent.unwrap())(self.egl_display, egl_surface, egl_surface, self.egl_context);
+        (self.libegl.eglSwapInterval.unwrap())(self.egl_display, interval);
+    }
 }
 
 #[derive(Clone)]
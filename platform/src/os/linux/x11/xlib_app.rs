@@ -746,141 +746,7 @@ impl XlibApp {
                 ptr::null_mut(),
             );
         }
-        match keysym as u32 {
-            x11_sys::XK_a => KeyCode::KeyA,
-            x11_sys::XK_A => KeyCode::KeyA,
-            x11_sys::XK_b => KeyCode::KeyB,
-            x11_sys::XK_B => KeyCode::KeyB,
-            x11_sys::XK_c => KeyCode::KeyC,
-            x11_sys::XK_C => KeyCode::KeyC,
-            x11_sys::XK_d => KeyCode::KeyD,
-            x11_sys::XK_D => KeyCode::KeyD,
-            x11_sys::XK_e => KeyCode::KeyE,
-            x11_sys::XK_E => KeyCode::KeyE,
-            x11_sys::XK_f => KeyCode::KeyF,
-            x11_sys::XK_F => KeyCode::KeyF,
-            x11_sys::XK_g => KeyCode::KeyG,
-            x11_sys::XK_G => KeyCode::KeyG,
-            x11_sys::XK_h => KeyCode::KeyH,
-            x11_sys::XK_H => KeyCode::KeyH,
-            x11_sys::XK_i => KeyCode::KeyI,
-            x11_sys::XK_I => KeyCode::KeyI,
-            x11_sys::XK_j => KeyCode::KeyJ,
-            x11_sys::XK_J => KeyCode::KeyJ,
-            x11_sys::XK_k => KeyCode::KeyK,
-            x11_sys::XK_K => KeyCode::KeyK,
-            x11_sys::XK_l => KeyCode::KeyL,
-            x11_sys::XK_L => KeyCode::KeyL,
-            x11_sys::XK_m => KeyCode::KeyM,
-            x11_sys::XK_M => KeyCode::KeyM,
-            x11_sys::XK_n => KeyCode::KeyN,
-            x11_sys::XK_N => KeyCode::KeyN,
-            x11_sys::XK_o => KeyCode::KeyO,
-            x11_sys::XK_O => KeyCode::KeyO,
-            x11_sys::XK_p => KeyCode::KeyP,
-            x11_sys::XK_P => KeyCode::KeyP,
-            x11_sys::XK_q => KeyCode::KeyQ,
-            x11_sys::XK_Q => KeyCode::KeyQ,
-            x11_sys::XK_r => KeyCode::KeyR,
-            x11_sys::XK_R => KeyCode::KeyR,
-            x11_sys::XK_s => KeyCode::KeyS,
-            x11_sys::XK_S => KeyCode::KeyS,
-            x11_sys::XK_t => KeyCode::KeyT,
-            x11_sys::XK_T => KeyCode::KeyT,
-            x11_sys::XK_u => KeyCode::KeyU,
-            x11_sys::XK_U => KeyCode::KeyU,
-            x11_sys::XK_v => KeyCode::KeyV,
-            x11_sys::XK_V => KeyCode::KeyV,
-            x11_sys::XK_w => KeyCode::KeyW,
-            x11_sys::XK_W => KeyCode::KeyW,
-            x11_sys::XK_x => KeyCode::KeyX,
-            x11_sys::XK_X => KeyCode::KeyX,
-            x11_sys::XK_y => KeyCode::KeyY,
-            x11_sys::XK_Y => KeyCode::KeyY,
-            x11_sys::XK_z => KeyCode::KeyZ,
-            x11_sys::XK_Z => KeyCode::KeyZ,
-            
-            x11_sys::XK_0 => KeyCode::Key0,
-            x11_sys::XK_1 => KeyCode::Key1,
-            x11_sys::XK_2 => KeyCode::Key2,
-            x11_sys::XK_3 => KeyCode::Key3,
-            x11_sys::XK_4 => KeyCode::Key4,
-            x11_sys::XK_5 => KeyCode::Key5,
-            x11_sys::XK_6 => KeyCode::Key6,
-            x11_sys::XK_7 => KeyCode::Key7,
-            x11_sys::XK_8 => KeyCode::Key8,
-            x11_sys::XK_9 => KeyCode::Key9,
-            
-            x11_sys::XK_Alt_L => KeyCode::Alt,
-            x11_sys::XK_Alt_R => KeyCode::Alt,
-            x11_sys::XK_Meta_L => KeyCode::Logo,
-            x11_sys::XK_Meta_R => KeyCode::Logo,
-            x11_sys::XK_Shift_L => KeyCode::Shift,
-            x11_sys::XK_Shift_R => KeyCode::Shift,
-            x11_sys::XK_Control_L => KeyCode::Control,
-            x11_sys::XK_Control_R => KeyCode::Control,
-            
-            x11_sys::XK_equal => KeyCode::Equals,
-            x11_sys::XK_minus => KeyCode::Minus,
-            x11_sys::XK_bracketright => KeyCode::RBracket,
-            x11_sys::XK_bracketleft => KeyCode::LBracket,
-            x11_sys::XK_Return => KeyCode::ReturnKey,
-            x11_sys::XK_grave => KeyCode::Backtick,
-            x11_sys::XK_semicolon => KeyCode::Semicolon,
-            x11_sys::XK_backslash => KeyCode::Backslash,
-            x11_sys::XK_comma => KeyCode::Comma,
-            x11_sys::XK_slash => KeyCode::Slash,
-            x11_sys::XK_period => KeyCode::Period,
-            x11_sys::XK_Tab => KeyCode::Tab,
-            x11_sys::XK_ISO_Left_Tab => KeyCode::Tab,
-            x11_sys::XK_space => KeyCode::Space,
-            x11_sys::XK_BackSpace => KeyCode::Backspace,
-            x11_sys::XK_Escape => KeyCode::Escape,
-            x11_sys::XK_Caps_Lock => KeyCode::Capslock,
-            x11_sys::XK_KP_Decimal => KeyCode::NumpadDecimal,
-            x11_sys::XK_KP_Multiply => KeyCode::NumpadMultiply,
-            x11_sys::XK_KP_Add => KeyCode::NumpadAdd,
-            x11_sys::XK_Num_Lock => KeyCode::Numlock,
-            x11_sys::XK_KP_Divide => KeyCode::NumpadDivide,
-            x11_sys::XK_KP_Enter => KeyCode::NumpadEnter,
-            x11_sys::XK_KP_Subtract => KeyCode::NumpadSubtract,
-            //keysim::XK_9 => KeyCode::NumpadEquals,
-            x11_sys::XK_KP_0 => KeyCode::Numpad0,
-            x11_sys::XK_KP_1 => KeyCode::Numpad1,
-            x11_sys::XK_KP_2 => KeyCode::Numpad2,
-            x11_sys::XK_KP_3 => KeyCode::Numpad3,
-            x11_sys::XK_KP_4 => KeyCode::Numpad4,
-            x11_sys::XK_KP_5 => KeyCode::Numpad5,
-            x11_sys::XK_KP_6 => KeyCode::Numpad6,
-            x11_sys::XK_KP_7 => KeyCode::Numpad7,
-            x11_sys::XK_KP_8 => KeyCode::Numpad8,
-            x11_sys::XK_KP_9 => KeyCode::Numpad9,
-            
-            x11_sys::XK_F1 => KeyCode::F1,
-            x11_sys::XK_F2 => KeyCode::F2,
-            x11_sys::XK_F3 => KeyCode::F3,
-            x11_sys::XK_F4 => KeyCode::F4,
-            x11_sys::XK_F5 => KeyCode::F5,
-            x11_sys::XK_F6 => KeyCode::F6,
-            x11_sys::XK_F7 => KeyCode::F7,
-            x11_sys::XK_F8 => KeyCode::F8,
-            x11_sys::XK_F9 => KeyCode::F9,
-            x11_sys::XK_F10 => KeyCode::F10,
-            x11_sys::XK_F11 => KeyCode::F11,
-            x11_sys::XK_F12 => KeyCode::F12,
-            
-            x11_sys::XK_Print => KeyCode::PrintScreen,
-            x11_sys::XK_Home => KeyCode::Home,
-            x11_sys::XK_Page_Up => KeyCode::PageUp,
-            x11_sys::XK_Delete => KeyCode::Delete,
-            x11_sys::XK_End => KeyCode::End,
-            x11_sys::XK_Page_Down => KeyCode::PageDown,
-            x11_sys::XK_Left => KeyCode::ArrowLeft,
-            x11_sys::XK_Right => KeyCode::ArrowRight,
-            x11_sys::XK_Down => KeyCode::ArrowDown,
-            x11_sys::XK_Up => KeyCode::ArrowUp,
-            _ => KeyCode::Unknown,
-        }
+        keysym_to_key_code(keysym as u32)
     }
 
     pub unsafe fn copy_to_clipboard(&mut self, text: &String, window_id: c_ulong, time: u64) {
@@ -939,3 +805,141 @@ impl XlibAtoms {
     }
 }
 
+// xkb keysyms share their values with the X11 ones, so the Wayland backend maps them here as well
+pub fn keysym_to_key_code(keysym: u32) -> KeyCode {
+    match keysym {
+        x11_sys::XK_a => KeyCode::KeyA,
+        x11_sys::XK_A => KeyCode::KeyA,
+        x11_sys::XK_b => KeyCode::KeyB,
+        x11_sys::XK_B => KeyCode::KeyB,
+        x11_sys::XK_c => KeyCode::KeyC,
+        x11_sys::XK_C => KeyCode::KeyC,
+        x11_sys::XK_d => KeyCode::KeyD,
+        x11_sys::XK_D => KeyCode::KeyD,
+        x11_sys::XK_e => KeyCode::KeyE,
+        x11_sys::XK_E => KeyCode::KeyE,
+        x11_sys::XK_f => KeyCode::KeyF,
+        x11_sys::XK_F => KeyCode::KeyF,
+        x11_sys::XK_g => KeyCode::KeyG,
+        x11_sys::XK_G => KeyCode::KeyG,
+        x11_sys::XK_h => KeyCode::KeyH,
+        x11_sys::XK_H => KeyCode::KeyH,
+        x11_sys::XK_i => KeyCode::KeyI,
+        x11_sys::XK_I => KeyCode::KeyI,
+        x11_sys::XK_j => KeyCode::KeyJ,
+        x11_sys::XK_J => KeyCode::KeyJ,
+        x11_sys::XK_k => KeyCode::KeyK,
+        x11_sys::XK_K => KeyCode::KeyK,
+        x11_sys::XK_l => KeyCode::KeyL,
+        x11_sys::XK_L => KeyCode::KeyL,
+        x11_sys::XK_m => KeyCode::KeyM,
+        x11_sys::XK_M => KeyCode::KeyM,
+        x11_sys::XK_n => KeyCode::KeyN,
+        x11_sys::XK_N => KeyCode::KeyN,
+        x11_sys::XK_o => KeyCode::KeyO,
+        x11_sys::XK_O => KeyCode::KeyO,
+        x11_sys::XK_p => KeyCode::KeyP,
+        x11_sys::XK_P => KeyCode::KeyP,
+        x11_sys::XK_q => KeyCode::KeyQ,
+        x11_sys::XK_Q => KeyCode::KeyQ,
+        x11_sys::XK_r => KeyCode::KeyR,
+        x11_sys::XK_R => KeyCode::KeyR,
+        x11_sys::XK_s => KeyCode::KeyS,
+        x11_sys::XK_S => KeyCode::KeyS,
+        x11_sys::XK_t => KeyCode::KeyT,
+        x11_sys::XK_T => KeyCode::KeyT,
+        x11_sys::XK_u => KeyCode::KeyU,
+        x11_sys::XK_U => KeyCode::KeyU,
+        x11_sys::XK_v => KeyCode::KeyV,
+        x11_sys::XK_V => KeyCode::KeyV,
+        x11_sys::XK_w => KeyCode::KeyW,
+        x11_sys::XK_W => KeyCode::KeyW,
+        x11_sys::XK_x => KeyCode::KeyX,
+        x11_sys::XK_X => KeyCode::KeyX,
+        x11_sys::XK_y => KeyCode::KeyY,
+        x11_sys::XK_Y => KeyCode::KeyY,
+        x11_sys::XK_z => KeyCode::KeyZ,
+        x11_sys::XK_Z => KeyCode::KeyZ,
+        
+        x11_sys::XK_0 => KeyCode::Key0,
+        x11_sys::XK_1 => KeyCode::Key1,
+        x11_sys::XK_2 => KeyCode::Key2,
+        x11_sys::XK_3 => KeyCode::Key3,
+        x11_sys::XK_4 => KeyCode::Key4,
+        x11_sys::XK_5 => KeyCode::Key5,
+        x11_sys::XK_6 => KeyCode::Key6,
+        x11_sys::XK_7 => KeyCode::Key7,
+        x11_sys::XK_8 => KeyCode::Key8,
+        x11_sys::XK_9 => KeyCode::Key9,
+        
+        x11_sys::XK_Alt_L => KeyCode::Alt,
+        x11_sys::XK_Alt_R => KeyCode::Alt,
+        x11_sys::XK_Meta_L => KeyCode::Logo,
+        x11_sys::XK_Meta_R => KeyCode::Logo,
+        x11_sys::XK_Shift_L => KeyCode::Shift,
+        x11_sys::XK_Shift_R => KeyCode::Shift,
+        x11_sys::XK_Control_L => KeyCode::Control,
+        x11_sys::XK_Control_R => KeyCode::Control,
+        
+        x11_sys::XK_equal => KeyCode::Equals,
+        x11_sys::XK_minus => KeyCode::Minus,
+        x11_sys::XK_bracketright => KeyCode::RBracket,
+        x11_sys::XK_bracketleft => KeyCode::LBracket,
+        x11_sys::XK_Return => KeyCode::ReturnKey,
+        x11_sys::XK_grave => KeyCode::Backtick,
+        x11_sys::XK_semicolon => KeyCode::Semicolon,
+        x11_sys::XK_backslash => KeyCode::Backslash,
+        x11_sys::XK_comma => KeyCode::Comma,
+        x11_sys::XK_slash => KeyCode::Slash,
+        x11_sys::XK_period => KeyCode::Period,
+        x11_sys::XK_Tab => KeyCode::Tab,
+        x11_sys::XK_ISO_Left_Tab => KeyCode::Tab,
+        x11_sys::XK_space => KeyCode::Space,
+        x11_sys::XK_BackSpace => KeyCode::Backspace,
+        x11_sys::XK_Escape => KeyCode::Escape,
+        x11_sys::XK_Caps_Lock => KeyCode::Capslock,
+        x11_sys::XK_KP_Decimal => KeyCode::NumpadDecimal,
+        x11_sys::XK_KP_Multiply => KeyCode::NumpadMultiply,
+        x11_sys::XK_KP_Add => KeyCode::NumpadAdd,
+        x11_sys::XK_Num_Lock => KeyCode::Numlock,
+        x11_sys::XK_KP_Divide => KeyCode::NumpadDivide,
+        x11_sys::XK_KP_Enter => KeyCode::NumpadEnter,
+        x11_sys::XK_KP_Subtract => KeyCode::NumpadSubtract,
+        //keysim::XK_9 => KeyCode::NumpadEquals,
+        x11_sys::XK_KP_0 => KeyCode::Numpad0,
+        x11_sys::XK_KP_1 => KeyCode::Numpad1,
+        x11_sys::XK_KP_2 => KeyCode::Numpad2,
+        x11_sys::XK_KP_3 => KeyCode::Numpad3,
+        x11_sys::XK_KP_4 => KeyCode::Numpad4,
+        x11_sys::XK_KP_5 => KeyCode::Numpad5,
+        x11_sys::XK_KP_6 => KeyCode::Numpad6,
+        x11_sys::XK_KP_7 => KeyCode::Numpad7,
+        x11_sys::XK_KP_8 => KeyCode::Numpad8,
+        x11_sys::XK_KP_9 => KeyCode::Numpad9,
+        
+        x11_sys::XK_F1 => KeyCode::F1,
+        x11_sys::XK_F2 => KeyCode::F2,
+        x11_sys::XK_F3 => KeyCode::F3,
+        x11_sys::XK_F4 => KeyCode::F4,
+        x11_sys::XK_F5 => KeyCode::F5,
+        x11_sys::XK_F6 => KeyCode::F6,
+        x11_sys::XK_F7 => KeyCode::F7,
+        x11_sys::XK_F8 => KeyCode::F8,
+        x11_sys::XK_F9 => KeyCode::F9,
+        x11_sys::XK_F10 => KeyCode::F10,
+        x11_sys::XK_F11 => KeyCode::F11,
+        x11_sys::XK_F12 => KeyCode::F12,
+        
+        x11_sys::XK_Print => KeyCode::PrintScreen,
+        x11_sys::XK_Home => KeyCode::Home,
+        x11_sys::XK_Page_Up => KeyCode::PageUp,
+        x11_sys::XK_Delete => KeyCode::Delete,
+        x11_sys::XK_End => KeyCode::End,
+        x11_sys::XK_Page_Down => KeyCode::PageDown,
+        x11_sys::XK_Left => KeyCode::ArrowLeft,
+        x11_sys::XK_Right => KeyCode::ArrowRight,
+        x11_sys::XK_Down => KeyCode::ArrowDown,
+        x11_sys::XK_Up => KeyCode::ArrowUp,
+        _ => KeyCode::Unknown,
+    }
+}
@@ -16,7 +16,7 @@ use {
 
 pub struct WindowHandle(PoolId);
 
-#[derive(Clone, Debug, PartialEq, Copy)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)]
 pub struct WindowId(usize, u64);
 
 impl WindowId{
@@ -37,6 +37,10 @@ impl CxWindowPool {
     pub fn window_id_contains(&self, pos:DVec2)->(WindowId, DVec2){
         for (index,item) in self.0.pool.iter().enumerate(){
             let window = &item.item;
+            // a closed window keeps its last geometry in the pool
+            if !window.is_created{
+                continue
+            }
             if pos.x>= window.window_geom.position.x &&
                 pos.y>= window.window_geom.position.y && 
                 pos.x<= window.window_geom.position.x+window.window_geom.inner_size.x  &&
@@ -61,6 +65,10 @@ impl CxWindowPool {
         return (WindowId(0, self.0.pool[0].generation), self.0.pool[0].item.window_geom.position)
     }
     
+    pub fn id_iter(&self)->impl Iterator<Item = WindowId> + '_{
+        self.0.pool.iter().enumerate().map(|(index, item)| WindowId(index, item.generation))
+    }
+    
     pub fn is_valid(&self, v: WindowId)->bool{
         if v.0 < self.0.pool.len(){
             if self.0.pool[v.0].generation == v.1{
@@ -0,0 +1,104 @@
+use proc_macro::TokenStream;
+
+use makepad_micro_proc_macro::{TokenBuilder, TokenParser, error};
+use makepad_live_id::*;
+
+// #[bind(widget.path)], #[bind(widget.path, validate = Self::check)] or #[bind(list, template = Item)]
+struct Bind {
+    path: Vec<String>,
+    template: Option<String>,
+    validate: Option<TokenStream>,
+}
+
+fn parse_bind(args: TokenStream) -> Result<Bind, TokenStream> {
+    let mut parser = TokenParser::new(args);
+    let mut bind = Bind {path: Vec::new(), template: None, validate: None};
+    while let Some(id) = parser.eat_any_ident() {
+        bind.path.push(id);
+        if !parser.eat_punct_alone('.') {
+            break
+        }
+    }
+    if bind.path.is_empty() {
+        return Err(error("Expected a widget path in #[bind(..)]"))
+    }
+    while parser.eat_punct_alone(',') {
+        let Some(key) = parser.eat_any_ident() else {break};
+        parser.expect_punct_alone('=')?;
+        match key.as_str() {
+            "template" => bind.template = Some(parser.expect_any_ident()?),
+            "validate" => match parser.eat_ident_path() {
+                Some(path) => bind.validate = Some(path),
+                None => return Err(error("validate expects a function path, e.g. validate = Self::check"))
+            },
+            _ => return Err(error(&format!("Unknown #[bind(..)] option {}, expected template or validate", key)))
+        }
+    }
+    Ok(bind)
+}
+
+pub fn derive_data_bind_impl(input: TokenStream) -> TokenStream {
+    let mut tb = TokenBuilder::new();
+    let mut parser = TokenParser::new(input);
+    let _main_attribs = parser.eat_attributes();
+    parser.eat_ident("pub");
+    if parser.eat_ident("struct") {
+        let struct_name = match parser.expect_any_ident() {
+            Ok(name) => name,
+            Err(err) => return err
+        };
+        let generic = parser.eat_generic();
+        let types = parser.eat_all_types();
+        let where_clause = parser.eat_where_clause(None);
+
+        let fields = if types.is_some() {
+            return error("DataBind needs a struct with named fields")
+        }
+        else if let Some(fields) = parser.eat_all_struct_fields() {
+            fields
+        }
+        else {
+            return error("Unexpected field form")
+        };
+
+        tb.add("impl").stream(generic.clone());
+        tb.add("DataBind for").ident(&struct_name).stream(generic).stream(where_clause).add("{");
+        tb.add("    fn data_bind(&mut self, map: &mut DataBindingMap) {");
+        for field in &fields {
+            let Some(attr) = field.attrs.iter().find( | v | v.name == "bind") else {continue};
+            let Some(args) = &attr.args else {
+                return error("Expected #[bind(widget.path)]")
+            };
+            let bind = match parse_bind(args.clone()) {
+                Ok(bind) => bind,
+                Err(err) => return err
+            };
+            if bind.template.is_some() {
+                tb.add("        map.bind_list(");
+            }
+            else if bind.validate.is_some() {
+                tb.add("        map.bind_validated(");
+            }
+            else {
+                tb.add("        map.bind_value(");
+            }
+            tb.add("LiveId(").suf_u64(LiveId::from_str(&field.name).0).add("), &[&[");
+            for id in &bind.path {
+                tb.add("LiveId(").suf_u64(LiveId::from_str(id).0).add("),");
+            }
+            tb.add("]],");
+            if let Some(template) = &bind.template {
+                tb.add("LiveId(").suf_u64(LiveId::from_str(template).0).add("),");
+            }
+            tb.add("&mut self.").ident(&field.name);
+            if let Some(validate) = bind.validate {
+                tb.add(",").stream(Some(validate));
+            }
+            tb.add(");");
+        }
+        tb.add("    }");
+        tb.add("}");
+        return tb.end();
+    }
+    parser.unexpected()
+}
@@ -3,6 +3,9 @@ use proc_macro::TokenStream;
 mod derive_widget;
 use crate::derive_widget::*;
 
+mod derive_data_bind;
+use crate::derive_data_bind::*;
+
 
 /*
 #[proc_macro_derive(Widget)]
@@ -51,3 +54,8 @@ pub fn derive_widget_register(input: TokenStream) -> TokenStream {
 pub fn derive_widget_set(input: TokenStream) -> TokenStream {
     derive_widget_set_impl(input)
 }
+
+#[proc_macro_derive(DataBind, attributes(bind))]
+pub fn derive_data_bind(input: TokenStream) -> TokenStream {
+    derive_data_bind_impl(input)
+}
@@ -12,12 +12,18 @@ live_design!{
     import crate::drop_down::DropDownBase;
     import crate::file_tree::FileTreeBase;
     import crate::file_tree::FileTreeNodeBase;
+    import crate::data_grid::DataGridBase;
+    import crate::chart::ChartBase;
+    import crate::rich_text_input::RichTextInputBase;
     import crate::fold_button::FoldButtonBase;
     import crate::fold_header::FoldHeaderBase;
     import crate::image::ImageBase;
     import crate::multi_image::MultiImageBase;
     import crate::image_blend::ImageBlendBase;
     import crate::icon::IconBase;
+    import crate::svg::SvgBase;
+    import crate::vector_canvas::VectorCanvasBase;
+    import crate::viewport_3d::Viewport3DBase;
     import crate::rotated_image::RotatedImageBase;
     import crate::modal::ModalBase;
     import crate::tooltip::TooltipBase;
@@ -38,6 +44,10 @@ live_design!{
     import crate::scroll_bars::ScrollBarsBase;
     import crate::slide_panel::SlidePanelBase;
     import crate::slider::SliderBase;
+    import crate::number_input::NumberInputBase;
+    import crate::date_picker::DatePickerBase;
+    import crate::date_picker::CalendarPopupBase;
+    import crate::time_picker::TimePickerBase;
     import crate::slides_view::SlidesViewBase;
     import crate::splitter::SplitterBase;
     import crate::tab::TabBase;
@@ -103,6 +113,41 @@ live_design!{
         }
     }
 
+    Svg = <SvgBase> {
+        width: Fit,
+        height: Fit,
+        draw_svg: {
+            preserve_colors: true,
+            fit_view_box: true,
+        }
+    }
+
+    VectorCanvas = <VectorCanvasBase> {
+        width: Fill,
+        height: Fill,
+    }
+
+    Viewport3D = <Viewport3DBase> {
+        width: Fill,
+        height: Fill,
+        draw_bg: {
+            texture image: texture2d
+            varying scale: vec2
+            varying shift: vec2
+            fn vertex(self) -> vec4 {
+                let dpi = self.dpi_factor;
+                let ceil_size = ceil(self.rect_size * dpi) / dpi
+                let floor_pos = floor(self.rect_pos * dpi) / dpi
+                self.scale = self.rect_size / ceil_size;
+                self.shift = (self.rect_pos - floor_pos) / ceil_size;
+                return self.clip_and_transform_vertex(self.rect_pos, self.rect_size)
+            }
+            fn pixel(self) -> vec4 {
+                return sample2d_rt(self.image, self.pos * self.scale + self.shift);
+            }
+        }
+    }
+
     Icon = <IconBase> {
         width: Fit,
         height: Fit,
@@ -761,7 +806,7 @@ live_design!{
         instance border_width: 0.0
         instance border_color: #0000
         instance inset: vec4(0.0, 0.0, 0.0, 0.0)
-        instance radius: 5
+        instance radius: 5.0
         
         fn get_color(self) -> vec4 {
             return self.color
@@ -773,7 +818,7 @@ live_design!{
         
         fn pixel(self) -> vec4 {
             let sdf = Sdf2d::viewport(self.pos * self.rect_size)
-            if self.radius.x > 0.0 {
+            if self.radius > 0.0 {
                 sdf.hexagon(
                     self.rect_size.x * 0.5,
                     self.rect_size.y * 0.5,
@@ -790,9 +835,9 @@ live_design!{
                     )
                 )
             }
-            sdf.fill_keep(color)
+            sdf.fill_keep(self.get_color())
             if self.border_width > 0.0 {
-                sdf.stroke(self.border_color, self.border_width)
+                sdf.stroke(self.get_border_color(), self.border_width)
             }
             return sdf.result
         }
@@ -952,10 +997,16 @@ live_design!{
     DropDownBase = <DropDownBase> {}
     FileTreeBase = <FileTreeBase> {}
     FileTreeNodeBase = <FileTreeNodeBase> {}
+    DataGridBase = <DataGridBase> {}
+    ChartBase = <ChartBase> {}
+    RichTextInputBase = <RichTextInputBase> {}
     FoldButtonBase = <FoldButtonBase> {}
     FoldHeaderBase = <FoldHeaderBase> {}
     ImageBase = <ImageBase> {}
     IconBase = <IconBase> {}
+    SvgBase = <SvgBase> {}
+    VectorCanvasBase = <VectorCanvasBase> {}
+    Viewport3DBase = <Viewport3DBase> {}
     RotatedImageBase = <RotatedImageBase> {}
     ModalBase = <ModalBase> {}
     TooltipBase = <TooltipBase> {}
@@ -973,6 +1024,10 @@ live_design!{
     ScrollBarsBase = <ScrollBarsBase> {}
     SlidePanelBase = <SlidePanelBase> {}   
     SliderBase = <SliderBase>{}
+    NumberInputBase = <NumberInputBase>{}
+    DatePickerBase = <DatePickerBase>{}
+    CalendarPopupBase = <CalendarPopupBase>{}
+    TimePickerBase = <TimePickerBase>{}
     SlidesViewBase = <SlidesViewBase>{}
     SplitterBase = <SplitterBase>{}
     TabBase = <TabBase>{}
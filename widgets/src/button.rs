@@ -55,6 +55,10 @@ pub struct Button {
     #[live(true)]
     visible: bool,
 
+    /// Where the button goes in the tab order of its window.
+    #[live]
+    nav_order: NavOrder,
+
     /// It indicates if the hover state will be reset when the button is clicked.
     /// This could be useful for buttons that disappear when clicked, where the hover state
     /// should not be preserved.
@@ -104,6 +108,15 @@ impl Widget for Button {
                 Hit::FingerHoverOut(_) if self.enabled => {
                     self.animator_play(cx, id!(hover.off));
                 }
+                Hit::AccessAction(e) if self.enabled => if let AccessAction::Press = e.action {
+                    cx.widget_action_with_data(&self.action_data, uid, &scope.path, ButtonAction::Clicked(Default::default()));
+                }
+                Hit::KeyDown(ke) if self.enabled && !ke.is_repeat => match ke.key_code {
+                    KeyCode::Space | KeyCode::ReturnKey => {
+                        cx.widget_action_with_data(&self.action_data, uid, &scope.path, ButtonAction::Clicked(ke.modifiers));
+                    }
+                    _ => ()
+                }
                 Hit::FingerUp(fe) if self.enabled => {
                     if fe.is_over {
                         cx.widget_action_with_data(&self.action_data, uid, &scope.path, ButtonAction::Clicked(fe.modifiers));
@@ -135,6 +148,14 @@ impl Widget for Button {
         self.draw_text
             .draw_walk(cx, self.label_walk, Align::default(), self.text.as_ref());
         self.draw_bg.end(cx);
+        cx.add_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::Button)
+            .with_name(self.text.as_ref())
+            .with_states(AccessStates {disabled: !self.enabled, ..Default::default()})
+            .with_action(AccessActionKind::Press)
+            .with_area(self.draw_bg.area()));
+        if self.enabled {
+            cx.add_nav_stop_with(NavStop::new(self.draw_bg.area(), NavRole::Button).with_order(self.nav_order));
+        }
         DrawStep::done()
     }
 
@@ -0,0 +1,801 @@
+use {
+    std::{
+        collections::VecDeque,
+        ops::Range,
+    },
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        makepad_platform::event::DigitId,
+        widget::*,
+    }
+};
+
+live_design!{
+    DrawChartArea = {{DrawChartArea}} {}
+    ChartBase = {{Chart}} {}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChartKind {
+    #[default]
+    Line,
+    Bar,
+    Scatter,
+    Area,
+}
+
+/// One series of a `Chart`. The points live in a ring buffer, with a capacity set the oldest
+/// points are dropped as new ones are pushed, which is what a streaming time series wants.
+#[derive(Clone, Debug, Default)]
+pub struct ChartSeries {
+    pub name: String,
+    pub kind: ChartKind,
+    /// the color of the series, the chart picks one from its palette if there is none
+    pub color: Option<Vec4>,
+    points: VecDeque<DVec2>,
+    capacity: Option<usize>,
+    /// whether the x values only go up, which lets a draw skip to the points in view
+    sorted: bool,
+}
+
+impl ChartSeries {
+    pub fn new(name: &str, kind: ChartKind) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            sorted: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Keeps at most `capacity` points, dropping the oldest
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self.trim();
+        self
+    }
+
+    pub fn with_points(mut self, points: impl IntoIterator<Item = DVec2>) -> Self {
+        self.extend(points);
+        self
+    }
+
+    pub fn push(&mut self, point: DVec2) {
+        if self.points.back().is_some_and( | last | last.x > point.x) {
+            self.sorted = false;
+        }
+        self.points.push_back(point);
+        self.trim();
+    }
+
+    pub fn extend(&mut self, points: impl IntoIterator<Item = DVec2>) {
+        for point in points {
+            self.push(point);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.sorted = true;
+    }
+
+    pub fn points(&self) -> &VecDeque<DVec2> {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    fn trim(&mut self) {
+        if let Some(capacity) = self.capacity {
+            while self.points.len() > capacity {
+                self.points.pop_front();
+            }
+        }
+    }
+
+    /// The points between `x_min` and `x_max`, with one more on either side so lines run
+    /// to the edge of the plot
+    fn visible(&self, x_min: f64, x_max: f64) -> Range<usize> {
+        if !self.sorted {
+            return 0..self.points.len()
+        }
+        let start = self.points.partition_point( | p | p.x < x_min).saturating_sub(1);
+        let end = (self.points.partition_point( | p | p.x <= x_max) + 1).min(self.points.len());
+        start..end.max(start)
+    }
+}
+
+/// The data range shown by a `Chart`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChartView {
+    pub min: DVec2,
+    pub max: DVec2,
+}
+
+impl ChartView {
+    pub fn span(&self) -> DVec2 {
+        self.max - self.min
+    }
+}
+
+/// Round numbers between `min` and `max`, spaced 1, 2 or 5 times a power of ten apart and
+/// at most `max_count` of them.
+pub fn chart_ticks(min: f64, max: f64, max_count: usize) -> Vec<f64> {
+    if max <= min || max_count == 0 {
+        return Vec::new()
+    }
+    let step = tick_step(max - min, max_count);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    // multiplying the index keeps the error from adding up along the axis
+    (first..=last).map( | i | i as f64 * step).collect()
+}
+
+fn tick_step(span: f64, max_count: usize) -> f64 {
+    let raw = span / max_count as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0, 10.0].iter().map( | m | m * magnitude).find( | step | *step >= raw).unwrap_or(10.0 * magnitude)
+}
+
+/// A value with as many decimals as the tick step needs
+fn format_tick(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    let value = if value.abs() < step * 1e-6 {0.0} else {value};
+    format!("{:.*}", decimals, value)
+}
+
+/// Reduces the points in `range` to the first, lowest, highest and last point of every pixel
+/// column between `x_min` and `x_max`. A line through what is left draws the same pixels as
+/// one through all of them, so a million points cost a few per column. The points have to
+/// be sorted by x.
+pub fn chart_decimate(points: &VecDeque<DVec2>, range: Range<usize>, x_min: f64, x_max: f64, columns: usize, out: &mut Vec<DVec2>) {
+    out.clear();
+    if range.len() <= columns * 4 || x_max <= x_min {
+        out.extend(points.range(range));
+        return
+    }
+    let scale = columns as f64 / (x_max - x_min);
+    let mut column = None;
+    // indices of the first, lowest, highest and last point in the column
+    let mut picks = [0usize; 4];
+    let flush = | picks: &mut [usize; 4], out: &mut Vec<DVec2> | {
+        picks[1..3].sort_unstable();
+        let mut last = None;
+        for index in *picks {
+            if last != Some(index) {
+                out.push(points[index]);
+                last = Some(index);
+            }
+        }
+    };
+    for index in range {
+        let p = points[index];
+        let c = ((p.x - x_min) * scale).floor() as i64;
+        if column != Some(c) {
+            if column.is_some() {
+                flush(&mut picks, out);
+            }
+            column = Some(c);
+            picks = [index; 4];
+            continue
+        }
+        if p.y < points[picks[1]].y {
+            picks[1] = index;
+        }
+        if p.y > points[picks[2]].y {
+            picks[2] = index;
+        }
+        picks[3] = index;
+    }
+    if column.is_some() {
+        flush(&mut picks, out);
+    }
+}
+
+#[derive(Live, LiveHook, LiveRegister)]#[repr(C)]
+pub struct DrawChartArea {
+    #[deref] draw_super: DrawQuad,
+    #[live] color: Vec4,
+    /// where the line leaves the left and right edge of the quad and where the baseline is,
+    /// in pixels from its top
+    #[live] line_start: f32,
+    #[live] line_end: f32,
+    #[live] base: f32,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct Chart {
+    #[redraw] #[rust] area: Area,
+    #[walk] walk: Walk,
+    #[layout] layout: Layout,
+
+    #[live] draw_bg: DrawQuad,
+    #[live] draw_grid: DrawColor,
+    #[live] draw_line: DrawLine,
+    #[live] draw_bar: DrawColor,
+    #[live] draw_area: DrawChartArea,
+    #[live] draw_point: DrawColor,
+    #[live] draw_label: DrawText,
+    #[live] draw_legend: DrawQuad,
+    #[live] draw_tooltip: DrawQuad,
+    #[live] legend_layout: Layout,
+    #[live] tooltip_layout: Layout,
+
+    /// the series colors, in order
+    #[live] palette: Vec<Vec4>,
+    #[live] axis_color: Vec4,
+    #[live] grid_color: Vec4,
+    #[live(48.0)] y_axis_width: f64,
+    #[live(20.0)] x_axis_height: f64,
+    /// the least number of pixels between two ticks
+    #[live(80.0)] x_tick_spacing: f64,
+    #[live(40.0)] y_tick_spacing: f64,
+    #[live(1.5)] line_width: f64,
+    #[live(5.0)] point_size: f64,
+    /// the part of its slot a bar fills
+    #[live(0.8)] bar_width: f64,
+    #[live(0.3)] area_opacity: f64,
+    /// how close to a point the mouse has to be for its tooltip
+    #[live(12.0)] hover_radius: f64,
+    #[live(true)] show_legend: bool,
+    /// with an automatic view only the last `x_span` of the data is shown, 0 shows all of it
+    #[live] x_span: f64,
+    #[live(true)] zoom_x: bool,
+    #[live(true)] zoom_y: bool,
+
+    #[rust] series: Vec<ChartSeries>,
+    /// set once the view is panned or zoomed, otherwise the view follows the data
+    #[rust] fixed_view: Option<ChartView>,
+    /// the view and plot of the last draw, events work on what is on screen
+    #[rust] shown: Option<ChartView>,
+    #[rust] plot: Rect,
+    #[rust] tick_steps: DVec2,
+    #[rust] hover: Option<(usize, DVec2)>,
+    /// the fingers on the plot and where they were last
+    #[rust] fingers: Vec<(DigitId, DVec2)>,
+    #[rust] scratch: Vec<DVec2>,
+}
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ChartAction {
+    None,
+    ViewChanged(ChartView),
+    PointHovered {series: usize, point: DVec2},
+}
+
+impl Chart {
+
+    pub fn set_series(&mut self, cx: &mut Cx, series: Vec<ChartSeries>) {
+        self.series = series;
+        self.hover = None;
+        self.redraw(cx);
+    }
+
+    /// Adds a series and returns its index
+    pub fn add_series(&mut self, cx: &mut Cx, series: ChartSeries) -> usize {
+        self.series.push(series);
+        self.redraw(cx);
+        self.series.len() - 1
+    }
+
+    pub fn series(&self) -> &[ChartSeries] {
+        &self.series
+    }
+
+    /// Appends points to a series, dropping the oldest ones past its capacity
+    pub fn append(&mut self, cx: &mut Cx, series: usize, points: &[DVec2]) {
+        if let Some(s) = self.series.get_mut(series) {
+            s.extend(points.iter().cloned());
+            self.redraw(cx);
+        }
+    }
+
+    pub fn clear(&mut self, cx: &mut Cx) {
+        for series in &mut self.series {
+            series.clear();
+        }
+        self.hover = None;
+        self.redraw(cx);
+    }
+
+    /// The view of the last draw
+    pub fn view(&self) -> Option<ChartView> {
+        self.shown
+    }
+
+    /// Fixes the view, `None` makes it follow the data again
+    pub fn set_view(&mut self, cx: &mut Cx, view: Option<ChartView>) {
+        self.fixed_view = view;
+        self.redraw(cx);
+    }
+
+    fn series_color(&self, index: usize) -> Vec4 {
+        self.series[index].color.unwrap_or_else( || {
+            if self.palette.is_empty() {
+                self.axis_color
+            }
+            else {
+                self.palette[index % self.palette.len()]
+            }
+        })
+    }
+
+    /// The view that fits all the data, or the last `x_span` of it
+    fn auto_view(&self) -> ChartView {
+        let mut x = (f64::INFINITY, f64::NEG_INFINITY);
+        for series in &self.series {
+            if series.sorted {
+                if let (Some(first), Some(last)) = (series.points.front(), series.points.back()) {
+                    x = (x.0.min(first.x), x.1.max(last.x));
+                }
+            }
+            else {
+                for p in &series.points {
+                    x = (x.0.min(p.x), x.1.max(p.x));
+                }
+            }
+        }
+        if x.0 > x.1 {
+            return ChartView {min: dvec2(0.0, 0.0), max: dvec2(1.0, 1.0)}
+        }
+        if self.x_span > 0.0 {
+            x.0 = x.1 - self.x_span;
+        }
+        let mut y = (f64::INFINITY, f64::NEG_INFINITY);
+        for series in &self.series {
+            if matches!(series.kind, ChartKind::Bar | ChartKind::Area) {
+                y = (y.0.min(0.0), y.1.max(0.0));
+            }
+            for p in series.points.range(series.visible(x.0, x.1)) {
+                if p.x >= x.0 && p.x <= x.1 {
+                    y = (y.0.min(p.y), y.1.max(p.y));
+                }
+            }
+        }
+        if y.0 > y.1 {
+            y = (0.0, 1.0);
+        }
+        // bars at the ends of the data need room for their width
+        let has_bars = self.series.iter().any( | s | s.kind == ChartKind::Bar);
+        let pad = dvec2(if has_bars {(x.1 - x.0).max(1.0) * 0.05} else {0.0}, (y.1 - y.0) * 0.05);
+        let mut view = ChartView {
+            min: dvec2(x.0, y.0) - pad,
+            max: dvec2(x.1, y.1) + pad,
+        };
+        // a single value still gets a range around it
+        if view.max.x <= view.min.x {
+            view.min.x -= 0.5;
+            view.max.x += 0.5;
+        }
+        if view.max.y <= view.min.y {
+            view.min.y -= 0.5;
+            view.max.y += 0.5;
+        }
+        view
+    }
+
+    fn to_screen(&self, view: &ChartView, p: DVec2) -> DVec2 {
+        let span = view.span();
+        dvec2(
+            self.plot.pos.x + (p.x - view.min.x) / span.x * self.plot.size.x,
+            self.plot.pos.y + (view.max.y - p.y) / span.y * self.plot.size.y,
+        )
+    }
+
+    fn to_data(&self, view: &ChartView, abs: DVec2) -> DVec2 {
+        let span = view.span();
+        dvec2(
+            view.min.x + (abs.x - self.plot.pos.x) / self.plot.size.x * span.x,
+            view.max.y - (abs.y - self.plot.pos.y) / self.plot.size.y * span.y,
+        )
+    }
+
+    fn set_user_view(&mut self, cx: &mut Cx, scope: &mut Scope, view: ChartView) {
+        self.fixed_view = Some(view);
+        self.shown = Some(view);
+        self.hover = None;
+        self.redraw(cx);
+        cx.widget_action(self.widget_uid(), &scope.path, ChartAction::ViewChanged(view));
+    }
+
+    /// Moves the view by a distance in pixels
+    fn pan(&mut self, cx: &mut Cx, scope: &mut Scope, delta: DVec2) {
+        let Some(view) = self.shown else {
+            return
+        };
+        if self.plot.size.x <= 0.0 || self.plot.size.y <= 0.0 {
+            return
+        }
+        let span = view.span();
+        let shift = dvec2(
+            if self.zoom_x {-delta.x / self.plot.size.x * span.x} else {0.0},
+            if self.zoom_y {delta.y / self.plot.size.y * span.y} else {0.0},
+        );
+        self.set_user_view(cx, scope, ChartView {min: view.min + shift, max: view.max + shift});
+    }
+
+    /// Scales the view around a point on the screen, a factor above 1 zooms out
+    fn zoom(&mut self, cx: &mut Cx, scope: &mut Scope, abs: DVec2, factor: f64) {
+        let Some(view) = self.shown else {
+            return
+        };
+        let center = self.to_data(&view, abs);
+        let factor = dvec2(
+            if self.zoom_x {factor} else {1.0},
+            if self.zoom_y {factor} else {1.0},
+        );
+        self.set_user_view(cx, scope, ChartView {
+            min: center + (view.min - center) * factor,
+            max: center + (view.max - center) * factor,
+        });
+    }
+
+    /// The point closest to `abs` within the hover radius
+    fn point_at(&self, abs: DVec2) -> Option<(usize, DVec2)> {
+        let view = self.shown?;
+        if !self.plot.contains(abs) {
+            return None
+        }
+        let reach = self.hover_radius / self.plot.size.x * view.span().x;
+        let x = self.to_data(&view, abs).x;
+        let mut best = None;
+        let mut best_distance = self.hover_radius;
+        for (index, series) in self.series.iter().enumerate() {
+            for p in series.points.range(series.visible(x - reach, x + reach)) {
+                let distance = self.to_screen(&view, *p).distance(&abs);
+                if distance <= best_distance {
+                    best_distance = distance;
+                    best = Some((index, *p));
+                }
+            }
+        }
+        best
+    }
+
+    fn draw_label_in(&mut self, cx: &mut Cx2d, rect: Rect, align: Align, text: &str) {
+        cx.begin_turtle(Walk::abs_rect(rect), Layout {align, ..Layout::default()});
+        self.draw_label.draw_walk(cx, Walk::fit(), Align::default(), text);
+        cx.end_turtle();
+    }
+
+    fn draw_axes(&mut self, cx: &mut Cx2d, view: &ChartView) {
+        let plot = self.plot;
+        let x_ticks = chart_ticks(view.min.x, view.max.x, (plot.size.x / self.x_tick_spacing).floor() as usize);
+        let y_ticks = chart_ticks(view.min.y, view.max.y, (plot.size.y / self.y_tick_spacing).floor() as usize);
+        self.tick_steps = dvec2(
+            tick_step(view.span().x, (plot.size.x / self.x_tick_spacing).floor().max(1.0) as usize),
+            tick_step(view.span().y, (plot.size.y / self.y_tick_spacing).floor().max(1.0) as usize),
+        );
+        self.draw_grid.color = self.grid_color;
+        for x in &x_ticks {
+            let px = self.to_screen(view, dvec2(*x, 0.0)).x.floor();
+            self.draw_grid.draw_abs(cx, Rect {pos: dvec2(px, plot.pos.y), size: dvec2(1.0, plot.size.y)});
+        }
+        for y in &y_ticks {
+            let py = self.to_screen(view, dvec2(0.0, *y)).y.floor();
+            self.draw_grid.draw_abs(cx, Rect {pos: dvec2(plot.pos.x, py), size: dvec2(plot.size.x, 1.0)});
+        }
+        self.draw_grid.color = self.axis_color;
+        self.draw_grid.draw_abs(cx, Rect {pos: dvec2(plot.pos.x, plot.pos.y), size: dvec2(1.0, plot.size.y)});
+        self.draw_grid.draw_abs(cx, Rect {pos: dvec2(plot.pos.x, plot.pos.y + plot.size.y - 1.0), size: dvec2(plot.size.x, 1.0)});
+
+        let steps = self.tick_steps;
+        for x in x_ticks {
+            let px = self.to_screen(view, dvec2(x, 0.0)).x;
+            let rect = Rect {
+                pos: dvec2(px - self.x_tick_spacing * 0.5, plot.pos.y + plot.size.y),
+                size: dvec2(self.x_tick_spacing, self.x_axis_height)
+            };
+            self.draw_label_in(cx, rect, Align {x: 0.5, y: 0.5}, &format_tick(x, steps.x));
+        }
+        for y in y_ticks {
+            let py = self.to_screen(view, dvec2(0.0, y)).y;
+            let rect = Rect {
+                pos: dvec2(plot.pos.x - self.y_axis_width, py - self.y_tick_spacing * 0.5),
+                size: dvec2(self.y_axis_width - 4.0, self.y_tick_spacing)
+            };
+            self.draw_label_in(cx, rect, Align {x: 1.0, y: 0.5}, &format_tick(y, steps.y));
+        }
+    }
+
+    fn draw_series(&mut self, cx: &mut Cx2d, view: &ChartView) {
+        let plot = self.plot;
+        let columns = plot.size.x.ceil().max(1.0) as usize;
+        let base = self.to_screen(view, dvec2(0.0, 0.0)).y.clamp(plot.pos.y, plot.pos.y + plot.size.y);
+        let bar_series: Vec<usize> = (0..self.series.len()).filter( | i | self.series[*i].kind == ChartKind::Bar).collect();
+        let mut points = std::mem::take(&mut self.scratch);
+
+        for index in 0..self.series.len() {
+            let color = self.series_color(index);
+            let series = &self.series[index];
+            let kind = series.kind;
+            let range = series.visible(view.min.x, view.max.x);
+            let visible = range.len();
+            // scattered points can't be joined per column, they are thinned out per pixel below
+            if series.sorted && kind != ChartKind::Scatter {
+                chart_decimate(&series.points, range, view.min.x, view.max.x, columns, &mut points);
+            }
+            else {
+                points.clear();
+                points.extend(series.points.range(range));
+            }
+            let dense = visible > points.len();
+            match kind {
+                ChartKind::Line => {
+                    for pair in points.windows(2) {
+                        let a = self.to_screen(view, pair[0]);
+                        let b = self.to_screen(view, pair[1]);
+                        self.draw_line.draw_line_abs(cx, a, b, color, self.line_width);
+                    }
+                }
+                ChartKind::Area => {
+                    self.draw_area.color = vec4(color.x, color.y, color.z, color.w * self.area_opacity as f32);
+                    for pair in points.windows(2) {
+                        let a = self.to_screen(view, pair[0]);
+                        let b = self.to_screen(view, pair[1]);
+                        if b.x - a.x < 0.01 {
+                            continue
+                        }
+                        let top = a.y.min(b.y).min(base);
+                        let bottom = a.y.max(b.y).max(base);
+                        self.draw_area.line_start = (a.y - top) as f32;
+                        self.draw_area.line_end = (b.y - top) as f32;
+                        self.draw_area.base = (base - top) as f32;
+                        self.draw_area.draw_abs(cx, Rect {pos: dvec2(a.x, top), size: dvec2(b.x - a.x, bottom - top)});
+                    }
+                    for pair in points.windows(2) {
+                        let a = self.to_screen(view, pair[0]);
+                        let b = self.to_screen(view, pair[1]);
+                        self.draw_line.draw_line_abs(cx, a, b, color, self.line_width);
+                    }
+                }
+                ChartKind::Bar => {
+                    // bars of several series stand side by side in the slot of their x
+                    let (width, offset) = if dense || points.len() < 2 {
+                        let slot = if points.len() < 2 {plot.size.x * 0.1} else {1.0};
+                        (slot, 0.0)
+                    }
+                    else {
+                        let first = self.to_screen(view, points[0]).x;
+                        let last = self.to_screen(view, points[points.len() - 1]).x;
+                        let slot = (last - first) / (points.len() - 1) as f64 * self.bar_width;
+                        let group = bar_series.len().max(1) as f64;
+                        let order = bar_series.iter().position( | i | *i == index).unwrap_or(0) as f64;
+                        (slot / group, slot * (order / group - 0.5))
+                    };
+                    self.draw_bar.color = color;
+                    for p in &points {
+                        let p = self.to_screen(view, *p);
+                        let top = p.y.min(base);
+                        self.draw_bar.draw_abs(cx, Rect {
+                            pos: dvec2(p.x + offset, top),
+                            size: dvec2(width.max(1.0), (p.y.max(base) - top).max(1.0))
+                        });
+                    }
+                }
+                ChartKind::Scatter => {
+                    // a point that lands on a pixel another point already covered adds nothing
+                    let cells = dvec2(plot.size.x.ceil() + 1.0, plot.size.y.ceil() + 1.0);
+                    let mut covered = vec![0u64; (cells.x * cells.y) as usize / 64 + 1];
+                    self.draw_point.color = color;
+                    let size = self.point_size;
+                    for p in &points {
+                        let p = self.to_screen(view, *p);
+                        let cell = (p - plot.pos).floor();
+                        if cell.x < 0.0 || cell.y < 0.0 || cell.x >= cells.x || cell.y >= cells.y {
+                            continue
+                        }
+                        let bit = (cell.y * cells.x + cell.x) as usize;
+                        if covered[bit / 64] & (1 << (bit % 64)) != 0 {
+                            continue
+                        }
+                        covered[bit / 64] |= 1 << (bit % 64);
+                        self.draw_point.draw_abs(cx, Rect {pos: p - dvec2(size, size) * 0.5, size: dvec2(size, size)});
+                    }
+                }
+            }
+        }
+        self.scratch = points;
+
+        if let Some((index, point)) = self.hover {
+            if index < self.series.len() {
+                self.draw_point.color = self.series_color(index);
+                let size = self.point_size * 2.0;
+                let p = self.to_screen(view, point);
+                self.draw_point.draw_abs(cx, Rect {pos: p - dvec2(size, size) * 0.5, size: dvec2(size, size)});
+            }
+        }
+    }
+
+    fn draw_legend(&mut self, cx: &mut Cx2d) {
+        if !self.show_legend || self.series.is_empty() {
+            return
+        }
+        // the legend sits in the top right corner of the plot
+        cx.begin_turtle(Walk::abs_rect(self.plot), Layout {align: Align {x: 1.0, y: 0.0}, ..Layout::default()});
+        self.draw_legend.begin(cx, Walk::fit(), self.legend_layout);
+        let size = self.point_size * 1.5;
+        for index in 0..self.series.len() {
+            self.draw_point.color = self.series_color(index);
+            self.draw_point.draw_walk(cx, Walk::fixed(size, size));
+            let name = self.series[index].name.clone();
+            self.draw_label.draw_walk(cx, Walk::fit(), Align::default(), &name);
+        }
+        self.draw_legend.end(cx);
+        cx.end_turtle();
+    }
+
+    fn draw_tooltip(&mut self, cx: &mut Cx2d, view: &ChartView) {
+        let Some((index, point)) = self.hover else {
+            return
+        };
+        let Some(series) = self.series.get(index) else {
+            return
+        };
+        let text = format!(
+            "{}: {}, {}",
+            series.name,
+            format_tick(point.x, self.tick_steps.x / 10.0),
+            format_tick(point.y, self.tick_steps.y / 10.0)
+        );
+        let pos = self.to_screen(view, point) + dvec2(self.hover_radius, -self.hover_radius);
+        self.draw_tooltip.begin(cx, Walk {abs_pos: Some(pos), ..Walk::fit()}, self.tooltip_layout);
+        // the tooltip covers the labels, so its text can't join their draw call
+        self.draw_label.new_draw_call(cx);
+        self.draw_label.draw_walk(cx, Walk::fit(), Align::default(), &text);
+        self.draw_tooltip.end(cx);
+    }
+
+    fn draw_chart(&mut self, cx: &mut Cx2d, walk: Walk) {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.draw_bg.draw_abs(cx, rect);
+        let padding = self.layout.padding;
+        self.plot = Rect {
+            pos: rect.pos + dvec2(padding.left + self.y_axis_width, padding.top),
+            size: dvec2(
+                (rect.size.x - padding.left - padding.right - self.y_axis_width).max(0.0),
+                (rect.size.y - padding.top - padding.bottom - self.x_axis_height).max(0.0),
+            )
+        };
+        if self.plot.size.x <= 0.0 || self.plot.size.y <= 0.0 {
+            return
+        }
+        let view = self.fixed_view.unwrap_or_else( || self.auto_view());
+        self.shown = Some(view);
+
+        self.draw_axes(cx, &view);
+        // the series are clipped to the plot
+        cx.begin_turtle(Walk::abs_rect(self.plot), Layout::default());
+        self.draw_series(cx, &view);
+        cx.end_turtle();
+        self.draw_legend(cx);
+        self.draw_tooltip(cx, &view);
+    }
+}
+
+impl Widget for Chart {
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        match event.hits(cx, self.area) {
+            Hit::FingerHoverOver(fe) | Hit::FingerHoverIn(fe) => {
+                let hover = self.point_at(fe.abs);
+                if hover != self.hover {
+                    self.hover = hover;
+                    self.redraw(cx);
+                    if let Some((series, point)) = hover {
+                        cx.widget_action(self.widget_uid(), &scope.path, ChartAction::PointHovered {series, point});
+                    }
+                }
+            }
+            Hit::FingerHoverOut(_) if self.hover.take().is_some() => {
+                self.redraw(cx);
+            }
+            Hit::FingerDown(fe) => {
+                if fe.tap_count == 2 && self.fixed_view.is_some() {
+                    self.fixed_view = None;
+                    self.redraw(cx);
+                }
+                self.fingers.retain( | (digit, _) | *digit != fe.digit_id);
+                self.fingers.push((fe.digit_id, fe.abs));
+            }
+            Hit::FingerMove(fe) => {
+                let Some(finger) = self.fingers.iter().position( | (digit, _) | *digit == fe.digit_id) else {
+                    return
+                };
+                let last = self.fingers[finger].1;
+                self.fingers[finger].1 = fe.abs;
+                if self.fingers.len() >= 2 {
+                    // two fingers pinch, zooming around the middle between them
+                    let other = self.fingers[if finger == 0 {1} else {0}].1;
+                    let before = last.distance(&other);
+                    let after = fe.abs.distance(&other);
+                    if before > 0.0 && after > 0.0 {
+                        self.zoom(cx, scope, (fe.abs + other) * 0.5, before / after);
+                    }
+                    self.pan(cx, scope, (fe.abs - last) * 0.5);
+                }
+                else {
+                    self.pan(cx, scope, fe.abs - last);
+                }
+            }
+            Hit::FingerUp(fe) => {
+                self.fingers.retain( | (digit, _) | *digit != fe.digit_id);
+            }
+            Hit::FingerScroll(se) => {
+                // a wheel zooms, a trackpad pans unless it pinches, which comes with control held
+                if se.device.is_mouse() || se.modifiers.control {
+                    self.zoom(cx, scope, se.abs, (se.scroll.y * 0.002).exp());
+                }
+                else {
+                    self.pan(cx, scope, -se.scroll);
+                }
+            }
+            _ => ()
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.draw_chart(cx, walk);
+        DrawStep::done()
+    }
+}
+
+impl ChartRef {
+    pub fn set_series(&self, cx: &mut Cx, series: Vec<ChartSeries>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_series(cx, series);
+        }
+    }
+
+    pub fn add_series(&self, cx: &mut Cx, series: ChartSeries) -> Option<usize> {
+        self.borrow_mut().map( | mut inner | inner.add_series(cx, series))
+    }
+
+    pub fn append(&self, cx: &mut Cx, series: usize, points: &[DVec2]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.append(cx, series, points);
+        }
+    }
+
+    pub fn clear(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.clear(cx);
+        }
+    }
+
+    pub fn view(&self) -> Option<ChartView> {
+        self.borrow().and_then( | inner | inner.view())
+    }
+
+    pub fn set_view(&self, cx: &mut Cx, view: Option<ChartView>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_view(cx, view);
+        }
+    }
+
+    pub fn view_changed(&self, actions: &Actions) -> Option<ChartView> {
+        actions.filter_widget_actions_cast::<ChartAction>(self.widget_uid()).find_map( | a | match a {
+            ChartAction::ViewChanged(view) => Some(view),
+            _ => None
+        })
+    }
+
+    pub fn point_hovered(&self, actions: &Actions) -> Option<(usize, DVec2)> {
+        actions.filter_widget_actions_cast::<ChartAction>(self.widget_uid()).find_map( | a | match a {
+            ChartAction::PointHovered {series, point} => Some((series, point)),
+            _ => None
+        })
+    }
+}
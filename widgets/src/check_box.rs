@@ -49,6 +49,7 @@ pub struct CheckBox {
     #[live] text: ArcStringMut,
     
     #[live] bind: String,
+    #[live] nav_order: NavOrder,
     #[action_data] #[rust] action_data: WidgetActionData,
 }
 
@@ -72,6 +73,26 @@ impl CheckBox {
         self.draw_icon.draw_walk(cx, self.icon_walk);
         self.draw_text.draw_walk(cx, self.label_walk, self.label_align, self.text.as_ref());
         self.draw_check.end(cx);
+        cx.add_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::CheckBox)
+            .with_name(self.text.as_ref())
+            .with_states(AccessStates {checked: Some(self.animator_in_state(cx, id!(selected.on))), ..Default::default()})
+            .with_action(AccessActionKind::Press)
+            .with_area(self.draw_check.area()));
+        cx.add_nav_stop_with(NavStop::new(self.draw_check.area(), NavRole::CheckBox).with_order(self.nav_order));
+    }
+    
+    fn toggle(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        let uid = self.widget_uid();
+        if self.animator_in_state(cx, id!(selected.on)) {
+            self.animator_play(cx, id!(selected.off));
+            cx.widget_action_with_data(&self.action_data, uid, &scope.path, CheckBoxAction::Change(false));
+        }
+        else {
+            self.animator_play(cx, id!(selected.on));
+            cx.widget_action_with_data(&self.action_data, uid, &scope.path, CheckBoxAction::Change(true));
+        }
+        // the animation alone doesn't redraw, the accessibility tree needs the new state
+        self.draw_check.redraw(cx);
     }
 }
 
@@ -96,7 +117,6 @@ impl Widget for CheckBox {
     }
     
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
-        let uid = self.widget_uid();
         self.animator_handle_event(cx, event);
                 
         match event.hits(cx, self.draw_check.area()) {
@@ -108,15 +128,23 @@ impl Widget for CheckBox {
                 self.animator_play(cx, id!(hover.off));
             },
             Hit::FingerDown(_fe) => {
-                if self.animator_in_state(cx, id!(selected.on)) {
-                    self.animator_play(cx, id!(selected.off));
-                    cx.widget_action_with_data(&self.action_data, uid, &scope.path, CheckBoxAction::Change(false));
-                }
-                else {
-                    self.animator_play(cx, id!(selected.on));
-                    cx.widget_action_with_data(&self.action_data, uid, &scope.path, CheckBoxAction::Change(true));
-                }
+                self.toggle(cx, scope);
+            },
+            Hit::AccessAction(e) => if let AccessAction::Press = e.action {
+                self.toggle(cx, scope);
+            },
+            Hit::KeyDown(ke) if !ke.is_repeat => match ke.key_code {
+                KeyCode::Space | KeyCode::ReturnKey => self.toggle(cx, scope),
+                _ => ()
             },
+            Hit::KeyFocus(_) => {
+                self.animator_play(cx, id!(focus.on));
+                self.draw_check.redraw(cx);
+            }
+            Hit::KeyFocusLost(_) => {
+                self.animator_play(cx, id!(focus.off));
+                self.draw_check.redraw(cx);
+            }
             Hit::FingerUp(_fe) => {
                                 
             }
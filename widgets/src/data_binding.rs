@@ -1,6 +1,10 @@
-use crate::{
-    makepad_platform::*,
-    widget::*,
+use {
+    std::sync::Arc,
+    crate::{
+        makepad_platform::*,
+        widget::*,
+        portal_list::PortalListWidgetRefExt,
+    }
 };
 
 #[derive(Debug)]
@@ -46,7 +50,10 @@ impl DataBindingStore {
 
 enum Direction<'a> {
     DataToWidgets(&'a DataBindingStore),
-    WidgetsToData(&'a Actions, &'a mut DataBindingStore)
+    WidgetsToData(&'a Actions, &'a mut DataBindingStore),
+    FieldsToWidgets,
+    WidgetsToFields(&'a Actions, &'a mut Vec<Vec<LiveId>>),
+    RecordFields(&'a mut DataBoundItem),
 }
 
 pub struct DataBindingMap<'a> {
@@ -54,6 +61,160 @@ pub struct DataBindingMap<'a> {
     pub cx: &'a mut Cx,
     direction: Direction<'a>,
     pub ui: &'a WidgetRef,
+    // where the fields being bound sit in the root struct, for the change paths of list items
+    path: Vec<LiveId>,
+}
+
+/// A struct whose fields are bound to widgets, usually implemented with `#[derive(DataBind)]`:
+///
+/// ```ignore
+/// #[derive(DataBind)]
+/// struct Profile {
+///     #[bind(form.name, validate = Self::check_name)] name: String,
+///     #[bind(form.age)] age: u32,
+///     #[bind(todo_list, template = TodoItem)] todos: Vec<Todo>,
+/// }
+/// ```
+///
+/// A list field drives the items of the `PortalList` it's bound to, its items are bound
+/// within the item widget using the `template` given.
+pub trait DataBind {
+    /// Binds every field to its widgets, the map decides which way the values flow
+    fn data_bind(&mut self, map: &mut DataBindingMap);
+
+    /// Shows the fields in their widgets
+    fn data_to_widgets(&mut self, cx: &mut Cx, ui: &WidgetRef) where Self: Sized {
+        self.data_bind(&mut DataBindingMap {
+            debug_missing: false,
+            cx,
+            direction: Direction::FieldsToWidgets,
+            ui,
+            path: Vec::new(),
+        });
+    }
+
+    /// Reads the edits in `actions` back into the fields, returning the paths of the fields
+    /// that changed. List items are in a path by their index, as `LiveId(index)`.
+    fn widgets_to_data(&mut self, cx: &mut Cx, actions: &Actions, ui: &WidgetRef) -> Vec<Vec<LiveId>> where Self: Sized {
+        let mut changed = Vec::new();
+        self.data_bind(&mut DataBindingMap {
+            debug_missing: false,
+            cx,
+            direction: Direction::WidgetsToFields(actions, &mut changed),
+            ui,
+            path: Vec::new(),
+        });
+        changed
+    }
+}
+
+/// A field type that can be bound to widgets, converting to and from the values they read and write
+pub trait DataValue: Sized + PartialEq {
+    fn to_live_value(&self) -> LiveValue;
+    /// The error is shown on the widget that wrote the value
+    fn from_live_value(value: &LiveValue) -> Result<Self, String>;
+}
+
+impl DataValue for LiveValue {
+    fn to_live_value(&self) -> LiveValue {
+        self.clone()
+    }
+
+    fn from_live_value(value: &LiveValue) -> Result<Self, String> {
+        Ok(value.clone())
+    }
+}
+
+impl DataValue for String {
+    fn to_live_value(&self) -> LiveValue {
+        LiveValue::String(Arc::new(self.clone()))
+    }
+
+    fn from_live_value(value: &LiveValue) -> Result<Self, String> {
+        value.as_str().map( | v | v.to_string()).ok_or_else( | | "Enter some text".to_string())
+    }
+}
+
+impl DataValue for bool {
+    fn to_live_value(&self) -> LiveValue {
+        LiveValue::Bool(*self)
+    }
+
+    fn from_live_value(value: &LiveValue) -> Result<Self, String> {
+        value.as_bool().ok_or_else( | | "Expected yes or no".to_string())
+    }
+}
+
+macro_rules! impl_data_value_float {
+    ($($ty:ty),*) => {$(
+        impl DataValue for $ty {
+            fn to_live_value(&self) -> LiveValue {
+                LiveValue::Float64(*self as f64)
+            }
+
+            fn from_live_value(value: &LiveValue) -> Result<Self, String> {
+                match value.as_str() {
+                    Some(text) => text.trim().parse::<f64>().ok().filter( | v | v.is_finite()),
+                    None => value.as_float()
+                }.map( | v | v as $ty).ok_or_else( | | "Enter a number".to_string())
+            }
+        }
+    )*}
+}
+
+impl_data_value_float!(f32, f64);
+
+macro_rules! impl_data_value_int {
+    ($($ty:ty),*) => {$(
+        impl DataValue for $ty {
+            fn to_live_value(&self) -> LiveValue {
+                LiveValue::Int64(*self as i64)
+            }
+
+            fn from_live_value(value: &LiveValue) -> Result<Self, String> {
+                let v = match value.as_str() {
+                    Some(text) => text.trim().parse::<i64>().ok(),
+                    None => value.as_float().filter( | v | v.fract() == 0.0).map( | v | v as i64)
+                }.ok_or_else( | | "Enter a whole number".to_string())?;
+                <$ty>::try_from(v).map_err( | _ | format!("Enter a number from {} to {}", <$ty>::MIN, <$ty>::MAX))
+            }
+        }
+    )*}
+}
+
+impl_data_value_int!(i32, i64, u8, u16, u32, u64, usize);
+
+/// The fields of one item of a bound list, recorded so the `PortalList` can fill in
+/// the item widgets as it creates them
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DataBoundItem {
+    nodes: Vec<LiveNode>,
+    binds: Vec<(LiveId, Vec<Vec<LiveId>>)>,
+}
+
+impl DataBoundItem {
+    pub fn data_to_widgets(&self, cx: &mut Cx, item: &WidgetRef) {
+        for (data_id, widgets) in &self.binds {
+            for path in widgets {
+                for widget in item.widgets(&[path]).iter() {
+                    widget.data_to_widget(cx, &self.nodes, &[*data_id]);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataBoundList {
+    pub template: LiveId,
+    pub items: Vec<DataBoundItem>,
+}
+
+fn empty_nodes() -> Vec<LiveNode> {
+    let mut nodes = Vec::new();
+    nodes.open_object(LiveId(0));
+    nodes.close();
+    nodes
 }
 
 impl DataBindingStore {
@@ -63,6 +224,7 @@ impl DataBindingStore {
             direction: Direction::DataToWidgets(self),
             cx,
             ui,
+            path: Vec::new(),
         }
     }
     
@@ -72,6 +234,7 @@ impl DataBindingStore {
             direction: Direction::WidgetsToData(actions, self),
             cx,
             ui,
+            path: Vec::new(),
         }
     }
 }
@@ -116,6 +279,111 @@ impl<'a> DataBindingMap<'a> {
                     log!("No widgets found for databinding {:?}", widgets);
                 }
             }
+            _ => ()
+        }
+    }
+    
+    /// Binds a field of a `DataBind` struct to widgets
+    pub fn bind_value<T: DataValue>(&mut self, data_id: LiveId, widgets: &[&[LiveId]], value: &mut T) {
+        self.bind_validated(data_id, widgets, value, | _ | Ok(()))
+    }
+    
+    /// Binds a field of a `DataBind` struct to widgets, the values the widgets write are only
+    /// taken when `validate` accepts them, otherwise its error is shown on the widget
+    pub fn bind_validated<T: DataValue>(&mut self, data_id: LiveId, widgets: &[&[LiveId]], value: &mut T, validate: fn(&T) -> Result<(), String>) {
+        match &mut self.direction {
+            Direction::FieldsToWidgets => {
+                let mut nodes = empty_nodes();
+                nodes.write_field_value(&[data_id], value.to_live_value());
+                let mut any_found = false;
+                for widget in self.ui.widgets(widgets).iter() {
+                    any_found = true;
+                    widget.data_to_widget(self.cx, &nodes, &[data_id]);
+                }
+                if !any_found && self.debug_missing {
+                    log!("No widgets found for databinding {:?}", widgets);
+                }
+            }
+            Direction::WidgetsToFields(actions, changed) => {
+                if actions.is_empty() {
+                    return
+                }
+                for widget in self.ui.widgets(widgets).iter() {
+                    let mut nodes = empty_nodes();
+                    if !widget.widget_to_data(self.cx, actions, &mut nodes, &[data_id]) {
+                        continue
+                    }
+                    let Some(new_value) = nodes.read_field_value(&[data_id]) else {continue};
+                    match T::from_live_value(new_value).and_then( | v | validate(&v).map( | _ | v)) {
+                        Ok(new_value) => {
+                            widget.set_data_error(self.cx, None);
+                            if new_value != *value {
+                                *value = new_value;
+                                let mut path = self.path.clone();
+                                path.push(data_id);
+                                changed.push(path);
+                            }
+                        }
+                        Err(error) => {
+                            widget.set_data_error(self.cx, Some(&error));
+                        }
+                    }
+                }
+            }
+            Direction::RecordFields(item) => {
+                item.nodes.write_field_value(&[data_id], value.to_live_value());
+                item.binds.push((data_id, widgets.iter().map( | path | path.to_vec()).collect()));
+            }
+            _ => ()
+        }
+    }
+    
+    /// Binds a list of `DataBind` items to a `PortalList`, which then draws an item from
+    /// `template` for each of them. Lists within the items aren't bound.
+    pub fn bind_list<T: DataBind>(&mut self, data_id: LiveId, widgets: &[&[LiveId]], template: LiveId, items: &mut [T]) {
+        match &mut self.direction {
+            Direction::FieldsToWidgets => {
+                let mut list = DataBoundList {template, items: Vec::new()};
+                for data in items.iter_mut() {
+                    let mut item = DataBoundItem {nodes: empty_nodes(), binds: Vec::new()};
+                    data.data_bind(&mut DataBindingMap {
+                        debug_missing: self.debug_missing,
+                        cx: &mut *self.cx,
+                        direction: Direction::RecordFields(&mut item),
+                        ui: self.ui,
+                        path: Vec::new(),
+                    });
+                    list.items.push(item);
+                }
+                for widget in self.ui.widgets(widgets).iter() {
+                    if let Some(mut portal_list) = widget.as_portal_list().borrow_mut() {
+                        portal_list.set_bound_items(self.cx, list.clone());
+                    }
+                }
+            }
+            Direction::WidgetsToFields(actions, changed) => {
+                if actions.is_empty() {
+                    return
+                }
+                for widget in self.ui.widgets(widgets).iter() {
+                    let mut with_actions = widget.as_portal_list().items_with_actions(actions);
+                    with_actions.sort_by_key( | (index, _) | *index);
+                    with_actions.dedup_by_key( | (index, _) | *index);
+                    for (index, item) in with_actions {
+                        let Some(data) = items.get_mut(index) else {continue};
+                        let mut path = self.path.clone();
+                        path.extend([data_id, LiveId(index as u64)]);
+                        data.data_bind(&mut DataBindingMap {
+                            debug_missing: self.debug_missing,
+                            cx: &mut *self.cx,
+                            direction: Direction::WidgetsToFields(actions, changed),
+                            ui: &item,
+                            path,
+                        });
+                    }
+                }
+            }
+            _ => ()
         }
     }
     
@@ -0,0 +1,782 @@
+use {
+    std::{
+        cell::RefCell,
+        ops::Range,
+        rc::Rc,
+    },
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        widget::*,
+        drop_down::*,
+        text_input::*,
+        scroll_bars::ScrollBars,
+    }
+};
+
+live_design!{
+    DrawGridCell = {{DrawGridCell}} {}
+    DrawGridHeader = {{DrawGridHeader}} {}
+    DataGridBase = {{DataGrid}} {}
+}
+
+/// The data behind a `DataGrid`. The grid only asks for the cells it is about to draw, so a
+/// source can serve millions of rows straight from wherever the data lives.
+pub trait DataGridSource {
+    fn row_count(&self) -> usize;
+    fn column_count(&self) -> usize;
+    fn column_title(&self, column: usize) -> String;
+    fn cell_text(&self, row: usize, column: usize) -> String;
+
+    /// How a cell is edited in place, cells are read only by default
+    fn cell_editor(&self, _row: usize, _column: usize) -> DataGridEditor {
+        DataGridEditor::None
+    }
+
+    /// Called when an in-place edit is committed
+    fn set_cell_text(&mut self, _row: usize, _column: usize, _text: &str) {}
+
+    /// Reorders the rows by a column, returns false if the source can't sort by it
+    fn sort_by(&mut self, _column: usize, _ascending: bool) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataGridEditor {
+    None,
+    Text,
+    /// picks one of the labels
+    DropDown(Vec<String>),
+}
+
+/// The selected cells, the columns are data columns in the order they are shown
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataGridSelection {
+    pub rows: Range<usize>,
+    pub columns: Vec<usize>,
+}
+
+#[derive(Live, LiveHook, LiveRegister)]#[repr(C)]
+struct DrawGridCell {
+    #[deref] draw_super: DrawQuad,
+    #[live] is_even: f32,
+    #[live] selected: f32,
+    #[live] cursor: f32,
+    #[live] focussed: f32,
+}
+
+#[derive(Live, LiveHook, LiveRegister)]#[repr(C)]
+struct DrawGridHeader {
+    #[deref] draw_super: DrawQuad,
+    /// 1.0 when sorted ascending, -1.0 when sorted descending
+    #[live] sort: f32,
+    #[live] dragging: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct GridCell {
+    row: usize,
+    /// the column as shown, see `column_order`
+    column: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum GridDrag {
+    Select,
+    Resize {column: usize, start_width: f64},
+    Header {column: usize, moved: bool, target: usize},
+}
+
+enum GridHit {
+    Header(usize),
+    HeaderEdge(usize),
+    Cell(GridCell),
+}
+
+struct GridEditor {
+    cell: GridCell,
+    widget: WidgetRef,
+    labels: Vec<String>,
+    focus_pending: bool,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct DataGrid {
+    #[redraw] #[live] scroll_bars: ScrollBars,
+    #[walk] walk: Walk,
+    #[layout] layout: Layout,
+
+    #[live] draw_bg: DrawQuad,
+    #[live] draw_cell: DrawGridCell,
+    #[live] draw_header: DrawGridHeader,
+    #[live] draw_cell_text: DrawText,
+    #[live] draw_header_text: DrawText,
+    #[live] draw_marker: DrawQuad,
+    #[live] cell_layout: Layout,
+
+    #[live] text_editor: Option<LivePtr>,
+    #[live] drop_down_editor: Option<LivePtr>,
+
+    #[live(23.0)] row_height: f64,
+    #[live(25.0)] header_height: f64,
+    #[live(100.0)] column_width: f64,
+    #[live(30.0)] min_column_width: f64,
+    /// how close to the right edge of a header the column can be resized
+    #[live(4.0)] resize_handle_width: f64,
+    #[live(4.0)] min_drag_distance: f64,
+    #[live] nav_order: NavOrder,
+
+    #[rust] source: Option<Rc<RefCell<dyn DataGridSource >>>,
+    /// the data column shown at every position
+    #[rust] column_order: Vec<usize>,
+    /// widths by data column
+    #[rust] column_widths: Vec<f64>,
+    /// where every shown column starts, with the total width at the end
+    #[rust] column_x: Vec<f64>,
+    #[rust] sort: Option<(usize, bool)>,
+    #[rust] anchor: Option<GridCell>,
+    #[rust] cursor: GridCell,
+    #[rust] drag: Option<GridDrag>,
+    #[rust] editor: Option<GridEditor>,
+}
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum DataGridAction {
+    None,
+    SelectionChanged,
+    CellEdited {row: usize, column: usize, text: String},
+    SortChanged {column: usize, ascending: bool},
+    ColumnResized {column: usize, width: f64},
+    ColumnMoved {column: usize, to: usize},
+}
+
+impl DataGrid {
+
+    pub fn set_source(&mut self, cx: &mut Cx, source: Rc<RefCell<dyn DataGridSource >>) {
+        self.source = Some(source);
+        self.column_order.clear();
+        self.sort = None;
+        self.anchor = None;
+        self.cursor = GridCell::default();
+        self.editor = None;
+        self.sync_columns();
+        self.scroll_bars.redraw(cx);
+    }
+
+    pub fn selection(&self) -> Option<DataGridSelection> {
+        let anchor = self.anchor?;
+        let rows = anchor.row.min(self.cursor.row)..anchor.row.max(self.cursor.row) + 1;
+        let columns = anchor.column.min(self.cursor.column)..anchor.column.max(self.cursor.column) + 1;
+        Some(DataGridSelection {
+            rows,
+            columns: self.column_order.get(columns).unwrap_or_default().to_vec(),
+        })
+    }
+
+    pub fn sort(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    /// The data columns in the order they are shown
+    pub fn column_order(&self) -> &[usize] {
+        &self.column_order
+    }
+
+    pub fn column_width(&self, column: usize) -> f64 {
+        self.column_widths.get(column).cloned().unwrap_or(self.column_width)
+    }
+
+    pub fn set_column_width(&mut self, cx: &mut Cx, column: usize, width: f64) {
+        if let Some(w) = self.column_widths.get_mut(column) {
+            *w = width.max(self.min_column_width);
+            self.scroll_bars.redraw(cx);
+        }
+    }
+
+    /// The selected cells as tab separated values, one line per row
+    pub fn selection_tsv(&self) -> Option<String> {
+        let source = self.source.as_ref()?.borrow();
+        let selection = self.selection()?;
+        let mut tsv = String::new();
+        for row in selection.rows {
+            for (i, column) in selection.columns.iter().enumerate() {
+                if i > 0 {
+                    tsv.push('\t');
+                }
+                tsv.extend(source.cell_text(row, *column).chars().map( | c | if c == '\t' || c == '\n' {' '} else {c}));
+            }
+            tsv.push('\n');
+        }
+        Some(tsv)
+    }
+
+    fn sync_columns(&mut self) {
+        let count = self.source.as_ref().map( | s | s.borrow().column_count()).unwrap_or(0);
+        if self.column_order.len() != count {
+            self.column_order = (0..count).collect();
+        }
+        self.column_widths.resize(count, self.column_width);
+        self.column_x.clear();
+        let mut x = 0.0;
+        for column in &self.column_order {
+            self.column_x.push(x);
+            x += self.column_widths[*column];
+        }
+        self.column_x.push(x);
+    }
+
+    fn row_count(&self) -> usize {
+        self.source.as_ref().map( | s | s.borrow().row_count()).unwrap_or(0)
+    }
+
+    /// The width of the first column, which stays in place when scrolling sideways
+    fn sticky_width(&self) -> f64 {
+        self.column_x.get(1).cloned().unwrap_or(0.0)
+    }
+
+    /// The rect a cell is drawn in, the sticky column is only moved by the vertical scroll
+    fn cell_rect(&self, viewport: Rect, cell: GridCell) -> Rect {
+        let scroll = self.scroll_bars.get_scroll_pos();
+        let x = if cell.column == 0 {0.0} else {self.column_x[cell.column] - scroll.x};
+        Rect {
+            pos: viewport.pos + dvec2(x, self.header_height + cell.row as f64 * self.row_height - scroll.y),
+            size: dvec2(self.column_x[cell.column + 1] - self.column_x[cell.column], self.row_height)
+        }
+    }
+
+    fn header_rect(&self, viewport: Rect, column: usize) -> Rect {
+        let scroll = self.scroll_bars.get_scroll_pos();
+        let x = if column == 0 {0.0} else {self.column_x[column] - scroll.x};
+        Rect {
+            pos: viewport.pos + dvec2(x, 0.0),
+            size: dvec2(self.column_x[column + 1] - self.column_x[column], self.header_height)
+        }
+    }
+
+    fn hit_test(&self, cx: &Cx, abs: DVec2, clamp: bool) -> Option<GridHit> {
+        let column_count = self.column_order.len();
+        let row_count = self.row_count();
+        if column_count == 0 {
+            return None
+        }
+        let rect = self.draw_bg.area().rect(cx);
+        let scroll = self.scroll_bars.get_scroll_pos();
+        let local = abs - rect.pos;
+        let sticky = self.sticky_width();
+        let x = if local.x < sticky {local.x} else {local.x + scroll.x};
+        let column = self.column_x.partition_point( | cx | *cx <= x);
+        if !clamp && (local.x < 0.0 || column > column_count) {
+            return None
+        }
+        let column = column.clamp(1, column_count) - 1;
+        if local.y < self.header_height && !clamp {
+            if (self.column_x[column + 1] - x).abs() <= self.resize_handle_width {
+                return Some(GridHit::HeaderEdge(column))
+            }
+            if column > 0 && (x - self.column_x[column]).abs() <= self.resize_handle_width {
+                return Some(GridHit::HeaderEdge(column - 1))
+            }
+            return Some(GridHit::Header(column))
+        }
+        if row_count == 0 {
+            return None
+        }
+        let y = local.y - self.header_height + scroll.y;
+        let row = (y / self.row_height).floor();
+        if !clamp && (row < 0.0 || row >= row_count as f64) {
+            return None
+        }
+        let row = (row.max(0.0) as usize).min(row_count - 1);
+        Some(GridHit::Cell(GridCell {row, column}))
+    }
+
+    fn scroll_to_cursor(&mut self, cx: &mut Cx) {
+        // the header and the sticky column cover the top and left of the viewport
+        let sticky = self.sticky_width();
+        let (x, w) = if self.cursor.column == 0 {
+            (self.scroll_bars.get_scroll_pos().x, 0.0)
+        }
+        else {
+            let x = self.column_x[self.cursor.column];
+            (x - sticky, self.column_x[self.cursor.column + 1] - x + sticky)
+        };
+        self.scroll_bars.scroll_into_view(cx, Rect {
+            pos: dvec2(x, self.cursor.row as f64 * self.row_height),
+            size: dvec2(w, self.row_height + self.header_height)
+        });
+    }
+
+    fn set_cursor(&mut self, cx: &mut Cx, scope: &mut Scope, cell: GridCell, extend: bool) {
+        let anchor = if extend {self.anchor.unwrap_or(self.cursor)} else {cell};
+        self.select(cx, scope, Some(anchor), cell);
+    }
+
+    fn select(&mut self, cx: &mut Cx, scope: &mut Scope, anchor: Option<GridCell>, cursor: GridCell) {
+        if self.anchor != anchor || self.cursor != cursor {
+            self.anchor = anchor;
+            self.cursor = cursor;
+            self.scroll_bars.redraw(cx);
+            cx.widget_action(self.widget_uid(), &scope.path, DataGridAction::SelectionChanged);
+        }
+    }
+
+    fn move_cursor(&mut self, cx: &mut Cx, scope: &mut Scope, rows: isize, columns: isize, extend: bool) {
+        let row_count = self.row_count();
+        let column_count = self.column_order.len();
+        if row_count == 0 || column_count == 0 {
+            return
+        }
+        let cell = GridCell {
+            row: self.cursor.row.saturating_add_signed(rows).min(row_count - 1),
+            column: self.cursor.column.saturating_add_signed(columns).min(column_count - 1),
+        };
+        self.set_cursor(cx, scope, cell, extend);
+        self.scroll_to_cursor(cx);
+    }
+
+    fn sort_by(&mut self, cx: &mut Cx, scope: &mut Scope, column: usize) {
+        let Some(source) = self.source.clone() else {
+            return
+        };
+        let column = self.column_order[column];
+        let ascending = !matches!(self.sort, Some((c, true)) if c == column);
+        if source.borrow_mut().sort_by(column, ascending) {
+            self.sort = Some((column, ascending));
+            // the source doesn't tell where the rows went, so the selected row indices mean nothing now
+            self.close_editor(cx);
+            self.select(cx, scope, None, GridCell::default());
+            self.scroll_bars.redraw(cx);
+            cx.widget_action(self.widget_uid(), &scope.path, DataGridAction::SortChanged {column, ascending});
+        }
+    }
+
+    fn move_column(&mut self, cx: &mut Cx, scope: &mut Scope, from: usize, target: usize) {
+        let to = if target > from {target - 1} else {target};
+        if to == from {
+            return
+        }
+        let column = self.column_order.remove(from);
+        self.column_order.insert(to, column);
+        self.sync_columns();
+        self.scroll_bars.redraw(cx);
+        cx.widget_action(self.widget_uid(), &scope.path, DataGridAction::ColumnMoved {column, to});
+    }
+
+    fn open_editor(&mut self, cx: &mut Cx) {
+        let Some(source) = self.source.clone() else {
+            return
+        };
+        let cell = self.cursor;
+        let Some(column) = self.column_order.get(cell.column).cloned() else {
+            return
+        };
+        let source = source.borrow();
+        let text = source.cell_text(cell.row, column);
+        let (widget, labels) = match source.cell_editor(cell.row, column) {
+            DataGridEditor::None => return,
+            DataGridEditor::Text => {
+                let widget = WidgetRef::new_from_ptr(cx, self.text_editor);
+                if let Some(mut input) = widget.as_text_input().borrow_mut() {
+                    input.set_text(&text);
+                    input.select_all();
+                }
+                (widget, Vec::new())
+            }
+            DataGridEditor::DropDown(labels) => {
+                let widget = WidgetRef::new_from_ptr(cx, self.drop_down_editor);
+                let drop_down = widget.as_drop_down();
+                drop_down.set_labels(labels.clone());
+                drop_down.set_selected_by_label(&text);
+                (widget, labels)
+            }
+        };
+        // the editor can only take the key focus once it has been drawn
+        self.editor = Some(GridEditor {cell, widget, labels, focus_pending: true});
+        self.scroll_bars.redraw(cx);
+    }
+
+    fn commit_editor(&mut self, cx: &mut Cx, scope: &mut Scope, text: String) {
+        let Some(editor) = self.editor.take() else {
+            return
+        };
+        if let (Some(source), Some(column)) = (&self.source, self.column_order.get(editor.cell.column)) {
+            source.borrow_mut().set_cell_text(editor.cell.row, *column, &text);
+            cx.widget_action(self.widget_uid(), &scope.path, DataGridAction::CellEdited {
+                row: editor.cell.row,
+                column: *column,
+                text
+            });
+        }
+        self.scroll_bars.redraw(cx);
+    }
+
+    fn close_editor(&mut self, cx: &mut Cx) {
+        if self.editor.take().is_some() {
+            self.scroll_bars.redraw(cx);
+        }
+    }
+
+    fn editor_text(&self) -> Option<String> {
+        let editor = self.editor.as_ref()?;
+        if editor.labels.is_empty() {
+            Some(editor.widget.text())
+        }
+        else {
+            Some(editor.widget.as_drop_down().selected_label())
+        }
+    }
+
+    fn handle_editor_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let Some(editor) = &self.editor else {
+            return
+        };
+        let widget = editor.widget.clone();
+        let actions = cx.capture_actions( | cx | widget.handle_event(cx, event, scope));
+        if let Some(index) = widget.as_drop_down().selected(&actions) {
+            let label = editor.labels.get(index).cloned().unwrap_or_default();
+            self.commit_editor(cx, scope, label);
+            cx.set_key_focus(self.draw_bg.area());
+            return
+        }
+        let text_input = widget.as_text_input();
+        if let Some(text) = text_input.returned(&actions) {
+            self.commit_editor(cx, scope, text);
+            cx.set_key_focus(self.draw_bg.area());
+        }
+        else if text_input.escape(&actions) {
+            self.close_editor(cx);
+            cx.set_key_focus(self.draw_bg.area());
+        }
+        // clicking elsewhere keeps what was typed so far
+        else if actions.filter_widget_actions_cast::<TextInputAction>(widget.widget_uid()).any( | a | matches!(a, TextInputAction::KeyFocusLost)) {
+            if let Some(text) = self.editor_text() {
+                self.commit_editor(cx, scope, text);
+            }
+        }
+    }
+
+    fn draw_cells(&mut self, cx: &mut Cx2d, viewport: Rect, rows: Range<usize>, columns: Range<usize>, focussed: bool) {
+        let Some(source) = self.source.clone() else {
+            return
+        };
+        let source = source.borrow();
+        let selection = self.anchor.map( | anchor | (
+            anchor.row.min(self.cursor.row)..anchor.row.max(self.cursor.row) + 1,
+            anchor.column.min(self.cursor.column)..anchor.column.max(self.cursor.column) + 1
+        ));
+        for row in rows {
+            for column in columns.clone() {
+                let cell = GridCell {row, column};
+                let rect = self.cell_rect(viewport, cell);
+                self.draw_cell.is_even = if row % 2 == 0 {1.0} else {0.0};
+                self.draw_cell.selected = match &selection {
+                    Some((rows, columns)) if rows.contains(&row) && columns.contains(&column) => 1.0,
+                    _ => 0.0
+                };
+                self.draw_cell.cursor = if self.anchor.is_some() && self.cursor == cell {1.0} else {0.0};
+                self.draw_cell.focussed = if focussed {1.0} else {0.0};
+                self.draw_cell.begin(cx, Walk::abs_rect(rect), self.cell_layout);
+                if self.editor.as_ref().is_none_or( | e | e.cell != cell) {
+                    let text = source.cell_text(row, self.column_order[column]);
+                    self.draw_cell_text.draw_walk(cx, Walk::fit(), Align::default(), &text);
+                }
+                self.draw_cell.end(cx);
+            }
+        }
+    }
+
+    fn draw_headers(&mut self, cx: &mut Cx2d, viewport: Rect, columns: Range<usize>) {
+        let Some(source) = self.source.clone() else {
+            return
+        };
+        let source = source.borrow();
+        // the header covers the body, so its text can't join the draw call of the cell text
+        self.draw_header.new_draw_call(cx);
+        self.draw_header_text.new_draw_call(cx);
+        let dragged = match self.drag {
+            Some(GridDrag::Header {column, moved: true, ..}) => Some(column),
+            _ => None
+        };
+        for column in columns {
+            let data_column = self.column_order[column];
+            self.draw_header.sort = match self.sort {
+                Some((c, true)) if c == data_column => 1.0,
+                Some((c, false)) if c == data_column => -1.0,
+                _ => 0.0
+            };
+            self.draw_header.dragging = if dragged == Some(column) {1.0} else {0.0};
+            let rect = self.header_rect(viewport, column);
+            self.draw_header.begin(cx, Walk::abs_rect(rect), self.cell_layout);
+            self.draw_header_text.draw_walk(cx, Walk::fit(), Align::default(), &source.column_title(data_column));
+            self.draw_header.end(cx);
+        }
+    }
+
+    fn draw_editor(&mut self, cx: &mut Cx2d, viewport: Rect, columns: &Range<usize>, rows: &Range<usize>) {
+        let Some(cell) = self.editor.as_ref().map( | e | e.cell) else {
+            return
+        };
+        if !columns.contains(&cell.column) || !rows.contains(&cell.row) {
+            return
+        }
+        let rect = self.cell_rect(viewport, cell);
+        let editor = self.editor.as_mut().unwrap();
+        editor.widget.draw_walk_all(cx, &mut Scope::empty(), Walk::abs_rect(rect));
+        if editor.focus_pending {
+            editor.focus_pending = false;
+            if let Some(input) = editor.widget.as_text_input().borrow() {
+                input.set_key_focus(cx);
+            }
+            if let Some(mut drop_down) = editor.widget.as_drop_down().borrow_mut() {
+                drop_down.set_key_focus(cx);
+                drop_down.set_open(cx);
+            }
+        }
+    }
+
+    /// Draws a region of the grid clipped to `rect`
+    fn draw_region(&mut self, cx: &mut Cx2d, rect: Rect, f: impl FnOnce(&mut Self, &mut Cx2d)) {
+        if rect.size.x <= 0.0 || rect.size.y <= 0.0 {
+            return
+        }
+        cx.begin_turtle(Walk::abs_rect(rect), Layout::default());
+        f(self, cx);
+        cx.end_turtle();
+    }
+
+    fn draw_grid(&mut self, cx: &mut Cx2d, walk: Walk) {
+        self.sync_columns();
+        self.scroll_bars.begin(cx, walk, self.layout);
+
+        let viewport = cx.turtle().unscrolled_rect();
+        self.draw_bg.draw_abs(cx, viewport);
+        let focussed = cx.has_key_focus(self.draw_bg.area());
+
+        let scroll = self.scroll_bars.get_scroll_pos();
+        let row_count = self.row_count();
+        let column_count = self.column_order.len();
+        let sticky = self.sticky_width().min(viewport.size.x);
+
+        // only the rows and columns in view are drawn, rows all have the same height
+        let first_row = ((scroll.y / self.row_height).floor().max(0.0) as usize).min(row_count);
+        let end_row = (((scroll.y + viewport.size.y - self.header_height) / self.row_height).ceil().max(0.0) as usize).min(row_count);
+        let rows = first_row..end_row.max(first_row);
+        let first_column = self.column_x.partition_point( | x | *x <= scroll.x + sticky).saturating_sub(1).max(1).min(column_count);
+        let end_column = self.column_x.partition_point( | x | *x < scroll.x + viewport.size.x).min(column_count);
+        let columns = first_column..end_column.max(first_column);
+        let sticky_columns = 0..column_count.min(1);
+
+        let body = Rect {
+            pos: viewport.pos + dvec2(sticky, self.header_height),
+            size: viewport.size - dvec2(sticky, self.header_height)
+        };
+        self.draw_region(cx, body, | this, cx | {
+            this.draw_cells(cx, viewport, rows.clone(), columns.clone(), focussed);
+            this.draw_editor(cx, viewport, &columns, &rows);
+        });
+        let first = Rect {
+            pos: viewport.pos + dvec2(0.0, self.header_height),
+            size: dvec2(sticky, viewport.size.y - self.header_height)
+        };
+        self.draw_region(cx, first, | this, cx | {
+            this.draw_cells(cx, viewport, rows.clone(), sticky_columns.clone(), focussed);
+            this.draw_editor(cx, viewport, &sticky_columns, &rows);
+        });
+        let header = Rect {
+            pos: viewport.pos + dvec2(sticky, 0.0),
+            size: dvec2(viewport.size.x - sticky, self.header_height)
+        };
+        self.draw_region(cx, header, | this, cx | {
+            this.draw_headers(cx, viewport, columns.clone());
+        });
+        let corner = Rect {
+            pos: viewport.pos,
+            size: dvec2(sticky, self.header_height)
+        };
+        self.draw_region(cx, corner, | this, cx | {
+            this.draw_headers(cx, viewport, sticky_columns.clone());
+        });
+
+        // where a dragged column will land
+        if let Some(GridDrag::Header {moved: true, target, ..}) = self.drag {
+            let x = if target == 0 {0.0} else {self.column_x[target] - scroll.x};
+            self.draw_marker.draw_abs(cx, Rect {
+                pos: viewport.pos + dvec2(x.max(0.0) - 1.0, 0.0),
+                size: dvec2(2.0, viewport.size.y)
+            });
+        }
+
+        let total = dvec2(self.column_x.last().cloned().unwrap_or(0.0), self.header_height + row_count as f64 * self.row_height);
+        cx.turtle_mut().set_used(total.x, total.y);
+        self.scroll_bars.end(cx);
+
+        cx.add_nav_stop_with(NavStop::new(self.draw_bg.area(), NavRole::Grid).with_order(self.nav_order));
+    }
+}
+
+impl Widget for DataGrid {
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.handle_editor_event(cx, event, scope);
+        self.scroll_bars.handle_event(cx, event, scope);
+
+        match event.hits(cx, self.draw_bg.area()) {
+            Hit::KeyFocus(_) | Hit::KeyFocusLost(_) => {
+                self.scroll_bars.redraw(cx);
+            }
+            Hit::FingerHoverOver(fe) => {
+                if let Some(GridHit::HeaderEdge(_)) = self.hit_test(cx, fe.abs, false) {
+                    cx.set_cursor(MouseCursor::ColResize);
+                }
+                else {
+                    cx.set_cursor(MouseCursor::Default);
+                }
+            }
+            Hit::FingerDown(fe) => {
+                if let Some(text) = self.editor_text() {
+                    self.commit_editor(cx, scope, text);
+                }
+                cx.set_key_focus(self.draw_bg.area());
+                match self.hit_test(cx, fe.abs, false) {
+                    Some(GridHit::HeaderEdge(column)) => {
+                        let column = self.column_order[column];
+                        self.drag = Some(GridDrag::Resize {column, start_width: self.column_widths[column]});
+                    }
+                    Some(GridHit::Header(column)) => {
+                        self.drag = Some(GridDrag::Header {column, moved: false, target: column});
+                    }
+                    Some(GridHit::Cell(cell)) => {
+                        self.set_cursor(cx, scope, cell, fe.modifiers.shift);
+                        self.drag = Some(GridDrag::Select);
+                        if fe.tap_count == 2 && !fe.modifiers.shift {
+                            self.open_editor(cx);
+                        }
+                    }
+                    None => ()
+                }
+            }
+            Hit::FingerMove(fe) => match self.drag {
+                Some(GridDrag::Select) => {
+                    if let Some(GridHit::Cell(cell)) = self.hit_test(cx, fe.abs, true) {
+                        self.set_cursor(cx, scope, cell, true);
+                    }
+                }
+                Some(GridDrag::Resize {column, start_width}) => {
+                    let width = (start_width + fe.abs.x - fe.abs_start.x).max(self.min_column_width);
+                    if self.column_widths[column] != width {
+                        self.column_widths[column] = width;
+                        self.scroll_bars.redraw(cx);
+                        cx.widget_action(self.widget_uid(), &scope.path, DataGridAction::ColumnResized {column, width});
+                    }
+                }
+                Some(GridDrag::Header {column, moved, ..})
+                    if moved || fe.abs.distance(&fe.abs_start) >= self.min_drag_distance => {
+                    let rect = self.draw_bg.area().rect(cx);
+                    let local = fe.abs.x - rect.pos.x;
+                    let x = if local < self.sticky_width() {local} else {local + self.scroll_bars.get_scroll_pos().x};
+                    // the column lands in front of the first column whose middle is right of the finger
+                    let target = (0..self.column_order.len())
+                        .find( | c | (self.column_x[*c] + self.column_x[*c + 1]) * 0.5 > x)
+                        .unwrap_or(self.column_order.len());
+                    self.drag = Some(GridDrag::Header {column, moved: true, target});
+                    self.scroll_bars.redraw(cx);
+                }
+                _ => ()
+            }
+            Hit::FingerUp(fe) => {
+                match self.drag.take() {
+                    Some(GridDrag::Header {column, moved: false, ..}) if fe.is_over => {
+                        self.sort_by(cx, scope, column);
+                    }
+                    Some(GridDrag::Header {column, moved: true, target}) => {
+                        self.move_column(cx, scope, column, target);
+                        self.scroll_bars.redraw(cx);
+                    }
+                    _ => ()
+                }
+            }
+            Hit::KeyDown(ke) => {
+                let extend = ke.modifiers.shift;
+                let page = ((self.draw_bg.area().rect(cx).size.y - self.header_height) / self.row_height).floor().max(1.0) as isize;
+                match ke.key_code {
+                    KeyCode::ArrowUp => self.move_cursor(cx, scope, -1, 0, extend),
+                    KeyCode::ArrowDown => self.move_cursor(cx, scope, 1, 0, extend),
+                    KeyCode::ArrowLeft => self.move_cursor(cx, scope, 0, -1, extend),
+                    KeyCode::ArrowRight => self.move_cursor(cx, scope, 0, 1, extend),
+                    KeyCode::PageUp => self.move_cursor(cx, scope, -page, 0, extend),
+                    KeyCode::PageDown => self.move_cursor(cx, scope, page, 0, extend),
+                    KeyCode::Home => self.move_cursor(cx, scope, 0, isize::MIN, extend),
+                    KeyCode::End => self.move_cursor(cx, scope, 0, isize::MAX, extend),
+                    KeyCode::KeyA if ke.modifiers.control || ke.modifiers.logo => {
+                        let rows = self.row_count();
+                        let columns = self.column_order.len();
+                        if rows > 0 && columns > 0 {
+                            self.select(cx, scope, Some(GridCell::default()), GridCell {row: rows - 1, column: columns - 1});
+                        }
+                    }
+                    KeyCode::ReturnKey | KeyCode::F2 if !ke.is_repeat && self.anchor.is_some() => {
+                        self.open_editor(cx);
+                    }
+                    _ => ()
+                }
+            }
+            Hit::TextCopy(ce) => {
+                *ce.response.borrow_mut() = self.selection_tsv();
+            }
+            _ => ()
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.draw_grid(cx, walk);
+        DrawStep::done()
+    }
+}
+
+impl DataGridRef {
+    pub fn set_source(&self, cx: &mut Cx, source: Rc<RefCell<dyn DataGridSource >>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_source(cx, source);
+        }
+    }
+
+    pub fn selection(&self) -> Option<DataGridSelection> {
+        self.borrow().and_then( | inner | inner.selection())
+    }
+
+    pub fn selection_tsv(&self) -> Option<String> {
+        self.borrow().and_then( | inner | inner.selection_tsv())
+    }
+
+    pub fn sort(&self) -> Option<(usize, bool)> {
+        self.borrow().and_then( | inner | inner.sort())
+    }
+
+    pub fn column_order(&self) -> Vec<usize> {
+        self.borrow().map( | inner | inner.column_order().to_vec()).unwrap_or_default()
+    }
+
+    pub fn column_width(&self, column: usize) -> f64 {
+        self.borrow().map( | inner | inner.column_width(column)).unwrap_or(0.0)
+    }
+
+    pub fn selection_changed(&self, actions: &Actions) -> bool {
+        actions.filter_widget_actions_cast::<DataGridAction>(self.widget_uid())
+            .any( | a | matches!(a, DataGridAction::SelectionChanged))
+    }
+
+    pub fn cell_edited(&self, actions: &Actions) -> Option<(usize, usize, String)> {
+        actions.filter_widget_actions_cast::<DataGridAction>(self.widget_uid()).find_map( | a | match a {
+            DataGridAction::CellEdited {row, column, text} => Some((row, column, text)),
+            _ => None
+        })
+    }
+
+    pub fn sort_changed(&self, actions: &Actions) -> Option<(usize, bool)> {
+        actions.filter_widget_actions_cast::<DataGridAction>(self.widget_uid()).find_map( | a | match a {
+            DataGridAction::SortChanged {column, ascending} => Some((column, ascending)),
+            _ => None
+        })
+    }
+}
@@ -0,0 +1,683 @@
+use {
+    std::{
+        fmt,
+        rc::Rc,
+        cell::RefCell,
+        sync::Arc,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        widget::*,
+    }
+};
+
+live_design!{
+    DrawCalendarDay = {{DrawCalendarDay}} {}
+    DrawCalendarText = {{DrawCalendarText}} {}
+    CalendarPopupBase = {{CalendarPopup}} {}
+    DatePickerBase = {{DatePicker}} {}
+}
+
+/// A day in the proleptic Gregorian calendar
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalendarDate {
+    pub year: i32,
+    /// 1 to 12
+    pub month: u32,
+    /// 1 to the length of the month
+    pub day: u32,
+}
+
+impl Default for CalendarDate {
+    fn default() -> Self {
+        Self {year: 1970, month: 1, day: 1}
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl CalendarDate {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > Self::days_in_month(year, month) {
+            return None
+        }
+        Some(Self {year, month, day})
+    }
+
+    pub fn is_leap_year(year: i32) -> bool {
+        year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+    }
+
+    pub fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            2 if Self::is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31
+        }
+    }
+
+    /// Parses the `YYYY-MM-DD` form `Display` writes
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        Self::new(year, month, day)
+    }
+
+    /// The day in UTC
+    pub fn today() -> Self {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, | d | d.as_secs());
+        Self::from_days((secs / 86400) as i64)
+    }
+
+    /// Days since 1970-01-01
+    pub fn to_days(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 {1} else {0};
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = self.month as i64;
+        let day_of_year = (153 * (if month > 2 {month - 3} else {month + 9}) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+
+    pub fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 {mp + 3} else {mp - 9} as u32;
+        let year = (year_of_era + era * 400 + if month <= 2 {1} else {0}) as i32;
+        Self {year, month, day}
+    }
+
+    /// 0 is Sunday, 6 is Saturday
+    pub fn weekday(&self) -> u32 {
+        (self.to_days() + 4).rem_euclid(7) as u32
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Moves by whole months, keeping the day where the month is long enough
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u32 + 1;
+        Self {year, month, day: self.day.min(Self::days_in_month(year, month))}
+    }
+
+    pub fn first_of_month(&self) -> Self {
+        Self {day: 1, ..*self}
+    }
+}
+
+#[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
+pub struct DrawCalendarDay {
+    #[deref] draw_super: DrawQuad,
+    #[live] hover: f32,
+    #[live] selected: f32,
+    #[live] today: f32,
+    #[live] outside: f32,
+    #[live] disabled: f32,
+}
+
+#[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
+pub struct DrawCalendarText {
+    #[deref] draw_super: DrawText,
+    #[live] selected: f32,
+    #[live] outside: f32,
+    #[live] disabled: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CalendarCell {
+    PrevMonth,
+    NextMonth,
+    Day(CalendarDate),
+    Disabled,
+}
+
+pub enum CalendarPopupAction {
+    Select(CalendarDate),
+    None
+}
+
+/// The month grid a `DatePicker` opens, drawn in an overlay like a `PopupMenu`
+#[derive(Live, LiveHook, LiveRegister)]
+pub struct CalendarPopup {
+    #[live] draw_list: DrawList2d,
+
+    #[live] draw_bg: DrawQuad,
+    #[live] draw_day: DrawCalendarDay,
+    #[live] draw_text: DrawCalendarText,
+    #[live] draw_title: DrawText,
+    #[live] draw_weekday: DrawText,
+
+    #[layout] layout: Layout,
+    #[walk] walk: Walk,
+    #[live] title_walk: Walk,
+    #[live] cell_walk: Walk,
+    #[live] cell_layout: Layout,
+
+    #[live] month_names: Vec<String>,
+    /// Starting at Sunday
+    #[live] weekday_names: Vec<String>,
+    /// 0 starts the weeks on Sunday, 1 on Monday
+    #[live] first_day_of_week: u32,
+
+    #[rust] month: CalendarDate,
+    #[rust] cursor: CalendarDate,
+    #[rust] hover: Option<CalendarCell>,
+    #[rust] cells: Vec<(Rect, CalendarCell)>,
+}
+
+impl CalendarPopup {
+    pub fn open(&mut self, cx: &mut Cx, date: CalendarDate) {
+        self.cursor = date;
+        self.month = date.first_of_month();
+        self.hover = None;
+        self.draw_list.redraw(cx);
+    }
+
+    pub fn cursor(&self) -> CalendarDate {
+        self.cursor
+    }
+
+    /// Moves the keyboard cursor, paging the month along with it
+    pub fn move_cursor(&mut self, cx: &mut Cx, days: i64) {
+        self.set_cursor(cx, self.cursor.add_days(days));
+    }
+
+    pub fn move_month(&mut self, cx: &mut Cx, months: i32) {
+        self.set_cursor(cx, self.cursor.add_months(months));
+    }
+
+    fn set_cursor(&mut self, cx: &mut Cx, date: CalendarDate) {
+        self.cursor = date;
+        self.month = date.first_of_month();
+        self.draw_list.redraw(cx);
+    }
+
+    pub fn contains_pos(&self, cx: &mut Cx, pos: DVec2) -> bool {
+        self.draw_bg.area().clipped_rect(cx).contains(pos)
+    }
+
+    fn cell_at(&self, cx: &mut Cx, pos: DVec2) -> Option<CalendarCell> {
+        // the cells were recorded relative to the background, which gets shifted into place after drawing
+        let origin = self.draw_bg.area().rect(cx).pos;
+        self.cells.iter().find( | (rect, _) | rect.contains(pos - origin)).map( | (_, cell) | *cell)
+    }
+
+    fn draw_cell(&mut self, cx: &mut Cx2d, label: &str, cell: CalendarCell, selected: bool, today: bool, outside: bool) -> Rect {
+        let hover = self.hover == Some(cell) || cell == CalendarCell::Day(self.cursor);
+        let disabled = cell == CalendarCell::Disabled;
+        self.draw_day.hover = if hover && !disabled {1.0} else {0.0};
+        self.draw_day.selected = if selected {1.0} else {0.0};
+        self.draw_day.today = if today {1.0} else {0.0};
+        self.draw_day.outside = if outside {1.0} else {0.0};
+        self.draw_day.disabled = if disabled {1.0} else {0.0};
+        self.draw_text.selected = self.draw_day.selected;
+        self.draw_text.outside = self.draw_day.outside;
+        self.draw_text.disabled = self.draw_day.disabled;
+
+        self.draw_day.begin(cx, self.cell_walk, self.cell_layout);
+        self.draw_text.draw_walk(cx, Walk::fit(), Align::default(), label);
+        self.draw_day.end(cx);
+        self.draw_day.area().rect(cx)
+    }
+
+    pub fn draw_calendar(
+        &mut self,
+        cx: &mut Cx2d,
+        selected: Option<CalendarDate>,
+        min: Option<CalendarDate>,
+        max: Option<CalendarDate>,
+        shift_area: Area,
+        shift: DVec2,
+    ) {
+        self.draw_list.begin_overlay_reuse(cx);
+        cx.begin_pass_sized_turtle(Layout::flow_down());
+        self.draw_bg.begin(cx, self.walk, self.layout);
+
+        let mut cells = Vec::new();
+        let today = CalendarDate::today();
+
+        cx.begin_turtle(Walk::fit(), Layout::flow_right());
+        let rect = self.draw_cell(cx, "<", CalendarCell::PrevMonth, false, false, false);
+        cells.push((rect, CalendarCell::PrevMonth));
+        let title = format!(
+            "{} {}",
+            self.month_names.get(self.month.month as usize - 1).map_or("", | s | s.as_str()),
+            self.month.year
+        );
+        cx.begin_turtle(self.title_walk, self.cell_layout);
+        self.draw_title.draw_walk(cx, Walk::fit(), Align::default(), &title);
+        cx.end_turtle();
+        let rect = self.draw_cell(cx, ">", CalendarCell::NextMonth, false, false, false);
+        cells.push((rect, CalendarCell::NextMonth));
+        cx.end_turtle();
+
+        cx.begin_turtle(Walk::fit(), Layout::flow_right());
+        for i in 0..7 {
+            let name = self.weekday_names.get(((self.first_day_of_week + i) % 7) as usize).map_or("", | s | s.as_str());
+            cx.begin_turtle(self.cell_walk, self.cell_layout);
+            self.draw_weekday.draw_walk(cx, Walk::fit(), Align::default(), name);
+            cx.end_turtle();
+        }
+        cx.end_turtle();
+
+        // six weeks always fit a month, starting on the week the first falls in
+        let lead = (self.month.weekday() + 7 - self.first_day_of_week % 7) % 7;
+        let mut date = self.month.add_days(-(lead as i64));
+        for _ in 0..6 {
+            cx.begin_turtle(Walk::fit(), Layout::flow_right());
+            for _ in 0..7 {
+                let enabled = min.is_none_or( | min | date >= min) && max.is_none_or( | max | date <= max);
+                let cell = if enabled {CalendarCell::Day(date)} else {CalendarCell::Disabled};
+                let rect = self.draw_cell(
+                    cx,
+                    &date.day.to_string(),
+                    cell,
+                    selected == Some(date),
+                    date == today,
+                    date.month != self.month.month,
+                );
+                cells.push((rect, cell));
+                date = date.add_days(1);
+            }
+            cx.end_turtle();
+        }
+
+        self.draw_bg.end(cx);
+        let origin = self.draw_bg.area().rect(cx).pos;
+        self.cells = cells.into_iter().map( | (rect, cell) | (Rect {pos: rect.pos - origin, size: rect.size}, cell)).collect();
+
+        cx.end_pass_sized_turtle_with_shift(shift_area, shift);
+        self.draw_list.end(cx);
+    }
+
+    pub fn handle_event_with(
+        &mut self,
+        cx: &mut Cx,
+        event: &Event,
+        sweep_area: Area,
+        dispatch_action: &mut dyn FnMut(&mut Cx, CalendarPopupAction),
+    ) {
+        match event.hits_with_sweep_area(cx, self.draw_bg.area(), sweep_area) {
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                let hover = self.cell_at(cx, fe.abs);
+                if hover != self.hover {
+                    self.hover = hover;
+                    self.draw_list.redraw(cx);
+                }
+            }
+            Hit::FingerMove(fe) => {
+                let hover = self.cell_at(cx, fe.abs);
+                if hover != self.hover {
+                    self.hover = hover;
+                    self.draw_list.redraw(cx);
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                self.hover = None;
+                self.draw_list.redraw(cx);
+            }
+            Hit::FingerUp(fe) if fe.is_over => match self.cell_at(cx, fe.abs) {
+                Some(CalendarCell::PrevMonth) => {
+                    self.month = self.month.add_months(-1);
+                    self.draw_list.redraw(cx);
+                }
+                Some(CalendarCell::NextMonth) => {
+                    self.month = self.month.add_months(1);
+                    self.draw_list.redraw(cx);
+                }
+                Some(CalendarCell::Day(date)) => {
+                    self.cursor = date;
+                    dispatch_action(cx, CalendarPopupAction::Select(date));
+                }
+                _ => ()
+            }
+            _ => ()
+        }
+    }
+}
+
+#[derive(Live, Widget)]
+pub struct DatePicker {
+    #[animator] animator: Animator,
+
+    #[redraw] #[live] draw_bg: DrawQuad,
+    #[live] draw_text: DrawText,
+
+    #[walk] walk: Walk,
+    #[layout] layout: Layout,
+
+    #[live] popup: Option<LivePtr>,
+
+    /// The picked date as `YYYY-MM-DD`, empty when none is picked yet
+    #[live] value: String,
+    #[live] min: String,
+    #[live] max: String,
+    #[live] empty_message: String,
+    #[live] nav_order: NavOrder,
+
+    #[rust] date: Option<CalendarDate>,
+    #[rust] is_open: bool,
+}
+
+#[derive(Default, Clone)]
+struct CalendarPopupGlobal {
+    map: Rc<RefCell<ComponentMap<LivePtr, CalendarPopup >> >
+}
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum DatePickerAction {
+    Change(CalendarDate),
+    None
+}
+
+impl LiveHook for DatePicker {
+    fn after_apply(&mut self, cx: &mut Cx, apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        if !apply.from.is_from_doc() {
+            return
+        }
+        self.date = CalendarDate::parse(&self.value);
+        let Some(popup) = self.popup else {
+            return
+        };
+        let global = cx.global::<CalendarPopupGlobal>().clone();
+        let mut map = global.map.borrow_mut();
+
+        // when live styling clean up old style references
+        map.retain( | k, _ | cx.live_registry.borrow().generation_valid(*k));
+
+        map.get_or_insert(cx, popup, | cx | {
+            CalendarPopup::new_from_ptr(cx, Some(popup))
+        });
+    }
+}
+
+impl DatePicker {
+    pub fn date(&self) -> Option<CalendarDate> {
+        self.date
+    }
+
+    pub fn set_date(&mut self, date: Option<CalendarDate>) {
+        self.date = date;
+        self.value = date.map(| d | d.to_string()).unwrap_or_default();
+    }
+
+    fn range(&self) -> (Option<CalendarDate>, Option<CalendarDate>) {
+        (CalendarDate::parse(&self.min), CalendarDate::parse(&self.max))
+    }
+
+    fn clamp(&self, date: CalendarDate) -> CalendarDate {
+        let (min, max) = self.range();
+        let date = min.map_or(date, | min | date.max(min));
+        max.map_or(date, | max | date.min(max))
+    }
+
+    fn change(&mut self, cx: &mut Cx, scope: &Scope, date: CalendarDate) {
+        let date = self.clamp(date);
+        if self.date != Some(date) {
+            self.set_date(Some(date));
+            cx.widget_action(self.widget_uid(), &scope.path, DatePickerAction::Change(date));
+        }
+        self.draw_bg.redraw(cx);
+    }
+
+    pub fn set_open(&mut self, cx: &mut Cx) {
+        let Some(popup) = self.popup else {
+            return
+        };
+        self.is_open = true;
+        self.draw_bg.apply_over(cx, live!{open: 1.0});
+        self.draw_bg.redraw(cx);
+        let start = self.clamp(self.date.unwrap_or_else(CalendarDate::today));
+        let global = cx.global::<CalendarPopupGlobal>().clone();
+        let mut map = global.map.borrow_mut();
+        map.get_mut(&popup).unwrap().open(cx, start);
+        cx.sweep_lock(self.draw_bg.area());
+    }
+
+    pub fn set_closed(&mut self, cx: &mut Cx) {
+        self.is_open = false;
+        self.draw_bg.apply_over(cx, live!{open: 0.0});
+        self.draw_bg.redraw(cx);
+        cx.sweep_unlock(self.draw_bg.area());
+    }
+
+    fn with_popup<R>(&self, cx: &mut Cx, f: impl FnOnce(&mut Cx, &mut CalendarPopup) -> R) -> Option<R> {
+        let popup = self.popup?;
+        let global = cx.global::<CalendarPopupGlobal>().clone();
+        let mut map = global.map.borrow_mut();
+        let popup = map.get_mut(&popup)?;
+        Some(f(cx, popup))
+    }
+
+    pub fn draw_walk_date_picker(&mut self, cx: &mut Cx2d, walk: Walk) {
+        self.draw_bg.begin(cx, walk, self.layout);
+        let label = self.date.map(| d | d.to_string()).unwrap_or_else( | | self.empty_message.clone());
+        self.draw_text.draw_walk(cx, Walk::fit(), Align::default(), &label);
+        self.draw_bg.end(cx);
+
+        cx.add_nav_stop_with(NavStop::new(self.draw_bg.area(), NavRole::DropDown).with_order(self.nav_order));
+        cx.add_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::DateEditor)
+            .with_value(AccessValue::Text(self.value.clone()))
+            .with_states(AccessStates {
+                focusable: true,
+                focused: cx.has_key_focus(self.draw_bg.area()),
+                expanded: Some(self.is_open),
+                ..Default::default()
+            })
+            .with_action(AccessActionKind::Press)
+            .with_action(AccessActionKind::Focus)
+            .with_action(AccessActionKind::SetValue)
+            .with_area(self.draw_bg.area()));
+
+        if let (true, Some(popup)) = (self.is_open, self.popup) {
+            let (min, max) = self.range();
+            let global = cx.global::<CalendarPopupGlobal>().clone();
+            let mut map = global.map.borrow_mut();
+            let popup = map.get_mut(&popup).unwrap();
+            let shift = dvec2(0.0, self.draw_bg.area().rect(cx).size.y);
+            popup.draw_calendar(cx, self.date, min, max, self.draw_bg.area(), shift);
+        }
+    }
+}
+
+impl Widget for DatePicker {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.animator_handle_event(cx, event);
+
+        if self.is_open && self.popup.is_some() {
+            let area = self.draw_bg.area();
+            let mut selected = None;
+            let outside = self.with_popup(cx, | cx, popup | {
+                popup.handle_event_with(cx, event, area, &mut | _, action | {
+                    if let CalendarPopupAction::Select(date) = action {
+                        selected = Some(date);
+                    }
+                });
+                // clicking the picker itself toggles it below, anywhere else closes it
+                match event {
+                    Event::MouseDown(e) => !popup.contains_pos(cx, e.abs) && !area.clipped_rect(cx).contains(e.abs),
+                    _ => false
+                }
+            }).unwrap_or(false);
+            if let Some(date) = selected {
+                self.change(cx, scope, date);
+                self.set_closed(cx);
+            }
+            else if outside {
+                self.set_closed(cx);
+                self.animator_play(cx, id!(hover.off));
+            }
+        }
+
+        match event.hits_with_sweep_area(cx, self.draw_bg.area(), self.draw_bg.area()) {
+            Hit::KeyFocusLost(_) => {
+                self.animator_play(cx, id!(focus.off));
+                self.set_closed(cx);
+                self.animator_play(cx, id!(hover.off));
+            }
+            Hit::KeyFocus(_) => {
+                self.animator_play(cx, id!(focus.on));
+            }
+            Hit::KeyDown(ke) if self.is_open => {
+                let (days, months) = match ke.key_code {
+                    KeyCode::ArrowLeft => (-1, 0),
+                    KeyCode::ArrowRight => (1, 0),
+                    KeyCode::ArrowUp => (-7, 0),
+                    KeyCode::ArrowDown => (7, 0),
+                    KeyCode::PageUp => (0, -1),
+                    KeyCode::PageDown => (0, 1),
+                    _ => (0, 0)
+                };
+                match ke.key_code {
+                    KeyCode::ReturnKey | KeyCode::Space if !ke.is_repeat => {
+                        if let Some(date) = self.with_popup(cx, | _, popup | popup.cursor()) {
+                            self.change(cx, scope, date);
+                        }
+                        self.set_closed(cx);
+                    }
+                    KeyCode::Escape => {
+                        self.set_closed(cx);
+                    }
+                    _ if days != 0 => {
+                        self.with_popup(cx, | cx, popup | popup.move_cursor(cx, days));
+                    }
+                    _ if months != 0 => {
+                        self.with_popup(cx, | cx, popup | popup.move_month(cx, months));
+                    }
+                    _ => ()
+                }
+            }
+            Hit::KeyDown(ke) => match ke.key_code {
+                KeyCode::ArrowUp | KeyCode::ArrowDown => {
+                    let days = if ke.key_code == KeyCode::ArrowUp {-1} else {1};
+                    let date = self.date.map_or_else(CalendarDate::today, | d | d.add_days(days));
+                    self.change(cx, scope, date);
+                }
+                KeyCode::Space | KeyCode::ReturnKey if !ke.is_repeat => {
+                    self.set_open(cx);
+                }
+                _ => ()
+            }
+            Hit::AccessAction(e) => match e.action {
+                AccessAction::Press => {
+                    cx.set_key_focus(self.draw_bg.area());
+                    if self.is_open {
+                        self.set_closed(cx);
+                    }
+                    else {
+                        self.set_open(cx);
+                    }
+                }
+                AccessAction::Focus => {
+                    cx.set_key_focus(self.draw_bg.area());
+                }
+                AccessAction::SetText(text) => if let Some(date) = CalendarDate::parse(&text) {
+                    self.change(cx, scope, date);
+                }
+                _ => ()
+            }
+            Hit::FingerDown(_) => {
+                cx.set_key_focus(self.draw_bg.area());
+                if self.is_open {
+                    self.set_closed(cx);
+                }
+                else {
+                    self.set_open(cx);
+                }
+                self.animator_play(cx, id!(hover.pressed));
+            }
+            Hit::FingerHoverIn(_) => {
+                cx.set_cursor(MouseCursor::Hand);
+                self.animator_play(cx, id!(hover.on));
+            }
+            Hit::FingerHoverOut(_) => {
+                self.animator_play(cx, id!(hover.off));
+            }
+            Hit::FingerUp(fe) => {
+                if fe.is_over && fe.device.has_hovers() {
+                    self.animator_play(cx, id!(hover.on));
+                }
+                else {
+                    self.animator_play(cx, id!(hover.off));
+                }
+            }
+            _ => ()
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.draw_walk_date_picker(cx, walk);
+        DrawStep::done()
+    }
+
+    fn widget_to_data(&self, _cx: &mut Cx, actions: &Actions, nodes: &mut LiveNodeVec, path: &[LiveId]) -> bool {
+        match actions.find_widget_action_cast(self.widget_uid()) {
+            DatePickerAction::Change(date) => {
+                nodes.write_field_value(path, LiveValue::String(Arc::new(date.to_string())));
+                true
+            }
+            _ => false
+        }
+    }
+
+    fn data_to_widget(&mut self, cx: &mut Cx, nodes: &[LiveNode], path: &[LiveId]) {
+        if let Some(value) = nodes.read_field_value(path) {
+            if let Some(text) = value.as_str() {
+                let date = CalendarDate::parse(text);
+                if date != self.date {
+                    self.set_date(date);
+                    self.redraw(cx);
+                }
+            }
+        }
+    }
+
+    fn text(&self) -> String {
+        self.value.clone()
+    }
+
+    fn set_text(&mut self, v: &str) {
+        self.set_date(CalendarDate::parse(v));
+    }
+}
+
+impl DatePickerRef {
+    pub fn date(&self) -> Option<CalendarDate> {
+        self.borrow().and_then( | inner | inner.date())
+    }
+
+    pub fn set_date(&self, cx: &mut Cx, date: Option<CalendarDate>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_date(date);
+            inner.redraw(cx);
+        }
+    }
+
+    pub fn changed(&self, actions: &Actions) -> Option<CalendarDate> {
+        if let DatePickerAction::Change(date) = actions.find_widget_action_cast(self.widget_uid()) {
+            return Some(date)
+        }
+        None
+    }
+}
@@ -102,7 +102,8 @@ impl Widget for DesignerContainer {
             abs_pos: Some(data.rect.pos),
             width: Size::Fixed(data.rect.size.x),
             height: Size::Fixed(data.rect.size.y),
-            margin: Default::default()
+            margin: Default::default(),
+            ..Default::default()
         };
         while let Some(_next) = self.view.draw(cx, &mut Scope::empty()).step() {
             data.component.draw_all(cx, &mut Scope::empty());
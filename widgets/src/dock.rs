@@ -6,6 +6,7 @@ use crate::{
     makepad_draw::*,
     splitter::{SplitterAction, Splitter, SplitterAlign, SplitterAxis},
     tab_bar::{TabBarAction, TabBar},
+    window::{HostedWindows, Window, WindowAction},
 };
 
 live_design!{
@@ -50,6 +51,7 @@ pub struct Dock {
     
     #[live] tab_bar: Option<LivePtr>,
     #[live] splitter: Option<LivePtr>,
+    #[live] floating_window: Option<LivePtr>,
     
     #[rust] needs_save: bool,
     #[rust] area: Area,
@@ -62,6 +64,11 @@ pub struct Dock {
     #[rust] items: ComponentMap<LiveId, (LiveId, WidgetRef)>,
     #[rust] drop_state: Option<DropPosition>,
     #[rust] dock_item_iter_stack: Vec<(LiveId, usize)>,
+    
+    #[rust] windows: Vec<DockWindow>,
+    #[rust] closed_windows: Vec<Window>,
+    #[rust] tab_drag: Option<LiveId>,
+    #[rust] presets: HashMap<LiveId, DockLayout>,
 }
 
 impl WidgetNode for Dock{
@@ -151,6 +158,13 @@ struct TabBarWrap {
     contents_rect: Rect
 }
 
+// an os window showing a tree of dock items that was torn off the dock
+struct DockWindow {
+    root: LiveId,
+    window: Window,
+    drop_target_draw_list: DrawList2d,
+}
+
 #[derive(Copy, Debug, Clone)]
 enum DrawStackItem {
     Invalid,
@@ -160,7 +174,9 @@ enum DrawStackItem {
     Tabs {id: LiveId},
     TabLabel {id: LiveId, index: usize},
     Tab {id: LiveId},
-    TabContent {id: LiveId}
+    TabContent {id: LiveId},
+    Window {index: usize},
+    WindowEnd {index: usize}
 }
 
 impl DrawStackItem {
@@ -196,7 +212,8 @@ pub enum DockAction {
 pub struct DropPosition {
     part: DropPart,
     rect: Rect,
-    id: LiveId
+    id: LiveId,
+    root: LiveId
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -210,7 +227,7 @@ pub enum DropPart {
     Tab
 }
 
-#[derive(Clone, Debug, Live, LiveHook, SerRon, DeRon)]
+#[derive(Clone, Debug, Live, LiveHook, SerRon, DeRon, SerJson, DeJson)]
 #[live_ignore]
 pub enum DockItem {
     #[live {axis: SplitterAxis::Vertical, align: SplitterAlign::Weighted(0.5), a: LiveId(0), b: LiveId(0)}]
@@ -234,6 +251,22 @@ pub enum DockItem {
     }
 }
 
+/// Everything needed to restore a dock: the items of the dock itself (under `root`)
+/// and of its floating windows, with the geometry of those windows
+#[derive(Clone, Debug, Default, SerRon, DeRon, SerJson, DeJson)]
+pub struct DockLayout {
+    pub items: HashMap<LiveId, DockItem>,
+    pub windows: Vec<DockWindowLayout>,
+}
+
+#[derive(Clone, Debug, SerRon, DeRon, SerJson, DeJson)]
+pub struct DockWindowLayout {
+    pub root: LiveId,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
 
 
 impl LiveHook for Dock {
@@ -290,6 +323,8 @@ impl LiveHook for Dock {
     
     fn after_new_from_doc(&mut self, cx: &mut Cx) {
         self.create_all_items(cx);
+        // the layout from the dsl is always there to switch back to
+        self.presets.insert(live_id!(default), self.layout(cx));
     }
 }
 
@@ -315,7 +350,7 @@ impl Dock {
     fn end(&mut self, cx: &mut Cx2d) {
         
         if self.drop_target_draw_list.begin(cx, Walk::default()).is_redrawing() {
-            if let Some(pos) = &self.drop_state {
+            if let Some(pos) = self.drop_state.filter( | pos | pos.root == live_id!(root)) {
                 self.drag_quad.draw_abs(cx, pos.rect);
             }
             self.drop_target_draw_list.end(cx);
@@ -324,31 +359,71 @@ impl Dock {
         self.tab_bars.retain_visible();
         self.splitters.retain_visible();
         
-        // lets draw the corners here
-        for splitter in self.splitters.values() {
-            self.round_corner.draw_corners(cx, splitter.area_a().rect(cx));
-            self.round_corner.draw_corners(cx, splitter.area_b().rect(cx));
-        }
-        self.round_corner.draw_corners(cx, cx.turtle().rect());
+        self.draw_corners(cx, live_id!(root));
         
         cx.end_turtle_with_area(&mut self.area);
+        
+        // our floating windows get their input through the window we are in
+        if let Some(host_id) = self.area.window_id(cx) {
+            for window in &self.windows {
+                HostedWindows::host(cx, window.window.window_id(), host_id);
+            }
+        }
+    }
+    
+    fn draw_corners(&mut self, cx: &mut Cx2d, root: LiveId) {
+        let mut rects = Vec::new();
+        for (splitter_id, splitter) in self.splitters.iter() {
+            if self.item_root(*splitter_id) == root {
+                rects.push(splitter.area_a().rect(cx));
+                rects.push(splitter.area_b().rect(cx));
+            }
+        }
+        rects.push(cx.turtle().rect());
+        for rect in rects {
+            self.round_corner.draw_corners(cx, rect);
+        }
+    }
+    
+    // the root of the tree an item is in, `root` for the dock itself or the root of a floating window
+    fn item_root(&self, item_id: LiveId) -> LiveId {
+        let mut item_id = item_id;
+        'up: loop {
+            for (parent_id, item) in self.dock_items.iter() {
+                let is_parent = match item {
+                    DockItem::Splitter {a, b, ..} => *a == item_id || *b == item_id,
+                    DockItem::Tabs {tabs, ..} => tabs.contains(&item_id),
+                    DockItem::Tab {..} => false
+                };
+                if is_parent {
+                    item_id = *parent_id;
+                    continue 'up;
+                }
+            }
+            return item_id
+        }
     }
     
-    fn find_drop_position(&self, cx: &Cx, abs: DVec2) -> Option<DropPosition> {
+    fn find_drop_position(&self, cx: &Cx, root: LiveId, abs: DVec2) -> Option<DropPosition> {
         for (tab_bar_id, tab_bar) in self.tab_bars.iter() {
+            if self.item_root(*tab_bar_id) != root {
+                continue;
+            }
             let rect = tab_bar.contents_rect;
             if let Some((tab_id, rect)) = tab_bar.tab_bar.is_over_tab(cx, abs) {
                 return Some(DropPosition {
                     part: DropPart::Tab,
                     id: tab_id,
-                    rect
+                    rect,
+                    root
                 })
             }
             else if let Some(rect) = tab_bar.tab_bar.is_over_tab_bar(cx, abs) {
                 return Some(DropPosition {
                     part: DropPart::TabBar,
                     id: *tab_bar_id,
-                    rect
+                    rect,
+                    root
                 })
             }
             else if rect.contains(abs) {
@@ -358,6 +433,7 @@ impl Dock {
                     return Some(DropPosition {
                         part: DropPart::Left,
                         id: *tab_bar_id,
+                        root,
                         rect: Rect {
                             pos: rect.pos,
                             size: DVec2 {
@@ -370,6 +446,7 @@ impl Dock {
                     return Some(DropPosition {
                         part: DropPart::Right,
                         id: *tab_bar_id,
+                        root,
                         rect: Rect {
                             pos: DVec2 {
                                 x: rect.pos.x + rect.size.x / 2.0,
@@ -385,6 +462,7 @@ impl Dock {
                     return Some(DropPosition {
                         part: DropPart::Top,
                         id: *tab_bar_id,
+                        root,
                         rect: Rect {
                             pos: rect.pos,
                             size: DVec2 {
@@ -397,6 +475,7 @@ impl Dock {
                     return Some(DropPosition {
                         part: DropPart::Bottom,
                         id: *tab_bar_id,
+                        root,
                         rect: Rect {
                             pos: DVec2 {
                                 x: rect.pos.x,
@@ -412,7 +491,8 @@ impl Dock {
                     return Some(DropPosition {
                         part: DropPart::Center,
                         id: *tab_bar_id,
-                        rect
+                        rect,
+                        root
                     })
                 }
             }
@@ -455,6 +535,12 @@ impl Dock {
     }
     
     fn set_parent_split(&mut self, what_item: LiveId, replace_item: LiveId) {
+        for window in &mut self.windows {
+            if window.root == what_item {
+                window.root = replace_item;
+                return
+            }
+        }
         for item in self.dock_items.values_mut() {
             match item {
                 DockItem::Splitter {a, b, ..} => {
@@ -569,7 +655,12 @@ impl Dock {
                     let tabs_id = *tabs_id;
                     tabs.remove(pos);
                     if tabs.len() == 0 { // unsplit
-                        if *closable {
+                        if self.windows.iter().any( | window | window.root == tabs_id) {
+                            // a floating window without tabs goes away
+                            self.dock_items.remove(&tabs_id);
+                            self.close_window(cx, tabs_id);
+                        }
+                        else if *closable {
                             self.unsplit_tabs(cx, tabs_id);
                         }
                         if !keep_item {
@@ -612,8 +703,8 @@ impl Dock {
         false
     }
     
-    fn handle_drop(&mut self, cx: &mut Cx, abs: DVec2, item: LiveId, is_move: bool) -> bool {
-        if let Some(pos) = self.find_drop_position(cx, abs) {
+    fn handle_drop(&mut self, cx: &mut Cx, root: LiveId, abs: DVec2, item: LiveId, is_move: bool) -> bool {
+        if let Some(pos) = self.find_drop_position(cx, root, abs) {
             self.needs_save = true;                    
             // ok now what
             // we have a pos
@@ -723,7 +814,7 @@ impl Dock {
     
     fn drop_create(&mut self, cx: &mut Cx, abs: DVec2, item: LiveId, kind: LiveId, name: String, template:LiveId) {
         // lets add a tab
-        if self.handle_drop(cx, abs, item, false) {
+        if self.handle_drop(cx, live_id!(root), abs, item, false) {
             self.needs_save = true;
             self.dock_items.insert(item, DockItem::Tab {
                 name,
@@ -741,7 +832,7 @@ impl Dock {
         if let Some(DockItem::Tab {name, kind, ..}) = self.dock_items.get(&item) {
             let name = name.clone();
             let kind = kind.clone();
-            if self.handle_drop(cx, abs, new_item, false) {
+            if self.handle_drop(cx, live_id!(root), abs, new_item, false) {
                 self.needs_save = true;
                 self.dock_items.insert(new_item, DockItem::Tab {
                     name,
@@ -801,6 +892,7 @@ impl Dock {
         
     pub fn load_state(&mut self, cx: &mut Cx, dock_items: HashMap<LiveId, DockItem>) {
         //log!("{:#?}", self.dock_items);
+        self.close_all_windows(cx);
         self.dock_items = dock_items;
         self.items.clear();
         self.tab_bars.clear();
@@ -808,6 +900,226 @@ impl Dock {
         self.area.redraw(cx);
         self.create_all_items(cx);
     }
+    
+    pub fn layout(&self, cx: &Cx) -> DockLayout {
+        DockLayout {
+            items: self.dock_items.clone(),
+            windows: self.windows.iter().map( | window | {
+                let cxwindow = &cx.windows[window.window.window_id()];
+                let (position, size) = if cxwindow.is_created {
+                    (cxwindow.window_geom.position, cxwindow.window_geom.inner_size)
+                }
+                else {
+                    (cxwindow.create_position.unwrap_or_default(), cxwindow.create_inner_size.unwrap_or_default())
+                };
+                DockWindowLayout {
+                    root: window.root,
+                    x: position.x,
+                    y: position.y,
+                    width: size.x,
+                    height: size.y
+                }
+            }).collect()
+        }
+    }
+    
+    pub fn set_layout(&mut self, cx: &mut Cx, layout: DockLayout) {
+        self.close_all_windows(cx);
+        self.dock_items = layout.items;
+        // tabs that are still there keep their widgets, and with that their state
+        let dock_items = &self.dock_items;
+        self.items.retain( | item_id, (kind, _) | {
+            matches!(dock_items.get(item_id), Some(DockItem::Tab {kind: item_kind, ..}) if item_kind == kind)
+        });
+        self.tab_bars.clear();
+        self.splitters.clear();
+        self.drop_state = None;
+        self.tab_drag = None;
+        for window in layout.windows {
+            self.open_window(cx, window.root, dvec2(window.x, window.y), Some(dvec2(window.width, window.height)));
+        }
+        self.area.redraw(cx);
+        self.create_all_items(cx);
+    }
+    
+    pub fn store_preset(&mut self, cx: &Cx, name: LiveId) {
+        let layout = self.layout(cx);
+        self.presets.insert(name, layout);
+    }
+    
+    pub fn switch_preset(&mut self, cx: &mut Cx, name: LiveId) -> bool {
+        if let Some(layout) = self.presets.get(&name).cloned() {
+            self.set_layout(cx, layout);
+            self.needs_save = true;
+            return true
+        }
+        false
+    }
+    
+    pub fn preset_names(&self) -> Vec<LiveId> {
+        let mut names: Vec<LiveId> = self.presets.keys().cloned().collect();
+        names.sort();
+        names
+    }
+    
+    fn open_window(&mut self, cx: &mut Cx, root: LiveId, position: DVec2, size: Option<DVec2>) {
+        let mut window = Window::new_from_ptr(cx, self.floating_window);
+        window.apply_over(cx, live!{
+            window: {position: (position)}
+        });
+        if let Some(size) = size {
+            window.apply_over(cx, live!{
+                window: {inner_size: (size)}
+            });
+        }
+        if let Some(host_id) = self.area.window_id(cx) {
+            HostedWindows::host(cx, window.window_id(), host_id);
+        }
+        self.windows.push(DockWindow {
+            root,
+            window,
+            drop_target_draw_list: DrawList2d::new(cx)
+        });
+        self.area.redraw(cx);
+    }
+    
+    fn close_window(&mut self, cx: &mut Cx, root: LiveId) {
+        if let Some(index) = self.windows.iter().position( | window | window.root == root) {
+            let mut window = self.windows.remove(index).window;
+            HostedWindows::release(cx, window.window_id());
+            window.close(cx);
+            // dropping it right away would hand its window id out again before the close went through
+            self.closed_windows.push(window);
+            self.area.redraw(cx);
+        }
+    }
+    
+    fn close_all_windows(&mut self, cx: &mut Cx) {
+        while let Some(window) = self.windows.last() {
+            let root = window.root;
+            self.close_window(cx, root);
+        }
+    }
+    
+    fn first_tabs(&self, item_id: LiveId) -> Option<LiveId> {
+        match self.dock_items.get(&item_id) {
+            Some(DockItem::Splitter {a, b, ..}) => self.first_tabs(*a).or_else( || self.first_tabs(*b)),
+            Some(DockItem::Tabs {..}) => Some(item_id),
+            _ => None
+        }
+    }
+    
+    fn tree_items(&self, item_id: LiveId, items: &mut Vec<LiveId>) {
+        items.push(item_id);
+        match self.dock_items.get(&item_id) {
+            Some(DockItem::Splitter {a, b, ..}) => {
+                self.tree_items(*a, items);
+                self.tree_items(*b, items);
+            }
+            Some(DockItem::Tabs {tabs, ..}) => for tab_id in tabs {
+                self.tree_items(*tab_id, items);
+            }
+            _ => ()
+        }
+    }
+    
+    // the os closed a floating window, its tabs go back into the dock
+    fn redock_window(&mut self, cx: &mut Cx, root: LiveId) {
+        if let Some(index) = self.windows.iter().position( | window | window.root == root) {
+            let window = self.windows.remove(index).window;
+            HostedWindows::release(cx, window.window_id());
+            self.closed_windows.push(window);
+        }
+        let mut items = Vec::new();
+        self.tree_items(root, &mut items);
+        let mut tabs = Vec::new();
+        for item_id in items {
+            match self.dock_items.get(&item_id) {
+                Some(DockItem::Tab {..}) => tabs.push(item_id),
+                _ => {self.dock_items.remove(&item_id);}
+            }
+        }
+        if let Some(DockItem::Tabs {tabs: dock_tabs, selected, ..}) = self.first_tabs(live_id!(root)).and_then( | id | self.dock_items.get_mut(&id)) {
+            if !tabs.is_empty() {
+                *selected = dock_tabs.len();
+            }
+            dock_tabs.extend(tabs);
+        }
+        self.needs_save = true;
+        self.area.redraw(cx);
+    }
+    
+    // the window a screen position is in, with the position in that window
+    fn find_drop_window(&self, cx: &Cx, screen: DVec2) -> Option<(LiveId, DVec2)> {
+        for window in &self.windows {
+            let cxwindow = &cx.windows[window.window.window_id()];
+            let abs = screen - cxwindow.window_geom.position;
+            let size = cxwindow.window_geom.inner_size;
+            if cxwindow.is_created && abs.x >= 0.0 && abs.y >= 0.0 && abs.x <= size.x && abs.y <= size.y {
+                return Some((window.root, abs))
+            }
+        }
+        if let Some(window_id) = self.area.window_id(cx) {
+            let abs = screen - cx.windows[window_id].window_geom.position;
+            if self.area.rect(cx).contains(abs) {
+                return Some((live_id!(root), abs))
+            }
+        }
+        None
+    }
+    
+    fn screen_position(cx: &Cx, window_id: WindowId, abs: DVec2) -> DVec2 {
+        cx.windows[window_id].window_geom.position + abs
+    }
+    
+    fn drag_tab_move(&mut self, cx: &mut Cx, window_id: WindowId, abs: DVec2) {
+        let screen = Self::screen_position(cx, window_id, abs);
+        let drop_state = self.find_drop_window(cx, screen).and_then( | (root, abs) | {
+            self.find_drop_position(cx, root, abs)
+        });
+        if drop_state != self.drop_state {
+            self.drop_state = drop_state;
+            self.redraw_drop_targets(cx);
+        }
+    }
+    
+    // a tab dropped on a dock window moves there, anywhere else it tears off into a new window
+    fn drag_tab_end(&mut self, cx: &mut Cx, tab_id: LiveId, window_id: WindowId, abs: DVec2) {
+        self.drop_state = None;
+        self.redraw_drop_targets(cx);
+        let screen = Self::screen_position(cx, window_id, abs);
+        if let Some((root, abs)) = self.find_drop_window(cx, screen) {
+            if self.handle_drop(cx, root, abs, tab_id, true) {
+                self.select_tab(cx, tab_id);
+                self.area.redraw(cx);
+            }
+            return
+        }
+        if let Some((tabs_id, _)) = self.find_tab_bar_of_tab(tab_id) {
+            // the only tab of a floating window has nothing to tear off from
+            if let Some(DockItem::Tabs {tabs, ..}) = self.dock_items.get(&tabs_id) {
+                if tabs.len() == 1 && self.windows.iter().any( | window | window.root == tabs_id) {
+                    return
+                }
+            }
+        }
+        self.close_tab(cx, tab_id, true);
+        let tabs_id = LiveId::unique();
+        self.dock_items.insert(tabs_id, DockItem::Tabs {
+            tabs: vec![tab_id],
+            selected: 0,
+            closable: true
+        });
+        self.open_window(cx, tabs_id, screen, None);
+        self.needs_save = true;
+    }
+    
+    fn redraw_drop_targets(&mut self, cx: &mut Cx) {
+        self.drop_target_draw_list.redraw(cx);
+        for window in &self.windows {
+            window.drop_target_draw_list.redraw(cx);
+        }
+    }
 }
 
 
@@ -839,7 +1151,11 @@ impl Widget for Dock {
             let contents_view = &mut tab_bar.contents_draw_list;
             for action in cx.capture_actions(|cx| tab_bar.tab_bar.handle_event(cx, event, scope)) {
                 match action.as_widget_action().cast() {
-                    TabBarAction::ShouldTabStartDrag(item) => cx.widget_action(uid, &scope.path, DockAction::ShouldTabStartDrag(item)),
+                    TabBarAction::ShouldTabStartDrag(item) => {
+                        // we move the tab ourselves unless the app starts an os drag for it
+                        self.tab_drag = Some(item);
+                        cx.widget_action(uid, &scope.path, DockAction::ShouldTabStartDrag(item))
+                    }
                     TabBarAction::TabWasPressed(tab_id) => {
                         self.needs_save = true;
                         if let Some(DockItem::Tabs {tabs, selected, ..}) = dock_items.get_mut(&panel_id) {
@@ -867,17 +1183,42 @@ impl Widget for Dock {
             });
         }
         
+        let mut closed_windows = Vec::new();
+        for window in self.windows.iter_mut() {
+            for action in cx.capture_actions( | cx | window.window.handle_event(cx, event, scope)) {
+                match action.as_widget_action().cast() {
+                    WindowAction::WindowClosed => closed_windows.push(window.root),
+                    WindowAction::WindowGeomChange(_) => self.needs_save = true,
+                    _ => ()
+                }
+            }
+        }
+        for root in closed_windows {
+            self.redock_window(cx, root);
+        }
+        
+        if let Some(tab_id) = self.tab_drag {
+            match event {
+                Event::MouseMove(e) => self.drag_tab_move(cx, e.window_id, e.abs),
+                Event::MouseUp(e) => {
+                    self.tab_drag = None;
+                    self.drag_tab_end(cx, tab_id, e.window_id, e.abs);
+                }
+                _ => ()
+            }
+        }
+        
         if let Event::DragEnd = event {
             // end our possible dragstate
             self.drop_state = None;
-            self.drop_target_draw_list.redraw(cx);
+            self.redraw_drop_targets(cx);
         }
         
         // alright lets manage the drag areas
         match event.drag_hits(cx, self.area) {
             DragHit::Drag(f) => {
                 self.drop_state = None;
-                self.drop_target_draw_list.redraw(cx);
+                self.redraw_drop_targets(cx);
                 match f.state {
                     DragState::In | DragState::Over => {
                         cx.widget_action(uid, &scope.path, DockAction::Drag(f.clone()))
@@ -888,7 +1229,7 @@ impl Widget for Dock {
             DragHit::Drop(f) => {
                 self.needs_save = true;
                 self.drop_state = None;
-                self.drop_target_draw_list.redraw(cx);
+                self.redraw_drop_targets(cx);
                 cx.widget_action(uid, &scope.path, DockAction::Drop(f.clone()))
             }
             _ => {}
@@ -898,8 +1239,9 @@ impl Widget for Dock {
     fn draw_walk(&mut self, cx: &mut Cx2d, scope:&mut Scope, walk: Walk) -> DrawStep {
         if self.draw_state.begin_with(cx, &self.dock_items, | _, dock_items | {
             let id = live_id!(root);
-            vec![DrawStackItem::from_dock_item(id, dock_items.get(&id))]
+            vec![DrawStackItem::Window {index: 0}, DrawStackItem::from_dock_item(id, dock_items.get(&id))]
         }) {
+            self.closed_windows.clear();
             self.begin(cx, walk);
         }
         
@@ -1000,6 +1342,30 @@ impl Widget for Dock {
                     }
                     else {panic!()}
                 }
+                Some(DrawStackItem::Window {index}) => {
+                    // floating windows are drawn from here, inside the window of the dock
+                    if let Some(window) = self.windows.get_mut(index) {
+                        stack.push(DrawStackItem::Window {index: index + 1});
+                        stack.push(DrawStackItem::WindowEnd {index});
+                        window.window.begin_always(cx);
+                        cx.begin_turtle(Walk::default(), self.layout);
+                        let root = window.root;
+                        stack.push(DrawStackItem::from_dock_item(root, self.dock_items.get(&root)));
+                    }
+                }
+                Some(DrawStackItem::WindowEnd {index}) => {
+                    let root = self.windows[index].root;
+                    self.draw_corners(cx, root);
+                    let window = &mut self.windows[index];
+                    if window.drop_target_draw_list.begin(cx, Walk::default()).is_redrawing() {
+                        if let Some(pos) = self.drop_state.filter( | pos | pos.root == root) {
+                            self.drag_quad.draw_abs(cx, pos.rect);
+                        }
+                        window.drop_target_draw_list.end(cx);
+                    }
+                    cx.end_turtle();
+                    window.window.end(cx);
+                }
                 Some(DrawStackItem::Invalid) => {}
                 None => {
                     break
@@ -1040,7 +1406,7 @@ impl DockRef {
     // user wants to drag, set dh accordingly
     pub fn accept_drag(&self, cx: &mut Cx, dh: DragHitEvent, dr: DragResponse) {
         if let Some(mut dock) = self.borrow_mut() {
-            if let Some(pos) = dock.find_drop_position(cx, dh.abs) {
+            if let Some(pos) = dock.find_drop_position(cx, live_id!(root), dh.abs) {
                 *dh.response.lock().unwrap() = dr;
                 dock.drop_state = Some(pos);
             }
@@ -1065,7 +1431,7 @@ impl DockRef {
     
     pub fn drop_move(&self, cx: &mut Cx, abs: DVec2, item: LiveId) {
         if let Some(mut dock) = self.borrow_mut() {
-            dock.handle_drop(cx, abs, item, true);
+            dock.handle_drop(cx, live_id!(root), abs, item, true);
         }
     }
     
@@ -1151,6 +1517,42 @@ impl DockRef {
     }
     
     pub fn tab_start_drag(&self, cx: &mut Cx, _tab_id: LiveId, item: DragItem) {
+        if let Some(mut dock) = self.borrow_mut() {
+            dock.tab_drag = None;
+        }
         cx.start_dragging(vec![item]);
     }
+    
+    pub fn layout(&self, cx: &Cx) -> Option<DockLayout> {
+        if let Some(dock) = self.borrow() {
+            return Some(dock.layout(cx));
+        }
+        None
+    }
+    
+    pub fn set_layout(&self, cx: &mut Cx, layout: DockLayout) {
+        if let Some(mut dock) = self.borrow_mut() {
+            dock.set_layout(cx, layout);
+        }
+    }
+    
+    pub fn store_preset(&self, cx: &Cx, name: LiveId) {
+        if let Some(mut dock) = self.borrow_mut() {
+            dock.store_preset(cx, name);
+        }
+    }
+    
+    pub fn switch_preset(&self, cx: &mut Cx, name: LiveId) -> bool {
+        if let Some(mut dock) = self.borrow_mut() {
+            return dock.switch_preset(cx, name);
+        }
+        false
+    }
+    
+    pub fn preset_names(&self) -> Vec<LiveId> {
+        if let Some(dock) = self.borrow() {
+            return dock.preset_names();
+        }
+        Vec::new()
+    }
 }
@@ -43,6 +43,7 @@ pub struct DropDown {
     #[rust] is_open: bool,
     
     #[live] selected_item: usize,
+    #[live] nav_order: NavOrder,
     
     #[layout] layout: Layout,
 }
@@ -86,6 +87,10 @@ pub enum DropDownAction {
 
 impl DropDown {
     
+    pub fn set_key_focus(&self, cx: &mut Cx) {
+        cx.set_key_focus(self.draw_bg.area());
+    }
+    
     pub fn set_open(&mut self, cx: &mut Cx) {
         self.is_open = true;
         self.draw_bg.apply_over(cx, live!{open: 1.0});
@@ -128,7 +133,19 @@ impl DropDown {
         }
         self.draw_bg.end(cx);
         
-        cx.add_nav_stop(self.draw_bg.area(), NavRole::DropDown, Margin::default());
+        cx.add_nav_stop_with(NavStop::new(self.draw_bg.area(), NavRole::DropDown).with_order(self.nav_order));
+        cx.add_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::DropDown)
+            .with_value(AccessValue::Text(self.labels.get(self.selected_item).cloned().unwrap_or_default()))
+            .with_states(AccessStates {
+                focusable: true,
+                focused: cx.has_key_focus(self.draw_bg.area()),
+                expanded: Some(self.is_open),
+                ..Default::default()
+            })
+            .with_action(AccessActionKind::Press)
+            .with_action(AccessActionKind::Focus)
+            .with_action(AccessActionKind::SetValue)
+            .with_area(self.draw_bg.area()));
         
         if self.is_open && self.popup_menu.is_some() {
             //cx.set_sweep_lock(self.draw_bg.area());
@@ -258,13 +275,43 @@ impl Widget for DropDown {
                     }
                 }
                 KeyCode::ArrowDown => {
-                    if self.values.len() > 0 && self.selected_item < self.values.len() - 1 {
+                    if self.selected_item + 1 < self.labels.len() {
                         self.selected_item += 1;
                         cx.widget_action(uid, &scope.path, DropDownAction::Select(self.selected_item, self.values.get(self.selected_item).cloned().unwrap_or(LiveValue::None)));
                         self.set_closed(cx);
                         self.draw_bg.redraw(cx);
                     }
                 },
+                KeyCode::Space | KeyCode::ReturnKey if !ke.is_repeat => {
+                    if self.is_open {
+                        self.set_closed(cx);
+                    }
+                    else {
+                        self.set_open(cx);
+                    }
+                }
+                _ => ()
+            }
+            Hit::AccessAction(e) => match e.action {
+                AccessAction::Press => {
+                    cx.set_key_focus(self.draw_bg.area());
+                    if self.is_open {
+                        self.set_closed(cx);
+                    }
+                    else {
+                        self.set_open(cx);
+                    }
+                }
+                AccessAction::Focus => {
+                    cx.set_key_focus(self.draw_bg.area());
+                }
+                // screen readers can pick an item by its label without going through the popup
+                AccessAction::SetText(label) => if let Some(index) = self.labels.iter().position( | l | *l == label) {
+                    self.selected_item = index;
+                    cx.widget_action(uid, &scope.path, DropDownAction::Select(self.selected_item, self.values.get(self.selected_item).cloned().unwrap_or(LiveValue::None)));
+                    self.set_closed(cx);
+                    self.draw_bg.redraw(cx);
+                }
                 _ => ()
             }
             Hit::FingerDown(_fe) => {
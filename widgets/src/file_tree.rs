@@ -154,6 +154,7 @@ impl FileTreeNode {
         
         self.draw_name.draw_walk(cx, Walk::fit(), Align::default(), name);
         self.draw_bg.end(cx);
+        self.add_nav_stop(cx);
     }
     
     pub fn draw_file(&mut self, cx: &mut Cx2d, name: &str, is_even: f32, node_height: f64, depth: usize, scale: f64) {
@@ -165,6 +166,12 @@ impl FileTreeNode {
         
         self.draw_name.draw_walk(cx, Walk::fit(), Align::default(), name);
         self.draw_bg.end(cx);
+        self.add_nav_stop(cx);
+    }
+    
+    fn add_nav_stop(&mut self, cx: &mut Cx2d) {
+        let selected = self.animator_in_state(cx, id!(select.on));
+        cx.add_nav_stop_with(NavStop::new(self.draw_bg.area(), NavRole::TreeItem).with_selected(selected));
     }
     
     fn indent_walk(&self, depth: usize) -> Walk {
@@ -178,6 +185,7 @@ impl FileTreeNode {
                 right: depth as f64 * 4.0,
                 bottom: 0.0,
             },
+            ..Default::default()
         }
     }
     
@@ -217,28 +225,53 @@ impl FileTreeNode {
                 }
             }
             Hit::FingerDown(_) => {
-                self.animator_play(cx, id!(select.on));
-                if self.is_folder {
-                    if self.animator_in_state(cx, id!(open.on)) {
-                        self.animator_play(cx, id!(open.off));
-                        actions.push((node_id, FileTreeNodeAction::Closing));
-                    }
-                    else {
-                        self.animator_play(cx, id!(open.on));
-                        actions.push((node_id, FileTreeNodeAction::Opening));
-                    }
+                self.activate(cx, node_id, actions);
+            }
+            Hit::KeyDown(ke) => match ke.key_code {
+                KeyCode::Space | KeyCode::ReturnKey if !ke.is_repeat => {
+                    self.activate(cx, node_id, actions);
                 }
-                actions.push((node_id, FileTreeNodeAction::WasClicked));
+                KeyCode::ArrowRight if self.is_folder && self.animator_in_state(cx, id!(open.off)) => {
+                    self.animator_play(cx, id!(open.on));
+                    actions.push((node_id, FileTreeNodeAction::Opening));
+                }
+                KeyCode::ArrowLeft if self.is_folder && self.animator_in_state(cx, id!(open.on)) => {
+                    self.animator_play(cx, id!(open.off));
+                    actions.push((node_id, FileTreeNodeAction::Closing));
+                }
+                _ => ()
+            }
+            Hit::KeyFocus(_) => {
+                self.set_is_focussed(cx, true, Animate::Yes);
+            }
+            Hit::KeyFocusLost(_) => {
+                self.set_is_focussed(cx, false, Animate::Yes);
             }
             _ => {}
         }
     }
+    
+    fn activate(&mut self, cx: &mut Cx, node_id: LiveId, actions: &mut Vec<(LiveId, FileTreeNodeAction)>) {
+        self.animator_play(cx, id!(select.on));
+        if self.is_folder {
+            if self.animator_in_state(cx, id!(open.on)) {
+                self.animator_play(cx, id!(open.off));
+                actions.push((node_id, FileTreeNodeAction::Closing));
+            }
+            else {
+                self.animator_play(cx, id!(open.on));
+                actions.push((node_id, FileTreeNodeAction::Opening));
+            }
+        }
+        actions.push((node_id, FileTreeNodeAction::WasClicked));
+    }
 }
 
 impl FileTree {
     
     pub fn begin(&mut self, cx: &mut Cx2d, walk: Walk) {
         self.scroll_bars.begin(cx, walk, self.layout);
+        cx.begin_nav_group(NavAxis::Vertical);
         self.count = 0;
     }
     
@@ -254,6 +287,7 @@ impl FileTree {
             walk += height.max(1.0);
         }
         
+        cx.end_nav_group();
         self.draw_scroll_shadow.draw(cx, dvec2(0., 0.));
         self.scroll_bars.end(cx);
         
@@ -418,7 +452,10 @@ impl Widget for FileTree {
                     self.open_nodes.remove(&node_id);
                 }
                 FileTreeNodeAction::WasClicked => {
-                    cx.set_key_focus(self.scroll_bars.area());
+                    // the node itself takes the focus so the arrow keys continue from there
+                    if let Some((node, _)) = self.tree_nodes.get(&node_id) {
+                        cx.set_key_focus(node.draw_bg.area());
+                    }
                     if let Some(last_selected) = self.selected_node_id {
                         if last_selected != node_id {
                             self.tree_nodes.get_mut(&last_selected).unwrap().0.set_is_selected(cx, false, Animate::Yes);
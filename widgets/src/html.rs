@@ -2,7 +2,7 @@ use crate::{
     makepad_derive_widget::*,
     makepad_draw::*,
     makepad_html::*,
-    text_flow::TextFlow,
+    text_flow::{TextFlow, TextFlowCopyFormat},
     widget::*,
 };
 
@@ -69,6 +69,7 @@ impl LiveHook for Html {
         if new_doc != self.doc{
             self.doc = new_doc;
             self.text_flow.clear_items();
+            self.text_flow.clear_selection();
         }
         if errors.as_ref().unwrap().len()>0{
             log!("HTML parser returned errors {:?}", errors)
@@ -93,7 +94,7 @@ impl Html {
             *trim = TrimWhitespaceInText::Trim;
             tf.bold.push();
             tf.push_size_abs_scale(scale);
-            cx.turtle_new_line();
+            tf.new_paragraph(cx);
         }
 
         match node.open_tag_lc() {
@@ -106,8 +107,8 @@ impl Html {
 
             some_id!(p) => {
                 // there's probably a better way to do this by setting margins...
-                cx.turtle_new_line();
-                cx.turtle_new_line();
+                tf.new_paragraph(cx);
+                tf.new_paragraph(cx);
                 trim_whitespace_in_text = TrimWhitespaceInText::Trim;
             }
             some_id!(code) => {
@@ -119,26 +120,26 @@ impl Html {
                 tf.inline_code.push();
             }
             some_id!(pre) => {
-                cx.turtle_new_line();
+                tf.new_paragraph(cx);
                 tf.fixed.push();
                 tf.ignore_newlines.push(false);
                 tf.combine_spaces.push(false);
                 tf.begin_code(cx);
             }
             some_id!(blockquote) => {
-                cx.turtle_new_line();
+                tf.new_paragraph(cx);
                 tf.ignore_newlines.push(false);
                 tf.combine_spaces.push(false);
                 tf.begin_quote(cx);
             }
             some_id!(br) => {
-                cx.turtle_new_line();
+                tf.new_line(cx);
             }
             some_id!(hr)
             | some_id!(sep) => {
-                cx.turtle_new_line();
+                tf.new_paragraph(cx);
                 tf.sep(cx);
-                cx.turtle_new_line();
+                tf.new_paragraph(cx);
             }
             some_id!(u) => tf.underline.push(),
             some_id!(del)
@@ -234,7 +235,7 @@ impl Html {
                 
                 // Now, actually emit the list item.
                 // log!("marker: {marker}, pad: {pad}");
-                cx.turtle_new_line();
+                tf.new_paragraph(cx);
                 tf.begin_list_item(cx, marker, pad);
             }
             Some(x) => return (Some(x), trim_whitespace_in_text),
@@ -258,15 +259,15 @@ impl Html {
             | some_id!(h6) => {
                 tf.font_sizes.pop();
                 tf.bold.pop();
-                cx.turtle_new_line();
+                tf.new_paragraph(cx);
             }
             some_id!(b)
             | some_id!(strong) => tf.bold.pop(),
             some_id!(i)
             | some_id!(em) => tf.italic.pop(),
             some_id!(p) => {
-                cx.turtle_new_line();
-                cx.turtle_new_line();
+                tf.new_paragraph(cx);
+                tf.new_paragraph(cx);
             }
             some_id!(blockquote) => {
                 tf.ignore_newlines.pop();
@@ -369,6 +370,7 @@ impl Widget for Html {
         self.body.set(v);
         let mut errors = Some(Vec::new());
         self.doc = parse_html(self.body.as_ref(), &mut errors, InternLiveId::No);
+        self.text_flow.clear_selection();
         if errors.as_ref().unwrap().len()>0{
             log!("HTML parser returned errors {:?}", errors)
         }
@@ -406,6 +408,12 @@ fn handle_custom_widget(
 }
 
 
+impl HtmlRef {
+    pub fn selected_text_as(&self, format: TextFlowCopyFormat) -> Option<String> {
+        self.borrow().and_then( | inner | inner.text_flow.selected_text_as(format))
+    }
+}
+
 #[derive(Debug, Clone, DefaultNone)]
 pub enum HtmlLinkAction {
     Clicked {
@@ -47,6 +47,9 @@ impl Widget for Label {
         });
         self.draw_text.draw_walk(cx, walk, self.align, self.text.as_ref());
         cx.end_turtle_with_area(&mut self.area);
+        cx.add_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::Label)
+            .with_name(self.text.as_ref().trim())
+            .with_area(self.area));
         DrawStep::done()
     }
     
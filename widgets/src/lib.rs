@@ -12,6 +12,9 @@ pub mod label;
 pub mod image;
 pub mod image_blend;
 pub mod icon;
+pub mod svg;
+pub mod vector_canvas;
+pub mod viewport_3d;
 pub mod link_label;
 pub mod drop_down;
 pub mod popup_menu;
@@ -19,6 +22,9 @@ pub mod check_box;
 pub mod radio_button;
 pub mod text_input;
 pub mod slider;
+pub mod number_input;
+pub mod date_picker;
+pub mod time_picker;
 pub mod scroll_bar;
 pub mod scroll_bars;
 pub mod splitter;
@@ -53,6 +59,10 @@ pub mod page_flip;
 pub mod keyboard_view;
 pub mod flat_list;
 pub mod file_tree;
+pub mod data_grid;
+pub mod chart;
+pub mod rich_text_input;
+pub mod theme;
 pub mod slides_view;
 pub mod color_picker;
 pub mod root;
@@ -85,15 +95,21 @@ pub mod designer_data;
 pub mod designer_toolbox;
 
 pub use crate::{
-    data_binding::{DataBindingStore, DataBindingMap},
+    data_binding::{DataBindingStore, DataBindingMap, DataBind, DataValue, DataBoundList, DataBoundItem},
     button::*,
     cached_widget::*,
     view::*,
     image::*,
     image_blend::*,
     icon::*,
+    svg::*,
+    vector_canvas::*,
+    viewport_3d::*,
     label::*,
     slider::*,
+    number_input::*,
+    date_picker::*,
+    time_picker::*,
     root::*,
     text_flow::*,
     markdown::*,
@@ -109,6 +125,10 @@ pub use crate::{
     link_label::*,
     portal_list::*,
     flat_list::*,
+    data_grid::*,
+    chart::*,
+    rich_text_input::*,
+    theme::*,
     page_flip::*,
     slide_panel::*,
     fold_button::*,
@@ -164,6 +184,9 @@ pub fn live_design(cx: &mut Cx) {
     crate::multi_image::live_design(cx);
     crate::image_blend::live_design(cx);
     crate::icon::live_design(cx);
+    crate::svg::live_design(cx);
+    crate::vector_canvas::live_design(cx);
+    crate::viewport_3d::live_design(cx);
     crate::rotated_image::live_design(cx);
     crate::modal::live_design(cx);
     crate::tooltip::live_design(cx);
@@ -184,6 +207,9 @@ pub fn live_design(cx: &mut Cx) {
     crate::radio_button::live_design(cx);
     crate::popup_menu::live_design(cx);
     crate::drop_down::live_design(cx);
+    crate::number_input::live_design(cx);
+    crate::date_picker::live_design(cx);
+    crate::time_picker::live_design(cx);
     crate::multi_window::live_design(cx);
     crate::portal_list::live_design(cx);
     crate::flat_list::live_design(cx);
@@ -193,6 +219,9 @@ pub fn live_design(cx: &mut Cx) {
     crate::dock::live_design(cx);
     crate::color_picker::live_design(cx);
     crate::file_tree::live_design(cx);
+    crate::data_grid::live_design(cx);
+    crate::chart::live_design(cx);
+    crate::rich_text_input::live_design(cx);
     crate::slides_view::live_design(cx);
     crate::tab_close_button::live_design(cx);
     crate::keyboard_view::live_design(cx);
@@ -3,7 +3,7 @@ use crate::{
     makepad_derive_widget::*,
     makepad_draw::*,
     widget::*,
-    text_flow::TextFlow,
+    text_flow::{TextFlow, TextFlowCopyFormat},
     link_label::LinkLabel,
     WidgetMatchEvent,
 };
@@ -79,6 +79,7 @@ impl Markdown {
         if new_doc != self.doc{
             self.doc = new_doc;
             //self.text_flow.clear_items();
+            self.text_flow.clear_selection();
         }
     }
     
@@ -87,38 +88,38 @@ impl Markdown {
         for node in &doc.nodes{
             match node{
                 MarkdownNode::BeginHead{level}=>{
-                    cx.turtle_new_line_with_spacing(self.paragraph_spacing);
+                    tf.new_paragraph_with_spacing(cx, self.paragraph_spacing);
                     tf.push_size_abs_scale(4.5 / *level as f64);
                     tf.bold.push();
                 },
                 MarkdownNode::Separator=>{
-                    cx.turtle_new_line_with_spacing(self.paragraph_spacing);
+                    tf.new_paragraph_with_spacing(cx, self.paragraph_spacing);
                     tf.sep(cx);
                 }
                 MarkdownNode::EndHead=>{
                     tf.bold.pop();
                     tf.font_sizes.pop();
-                    cx.turtle_new_line();
+                    tf.new_paragraph(cx);
                 },
                 MarkdownNode::NewLine{paragraph: true}=>{
-                    cx.turtle_new_line_with_spacing(self.paragraph_spacing);
+                    tf.new_paragraph_with_spacing(cx, self.paragraph_spacing);
                 },
                 MarkdownNode::NewLine{paragraph: false}=>{
                     if self.in_code_block{
                         self.code_block_string.push_str("\n");
                     }
                     else{
-                        cx.turtle_new_line();
+                        tf.new_line(cx);
                     }
                 },
                 MarkdownNode::BeginNormal=>{
-                    cx.turtle_new_line_with_spacing(self.paragraph_spacing);
+                    tf.new_paragraph_with_spacing(cx, self.paragraph_spacing);
                 },
                 MarkdownNode::EndNormal=>{
                                         
                 },
                 MarkdownNode::BeginListItem{label}=>{
-                    cx.turtle_new_line();
+                    tf.new_line(cx);
                     let str = match label{
                         MarkdownListLabel::Plus=>"+",
                         MarkdownListLabel::Minus=>"-",
@@ -149,7 +150,7 @@ impl Markdown {
                     tf.draw_text(cx, " ]");
                 },
                 MarkdownNode::BeginQuote=>{
-                    cx.turtle_new_line_with_spacing(self.paragraph_spacing);
+                    tf.new_paragraph_with_spacing(cx, self.paragraph_spacing);
                     tf.begin_quote(cx);
                 },
                 MarkdownNode::EndQuote=>{
@@ -176,13 +177,13 @@ impl Markdown {
                     if self.use_code_block_widget{
                         self.in_code_block = true;
                         self.code_block_string.clear();
-                        cx.turtle_new_line_with_spacing(self.pre_code_spacing);
+                        tf.new_paragraph_with_spacing(cx, self.pre_code_spacing);
                     }
                     else{
                         const FIXED_FONT_SIZE_SCALE: f64 = 0.85;
                         tf.push_size_rel_scale(FIXED_FONT_SIZE_SCALE);
                         // alright lets check if we need to use a widget
-                        cx.turtle_new_line_with_spacing(self.paragraph_spacing);
+                        tf.new_paragraph_with_spacing(cx, self.paragraph_spacing);
                         tf.combine_spaces.push(false);
                         tf.fixed.push();
                                 
@@ -241,6 +242,10 @@ impl MarkdownRef {
         let Some(mut inner) = self.borrow_mut() else { return };
         inner.set_text(v)
     }
+    
+    pub fn selected_text_as(&self, format: TextFlowCopyFormat) -> Option<String> {
+        self.borrow().and_then( | inner | inner.text_flow.selected_text_as(format))
+    }
 }
 
 #[derive(Live, LiveHook, Widget)]
@@ -3,7 +3,7 @@ use crate::makepad_draw::*;
 
 live_design!{
     import makepad_draw::shader::std::*;
-    
+
     NavControlBase = {{NavControl}} {}
 }
 
@@ -12,11 +12,15 @@ pub struct NavControl {
     #[live] draw_list: DrawList2d,
     #[live] draw_focus: DrawQuad,
     #[live] draw_text: DrawText,
+    /// how far the focus ring sits outside the focused widget
+    #[live(2.0)] focus_margin: f64,
     #[rust] _recent_focus: Area,
+    /// the ring is only shown once the keyboard moved the focus, a click hides it again
+    #[rust] focus_visible: bool,
 }
 
 impl NavControl {
-    
+
     pub fn send_trigger_to_scroll_stack(cx: &mut Cx, stack:Vec<Area>){
         let mut prev_area = None;
         for next_area in stack{
@@ -29,53 +33,101 @@ impl NavControl {
             prev_area = Some(next_area);
         }
     }
-    
+
+    fn focus_stop(&mut self, cx: &mut Cx, stop: &NavStopInfo) {
+        let mut scroll_stack = stop.scroll_stack.clone();
+        scroll_stack.push(stop.stop.area);
+        Self::send_trigger_to_scroll_stack(cx, scroll_stack);
+        cx.set_key_focus(stop.stop.area);
+        self.focus_visible = true;
+        self.draw_list.redraw(cx);
+    }
+
+    fn handle_tab(&mut self, cx: &mut Cx, root: DrawListId, backwards: bool) {
+        let (stops, _) = Cx2d::collect_nav_stops(cx, root);
+        let order = Cx2d::tab_order(cx, &stops);
+        if order.is_empty() {
+            return
+        }
+        let current = order.iter().position( | index | cx.has_key_focus(stops[*index].stop.area));
+        let next = match (current, backwards) {
+            (Some(pos), false) => (pos + 1) % order.len(),
+            (Some(pos), true) => (pos + order.len() - 1) % order.len(),
+            (None, false) => 0,
+            (None, true) => order.len() - 1,
+        };
+        self.focus_stop(cx, &stops[order[next]]);
+    }
+
+    fn handle_arrow(&mut self, cx: &mut Cx, root: DrawListId, key_code: KeyCode) {
+        let (stops, groups) = Cx2d::collect_nav_stops(cx, root);
+        let Some(current) = stops.iter().position( | s | cx.has_key_focus(s.stop.area)) else {
+            return
+        };
+        let Some(group) = stops[current].group else {
+            return
+        };
+        if stops[current].stop.role.handles_arrow_keys() {
+            return
+        }
+        let forward = match (groups[group], key_code) {
+            (NavAxis::Horizontal | NavAxis::Both, KeyCode::ArrowLeft) => false,
+            (NavAxis::Horizontal | NavAxis::Both, KeyCode::ArrowRight) => true,
+            (NavAxis::Vertical | NavAxis::Both, KeyCode::ArrowUp) => false,
+            (NavAxis::Vertical | NavAxis::Both, KeyCode::ArrowDown) => true,
+            _ => return
+        };
+        let members: Vec<usize> = (0..stops.len()).filter( | i | stops[*i].group == Some(group)).collect();
+        let pos = members.iter().position( | i | *i == current).unwrap();
+        let next = if forward {
+            members.get(pos + 1)
+        }
+        else if pos > 0 {
+            members.get(pos - 1)
+        }
+        else {
+            None
+        };
+        if let Some(next) = next {
+            self.focus_stop(cx, &stops[*next]);
+        }
+    }
+
     pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, root: DrawListId) {
         match event {
             Event::KeyDown(ke) => match ke.key_code {
                 KeyCode::Tab => {
-                    if ke.modifiers.shift {
-                        let mut prev_area = Area::Empty;
-                        if let Some((prev_area, scroll_stack)) = Cx2d::iterate_nav_stops(cx, root, | cx, stop | {
-                            if cx.has_key_focus(stop.area) {
-                                return Some(prev_area);
-                            }
-                            prev_area = stop.area;
-                            None
-                        }) {
-                            if !prev_area.is_empty() {
-                                Self::send_trigger_to_scroll_stack(cx, scroll_stack);
-                                cx.set_key_focus(prev_area);
-                            }
-                        }
-                    }
-                    else {
-                        let mut next_stop = false;
-                        if let Some((next_area, scroll_stack)) = Cx2d::iterate_nav_stops(cx, root, | cx, stop | {
-                            if next_stop {
-                                return Some(stop.area)
-                            }
-                            if cx.has_key_focus(stop.area) {
-                                next_stop = true;
-                            }
-                            None
-                        }) {
-                            Self::send_trigger_to_scroll_stack(cx, scroll_stack);
-                            cx.set_key_focus(next_area);
-                        }
-                    }
+                    self.handle_tab(cx, root, ke.modifiers.shift);
+                }
+                KeyCode::ArrowLeft | KeyCode::ArrowRight | KeyCode::ArrowUp | KeyCode::ArrowDown
+                    if !ke.modifiers.any() => {
+                    self.handle_arrow(cx, root, ke.key_code);
                 }
                 _ => ()
             },
+            Event::MouseDown(_) | Event::TouchUpdate(_) if self.focus_visible => {
+                self.focus_visible = false;
+                self.draw_list.redraw(cx);
+            }
             _ => ()
         }
     }
-    
+
     pub fn draw(&mut self, cx: &mut Cx2d) {
-        if !self.draw_list.begin(cx, Walk::default()).is_redrawing() {
-            return
+        // the focused widget can move whenever anything redraws, so the ring is always redrawn
+        self.draw_list.begin_always(cx);
+        let area = cx.key_focus();
+        if self.focus_visible && area.is_valid(cx) {
+            // clipping only happens when the window's turtle ends, so this is the unclipped rect
+            let rect = area.rect(cx);
+            if rect.size.x > 0.0 && rect.size.y > 0.0 {
+                let m = self.focus_margin;
+                self.draw_focus.draw_abs(cx, Rect {
+                    pos: rect.pos - dvec2(m, m),
+                    size: rect.size + dvec2(2.0 * m, 2.0 * m)
+                });
+            }
         }
-        
         self.draw_list.end(cx);
     }
 }
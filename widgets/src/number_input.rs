@@ -0,0 +1,380 @@
+use {
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        widget::*,
+        text_input::{TextInput, TextInputAction}
+    }
+};
+
+live_design!{
+    NumberInputBase = {{NumberInput}} {}
+}
+
+/// The decimal and digit group separators a number is shown and typed with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NumberFormat {
+    pub decimal: char,
+    pub group: Option<char>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {decimal: '.', group: Some(',')}
+    }
+}
+
+impl NumberFormat {
+    /// Separators for a POSIX style locale name like `de_DE.UTF-8`, or a bare language like `fr`
+    pub fn for_locale(locale: &str) -> Self {
+        let name = locale.split(['.', '@']).next().unwrap_or_default();
+        if name.is_empty() || name == "C" || name == "POSIX" {
+            return Self {decimal: '.', group: None}
+        }
+        let mut parts = name.split(['_', '-']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.next().unwrap_or_default().to_ascii_uppercase();
+        let (decimal, group) = match (language.as_str(), region.as_str()) {
+            ("de" | "it", "CH") => ('.', '\''),
+            ("de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" | "sr", _) => (',', '.'),
+            ("fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "nb" | "nn" | "no" | "fi" | "uk" | "hu" | "bg" | "lt" | "lv" | "et", _) => (',', '\u{a0}'),
+            _ => ('.', ',')
+        };
+        Self {decimal, group: Some(group)}
+    }
+
+    /// Separators for the locale the process runs in
+    pub fn system() -> Self {
+        let locale = ["LC_ALL", "LC_NUMERIC", "LANG"].iter()
+            .filter_map( | var | std::env::var(var).ok())
+            .find( | v | !v.is_empty())
+            .unwrap_or_default();
+        Self::for_locale(&locale)
+    }
+
+    pub fn format(&self, value: f64, precision: usize) -> String {
+        let digits = format!("{:.*}", precision, value.abs());
+        let (int, frac) = match digits.split_once('.') {
+            Some((int, frac)) => (int, Some(frac)),
+            None => (digits.as_str(), None)
+        };
+        let mut out = String::new();
+        // rounding can turn a tiny negative number into zero, which shouldn't keep its sign
+        if value < 0.0 && digits.chars().any( | c | c.is_ascii_digit() && c != '0') {
+            out.push('-');
+        }
+        for (i, c) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 {
+                if let Some(group) = self.group {
+                    out.push(group);
+                }
+            }
+            out.push(c);
+        }
+        if let Some(frac) = frac {
+            out.push(self.decimal);
+            out.push_str(frac);
+        }
+        out
+    }
+
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        let mut clean = String::new();
+        for c in text.trim().chars() {
+            if Some(c) == self.group || c.is_whitespace() {
+                continue;
+            }
+            match c {
+                c if c == self.decimal => clean.push('.'),
+                '\u{2212}' => clean.push('-'),
+                c => clean.push(c)
+            }
+        }
+        clean.parse::<f64>().ok().filter( | v | v.is_finite())
+    }
+}
+
+#[derive(Live, Widget)]
+pub struct NumberInput {
+    #[animator] animator: Animator,
+
+    #[redraw] #[live] draw_bg: DrawQuad,
+    #[live] draw_spinner: DrawQuad,
+
+    #[walk] walk: Walk,
+    #[layout] layout: Layout,
+    #[live] spinner_walk: Walk,
+
+    #[live] text_input: TextInput,
+
+    #[live(f64::MIN)] min: f64,
+    #[live(f64::MAX)] max: f64,
+    #[live(1.0)] step: f64,
+    #[live] precision: usize,
+    #[live] default: f64,
+
+    /// The locale the value is shown in, like `de_DE`, empty for the system locale
+    #[live] locale: String,
+    #[live(true)] grouping: bool,
+    /// How many pixels the finger moves on the spinner to change the value by one step
+    #[live(4.0)] scrub_distance: f64,
+
+    #[rust] value: f64,
+    #[rust] format: NumberFormat,
+    #[rust] scrub: Option<(f64, bool)>,
+}
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum NumberInputAction {
+    Change(f64),
+    None
+}
+
+impl LiveHook for NumberInput {
+    fn after_apply(&mut self, _cx: &mut Cx, apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        let format = if self.locale.is_empty() {
+            NumberFormat::system()
+        }
+        else {
+            NumberFormat::for_locale(&self.locale)
+        };
+        self.format = NumberFormat {
+            group: format.group.filter( | _ | self.grouping),
+            ..format
+        };
+        if apply.from.is_from_doc() {
+            self.update_text_input();
+        }
+    }
+
+    fn after_new_from_doc(&mut self, _cx: &mut Cx) {
+        self.value = self.clamp(self.default);
+        self.update_text_input();
+    }
+}
+
+impl NumberInput {
+    fn clamp(&self, value: f64) -> f64 {
+        let value = if self.step > 0.0 && self.min > f64::MIN {
+            ((value - self.min) / self.step).round() * self.step + self.min
+        }
+        else if self.step > 0.0 {
+            (value / self.step).round() * self.step
+        }
+        else {
+            value
+        };
+        value.max(self.min).min(self.max)
+    }
+
+    pub fn update_text_input(&mut self) {
+        self.text_input.text = self.format.format(self.value, self.precision);
+        self.text_input.select_all();
+    }
+
+    pub fn update_text_input_and_redraw(&mut self, cx: &mut Cx) {
+        self.update_text_input();
+        self.text_input.redraw(cx);
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: f64) {
+        self.value = self.clamp(value);
+        self.update_text_input();
+    }
+
+    pub fn number_format(&self) -> NumberFormat {
+        self.format
+    }
+
+    fn change(&mut self, cx: &mut Cx, scope: &Scope, value: f64) {
+        let value = self.clamp(value);
+        let changed = value != self.value;
+        self.value = value;
+        self.update_text_input_and_redraw(cx);
+        if changed {
+            cx.widget_action(self.widget_uid(), &scope.path, NumberInputAction::Change(value));
+        }
+    }
+
+    fn commit_text(&mut self, cx: &mut Cx, scope: &Scope) {
+        match self.format.parse(&self.text_input.text) {
+            Some(value) => self.change(cx, scope, value),
+            None => self.update_text_input_and_redraw(cx)
+        }
+    }
+
+    fn step_size(&self) -> f64 {
+        if self.step > 0.0 {self.step} else {1.0}
+    }
+
+    pub fn draw_walk_number_input(&mut self, cx: &mut Cx2d, walk: Walk) {
+        self.draw_bg.begin(cx, walk, self.layout);
+        cx.begin_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::SpinButton)
+            .with_value(AccessValue::Number {value: self.value, min: self.min, max: self.max, step: self.step_size()})
+            .with_action(AccessActionKind::SetValue)
+            .with_action(AccessActionKind::Increment)
+            .with_action(AccessActionKind::Decrement)
+            .with_area(self.draw_bg.area()));
+
+        // a filling text input leaves room for the spinner after it
+        let mut walk = self.text_input.walk(cx);
+        if walk.width.is_fill() {
+            let spinner_width = self.spinner_walk.width.fixed_or_zero() + self.spinner_walk.margin.width();
+            walk.width = Size::Fixed((cx.turtle().width_left() - spinner_width - walk.margin.width()).max(0.0));
+        }
+        let _ = self.text_input.draw_walk(cx, &mut Scope::empty(), walk);
+        self.draw_spinner.draw_walk(cx, self.spinner_walk);
+
+        cx.end_access_node();
+        self.draw_bg.end(cx);
+    }
+}
+
+impl Widget for NumberInput {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.animator_handle_event(cx, event);
+
+        // the arrow keys step the value instead of moving the text cursor up and down
+        if let Event::KeyDown(ke) = event {
+            if cx.has_key_focus(self.text_input.area()) {
+                let steps = match ke.key_code {
+                    KeyCode::ArrowUp => 1.0,
+                    KeyCode::ArrowDown => -1.0,
+                    KeyCode::PageUp => 10.0,
+                    KeyCode::PageDown => -10.0,
+                    _ => 0.0
+                };
+                if steps != 0.0 {
+                    let steps = if ke.modifiers.shift {steps * 10.0} else {steps};
+                    let start = self.format.parse(&self.text_input.text).unwrap_or(self.value);
+                    self.change(cx, scope, start + steps * self.step_size());
+                    return
+                }
+            }
+        }
+
+        for action in cx.capture_actions( | cx | self.text_input.handle_event(cx, event, scope)) {
+            match action.as_widget_action().cast() {
+                TextInputAction::KeyFocusLost => {
+                    self.commit_text(cx, scope);
+                }
+                TextInputAction::Return(_) => {
+                    self.commit_text(cx, scope);
+                }
+                TextInputAction::Escape => {
+                    self.update_text_input_and_redraw(cx);
+                }
+                _ => ()
+            }
+        }
+
+        match event.hits(cx, self.draw_spinner.area()) {
+            Hit::FingerHoverIn(_) => {
+                cx.set_cursor(MouseCursor::NsResize);
+                self.animator_play(cx, id!(hover.on));
+            }
+            Hit::FingerHoverOut(_) => {
+                self.animator_play(cx, id!(hover.off));
+            }
+            Hit::FingerDown(_) => {
+                self.text_input.set_key_focus(cx);
+                self.scrub = Some((self.value, false));
+                self.animator_play(cx, id!(drag.on));
+            }
+            Hit::FingerMove(fe) => if let Some((start, _)) = self.scrub {
+                // dragging right or up scrubs the value up, one step per scrub_distance
+                let delta = fe.abs - fe.abs_start;
+                let steps = ((delta.x - delta.y) / self.scrub_distance).trunc();
+                if steps != 0.0 {
+                    self.scrub = Some((start, true));
+                }
+                self.change(cx, scope, start + steps * self.step_size());
+            }
+            Hit::FingerUp(fe) => {
+                self.animator_play(cx, id!(drag.off));
+                if let Some((_, false)) = self.scrub.take() {
+                    if fe.is_over {
+                        // a click without scrubbing hits the up or the down arrow
+                        let up = fe.abs.y < fe.rect.pos.y + fe.rect.size.y * 0.5;
+                        self.change(cx, scope, self.value + if up {self.step_size()} else {-self.step_size()});
+                    }
+                }
+            }
+            _ => ()
+        }
+
+        if let Hit::AccessAction(e) = event.hits(cx, self.draw_bg.area()) {
+            let value = match e.action {
+                AccessAction::Increment => Some(self.value + self.step_size()),
+                AccessAction::Decrement => Some(self.value - self.step_size()),
+                AccessAction::SetNumber(v) => Some(v),
+                _ => None
+            };
+            if let Some(value) = value {
+                self.change(cx, scope, value);
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.draw_walk_number_input(cx, walk);
+        DrawStep::done()
+    }
+
+    fn widget_to_data(&self, _cx: &mut Cx, actions: &Actions, nodes: &mut LiveNodeVec, path: &[LiveId]) -> bool {
+        match actions.find_widget_action_cast(self.widget_uid()) {
+            NumberInputAction::Change(v) => {
+                nodes.write_field_value(path, LiveValue::Float64(v));
+                true
+            }
+            _ => false
+        }
+    }
+
+    fn data_to_widget(&mut self, cx: &mut Cx, nodes: &[LiveNode], path: &[LiveId]) {
+        if let Some(value) = nodes.read_field_value(path) {
+            if let Some(value) = value.as_float() {
+                self.set_value(value);
+                self.text_input.redraw(cx);
+            }
+        }
+    }
+
+    fn set_data_error(&mut self, cx: &mut Cx, error: Option<&str>) {
+        self.text_input.set_data_error(cx, error);
+    }
+
+    fn text(&self) -> String {
+        self.format.format(self.value, self.precision)
+    }
+
+    fn set_text(&mut self, v: &str) {
+        if let Some(value) = self.format.parse(v) {
+            self.set_value(value);
+        }
+    }
+}
+
+impl NumberInputRef {
+    pub fn value(&self) -> Option<f64> {
+        self.borrow().map( | inner | inner.value())
+    }
+
+    pub fn set_value(&self, cx: &mut Cx, value: f64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_value(value);
+            inner.text_input.redraw(cx);
+        }
+    }
+
+    pub fn changed(&self, actions: &Actions) -> Option<f64> {
+        if let NumberInputAction::Change(v) = actions.find_widget_action_cast(self.widget_uid()) {
+            return Some(v)
+        }
+        None
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     widget::*,
     makepad_derive_widget::*,
     makepad_draw::*,
+    data_binding::DataBoundList,
     scroll_bar::{ScrollBar, ScrollAxis, ScrollBarAction}
 };
 
@@ -80,6 +81,9 @@ pub struct PortalList {
     
     #[rust] templates: ComponentMap<LiveId, LivePtr>,
     #[rust] items: ComponentMap<usize, (LiveId, WidgetRef)>,
+    #[rust] bound_items: Option<DataBoundList>,
+    // the bound items whose widgets haven't been given their data yet
+    #[rust] bound_pending: Vec<bool>,
     //#[rust(DragState::None)] drag_state: DragState,
     #[rust(ScrollState::Stopped)] scroll_state: ScrollState
 }
@@ -136,6 +140,12 @@ impl PortalList {
     fn begin(&mut self, cx: &mut Cx2d, walk: Walk) {
         cx.begin_turtle(walk, self.layout);
         self.draw_align_list.clear();
+        cx.begin_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::List));
+        // items that are nav stops (a View with `nav_stop: true`) are walked with the arrow keys
+        cx.begin_nav_group(match self.vec_index {
+            Vec2Index::X => NavAxis::Horizontal,
+            Vec2Index::Y => NavAxis::Vertical,
+        });
     }
     
     fn end(&mut self, cx: &mut Cx2d) {
@@ -300,7 +310,9 @@ impl PortalList {
             self.items.retain_visible();
         }
 
+        cx.end_nav_group();
         cx.end_turtle_with_area(&mut self.area);
+        cx.end_access_node_with_area(self.area);
         self.visible_items = visible_items;
     }
 
@@ -334,7 +346,8 @@ impl PortalList {
                                 abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y + self.first_scroll)),
                                 margin: Default::default(),
                                 width: Size::Fill,
-                                height: Size::Fit
+                                height: Size::Fit,
+                                ..Default::default()
                             }, layout);
                         }
                         Vec2Index::X => {
@@ -342,7 +355,8 @@ impl PortalList {
                                 abs_pos: Some(dvec2(viewport.pos.x + self.first_scroll, viewport.pos.y)),
                                 margin: Default::default(),
                                 width: Size::Fit,
-                                height: Size::Fill
+                                height: Size::Fill,
+                                ..Default::default()
                             }, layout);
                         }
                     }
@@ -375,7 +389,8 @@ impl PortalList {
                                         abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y)),
                                         margin: Default::default(),
                                         width: Size::Fill,
-                                        height: Size::Fit
+                                        height: Size::Fit,
+                                        ..Default::default()
                                     }, layout);
                                 }
                                 Vec2Index::X => {
@@ -383,7 +398,8 @@ impl PortalList {
                                         abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y)),
                                         margin: Default::default(),
                                         width: Size::Fit,
-                                        height: Size::Fill
+                                        height: Size::Fill,
+                                        ..Default::default()
                                     }, layout);
                                 }
                             }
@@ -414,7 +430,8 @@ impl PortalList {
                                 abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y + pos + rect.size.index(vi))),
                                 margin: Default::default(),
                                 width: Size::Fill,
-                                height: Size::Fit
+                                height: Size::Fit,
+                                ..Default::default()
                             }, layout);
                         }
                         Vec2Index::X => {
@@ -422,7 +439,8 @@ impl PortalList {
                                 abs_pos: Some(dvec2(viewport.pos.x + pos + rect.size.index(vi), viewport.pos.y)),
                                 margin: Default::default(),
                                 width: Size::Fit,
-                                height: Size::Fill
+                                height: Size::Fill,
+                                ..Default::default()
                             }, layout);
                         }
                     }
@@ -456,7 +474,8 @@ impl PortalList {
                                     abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y + total_height)),
                                     margin: Default::default(),
                                     width: Size::Fill,
-                                    height: Size::Fit
+                                    height: Size::Fit,
+                                    ..Default::default()
                                 }, Layout::flow_down());
                                 return Some(last_index + 1);
                             }
@@ -481,7 +500,8 @@ impl PortalList {
                         abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y)),
                         margin: Default::default(),
                         width: Size::Fill,
-                        height: Size::Fit
+                        height: Size::Fit,
+                        ..Default::default()
                     }, Layout::flow_down());
                     
                     return Some(index - 1);
@@ -594,6 +614,37 @@ impl PortalList {
         }
     }
     
+    /// Makes the list draw an item for each of `list.items` by itself, filled in with their data.
+    /// Set by `DataBindingMap::bind_list`.
+    pub fn set_bound_items(&mut self, cx: &mut Cx, list: DataBoundList) {
+        if self.bound_items.as_ref() == Some(&list) {
+            return
+        }
+        self.set_item_range(cx, 0, list.items.len());
+        self.bound_pending = vec![true; list.items.len()];
+        self.bound_items = Some(list);
+        self.area.redraw(cx);
+    }
+
+    fn draw_bound_items(&mut self, cx: &mut Cx2d, scope: &mut Scope) {
+        while let Some(index) = self.next_visible_item(cx) {
+            let Some(list) = &self.bound_items else {continue};
+            // past the end nothing is drawn, which ends the list
+            if index >= list.items.len() {
+                continue
+            }
+            let template = list.template;
+            let (item, existed) = self.item_with_existed(cx, index, template);
+            if !existed || self.bound_pending[index] {
+                self.bound_pending[index] = false;
+                if let Some(list) = &self.bound_items {
+                    list.items[index].data_to_widgets(cx, &item);
+                }
+            }
+            item.draw_all(cx, scope);
+        }
+    }
+    
     pub fn update_scroll_bar(&mut self, cx: &mut Cx) {
         let scroll_pos = ((self.first_id - self.range_start) as f64 / ((self.range_end - self.range_start).max(self.view_window + 1) - self.view_window) as f64) * self.scroll_bar.get_scroll_view_total();
         // move the scrollbar to the right 'top' position
@@ -898,10 +949,14 @@ impl Widget for PortalList {
         }
     }
     
-    fn draw_walk(&mut self, cx: &mut Cx2d, _scope:&mut Scope, walk: Walk) -> DrawStep {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope:&mut Scope, walk: Walk) -> DrawStep {
         if self.draw_state.begin(cx, ListDrawState::Begin) {
             self.begin(cx, walk);
-            return DrawStep::make_step()
+            // a list bound to data draws its own items, otherwise the caller draws them
+            if self.bound_items.is_none() {
+                return DrawStep::make_step()
+            }
+            self.draw_bound_items(cx, scope);
         }
         // ok so if we are
         if let Some(_) = self.draw_state.get() {
@@ -69,6 +69,7 @@ pub struct RadioButton {
     #[live] text: ArcStringMut,
     
     #[live] bind: String,
+    #[live] nav_order: NavOrder,
 }
 
 #[derive(Clone, Debug, DefaultNone)]
@@ -97,6 +98,14 @@ impl RadioButton {
         }
         self.draw_text.draw_walk(cx, self.label_walk, self.label_align, self.text.as_ref());
         self.draw_radio.end(cx);
+        cx.add_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::RadioButton)
+            .with_name(self.text.as_ref())
+            .with_states(AccessStates {checked: Some(self.animator_in_state(cx, id!(selected.on))), ..Default::default()})
+            .with_action(AccessActionKind::Press)
+            .with_area(self.draw_radio.area()));
+        cx.add_nav_stop_with(NavStop::new(self.draw_radio.area(), NavRole::RadioButton)
+            .with_order(self.nav_order)
+            .with_selected(self.animator_in_state(cx, id!(selected.on))));
     }
         
 }
@@ -131,9 +140,12 @@ impl Widget for RadioButton {
                 cx.set_cursor(MouseCursor::Arrow);
                 self.animator_play(cx, id!(hover.off));
             },
-            Hit::FingerDown(_fe) => {
+            Hit::FingerDown(_)
+            | Hit::AccessAction(AccessActionEvent {action: AccessAction::Press, ..})
+            | Hit::KeyDown(KeyEvent {key_code: KeyCode::Space | KeyCode::ReturnKey, is_repeat: false, ..}) => {
                 if self.animator_in_state(cx, id!(selected.off)) {
                     self.animator_play(cx, id!(selected.on));
+                    self.draw_radio.redraw(cx);
                     cx.widget_action(uid, &scope.path, RadioButtonAction::Clicked);
                 }
             },
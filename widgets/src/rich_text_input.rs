@@ -0,0 +1,1469 @@
+use {
+    std::ops::Range,
+    unicode_segmentation::UnicodeSegmentation,
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        makepad_html::*,
+        makepad_markdown::*,
+        widget::*,
+        text_flow::{TextFlow, TextFlowCopyFormat, FlowPos},
+    },
+};
+
+live_design!{
+    RichTextInputBase = {{RichTextInput}} {}
+}
+
+const HEADING_SCALES: [f64; 6] = [2.0, 1.5, 1.17, 1.0, 0.83, 0.67];
+const FIXED_FONT_SIZE_SCALE: f64 = 0.85;
+const BULLET: &str = "•";
+
+/// The inline styles of a span of text
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RichAttrs {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub code: bool,
+    /// the url when the text is a link
+    pub link: Option<String>,
+}
+
+/// A piece of text that has the same styles all the way
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RichSpan {
+    pub text: String,
+    pub attrs: RichAttrs,
+}
+
+impl RichSpan {
+    pub fn new(text: &str, attrs: RichAttrs) -> Self {
+        Self {text: text.to_string(), attrs}
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RichBlockKind {
+    #[default] Paragraph,
+    /// a heading of level 1 to 6
+    Heading(u8),
+    Bullet,
+    Numbered,
+    Quote,
+    /// a line of a code block, code lines that follow each other make one block
+    Code,
+}
+
+/// A paragraph of the document, its text is the text of its spans
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RichBlock {
+    pub kind: RichBlockKind,
+    pub spans: Vec<RichSpan>,
+}
+
+impl RichBlock {
+    pub fn new(kind: RichBlockKind) -> Self {
+        Self {kind, spans: Vec::new()}
+    }
+
+    pub fn with_spans(kind: RichBlockKind, spans: Vec<RichSpan>) -> Self {
+        let mut block = Self {kind, spans};
+        block.normalize();
+        block
+    }
+
+    pub fn text(&self) -> String {
+        self.spans.iter().map( | span | span.text.as_str()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.iter().map( | span | span.text.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.iter().all( | span | span.text.is_empty())
+    }
+
+    /// The styles of the text in front of `offset`, or of the text after it at the start of the block
+    pub fn attrs_at(&self, offset: usize) -> RichAttrs {
+        let mut start = 0;
+        for span in &self.spans {
+            let end = start + span.text.len();
+            if offset > start && offset <= end {
+                return span.attrs.clone()
+            }
+            start = end;
+        }
+        self.spans.first().map(| span | span.attrs.clone()).unwrap_or_default()
+    }
+
+    // cuts the span `offset` falls in, and returns the index of the span that starts there
+    fn split_at(&mut self, offset: usize) -> usize {
+        let mut start = 0;
+        for i in 0..self.spans.len() {
+            if offset <= start {
+                return i
+            }
+            let len = self.spans[i].text.len();
+            if offset < start + len {
+                let text = self.spans[i].text.split_off(offset - start);
+                let attrs = self.spans[i].attrs.clone();
+                self.spans.insert(i + 1, RichSpan {text, attrs});
+                return i + 1
+            }
+            start += len;
+        }
+        self.spans.len()
+    }
+
+    fn insert(&mut self, offset: usize, text: &str, attrs: RichAttrs) {
+        let i = self.split_at(offset);
+        self.spans.insert(i, RichSpan::new(text, attrs));
+        self.normalize();
+    }
+
+    fn remove(&mut self, range: Range<usize>) {
+        let i = self.split_at(range.start);
+        let j = self.split_at(range.end);
+        self.spans.drain(i..j);
+        self.normalize();
+    }
+
+    fn split_off(&mut self, offset: usize) -> Vec<RichSpan> {
+        let i = self.split_at(offset);
+        let tail = self.spans.split_off(i);
+        self.normalize();
+        tail
+    }
+
+    // drops the empty spans and merges the ones that look the same
+    fn normalize(&mut self) {
+        let mut spans: Vec<RichSpan> = Vec::with_capacity(self.spans.len());
+        for span in self.spans.drain(..) {
+            if span.text.is_empty() {
+                continue
+            }
+            match spans.last_mut() {
+                Some(last) if last.attrs == span.attrs => last.text.push_str(&span.text),
+                _ => spans.push(span)
+            }
+        }
+        self.spans = spans;
+    }
+}
+
+/// A position in a rich document, a byte offset in the text of one of its blocks
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RichPos {
+    pub block: usize,
+    pub offset: usize,
+}
+
+impl RichPos {
+    pub fn new(block: usize, offset: usize) -> Self {
+        Self {block, offset}
+    }
+}
+
+/// The document of a rich text input: blocks of spans of styled text, never without a block
+#[derive(Clone, Debug, PartialEq)]
+pub struct RichDocument {
+    pub blocks: Vec<RichBlock>,
+}
+
+impl Default for RichDocument {
+    fn default() -> Self {
+        Self {blocks: vec![RichBlock::default()]}
+    }
+}
+
+impl RichDocument {
+    pub fn from_text(text: &str) -> Self {
+        let mut doc = Self::default();
+        doc.insert(RichPos::default(), text, &RichAttrs::default());
+        doc
+    }
+
+    /// The text of the blocks, one line each
+    pub fn text(&self) -> String {
+        self.blocks.iter().map( | block | block.text()).collect::<Vec<_>>().join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.iter().all( | block | block.is_empty())
+    }
+
+    pub fn end(&self) -> RichPos {
+        let block = self.blocks.len() - 1;
+        RichPos {block, offset: self.blocks[block].len()}
+    }
+
+    /// The closest position that is in the document and on a char boundary
+    pub fn clamp(&self, pos: RichPos) -> RichPos {
+        if pos.block >= self.blocks.len() {
+            return self.end()
+        }
+        let text = self.blocks[pos.block].text();
+        let mut offset = pos.offset.min(text.len());
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        RichPos {block: pos.block, offset}
+    }
+
+    /// The styles text typed at `pos` gets
+    pub fn attrs_at(&self, pos: RichPos) -> RichAttrs {
+        let pos = self.clamp(pos);
+        self.blocks[pos.block].attrs_at(pos.offset)
+    }
+
+    /// Inserts text with one style, every newline in it starts a block like the one it is in.
+    /// Returns the position at the end of the text
+    pub fn insert(&mut self, pos: RichPos, text: &str, attrs: &RichAttrs) -> RichPos {
+        let mut pos = self.clamp(pos);
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                let kind = self.blocks[pos.block].kind;
+                pos = self.split_block(pos, kind);
+            }
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            self.blocks[pos.block].insert(pos.offset, line, attrs.clone());
+            pos.offset += line.len();
+        }
+        pos
+    }
+
+    /// Inserts the blocks of another document, the first one joins the block at `pos` and
+    /// the rest of that block goes behind the last one. Returns the position at the end of them
+    pub fn insert_document(&mut self, pos: RichPos, other: &RichDocument) -> RichPos {
+        let pos = self.clamp(pos);
+        let mut blocks = other.blocks.clone();
+        let first = blocks.remove(0);
+        let was_empty = self.blocks[pos.block].is_empty();
+        let mut offset = pos.offset;
+        for span in first.spans {
+            self.blocks[pos.block].insert(offset, &span.text, span.attrs);
+            offset += span.text.len();
+        }
+        // an empty block becomes a list item or a heading when one is pasted into it
+        if was_empty && first.kind != RichBlockKind::Paragraph {
+            self.blocks[pos.block].kind = first.kind;
+        }
+        if blocks.is_empty() {
+            return RichPos {block: pos.block, offset}
+        }
+        let tail = self.blocks[pos.block].split_off(offset);
+        let last = pos.block + blocks.len();
+        self.blocks.splice(pos.block + 1..pos.block + 1, blocks);
+        let end = RichPos {block: last, offset: self.blocks[last].len()};
+        self.blocks[last].spans.extend(tail);
+        self.blocks[last].normalize();
+        end
+    }
+
+    /// Moves the text after `pos` into a new block of `kind`, returns where that block starts
+    pub fn split_block(&mut self, pos: RichPos, kind: RichBlockKind) -> RichPos {
+        let pos = self.clamp(pos);
+        let tail = self.blocks[pos.block].split_off(pos.offset);
+        self.blocks.insert(pos.block + 1, RichBlock {kind, spans: tail});
+        RichPos {block: pos.block + 1, offset: 0}
+    }
+
+    /// Removes the text between two positions, the blocks they are in become one
+    pub fn delete(&mut self, range: Range<RichPos>) {
+        let (start, end) = (self.clamp(range.start), self.clamp(range.end));
+        if start >= end {
+            return
+        }
+        if start.block == end.block {
+            self.blocks[start.block].remove(start.offset..end.offset);
+            return
+        }
+        let tail = self.blocks[end.block].split_off(end.offset);
+        let block = &mut self.blocks[start.block];
+        block.split_off(start.offset);
+        block.spans.extend(tail);
+        block.normalize();
+        self.blocks.drain(start.block + 1..=end.block);
+    }
+
+    /// A copy of the text between two positions, with its styles and blocks
+    pub fn slice(&self, range: Range<RichPos>) -> RichDocument {
+        let (start, end) = (self.clamp(range.start), self.clamp(range.end));
+        let mut blocks = Vec::new();
+        for index in start.block..=end.block.max(start.block) {
+            let mut block = self.blocks[index].clone();
+            if index == end.block {
+                block.split_off(end.offset.max(if index == start.block {start.offset} else {0}));
+            }
+            if index == start.block {
+                block.spans = block.split_off(start.offset);
+            }
+            blocks.push(block);
+        }
+        RichDocument {blocks}
+    }
+
+    // calls `f` with the spans between two positions, cut to fit
+    fn spans_in_mut(&mut self, range: Range<RichPos>, mut f: impl FnMut(&mut RichSpan)) {
+        let (start, end) = (self.clamp(range.start), self.clamp(range.end));
+        for index in start.block..=end.block {
+            let block = &mut self.blocks[index];
+            let from = if index == start.block {start.offset} else {0};
+            let to = if index == end.block {end.offset} else {block.len()};
+            let i = block.split_at(from);
+            let j = block.split_at(to);
+            block.spans[i..j].iter_mut().for_each(&mut f);
+            block.normalize();
+        }
+    }
+
+    /// Changes the styles of the text between two positions
+    pub fn set_attrs(&mut self, range: Range<RichPos>, mut f: impl FnMut(&mut RichAttrs)) {
+        self.spans_in_mut(range, | span | f(&mut span.attrs));
+    }
+
+    /// Whether all of the text between two positions has a style, false when there is no text
+    pub fn all_attrs(&self, range: Range<RichPos>, f: impl Fn(&RichAttrs) -> bool) -> bool {
+        let slice = self.slice(range);
+        let mut spans = slice.blocks.iter().flat_map( | block | block.spans.iter()).peekable();
+        spans.peek().is_some() && spans.all( | span | f(&span.attrs))
+    }
+
+    pub fn set_block_kind(&mut self, blocks: Range<usize>, kind: RichBlockKind) {
+        for block in &mut self.blocks[blocks] {
+            block.kind = kind;
+        }
+    }
+
+    pub fn from_markdown(markdown: &str) -> Self {
+        let doc = parse_markdown(markdown);
+        let mut builder = RichBuilder::default();
+        let mut in_code = false;
+        for node in &doc.nodes {
+            match node {
+                MarkdownNode::BeginHead {level} => {
+                    builder.open_block(RichBlockKind::Heading((*level).clamp(1, 6) as u8));
+                }
+                MarkdownNode::BeginNormal => builder.open_block(RichBlockKind::Paragraph),
+                MarkdownNode::BeginListItem {label} => builder.open_block(match label {
+                    MarkdownListLabel::Number {..} => RichBlockKind::Numbered,
+                    _ => RichBlockKind::Bullet
+                }),
+                MarkdownNode::BeginQuote => builder.open_block(RichBlockKind::Quote),
+                MarkdownNode::BeginCode {..} => {
+                    in_code = true;
+                    builder.open_block(RichBlockKind::Code);
+                }
+                MarkdownNode::EndCode => {
+                    in_code = false;
+                    builder.close_block();
+                }
+                MarkdownNode::EndHead | MarkdownNode::EndNormal | MarkdownNode::EndListItem |
+                MarkdownNode::EndQuote | MarkdownNode::Separator | MarkdownNode::NewLine {paragraph: true} => {
+                    builder.close_block();
+                }
+                MarkdownNode::NewLine {paragraph: false} => {
+                    if in_code {
+                        builder.push_block(RichBlockKind::Code);
+                    }
+                    else {
+                        builder.text(" ");
+                    }
+                }
+                MarkdownNode::BeginBold => builder.bold += 1,
+                MarkdownNode::EndBold => builder.bold = builder.bold.saturating_sub(1),
+                MarkdownNode::BeginItalic => builder.italic += 1,
+                MarkdownNode::EndItalic => builder.italic = builder.italic.saturating_sub(1),
+                // the parser calls ~~text~~ underline, it is strikethrough everywhere else
+                MarkdownNode::BeginUnderline => builder.strikethrough += 1,
+                MarkdownNode::EndUnderline => builder.strikethrough = builder.strikethrough.saturating_sub(1),
+                MarkdownNode::BeginInlineCode => builder.code += 1,
+                MarkdownNode::EndInlineCode => builder.code = builder.code.saturating_sub(1),
+                MarkdownNode::Link {start, url_start, end} => {
+                    builder.links.push(doc.decoded[*url_start..*end].to_string());
+                    builder.text(&doc.decoded[*start..*url_start]);
+                    builder.links.pop();
+                }
+                MarkdownNode::Image {start, url_start, ..} => builder.text(&doc.decoded[*start..*url_start]),
+                MarkdownNode::Text {start, end} => builder.text(&doc.decoded[*start..*end]),
+            }
+        }
+        builder.finish()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let mut number = 0;
+        for (index, block) in self.blocks.iter().enumerate() {
+            let prev = index.checked_sub(1).map( | i | self.blocks[i].kind);
+            // the items of a list and code lines follow each other directly, other blocks have an empty line between them
+            match (prev, block.kind) {
+                (None, _) => (),
+                (Some(RichBlockKind::Code), RichBlockKind::Code) => out.push('\n'),
+                (Some(RichBlockKind::Code), _) => out.push_str("\n```\n\n"),
+                (Some(RichBlockKind::Bullet), RichBlockKind::Bullet) | (Some(RichBlockKind::Numbered), RichBlockKind::Numbered) => out.push('\n'),
+                _ => out.push_str("\n\n"),
+            }
+            number = if prev == Some(RichBlockKind::Numbered) {number + 1} else {1};
+            match block.kind {
+                RichBlockKind::Paragraph => (),
+                RichBlockKind::Heading(level) => {
+                    out.push_str(&"#".repeat(level.clamp(1, 6) as usize));
+                    out.push(' ');
+                }
+                RichBlockKind::Bullet => out.push_str("- "),
+                RichBlockKind::Numbered => out.push_str(&format!("{}. ", number)),
+                RichBlockKind::Quote => out.push_str("> "),
+                RichBlockKind::Code => {
+                    if prev != Some(RichBlockKind::Code) {
+                        out.push_str("```\n");
+                    }
+                    out.push_str(&block.text());
+                    continue
+                }
+            }
+            for span in &block.spans {
+                out.push_str(&markdown_span(span));
+            }
+        }
+        if self.blocks.last().is_some_and( | block | block.kind == RichBlockKind::Code) {
+            out.push_str("\n```");
+        }
+        out
+    }
+
+    pub fn from_html(html: &str) -> Self {
+        let mut errors = None;
+        let doc = parse_html(html, &mut errors, InternLiveId::No);
+        let mut builder = RichBuilder::default();
+        let mut lists = Vec::new();
+        let mut node = doc.new_walker();
+        while !node.done() {
+            match node.open_tag_lc() {
+                some_id!(p) | some_id!(div) => builder.open_block(builder.paragraph_kind()),
+                some_id!(h1) => builder.open_block(RichBlockKind::Heading(1)),
+                some_id!(h2) => builder.open_block(RichBlockKind::Heading(2)),
+                some_id!(h3) => builder.open_block(RichBlockKind::Heading(3)),
+                some_id!(h4) => builder.open_block(RichBlockKind::Heading(4)),
+                some_id!(h5) => builder.open_block(RichBlockKind::Heading(5)),
+                some_id!(h6) => builder.open_block(RichBlockKind::Heading(6)),
+                some_id!(ul) => lists.push(RichBlockKind::Bullet),
+                some_id!(ol) => lists.push(RichBlockKind::Numbered),
+                some_id!(li) => builder.open_block(*lists.last().unwrap_or(&RichBlockKind::Bullet)),
+                some_id!(blockquote) => {
+                    builder.quote += 1;
+                    builder.open_block(RichBlockKind::Quote);
+                }
+                some_id!(pre) => {
+                    builder.pre += 1;
+                    builder.open_block(RichBlockKind::Code);
+                }
+                some_id!(br) => {
+                    let kind = builder.blocks.last().map_or(RichBlockKind::Paragraph, | block | block.kind);
+                    builder.push_block(kind);
+                }
+                some_id!(b) | some_id!(strong) => builder.bold += 1,
+                some_id!(i) | some_id!(em) => builder.italic += 1,
+                some_id!(u) => builder.underline += 1,
+                some_id!(s) | some_id!(del) | some_id!(strike) => builder.strikethrough += 1,
+                some_id!(code) => builder.code += 1,
+                some_id!(a) => builder.links.push(node.find_attr_lc(live_id!(href)).unwrap_or("").to_string()),
+                _ => ()
+            }
+            match node.close_tag_lc() {
+                some_id!(p) | some_id!(div) | some_id!(li) |
+                some_id!(h1) | some_id!(h2) | some_id!(h3) | some_id!(h4) | some_id!(h5) | some_id!(h6) => {
+                    builder.close_block();
+                }
+                some_id!(ul) | some_id!(ol) => {
+                    lists.pop();
+                }
+                some_id!(blockquote) => {
+                    builder.quote = builder.quote.saturating_sub(1);
+                    builder.close_block();
+                }
+                some_id!(pre) => {
+                    builder.pre = builder.pre.saturating_sub(1);
+                    builder.close_block();
+                }
+                some_id!(b) | some_id!(strong) => builder.bold = builder.bold.saturating_sub(1),
+                some_id!(i) | some_id!(em) => builder.italic = builder.italic.saturating_sub(1),
+                some_id!(u) => builder.underline = builder.underline.saturating_sub(1),
+                some_id!(s) | some_id!(del) | some_id!(strike) => builder.strikethrough = builder.strikethrough.saturating_sub(1),
+                some_id!(code) => builder.code = builder.code.saturating_sub(1),
+                some_id!(a) => {
+                    builder.links.pop();
+                }
+                _ => ()
+            }
+            if let Some(text) = node.text() {
+                if builder.pre > 0 {
+                    // the newline right after <pre> and the one before </pre> are not part of the code
+                    let text = match builder.blocks.last() {
+                        Some(block) if block.is_empty() => text.strip_prefix('\n').unwrap_or(text),
+                        _ => text
+                    };
+                    for (i, line) in text.strip_suffix('\n').unwrap_or(text).split('\n').enumerate() {
+                        if i > 0 {
+                            builder.push_block(RichBlockKind::Code);
+                        }
+                        builder.text(line);
+                    }
+                }
+                else {
+                    builder.text(&collapse_whitespace(text));
+                }
+            }
+            node.walk();
+        }
+        builder.finish()
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        let mut index = 0;
+        while index < self.blocks.len() {
+            let kind = self.blocks[index].kind;
+            // lists, quotes and code blocks take in the blocks of their kind that follow
+            let end = match kind {
+                RichBlockKind::Paragraph | RichBlockKind::Heading(_) => index + 1,
+                _ => self.blocks[index..].iter().position( | block | block.kind != kind).map_or(self.blocks.len(), | n | index + n)
+            };
+            let group = &self.blocks[index..end];
+            let inline = | block: &RichBlock | block.spans.iter().map(html_span).collect::<String>();
+            let each = | open: &str, close: &str | group.iter().map( | block | format!("{}{}{}", open, inline(block), close)).collect::<String>();
+            match kind {
+                RichBlockKind::Paragraph => out.push_str(&each("<p>", "</p>")),
+                RichBlockKind::Heading(level) => {
+                    let level = level.clamp(1, 6);
+                    out.push_str(&each(&format!("<h{}>", level), &format!("</h{}>", level)));
+                }
+                RichBlockKind::Bullet => out.push_str(&format!("<ul>{}</ul>", each("<li>", "</li>"))),
+                RichBlockKind::Numbered => out.push_str(&format!("<ol>{}</ol>", each("<li>", "</li>"))),
+                RichBlockKind::Quote => out.push_str(&format!("<blockquote>{}</blockquote>", each("<p>", "</p>"))),
+                RichBlockKind::Code => {
+                    let lines: Vec<String> = group.iter().map( | block | escape_html(&block.text())).collect();
+                    out.push_str(&format!("<pre><code>{}</code></pre>", lines.join("\n")));
+                }
+            }
+            index = end;
+        }
+        out
+    }
+}
+
+// collects the blocks of a parsed markdown or html document
+#[derive(Default)]
+struct RichBuilder {
+    blocks: Vec<RichBlock>,
+    open: bool,
+    bold: usize,
+    italic: usize,
+    underline: usize,
+    strikethrough: usize,
+    code: usize,
+    links: Vec<String>,
+    quote: usize,
+    pre: usize,
+}
+
+impl RichBuilder {
+    // a block that has no text yet takes the new kind, so nested block tags make one block
+    fn open_block(&mut self, kind: RichBlockKind) {
+        match self.blocks.last_mut() {
+            Some(block) if self.open && block.is_empty() => block.kind = kind,
+            Some(block) if !self.open && block.is_empty() && block.kind != RichBlockKind::Code => block.kind = kind,
+            _ => self.blocks.push(RichBlock::new(kind))
+        }
+        self.open = true;
+    }
+
+    fn push_block(&mut self, kind: RichBlockKind) {
+        self.blocks.push(RichBlock::new(kind));
+        self.open = true;
+    }
+
+    fn close_block(&mut self) {
+        self.open = false;
+    }
+
+    // paragraphs in a quote are quote blocks
+    fn paragraph_kind(&self) -> RichBlockKind {
+        if self.quote > 0 {RichBlockKind::Quote} else {RichBlockKind::Paragraph}
+    }
+
+    fn attrs(&self) -> RichAttrs {
+        RichAttrs {
+            bold: self.bold > 0,
+            italic: self.italic > 0,
+            underline: self.underline > 0,
+            strikethrough: self.strikethrough > 0,
+            // the code tag of a pre block is not inline code
+            code: self.code > 0 && self.pre == 0,
+            link: self.links.last().cloned(),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if !self.open {
+            // whitespace between blocks is not text
+            if text.trim().is_empty() {
+                return
+            }
+            self.open_block(self.paragraph_kind());
+        }
+        let attrs = self.attrs();
+        let block = self.blocks.last_mut().unwrap();
+        let text = if block.is_empty() && block.kind != RichBlockKind::Code {text.trim_start()} else {text};
+        if !text.is_empty() {
+            block.spans.push(RichSpan::new(text, attrs));
+        }
+    }
+
+    fn finish(mut self) -> RichDocument {
+        for block in &mut self.blocks {
+            if block.kind != RichBlockKind::Code {
+                if let Some(span) = block.spans.last_mut() {
+                    span.text.truncate(span.text.trim_end().len());
+                }
+            }
+            block.normalize();
+        }
+        if self.blocks.is_empty() {
+            return RichDocument::default()
+        }
+        RichDocument {blocks: self.blocks}
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, word) in text.split(char::is_whitespace).enumerate() {
+        if i > 0 && !out.ends_with(' ') {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+fn markdown_span(span: &RichSpan) -> String {
+    let attrs = &span.attrs;
+    // emphasis markers have to touch the text they wrap
+    let core = span.text.trim();
+    if core.is_empty() {
+        return span.text.clone()
+    }
+    let lead = &span.text[..span.text.len() - span.text.trim_start().len()];
+    let trail = &span.text[span.text.trim_end().len()..];
+    let mut core = if attrs.code {format!("`{}`", core)} else {core.to_string()};
+    if let Some(link) = &attrs.link {
+        core = format!("[{}]({})", core, link);
+    }
+    if attrs.strikethrough {
+        core = format!("~~{}~~", core);
+    }
+    if attrs.italic {
+        core = format!("*{}*", core);
+    }
+    if attrs.bold {
+        core = format!("**{}**", core);
+    }
+    format!("{}{}{}", lead, core, trail)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn html_span(span: &RichSpan) -> String {
+    let attrs = &span.attrs;
+    let mut out = escape_html(&span.text);
+    for (on, tag) in [
+        (attrs.code, "code"),
+        (attrs.strikethrough, "s"),
+        (attrs.underline, "u"),
+        (attrs.italic, "i"),
+        (attrs.bold, "b"),
+    ] {
+        if on {
+            out = format!("<{}>{}</{}>", tag, out, tag);
+        }
+    }
+    if let Some(link) = &attrs.link {
+        out = format!("<a href=\"{}\">{}</a>", escape_html(link).replace('"', "&quot;"), out);
+    }
+    out
+}
+
+/// A style the formatting commands of a rich text input switch on and off
+#[derive(Clone, Debug, PartialEq)]
+pub enum RichFormat {
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Code,
+    /// makes the text a link, or no link with None
+    Link(Option<String>),
+    Block(RichBlockKind),
+}
+
+impl RichFormat {
+    fn flag<'a>(&self, attrs: &'a mut RichAttrs) -> Option<&'a mut bool> {
+        match self {
+            Self::Bold => Some(&mut attrs.bold),
+            Self::Italic => Some(&mut attrs.italic),
+            Self::Underline => Some(&mut attrs.underline),
+            Self::Strikethrough => Some(&mut attrs.strikethrough),
+            Self::Code => Some(&mut attrs.code),
+            Self::Link(_) | Self::Block(_) => None,
+        }
+    }
+
+    fn apply(&self, attrs: &mut RichAttrs, on: bool) {
+        match self {
+            Self::Link(link) => attrs.link = if on {link.clone()} else {None},
+            _ => if let Some(flag) = self.flag(attrs) {
+                *flag = on;
+            }
+        }
+    }
+
+    fn is_set(&self, attrs: &RichAttrs) -> bool {
+        match self {
+            Self::Link(link) => attrs.link == *link,
+            _ => self.flag(&mut attrs.clone()).is_some_and( | flag | *flag)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct RichCursor {
+    head: RichPos,
+    tail: RichPos,
+}
+
+impl RichCursor {
+    fn at(pos: RichPos) -> Self {
+        Self {head: pos, tail: pos}
+    }
+
+    fn is_empty(&self) -> bool {
+        self.head == self.tail
+    }
+
+    fn range(&self) -> Range<RichPos> {
+        self.head.min(self.tail)..self.head.max(self.tail)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum EditKind {
+    Insert,
+    Backspace,
+    Delete,
+    Other,
+}
+
+impl EditKind {
+    fn can_merge_with(self, other: EditKind) -> bool {
+        self != Self::Other && self == other
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RichSnapshot {
+    doc: RichDocument,
+    cursor: RichCursor,
+}
+
+// undo goes back to the document as it was before a group of edits of one kind
+#[derive(Default)]
+struct RichHistory {
+    current_edit_kind: Option<EditKind>,
+    undo_stack: Vec<RichSnapshot>,
+    redo_stack: Vec<RichSnapshot>,
+}
+
+impl RichHistory {
+    fn force_new_edit_group(&mut self) {
+        self.current_edit_kind = None;
+    }
+
+    fn create_or_extend_edit_group(&mut self, edit_kind: EditKind, doc: &RichDocument, cursor: RichCursor) {
+        if !self.current_edit_kind.is_some_and( | current | current.can_merge_with(edit_kind)) {
+            self.undo_stack.push(RichSnapshot {doc: doc.clone(), cursor});
+            self.current_edit_kind = Some(edit_kind);
+        }
+        self.redo_stack.clear();
+    }
+
+    fn undo(&mut self, doc: &mut RichDocument, cursor: &mut RichCursor) -> bool {
+        Self::swap(&mut self.undo_stack, &mut self.redo_stack, doc, cursor) && {
+            self.current_edit_kind = None;
+            true
+        }
+    }
+
+    fn redo(&mut self, doc: &mut RichDocument, cursor: &mut RichCursor) -> bool {
+        Self::swap(&mut self.redo_stack, &mut self.undo_stack, doc, cursor) && {
+            self.current_edit_kind = None;
+            true
+        }
+    }
+
+    fn swap(from: &mut Vec<RichSnapshot>, to: &mut Vec<RichSnapshot>, doc: &mut RichDocument, cursor: &mut RichCursor) -> bool {
+        let Some(snapshot) = from.pop() else {
+            return false
+        };
+        to.push(RichSnapshot {doc: std::mem::replace(doc, snapshot.doc), cursor: *cursor});
+        *cursor = snapshot.cursor;
+        true
+    }
+
+    fn clear(&mut self) {
+        self.current_edit_kind = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+// the text flow draws every span as a run, and an empty block as a placeholder run
+#[derive(Clone, Copy, Debug)]
+struct RichRun {
+    block: usize,
+    start: usize,
+    len: usize,
+}
+
+#[derive(Live, Widget)]
+pub struct RichTextInput {
+    #[deref] text_flow: TextFlow,
+    #[live] draw_bg: DrawColor,
+    #[live] draw_cursor: DrawColor,
+    #[live(2.0)] cursor_width: f64,
+    #[live] link_color: Vec4,
+    #[live] paragraph_spacing: f64,
+    /// the format copy and cut put on the clipboard, and paste reads html in as well
+    #[live] clipboard_format: TextFlowCopyFormat,
+    #[live] is_read_only: bool,
+    /// markdown the document starts with
+    #[live] body: ArcStringMut,
+
+    #[rust] doc: RichDocument,
+    #[rust] cursor: RichCursor,
+    #[rust] typing_attrs: RichAttrs,
+    #[rust] history: RichHistory,
+    /// what the last text input inserted, an input method replaces it while it composes
+    #[rust] last_insert: Option<Range<RichPos>>,
+    #[rust] runs: Vec<RichRun>,
+}
+
+impl LiveHook for RichTextInput {
+    fn after_apply_from(&mut self, _cx: &mut Cx, apply: &mut Apply) {
+        if apply.from.is_from_doc() {
+            let doc = RichDocument::from_markdown(self.body.as_ref());
+            self.set_document(doc);
+        }
+    }
+}
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum RichTextInputAction {
+    Changed,
+    None
+}
+
+impl Widget for RichTextInput {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        match event.hits(cx, self.draw_bg.area()) {
+            Hit::KeyFocus(_) => {
+                self.history.force_new_edit_group();
+                self.draw_bg.redraw(cx);
+            }
+            Hit::KeyFocusLost(_) => {
+                cx.hide_text_ime();
+                self.draw_bg.redraw(cx);
+            }
+            Hit::FingerHoverIn(_) => {
+                cx.set_cursor(MouseCursor::Text);
+            }
+            Hit::FingerDown(fe) => {
+                cx.set_key_focus(self.draw_bg.area());
+                if let Some(pos) = self.pos_at(cx, fe.abs) {
+                    match fe.tap_count {
+                        2 => self.select_word(pos),
+                        3.. => self.select_range(RichPos::new(pos.block, 0)..RichPos::new(pos.block, self.doc.blocks[pos.block].len())),
+                        _ => self.move_cursor(pos, fe.modifiers.shift),
+                    }
+                    self.draw_bg.redraw(cx);
+                }
+            }
+            Hit::FingerMove(fe) => {
+                if let Some(pos) = self.pos_at(cx, fe.abs) {
+                    self.move_cursor(pos, true);
+                    self.draw_bg.redraw(cx);
+                }
+            }
+            Hit::KeyDown(ke) => self.handle_key_down(cx, scope, &ke),
+            Hit::TextInput(te) if !self.is_read_only => {
+                if te.was_paste {
+                    self.paste(cx, scope, &te.input);
+                }
+                else {
+                    if te.replace_last {
+                        if let Some(last) = self.last_insert.clone().filter( | last | self.cursor == RichCursor::at(last.end)) {
+                            self.cursor = RichCursor {head: last.end, tail: last.start};
+                        }
+                    }
+                    self.insert_text(cx, scope, &te.input, EditKind::Insert);
+                }
+            }
+            Hit::TextCopy(ce) => {
+                *ce.response.borrow_mut() = self.selected_text();
+            }
+            Hit::TextCut(ce) => {
+                *ce.response.borrow_mut() = self.selected_text();
+                if !self.cursor.is_empty() && !self.is_read_only {
+                    self.delete_range(cx, scope, self.cursor.range(), EditKind::Other);
+                }
+            }
+            _ => ()
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.runs = self.layout_runs();
+        let selection = (!self.cursor.is_empty()).then( || (self.flow_pos(self.cursor.tail), self.flow_pos(self.cursor.head)));
+        self.text_flow.set_selection(selection);
+
+        self.draw_bg.begin(cx, walk, Layout::default());
+        self.text_flow.begin(cx, Walk::fill_fit());
+        self.draw_doc(cx);
+        self.text_flow.end(cx);
+
+        let focused = cx.has_key_focus(self.draw_bg.area());
+        let caret = self.text_flow.caret_rect(self.flow_pos(self.cursor.head));
+        if let (true, Some(caret)) = (focused, caret) {
+            self.draw_cursor.draw_abs(cx, Rect {
+                pos: caret.pos - dvec2(self.cursor_width * 0.5, 0.0),
+                size: dvec2(self.cursor_width, caret.size.y)
+            });
+        }
+        self.draw_bg.end(cx);
+        if let (true, Some(caret)) = (focused, caret) {
+            let origin = self.draw_bg.area().rect(cx).pos;
+            cx.show_text_ime(self.draw_bg.area(), caret.pos - origin);
+        }
+        DrawStep::done()
+    }
+
+    fn text(&self) -> String {
+        self.doc.text()
+    }
+
+    fn set_text(&mut self, v: &str) {
+        self.set_document(RichDocument::from_text(v));
+    }
+}
+
+impl RichTextInput {
+    pub fn document(&self) -> &RichDocument {
+        &self.doc
+    }
+
+    /// Replaces the document, which also forgets the undo history
+    pub fn set_document(&mut self, doc: RichDocument) {
+        self.doc = doc;
+        self.cursor = RichCursor::default();
+        self.typing_attrs = self.doc.attrs_at(RichPos::default());
+        self.last_insert = None;
+        self.history.clear();
+    }
+
+    pub fn set_markdown(&mut self, markdown: &str) {
+        self.set_document(RichDocument::from_markdown(markdown));
+    }
+
+    pub fn set_html(&mut self, html: &str) {
+        self.set_document(RichDocument::from_html(html));
+    }
+
+    /// The selection from its anchor to the cursor
+    pub fn selection(&self) -> (RichPos, RichPos) {
+        (self.cursor.tail, self.cursor.head)
+    }
+
+    pub fn set_selection(&mut self, anchor: RichPos, cursor: RichPos) {
+        self.cursor = RichCursor {head: self.doc.clamp(cursor), tail: self.doc.clamp(anchor)};
+        self.cursor_moved();
+    }
+
+    pub fn select_all(&mut self) {
+        self.select_range(RichPos::default()..self.doc.end());
+    }
+
+    /// Whether a format is on for the selection, or for what gets typed at the cursor
+    pub fn is_active(&self, format: &RichFormat) -> bool {
+        let range = self.cursor.range();
+        match format {
+            RichFormat::Block(kind) => self.doc.blocks[range.start.block..=range.end.block].iter().all( | block | block.kind == *kind),
+            _ if self.cursor.is_empty() => format.is_set(&self.typing_attrs),
+            _ => self.doc.all_attrs(range, | attrs | format.is_set(attrs))
+        }
+    }
+
+    /// Switches a format on for the selection, or off when all of it has it. Without a selection
+    /// inline styles apply to what gets typed next, and block kinds to the block of the cursor
+    pub fn apply_format(&mut self, cx: &mut Cx, scope: &mut Scope, format: RichFormat) {
+        if self.is_read_only {
+            return
+        }
+        let range = self.cursor.range();
+        let active = self.is_active(&format);
+        if let RichFormat::Block(kind) = format {
+            self.history.create_or_extend_edit_group(EditKind::Other, &self.doc, self.cursor);
+            let kind = if active {RichBlockKind::Paragraph} else {kind};
+            self.doc.set_block_kind(range.start.block..range.end.block + 1, kind);
+        }
+        else if self.cursor.is_empty() {
+            format.apply(&mut self.typing_attrs, !active);
+            self.history.force_new_edit_group();
+            self.draw_bg.redraw(cx);
+            return
+        }
+        else {
+            self.history.create_or_extend_edit_group(EditKind::Other, &self.doc, self.cursor);
+            self.doc.set_attrs(range, | attrs | format.apply(attrs, !active));
+        }
+        self.history.force_new_edit_group();
+        self.draw_bg.redraw(cx);
+        cx.widget_action(self.widget_uid(), &scope.path, RichTextInputAction::Changed);
+    }
+
+    /// The selection in the clipboard format, None when nothing is selected
+    pub fn selected_text(&self) -> Option<String> {
+        if self.cursor.is_empty() {
+            return None
+        }
+        let slice = self.doc.slice(self.cursor.range());
+        Some(match self.clipboard_format {
+            TextFlowCopyFormat::PlainText => slice.text(),
+            TextFlowCopyFormat::Markdown => slice.to_markdown(),
+            TextFlowCopyFormat::Html => slice.to_html(),
+        })
+    }
+
+    fn cursor_moved(&mut self) {
+        self.typing_attrs = self.doc.attrs_at(self.cursor.head.min(self.cursor.tail));
+        self.last_insert = None;
+        self.history.force_new_edit_group();
+    }
+
+    fn move_cursor(&mut self, pos: RichPos, select: bool) {
+        self.cursor.head = self.doc.clamp(pos);
+        if !select {
+            self.cursor.tail = self.cursor.head;
+        }
+        self.cursor_moved();
+    }
+
+    fn select_range(&mut self, range: Range<RichPos>) {
+        self.cursor = RichCursor {head: range.end, tail: range.start};
+        self.cursor_moved();
+    }
+
+    fn select_word(&mut self, pos: RichPos) {
+        let text = self.doc.blocks[pos.block].text();
+        for (start, word) in text.split_word_bound_indices() {
+            let end = start + word.len();
+            if pos.offset < end || end == text.len() {
+                self.select_range(RichPos::new(pos.block, start)..RichPos::new(pos.block, end));
+                return
+            }
+        }
+        self.move_cursor(pos, false);
+    }
+
+    fn prev_pos(&self, pos: RichPos) -> RichPos {
+        if pos.offset == 0 {
+            return match pos.block.checked_sub(1) {
+                Some(block) => RichPos::new(block, self.doc.blocks[block].len()),
+                None => pos
+            }
+        }
+        let text = self.doc.blocks[pos.block].text();
+        let offset = text[..pos.offset].grapheme_indices(true).next_back().map_or(0, | (i, _) | i);
+        RichPos::new(pos.block, offset)
+    }
+
+    fn next_pos(&self, pos: RichPos) -> RichPos {
+        let text = self.doc.blocks[pos.block].text();
+        match text[pos.offset..].graphemes(true).next() {
+            Some(grapheme) => RichPos::new(pos.block, pos.offset + grapheme.len()),
+            None if pos.block + 1 < self.doc.blocks.len() => RichPos::new(pos.block + 1, 0),
+            None => pos
+        }
+    }
+
+    // the position a line up or down from the cursor, the start or the end past the first or last line
+    fn vertical_pos(&self, cx: &Cx, down: bool) -> RichPos {
+        let head = self.cursor.head;
+        let Some(caret) = self.text_flow.caret_rect(self.flow_pos(head)) else {
+            return head
+        };
+        let y = if down {caret.pos.y + caret.size.y * 1.5} else {caret.pos.y - caret.size.y * 0.5};
+        match self.text_flow.pos_at_abs(cx, dvec2(caret.pos.x, y)).map( | pos | self.rich_pos(pos)) {
+            Some(pos) if pos != head && (pos > head) == down => pos,
+            _ if down => self.doc.end(),
+            _ => RichPos::default()
+        }
+    }
+
+    fn pos_at(&self, cx: &Cx, abs: DVec2) -> Option<RichPos> {
+        self.text_flow.pos_at_abs(cx, abs).map( | pos | self.rich_pos(pos))
+    }
+
+    fn layout_runs(&self) -> Vec<RichRun> {
+        let mut runs = Vec::new();
+        for (block, rich_block) in self.doc.blocks.iter().enumerate() {
+            if rich_block.is_empty() {
+                runs.push(RichRun {block, start: 0, len: 0});
+            }
+            let mut start = 0;
+            for span in &rich_block.spans {
+                runs.push(RichRun {block, start, len: span.text.len()});
+                start += span.text.len();
+            }
+        }
+        runs
+    }
+
+    // at the edge of two spans the position goes with the later one
+    fn flow_pos(&self, pos: RichPos) -> FlowPos {
+        let mut found = FlowPos::default();
+        for (run, rich_run) in self.runs.iter().enumerate() {
+            if rich_run.block == pos.block && pos.offset >= rich_run.start && pos.offset <= rich_run.start + rich_run.len {
+                found = FlowPos {run, index: pos.offset - rich_run.start};
+                if pos.offset < rich_run.start + rich_run.len {
+                    break
+                }
+            }
+        }
+        found
+    }
+
+    fn rich_pos(&self, pos: FlowPos) -> RichPos {
+        match self.runs.get(pos.run) {
+            Some(run) => self.doc.clamp(RichPos::new(run.block, run.start + pos.index.min(run.len))),
+            None => self.doc.end()
+        }
+    }
+
+    fn draw_doc(&mut self, cx: &mut Cx2d) {
+        let tf = &mut self.text_flow;
+        let blocks = &self.doc.blocks;
+        tf.combine_spaces.push(false);
+        let mut number = 0;
+        for (index, block) in blocks.iter().enumerate() {
+            let prev = index.checked_sub(1).map( | i | blocks[i].kind);
+            let next = blocks.get(index + 1).map( | block | block.kind);
+            let is_list = | kind: Option<RichBlockKind> | matches!(kind, Some(RichBlockKind::Bullet | RichBlockKind::Numbered));
+            if index > 0 && (prev != Some(block.kind) || !matches!(block.kind, RichBlockKind::Quote | RichBlockKind::Code)) {
+                if is_list(prev) && is_list(Some(block.kind)) {
+                    tf.new_line(cx);
+                }
+                else {
+                    tf.new_paragraph_with_spacing(cx, self.paragraph_spacing);
+                }
+            }
+            match block.kind {
+                RichBlockKind::Paragraph => (),
+                RichBlockKind::Heading(level) => {
+                    tf.push_size_abs_scale(HEADING_SCALES[level.clamp(1, 6) as usize - 1]);
+                    tf.bold.push();
+                }
+                RichBlockKind::Bullet => tf.begin_list_item(cx, BULLET, 1.5),
+                RichBlockKind::Numbered => {
+                    number = if prev == Some(RichBlockKind::Numbered) {number + 1} else {1};
+                    tf.begin_list_item(cx, &format!("{}.", number), 1.5);
+                }
+                RichBlockKind::Quote | RichBlockKind::Code if prev == Some(block.kind) => tf.new_line(cx),
+                RichBlockKind::Quote => tf.begin_quote(cx),
+                RichBlockKind::Code => {
+                    tf.push_size_rel_scale(FIXED_FONT_SIZE_SCALE);
+                    tf.fixed.push();
+                    tf.begin_code(cx);
+                }
+            }
+            if block.is_empty() {
+                tf.draw_text(cx, " ");
+            }
+            for span in &block.spans {
+                let attrs = &span.attrs;
+                let code = attrs.code && block.kind != RichBlockKind::Code;
+                let counters = [
+                    (attrs.bold, &mut tf.bold),
+                    (attrs.italic, &mut tf.italic),
+                    (attrs.underline || attrs.link.is_some(), &mut tf.underline),
+                    (attrs.strikethrough, &mut tf.strikethrough),
+                    (code, &mut tf.fixed),
+                    (code, &mut tf.inline_code),
+                ];
+                for (on, counter) in counters {
+                    if on {counter.push()}
+                }
+                if code {
+                    tf.push_size_rel_scale(FIXED_FONT_SIZE_SCALE);
+                }
+                if attrs.link.is_some() {
+                    tf.font_colors.push(self.link_color);
+                }
+                tf.draw_text(cx, &span.text);
+                if attrs.link.is_some() {
+                    tf.font_colors.pop();
+                }
+                if code {
+                    tf.font_sizes.pop();
+                }
+                let counters = [
+                    (attrs.bold, &mut tf.bold),
+                    (attrs.italic, &mut tf.italic),
+                    (attrs.underline || attrs.link.is_some(), &mut tf.underline),
+                    (attrs.strikethrough, &mut tf.strikethrough),
+                    (code, &mut tf.fixed),
+                    (code, &mut tf.inline_code),
+                ];
+                for (on, counter) in counters {
+                    if on {counter.pop()}
+                }
+            }
+            match block.kind {
+                RichBlockKind::Heading(_) => {
+                    tf.bold.pop();
+                    tf.font_sizes.pop();
+                }
+                RichBlockKind::Bullet | RichBlockKind::Numbered => tf.end_list_item(cx),
+                RichBlockKind::Quote if next != Some(block.kind) => tf.end_quote(cx),
+                RichBlockKind::Code if next != Some(block.kind) => {
+                    tf.end_code(cx);
+                    tf.fixed.pop();
+                    tf.font_sizes.pop();
+                }
+                _ => ()
+            }
+        }
+        tf.combine_spaces.pop();
+    }
+
+    fn changed(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        self.draw_bg.redraw(cx);
+        cx.widget_action(self.widget_uid(), &scope.path, RichTextInputAction::Changed);
+    }
+
+    fn insert_text(&mut self, cx: &mut Cx, scope: &mut Scope, text: &str, edit_kind: EditKind) {
+        if text.is_empty() && self.cursor.is_empty() {
+            return
+        }
+        self.history.create_or_extend_edit_group(edit_kind, &self.doc, self.cursor);
+        let range = self.cursor.range();
+        self.doc.delete(range.clone());
+        let end = self.doc.insert(range.start, text, &self.typing_attrs);
+        self.cursor = RichCursor::at(end);
+        self.last_insert = Some(range.start..end);
+        self.changed(cx, scope);
+    }
+
+    fn delete_range(&mut self, cx: &mut Cx, scope: &mut Scope, range: Range<RichPos>, edit_kind: EditKind) {
+        if range.is_empty() {
+            return
+        }
+        self.history.create_or_extend_edit_group(edit_kind, &self.doc, self.cursor);
+        self.doc.delete(range.clone());
+        self.cursor = RichCursor::at(range.start);
+        self.typing_attrs = self.doc.attrs_at(range.start);
+        self.last_insert = None;
+        self.changed(cx, scope);
+    }
+
+    fn paste(&mut self, cx: &mut Cx, scope: &mut Scope, input: &str) {
+        let pasted = match self.clipboard_format {
+            TextFlowCopyFormat::PlainText => None,
+            _ if input.trim_start().starts_with('<') => Some(RichDocument::from_html(input)),
+            TextFlowCopyFormat::Markdown => Some(RichDocument::from_markdown(input)),
+            TextFlowCopyFormat::Html => None,
+        };
+        let Some(pasted) = pasted else {
+            self.insert_text(cx, scope, input, EditKind::Other);
+            return
+        };
+        self.history.create_or_extend_edit_group(EditKind::Other, &self.doc, self.cursor);
+        let range = self.cursor.range();
+        self.doc.delete(range.clone());
+        let end = self.doc.insert_document(range.start, &pasted);
+        self.cursor = RichCursor::at(end);
+        self.cursor_moved();
+        self.changed(cx, scope);
+    }
+
+    fn set_block_kind(&mut self, cx: &mut Cx, scope: &mut Scope, block: usize, kind: RichBlockKind) {
+        self.history.create_or_extend_edit_group(EditKind::Other, &self.doc, self.cursor);
+        self.doc.set_block_kind(block..block + 1, kind);
+        self.changed(cx, scope);
+    }
+
+    fn new_block(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        let pos = self.cursor.range().start;
+        let block = &self.doc.blocks[pos.block];
+        let kind = block.kind;
+        // return in an empty list item or quote ends the list or the quote
+        if self.cursor.is_empty() && block.is_empty() && matches!(kind, RichBlockKind::Bullet | RichBlockKind::Numbered | RichBlockKind::Quote) {
+            self.set_block_kind(cx, scope, pos.block, RichBlockKind::Paragraph);
+            return
+        }
+        self.history.create_or_extend_edit_group(EditKind::Other, &self.doc, self.cursor);
+        self.doc.delete(self.cursor.range());
+        let next_kind = match kind {
+            RichBlockKind::Heading(_) => RichBlockKind::Paragraph,
+            kind => kind
+        };
+        let pos = if pos.offset == 0 && !self.doc.blocks[pos.block].is_empty() {
+            // in front of the text the new block goes above, and the block keeps its kind
+            self.doc.blocks.insert(pos.block, RichBlock::new(next_kind));
+            RichPos::new(pos.block + 1, 0)
+        }
+        else {
+            self.doc.split_block(pos, next_kind)
+        };
+        self.cursor = RichCursor::at(pos);
+        self.last_insert = None;
+        self.history.force_new_edit_group();
+        self.changed(cx, scope);
+    }
+
+    fn handle_key_down(&mut self, cx: &mut Cx, scope: &mut Scope, ke: &KeyEvent) {
+        let command = ke.modifiers.control || ke.modifiers.logo;
+        let shift = ke.modifiers.shift;
+        let editable = !self.is_read_only;
+        match ke.key_code {
+            KeyCode::ArrowLeft => {
+                let pos = if !shift && !self.cursor.is_empty() {self.cursor.range().start} else {self.prev_pos(self.cursor.head)};
+                self.move_cursor(pos, shift);
+            }
+            KeyCode::ArrowRight => {
+                let pos = if !shift && !self.cursor.is_empty() {self.cursor.range().end} else {self.next_pos(self.cursor.head)};
+                self.move_cursor(pos, shift);
+            }
+            KeyCode::ArrowUp => self.move_cursor(self.vertical_pos(cx, false), shift),
+            KeyCode::ArrowDown => self.move_cursor(self.vertical_pos(cx, true), shift),
+            KeyCode::Home => self.move_cursor(RichPos::new(self.cursor.head.block, 0), shift),
+            KeyCode::End => {
+                let block = self.cursor.head.block;
+                self.move_cursor(RichPos::new(block, self.doc.blocks[block].len()), shift);
+            }
+            KeyCode::KeyA if command => self.select_all(),
+            KeyCode::KeyB if command => return self.apply_format(cx, scope, RichFormat::Bold),
+            KeyCode::KeyI if command => return self.apply_format(cx, scope, RichFormat::Italic),
+            KeyCode::KeyU if command => return self.apply_format(cx, scope, RichFormat::Underline),
+            KeyCode::KeyZ | KeyCode::KeyY if command && editable => {
+                let redo = ke.key_code == KeyCode::KeyY || shift;
+                let done = if redo {
+                    self.history.redo(&mut self.doc, &mut self.cursor)
+                }
+                else {
+                    self.history.undo(&mut self.doc, &mut self.cursor)
+                };
+                if done {
+                    self.typing_attrs = self.doc.attrs_at(self.cursor.range().start);
+                    self.last_insert = None;
+                    self.changed(cx, scope);
+                }
+                return
+            }
+            KeyCode::Backspace if editable => {
+                let head = self.cursor.head;
+                let kind = self.doc.blocks[head.block].kind;
+                if !self.cursor.is_empty() {
+                    self.delete_range(cx, scope, self.cursor.range(), EditKind::Backspace);
+                }
+                else if head.offset == 0 && kind != RichBlockKind::Paragraph {
+                    // at the start of a block it first goes back to being a paragraph
+                    self.set_block_kind(cx, scope, head.block, RichBlockKind::Paragraph);
+                }
+                else {
+                    self.delete_range(cx, scope, self.prev_pos(head)..head, EditKind::Backspace);
+                }
+                return
+            }
+            KeyCode::Delete if editable => {
+                let range = if self.cursor.is_empty() {self.cursor.head..self.next_pos(self.cursor.head)} else {self.cursor.range()};
+                return self.delete_range(cx, scope, range, EditKind::Delete)
+            }
+            KeyCode::ReturnKey if editable => return self.new_block(cx, scope),
+            _ => return
+        }
+        self.draw_bg.redraw(cx);
+    }
+}
+
+impl RichTextInputRef {
+    pub fn document(&self) -> RichDocument {
+        self.borrow().map( | inner | inner.doc.clone()).unwrap_or_default()
+    }
+
+    pub fn set_document(&self, cx: &mut Cx, doc: RichDocument) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_document(doc);
+            inner.draw_bg.redraw(cx);
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        self.borrow().map( | inner | inner.doc.to_markdown()).unwrap_or_default()
+    }
+
+    pub fn to_html(&self) -> String {
+        self.borrow().map( | inner | inner.doc.to_html()).unwrap_or_default()
+    }
+
+    pub fn set_markdown(&self, cx: &mut Cx, markdown: &str) {
+        self.set_document(cx, RichDocument::from_markdown(markdown));
+    }
+
+    pub fn set_html(&self, cx: &mut Cx, html: &str) {
+        self.set_document(cx, RichDocument::from_html(html));
+    }
+
+    pub fn selection(&self) -> Option<(RichPos, RichPos)> {
+        self.borrow().map( | inner | inner.selection())
+    }
+
+    pub fn set_selection(&self, cx: &mut Cx, anchor: RichPos, cursor: RichPos) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_selection(anchor, cursor);
+            inner.draw_bg.redraw(cx);
+        }
+    }
+
+    /// Applies a format from outside of the widget, like a toolbar button, and gives the
+    /// key focus back to the text
+    pub fn apply_format(&self, cx: &mut Cx, format: RichFormat) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.apply_format(cx, &mut Scope::empty(), format);
+            cx.set_key_focus(inner.draw_bg.area());
+        }
+    }
+
+    pub fn is_active(&self, format: &RichFormat) -> bool {
+        self.borrow().is_some_and( | inner | inner.is_active(format))
+    }
+
+    pub fn changed(&self, actions: &Actions) -> bool {
+        actions.filter_widget_actions_cast::<RichTextInputAction>(self.widget_uid())
+            .any( | action | matches!(action, RichTextInputAction::Changed))
+    }
+}
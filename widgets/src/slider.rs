@@ -126,6 +126,14 @@ impl Slider {
     pub fn draw_walk_slider(&mut self, cx: &mut Cx2d, walk: Walk) {
         self.draw_slider.slide_pos = self.relative_value as f32;
         self.draw_slider.begin(cx, walk, self.layout);
+        // the value text input ends up as a child of the slider node
+        cx.begin_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::Slider)
+            .with_name(&self.text)
+            .with_value(AccessValue::Number {value: self.to_external(), min: self.min, max: self.max, step: self.access_step()})
+            .with_action(AccessActionKind::SetValue)
+            .with_action(AccessActionKind::Increment)
+            .with_action(AccessActionKind::Decrement)
+            .with_area(self.draw_slider.area()));
         
         if let Some(mut dw) = cx.defer_walk(self.label_walk) {
             //, (self.value*100.0) as usize);
@@ -139,8 +147,13 @@ impl Slider {
             cx.end_turtle_with_area(&mut self.label_area);
         }
         
+        cx.end_access_node();
         self.draw_slider.end(cx);
     }
+    
+    fn access_step(&self) -> f64 {
+        if self.step != 0.0 {self.step} else {(self.max - self.min) / 100.0}
+    }
 
     pub fn value(&self) -> f64 {
         self.to_external()
@@ -240,6 +253,20 @@ impl Widget for Slider {
                 self.dragging = None;
                 cx.widget_action(uid, &scope.path, SliderAction::EndSlide);
             }
+            Hit::AccessAction(e) => {
+                let value = match e.action {
+                    AccessAction::Increment => Some(self.to_external() + self.access_step()),
+                    AccessAction::Decrement => Some(self.to_external() - self.access_step()),
+                    AccessAction::SetNumber(v) => Some(v),
+                    _ => None
+                };
+                if let Some(v) = value {
+                    self.set_internal(v.max(self.min).min(self.max));
+                    self.draw_slider.redraw(cx);
+                    self.update_text_input_and_redraw(cx);
+                    cx.widget_action(uid, &scope.path, SliderAction::Slide(self.to_external()));
+                }
+            }
             Hit::FingerMove(fe) => {
                 let rel = fe.abs - fe.abs_start;
                 if let Some(start_pos) = self.dragging {
@@ -173,7 +173,8 @@ impl Widget for SlidesView {
                 abs_pos: None,
                 margin: Default::default(),
                 width: Size::Fill,
-                height: Size::Fill
+                height: Size::Fill,
+                ..Default::default()
             }, Layout::flow_down().with_scroll(
                 dvec2(rect.size.x * self.current_slide.fract(), 0.0)
             ));
@@ -195,7 +196,8 @@ impl Widget for SlidesView {
                 abs_pos: None,
                 margin: Default::default(),
                 width: Size::Fill,
-                height: Size::Fill
+                height: Size::Fill,
+                ..Default::default()
             }, Layout::flow_down().with_scroll(
                 dvec2(-rect.size.x * (1.0-self.current_slide.fract()), 0.0)
             ));
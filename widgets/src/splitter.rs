@@ -18,7 +18,7 @@ pub struct DrawSplitter {
     #[live] is_vertical: f32,
 }
 
-#[derive(Copy, Clone, Debug, Live, LiveHook, SerRon, DeRon)]
+#[derive(Copy, Clone, Debug, Live, LiveHook, SerRon, DeRon, SerJson, DeJson)]
 #[live_ignore]
 pub enum SplitterAxis {
     #[pick] Horizontal,
@@ -32,7 +32,7 @@ impl Default for SplitterAxis {
 }
 
 
-#[derive(Clone, Copy, Debug, Live, LiveHook, SerRon, DeRon)]
+#[derive(Clone, Copy, Debug, Live, LiveHook, SerRon, DeRon, SerJson, DeJson)]
 #[live_ignore]
 pub enum SplitterAlign {
     #[live(50.0)] FromA(f64),
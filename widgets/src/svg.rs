@@ -0,0 +1,30 @@
+use crate::{
+    makepad_derive_widget::*,
+    makepad_draw::*,
+    widget::*
+};
+
+live_design!{
+    SvgBase = {{Svg}} {}
+}
+
+/// Shows an svg file in its own colors, sized by its `viewBox` when the walk is `Fit`.
+#[derive(Live, Widget)]
+pub struct Svg {
+    #[walk] walk: Walk,
+    #[redraw] #[live] draw_svg: DrawIcon,
+    #[live] source: LiveDependency,
+}
+
+impl LiveHook for Svg {
+    fn after_apply(&mut self, _cx: &mut Cx, _apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        self.draw_svg.svg_file = self.source.clone();
+    }
+}
+
+impl Widget for Svg {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.draw_svg.draw_walk(cx, walk);
+        DrawStep::done()
+    }
+}
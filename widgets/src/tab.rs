@@ -30,6 +30,7 @@ pub struct Tab {
     #[live] selected: f32,
     
     #[live(10.0)] min_drag_dist: f64,
+    #[live] nav_order: NavOrder,
     
     #[walk] walk: Walk,
     #[layout] layout: Layout,
@@ -69,6 +70,9 @@ impl Tab {
         self.draw_name.draw_walk(cx, Walk::fit(), Align::default(), name);
         //cx.turtle_align_y();
         self.draw_bg.end(cx);
+        cx.add_nav_stop_with(NavStop::new(self.draw_bg.area(), NavRole::Tab)
+            .with_order(self.nav_order)
+            .with_selected(self.is_selected));
         
         //if self.is_dragged {
         //    self.draw_drag.draw_abs(cx, self.draw_bg.area().get_clipped_rect(cx));
@@ -117,6 +121,10 @@ impl Tab {
             Hit::FingerDown(_) => {
                 dispatch_action(cx, TabAction::WasPressed);
             }
+            Hit::KeyDown(ke) if !ke.is_repeat => match ke.key_code {
+                KeyCode::Space | KeyCode::ReturnKey => dispatch_action(cx, TabAction::WasPressed),
+                _ => ()
+            }
             _ => {}
         }
     }
@@ -154,6 +154,7 @@ impl TabBar {
         //    self.selected_tab_id = None
         // }
         self.scroll_bars.begin(cx, walk, Layout::flow_right());
+        cx.begin_nav_group(NavAxis::Horizontal);
         self.tab_order.clear();
     }
     
@@ -170,6 +171,7 @@ impl TabBar {
         }
         self.tabs.retain_visible();
         self.draw_fill.draw_walk(cx, Walk::size(Size::Fill, Size::Fill));
+        cx.end_nav_group();
         self.scroll_bars.end(cx);
     }
     
@@ -1,8 +1,12 @@
-use crate::{
-    makepad_derive_widget::*,
-    makepad_draw::*,
-    widget::*,
-}; 
+use {
+    std::ops::Range,
+    unicode_segmentation::UnicodeSegmentation,
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        widget::*,
+    },
+};
     
 live_design!{
     DrawFlowBlock = {{DrawFlowBlock}} {}
@@ -48,6 +52,67 @@ pub struct DrawFlowBlock {
     #[live] block_type: FlowBlockType
 }
 
+/// What Ctrl/Cmd+C puts on the clipboard
+#[derive(Copy, Clone, Debug, Live, LiveHook, PartialEq)]
+#[live_ignore]
+pub enum TextFlowCopyFormat {
+    #[pick] PlainText,
+    Markdown,
+    Html,
+}
+
+/// What separates a run of text from the one drawn before it
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub enum FlowBreak {
+    #[default] None,
+    Line,
+    Paragraph,
+}
+
+/// The styles a run of text was drawn with, kept for copying as Markdown or HTML
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlowRunStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub inline_code: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub code_block: bool,
+    pub quote_depth: usize,
+}
+
+/// A piece of text as it was drawn, the text flow keeps them to select and copy from
+#[derive(Clone, Debug, Default)]
+pub struct FlowRun {
+    pub text: String,
+    pub style: FlowRunStyle,
+    pub break_before: FlowBreak,
+    /// the marker when the run starts a list item
+    pub list_marker: Option<String>,
+    /// where the pieces of the text ended up, relative to the text flow
+    chunks: Vec<FlowChunk>,
+}
+
+#[derive(Clone, Debug)]
+struct FlowChunk {
+    rect: Rect,
+    carets: Vec<(usize, f64)>,
+}
+
+/// A position in the text of a text flow, a byte index in one of its runs
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct FlowPos {
+    pub run: usize,
+    pub index: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SelectUnit {
+    Char,
+    Word,
+    Paragraph,
+}
+
 #[derive(Default)]
 pub struct StackCounter(usize);
 impl StackCounter{
@@ -77,6 +142,11 @@ pub struct TextFlow {
     #[live] draw_fixed: DrawText,
     
     #[live] draw_block: DrawFlowBlock,
+    #[live] draw_selection: DrawColor,
+    
+    /// The text can be selected with the mouse and copied
+    #[live(true)] selectable: bool,
+    #[live] copy_format: TextFlowCopyFormat,
     
     /// The default font size used for all text if not otherwise specified.
     #[live] font_size: f64,
@@ -97,6 +167,17 @@ pub struct TextFlow {
     #[rust] pub underline: StackCounter,
     #[rust] pub strikethrough: StackCounter,
     #[rust] pub inline_code: StackCounter,
+    #[rust] pub code_block: StackCounter,
+    #[rust] pub quote: StackCounter,
+    
+    #[rust] runs: Vec<FlowRun>,
+    #[rust] pending_break: FlowBreak,
+    #[rust] pending_list_marker: Option<String>,
+    #[rust] origin: DVec2,
+    /// the anchor and the cursor, in the order they were made
+    #[rust] selection: Option<(FlowPos, FlowPos)>,
+    /// what a drag extends by, and the range the first click selected
+    #[rust] select_unit: Option<(SelectUnit, FlowPos, FlowPos)>,
         
     #[rust] pub item_counter: u64,
     
@@ -215,6 +296,9 @@ impl Widget for TextFlow {
                 entry.handle_event(cx, event, scope);
             });
         }
+        if self.selectable {
+            self.handle_selection_event(cx, event);
+        }
     }
 }
 
@@ -226,6 +310,14 @@ impl TextFlow{
         cx.begin_turtle(walk, self.layout);
         self.draw_state.set(DrawState::Drawing);
         self.draw_block.append_to_draw_call(cx);
+        if self.selection.is_some() {
+            // the highlight goes between the blocks and the text
+            self.draw_selection.new_draw_call(cx);
+            for dt in [&self.draw_normal, &self.draw_italic, &self.draw_bold, &self.draw_bold_italic, &self.draw_fixed] {
+                dt.new_draw_call(cx);
+            }
+        }
+        self.origin = cx.turtle().rect().pos;
         self.clear_stacks();
     }
     
@@ -238,6 +330,11 @@ impl TextFlow{
         self.underline.clear();
         self.strikethrough.clear();
         self.inline_code.clear();
+        self.code_block.clear();
+        self.quote.clear();
+        self.runs.clear();
+        self.pending_break = FlowBreak::None;
+        self.pending_list_marker = None;
         //self.font.clear();
         self.font_sizes.clear();
         self.font_colors.clear();
@@ -271,13 +368,16 @@ impl TextFlow{
         self.draw_block.block_type = FlowBlockType::Code;
         self.draw_block.begin(cx, self.code_walk, self.code_layout);
         self.area_stack.push(self.draw_block.draw_vars.area);
-        
+        self.code_block.push();
+        self.mark_break(FlowBreak::Paragraph);
     }
     
     pub fn end_code(&mut self, cx:&mut Cx2d){
         // check if we need to use a widget
         self.draw_block.draw_vars.area = self.area_stack.pop().unwrap();
         self.draw_block.end(cx);
+        self.code_block.pop();
+        self.mark_break(FlowBreak::Paragraph);
     }
     
     pub fn begin_list_item(&mut self, cx:&mut Cx2d, dot:&str, pad:f64){
@@ -312,15 +412,40 @@ impl TextFlow{
         self.draw_normal.draw_abs(cx, pos, dot);
         
         self.area_stack.push(self.draw_block.draw_vars.area);
+        self.mark_break(FlowBreak::Paragraph);
+        self.pending_list_marker = Some(dot.to_string());
     }
     
     pub fn end_list_item(&mut self, cx:&mut Cx2d){
         cx.end_turtle();
+        self.mark_break(FlowBreak::Paragraph);
     }
     
     pub fn sep(&mut self, cx:&mut Cx2d){
         self.draw_block.block_type = FlowBlockType::Sep;
         self.draw_block.draw_walk(cx, self.sep_walk);
+        self.mark_break(FlowBreak::Paragraph);
+    }
+    
+    /// A hard line break, the text after it is in the same paragraph
+    pub fn new_line(&mut self, cx:&mut Cx2d){
+        cx.turtle_new_line();
+        self.mark_break(FlowBreak::Line);
+    }
+    
+    /// A line break that starts a new paragraph
+    pub fn new_paragraph(&mut self, cx:&mut Cx2d){
+        cx.turtle_new_line();
+        self.mark_break(FlowBreak::Paragraph);
+    }
+    
+    pub fn new_paragraph_with_spacing(&mut self, cx:&mut Cx2d, spacing: f64){
+        cx.turtle_new_line_with_spacing(spacing);
+        self.mark_break(FlowBreak::Paragraph);
+    }
+    
+    fn mark_break(&mut self, kind: FlowBreak){
+        self.pending_break = self.pending_break.max(kind);
     }
     
     pub fn begin_quote(&mut self, cx:&mut Cx2d){
@@ -328,11 +453,15 @@ impl TextFlow{
         self.draw_block.block_type = FlowBlockType::Quote;
         self.draw_block.begin(cx, self.quote_walk, self.quote_layout);
         self.area_stack.push(self.draw_block.draw_vars.area);
+        self.quote.push();
+        self.mark_break(FlowBreak::Paragraph);
     }
         
     pub fn end_quote(&mut self, cx:&mut Cx2d){
         self.draw_block.draw_vars.area = self.area_stack.pop().unwrap();
         self.draw_block.end(cx);
+        self.quote.pop();
+        self.mark_break(FlowBreak::Paragraph);
     }
     /*
     pub fn counted_item(&mut self, cx: &mut Cx, template: LiveId) -> Option<WidgetRef> {
@@ -473,17 +602,25 @@ impl TextFlow{
             // the turtle is at pos X so we walk it.
            
             let areas_tracker = &mut self.areas_tracker;
+            let origin = self.origin;
+            let mut chunks = Vec::new();
+            let mut record = | rect: Rect, carets: &[(usize, f64)] | {
+                chunks.push(FlowChunk {
+                    rect: Rect {pos: rect.pos - origin, size: rect.size},
+                    carets: carets.to_vec()
+                });
+            };
             if self.inline_code.value() > 0{
                 let db = &mut self.draw_block;
                 db.block_type = FlowBlockType::InlineCode;
                 let rect = TextFlow::walk_margin(cx, self.inline_code_margin.left);
                 areas_tracker.track_rect(cx, rect);
-                dt.draw_walk_resumable_with(cx, text, |cx, mut rect|{
+                dt.draw_walk_resumable_with_chunks(cx, text, |cx, mut rect|{
                     rect.pos -= self.inline_code_padding.left_top();
                     rect.size += self.inline_code_padding.size();
                     db.draw_abs(cx, rect);
                     areas_tracker.track_rect(cx, rect);
-                });
+                }, &mut record);
                 let rect = TextFlow::walk_margin(cx, self.inline_code_margin.right);
                 areas_tracker.track_rect(cx, rect);
             }
@@ -491,26 +628,65 @@ impl TextFlow{
                 let db = &mut self.draw_block;
                 db.line_color = *font_color;
                 db.block_type = FlowBlockType::Strikethrough;
-                dt.draw_walk_resumable_with(cx, text, |cx, rect|{
+                dt.draw_walk_resumable_with_chunks(cx, text, |cx, rect|{
                     db.draw_abs(cx, rect);
                     areas_tracker.track_rect(cx, rect);
-                });
+                }, &mut record);
             }
             else if self.underline.value() > 0{
                 let db = &mut self.draw_block;
                 db.line_color = *font_color;
                 db.block_type = FlowBlockType::Underline;
-                dt.draw_walk_resumable_with(cx, text, |cx, rect|{
+                dt.draw_walk_resumable_with_chunks(cx, text, |cx, rect|{
                     db.draw_abs(cx, rect);
                     areas_tracker.track_rect(cx, rect);
-                });
+                }, &mut record);
             }
             else{
-                dt.draw_walk_resumable_with(cx, text, |cx, rect|{
+                dt.draw_walk_resumable_with_chunks(cx, text, |cx, rect|{
                     areas_tracker.track_rect(cx, rect);
+                }, &mut record);
+            }
+            self.push_run(cx, text, chunks);
+        }
+    }
+    
+    fn push_run(&mut self, cx: &mut Cx2d, text: &str, chunks: Vec<FlowChunk>) {
+        let run = self.runs.len();
+        if let Some(range) = self.selected_range_in(run, text.len()) {
+            for chunk in &chunks {
+                let (Some(first), Some(last)) = (chunk.carets.first(), chunk.carets.last()) else {
+                    continue
+                };
+                let start = range.start.max(first.0);
+                let end = range.end.min(last.0);
+                if start >= end {
+                    continue
+                }
+                let x = | index: usize | chunk.carets.iter().find( | (i, _) | *i >= index).map_or(last.1, | (_, x) | *x);
+                // in right-to-left chunks the end of the selection is left of its start
+                let (x1, x2) = (x(start).min(x(end)), x(start).max(x(end)));
+                self.draw_selection.draw_abs(cx, Rect {
+                    pos: self.origin + chunk.rect.pos + dvec2(x1, 0.0),
+                    size: dvec2(x2 - x1, chunk.rect.size.y)
                 });
             }
         }
+        self.runs.push(FlowRun {
+            text: text.to_string(),
+            style: FlowRunStyle {
+                bold: self.bold.value() > 0,
+                italic: self.italic.value() > 0,
+                inline_code: self.inline_code.value() > 0,
+                underline: self.underline.value() > 0,
+                strikethrough: self.strikethrough.value() > 0,
+                code_block: self.code_block.value() > 0,
+                quote_depth: self.quote.value(),
+            },
+            break_before: std::mem::take(&mut self.pending_break),
+            list_marker: self.pending_list_marker.take(),
+            chunks,
+        });
     }
     
     pub fn walk_margin(cx:&mut Cx2d, margin:f64)->Rect{
@@ -531,6 +707,389 @@ impl TextFlow{
     }
 }
 
+impl TextFlow {
+    fn handle_selection_event(&mut self, cx: &mut Cx, event: &Event) {
+        match event.hits(cx, self.area) {
+            Hit::FingerHoverIn(_) => {
+                cx.set_cursor(MouseCursor::Text);
+            }
+            Hit::FingerDown(fe) => {
+                cx.set_key_focus(self.area);
+                let Some(pos) = self.pos_at(fe.abs - self.area.rect(cx).pos) else {
+                    return
+                };
+                let unit = match fe.tap_count {
+                    2 => SelectUnit::Word,
+                    3.. => SelectUnit::Paragraph,
+                    _ => SelectUnit::Char,
+                };
+                self.select_unit = match self.selection {
+                    Some((anchor, _)) if fe.modifiers.shift && unit == SelectUnit::Char => {
+                        Some((unit, anchor, anchor))
+                    }
+                    _ => {
+                        let (start, end) = self.unit_range(unit, pos);
+                        Some((unit, start, end))
+                    }
+                };
+                self.select_to(pos);
+                self.area.redraw(cx);
+            }
+            Hit::FingerMove(fe) => {
+                if let Some(pos) = self.pos_at(fe.abs - self.area.rect(cx).pos) {
+                    self.select_to(pos);
+                    self.area.redraw(cx);
+                }
+            }
+            Hit::KeyDown(ke) if ke.key_code == KeyCode::KeyA && (ke.modifiers.control || ke.modifiers.logo) => {
+                self.select_all();
+                self.area.redraw(cx);
+            }
+            Hit::KeyFocusLost(_) => {
+                self.clear_selection();
+                self.area.redraw(cx);
+            }
+            Hit::TextCopy(ce) => {
+                *ce.response.borrow_mut() = self.selected_text_as(self.copy_format);
+            }
+            _ => ()
+        }
+    }
+    
+    /// The position in the text closest to a point relative to the text flow
+    fn pos_at(&self, point: DVec2) -> Option<FlowPos> {
+        let distance = | from: f64, size: f64, p: f64 | if p < from {from - p} else {(p - from - size).max(0.0)};
+        let mut closest: Option<(f64, f64, FlowPos)> = None;
+        for (run, flow_run) in self.runs.iter().enumerate() {
+            for chunk in &flow_run.chunks {
+                let dy = distance(chunk.rect.pos.y, chunk.rect.size.y, point.y);
+                let dx = distance(chunk.rect.pos.x, chunk.rect.size.x, point.x);
+                if closest.is_none_or( | (cy, cx, _) | (dy, dx) < (cy, cx)) {
+                    let x = point.x - chunk.rect.pos.x;
+                    let index = chunk.carets.iter()
+                        .min_by( | a, b | (a.1 - x).abs().total_cmp(&(b.1 - x).abs()))
+                        .map_or(0, | (index, _) | *index);
+                    closest = Some((dy, dx, FlowPos {run, index}));
+                }
+            }
+        }
+        closest.map( | (_, _, pos) | pos)
+    }
+    
+    fn unit_range(&self, unit: SelectUnit, pos: FlowPos) -> (FlowPos, FlowPos) {
+        match unit {
+            SelectUnit::Char => (pos, pos),
+            SelectUnit::Word => {
+                let text = &self.runs[pos.run].text;
+                for (start, word) in text.split_word_bound_indices() {
+                    let end = start + word.len();
+                    if pos.index < end || end == text.len() {
+                        return (FlowPos {run: pos.run, index: start}, FlowPos {run: pos.run, index: end})
+                    }
+                }
+                (pos, pos)
+            }
+            SelectUnit::Paragraph => {
+                let starts_paragraph = | run: usize | self.runs[run].break_before == FlowBreak::Paragraph;
+                let first = (1..=pos.run).rev().find( | run | starts_paragraph(*run)).unwrap_or(0);
+                let last = (pos.run + 1..self.runs.len()).find( | run | starts_paragraph(*run)).unwrap_or(self.runs.len()) - 1;
+                (FlowPos {run: first, index: 0}, FlowPos {run: last, index: self.runs[last].text.len()})
+            }
+        }
+    }
+    
+    // extends the selection of the last click by its unit, either way from what it first selected
+    fn select_to(&mut self, pos: FlowPos) {
+        let Some((unit, start, end)) = self.select_unit else {
+            return
+        };
+        let (from, to) = self.unit_range(unit, pos);
+        self.selection = Some(if from < start {(end, from)} else {(start, to.max(end))});
+    }
+    
+    fn ordered_selection(&self) -> Option<(FlowPos, FlowPos)> {
+        let (anchor, cursor) = self.selection?;
+        Some((anchor.min(cursor), anchor.max(cursor)))
+    }
+    
+    /// The selected byte range of a run
+    fn selected_range_in(&self, run: usize, len: usize) -> Option<Range<usize>> {
+        let (start, end) = self.ordered_selection()?;
+        if run < start.run || run > end.run {
+            return None
+        }
+        let from = if run == start.run {start.index} else {0};
+        let to = if run == end.run {end.index.min(len)} else {len};
+        (from < to).then_some(from..to)
+    }
+    
+    /// The anchor and the cursor of the selection
+    pub fn selection(&self) -> Option<(FlowPos, FlowPos)> {
+        self.selection
+    }
+    
+    /// Selects from `anchor` to `cursor`, the highlight shows from the next draw on
+    pub fn set_selection(&mut self, selection: Option<(FlowPos, FlowPos)>) {
+        self.selection = selection;
+        self.select_unit = None;
+    }
+    
+    /// The number of runs the last draw made, every `draw_text` makes one
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+    
+    /// The position in the text closest to a point on the screen
+    pub fn pos_at_abs(&self, cx: &Cx, abs: DVec2) -> Option<FlowPos> {
+        self.pos_at(abs - self.area.rect(cx).pos)
+    }
+    
+    /// Where a caret in front of `pos` goes on the screen, a zero wide rect as high as its line
+    pub fn caret_rect(&self, pos: FlowPos) -> Option<Rect> {
+        let run = self.runs.get(pos.run)?;
+        let chunk = run.chunks.iter()
+            .find( | chunk | {
+                // chunks are in the order they are shown in, which isn't the text order in bidi text
+                chunk.carets.first().is_some_and( | (index, _) | *index <= pos.index)
+                    && chunk.carets.last().is_some_and( | (index, _) | *index >= pos.index)
+            })
+            .or(run.chunks.last())?;
+        let x = chunk.carets.iter()
+            .find( | (index, _) | *index >= pos.index)
+            .or(chunk.carets.last())
+            .map_or(0.0, | (_, x) | *x);
+        Some(Rect {
+            pos: self.origin + chunk.rect.pos + dvec2(x, 0.0),
+            size: dvec2(0.0, chunk.rect.size.y)
+        })
+    }
+    
+    pub fn select_all(&mut self) {
+        if let Some(last) = self.runs.len().checked_sub(1) {
+            self.selection = Some((FlowPos::default(), FlowPos {run: last, index: self.runs[last].text.len()}));
+        }
+    }
+    
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
+        self.select_unit = None;
+    }
+    
+    /// The selected text as plain text, None when nothing is selected
+    pub fn selected_text(&self) -> Option<String> {
+        self.selected_text_as(TextFlowCopyFormat::PlainText)
+    }
+    
+    pub fn selected_text_as(&self, format: TextFlowCopyFormat) -> Option<String> {
+        let blocks = self.selected_blocks();
+        if blocks.is_empty() {
+            return None
+        }
+        Some(match format {
+            TextFlowCopyFormat::PlainText => blocks_to_plain_text(&blocks),
+            TextFlowCopyFormat::Markdown => blocks_to_markdown(&blocks),
+            TextFlowCopyFormat::Html => blocks_to_html(&blocks),
+        })
+    }
+    
+    // the selected runs grouped by paragraph and line, with the styles they were drawn with
+    fn selected_blocks(&self) -> Vec<FlowBlock<'_>> {
+        let mut blocks: Vec<FlowBlock> = Vec::new();
+        let Some((start, end)) = self.ordered_selection() else {
+            return blocks
+        };
+        for run in start.run..=end.run.min(self.runs.len().saturating_sub(1)) {
+            let flow_run = &self.runs[run];
+            let Some(range) = self.selected_range_in(run, flow_run.text.len()) else {
+                continue
+            };
+            if blocks.is_empty() || flow_run.break_before == FlowBreak::Paragraph {
+                blocks.push(FlowBlock {
+                    list_marker: flow_run.list_marker.as_deref().filter( | _ | range.start == 0),
+                    code_block: flow_run.style.code_block,
+                    quote_depth: flow_run.style.quote_depth,
+                    lines: vec![Vec::new()],
+                });
+            }
+            let block = blocks.last_mut().unwrap();
+            if flow_run.break_before == FlowBreak::Line {
+                block.lines.push(Vec::new());
+            }
+            for (i, line) in flow_run.text[range].split('\n').enumerate() {
+                if i > 0 {
+                    block.lines.push(Vec::new());
+                }
+                let spans = block.lines.last_mut().unwrap();
+                match spans.last_mut() {
+                    Some((style, text)) if *style == flow_run.style => text.push_str(line),
+                    _ if line.is_empty() => (),
+                    _ => spans.push((flow_run.style, line.to_string())),
+                }
+            }
+        }
+        blocks
+    }
+}
+
+struct FlowBlock<'a> {
+    list_marker: Option<&'a str>,
+    code_block: bool,
+    quote_depth: usize,
+    lines: Vec<Vec<(FlowRunStyle, String)>>,
+}
+
+// list items follow each other directly, other paragraphs have an empty line between them
+fn block_separator(prev: &FlowBlock, next: &FlowBlock) -> &'static str {
+    if prev.list_marker.is_some() && next.list_marker.is_some() {"\n"} else {"\n\n"}
+}
+
+fn blocks_to_plain_text(blocks: &[FlowBlock]) -> String {
+    let mut out = String::new();
+    for (i, block) in blocks.iter().enumerate() {
+        if i > 0 {
+            out.push_str(block_separator(&blocks[i - 1], block));
+        }
+        for (j, line) in block.lines.iter().enumerate() {
+            if j > 0 {
+                out.push('\n');
+            }
+            line.iter().for_each( | (_, text) | out.push_str(text));
+        }
+    }
+    out
+}
+
+fn markdown_span(style: &FlowRunStyle, text: &str) -> String {
+    if style.inline_code {
+        return format!("`{}`", text)
+    }
+    // emphasis markers have to touch the text they wrap
+    let core = text.trim();
+    if core.is_empty() {
+        return text.to_string()
+    }
+    let lead = &text[..text.len() - text.trim_start().len()];
+    let trail = &text[text.trim_end().len()..];
+    let mut core = core.to_string();
+    if style.strikethrough {
+        core = format!("~~{}~~", core);
+    }
+    if style.italic {
+        core = format!("*{}*", core);
+    }
+    if style.bold {
+        core = format!("**{}**", core);
+    }
+    format!("{}{}{}", lead, core, trail)
+}
+
+fn markdown_list_marker(marker: &str) -> &str {
+    if marker.starts_with( | c: char | c.is_ascii_digit()) {marker} else {"-"}
+}
+
+fn blocks_to_markdown(blocks: &[FlowBlock]) -> String {
+    let mut out = String::new();
+    for (i, block) in blocks.iter().enumerate() {
+        if i > 0 {
+            out.push_str(block_separator(&blocks[i - 1], block));
+        }
+        let quote = "> ".repeat(block.quote_depth);
+        if block.code_block {
+            out.push_str(&format!("{}```\n", quote));
+            for line in &block.lines {
+                out.push_str(&quote);
+                line.iter().for_each( | (_, text) | out.push_str(text));
+                out.push('\n');
+            }
+            out.push_str(&format!("{}```", quote));
+            continue
+        }
+        for (j, line) in block.lines.iter().enumerate() {
+            if j > 0 {
+                out.push_str("  \n");
+            }
+            out.push_str(&quote);
+            if let (0, Some(marker)) = (j, block.list_marker) {
+                out.push_str(markdown_list_marker(marker));
+                out.push(' ');
+            }
+            line.iter().for_each( | (style, text) | out.push_str(&markdown_span(style, text)));
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn html_span(style: &FlowRunStyle, text: &str) -> String {
+    let mut out = escape_html(text);
+    for (on, tag) in [
+        (style.inline_code, "code"),
+        (style.strikethrough, "s"),
+        (style.underline, "u"),
+        (style.italic, "i"),
+        (style.bold, "b"),
+    ] {
+        if on {
+            out = format!("<{}>{}</{}>", tag, out, tag);
+        }
+    }
+    out
+}
+
+fn blocks_to_html(blocks: &[FlowBlock]) -> String {
+    let mut out = String::new();
+    let mut list: Option<&str> = None;
+    for block in blocks {
+        let list_tag = block.list_marker.map( | marker | {
+            if marker.starts_with( | c: char | c.is_ascii_digit()) {"ol"} else {"ul"}
+        });
+        if list != list_tag {
+            if let Some(tag) = list {
+                out.push_str(&format!("</{}>", tag));
+            }
+            if let Some(tag) = list_tag {
+                out.push_str(&format!("<{}>", tag));
+            }
+            list = list_tag;
+        }
+        out.push_str(&"<blockquote>".repeat(block.quote_depth));
+        let lines: Vec<String> = block.lines.iter().map( | line | {
+            line.iter().map( | (style, text) | {
+                if block.code_block {escape_html(text)} else {html_span(style, text)}
+            }).collect()
+        }).collect();
+        let (open, separator, close) = match (block.code_block, list_tag.is_some()) {
+            (true, _) => ("<pre><code>", "\n", "</code></pre>"),
+            (false, true) => ("<li>", "<br>", "</li>"),
+            (false, false) => ("<p>", "<br>", "</p>"),
+        };
+        out.push_str(open);
+        out.push_str(&lines.join(separator));
+        out.push_str(close);
+        out.push_str(&"</blockquote>".repeat(block.quote_depth));
+    }
+    if let Some(tag) = list {
+        out.push_str(&format!("</{}>", tag));
+    }
+    out
+}
+
+impl TextFlowRef {
+    pub fn selected_text_as(&self, format: TextFlowCopyFormat) -> Option<String> {
+        self.borrow().and_then( | inner | inner.selected_text_as(format))
+    }
+    
+    pub fn select_all(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.select_all();
+            inner.redraw(cx);
+        }
+    }
+}
+
 #[derive(Debug, Clone, DefaultNone)]
 pub enum TextFlowLinkAction {
     Clicked {
@@ -1,4 +1,5 @@
 use {
+    std::sync::Arc,
     crate::{
         makepad_derive_widget::*,
         makepad_draw::*,
@@ -38,9 +39,11 @@ pub struct TextInput {
     #[live] pub is_numeric_only: bool,
     #[live] pub empty_message: String,
     #[live] pub text: String,
+    #[live] pub nav_order: NavOrder,
 
     #[rust] cursor: Cursor,
     #[rust] history: History,
+    #[rust] data_error: Option<String>,
 }
 
 impl TextInput {
@@ -48,6 +51,10 @@ impl TextInput {
         cx.set_key_focus(self.draw_bg.area());
     }
 
+    pub fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
     pub fn set_cursor(&mut self, cursor: Cursor) {
         self.cursor = cursor;
     }
@@ -276,7 +283,12 @@ impl Widget for TextInput {
                 },
                 ..
             }) => {
-                self.move_cursor_left(is_select);
+                // in right-to-left text the previous grapheme is on the right
+                if is_rtl_paragraph(&self.text, self.cursor.head.index) {
+                    self.move_cursor_right(is_select);
+                } else {
+                    self.move_cursor_left(is_select);
+                }
                 self.draw_bg.redraw(cx);
             },
             Hit::KeyDown(KeyEvent {
@@ -289,7 +301,12 @@ impl Widget for TextInput {
                 },
                 ..
             }) => {
-                self.move_cursor_right( is_select);
+                // in right-to-left text the next grapheme is on the left
+                if is_rtl_paragraph(&self.text, self.cursor.head.index) {
+                    self.move_cursor_left(is_select);
+                } else {
+                    self.move_cursor_right(is_select);
+                }
                 self.draw_bg.redraw(cx);
             },
             Hit::KeyDown(KeyEvent {
@@ -523,6 +540,23 @@ impl Widget for TextInput {
                     cx.widget_action(uid, &scope.path, TextInputAction::Change(self.text.clone()));
                 }
             }
+            Hit::AccessAction(e) => match e.action {
+                AccessAction::Focus => {
+                    self.set_key_focus(cx);
+                }
+                AccessAction::SetText(text) if !self.is_read_only => {
+                    let text = self.filter_input(text);
+                    self.history.create_or_extend_edit_group(EditKind::Other, self.cursor);
+                    self.apply_edit(Edit {
+                        start: 0,
+                        end: self.text.len(),
+                        replace_with: text,
+                    });
+                    self.draw_bg.redraw(cx);
+                    cx.widget_action(uid, &scope.path, TextInputAction::Change(self.text.clone()));
+                }
+                _ => ()
+            }
             Hit::FingerHoverIn(_) => {
                 cx.set_cursor(MouseCursor::Text);
                 self.animator_play(cx, id!(hover.on));
@@ -638,7 +672,21 @@ impl Widget for TextInput {
             );
         }
 
-        cx.add_nav_stop(self.draw_bg.area(), NavRole::TextInput, Margin::default());
+        cx.add_nav_stop_with(NavStop::new(self.draw_bg.area(), NavRole::TextInput).with_order(self.nav_order));
+        cx.add_access_node(AccessNode::new(self.widget_uid().into(), AccessRole::TextInput)
+            .with_name(&self.empty_message)
+            .with_value(AccessValue::Text(self.text.clone()))
+            .with_description(self.data_error.as_deref().unwrap_or(""))
+            .with_states(AccessStates {
+                focusable: true,
+                focused: cx.has_key_focus(self.draw_bg.area()),
+                editable: !self.is_read_only,
+                invalid: self.data_error.is_some(),
+                ..Default::default()
+            })
+            .with_action(AccessActionKind::Focus)
+            .with_action(AccessActionKind::SetValue)
+            .with_area(self.draw_bg.area()));
 
         DrawStep::done()
     }
@@ -656,6 +704,50 @@ impl Widget for TextInput {
         self.cursor.tail.index = self.cursor.tail.index.min(text.len());
         self.history.clear();
     }
+
+    fn widget_to_data(&self, _cx: &mut Cx, actions: &Actions, nodes: &mut LiveNodeVec, path: &[LiveId]) -> bool {
+        let text = actions.filter_widget_actions_cast::<TextInputAction>(self.widget_uid()).filter_map( | action | {
+            if let TextInputAction::Change(text) = action {Some(text)} else {None}
+        }).last();
+        if let Some(text) = text {
+            nodes.write_field_value(path, LiveValue::String(Arc::new(text)));
+            return true
+        }
+        false
+    }
+
+    fn data_to_widget(&mut self, cx: &mut Cx, nodes: &[LiveNode], path: &[LiveId]) {
+        // rejected text stays up with its error until it's corrected
+        if self.data_error.is_some() {
+            return
+        }
+        let Some(value) = nodes.read_field_value(path) else {return};
+        let text = if let Some(text) = value.as_str() {
+            text.to_string()
+        }
+        else if let Some(v) = value.as_float() {
+            // text that already reads as the value is kept, so typing `1.` isn't rewritten to `1`
+            if self.text.trim().parse::<f64>() == Ok(v) {
+                return
+            }
+            v.to_string()
+        }
+        else {
+            return
+        };
+        if self.text != text {
+            self.set_text(&text);
+            self.redraw(cx);
+        }
+    }
+
+    fn set_data_error(&mut self, cx: &mut Cx, error: Option<&str>) {
+        if self.data_error.as_deref() == error {
+            return
+        }
+        self.data_error = error.map( | e | e.to_string());
+        self.animator_toggle(cx, error.is_some(), Animate::Yes, id!(error.on), id!(error.off));
+    }
 }
 
 /// The saved (checkpointed) state of a text input widget.
@@ -924,6 +1016,11 @@ impl TextInputRef {
         }
     }
 
+    /// Why the value last typed was rejected by the data binding
+    pub fn data_error(&self) -> Option<String> {
+        self.borrow().and_then( | inner | inner.data_error.clone())
+    }
+
     /// Saves the internal state of this text input widget
     /// to a new `TextInputState` object.
     pub fn save_state(&self) -> TextInputState {
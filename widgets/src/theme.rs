@@ -0,0 +1,68 @@
+use crate::makepad_draw::*;
+
+/// The variants of the desktop theme, which switch the `dark_mode` and `high_contrast`
+/// env variables the theme reads its palette from. Every widget updates on the next frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+    /// dark, with the strongest contrast between text and background
+    HighContrast,
+    /// dark or light, as the OS prefers, where the platform reports a preference
+    System,
+}
+
+pub trait CxThemeExt {
+    fn set_theme_mode(&mut self, mode: ThemeMode);
+    fn theme_mode(&self) -> ThemeMode;
+    /// Overrides the theme constants the object the pointer points at sets, for instance
+    /// `my_theme: {THEME_COLOR_MAKEPAD: #2a9df4, THEME_CORNER_RADIUS: 6.0}`.
+    /// Values derived from them follow, `None` goes back to the theme as it is.
+    fn set_theme_overrides(&mut self, theme: Option<LivePtr>);
+}
+
+impl CxThemeExt for Cx {
+    fn set_theme_mode(&mut self, mode: ThemeMode) {
+        self.set_follow_system_dark_mode(mode == ThemeMode::System);
+        self.set_high_contrast(mode == ThemeMode::HighContrast);
+        match mode {
+            ThemeMode::Dark | ThemeMode::HighContrast => self.set_dark_mode(true),
+            ThemeMode::Light => self.set_dark_mode(false),
+            // without a preference from the OS the theme stays as it is
+            ThemeMode::System => ()
+        }
+    }
+    
+    fn theme_mode(&self) -> ThemeMode {
+        if self.follow_system_dark_mode() {
+            ThemeMode::System
+        }
+        else if self.high_contrast() {
+            ThemeMode::HighContrast
+        }
+        else if self.dark_mode() {
+            ThemeMode::Dark
+        }
+        else {
+            ThemeMode::Light
+        }
+    }
+    
+    fn set_theme_overrides(&mut self, theme: Option<LivePtr>) {
+        let mut overrides = Vec::new();
+        if let Some(theme) = theme {
+            let live_registry = self.live_registry.borrow();
+            let (nodes, index) = live_registry.ptr_to_nodes_index(theme);
+            let mut node_iter = nodes.first_child(index);
+            while let Some(index) = node_iter {
+                // objects and classes aren't constants the palette can derive from
+                if !nodes[index].value.is_open() {
+                    overrides.push((nodes[index].id, nodes[index].value.clone()));
+                }
+                node_iter = nodes.next_child(index);
+            }
+        }
+        self.set_live_overrides(overrides);
+    }
+}
@@ -13,6 +13,8 @@ live_design! {
     THEME_BEVELING = 0.75
     THEME_FONT_SIZE_BASE = 7.5
     THEME_FONT_SIZE_CONTRAST = 2.5// Greater values = greater font-size steps between font-formats (i.e. from H3 to H2)
+    THEME_COLOR_BG_APP_AMOUNT = 0.3 // How far the app background is from black towards white
+    THEME_COLOR_FG_APP_AMOUNT = 0.36
 
     // DIMENSIONS
     THEME_SPACE_1 = (0.5 * (THEME_SPACE_FACTOR))
@@ -51,8 +53,29 @@ live_design! {
     THEME_COLOR_W_H = #FFFFFF00
     THEME_COLOR_B = #000000FF
     THEME_COLOR_B_H = #00000000
+    THEME_COLOR_MAKEPAD = #FF5C39FF
+    THEME_COLOR_TEXT_LINK = #4A90E2FF
+
+    // LIGHT AND HIGH CONTRAST
+    // Picked at runtime with cx.set_dark_mode and cx.set_high_contrast, or ThemeMode.
+    // To change a few values without copying this file, see CxThemeExt::set_theme_overrides.
+    if (!dark_mode) {
+        // black and white trade places, every shade below follows
+        THEME_COLOR_W = #000000FF
+        THEME_COLOR_W_H = #00000000
+        THEME_COLOR_B = #FFFFFFFF
+        THEME_COLOR_B_H = #FFFFFF00
+        THEME_COLOR_BG_APP_AMOUNT = 0.07
+        THEME_COLOR_FG_APP_AMOUNT = 0.03
+        THEME_COLOR_TEXT_LINK = #1F5FADFF
+    }
+    if (high_contrast) {
+        THEME_COLOR_CONTRAST = 2.5
+        THEME_COLOR_MAKEPAD = (dark_mode ? #FFD000FF : #0040C0FF)
+        THEME_COLOR_TEXT_LINK = (dark_mode ? #9CCBFFFF : #003A8CFF)
+    }
 
-    THEME_COLOR_WHITE = (mix(THEME_COLOR_W, #FFFFFF00, pow(0.1, THEME_COLOR_CONTRAST)))
+    THEME_COLOR_WHITE = (mix(THEME_COLOR_W, THEME_COLOR_W_H, pow(0.1, THEME_COLOR_CONTRAST)))
     THEME_COLOR_U_5 = (mix(THEME_COLOR_W, THEME_COLOR_W_H, pow(0.35, THEME_COLOR_CONTRAST)))
     THEME_COLOR_U_4 = (mix(THEME_COLOR_W, THEME_COLOR_W_H, pow(0.6, THEME_COLOR_CONTRAST)))
     THEME_COLOR_U_3 = (mix(THEME_COLOR_W, THEME_COLOR_W_H, pow(0.75, THEME_COLOR_CONTRAST)))
@@ -68,16 +91,14 @@ live_design! {
     THEME_COLOR_BLACK = (mix(THEME_COLOR_B, THEME_COLOR_B_H, pow(0.1, THEME_COLOR_CONTRAST)))
 
     // BASICS
-    THEME_COLOR_MAKEPAD = #FF5C39FF
-
     THEME_COLOR_BG_APP = (mix(
         mix(THEME_COLOR_B, THEME_COLOR_TINT, THEME_COLOR_TINT_AMOUNT),
         mix(THEME_COLOR_W, THEME_COLOR_TINT, THEME_COLOR_TINT_AMOUNT),
-        pow(0.3, THEME_COLOR_CONTRAST)))
+        pow(THEME_COLOR_BG_APP_AMOUNT, THEME_COLOR_CONTRAST)))
     THEME_COLOR_FG_APP = (mix(
         mix(THEME_COLOR_B, THEME_COLOR_TINT, THEME_COLOR_TINT_AMOUNT),
         mix(THEME_COLOR_W, THEME_COLOR_TINT, THEME_COLOR_TINT_AMOUNT),
-        pow(0.36, THEME_COLOR_CONTRAST))
+        pow(THEME_COLOR_FG_APP_AMOUNT, THEME_COLOR_CONTRAST))
     )
     THEME_COLOR_BG_HIGHLIGHT = (THEME_COLOR_FG_APP)
     THEME_COLOR_BG_UNFOCUSSED = (THEME_COLOR_BG_HIGHLIGHT * 0.85)
@@ -97,6 +118,7 @@ live_design! {
     THEME_COLOR_TEXT_INACTIVE = (THEME_COLOR_U_5)
     THEME_COLOR_TEXT_SELECTED = (THEME_COLOR_WHITE)
     THEME_COLOR_TEXT_FOCUSED = (THEME_COLOR_U_5)
+    THEME_COLOR_FOCUS_RING = (THEME_COLOR_MAKEPAD)
     THEME_COLOR_TEXT_PLACEHOLDER = (THEME_COLOR_U_4)
     THEME_COLOR_TEXT_META = (THEME_COLOR_U_4)
 
@@ -119,7 +141,7 @@ live_design! {
     THEME_COLOR_CTRL_SELECTED = (THEME_COLOR_U_2)
     THEME_COLOR_CTRL_INACTIVE = (THEME_COLOR_D_HIDDEN)
 
-    THEME_COLOR_FLOATING_BG = #505050FF // Elements that live on top of the UI like dialogs, popovers, and context menus.
+    THEME_COLOR_FLOATING_BG = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.315)) // Elements that live on top of the UI like dialogs, popovers, and context menus.
 
     // Background of textinputs, radios, checkboxes etc.
     THEME_COLOR_INSET_DEFAULT = (THEME_COLOR_D_1)
@@ -129,7 +151,7 @@ live_design! {
 
     // Progress bars, slider amounts etc.
     THEME_COLOR_AMOUNT_DEFAULT = (THEME_COLOR_U_3)
-    THEME_COLOR_AMOUNT_DEFAULT_BIG = #A
+    THEME_COLOR_AMOUNT_DEFAULT_BIG = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.667))
     THEME_COLOR_AMOUNT_HOVER = (THEME_COLOR_U_4)
     THEME_COLOR_AMOUNT_ACTIVE = (THEME_COLOR_U_5)
     THEME_COLOR_AMOUNT_TRACK_DEFAULT = (THEME_COLOR_D_3)
@@ -146,10 +168,10 @@ live_design! {
     THEME_COLOR_SLIDES_CHAPTER = (THEME_COLOR_MAKEPAD)
     THEME_COLOR_SLIDES_BG = (THEME_COLOR_D_4)
 
-    THEME_COLOR_SLIDER_BIG_NUB_TOP = #8
-    THEME_COLOR_SLIDER_BIG_NUB_TOP_HOVER = #A
-    THEME_COLOR_SLIDER_BIG_NUB_BOTTOM = #282828
-    THEME_COLOR_SLIDER_BIG_NUB_BOTTOM_HOVER = #3
+    THEME_COLOR_SLIDER_BIG_NUB_TOP = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.533))
+    THEME_COLOR_SLIDER_BIG_NUB_TOP_HOVER = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.667))
+    THEME_COLOR_SLIDER_BIG_NUB_BOTTOM = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.157))
+    THEME_COLOR_SLIDER_BIG_NUB_BOTTOM_HOVER = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.2))
 
     THEME_COLOR_CTRL_SCROLLBAR_HOVER = (THEME_COLOR_U_3)
 
@@ -157,6 +179,14 @@ live_design! {
     THEME_COLOR_DOCK_TAB_SELECTED = (THEME_COLOR_FG_APP)
     THEME_COLOR_DOCK_TAB_SELECTED_MINIMAL = (THEME_COLOR_U_4)
 
+    THEME_COLOR_CAPTION_BUTTON = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.2))
+    THEME_COLOR_CAPTION_BUTTON_HOVER = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.4))
+    THEME_COLOR_CAPTION_BUTTON_PRESSED = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.6))
+    THEME_COLOR_CAPTION_ICON = (THEME_COLOR_W)
+
+    THEME_COLOR_FOLD_ICON = (mix(THEME_COLOR_B, THEME_COLOR_W, 0.533))
+    THEME_COLOR_FOLD_ICON_HOVER = (THEME_COLOR_W)
+
 
     // TODO: THESE ARE APPLICATION SPECIFIC COLORS THAT SHOULD BE MOVED FROM THE GENERAL THEME TO THE GIVEN PROJECT
     THEME_COLOR_HIGH = #C00
@@ -186,18 +216,24 @@ live_design! {
     THEME_FONT_REGULAR = {
         font: { path: dep("crate://self/resources/IBMPlexSans-Text.ttf") }
         font2: { path: dep("crate://self/resources/LXGWWenKaiRegular.ttf") },
+        // picked by font_weight and font_style
+        font_family: [
+            { font: { path: dep("crate://self/resources/IBMPlexSans-SemiBold.ttf") }, weight: 600 },
+            { font: { path: dep("crate://self/resources/IBMPlexSans-Italic.ttf") }, style: Italic },
+            { font: { path: dep("crate://self/resources/IBMPlexSans-BoldItalic.ttf") }, weight: 700, style: Italic },
+        ]
     }
-    THEME_FONT_BOLD = {
-        font: { path: dep("crate://self/resources/IBMPlexSans-SemiBold.ttf") }
+    THEME_FONT_BOLD = <THEME_FONT_REGULAR> {
         font2: { path: dep("crate://self/resources/LXGWWenKaiBold.ttf") },
+        font_weight: 600,
     }
-    THEME_FONT_ITALIC = {
-        font: { path: dep("crate://self/resources/IBMPlexSans-Italic.ttf") }
-        font2: { path: dep("crate://self/resources/LXGWWenKaiRegular.ttf") },
+    THEME_FONT_ITALIC = <THEME_FONT_REGULAR> {
+        font_style: Italic,
     }
-    THEME_FONT_BOLD_ITALIC = {
-        font: { path: dep("crate://self/resources/IBMPlexSans-BoldItalic.ttf") },
+    THEME_FONT_BOLD_ITALIC = <THEME_FONT_REGULAR> {
         font2: { path: dep("crate://self/resources/LXGWWenKaiBold.ttf") },
+        font_weight: 700,
+        font_style: Italic,
     }
     THEME_FONT_CODE = {
         font: { path: dep("crate://self/resources/LiberationMono-Regular.ttf") }
@@ -513,7 +549,7 @@ live_design! {
                 return mix(
                     mix(
                         self.color,
-                        mix(self.color, #f, 0.5),
+                        mix(self.color, THEME_COLOR_W, 0.5),
                         self.hover
                     ),
                     self.color * 0.75,
@@ -645,6 +681,10 @@ live_design! {
 
         a = <HtmlLink> {}
 
+        draw_selection: {
+            color: (THEME_COLOR_BG_HIGHLIGHT_INLINE)
+        }
+
         draw_block:{
             line_color: (THEME_COLOR_TEXT_DEFAULT)
             sep_color: (THEME_COLOR_DIVIDER)
@@ -852,6 +892,10 @@ live_design! {
         
         link = <TextFlowLink> {}
         
+        draw_selection: {
+            color: (THEME_COLOR_BG_HIGHLIGHT_INLINE)
+        }
+
         draw_block:{
             line_color: (THEME_COLOR_TEXT_DEFAULT)
             sep_color: (THEME_COLOR_DIVIDER)
@@ -1111,6 +1155,10 @@ live_design! {
             margin: <THEME_MSPACE_V_1> {}
         }
 
+        draw_selection: {
+            color: (THEME_COLOR_BG_HIGHLIGHT_INLINE)
+        }
+
         draw_block: {
             line_color: (THEME_COLOR_TEXT_DEFAULT)
             sep_color: (THEME_COLOR_DIVIDER)
@@ -1402,7 +1450,7 @@ live_design! {
                 return mix(
                     mix(
                         self.color,
-                        mix(self.color, #f, 0.5),
+                        mix(self.color, THEME_COLOR_W, 0.5),
                         self.hover
                     ),
                     self.color * 0.75,
@@ -1938,27 +1986,27 @@ live_design! {
                 // WindowsMin
                 match self.button_type {
                     DesktopButtonType::WindowsMin => {
-                        sdf.clear(mix(THEME_COLOR_APP_CAPTION_BAR, mix(#6, #9, self.pressed), self.hover));
+                        sdf.clear(mix(THEME_COLOR_APP_CAPTION_BAR, mix(THEME_COLOR_CAPTION_BUTTON_HOVER, THEME_COLOR_CAPTION_BUTTON_PRESSED, self.pressed), self.hover));
                         sdf.move_to(c.x - sz, c.y);
                         sdf.line_to(c.x + sz, c.y);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_CAPTION_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         return sdf.result;
                     }
                     DesktopButtonType::WindowsMax => {
-                        sdf.clear(mix(THEME_COLOR_APP_CAPTION_BAR, mix(#6, #9, self.pressed), self.hover));
+                        sdf.clear(mix(THEME_COLOR_APP_CAPTION_BAR, mix(THEME_COLOR_CAPTION_BUTTON_HOVER, THEME_COLOR_CAPTION_BUTTON_PRESSED, self.pressed), self.hover));
                         sdf.rect(c.x - sz, c.y - sz, 2. * sz, 2. * sz);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_CAPTION_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         return sdf.result;
                     }
                     DesktopButtonType::WindowsMaxToggled => {
-                        let clear = mix(THEME_COLOR_APP_CAPTION_BAR, mix(#6, #9, self.pressed), self.hover);
+                        let clear = mix(THEME_COLOR_APP_CAPTION_BAR, mix(THEME_COLOR_CAPTION_BUTTON_HOVER, THEME_COLOR_CAPTION_BUTTON_PRESSED, self.pressed), self.hover);
                         sdf.clear(clear);
                         let sz = 3.5;
                         sdf.rect(c.x - sz + 1., c.y - sz - 1., 2. * sz, 2. * sz);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_CAPTION_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         sdf.rect(c.x - sz - 1., c.y - sz + 1., 2. * sz, 2. * sz);
                         sdf.fill_keep(clear);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_CAPTION_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         return sdf.result;
                     }
                     DesktopButtonType::WindowsClose => {
@@ -1967,7 +2015,8 @@ live_design! {
                         sdf.line_to(c.x + sz, c.y + sz);
                         sdf.move_to(c.x - sz, c.y + sz);
                         sdf.line_to(c.x + sz, c.y - sz);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        // white on the red of the hover, whatever the theme
+                        sdf.stroke(mix(THEME_COLOR_CAPTION_ICON, #f, self.hover), 0.5 + 0.5 * self.dpi_dilate);
                         return sdf.result;
                     }
                     DesktopButtonType::XRMode => {
@@ -1988,7 +2037,7 @@ live_design! {
                     }
                     DesktopButtonType::Fullscreen => {
                         sz = 8.;
-                        sdf.clear(mix(#3, mix(#6, #9, self.pressed), self.hover));
+                        sdf.clear(mix(THEME_COLOR_CAPTION_BUTTON, mix(THEME_COLOR_CAPTION_BUTTON_HOVER, THEME_COLOR_CAPTION_BUTTON_PRESSED, self.pressed), self.hover));
                         sdf.rect(c.x - sz, c.y - sz, 2. * sz, 2. * sz);
                         sdf.rect(c.x - sz + 1.5, c.y - sz + 1.5, 2. * (sz - 1.5), 2. * (sz - 1.5));
                         sdf.subtract();
@@ -1996,7 +2045,7 @@ live_design! {
                         sdf.subtract();
                         sdf.rect(c.x - sz - 2., c.y - sz + 4., 2. * (sz + 2.), 2. * (sz - 4.));
                         sdf.subtract();
-                        sdf.fill(#f); //, 0.5 + 0.5 * dpi_dilate);
+                        sdf.fill(THEME_COLOR_CAPTION_ICON); //, 0.5 + 0.5 * dpi_dilate);
 
                         return sdf.result;
                     }
@@ -2042,8 +2091,18 @@ live_design! {
 
     NavControl = <NavControlBase> {
         draw_focus: {
+            uniform border_width: 1.5
             fn pixel(self) -> vec4 {
-                return #000f
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(
+                    self.border_width,
+                    self.border_width,
+                    self.rect_size.x - self.border_width * 2.,
+                    self.rect_size.y - self.border_width * 2.,
+                    THEME_CORNER_RADIUS
+                );
+                sdf.stroke(THEME_COLOR_FOCUS_RING, self.border_width);
+                return sdf.result
             }
         }
         draw_text: {
@@ -2426,6 +2485,9 @@ live_design! {
         }
         tab_bar: <TabBar> {}
         splitter: <Splitter> {}
+        floating_window: <Window> {
+            window: {inner_size: vec2(640, 480)}
+        }
     }
 
     TabMinimal = <TabBase> {
@@ -2568,6 +2630,9 @@ live_design! {
         }
         tab_bar: <TabBarMinimal> {}
         splitter: <Splitter> {}
+        floating_window: <Window> {
+            window: {inner_size: vec2(640, 480)}
+        }
     }
 
     // TODO: remove?
@@ -3374,12 +3439,12 @@ live_design! {
                 return mix(
                     mix(
                         self.color,
-                        mix(self.color, #f, 0.4),
+                        mix(self.color, THEME_COLOR_W, 0.4),
                         self.hover
                     ),
                     mix(
                         self.color_active,
-                        mix(self.color_active, #f, 0.75),
+                        mix(self.color_active, THEME_COLOR_W, 0.75),
                         self.hover
                     ),
                     self.selected
@@ -3608,12 +3673,24 @@ live_design! {
                     }
                 }
             }
+            error = {
+                default: off
+                off = {
+                    from: {all: Forward {duration: 0.1}}
+                    apply: {draw_bg: {error: 0.0}}
+                }
+                on = {
+                    from: {all: Snap}
+                    apply: {draw_bg: {error: 1.0}}
+                }
+            }
         }
 
         draw_bg: {
             instance radius: (THEME_CORNER_RADIUS)
             instance hover: 0.0
             instance focus: 0.0
+            instance error: 0.0
             instance bodytop: (THEME_COLOR_INSET_DEFAULT)
             instance bodybottom: (THEME_COLOR_CTRL_ACTIVE)
 
@@ -3653,8 +3730,8 @@ live_design! {
                 sdf.fill_keep(body)
 
                 sdf.stroke(
-                    bot_gradient,
-                    THEME_BEVELING * 0.9
+                    mix(bot_gradient, THEME_COLOR_ERROR, self.error),
+                    mix(THEME_BEVELING * 0.9, THEME_BEVELING * 1.5, self.error)
                 )
 
                 return sdf.result
@@ -3724,6 +3801,353 @@ live_design! {
         }
     }
 
+    RichTextInput = <RichTextInputBase> {
+        width: Fill, height: Fit,
+        flow: RightWrap,
+        padding: <THEME_MSPACE_2> {}
+
+        font_size: (THEME_FONT_SIZE_P),
+        font_color: (THEME_COLOR_TEXT_DEFAULT),
+        link_color: (THEME_COLOR_TEXT_LINK),
+
+        paragraph_spacing: 12,
+        clipboard_format: Markdown,
+        inline_code_padding: <THEME_MSPACE_1> {},
+        inline_code_margin: <THEME_MSPACE_1> {},
+
+        draw_bg: {
+            color: (THEME_COLOR_INSET_DEFAULT)
+        }
+
+        draw_cursor: {
+            color: (THEME_COLOR_TEXT_CURSOR)
+        }
+
+        draw_normal: {
+            text_style: <THEME_FONT_REGULAR> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+            color: (THEME_COLOR_TEXT_DEFAULT)
+        }
+
+        draw_italic: {
+            text_style: <THEME_FONT_ITALIC> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+            color: (THEME_COLOR_TEXT_DEFAULT)
+        }
+
+        draw_bold: {
+            text_style: <THEME_FONT_BOLD> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+            color: (THEME_COLOR_TEXT_DEFAULT)
+        }
+
+        draw_bold_italic: {
+            text_style: <THEME_FONT_BOLD_ITALIC> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+            color: (THEME_COLOR_TEXT_DEFAULT)
+        }
+
+        draw_fixed: {
+            text_style: <THEME_FONT_CODE> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+            color: (THEME_COLOR_TEXT_DEFAULT)
+        }
+
+        code_layout: {
+            flow: RightWrap,
+            padding: <THEME_MSPACE_2> { left: (THEME_SPACE_3), right: (THEME_SPACE_3) }
+        }
+        code_walk: { width: Fill, height: Fit }
+
+        quote_layout: {
+            flow: RightWrap,
+            padding: <THEME_MSPACE_2> { left: (THEME_SPACE_3), right: (THEME_SPACE_3) }
+        }
+        quote_walk: { width: Fill, height: Fit, }
+
+        list_item_layout: {
+            flow: RightWrap,
+            padding: <THEME_MSPACE_1> {}
+        }
+        list_item_walk: {
+            height: Fit, width: Fill,
+        }
+
+        draw_selection: {
+            color: (THEME_COLOR_BG_HIGHLIGHT_INLINE)
+        }
+
+        draw_block: {
+            line_color: (THEME_COLOR_TEXT_DEFAULT)
+            sep_color: (THEME_COLOR_DIVIDER)
+            quote_bg_color: (THEME_COLOR_BG_HIGHLIGHT)
+            quote_fg_color: (THEME_COLOR_TEXT_DEFAULT)
+            code_color: (THEME_COLOR_BG_HIGHLIGHT)
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                match self.block_type {
+                    FlowBlockType::Quote => {
+                        sdf.box(
+                            0.,
+                            0.,
+                            self.rect_size.x,
+                            self.rect_size.y,
+                            2.
+                        );
+                        sdf.fill(self.quote_bg_color)
+                        sdf.box(
+                            THEME_SPACE_1,
+                            THEME_SPACE_1,
+                            THEME_SPACE_1,
+                            self.rect_size.y - THEME_SPACE_2,
+                            1.5
+                        );
+                        sdf.fill(self.quote_fg_color)
+                        return sdf.result;
+                    }
+                    FlowBlockType::Sep => {
+                        sdf.box(
+                            0.,
+                            1.,
+                            self.rect_size.x-1,
+                            self.rect_size.y-2.,
+                            2.
+                        );
+                        sdf.fill(self.sep_color);
+                        return sdf.result;
+                    }
+                    FlowBlockType::Code => {
+                        sdf.box(
+                            0.,
+                            0.,
+                            self.rect_size.x,
+                            self.rect_size.y,
+                            2.
+                        );
+                        sdf.fill(self.code_color);
+                        return sdf.result;
+                    }
+                    FlowBlockType::InlineCode => {
+                        sdf.box(
+                            1.,
+                            1.,
+                            self.rect_size.x,
+                            self.rect_size.y - 2.,
+                            2.
+                        );
+                        sdf.fill(self.code_color);
+                        return sdf.result;
+                    }
+                    FlowBlockType::Underline => {
+                        sdf.box(
+                            0.,
+                            self.rect_size.y-2,
+                            self.rect_size.x,
+                            2.0,
+                            0.5
+                        );
+                        sdf.fill(self.line_color);
+                        return sdf.result;
+                    }
+                    FlowBlockType::Strikethrough => {
+                        sdf.box(
+                            0.,
+                            self.rect_size.y * 0.45,
+                            self.rect_size.x,
+                            2.0,
+                            0.5
+                        );
+                        sdf.fill(self.line_color);
+                        return sdf.result;
+                    }
+                }
+                return #f00
+            }
+        }
+    }
+
+    DataGrid = <DataGridBase> {
+        width: Fill, height: Fill,
+
+        scroll_bars: <ScrollBars> {}
+        row_height: (THEME_DATA_ITEM_HEIGHT),
+        header_height: (THEME_DATA_ITEM_HEIGHT + 2.0),
+        column_width: 100.0,
+        min_column_width: 30.0,
+        resize_handle_width: 4.0,
+        min_drag_distance: 4.0,
+
+        cell_layout: {
+            padding: <THEME_MSPACE_H_2> {}
+            align: {y: 0.5}
+        }
+
+        draw_bg: {
+            fn pixel(self) -> vec4 {
+                return Pal::premul(THEME_COLOR_BG_CONTAINER)
+            }
+        }
+
+        // cells are plain rects with edge tests, a grid draws a lot of them
+        draw_cell: {
+            fn pixel(self) -> vec4 {
+                let p = self.pos * self.rect_size;
+                // grid lines along the right and bottom edge
+                if p.x > self.rect_size.x - 1.0 || p.y > self.rect_size.y - 1.0 {
+                    return Pal::premul(THEME_COLOR_DIVIDER)
+                }
+                let edge = min(min(p.x, p.y), min(self.rect_size.x - 1.0 - p.x, self.rect_size.y - 1.0 - p.y));
+                if self.cursor > 0.5 && edge < 1.5 {
+                    return Pal::premul(mix(THEME_COLOR_TEXT_DEFAULT, THEME_COLOR_FOCUS_RING, self.focussed))
+                }
+                return Pal::premul(mix(
+                    mix(
+                        THEME_COLOR_BG_EVEN,
+                        THEME_COLOR_BG_ODD,
+                        self.is_even
+                    ),
+                    THEME_COLOR_CTRL_SELECTED,
+                    self.selected
+                ))
+            }
+        }
+
+        draw_header: {
+            fn pixel(self) -> vec4 {
+                let p = self.pos * self.rect_size;
+                if p.x > self.rect_size.x - 1.0 || p.y > self.rect_size.y - 1.0 {
+                    return Pal::premul(THEME_COLOR_DIVIDER)
+                }
+                let bg = mix(THEME_COLOR_CTRL_DEFAULT, THEME_COLOR_CTRL_HOVER, self.dragging);
+                // the sort arrow points up when ascending
+                let c = vec2(self.rect_size.x - 10., self.rect_size.y * 0.5);
+                let d = (p.y - c.y) * self.sort;
+                if abs(self.sort) > 0.5 && abs(d) < 3.0 && abs(p.x - c.x) < (d + 3.0) * 0.6 {
+                    return Pal::premul(THEME_COLOR_TEXT_DEFAULT)
+                }
+                return Pal::premul(bg)
+            }
+        }
+
+        draw_cell_text: {
+            color: (THEME_COLOR_TEXT_DEFAULT)
+            text_style: <THEME_FONT_REGULAR> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+        }
+
+        draw_header_text: {
+            color: (THEME_COLOR_TEXT_SELECTED)
+            text_style: <THEME_FONT_BOLD> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+        }
+
+        draw_marker: {
+            fn pixel(self) -> vec4 {
+                return Pal::premul(THEME_COLOR_FOCUS_RING)
+            }
+        }
+
+        text_editor: <TextInput> {
+            width: Fill, height: Fill,
+            padding: <THEME_MSPACE_H_2> {}
+            label_align: {y: 0.5}
+        }
+
+        drop_down_editor: <DropDown> {
+            width: Fill, height: Fill,
+        }
+    }
+
+    Chart = <ChartBase> {
+        width: Fill, height: Fill,
+        padding: <THEME_MSPACE_2> {}
+
+        palette: [#FF5C39, #4FA3E0, #7BC96F, #E0B84F, #B07FE0, #E06F9B]
+        axis_color: (THEME_COLOR_TEXT_META)
+        grid_color: (THEME_COLOR_DIVIDER)
+        y_axis_width: 48.0,
+        x_axis_height: 20.0,
+        x_tick_spacing: 80.0,
+        y_tick_spacing: 40.0,
+        line_width: 1.5,
+        point_size: 5.0,
+        bar_width: 0.8,
+        area_opacity: 0.3,
+        hover_radius: 12.0,
+
+        draw_bg: {
+            fn pixel(self) -> vec4 {
+                return Pal::premul(THEME_COLOR_BG_CONTAINER)
+            }
+        }
+
+        draw_point: {
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                let r = self.rect_size.x * 0.5;
+                sdf.circle(r, r, r - 0.5);
+                sdf.fill(self.color);
+                return sdf.result
+            }
+        }
+
+        // fills between the line and the baseline, the line runs straight across the quad
+        draw_area: {
+            fn pixel(self) -> vec4 {
+                let p = self.pos * self.rect_size;
+                let y = mix(self.line_start, self.line_end, self.pos.x);
+                if p.y < min(y, self.base) || p.y > max(y, self.base) {
+                    return vec4(0.0)
+                }
+                return Pal::premul(self.color)
+            }
+        }
+
+        draw_label: {
+            color: (THEME_COLOR_TEXT_META)
+            text_style: <THEME_FONT_REGULAR> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+        }
+
+        legend_layout: {
+            flow: Right,
+            spacing: (THEME_SPACE_2)
+            padding: <THEME_MSPACE_2> {}
+            align: {y: 0.5}
+        }
+
+        draw_legend: {
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(0., 0., self.rect_size.x, self.rect_size.y, THEME_CORNER_RADIUS);
+                sdf.fill(THEME_COLOR_BG_CONTAINER * 0.9);
+                return sdf.result
+            }
+        }
+
+        tooltip_layout: {
+            padding: <THEME_MSPACE_2> {}
+        }
+
+        draw_tooltip: {
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(0., 0., self.rect_size.x, self.rect_size.y, THEME_CORNER_RADIUS);
+                sdf.fill(THEME_COLOR_FLOATING_BG);
+                return sdf.result
+            }
+        }
+    }
+
     Slider = <SliderBase> {
         min: 0.0, max: 1.0,
         step: 0.0,
@@ -3985,6 +4409,270 @@ live_design! {
     }
 
 
+    NumberInput = <NumberInputBase> {
+        width: 150, height: Fit,
+        flow: Right,
+        align: {y: 0.5}
+
+        step: 1.0,
+        precision: 0,
+        scrub_distance: 4.0,
+
+        spinner_walk: {width: 16, height: Fill}
+
+        text_input: <TextInput> {
+            width: Fill,
+            empty_message: "0",
+        }
+
+        draw_bg: {
+            fn pixel(self) -> vec4 {
+                return vec4(0.0)
+            }
+        }
+
+        draw_spinner: {
+            instance hover: 0.0
+            instance drag: 0.0
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                let c = self.rect_size * 0.5;
+                let sz = 3.0;
+                let gap = 2.0;
+                let color = mix(
+                    mix(THEME_COLOR_TEXT_DEFAULT, THEME_COLOR_TEXT_HOVER, self.hover),
+                    THEME_COLOR_TEXT_PRESSED,
+                    self.drag
+                );
+
+                sdf.move_to(c.x - sz, c.y - gap);
+                sdf.line_to(c.x, c.y - gap - sz);
+                sdf.line_to(c.x + sz, c.y - gap);
+                sdf.close_path();
+                sdf.fill(color);
+
+                sdf.move_to(c.x - sz, c.y + gap);
+                sdf.line_to(c.x, c.y + gap + sz);
+                sdf.line_to(c.x + sz, c.y + gap);
+                sdf.close_path();
+                sdf.fill(color);
+
+                return sdf.result
+            }
+        }
+
+        animator: {
+            hover = {
+                default: off
+                off = {
+                    from: {all: Forward {duration: 0.1}}
+                    apply: {draw_spinner: {hover: 0.0}}
+                }
+                on = {
+                    from: {all: Snap}
+                    apply: {draw_spinner: {hover: 1.0}}
+                }
+            }
+            drag = {
+                default: off
+                off = {
+                    from: {all: Forward {duration: 0.1}}
+                    apply: {draw_spinner: {drag: 0.0}}
+                }
+                on = {
+                    from: {all: Snap}
+                    apply: {draw_spinner: {drag: 1.0}}
+                }
+            }
+        }
+    }
+
+    CalendarPopup = <CalendarPopupBase> {
+        width: Fit, height: Fit,
+        flow: Down,
+        padding: <THEME_MSPACE_1> {}
+
+        title_walk: {width: 140, height: 24}
+        cell_walk: {width: 28, height: 24}
+        cell_layout: {align: {x: 0.5, y: 0.5}}
+
+        month_names: ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"]
+        weekday_names: ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"]
+        first_day_of_week: 1,
+
+        draw_bg: {
+            instance color: (THEME_COLOR_FLOATING_BG)
+            instance border_width: 1.0,
+            instance radius: 2.0
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size)
+                sdf.box(
+                    self.border_width,
+                    self.border_width,
+                    self.rect_size.x - self.border_width * 2.0,
+                    self.rect_size.y - self.border_width * 2.0,
+                    max(1.0, self.radius)
+                )
+                sdf.fill_keep(self.color)
+                sdf.stroke(mix(THEME_COLOR_BEVEL_LIGHT, THEME_COLOR_BEVEL_SHADOW, pow(self.pos.y, 0.35)), THEME_BEVELING)
+                return sdf.result;
+            }
+        }
+
+        draw_title: {
+            color: (THEME_COLOR_TEXT_DEFAULT),
+            text_style: <THEME_FONT_BOLD> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+        }
+
+        draw_weekday: {
+            color: (THEME_COLOR_TEXT_INACTIVE),
+            text_style: <THEME_FONT_REGULAR> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+        }
+
+        draw_day: {
+            uniform border_radius: (THEME_CORNER_RADIUS)
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(1., 1., self.rect_size.x - 2.0, self.rect_size.y - 2.0, self.border_radius);
+                sdf.fill_keep(mix(
+                    mix(THEME_COLOR_U_HIDDEN, THEME_COLOR_CTRL_HOVER, self.hover),
+                    THEME_COLOR_MAKEPAD,
+                    self.selected
+                ));
+                sdf.stroke(mix(THEME_COLOR_U_HIDDEN, THEME_COLOR_TEXT_DEFAULT, self.today * (1.0 - self.selected)), 1.0);
+                return sdf.result
+            }
+        }
+
+        draw_text: {
+            text_style: <THEME_FONT_REGULAR> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+            fn get_color(self) -> vec4 {
+                return mix(
+                    mix(
+                        mix(THEME_COLOR_TEXT_DEFAULT, THEME_COLOR_TEXT_INACTIVE, self.outside),
+                        THEME_COLOR_TEXT_PLACEHOLDER,
+                        self.disabled
+                    ),
+                    THEME_COLOR_TEXT_SELECTED,
+                    self.selected
+                )
+            }
+        }
+    }
+
+    DatePicker = <DatePickerBase> {
+        width: 120, height: Fit,
+        padding: <THEME_MSPACE_2> { right: 22.5 }
+        align: {x: 0., y: 0.}
+
+        empty_message: "YYYY-MM-DD",
+
+        popup: <CalendarPopup> {}
+
+        draw_text: {
+            instance hover: 0.0
+            text_style: <THEME_FONT_REGULAR> {
+                font_size: (THEME_FONT_SIZE_P)
+            }
+            fn get_color(self) -> vec4 {
+                return mix(THEME_COLOR_TEXT_DEFAULT, THEME_COLOR_TEXT_HOVER, self.hover)
+            }
+        }
+
+        draw_bg: {
+            instance hover: 0.0
+            instance focus: 0.0
+            instance pressed: 0.0
+            instance open: 0.0
+            uniform border_radius: (THEME_CORNER_RADIUS)
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(1., 1., self.rect_size.x - 2.0, self.rect_size.y - 2.0, self.border_radius);
+                sdf.fill_keep(mix(
+                    mix(THEME_COLOR_INSET_DEFAULT, THEME_COLOR_CTRL_HOVER, self.hover),
+                    THEME_COLOR_CTRL_ACTIVE,
+                    max(self.focus, self.open)
+                ));
+                sdf.stroke(mix(THEME_COLOR_BEVEL_SHADOW, THEME_COLOR_BEVEL_LIGHT, self.pos.y), THEME_BEVELING);
+
+                // a small calendar sheet on the right
+                let c = vec2(self.rect_size.x - 12.0, self.rect_size.y * 0.5);
+                let color = mix(THEME_COLOR_TEXT_DEFAULT, THEME_COLOR_TEXT_HOVER, self.hover);
+                sdf.box(c.x - 4.5, c.y - 4.0, 9.0, 8.0, 1.0);
+                sdf.stroke(color, 1.0);
+                sdf.rect(c.x - 4.5, c.y - 4.0, 9.0, 2.5);
+                sdf.fill(color);
+                return sdf.result
+            }
+        }
+
+        animator: {
+            hover = {
+                default: off,
+                off = {
+                    from: {all: Forward {duration: 0.1}}
+                    apply: {
+                        draw_bg: {pressed: 0.0, hover: 0.0}
+                        draw_text: {hover: 0.0}
+                    }
+                }
+                on = {
+                    from: {all: Snap}
+                    apply: {
+                        draw_bg: {pressed: 0.0, hover: 1.0}
+                        draw_text: {hover: 1.0}
+                    }
+                }
+                pressed = {
+                    from: {all: Forward {duration: 0.2}}
+                    apply: {
+                        draw_bg: {pressed: 1.0, hover: 1.0}
+                        draw_text: {hover: 1.0}
+                    }
+                }
+            }
+            focus = {
+                default: off
+                off = {
+                    from: {all: Forward {duration: 0.2}}
+                    apply: {draw_bg: {focus: 0.0}}
+                }
+                on = {
+                    from: {all: Snap}
+                    apply: {draw_bg: {focus: 1.0}}
+                }
+            }
+        }
+    }
+
+    TimePicker = <TimePickerBase> {
+        width: 90, height: Fit,
+
+        minute_step: 1,
+        use_24h: true,
+
+        text_input: <TextInput> {
+            width: Fill,
+            empty_message: "--:--",
+        }
+
+        draw_bg: {
+            fn pixel(self) -> vec4 {
+                return vec4(0.0)
+            }
+        }
+    }
+
     SlidesView = <SlidesViewBase> {
         anim_speed: 0.9
     }
@@ -4305,10 +4993,10 @@ live_design! {
                     // PLUS
                     sdf.box(0.5, sz * 3.0, sz * 2.5, sz * 0.7, 1.0); // rounding = 3rd value
                     // vertical
-                    sdf.fill_keep(mix(#8F, #FF, self.hover));
+                    sdf.fill_keep(mix(THEME_COLOR_FOLD_ICON, THEME_COLOR_FOLD_ICON_HOVER, self.hover));
                     sdf.box(sz * 1.0, sz * 2.125, sz * 0.7, sz * 2.5, 1.0); // rounding = 3rd value
 
-                    sdf.fill_keep(mix(mix(#8F, #FF, self.hover), #FFF0, self.open))
+                    sdf.fill_keep(mix(mix(THEME_COLOR_FOLD_ICON, THEME_COLOR_FOLD_ICON_HOVER, self.hover), THEME_COLOR_W_H, self.open))
 
                     return sdf.result
                 }
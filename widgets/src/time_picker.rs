@@ -0,0 +1,268 @@
+use {
+    std::{
+        fmt,
+        sync::Arc,
+    },
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        widget::*,
+        text_input::{TextInput, TextInputAction, Cursor}
+    }
+};
+
+live_design!{
+    TimePickerBase = {{TimePicker}} {}
+}
+
+/// A time on a 24 hour clock, to the minute
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeOfDay {
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u32, minute: u32) -> Option<Self> {
+        if hour > 23 || minute > 59 {
+            return None
+        }
+        Some(Self {hour, minute})
+    }
+
+    pub fn from_minutes(minutes: i64) -> Self {
+        let minutes = minutes.rem_euclid(24 * 60) as u32;
+        Self {hour: minutes / 60, minute: minutes % 60}
+    }
+
+    pub fn minutes(&self) -> i64 {
+        (self.hour * 60 + self.minute) as i64
+    }
+
+    /// Wraps around midnight
+    pub fn add_minutes(&self, minutes: i64) -> Self {
+        Self::from_minutes(self.minutes() + minutes)
+    }
+
+    /// Reads what people type into a time field: `9`, `930`, `09:30`, `9.30`, `9:30 pm` or `12am`
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_ascii_lowercase();
+        let (text, pm) = if let Some(text) = text.strip_suffix("pm") {
+            (text.trim_end(), Some(true))
+        }
+        else if let Some(text) = text.strip_suffix("am") {
+            (text.trim_end(), Some(false))
+        }
+        else {
+            (text.as_str(), None)
+        };
+        let (hour, minute) = match text.split_once([':', '.', 'h']) {
+            Some((hour, minute)) => (hour.parse().ok()?, if minute.is_empty() {0} else {minute.parse().ok()?}),
+            None if text.len() > 2 && text.bytes().all( | b | b.is_ascii_digit()) => (text[..text.len() - 2].parse().ok()?, text[text.len() - 2..].parse().ok()?),
+            None => (text.parse().ok()?, 0)
+        };
+        let hour = match pm {
+            Some(_) if hour == 0 || hour > 12 => return None,
+            Some(true) => hour % 12 + 12,
+            Some(false) => hour % 12,
+            None => hour
+        };
+        Self::new(hour, minute)
+    }
+
+    pub fn format(&self, use_24h: bool) -> String {
+        if use_24h {
+            self.to_string()
+        }
+        else {
+            let hour = if self.hour.is_multiple_of(12) {12} else {self.hour % 12};
+            format!("{}:{:02} {}", hour, self.minute, if self.hour < 12 {"AM"} else {"PM"})
+        }
+    }
+}
+
+#[derive(Live, Widget)]
+pub struct TimePicker {
+    #[redraw] #[live] draw_bg: DrawQuad,
+
+    #[walk] walk: Walk,
+    #[layout] layout: Layout,
+
+    #[live] text_input: TextInput,
+
+    /// The time as `HH:MM` on a 24 hour clock, empty when none is set yet
+    #[live] value: String,
+    /// How many minutes the arrow keys move the minutes by
+    #[live] minute_step: u32,
+    #[live(true)] use_24h: bool,
+
+    #[rust] time: Option<TimeOfDay>,
+}
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum TimePickerAction {
+    Change(TimeOfDay),
+    None
+}
+
+impl LiveHook for TimePicker {
+    fn after_apply(&mut self, _cx: &mut Cx, apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        if apply.from.is_from_doc() {
+            self.time = TimeOfDay::parse(&self.value);
+            self.update_text_input();
+        }
+    }
+}
+
+impl TimePicker {
+    pub fn time(&self) -> Option<TimeOfDay> {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: Option<TimeOfDay>) {
+        self.time = time;
+        self.value = time.map(| t | t.to_string()).unwrap_or_default();
+        self.update_text_input();
+    }
+
+    pub fn update_text_input(&mut self) {
+        self.text_input.text = self.time.map(| t | t.format(self.use_24h)).unwrap_or_default();
+        self.text_input.select_all();
+    }
+
+    fn change(&mut self, cx: &mut Cx, scope: &Scope, time: TimeOfDay) {
+        let changed = self.time != Some(time);
+        self.set_time(Some(time));
+        self.text_input.redraw(cx);
+        if changed {
+            cx.widget_action(self.widget_uid(), &scope.path, TimePickerAction::Change(time));
+        }
+    }
+
+    fn commit_text(&mut self, cx: &mut Cx, scope: &Scope) {
+        match TimeOfDay::parse(&self.text_input.text) {
+            Some(time) => self.change(cx, scope, time),
+            None => {
+                self.update_text_input();
+                self.text_input.redraw(cx);
+            }
+        }
+    }
+
+    pub fn draw_walk_time_picker(&mut self, cx: &mut Cx2d, walk: Walk) {
+        self.draw_bg.begin(cx, walk, self.layout);
+        let walk = self.text_input.walk(cx);
+        let _ = self.text_input.draw_walk(cx, &mut Scope::empty(), walk);
+        self.draw_bg.end(cx);
+    }
+}
+
+impl Widget for TimePicker {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        // the arrow keys step the hours or the minutes, whichever the cursor is in
+        if let Event::KeyDown(ke) = event {
+            if cx.has_key_focus(self.text_input.area()) && matches!(ke.key_code, KeyCode::ArrowUp | KeyCode::ArrowDown) {
+                let sign = if ke.key_code == KeyCode::ArrowUp {1} else {-1};
+                let time = TimeOfDay::parse(&self.text_input.text).or(self.time).unwrap_or_default();
+                let head = self.text_input.cursor().head.index;
+                let in_hours = self.text_input.text.find([':', '.']).is_none_or( | colon | head <= colon);
+                let step = if in_hours {60} else {
+                    // stepping lands on whole steps, so 10:07 steps to 10:10 and 10:05
+                    let step = self.minute_step.max(1) as i64;
+                    let minute = time.minute as i64;
+                    if sign > 0 {step - minute % step} else if minute % step != 0 {minute % step} else {step}
+                };
+                self.change(cx, scope, time.add_minutes(sign * step));
+                // keep the part that was stepped selected, so the arrows keep stepping it
+                let colon = self.text_input.text.find(':').unwrap_or(0);
+                let (start, end) = if in_hours {(0, colon)} else {(colon + 1, colon + 3)};
+                self.text_input.set_cursor(Cursor {
+                    head: IndexAffinity::new(end, Affinity::After),
+                    tail: IndexAffinity::new(start, Affinity::Before),
+                });
+                return
+            }
+        }
+
+        for action in cx.capture_actions( | cx | self.text_input.handle_event(cx, event, scope)) {
+            match action.as_widget_action().cast() {
+                TextInputAction::KeyFocusLost => {
+                    self.commit_text(cx, scope);
+                }
+                TextInputAction::Return(_) => {
+                    self.commit_text(cx, scope);
+                }
+                TextInputAction::Escape => {
+                    self.update_text_input();
+                    self.text_input.redraw(cx);
+                }
+                _ => ()
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.draw_walk_time_picker(cx, walk);
+        DrawStep::done()
+    }
+
+    fn widget_to_data(&self, _cx: &mut Cx, actions: &Actions, nodes: &mut LiveNodeVec, path: &[LiveId]) -> bool {
+        match actions.find_widget_action_cast(self.widget_uid()) {
+            TimePickerAction::Change(time) => {
+                nodes.write_field_value(path, LiveValue::String(Arc::new(time.to_string())));
+                true
+            }
+            _ => false
+        }
+    }
+
+    fn data_to_widget(&mut self, cx: &mut Cx, nodes: &[LiveNode], path: &[LiveId]) {
+        if let Some(value) = nodes.read_field_value(path) {
+            if let Some(text) = value.as_str() {
+                let time = TimeOfDay::parse(text);
+                if time != self.time {
+                    self.set_time(time);
+                    self.text_input.redraw(cx);
+                }
+            }
+        }
+    }
+
+    fn set_data_error(&mut self, cx: &mut Cx, error: Option<&str>) {
+        self.text_input.set_data_error(cx, error);
+    }
+
+    fn text(&self) -> String {
+        self.value.clone()
+    }
+
+    fn set_text(&mut self, v: &str) {
+        self.set_time(TimeOfDay::parse(v));
+    }
+}
+
+impl TimePickerRef {
+    pub fn time(&self) -> Option<TimeOfDay> {
+        self.borrow().and_then( | inner | inner.time())
+    }
+
+    pub fn set_time(&self, cx: &mut Cx, time: Option<TimeOfDay>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_time(time);
+            inner.text_input.redraw(cx);
+        }
+    }
+
+    pub fn changed(&self, actions: &Actions) -> Option<TimeOfDay> {
+        if let TimePickerAction::Change(time) = actions.find_widget_action_cast(self.widget_uid()) {
+            return Some(time)
+        }
+        None
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     Area,
     Cx,
+    DVec2,
     Event,
     Hit,
     MouseCursor,
@@ -20,6 +21,14 @@ pub enum ScrollMode {
     Swipe,
 }
 
+/// The coordinate of the finger a gesture follows
+#[derive(Default, Clone, Copy, Debug)]
+pub enum TouchAxis {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
 #[derive(Default, Clone, Debug)]
 enum ScrollState {
     #[default]
@@ -46,6 +55,7 @@ pub struct TouchGesture {
 
     scroll_mode: ScrollMode,
     scroll_state: ScrollState,
+    axis: TouchAxis,
 
     min_scrolled_at: f64,
     max_scrolled_at: f64,
@@ -64,6 +74,7 @@ impl TouchGesture {
 
             scroll_state: ScrollState::Stopped,
             scroll_mode: ScrollMode::DragAndDrop,
+            axis: TouchAxis::Vertical,
 
             scrolled_at: 0.0,
             min_scrolled_at: f64::MIN,
@@ -80,6 +91,10 @@ impl TouchGesture {
         self.scroll_mode = scroll_mode;
     }
 
+    pub fn set_axis(&mut self, axis: TouchAxis) {
+        self.axis = axis;
+    }
+
     pub fn set_range(&mut self, min_offset: f64, max_offset: f64) {
         self.min_scrolled_at = min_offset;
         self.max_scrolled_at = max_offset;
@@ -109,6 +124,16 @@ impl TouchGesture {
     }
 
     pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, area: Area) -> TouchMotionChange {
+        let change = self.handle_next_frame(cx, event);
+        if change.has_changed() {
+            return change
+        }
+        let hit = event.hits_with_capture_overload(cx, area, true);
+        self.handle_hit(cx, &hit)
+    }
+
+    /// Moves a flick or a pulldown on by a frame
+    pub fn handle_next_frame(&mut self, cx: &mut Cx, event: &Event) -> TouchMotionChange {
         let needs_pulldown_when_flicking = self.needs_pulldown_when_flicking();
         let needs_pulldown = self.needs_pulldown();
 
@@ -176,10 +201,20 @@ impl TouchGesture {
             _=>()
         }
 
-        match event.hits_with_capture_overload(cx, area, true) {
+        TouchMotionChange::None
+    }
+
+    /// Follows the finger of a hit, for gestures that share the hit of an area with other
+    /// gestures, such as one for every axis
+    pub fn handle_hit(&mut self, cx: &mut Cx, hit: &Hit) -> TouchMotionChange {
+        let abs = | abs: DVec2 | match self.axis {
+            TouchAxis::Vertical => abs.y,
+            TouchAxis::Horizontal => abs.x,
+        };
+        match hit {
             Hit::FingerDown(e) => {
                 self.scroll_state = ScrollState::Drag {
-                    samples: vec![ScrollSample{abs: e.abs.y, time: e.time}]
+                    samples: vec![ScrollSample{abs: abs(e.abs), time: e.time}]
                 };
 
                 return TouchMotionChange::ScrollStateChanged
@@ -188,7 +223,7 @@ impl TouchGesture {
                 cx.set_cursor(MouseCursor::Default);
                 match &mut self.scroll_state {
                     ScrollState::Drag {samples}=>{
-                        let new_abs = e.abs.y;
+                        let new_abs = abs(e.abs);
                         let old_sample = *samples.last().unwrap();
                         samples.push(ScrollSample{abs: new_abs, time: e.time});
                         if samples.len() > 4 {
@@ -0,0 +1,51 @@
+use crate::{
+    makepad_derive_widget::*,
+    makepad_draw::*,
+    makepad_draw::makepad_vector::geometry::{AffineTransformation, Vector},
+    widget::*
+};
+
+live_design!{
+    VectorCanvasBase = {{VectorCanvas}} {}
+}
+
+/// A surface to fill and stroke vector paths on, for charts and diagrams. Its `draw_walk` steps
+/// once with the canvas itself, the paths drawn before the next step are positioned in its own
+/// coordinates, with the origin at its top left corner.
+#[derive(Live, LiveHook, Widget)]
+pub struct VectorCanvas {
+    #[walk] walk: Walk,
+    #[layout] layout: Layout,
+    #[live] pub draw_vector: DrawVector,
+    #[redraw] #[rust] area: Area,
+    #[rust] draw_state: DrawStateWrap<()>,
+    #[rust] rect: Rect,
+}
+
+impl Widget for VectorCanvas {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        if self.draw_state.begin(cx, ()) {
+            cx.begin_turtle(walk, self.layout);
+            self.rect = cx.turtle().rect();
+            self.draw_vector.set_transform(AffineTransformation::translation(Vector::new(self.rect.pos.x, self.rect.pos.y)));
+            return DrawStep::make_step()
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl VectorCanvas {
+    /// The size the canvas was laid out with in this draw.
+    pub fn size(&self) -> DVec2 {
+        self.rect.size
+    }
+
+    pub fn fill(&mut self, cx: &mut Cx2d, path: &VectorPath, paint: impl Into<VectorPaint>, fill_rule: FillRule) {
+        self.draw_vector.fill(cx, path, paint, fill_rule);
+    }
+
+    pub fn stroke(&mut self, cx: &mut Cx2d, path: &VectorPath, paint: impl Into<VectorPaint>, stroke: &StrokeStyle) {
+        self.draw_vector.stroke(cx, path, paint, stroke);
+    }
+}
@@ -155,6 +155,14 @@ pub struct View {
     scroll_bars: Option<LivePtr>,
     #[live(false)]
     design_mode: bool,
+    /// Makes the view itself reachable with tab, like an item of a list.
+    #[live(false)]
+    nav_stop: bool,
+    #[live]
+    nav_order: NavOrder,
+    /// The arrow keys move the focus between the nav stops inside the view.
+    #[live(false)]
+    nav_group: bool,
 
     #[rust]
     find_cache: RefCell<SmallVec<[(u64, WidgetSet);3]>>,
@@ -288,6 +296,8 @@ pub enum ViewAction {
     FingerHoverOut(FingerHoverEvent),
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
+    /// Space or Enter was pressed on a view with `nav_stop: true`
+    Activated(KeyModifiers),
 }
 
 impl ViewRef {
@@ -354,6 +364,18 @@ impl ViewRef {
         None
     }
 
+    /// Returns the key modifiers if the view was activated from the keyboard
+    pub fn activated(&self, actions: &Actions) -> Option<KeyModifiers> {
+        actions.filter_widget_actions_cast::<ViewAction>(self.widget_uid()).find_map( | action | {
+            if let ViewAction::Activated(modifiers) = action {
+                Some(modifiers)
+            }
+            else {
+                None
+            }
+        })
+    }
+
     pub fn animator_cut(&self, cx: &mut Cx, state: &[LiveId; 2]) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.animator_cut(cx, state);
@@ -673,7 +695,7 @@ impl Widget for View {
             _=>()
         }
         
-        if self.visible && self.cursor.is_some() || self.animator.live_ptr.is_some() {
+        if self.visible && (self.cursor.is_some() || self.nav_stop) || self.animator.live_ptr.is_some() {
             match event.hits_with_capture_overload(cx, self.area(), self.capture_overload) {
                 Hit::FingerDown(e) => {
                     if self.grab_key_focus {
@@ -706,7 +728,14 @@ impl Widget for View {
                         self.animator_play(cx, id!(hover.off));
                     }
                 }
-                Hit::KeyDown(e) => cx.widget_action(uid, &scope.path, ViewAction::KeyDown(e)),
+                Hit::KeyDown(e) => {
+                    let activates = self.nav_stop && !e.is_repeat && matches!(e.key_code, KeyCode::Space | KeyCode::ReturnKey);
+                    let modifiers = e.modifiers;
+                    cx.widget_action(uid, &scope.path, ViewAction::KeyDown(e));
+                    if activates {
+                        cx.widget_action(uid, &scope.path, ViewAction::Activated(modifiers));
+                    }
+                }
                 Hit::KeyUp(e) => cx.widget_action(uid, &scope.path, ViewAction::KeyUp(e)),
                 _ => (),
             }
@@ -826,6 +855,9 @@ impl Widget for View {
             } else {
                 cx.begin_turtle(walk, self.layout.with_scroll(scroll)); //.with_scale(2.0 / self.dpi_factor.unwrap_or(2.0)));
             }
+            if self.nav_group {
+                cx.begin_nav_group(NavAxis::Both);
+            }
         }
 
         while let Some(DrawState::Drawing(step, resume)) = self.draw_state.get() {
@@ -859,6 +891,9 @@ impl Widget for View {
                 }
                 self.draw_state.set(DrawState::DeferWalk(step + 1));
             } else {
+                if self.nav_group {
+                    cx.end_nav_group();
+                }
                 if let Some(scroll_bars) = &mut self.scroll_bars_obj {
                     scroll_bars.draw_scroll_bars(cx);
                 };
@@ -877,6 +912,9 @@ impl Widget for View {
                     scroll_bars.set_area(self.area);
                     scroll_bars.end_nav_area(cx);
                 };
+                if self.nav_stop {
+                    cx.add_nav_stop_with(NavStop::new(self.area, NavRole::ListItem).with_order(self.nav_order));
+                }
 
                 if self.optimize.needs_draw_list() {
                     let rect = self.area.rect(cx);
@@ -1015,6 +1053,7 @@ impl View {
                 Size::Fixed(view_size.y)
             },
             margin: walk.margin,
+            ..walk
         }
     }
 
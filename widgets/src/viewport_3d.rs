@@ -0,0 +1,148 @@
+use crate::{
+    makepad_derive_widget::*,
+    makepad_draw::*,
+    touch_gesture::{ScrollMode, TouchAxis, TouchGesture},
+    widget::*
+};
+
+live_design!{
+    Viewport3DBase = {{Viewport3D}} {}
+}
+
+/// Shows a 3D scene from a glTF file, see `Scene3d::parse_gltf`. The scene is drawn in a pass
+/// of its own, with a depth texture, and dragging it turns the camera around it while the
+/// scroll wheel moves the camera closer or further away.
+#[derive(Live, Widget)]
+pub struct Viewport3D {
+    #[walk] walk: Walk,
+    #[redraw] #[live] draw_bg: DrawQuad,
+    #[live] pub draw_mesh: DrawMesh,
+    #[live] source: LiveDependency,
+    #[live] pub camera: OrbitCamera,
+    /// Aims the camera at a scene when it is loaded, from as close as all of it fits in view
+    #[live(true)] fit_camera: bool,
+    #[live] clear_color: Vec4,
+    /// The degrees the camera turns for every pixel dragged
+    #[live(0.5)] orbit_speed: f64,
+    #[rust] loaded_source: String,
+    #[rust] scene: Scene3d,
+    #[rust] pass: Option<Viewport3DPass>,
+    #[rust] yaw_gesture: TouchGesture,
+    #[rust] pitch_gesture: TouchGesture,
+    // where the gestures were when the camera was last turned
+    #[rust] dragged_at: DVec2,
+}
+
+struct Viewport3DPass {
+    pass: Pass,
+    draw_list: DrawList2d,
+    color_texture: Texture,
+    depth_texture: Texture,
+}
+
+impl LiveHook for Viewport3D {
+    fn after_new_from_doc(&mut self, _cx: &mut Cx) {
+        for (gesture, axis) in [(&mut self.yaw_gesture, TouchAxis::Horizontal), (&mut self.pitch_gesture, TouchAxis::Vertical)] {
+            *gesture = TouchGesture::new();
+            gesture.set_mode(ScrollMode::Swipe);
+            gesture.set_axis(axis);
+        }
+    }
+
+    fn after_apply(&mut self, cx: &mut Cx, _apply: &mut Apply, _index: usize, _nodes: &[LiveNode]) {
+        if let Some(pass) = &self.pass {
+            pass.pass.clear_color_textures(cx);
+            pass.pass.add_color_texture(cx, &pass.color_texture, PassClearColor::ClearWith(self.clear_color));
+        }
+        let source = self.source.as_str().to_string();
+        if source.is_empty() || source == self.loaded_source {
+            return
+        }
+        self.loaded_source = source.clone();
+        match cx.get_dependency(&source).and_then( | data | Scene3d::parse_gltf(&data)) {
+            Ok(scene) => self.set_scene(cx, scene),
+            Err(err) => error!("Viewport3D cannot load {}: {}", source, err)
+        }
+    }
+}
+
+impl Widget for Viewport3D {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, _scope: &mut Scope) {
+        let mut changed = self.yaw_gesture.handle_next_frame(cx, event).has_changed();
+        changed |= self.pitch_gesture.handle_next_frame(cx, event).has_changed();
+        // both gestures follow the same finger, which only hits the area once
+        let hit = event.hits_with_capture_overload(cx, self.draw_bg.area(), true);
+        if let Hit::FingerScroll(e) = &hit {
+            self.camera.zoom((e.scroll.y * 0.002).exp() as f32);
+            changed = true;
+        }
+        changed |= self.yaw_gesture.handle_hit(cx, &hit).has_changed();
+        changed |= self.pitch_gesture.handle_hit(cx, &hit).has_changed();
+        if changed {
+            let dragged_at = dvec2(self.yaw_gesture.scrolled_at, self.pitch_gesture.scrolled_at);
+            let delta = dragged_at - self.dragged_at;
+            self.dragged_at = dragged_at;
+            // the scene follows the finger, so the camera turns the other way
+            self.camera.orbit((delta.x * self.orbit_speed) as f32, (-delta.y * self.orbit_speed) as f32);
+            self.draw_bg.redraw(cx);
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle(walk);
+        let clear_color = self.clear_color;
+        let pass = self.pass.get_or_insert_with( | | {
+            let pass = Viewport3DPass {
+                pass: Pass::new(cx),
+                draw_list: DrawList2d::new(cx),
+                color_texture: Texture::new_with_format(cx, TextureFormat::RenderBGRAu8 {
+                    size: TextureSize::Auto,
+                    initial: true,
+                }),
+                depth_texture: Texture::new_with_format(cx, TextureFormat::DepthD32 {
+                    size: TextureSize::Auto,
+                    initial: true,
+                }),
+            };
+            pass.pass.add_color_texture(cx, &pass.color_texture, PassClearColor::ClearWith(clear_color));
+            pass.pass.set_depth_texture(cx, &pass.depth_texture, PassClearDepth::ClearWith(1.0));
+            pass
+        });
+        pass.pass.set_matrix_mode(cx, self.camera.matrix_mode());
+        cx.make_child_pass(&pass.pass);
+        cx.begin_pass(&pass.pass, None);
+        pass.draw_list.begin_always(cx);
+        self.scene.draw(cx, &mut self.draw_mesh, self.camera.eye());
+        pass.draw_list.end(cx);
+        cx.end_pass(&pass.pass);
+
+        self.draw_bg.draw_vars.set_texture(0, &pass.color_texture);
+        self.draw_bg.draw_abs(cx, rect);
+        cx.set_pass_area(&pass.pass, self.draw_bg.area());
+        DrawStep::done()
+    }
+}
+
+impl Viewport3D {
+    pub fn scene(&self) -> &Scene3d {
+        &self.scene
+    }
+
+    pub fn set_scene(&mut self, cx: &mut Cx, scene: Scene3d) {
+        if self.fit_camera {
+            if let Some(bounds) = scene.bounds() {
+                self.camera.fit(bounds);
+            }
+        }
+        self.scene = scene;
+        self.draw_bg.redraw(cx);
+    }
+}
+
+impl Viewport3DRef {
+    pub fn set_scene(&self, cx: &mut Cx, scene: Scene3d) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_scene(cx, scene);
+        }
+    }
+}
@@ -20,6 +20,12 @@ pub enum WidgetCache {
 #[derive(Clone, Debug, Copy, PartialEq)]
 pub struct WidgetUid(pub u64);
 
+impl From<WidgetUid> for AccessNodeId {
+    fn from(uid: WidgetUid) -> Self {
+        AccessNodeId(uid.0)
+    }
+}
+
 pub trait WidgetDesign: WidgetNode {}
 
 #[derive(Clone, Debug, DefaultNone)]
@@ -82,6 +88,8 @@ pub trait Widget: WidgetNode {
         false
     }
     fn data_to_widget(&mut self, _cx: &mut Cx, _nodes: &[LiveNode], _path: &[LiveId]) {}
+    /// Shows why the value this widget wrote to its data binding was rejected, `None` clears it
+    fn set_data_error(&mut self, _cx: &mut Cx, _error: Option<&str>) {}
     
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep;
 
@@ -475,6 +483,12 @@ impl WidgetRef {
         }
     }
 
+    pub fn set_data_error(&self, cx: &mut Cx, error: Option<&str>) {
+        if let Some(inner) = self.0.borrow_mut().as_mut() {
+            inner.widget.set_data_error(cx, error);
+        }
+    }
+
     pub fn uid_to_widget(&self, uid: WidgetUid) -> WidgetRef {
         if self.widget_uid() == uid {
             return self.clone();
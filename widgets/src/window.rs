@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use crate::{
     makepad_derive_widget::*,
     debug_view::DebugView,
@@ -111,6 +112,25 @@ impl LiveHook for Window {
     }
 }
 
+/// Windows a widget opens and draws from inside another window, mapped to that host window.
+/// The host passes their input events on to its children instead of dropping them.
+#[derive(Default)]
+pub struct HostedWindows(HashMap<WindowId, WindowId>);
+
+impl HostedWindows {
+    pub fn host(cx: &mut Cx, window_id: WindowId, host_id: WindowId) {
+        cx.global::<HostedWindows>().0.insert(window_id, host_id);
+    }
+    
+    pub fn release(cx: &mut Cx, window_id: WindowId) {
+        cx.global::<HostedWindows>().0.remove(&window_id);
+    }
+    
+    pub fn host_of(cx: &mut Cx, window_id: WindowId) -> Option<WindowId> {
+        cx.global::<HostedWindows>().0.get(&window_id).cloned()
+    }
+}
+
 #[derive(Clone, Debug, DefaultNone)]
 pub enum WindowAction {
     EventForOtherWindow,
@@ -127,6 +147,14 @@ impl Window {
             return Redrawing::no()
         }
         
+        self.begin_always(cx);
+        
+        Redrawing::yes()
+    }
+    
+    /// Begins drawing the window without checking if it needs a redraw,
+    /// for windows whose contents are drawn by a widget in another window
+    pub fn begin_always(&mut self, cx: &mut Cx2d) {
         cx.begin_pass(&self.pass, None);
 
         self.main_draw_list.begin_always(cx);
@@ -134,13 +162,20 @@ impl Window {
         cx.begin_pass_sized_turtle(Layout::flow_down());
         
         self.overlay.begin(cx);
-        
-        Redrawing::yes()
+    }
+    
+    pub fn window_id(&self) -> WindowId {
+        self.window.window_id()
+    }
+    
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.window.close(cx);
     }
     
     pub fn end(&mut self, cx: &mut Cx2d) {
         //while self.frame.draw_widget_continue(cx).is_not_done() {}
         self.debug_view.draw(cx);
+        self.nav_control.draw(cx);
         
         // lets draw our cursor
         if let OsType::LinuxDirect = cx.os_type() {
@@ -210,13 +245,21 @@ impl Widget for Window {
             }
             cx.repaint_pass_and_child_passes(self.pass.pass_id());
         }
+        // events of windows hosted by this one go to our children, which draw them
+        let window_id = self.window.window_id();
+        let is_other = | cx: &mut Cx, event_window_id: WindowId | {
+            event_window_id != window_id && HostedWindows::host_of(cx, event_window_id) != Some(window_id)
+        };
         let is_for_other_window = match event {
-            Event::WindowCloseRequested(ev) => ev.window_id != self.window.window_id(),
+            Event::WindowCloseRequested(ev) => is_other(cx, ev.window_id),
             Event::WindowClosed(ev) => {
                 if ev.window_id == self.window.window_id() {
-                    cx.widget_action(uid, &scope.path, WindowAction::WindowClosed)
+                    cx.widget_action(uid, &scope.path, WindowAction::WindowClosed);
+                    true
+                }
+                else {
+                    is_other(cx, ev.window_id)
                 }
-                true
             }
             Event::WindowGeomChange(ev) => {
                 if ev.window_id == self.window.window_id() {
@@ -238,7 +281,7 @@ impl Widget for Window {
                     cx.widget_action(uid, &scope.path, WindowAction::WindowGeomChange(ev.clone()));
                     return
                 }
-                true
+                is_other(cx, ev.window_id)
             },
             Event::WindowDragQuery(dq) => {
                 if dq.window_id == self.window.window_id() {
@@ -257,14 +300,17 @@ impl Widget for Window {
                         if dq.abs.x < self.caption_size.x && dq.abs.y < self.caption_size.y {
                         }*/
                     }
+                    true
+                }
+                else {
+                    is_other(cx, dq.window_id)
                 }
-                true
             }
-            Event::TouchUpdate(ev) => ev.window_id != self.window.window_id(),
-            Event::MouseDown(ev) => ev.window_id != self.window.window_id(),
-            Event::MouseMove(ev) => ev.window_id != self.window.window_id(),
-            Event::MouseUp(ev) => ev.window_id != self.window.window_id(),
-            Event::Scroll(ev) => ev.window_id != self.window.window_id(),
+            Event::TouchUpdate(ev) => is_other(cx, ev.window_id),
+            Event::MouseDown(ev) => is_other(cx, ev.window_id),
+            Event::MouseMove(ev) => is_other(cx, ev.window_id),
+            Event::MouseUp(ev) => is_other(cx, ev.window_id),
+            Event::Scroll(ev) => is_other(cx, ev.window_id),
             _ => false
         };
         
@@ -0,0 +1,266 @@
+#![cfg(target_os = "linux")]
+
+use {
+    makepad_widgets::*,
+    makepad_widgets::makepad_platform::os::linux::dbus::{DBusConnection, DBusMessage, DBusMessageType, DBusValue},
+    std::{
+        io::{BufRead, BufReader},
+        path::PathBuf,
+        process::{Child, Command, Stdio},
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    },
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 300), title: "Access"},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    flow: Down,
+                    ok_button = <Button>{text: "OK"}
+                    agree = <CheckBox>{text: "Agree"}
+                    caption = <Label>{text: "Caption"}
+                    name_input = <TextInput>{width: 200, empty_message: "Name"}
+                    volume = <Slider>{width: 200, text: "Volume", min: 0.0, max: 1.0, default: 0.5}
+                }
+            }
+        }
+    }
+}
+
+static CLICKS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_actions(&mut self, _cx: &mut Cx, actions: &Actions) {
+        if self.ui.button(id!(ok_button)).clicked(actions) {
+            CLICKS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+fn step_until(cx: &mut Cx, mut done: impl FnMut(&mut Cx) -> bool) {
+    for _ in 0..60 {
+        cx.headless_step(1.0 / 60.0);
+        if done(cx) {
+            return
+        }
+    }
+    panic!("condition not reached after 60 frames");
+}
+
+fn request(cx: &mut Cx, window_id: WindowId, node: &AccessNode, action: AccessAction) {
+    cx.access_request(AccessRequest {window_id, node_id: node.id, action});
+}
+
+#[test]
+fn widgets_populate_the_tree_and_handle_actions() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+
+    let window_id = cx.headless_framebuffers()[0].0;
+    let tree = cx.access_tree(window_id).unwrap();
+    assert_eq!(tree.title, "Access");
+
+    let button = tree.find_by_name(AccessRole::Button, "OK").unwrap().clone();
+    assert!(button.has_action(AccessActionKind::Press));
+    assert!(button.rect.size.x > 0.0 && button.rect.size.y > 0.0);
+    assert!(tree.find_by_name(AccessRole::Label, "Caption").is_some());
+    let check_box = tree.find_by_name(AccessRole::CheckBox, "Agree").unwrap().clone();
+    assert_eq!(check_box.states.checked, Some(false));
+    let input = tree.find_by_name(AccessRole::TextInput, "Name").unwrap().clone();
+    assert!(input.states.editable && input.states.focusable);
+    let slider = tree.find_by_name(AccessRole::Slider, "Volume").unwrap().clone();
+    assert!(matches!(slider.value, Some(AccessValue::Number {value, ..}) if value == 0.5));
+
+    let clicks = CLICKS.load(Ordering::SeqCst);
+    request(&mut cx, window_id, &button, AccessAction::Press);
+    step_until(&mut cx, | _ | CLICKS.load(Ordering::SeqCst) == clicks + 1);
+
+    request(&mut cx, window_id, &check_box, AccessAction::Press);
+    step_until(&mut cx, | cx | cx.access_tree(window_id).unwrap().find(check_box.id).unwrap().states.checked == Some(true));
+
+    request(&mut cx, window_id, &input, AccessAction::SetText("Ada".into()));
+    step_until(&mut cx, | cx | cx.access_tree(window_id).unwrap().find(input.id).unwrap().value == Some(AccessValue::Text("Ada".into())));
+
+    request(&mut cx, window_id, &slider, AccessAction::SetNumber(0.75));
+    step_until(&mut cx, | cx | matches!(
+        cx.access_tree(window_id).unwrap().find(slider.id).unwrap().value,
+        Some(AccessValue::Number {value, ..}) if (value - 0.75).abs() < 1e-9
+    ));
+
+    // a label has no actions, requests for it are dropped
+    let label = cx.access_tree(window_id).unwrap().find_by_name(AccessRole::Label, "Caption").unwrap().clone();
+    request(&mut cx, window_id, &label, AccessAction::Press);
+    cx.headless_step(1.0 / 60.0);
+}
+
+// a private bus so the test doesn't depend on (or disturb) the desktop's accessibility bus
+struct PrivateBus {
+    child: Child,
+    dir: PathBuf,
+    address: String,
+}
+
+impl PrivateBus {
+    fn start() -> Option<PrivateBus> {
+        let dir = std::env::temp_dir().join(format!("makepad-atspi-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).ok()?;
+        let config = dir.join("bus.conf");
+        std::fs::write(&config, format!(r#"<busconfig>
+            <type>session</type>
+            <listen>unix:path={}</listen>
+            <auth>EXTERNAL</auth>
+            <policy context="default">
+                <allow send_destination="*" eavesdrop="true"/>
+                <allow eavesdrop="true"/>
+                <allow own="*"/>
+            </policy>
+        </busconfig>"#, dir.join("bus").display())).ok()?;
+        let mut child = Command::new("dbus-daemon")
+            .arg(format!("--config-file={}", config.display()))
+            .arg("--nofork")
+            .arg("--print-address")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let mut address = String::new();
+        BufReader::new(child.stdout.take()?).read_line(&mut address).ok()?;
+        Some(PrivateBus {child, dir, address: address.trim().to_string()})
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+// frames are rendered by the shader interpreter, which is slow in debug builds
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+fn call(conn: &mut DBusConnection, dest: &str, path: &str, interface: &str, member: &str, body: Vec<DBusValue>) -> Vec<DBusValue> {
+    conn.call(DBusMessage::method_call(dest, path, interface, member, body), TIMEOUT).unwrap().body
+}
+
+fn children(conn: &mut DBusConnection, dest: &str, path: &str) -> Vec<String> {
+    let body = call(conn, dest, path, "org.a11y.atspi.Accessible", "GetChildren", vec![]);
+    body[0].as_slice().unwrap().iter().map( | child | child.as_slice().unwrap()[1].as_str().unwrap().to_string()).collect()
+}
+
+fn property(conn: &mut DBusConnection, dest: &str, path: &str, interface: &str, name: &str) -> DBusValue {
+    call(conn, dest, path, "org.freedesktop.DBus.Properties", "Get", vec![DBusValue::string(interface), DBusValue::string(name)]).remove(0)
+}
+
+// run with `cargo test --test accessibility -- --ignored` where dbus-daemon is installed
+#[test]
+#[ignore = "needs dbus-daemon"]
+fn screen_readers_see_and_press_widgets_over_atspi() {
+    let bus = PrivateBus::start().expect("dbus-daemon is not available");
+
+    // the test plays the AT-SPI registry, the first thing the bridge talks to
+    let mut registry = DBusConnection::connect(&bus.address).unwrap();
+    call(&mut registry, "org.freedesktop.DBus", "/org/freedesktop/DBus", "org.freedesktop.DBus", "RequestName", vec![
+        DBusValue::string("org.a11y.atspi.Registry"),
+        DBusValue::Uint32(4),
+    ]);
+
+    // the headless backend never starts the bridge by itself
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.start_atspi_bridge_on(bus.address.clone());
+
+    let start = Instant::now();
+    let app = loop {
+        assert!(start.elapsed() < TIMEOUT, "the bridge never registered");
+        let Some(msg) = registry.read_message(Some(Duration::from_millis(100))).unwrap() else {
+            continue
+        };
+        if msg.message_type == DBusMessageType::MethodCall && msg.member.as_deref() == Some("Embed") {
+            let app = msg.sender.clone().unwrap();
+            let unique_name = registry.unique_name.clone();
+            registry.send(msg.method_return(vec![DBusValue::Struct(vec![
+                DBusValue::string(unique_name),
+                DBusValue::object_path("/org/a11y/atspi/accessible/root"),
+            ])])).unwrap();
+            break app
+        }
+    };
+
+    let root = "/org/a11y/atspi/accessible/root";
+    assert_eq!(property(&mut registry, &app, root, "org.a11y.atspi.Application", "ToolkitName").as_str(), Some("Makepad"));
+
+    // the window shows up once it has been drawn
+    let start = Instant::now();
+    let window = loop {
+        assert!(start.elapsed() < TIMEOUT, "the window never showed up");
+        cx.headless_step(1.0 / 60.0);
+        if let Some(window) = children(&mut registry, &app, root).pop() {
+            break window
+        }
+    };
+    assert_eq!(property(&mut registry, &app, &window, "org.a11y.atspi.Accessible", "Name").as_str(), Some("Access"));
+
+    let mut button = None;
+    let mut slider = None;
+    for child in children(&mut registry, &app, &window) {
+        let role = call(&mut registry, &app, &child, "org.a11y.atspi.Accessible", "GetRole", vec![])[0].as_i64();
+        let name = property(&mut registry, &app, &child, "org.a11y.atspi.Accessible", "Name");
+        match (role, name.as_str()) {
+            (Some(43), Some("OK")) => button = Some(child),
+            (Some(51), Some("Volume")) => slider = Some(child),
+            _ => ()
+        }
+    }
+    let button = button.expect("no push button named OK");
+    let slider = slider.expect("no slider named Volume");
+
+    let actions = call(&mut registry, &app, &button, "org.a11y.atspi.Action", "GetActions", vec![]);
+    assert_eq!(actions[0].as_slice().unwrap()[0].as_slice().unwrap()[0].as_str(), Some("click"));
+    let clicks = CLICKS.load(Ordering::SeqCst);
+    let done = call(&mut registry, &app, &button, "org.a11y.atspi.Action", "DoAction", vec![DBusValue::Int32(0)]);
+    assert_eq!(done[0].as_bool(), Some(true));
+    step_until(&mut cx, | _ | CLICKS.load(Ordering::SeqCst) == clicks + 1);
+
+    call(&mut registry, &app, &slider, "org.freedesktop.DBus.Properties", "Set", vec![
+        DBusValue::string("org.a11y.atspi.Value"),
+        DBusValue::string("CurrentValue"),
+        DBusValue::variant(DBusValue::Double(0.25)),
+    ]);
+    let start = Instant::now();
+    loop {
+        assert!(start.elapsed() < TIMEOUT, "the slider never moved");
+        cx.headless_step(1.0 / 60.0);
+        let value = property(&mut registry, &app, &slider, "org.a11y.atspi.Value", "CurrentValue").as_f64().unwrap();
+        if (value - 0.25).abs() < 1e-9 {
+            break
+        }
+    }
+}
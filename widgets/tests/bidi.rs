@@ -0,0 +1,134 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        draw_text: {
+            text_style: {font: {path: dep("crate://self/resources/IBMPlexSans-Text.ttf")}}
+            wrap: Word
+        }
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 200)},
+                body = <View>{
+                    flow: Down,
+                    row = <View>{
+                        width: 300,
+                        height: 20,
+                        spacing: 10,
+                        direction: RightToLeft,
+                        first = <View>{width: 50, height: 20}
+                        second = <View>{width: 100, height: 20}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+    #[live] draw_text: DrawText,
+}
+
+struct Layouts {
+    // (index, affinity, x) of every caret in "abc אבג"
+    carets: Vec<(usize, Affinity, f64)>,
+    clicks: Vec<IndexAffinity>,
+    selection: Vec<Rect>,
+    rtl_start: DVec2,
+    row: Rect,
+    first: Rect,
+    second: Rect,
+}
+
+thread_local! {
+    static LAYOUTS: RefCell<Option<Layouts>> = const {RefCell::new(None)};
+}
+
+const MIXED: &str = "abc אבג";
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let row = self.ui.view(id!(row)).area().rect(cx);
+        let first = self.ui.view(id!(first)).area().rect(cx);
+        let second = self.ui.view(id!(second)).area().rect(cx);
+
+        let draw_event = DrawEvent::default();
+        let cx = &mut Cx2d::new(cx, &draw_event);
+        let fit = Walk::fit();
+        let dt = &self.draw_text;
+        let mut carets = Vec::new();
+        for (index, _) in MIXED.char_indices().chain(Some((MIXED.len(), ' '))) {
+            for affinity in [Affinity::Before, Affinity::After] {
+                let position = dt.index_affinity_to_position(cx, fit, Align::default(), 0.0, MIXED, IndexAffinity::new(index, affinity));
+                carets.push((index, affinity, position.x));
+            }
+        }
+        let clicks = carets.iter().map( | (_, _, x) | {
+            dt.position_to_index_affinity(cx, fit, Align::default(), 0.0, MIXED, dvec2(*x + 0.1, 5.0))
+        }).collect();
+        let selection = dt.selected_rects(cx, fit, Align::default(), 0.0, MIXED, IndexAffinity::new(4, Affinity::After), IndexAffinity::new(8, Affinity::Before));
+        let rtl_start = dt.index_affinity_to_position(cx, Walk::fixed(200.0, 20.0), Align::default(), 200.0, "אבג", IndexAffinity::new(0, Affinity::After));
+
+        let layouts = Layouts {carets, clicks, selection, rtl_start, row, first, second};
+        LAYOUTS.with( | l | *l.borrow_mut() = Some(layouts));
+    }
+}
+
+#[test]
+fn mixed_direction_text_and_mirrored_layout() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let layouts = LAYOUTS.with( | l | l.borrow_mut().take()).unwrap();
+    let x = | index: usize, affinity: Affinity | {
+        layouts.carets.iter().find( | c | c.0 == index && c.1 == affinity).unwrap().2
+    };
+
+    // "abc " runs left to right, then the hebrew word right to left from its right edge
+    let ltr = [0, 1, 2, 3, 4].map( | index | x(index, Affinity::Before));
+    assert!(ltr.windows(2).all( | w | w[0] < w[1]), "{:?}", ltr);
+    let rtl = [4, 6, 8, 10].map( | index | x(index, Affinity::After));
+    assert!(rtl.windows(2).all( | w | w[0] > w[1]), "{:?}", rtl);
+    // the end of "abc " and the end of the hebrew word are both next to the space
+    assert_eq!(x(10, Affinity::After), x(4, Affinity::Before));
+
+    // clicking on a caret puts the cursor where it is shown at that spot
+    for ((index, affinity, caret_x), click) in layouts.carets.iter().zip(&layouts.clicks) {
+        assert_eq!(x(click.index, click.affinity), *caret_x, "{:?}", (index, affinity, click));
+    }
+    assert_eq!(layouts.clicks[9], IndexAffinity::new(4, Affinity::After));
+    assert_eq!(layouts.clicks[8], IndexAffinity::new(4, Affinity::Before));
+
+    // selecting the first two hebrew letters covers the right part of the word
+    assert_eq!(layouts.selection.len(), 1);
+    let rect = layouts.selection[0];
+    assert!((rect.pos.x - x(8, Affinity::After)).abs() < 0.01, "{:?}", rect);
+    assert!((rect.pos.x + rect.size.x - x(4, Affinity::After)).abs() < 0.01, "{:?}", rect);
+
+    // a wrapping right-to-left paragraph starts at the right
+    assert!(layouts.rtl_start.x > 199.0 && layouts.rtl_start.x <= 200.0, "{:?}", layouts.rtl_start);
+    assert!(is_rtl_paragraph("abc\nאבג abc", 6));
+    assert!(!is_rtl_paragraph("abc\nאבג abc", 0));
+
+    // a right-to-left view puts its first child on the right
+    let (row, first, second) = (layouts.row, layouts.first, layouts.second);
+    assert_eq!(first.pos.x + first.size.x, row.pos.x + row.size.x);
+    assert_eq!(second.pos.x + second.size.x + 10.0, first.pos.x);
+    assert_eq!(second.size.x, 100.0);
+}
@@ -0,0 +1,187 @@
+use {
+    makepad_widgets::*,
+    std::{
+        cell::RefCell,
+        collections::VecDeque,
+    },
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 300)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    // the plot runs from (40, 0) to (400, 280)
+                    chart = <Chart>{padding: 0, y_axis_width: 40, x_axis_height: 20}
+                }
+            }
+        }
+    }
+}
+
+enum Command {
+    SetSeries(Vec<ChartSeries>),
+    Append(usize, Vec<DVec2>),
+}
+
+thread_local! {
+    static COMMAND: RefCell<Option<Command>> = const {RefCell::new(None)};
+    static VIEW: RefCell<Option<ChartView>> = const {RefCell::new(None)};
+    static CHANGED: RefCell<Vec<ChartView>> = const {RefCell::new(Vec::new())};
+    static HOVERED: RefCell<Vec<(usize, DVec2)>> = const {RefCell::new(Vec::new())};
+    static LENGTHS: RefCell<Vec<usize>> = const {RefCell::new(Vec::new())};
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_startup(&mut self, cx: &mut Cx) {
+        let line = ChartSeries::new("line", ChartKind::Line).with_points((0..=10).map( | x | dvec2(x as f64, x as f64)));
+        self.ui.chart(id!(chart)).set_series(cx, vec![line]);
+    }
+
+    fn handle_actions(&mut self, _cx: &mut Cx, actions: &Actions) {
+        let chart = self.ui.chart(id!(chart));
+        if let Some(view) = chart.view_changed(actions) {
+            CHANGED.with( | c | c.borrow_mut().push(view));
+        }
+        if let Some(hovered) = chart.point_hovered(actions) {
+            HOVERED.with( | h | h.borrow_mut().push(hovered));
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let chart = self.ui.chart(id!(chart));
+        if let Event::Signal = event {
+            match COMMAND.with( | c | c.borrow_mut().take()) {
+                Some(Command::SetSeries(series)) => chart.set_series(cx, series),
+                Some(Command::Append(series, points)) => chart.append(cx, series, &points),
+                None => ()
+            }
+        }
+        VIEW.with( | v | *v.borrow_mut() = chart.view());
+        let lengths = chart.borrow().map( | inner | inner.series().iter().map( | s | s.len()).collect());
+        LENGTHS.with( | l | *l.borrow_mut() = lengths.unwrap_or_default());
+    }
+}
+
+fn command(cx: &mut Cx, command: Command) {
+    COMMAND.with( | c | *c.borrow_mut() = Some(command));
+    SignalToUI::set_ui_signal();
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+}
+
+fn view() -> ChartView {
+    VIEW.with( | v | v.borrow().unwrap())
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[test]
+fn ticks_are_round_and_decimation_keeps_the_extremes() {
+    assert_eq!(chart_ticks(0.0, 10.0, 5), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+    assert_eq!(chart_ticks(-0.3, 0.75, 4), vec![0.0, 0.5]);
+    assert_eq!(chart_ticks(120.0, 980.0, 5), vec![200.0, 400.0, 600.0, 800.0]);
+    assert!(chart_ticks(1.0, 1.0, 4).is_empty());
+
+    // a million points over 500 columns come down to at most four a column
+    let points: VecDeque<DVec2> = (0..1_000_000).map( | i | {
+        let x = i as f64 / 1000.0;
+        dvec2(x, (x * 7.0).sin() + if i == 654_321 {5.0} else {0.0})
+    }).collect();
+    let mut out = Vec::new();
+    chart_decimate(&points, 0..points.len(), 0.0, 1000.0, 500, &mut out);
+    assert!(out.len() <= 2000, "{} points are left", out.len());
+    assert_eq!(out.first(), points.front());
+    assert_eq!(out.last(), points.back());
+    // a spike in between survives
+    assert!(out.iter().any( | p | p.y > 3.0));
+    assert!(out.windows(2).all( | w | w[0].x <= w[1].x));
+
+    // the series keeps the newest points past its capacity
+    let mut series = ChartSeries::new("stream", ChartKind::Line).with_capacity(100);
+    series.extend((0..1000).map( | x | dvec2(x as f64, 0.0)));
+    assert_eq!(series.len(), 100);
+    assert_eq!(series.points().front().unwrap().x, 900.0);
+}
+
+#[test]
+fn hover_zoom_pan_and_stream() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+
+    // the view fits the data, with some room above and below
+    let fitted = view();
+    assert!(close(fitted.min.x, 0.0) && close(fitted.max.x, 10.0));
+    assert!(close(fitted.min.y, -0.5) && close(fitted.max.y, 10.5));
+
+    // (5, 5) is in the middle of the plot
+    cx.headless_mouse_move(dvec2(222.0, 138.0), KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(HOVERED.with( | h | h.borrow().clone()), vec![(0, dvec2(5.0, 5.0))]);
+
+    // the wheel zooms in around the mouse, which keeps (5, 5) where it is
+    cx.headless_scroll(dvec2(220.0, 140.0), dvec2(0.0, -100.0), true);
+    cx.headless_step(1.0 / 60.0);
+    let zoomed = view();
+    assert!(zoomed.span().x < 9.0 && zoomed.span().y < 10.0);
+    assert!(close((zoomed.min.x + zoomed.max.x) * 0.5, 5.0));
+    assert_eq!(CHANGED.with( | c | c.borrow().last().cloned()), Some(zoomed));
+
+    // dragging a tenth of the plot to the right shows what is left of it
+    cx.headless_mouse_down(dvec2(220.0, 140.0), 0, KeyModifiers::default());
+    cx.headless_mouse_move(dvec2(256.0, 140.0), KeyModifiers::default());
+    cx.headless_mouse_up(dvec2(256.0, 140.0), 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    let panned = view();
+    assert!(close(panned.min.x, zoomed.min.x - zoomed.span().x * 0.1));
+    assert!(close(panned.min.y, zoomed.min.y));
+
+    // a double click goes back to following the data
+    cx.headless_step(1.0);
+    cx.headless_mouse_down(dvec2(220.0, 140.0), 0, KeyModifiers::default());
+    cx.headless_mouse_up(dvec2(220.0, 140.0), 0, KeyModifiers::default());
+    cx.headless_mouse_down(dvec2(220.0, 140.0), 0, KeyModifiers::default());
+    cx.headless_mouse_up(dvec2(220.0, 140.0), 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(view(), fitted);
+
+    // a streamed series only keeps its newest points, and the view follows them
+    command(&mut cx, Command::SetSeries(vec![
+        ChartSeries::new("stream", ChartKind::Area).with_capacity(100),
+        ChartSeries::new("bars", ChartKind::Bar),
+        ChartSeries::new("dots", ChartKind::Scatter).with_points([dvec2(950.0, 3.0), dvec2(900.0, -2.0)]),
+    ]));
+    command(&mut cx, Command::Append(0, (0..1000).map( | x | dvec2(x as f64, (x % 10) as f64)).collect()));
+    command(&mut cx, Command::Append(1, vec![dvec2(920.0, 4.0), dvec2(940.0, 6.0)]));
+    assert_eq!(LENGTHS.with( | l | l.borrow().clone()), vec![100, 2, 2]);
+    let streamed = view();
+    // the x range has room for the bars at its ends, areas and bars stand on zero
+    assert!(streamed.min.x < 900.0 && streamed.min.x > 850.0);
+    assert!(streamed.max.x > 999.0 && streamed.max.x < 1050.0);
+    assert!(streamed.min.y < -2.0 && streamed.max.y > 9.0);
+}
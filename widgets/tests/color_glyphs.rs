@@ -0,0 +1,91 @@
+use {
+    makepad_widgets::*,
+    makepad_widgets::makepad_draw::{
+        color_glyph,
+        font_atlas::{CxFont, CxFontsAtlasRc, GlyphId},
+        makepad_vector::geometry::{AffineTransformation, Vector},
+    },
+    std::rc::Rc,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 200)},
+                body = <View>{
+                    label = <Label>{
+                        // U+F0101, a linear gradient from red to blue
+                        text: "󰄁"
+                        draw_text: {text_style: {
+                            font: {path: dep("crate://self/../libs/ttf-parser/tests/fonts/colr_1.ttf")}
+                            font_size: 30.0
+                        }}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+const COLR_FONT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../libs/ttf-parser/tests/fonts/colr_1.ttf");
+
+fn rgba(pixel: u32) -> [u32; 4] {
+    [pixel >> 16 & 0xff, pixel >> 8 & 0xff, pixel & 0xff, pixel >> 24]
+}
+
+#[test]
+fn colr_glyphs_are_painted_into_the_color_page() {
+    let mut font = CxFont::load_from_ttf_bytes(Rc::new(std::fs::read(COLR_FONT).unwrap())).unwrap();
+
+    // a COLRv1 glyph is as big as its clip box, plain outlines have no color version
+    let bounds = font.get_color_glyph_bounds(9).unwrap();
+    assert_eq!((bounds.p_min.x, bounds.p_min.y, bounds.p_max.x, bounds.p_max.y), (100.0, 250.0, 900.0, 950.0));
+    assert!(font.get_color_glyph_bounds(1).is_none());
+
+    // at a tenth of its size the gradient runs from red on the left to blue on the right
+    let transform = AffineTransformation::identity()
+        .translate(Vector::new(-100.0, -250.0))
+        .uniform_scale(0.1);
+    let pixels = font.owned_font_face.with_ref( | face | {
+        color_glyph::rasterize_color_glyph(face, GlyphId(9), transform, 80, 70)
+    }).unwrap();
+    let [r, g, b, a] = rgba(pixels[35 * 80 + 20]);
+    assert!(a == 255 && r > 200 && b < 80 && g < 30, "{:?}", (r, g, b, a));
+    let [r, g, b, a] = rgba(pixels[35 * 80 + 60]);
+    assert!(a == 255 && b > 200 && r < 80 && g < 30, "{:?}", (r, g, b, a));
+
+    // drawing the glyph as text puts it in the color page of the atlas, not the SDF one
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let atlas = cx.get_global::<CxFontsAtlasRc>().clone();
+    let atlas = atlas.0.borrow();
+    assert!(atlas.color_alloc.xpos > 0);
+    let pixels = atlas.texture_color.take_vec_u32(&mut cx);
+    let painted: Vec<[u32; 4]> = pixels.iter().copied().map(rgba).filter( | c | c[3] == 255).collect();
+    assert!(painted.iter().any( | c | c[0] > 200 && c[2] < 80));
+    assert!(painted.iter().any( | c | c[2] > 200 && c[0] < 80));
+    atlas.texture_color.put_back_vec_u32(&mut cx, pixels, None);
+}
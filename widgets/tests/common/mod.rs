@@ -0,0 +1,152 @@
+// shared by several test crates, each of which only uses some of it
+#![allow(dead_code)]
+
+use makepad_widgets::*;
+
+// widgets that can only be instantiated from inside their parent, or that need a main module
+const NEEDS_CONTEXT: &[LiveId] = &[
+    live_id!(HtmlLink),
+    live_id!(Designer),
+];
+
+// Instantiates every widget the default theme defines, which compiles all the draw
+// shaders they use into cx.draw_shaders.compile_set.
+pub fn instantiate_theme_widgets() -> Cx {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_widgets::live_design(&mut cx);
+    cx.live_expand();
+    
+    let mut widget_ptrs = Vec::new();
+    {
+        let live_registry = cx.live_registry.borrow();
+        let widget_registry = live_registry.components.get::<WidgetRegistry>();
+        for (file_index, live_file) in live_registry.live_files.iter().enumerate() {
+            if !live_file.file_name.ends_with("theme_desktop_dark.rs") {
+                continue
+            }
+            let nodes = &live_file.expanded.nodes;
+            if nodes.is_empty() {
+                continue
+            }
+            let mut node_iter = nodes.first_child(0);
+            while let Some(index) = node_iter {
+                if let LiveValue::Class {live_type, ..} = nodes[index].value {
+                    if widget_registry.map.contains_key(&live_type) && !NEEDS_CONTEXT.contains(&nodes[index].id) {
+                        widget_ptrs.push(live_registry.file_id_index_to_live_ptr(LiveFileId::new(file_index), index));
+                    }
+                }
+                node_iter = nodes.next_child(index);
+            }
+        }
+    }
+    assert!(!widget_ptrs.is_empty());
+    
+    for ptr in widget_ptrs {
+        let _ = WidgetRef::new_from_ptr(&mut cx, Some(ptr));
+    }
+    
+    assert!(cx.draw_shaders.error_set.is_empty(), "some draw shaders failed to compile");
+    assert!(!cx.draw_shaders.compile_set.is_empty());
+    cx
+}
+
+fn derefs_to_draw_vars(live_registry: &LiveRegistry, live_type: LiveType) -> bool {
+    if let Some(lti) = live_registry.live_type_infos.get(&live_type) {
+        lti.fields.iter().any( | field | {
+            if let LiveFieldKind::Deref = field.live_field_kind {
+                field.live_type_info.live_type == LiveType::of::<DrawVars>() ||
+                    derefs_to_draw_vars(live_registry, field.live_type_info.live_type)
+            }
+            else {
+                false
+            }
+        })
+    }
+    else {
+        false
+    }
+}
+
+// Whether a draw struct has a 3D geometry, directly or through the struct it derefs to
+fn has_cube_3d_geometry(live_registry: &LiveRegistry, live_type: LiveType) -> bool {
+    if let Some(lti) = live_registry.live_type_infos.get(&live_type) {
+        lti.fields.iter().any( | field | {
+            field.live_type_info.live_type == LiveType::of::<GeometryCube3D>() ||
+                matches!(field.live_field_kind, LiveFieldKind::Deref) && has_cube_3d_geometry(live_registry, field.live_type_info.live_type)
+        })
+    }
+    else {
+        false
+    }
+}
+
+// Compiles every draw shader class the filtered modules define, at any depth of their
+// live documents, including the ones no widget instantiates.
+pub fn compile_draw_shaders(cx: &mut Cx, filter: impl Fn(LiveModuleId) -> bool) {
+    let mut shader_ptrs = Vec::new();
+    {
+        let live_registry = cx.live_registry.borrow();
+        for (file_index, live_file) in live_registry.live_files.iter().enumerate() {
+            if !filter(live_file.module_id) {
+                continue
+            }
+            for (index, node) in live_file.expanded.nodes.iter().enumerate() {
+                if let LiveValue::Class {live_type, ..} = node.value {
+                    if derefs_to_draw_vars(&live_registry, live_type) {
+                        let is_3d = has_cube_3d_geometry(&live_registry, live_type);
+                        shader_ptrs.push((live_registry.file_id_index_to_live_ptr(LiveFileId::new(file_index), index), is_3d));
+                    }
+                }
+            }
+        }
+    }
+    assert!(!shader_ptrs.is_empty());
+    
+    let geometry = GeometryQuad2D::new(cx);
+    let geometry_3d = GeometryCube3D::new(cx);
+    for (ptr, is_3d) in shader_ptrs {
+        let geometry: &dyn GeometryFields = if is_3d {&geometry_3d} else {&geometry};
+        let mut draw_vars = DrawVars::default();
+        draw_vars.init_shader(cx, &mut ApplyFrom::New.into(), DrawShaderPtr(ptr), geometry);
+    }
+    
+    assert!(cx.draw_shaders.error_set.is_empty(), "some draw shaders failed to compile");
+}
+
+// All the draw shaders of the draw and widgets crates.
+pub fn compile_all_draw_shaders() -> Cx {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_widgets::live_design(&mut cx);
+    cx.live_expand();
+    compile_draw_shaders(&mut cx, | module_id | {
+        module_id.0 == live_id!(makepad_draw) || module_id.0 == live_id!(makepad_widgets)
+    });
+    cx
+}
+
+// Starts the app on the headless backend, live_design is the one of the test crate.
+pub fn headless_app<A: LiveNew + AppMain + 'static>(live_design: fn(&mut Cx)) -> Cx {
+    let mut app: Option<A> = None;
+    let mut cx = Cx::new(Box::new(move | cx, event | {
+        if let Event::Startup = event {
+            app = Some(A::new_main(cx));
+        }
+        if let (Event::LiveEdit, Some(app)) = (event, &mut app) {
+            app.update_main(cx);
+        }
+        if let Some(app) = &mut app {
+            app.handle_event(cx, event);
+        }
+    }));
+    A::register_main_module(&mut cx);
+    live_design(&mut cx);
+    cx.init_cx_os();
+    cx.headless_start();
+    cx
+}
+
+pub fn press(cx: &mut Cx, key_code: KeyCode, modifiers: KeyModifiers) {
+    cx.headless_key_down(key_code, modifiers);
+    cx.headless_key_up(key_code, modifiers);
+    cx.headless_step(1.0 / 60.0);
+}
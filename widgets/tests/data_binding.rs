@@ -0,0 +1,213 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 400)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    flow: Down,
+                    padding: 10,
+                    spacing: 10,
+                    name = <TextInput>{width: 200}
+                    age = <TextInput>{width: 200}
+                    rating = <NumberInput>{precision: 1, step: 0.1, locale: "C"}
+                    todos = <PortalList>{
+                        width: Fill,
+                        height: 200,
+                        Todo = <View>{
+                            width: Fill,
+                            height: Fit,
+                            flow: Right,
+                            spacing: 10,
+                            title = <TextInput>{width: 150}
+                            done = <CheckBox>{text: "Done"}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, DataBind)]
+struct Todo {
+    #[bind(title)] title: String,
+    #[bind(done)] done: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, DataBind)]
+struct Profile {
+    #[bind(name)] name: String,
+    #[bind(age, validate = Self::check_age)] age: u32,
+    #[bind(rating)] rating: f64,
+    #[bind(todos, template = Todo)] todos: Vec<Todo>,
+    edits: usize,
+}
+
+impl Profile {
+    fn check_age(age: &u32) -> Result<(), String> {
+        if *age > 150 {
+            return Err("Enter an age up to 150".to_string())
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+struct State {
+    profile: Profile,
+    changes: Vec<Vec<LiveId>>,
+    texts: Vec<String>,
+    age_error: Option<String>,
+    rects: Vec<Rect>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+    #[rust] profile: Profile,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_startup(&mut self, cx: &mut Cx) {
+        self.profile = Profile {
+            name: "Ada".to_string(),
+            age: 36,
+            rating: 4.5,
+            todos: vec![
+                Todo {title: "Write".to_string(), done: false},
+                Todo {title: "Test".to_string(), done: true},
+            ],
+            edits: 0,
+        };
+        self.profile.data_to_widgets(cx, &self.ui);
+    }
+
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions) {
+        let changes = self.profile.widgets_to_data(cx, actions, &self.ui);
+        if changes.is_empty() {
+            return
+        }
+        // the app reacts to the edits and shows the result
+        self.profile.edits += changes.len();
+        if changes.contains(&vec![live_id!(age)]) {
+            self.profile.rating = self.profile.age as f64 / 10.0;
+        }
+        self.profile.data_to_widgets(cx, &self.ui);
+        STATE.with( | s | s.borrow_mut().changes.extend(changes));
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+
+        let list = self.ui.portal_list(id!(todos));
+        let item = | index | list.get_item(index).map(| (_, item) | item).unwrap_or_default();
+        let ui = &self.ui;
+        let texts = vec![
+            ui.widget(id!(name)).text(),
+            ui.widget(id!(age)).text(),
+            ui.widget(id!(rating)).text(),
+            item(0).widget(id!(title)).text(),
+            item(1).widget(id!(title)).text(),
+        ];
+        let rects = [ui.widget(id!(name)), ui.widget(id!(age)), item(0).widget(id!(done)), item(1).widget(id!(title))]
+            .into_iter().map( | w | w.area().rect(cx)).collect();
+        let age_error = ui.text_input(id!(age)).data_error();
+        let profile = self.profile.clone();
+        STATE.with( | s | {
+            let mut s = s.borrow_mut();
+            s.profile = profile;
+            s.texts = texts;
+            s.age_error = age_error;
+            s.rects = rects;
+        });
+    }
+}
+
+fn state() -> State {
+    STATE.with( | s | s.borrow().clone())
+}
+
+const CTRL: KeyModifiers = KeyModifiers {control: true, shift: false, alt: false, logo: false};
+
+fn type_into(cx: &mut Cx, rect: Rect, text: &str) {
+    cx.headless_click(rect.pos + dvec2(20.0, rect.size.y * 0.5));
+    cx.headless_step(1.0 / 60.0);
+    common::press(cx, KeyCode::KeyA, CTRL);
+    cx.headless_text_input(text);
+    cx.headless_step(1.0 / 60.0);
+}
+
+#[test]
+fn typed_struct_binds_both_ways() {
+    let mut cx = common::headless_app::<App>(live_design);
+    // nothing here looks at pixels
+    cx.headless_set_painting(false);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+
+    // the fields show in their widgets, the list draws an item per todo by itself
+    let s = state();
+    assert_eq!(s.texts, ["Ada", "36", "4.5", "Write", "Test"]);
+    let (name, age, done_0, title_1) = (s.rects[0], s.rects[1], s.rects[2], s.rects[3]);
+    assert!(done_0.size.x > 0.0 && title_1.size.y > 0.0);
+
+    // text that doesn't convert or validate is shown as an error and leaves the field alone
+    type_into(&mut cx, age, "abc");
+    let s = state();
+    assert_eq!((s.profile.age, s.age_error.as_deref()), (36, Some("Enter a whole number")));
+    assert_eq!(s.texts[1], "abc");
+    type_into(&mut cx, age, "200");
+    assert_eq!(state().age_error.as_deref(), Some("Enter an age up to 150"));
+    assert_eq!(state().profile.age, 36);
+
+    // a valid value clears the error, and what the app does in response shows up
+    type_into(&mut cx, age, "41");
+    let s = state();
+    assert_eq!((s.profile.age, s.age_error), (41, None));
+    assert_eq!(s.profile.rating, 4.1);
+    assert_eq!(s.texts[2], "4.1");
+
+    type_into(&mut cx, name, "Grace");
+    assert_eq!(state().profile.name, "Grace");
+
+    // edits in the list items land in the item they came from
+    cx.headless_click(done_0.pos + dvec2(8.0, done_0.size.y * 0.5));
+    cx.headless_step(1.0 / 60.0);
+    type_into(&mut cx, title_1, "Ship");
+    let s = state();
+    assert_eq!(s.profile.todos, [
+        Todo {title: "Write".to_string(), done: true},
+        Todo {title: "Ship".to_string(), done: true},
+    ]);
+    assert_eq!(s.changes, [
+        vec![live_id!(age)],
+        vec![live_id!(name)],
+        vec![live_id!(todos), LiveId(0), live_id!(done)],
+        vec![live_id!(todos), LiveId(1), live_id!(title)],
+    ]);
+    assert_eq!(s.profile.edits, 4);
+}
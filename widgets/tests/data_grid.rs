@@ -0,0 +1,258 @@
+use {
+    makepad_widgets::*,
+    std::{
+        cell::{Cell, RefCell},
+        collections::HashMap,
+        rc::Rc,
+    },
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(320, 140), title: "Grid"},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    grid = <DataGrid>{column_width: 120, row_height: 20, header_height: 20}
+                }
+            }
+        }
+    }
+}
+
+const ROWS: usize = 1_000_000;
+
+// a million rows that only exist when they are asked for
+#[derive(Default)]
+struct Numbers {
+    descending: bool,
+    // numbered columns after the four named ones
+    extra_columns: usize,
+    edits: HashMap<(usize, usize), String>,
+    cells_read: Cell<usize>,
+}
+
+impl Numbers {
+    fn id(&self, row: usize) -> usize {
+        if self.descending {ROWS - 1 - row} else {row}
+    }
+}
+
+impl DataGridSource for Numbers {
+    fn row_count(&self) -> usize {
+        ROWS
+    }
+
+    fn column_count(&self) -> usize {
+        4 + self.extra_columns
+    }
+
+    fn column_title(&self, column: usize) -> String {
+        ["Id", "Name", "Status", "Score"].get(column).map_or(format!("Column {}", column), | title | title.to_string())
+    }
+
+    fn cell_text(&self, row: usize, column: usize) -> String {
+        self.cells_read.set(self.cells_read.get() + 1);
+        let id = self.id(row);
+        if let Some(text) = self.edits.get(&(id, column)) {
+            return text.clone()
+        }
+        match column {
+            0 => id.to_string(),
+            1 => format!("Name {}", id),
+            2 => if id.is_multiple_of(2) {"Open"} else {"Closed"}.to_string(),
+            3 => (id * 7 % 100).to_string(),
+            _ => format!("{}:{}", id, column),
+        }
+    }
+
+    fn cell_editor(&self, _row: usize, column: usize) -> DataGridEditor {
+        match column {
+            1 => DataGridEditor::Text,
+            2 => DataGridEditor::DropDown(vec!["Open".into(), "Closed".into()]),
+            _ => DataGridEditor::None
+        }
+    }
+
+    fn set_cell_text(&mut self, row: usize, column: usize, text: &str) {
+        let id = self.id(row);
+        self.edits.insert((id, column), text.to_string());
+    }
+
+    fn sort_by(&mut self, column: usize, ascending: bool) -> bool {
+        if column != 0 {
+            return false
+        }
+        self.descending = !ascending;
+        true
+    }
+}
+
+thread_local! {
+    static SOURCE: Rc<RefCell<Numbers>> = Rc::new(RefCell::new(Numbers::default()));
+    static EDITS: RefCell<Vec<(usize, usize, String)>> = const {RefCell::new(Vec::new())};
+    static SELECTION: RefCell<Option<DataGridSelection>> = const {RefCell::new(None)};
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_startup(&mut self, cx: &mut Cx) {
+        let source = SOURCE.with( | s | s.clone());
+        self.ui.data_grid(id!(grid)).set_source(cx, source);
+    }
+
+    fn handle_actions(&mut self, _cx: &mut Cx, actions: &Actions) {
+        let grid = self.ui.data_grid(id!(grid));
+        if let Some(edit) = grid.cell_edited(actions) {
+            EDITS.with( | e | e.borrow_mut().push(edit));
+        }
+        if grid.selection_changed(actions) {
+            SELECTION.with( | s | *s.borrow_mut() = grid.selection());
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+// the middle of a cell, the header and the rows are 20 high
+fn cell(row: usize, x: f64) -> DVec2 {
+    dvec2(x, 20.0 + row as f64 * 20.0 + 10.0)
+}
+
+fn click(cx: &mut Cx, pos: DVec2, modifiers: KeyModifiers) {
+    cx.headless_mouse_move(pos, modifiers);
+    cx.headless_mouse_down(pos, 0, modifiers);
+    cx.headless_mouse_up(pos, 0, modifiers);
+    cx.headless_step(1.0 / 60.0);
+}
+
+fn drag(cx: &mut Cx, from: DVec2, to: DVec2) {
+    cx.headless_mouse_move(from, KeyModifiers::default());
+    cx.headless_mouse_down(from, 0, KeyModifiers::default());
+    cx.headless_mouse_move(lerp(from, to, 0.5), KeyModifiers::default());
+    cx.headless_mouse_move(to, KeyModifiers::default());
+    cx.headless_mouse_up(to, 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+}
+
+fn lerp(a: DVec2, b: DVec2, t: f64) -> DVec2 {
+    a + (b - a) * t
+}
+
+fn copy(cx: &mut Cx) -> String {
+    cx.headless_copy();
+    cx.headless_clipboard().to_string()
+}
+
+#[test]
+fn data_grid_virtualizes_selects_sorts_and_edits() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+
+    // only the cells in view are read, not the million rows
+    let read = SOURCE.with( | s | s.borrow().cells_read.get());
+    assert!(read > 0 && read < 200, "{} cells were read", read);
+
+    let shift = KeyModifiers {shift: true, ..Default::default()};
+    click(&mut cx, cell(1, 50.0), KeyModifiers::default());
+    click(&mut cx, cell(2, 150.0), shift);
+    assert_eq!(copy(&mut cx), "1\tName 1\n2\tName 2\n");
+
+    common::press(&mut cx, KeyCode::ArrowDown, KeyModifiers::default());
+    assert_eq!(copy(&mut cx), "Name 3\n");
+    common::press(&mut cx, KeyCode::ArrowRight, shift);
+    assert_eq!(copy(&mut cx), "Name 3\tClosed\n");
+
+    // the first click sorts ascending, which is what the ids already are, the second descending,
+    // the selected rows are somewhere else after a sort so the selection is dropped
+    click(&mut cx, dvec2(50.0, 10.0), KeyModifiers::default());
+    assert_eq!(SELECTION.with( | s | s.borrow().clone()), None);
+    click(&mut cx, cell(3, 50.0), KeyModifiers::default());
+    click(&mut cx, dvec2(50.0, 10.0), KeyModifiers::default());
+    assert_eq!(SELECTION.with( | s | s.borrow().clone()), None);
+    click(&mut cx, cell(0, 50.0), KeyModifiers::default());
+    assert_eq!(copy(&mut cx), "999999\n");
+
+    // a double click edits the name in place, return commits it
+    cx.headless_step(1.0);
+    let pos = cell(0, 150.0);
+    cx.headless_mouse_down(pos, 0, KeyModifiers::default());
+    cx.headless_mouse_up(pos, 0, KeyModifiers::default());
+    cx.headless_mouse_down(pos, 0, KeyModifiers::default());
+    cx.headless_mouse_up(pos, 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_text_input("Ada");
+    common::press(&mut cx, KeyCode::ReturnKey, KeyModifiers::default());
+    assert_eq!(EDITS.with( | e | e.borrow().clone()), vec![(0, 1, "Ada".to_string())]);
+    assert_eq!(SOURCE.with( | s | s.borrow().edits.get(&(999999, 1)).cloned()), Some("Ada".to_string()));
+    // the grid has the focus again, and shows the new text
+    assert_eq!(copy(&mut cx), "Ada\n");
+
+    // dragging the edge of the first header makes it wider
+    drag(&mut cx, dvec2(119.0, 10.0), dvec2(169.0, 10.0));
+    click(&mut cx, cell(0, 150.0), KeyModifiers::default());
+    assert_eq!(copy(&mut cx), "999999\n");
+
+    // dragging the name header to the front reorders the columns
+    drag(&mut cx, dvec2(230.0, 10.0), dvec2(10.0, 10.0));
+    click(&mut cx, cell(1, 50.0), KeyModifiers::default());
+    assert_eq!(copy(&mut cx), "Name 999998\n");
+}
+
+#[test]
+fn select_all_and_drop_down_editing() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+
+    // select all starts from the first cell, wherever the cursor was
+    click(&mut cx, cell(2, 150.0), KeyModifiers::default());
+    common::press(&mut cx, KeyCode::KeyA, KeyModifiers {control: true, ..Default::default()});
+    assert_eq!(SELECTION.with( | s | s.borrow().clone()), Some(DataGridSelection {rows: 0..ROWS, columns: vec![0, 1, 2, 3]}));
+
+    // F2 opens the drop down on the status, picking another label commits it
+    click(&mut cx, cell(0, 250.0), KeyModifiers::default());
+    common::press(&mut cx, KeyCode::F2, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    common::press(&mut cx, KeyCode::ArrowDown, KeyModifiers::default());
+    assert_eq!(EDITS.with( | e | e.borrow().clone()), vec![(0, 2, "Closed".to_string())]);
+    assert_eq!(copy(&mut cx), "Closed\n");
+}
+
+#[test]
+fn columns_are_virtualized_like_rows() {
+    SOURCE.with( | s | s.borrow_mut().extra_columns = 10_000);
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    let read = SOURCE.with( | s | s.borrow().cells_read.get());
+    assert!(read > 0 && read < 200, "{} cells were read", read);
+
+    // End scrolls all the way right, which draws a few more columns and not the ones in between
+    click(&mut cx, cell(0, 150.0), KeyModifiers::default());
+    common::press(&mut cx, KeyCode::End, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    let read = SOURCE.with( | s | s.borrow().cells_read.get()) - read;
+    assert!(read < 1000, "{} cells were read", read);
+    assert_eq!(copy(&mut cx), "0:10003\n");
+}
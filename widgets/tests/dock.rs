@@ -0,0 +1,153 @@
+use {
+    makepad_widgets::{*, makepad_platform::makepad_micro_serde::*},
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 300)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    dock = <Dock>{
+                        width: Fill,
+                        height: Fill,
+                        root = Tabs{
+                            tabs: [alpha, beta],
+                            selected: 0,
+                            closable: false
+                        }
+                        alpha = Tab{name: "Alpha", template: PermanentTab, kind: Page}
+                        beta = Tab{name: "Beta", template: PermanentTab, kind: Page}
+                        Page = <View>{width: Fill, height: Fill}
+                    }
+                }
+            }
+        }
+    }
+}
+
+enum Command {
+    StorePreset(LiveId),
+    SwitchPreset(LiveId),
+    SetLayout(DockLayout),
+}
+
+thread_local! {
+    static COMMAND: RefCell<Option<Command>> = const {RefCell::new(None)};
+    static LAYOUT: RefCell<Option<DockLayout>> = const {RefCell::new(None)};
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let dock = self.ui.dock(id!(dock));
+        if let Event::Signal = event {
+            match COMMAND.with( | c | c.borrow_mut().take()) {
+                Some(Command::StorePreset(name)) => dock.store_preset(cx, name),
+                Some(Command::SwitchPreset(name)) => assert!(dock.switch_preset(cx, name)),
+                Some(Command::SetLayout(layout)) => dock.set_layout(cx, layout),
+                None => ()
+            }
+        }
+        LAYOUT.with( | l | *l.borrow_mut() = dock.layout(cx));
+    }
+}
+
+fn command(cx: &mut Cx, command: Command) {
+    COMMAND.with( | c | *c.borrow_mut() = Some(command));
+    SignalToUI::set_ui_signal();
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+}
+
+fn layout() -> DockLayout {
+    LAYOUT.with( | l | l.borrow().clone().unwrap())
+}
+
+fn tabs(layout: &DockLayout, tabs_id: LiveId) -> Vec<LiveId> {
+    match layout.items.get(&tabs_id) {
+        Some(DockItem::Tabs {tabs, ..}) => tabs.clone(),
+        _ => panic!("{} is not a tabs item", tabs_id)
+    }
+}
+
+// mouse positions are on the screen, the floating windows sit next to the main one
+fn drag(cx: &mut Cx, from: DVec2, to: DVec2) {
+    cx.headless_mouse_move(from, KeyModifiers::default());
+    cx.headless_mouse_down(from, 0, KeyModifiers::default());
+    for i in 1..=4 {
+        cx.headless_mouse_move(from + (to - from) * (i as f64 / 4.0), KeyModifiers::default());
+    }
+    cx.headless_mouse_up(to, 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+}
+
+#[test]
+fn tabs_tear_off_redock_and_layouts_restore() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    assert!(layout().windows.is_empty());
+
+    // dropping a tab outside of every window opens it in a window of its own
+    drag(&mut cx, dvec2(20.0, 10.0), dvec2(600.0, 100.0));
+    let framebuffers = cx.headless_framebuffers();
+    assert_eq!(framebuffers.len(), 2);
+    // the dock draws the tab into the new window
+    assert_eq!((framebuffers[1].1.width, framebuffers[1].1.height), (640, 480));
+    let torn = layout();
+    assert_eq!(tabs(&torn, live_id!(root)), vec![live_id!(beta)]);
+    assert_eq!(torn.windows.len(), 1);
+    let window = &torn.windows[0];
+    assert_eq!((window.x, window.y, window.width, window.height), (600.0, 100.0, 640.0, 480.0));
+    assert_eq!(tabs(&torn, window.root), vec![live_id!(alpha)]);
+
+    // the layout survives both serializations, windows included
+    let ron = DockLayout::deserialize_ron(&torn.serialize_ron()).unwrap();
+    let json = DockLayout::deserialize_json(&torn.serialize_json()).unwrap();
+    for restored in [ron, json] {
+        assert_eq!(restored.items.len(), torn.items.len());
+        assert_eq!(tabs(&restored, window.root), vec![live_id!(alpha)]);
+        assert_eq!((restored.windows[0].x, restored.windows[0].width), (600.0, 640.0));
+    }
+
+    // presets swap the whole layout, the dsl one is there as default
+    command(&mut cx, Command::StorePreset(live_id!(torn)));
+    command(&mut cx, Command::SwitchPreset(live_id!(default)));
+    assert!(layout().windows.is_empty());
+    assert_eq!(tabs(&layout(), live_id!(root)), vec![live_id!(alpha), live_id!(beta)]);
+    assert_eq!(cx.headless_framebuffers().len(), 1);
+    command(&mut cx, Command::SwitchPreset(live_id!(torn)));
+    assert_eq!(layout().windows.len(), 1);
+    assert_eq!(cx.headless_framebuffers().len(), 2);
+
+    // dragging the tab back onto the dock closes its window
+    drag(&mut cx, dvec2(620.0, 110.0), dvec2(200.0, 150.0));
+    let docked = layout();
+    assert!(docked.windows.is_empty());
+    assert_eq!(tabs(&docked, live_id!(root)), vec![live_id!(beta), live_id!(alpha)]);
+    assert_eq!(cx.headless_framebuffers().len(), 1);
+
+    // and a saved layout brings it back out
+    command(&mut cx, Command::SetLayout(torn));
+    assert_eq!(layout().windows.len(), 1);
+    assert_eq!(cx.headless_framebuffers().len(), 2);
+}
@@ -0,0 +1,120 @@
+use {
+    makepad_widgets::*,
+    makepad_widgets::makepad_draw::font_atlas::{CxFontsAtlasRc, CxShapeCache, Direction},
+    std::{cell::RefCell, path::PathBuf},
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        plain: {
+            font: {path: dep("crate://self/resources/IBMPlexSans-Text.ttf")}
+        }
+        with_fallbacks: {
+            font: {path: dep("crate://self/resources/IBMPlexSans-Text.ttf")}
+            fallbacks: [
+                {path: dep("crate://self/resources/LiberationMono-Regular.ttf")},
+                {path: dep("crate://self/resources/NotoSans-Italic.ttf")},
+            ]
+        }
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 200)},
+                body = <View>{
+                    width: Fit,
+                    height: Fit,
+                    label = <Label>{
+                        text: "a─ɐ⇒"
+                        draw_text: {text_style: {
+                            fallbacks: [{path: dep("crate://self/resources/LiberationMono-Regular.ttf")}]
+                        }}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+    #[live] plain: TextStyle,
+    #[live] with_fallbacks: TextStyle,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<(TextStyle, TextStyle, Rect)>> = const {RefCell::new(None)};
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let state = (self.plain.clone(), self.with_fallbacks.clone(), self.ui.widget(id!(body)).area().rect(cx));
+        STATE.with( | s | *s.borrow_mut() = Some(state));
+    }
+}
+
+// (font file, glyph id, cluster) for each glyph
+fn shape(cx: &mut Cx, style: &TextStyle, text: &str) -> Vec<(String, usize, usize)> {
+    let atlas = cx.get_global::<CxFontsAtlasRc>().clone();
+    let mut atlas = atlas.0.borrow_mut();
+    let glyphs = CxShapeCache::new().shape(false, Direction::LeftToRight, text, &style.font_ids(), &mut atlas).to_vec();
+    glyphs.into_iter().map( | glyph | {
+        let path = atlas.font_id_to_path[&glyph.font_id].to_string();
+        let file = path.rsplit('/').next().unwrap().to_string();
+        (file, glyph.glyph_id, glyph.cluster)
+    }).collect()
+}
+
+fn files(glyphs: &[(String, usize, usize)]) -> Vec<&str> {
+    glyphs.iter().map( | (file, _, _) | file.as_str()).collect()
+}
+
+#[test]
+fn glyphs_fall_back_per_cluster() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let (plain, with_fallbacks, body) = STATE.with( | s | s.borrow().clone()).unwrap();
+
+    // each character comes from the first font in the list that has it, the rest stay tofu
+    let glyphs = shape(&mut cx, &with_fallbacks, "a─ɐ⇒");
+    assert_eq!(files(&glyphs), ["IBMPlexSans-Text.ttf", "LiberationMono-Regular.ttf", "NotoSans-Italic.ttf", "IBMPlexSans-Text.ttf"]);
+    assert_eq!(glyphs.iter().map( | g | g.2).collect::<Vec<_>>(), [0, 1, 4, 6]);
+    assert!(glyphs[..3].iter().all( | g | g.1 != 0));
+    assert_eq!(glyphs[3].1, 0);
+    let glyphs = shape(&mut cx, &plain, "a─");
+    assert_eq!(glyphs[1].1, 0);
+
+    // a mark is taken from the same font as the character it sits on
+    let glyphs = shape(&mut cx, &with_fallbacks, "xʃ\u{301}y");
+    assert_eq!(files(&glyphs), ["IBMPlexSans-Text.ttf", "NotoSans-Italic.ttf", "NotoSans-Italic.ttf", "IBMPlexSans-Text.ttf"]);
+    assert_eq!(glyphs.iter().map( | g | g.2).collect::<Vec<_>>(), [0, 1, 1, 5]);
+
+    // with system fonts on, characters none of the style's fonts have are looked up by coverage
+    let resources = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/resources"));
+    let mut system_fonts = SystemFonts::scan_dirs(std::slice::from_ref(&resources));
+    assert!(system_fonts.path(0).starts_with(&resources));
+    let liberation = system_fonts.font_for_char('♠').unwrap();
+    assert!(system_fonts.path(liberation).ends_with("LiberationMono-Regular.ttf"));
+    assert_eq!(system_fonts.font_for_char('\u{10FFFD}'), None);
+
+    Cx2d::set_system_fonts(&mut cx, Some(system_fonts));
+    let glyphs = shape(&mut cx, &plain, "a♠\u{10FFFD}");
+    assert_eq!(files(&glyphs), ["IBMPlexSans-Text.ttf", "LiberationMono-Regular.ttf", "IBMPlexSans-Text.ttf"]);
+    assert_eq!((glyphs[1].1 != 0, glyphs[2].1), (true, 0));
+    Cx2d::set_system_fonts(&mut cx, None);
+    assert_eq!(shape(&mut cx, &plain, "♠")[0].1, 0);
+
+    // drawing text that mixes fonts lays it out on one line
+    assert!(body.size.x > 20.0 && body.size.y < 30.0, "{:?}", body);
+}
@@ -0,0 +1,223 @@
+use {
+    makepad_widgets::*,
+    std::{cell::RefCell, sync::Arc},
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 400)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    flow: Down,
+                    padding: 10,
+                    spacing: 10,
+                    amount = <NumberInput>{
+                        min: 0, max: 100000,
+                        step: 0.5,
+                        precision: 1,
+                        locale: "de_DE"
+                    }
+                    day = <DatePicker>{
+                        min: "2024-02-05"
+                    }
+                    alarm = <TimePicker>{
+                        minute_step: 15
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct State {
+    amount: Option<f64>,
+    day: Option<String>,
+    alarm: Option<String>,
+    amount_text: String,
+    day_text: String,
+    alarm_text: String,
+    rects: Vec<Rect>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+    #[rust(DataBindingStore::new())] store: DataBindingStore,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl App {
+    fn data_bind(mut db: DataBindingMap) {
+        db.bind(id!(amount), ids!(amount));
+        db.bind(id!(day), ids!(day));
+        db.bind(id!(alarm), ids!(alarm));
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_startup(&mut self, cx: &mut Cx) {
+        self.store.nodes.write_field_value(id!(amount), LiveValue::Float64(1234.5));
+        self.store.nodes.write_field_value(id!(day), LiveValue::String(Arc::new("2024-02-10".to_string())));
+        self.store.nodes.write_field_value(id!(alarm), LiveValue::String(Arc::new("07:00".to_string())));
+        Self::data_bind(self.store.data_to_widgets(cx, &self.ui));
+    }
+
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions) {
+        self.store.data_bind(cx, actions, &self.ui, Self::data_bind);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let nodes = &self.store.nodes;
+        let state = State {
+            amount: nodes.read_field_value(id!(amount)).and_then( | v | v.as_float()),
+            day: nodes.read_field_value(id!(day)).and_then( | v | v.as_str().map( | s | s.to_string())),
+            alarm: nodes.read_field_value(id!(alarm)).and_then( | v | v.as_str().map( | s | s.to_string())),
+            amount_text: self.ui.widget(id!(amount)).text(),
+            day_text: self.ui.widget(id!(day)).text(),
+            alarm_text: self.ui.widget(id!(alarm)).text(),
+            rects: [id!(amount), id!(day), id!(alarm)].into_iter().map( | id | self.ui.widget(id).area().rect(cx)).collect(),
+        };
+        STATE.with( | s | *s.borrow_mut() = state);
+    }
+}
+
+fn state() -> State {
+    STATE.with( | s | s.borrow().clone())
+}
+
+const SHIFT: KeyModifiers = KeyModifiers {shift: true, control: false, alt: false, logo: false};
+const CTRL: KeyModifiers = KeyModifiers {control: true, shift: false, alt: false, logo: false};
+
+fn type_text(cx: &mut Cx, text: &str) {
+    common::press(cx, KeyCode::KeyA, CTRL);
+    cx.headless_text_input(text);
+    cx.headless_step(1.0 / 60.0);
+    common::press(cx, KeyCode::ReturnKey, KeyModifiers::default());
+}
+
+#[test]
+fn dates_times_and_numbers_parse_and_step() {
+    let de = NumberFormat::for_locale("de_DE.UTF-8");
+    assert_eq!(de, NumberFormat {decimal: ',', group: Some('.')});
+    assert_eq!(de.format(-1234567.891, 2), "-1.234.567,89");
+    assert_eq!(de.parse("1.234,5"), Some(1234.5));
+    assert_eq!(NumberFormat::for_locale("fr").format(12345.0, 0), "12\u{a0}345");
+    assert_eq!(NumberFormat::for_locale("en_US").parse("1,234.5"), Some(1234.5));
+    assert_eq!(NumberFormat::for_locale("C").format(-0.01, 1), "0.0");
+    assert_eq!(NumberFormat::for_locale("en").parse("12abc"), None);
+
+    let leap = CalendarDate::new(2024, 2, 29).unwrap();
+    assert_eq!(CalendarDate::new(2023, 2, 29), None);
+    assert_eq!(CalendarDate::from_days(0).to_string(), "1970-01-01");
+    assert_eq!(CalendarDate::from_days(leap.to_days()), leap);
+    assert_eq!(leap.weekday(), 4);
+    assert_eq!(leap.add_days(1).to_string(), "2024-03-01");
+    assert_eq!(leap.add_months(12).to_string(), "2025-02-28");
+    assert_eq!(CalendarDate::new(2024, 1, 31).unwrap().add_months(-2).to_string(), "2023-11-30");
+    assert_eq!(CalendarDate::parse("1999-12-31"), CalendarDate::new(1999, 12, 31));
+    assert_eq!(CalendarDate::parse("1999-13-01"), None);
+
+    assert_eq!(TimeOfDay::parse("9:30 pm"), TimeOfDay::new(21, 30));
+    assert_eq!(TimeOfDay::parse("12am"), TimeOfDay::new(0, 0));
+    assert_eq!(TimeOfDay::parse("0745"), TimeOfDay::new(7, 45));
+    assert_eq!(TimeOfDay::parse("24:00"), None);
+    assert_eq!(TimeOfDay::new(23, 50).unwrap().add_minutes(15).to_string(), "00:05");
+    assert_eq!(TimeOfDay::new(13, 5).unwrap().format(false), "1:05 PM");
+}
+
+#[test]
+fn inputs_edit_and_bind_to_data() {
+    let mut cx = common::headless_app::<App>(live_design);
+    // nothing here looks at pixels
+    cx.headless_set_painting(false);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+
+    // the store fills in the widgets on startup
+    let s = state();
+    assert_eq!((s.amount_text.as_str(), s.day_text.as_str(), s.alarm_text.as_str()), ("1.234,5", "2024-02-10", "07:00"));
+    let (amount, day, alarm) = (s.rects[0], s.rects[1], s.rects[2]);
+
+    // typed numbers are read in the input's locale, the arrow keys step them
+    cx.headless_click(amount.pos + dvec2(20.0, amount.size.y * 0.5));
+    type_text(&mut cx, "2.500,5");
+    assert_eq!(state().amount, Some(2500.5));
+    common::press(&mut cx, KeyCode::ArrowUp, KeyModifiers::default());
+    assert_eq!(state().amount, Some(2501.0));
+    common::press(&mut cx, KeyCode::ArrowDown, SHIFT);
+    assert_eq!(state().amount, Some(2496.0));
+    assert_eq!(state().amount_text, "2.496,0");
+    type_text(&mut cx, "-7");
+    assert_eq!(state().amount, Some(0.0));
+
+    // dragging the spinner scrubs a step per 4 pixels, clicking it steps once
+    let spinner = dvec2(amount.pos.x + amount.size.x - 8.0, amount.pos.y + amount.size.y * 0.5);
+    cx.headless_mouse_down(spinner, 0, KeyModifiers::default());
+    cx.headless_mouse_move(spinner + dvec2(20.0, 0.0), KeyModifiers::default());
+    cx.headless_mouse_up(spinner + dvec2(20.0, 0.0), 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(state().amount, Some(2.5));
+    cx.headless_click(spinner - dvec2(0.0, 4.0));
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(state().amount, Some(3.0));
+
+    // the calendar opens on the picked month, weeks start on monday, so february 2024 leads with three days
+    let cell = | col: f64, row: f64 | dvec2(day.pos.x + 3.0 + col * 28.0 + 14.0, day.pos.y + day.size.y + 3.0 + 48.0 + row * 24.0 + 12.0);
+    cx.headless_click(day.pos + dvec2(20.0, day.size.y * 0.5));
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_click(cell(2.0, 2.0));
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(state().day.as_deref(), Some("2024-02-14"));
+
+    // days before the minimum can't be picked, clicking outside closes the calendar
+    cx.headless_click(day.pos + dvec2(20.0, day.size.y * 0.5));
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_click(cell(6.0, 0.0));
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(state().day.as_deref(), Some("2024-02-14"));
+    cx.headless_click(dvec2(390.0, 390.0));
+    cx.headless_step(1.0 / 60.0);
+
+    // with the keyboard the arrows move through the grid and return picks
+    cx.headless_click(day.pos + dvec2(20.0, day.size.y * 0.5));
+    cx.headless_step(1.0 / 60.0);
+    common::press(&mut cx, KeyCode::ArrowDown, KeyModifiers::default());
+    common::press(&mut cx, KeyCode::PageDown, KeyModifiers::default());
+    common::press(&mut cx, KeyCode::ArrowLeft, KeyModifiers::default());
+    common::press(&mut cx, KeyCode::ReturnKey, KeyModifiers::default());
+    assert_eq!(state().day.as_deref(), Some("2024-03-20"));
+    common::press(&mut cx, KeyCode::ArrowUp, KeyModifiers::default());
+    assert_eq!(state().day_text, "2024-03-19");
+
+    // times are typed in any common form, the arrows step the minutes to whole steps
+    cx.headless_click(alarm.pos + dvec2(20.0, alarm.size.y * 0.5));
+    type_text(&mut cx, "9:37 pm");
+    assert_eq!(state().alarm.as_deref(), Some("21:37"));
+    common::press(&mut cx, KeyCode::ArrowUp, KeyModifiers::default());
+    assert_eq!(state().alarm.as_deref(), Some("21:45"));
+    common::press(&mut cx, KeyCode::ArrowUp, KeyModifiers::default());
+    assert_eq!(state().alarm.as_deref(), Some("22:00"));
+    type_text(&mut cx, "nonsense");
+    assert_eq!(state().alarm.as_deref(), Some("22:00"));
+}
@@ -0,0 +1,88 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(500, 300)},
+                body = <View>{
+                    flow: Down,
+                    grid = <View>{
+                        width: 400,
+                        height: Fit,
+                        padding: 10,
+                        flow: Grid,
+                        align: {x: 0.5, y: 0.5},
+                        grid: {columns: [100, Fr(1.0), Fit], column_gap: 10, row_gap: 5},
+                        a = <View>{width: 50, height: 20}
+                        b = <View>{width: Fill, height: 30}
+                        c = <View>{width: 60, height: 10}
+                        d = <View>{width: 150, height: 20, cell: {column_span: 2}}
+                        e = <View>{width: 40, height: 40, cell: {column: 2, row: 2, align: {x: 1.0, y: 0.0}}}
+                        f = <View>{width: 10, height: 10}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+thread_local! {
+    static RECTS: RefCell<Vec<Rect>> = const {RefCell::new(Vec::new())};
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let rects = [id!(grid), id!(a), id!(b), id!(c), id!(d), id!(e), id!(f)].iter().map( | id | {
+            self.ui.view(*id).area().rect(cx)
+        }).collect();
+        RECTS.with( | r | *r.borrow_mut() = rects);
+    }
+}
+
+#[test]
+fn grid_tracks_spans_and_cell_alignment() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let rects = RECTS.with( | r | r.borrow().clone());
+    let grid = rects[0];
+    let at = | i: usize | {
+        let rect = rects[i];
+        (rect.pos.x - grid.pos.x, rect.pos.y - grid.pos.y, rect.size.x, rect.size.y)
+    };
+
+    // columns are 100, what is left, and the widest child in the last one: 60
+    // rows fit their tallest children: 30, 20 and 40, and the grid fits its rows
+    assert_eq!(grid.size, dvec2(400.0, 10.0 + 30.0 + 5.0 + 20.0 + 5.0 + 40.0 + 10.0));
+    // children are centered in their cells
+    assert_eq!(at(1), (35.0, 15.0, 50.0, 20.0));
+    // a filling child gets the fractional column
+    assert_eq!(at(2), (120.0, 10.0, 200.0, 30.0));
+    assert_eq!(at(3), (330.0, 20.0, 60.0, 10.0));
+    // a child spanning two columns wraps to the next row and centers across both
+    assert_eq!(at(4), (90.0, 45.0, 150.0, 20.0));
+    // an explicit cell with its own alignment
+    assert_eq!(at(5), (350.0, 70.0, 40.0, 40.0));
+    // the next child takes the cell left free after the span
+    assert_eq!(at(6), (355.0, 50.0, 10.0, 10.0));
+}
@@ -0,0 +1,126 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(64, 48)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    padding: 8,
+                    show_bg: true,
+                    draw_bg: {color: #00f}
+                    panel = <View>{
+                        width: Fill,
+                        height: Fill,
+                        show_bg: true,
+                        cursor: Hand,
+                        draw_bg: {color: #f00}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+thread_local! {
+    static HTTP_ERRORS: RefCell<Vec<LiveId>> = const {RefCell::new(Vec::new())};
+}
+
+impl MatchEvent for App {
+    fn handle_http_request_error(&mut self, _cx: &mut Cx, request_id: LiveId, _err: &HttpError) {
+        HTTP_ERRORS.with( | e | e.borrow_mut().push(request_id));
+    }
+
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions) {
+        if self.ui.view(id!(panel)).finger_down(actions).is_some() {
+            self.ui.view(id!(panel)).apply_over(cx, live!{
+                draw_bg: {color: (vec4(0.0, 1.0, 0.0, 1.0))}
+            });
+            self.ui.redraw(cx);
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+fn assert_color(image: &HeadlessImage, x: usize, y: usize, color: Vec4) {
+    let pixel = image.pixel(x, y);
+    let diff = pixel - color;
+    assert!(diff.x.abs().max(diff.y.abs()).max(diff.z.abs()).max(diff.w.abs()) < 0.01, "pixel ({}, {}) is {:?}, expected {:?}", x, y, pixel, color);
+}
+
+#[test]
+fn clicking_a_view_repaints_the_framebuffer() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+
+    let window_id = cx.headless_framebuffers()[0].0;
+    let image = cx.headless_framebuffer(window_id).unwrap();
+    assert_eq!((image.width, image.height), (64, 48));
+    assert_color(image, 2, 2, vec4(0.0, 0.0, 1.0, 1.0));
+    assert_color(image, 32, 24, vec4(1.0, 0.0, 0.0, 1.0));
+
+    cx.headless_click(dvec2(32.0, 24.0));
+    cx.headless_step(1.0 / 60.0);
+
+    let image = cx.headless_framebuffer(window_id).unwrap();
+    assert_color(image, 2, 2, vec4(0.0, 0.0, 1.0, 1.0));
+    assert_color(image, 32, 24, vec4(0.0, 1.0, 0.0, 1.0));
+    assert_eq!(cx.headless_cursor(), MouseCursor::Hand);
+    assert_eq!(cx.headless_time(), 2.0 / 60.0);
+}
+
+#[test]
+fn unsupported_ops_answer_instead_of_hanging() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+
+    // there is no network, the request fails on the next step
+    cx.http_request(live_id!(fetch), HttpRequest::new("http://localhost/".to_string(), HttpMethod::GET));
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(HTTP_ERRORS.with( | e | e.borrow().clone()), vec![live_id!(fetch)]);
+
+    cx.show_text_ime(Area::Empty, dvec2(10.0, 20.0));
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(cx.headless_text_ime(), Some(dvec2(10.0, 20.0)));
+    cx.hide_text_ime();
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(cx.headless_text_ime(), None);
+}
+
+#[test]
+fn framebuffers_encode_as_png() {
+    let mut image = HeadlessImage::new(3, 2);
+    image.clear(vec4(1.0, 0.5, 0.0, 1.0));
+    let png = image.to_png();
+    assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]);
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    // the IEND chunk has a well known checksum
+    assert_eq!(&png[png.len() - 4..], &[0xae, 0x42, 0x60, 0x82]);
+}
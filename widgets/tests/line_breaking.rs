@@ -0,0 +1,114 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+
+    Wrapped = <View>{
+        width: Fit,
+        height: Fit,
+        label = <Label>{
+            width: 110,
+            draw_text: {wrap: Word, text_style: {font_size: 20}}
+        }
+    }
+
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 800)},
+                body = <View>{
+                    flow: Down,
+                    single = <Wrapped>{label = {text: "hyphen"}}
+                    cjk = <Wrapped>{label = {text: "中文文本换行测试中文文本换行测试"}}
+                    url = <Wrapped>{label = {text: "https://example.com/a/long/path"}}
+                    broken = <Wrapped>{label = {text: "hyphenation"}}
+                    hyphenated = <Wrapped>{label = {
+                        text: "hyphenation"
+                        draw_text: {text_style: {hyphenation: {path: dep("crate://self/tests/resources/hyphenation.pat")}}}
+                    }}
+                    soft = <Wrapped>{}
+                    soft_wide = <Wrapped>{label = {width: 300}}
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+/// The rect of every wrapper view, and the rects of the glyphs in it
+type Wrapped = (Rect, Vec<Rect>);
+
+thread_local! {
+    static STATE: RefCell<Vec<Wrapped>> = const {RefCell::new(Vec::new())};
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        // with a soft hyphen between the parts, which the DSL has no escape for
+        for id in [id!(soft.label), id!(soft_wide.label)] {
+            self.ui.label(id).set_text("hyphen\u{AD}ation");
+        }
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let ids = [id!(single), id!(cjk), id!(url), id!(broken), id!(hyphenated), id!(soft), id!(soft_wide)];
+        let state = ids.iter().map( | id | {
+            let view = self.ui.view(*id);
+            let area = view.widget(id!(label)).area();
+            let read = | id: LiveId | area.get_read_ref(cx, id, ShaderTy::Vec2).map( | r | {
+                (0..r.repeat).map( | i | dvec2(r.buffer[i * r.stride] as f64, r.buffer[i * r.stride + 1] as f64)).collect::<Vec<_>>()
+            }).unwrap_or_default();
+            let glyphs = read(live_id!(rect_pos)).into_iter().zip(read(live_id!(rect_size))).map( | (pos, size) | {
+                Rect {pos, size}
+            }).collect();
+            (view.area().rect(cx), glyphs)
+        }).collect();
+        STATE.with( | s | *s.borrow_mut() = state);
+    }
+}
+
+#[test]
+fn wrapping_breaks_hyphenates_and_keeps_graphemes() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let state = STATE.with( | s | s.borrow().clone());
+    // the first line is as high as one line of text, the others add the line spacing of 1.4
+    let line_height = state[0].0.size.y;
+    let lines = | i: usize | ((state[i].0.size.y - line_height) / (line_height * 1.4)).round() as usize + 1;
+    // glyphs are positioned by their bottom left corner and the padding of the atlas
+    let right = | i: usize | state[i].1.iter().map( | g | g.pos.x + g.size.x).fold(0.0, f64::max) - state[i].0.pos.x;
+
+    // ideographs can be broken between, and so can a url after its slashes
+    assert!(lines(1) >= 2, "{}", lines(1));
+    assert!(lines(2) >= 3, "{}", lines(2));
+    assert!(right(2) < 110.0 + 2.0, "{}", right(2));
+
+    // a word too long for a line is broken between graphemes, or hyphenated with a dictionary
+    assert_eq!((lines(3), state[3].1.len()), (2, 11));
+    assert_eq!((lines(4), state[4].1.len()), (2, 12));
+    // a soft hyphen shows as a hyphen only where the line breaks
+    assert_eq!(lines(6), 1);
+    assert_eq!(lines(5), 2);
+    assert_eq!(state[5].1.len(), state[6].1.len() + 1);
+
+    let hyphenator = Hyphenator::parse(include_str!("resources/hyphenation.pat"));
+    assert_eq!(hyphenator.hyphenate("hyphenation"), [2, 6]);
+    assert_eq!(hyphenator.hyphenate("(Hyphenation) TABLE"), [3, 7, 16]);
+    assert_eq!(hyphenator.hyphenate("table"), [2]);
+    assert!(hyphenator.hyphenate("hen").is_empty());
+}
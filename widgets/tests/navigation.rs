@@ -0,0 +1,135 @@
+use {
+    makepad_widgets::*,
+    std::sync::atomic::{AtomicUsize, Ordering},
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(300, 220), title: "Navigation"},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    flow: Down,
+                    padding: 10,
+                    spacing: 6,
+                    last_button = <Button>{text: "Last", nav_order: Bottom(0)}
+                    ok_button = <Button>{text: "OK"}
+                    agree = <CheckBox>{text: "Agree"}
+                    radio_a = <RadioButton>{text: "A"}
+                    radio_b = <RadioButton>{text: "B"}
+                    name_input = <TextInput>{width: 100, empty_message: "Name"}
+                    first_button = <Button>{text: "First", nav_order: Top(1)}
+                }
+            }
+        }
+    }
+}
+
+static CLICKS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_actions(&mut self, _cx: &mut Cx, actions: &Actions) {
+        if self.ui.button(id!(ok_button)).clicked(actions) {
+            CLICKS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+fn tab(cx: &mut Cx) {
+    common::press(cx, KeyCode::Tab, KeyModifiers::default());
+}
+
+fn shift_tab(cx: &mut Cx) {
+    common::press(cx, KeyCode::Tab, KeyModifiers {shift: true, ..Default::default()});
+}
+
+// the widgets are found through the accessibility tree, the focused one is the one under the key focus
+fn node(cx: &Cx, role: AccessRole, name: &str) -> AccessNode {
+    let window_id = cx.headless_framebuffers()[0].0;
+    cx.access_tree(window_id).unwrap().find_by_name(role, name).unwrap().clone()
+}
+
+fn focused_is(cx: &Cx, role: AccessRole, name: &str) -> bool {
+    let area = cx.key_focus();
+    area.is_valid(cx) && area.clipped_rect(cx) == node(cx, role, name).rect
+}
+
+#[test]
+fn tab_and_arrow_keys_reach_every_widget() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+
+    // Top(1) comes before all default stops, Bottom(0) after them
+    tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::Button, "First"));
+    tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::Button, "OK"));
+
+    // the focus ring is drawn just outside the focused button
+    let rect = node(&cx, AccessRole::Button, "OK").rect;
+    let window_id = cx.headless_framebuffers()[0].0;
+    let ring = cx.headless_framebuffer(window_id).unwrap().pixel(rect.pos.x as usize - 1, (rect.pos.y + rect.size.y * 0.5) as usize);
+    assert!(ring.x > 0.6 && ring.y < 0.5, "no focus ring, the pixel is {:?}", ring);
+
+    let clicks = CLICKS.load(Ordering::SeqCst);
+    common::press(&mut cx, KeyCode::Space, KeyModifiers::default());
+    assert_eq!(CLICKS.load(Ordering::SeqCst), clicks + 1);
+    common::press(&mut cx, KeyCode::ReturnKey, KeyModifiers::default());
+    assert_eq!(CLICKS.load(Ordering::SeqCst), clicks + 2);
+
+    tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::CheckBox, "Agree"));
+    common::press(&mut cx, KeyCode::Space, KeyModifiers::default());
+    assert_eq!(node(&cx, AccessRole::CheckBox, "Agree").states.checked, Some(true));
+
+    // the radio buttons are one stop, the arrow keys move within them
+    tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::RadioButton, "A"));
+    common::press(&mut cx, KeyCode::ArrowDown, KeyModifiers::default());
+    assert!(focused_is(&cx, AccessRole::RadioButton, "B"));
+    common::press(&mut cx, KeyCode::ArrowDown, KeyModifiers::default());
+    assert!(focused_is(&cx, AccessRole::RadioButton, "B"));
+    common::press(&mut cx, KeyCode::Space, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(node(&cx, AccessRole::RadioButton, "B").states.checked, Some(true));
+
+    tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::TextInput, "Name"));
+    tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::Button, "Last"));
+    tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::Button, "First"));
+
+    // shift-tab wraps around, and enters the radio group on the selected button
+    shift_tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::Button, "Last"));
+    shift_tab(&mut cx);
+    shift_tab(&mut cx);
+    assert!(focused_is(&cx, AccessRole::RadioButton, "B"));
+    common::press(&mut cx, KeyCode::ArrowUp, KeyModifiers::default());
+    assert!(focused_is(&cx, AccessRole::RadioButton, "A"));
+}
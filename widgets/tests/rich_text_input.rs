@@ -0,0 +1,196 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 300)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    editor = <RichTextInput>{
+                        height: Fill,
+                        body: "# Notes"
+                    }
+                }
+            }
+        }
+    }
+}
+
+thread_local! {
+    static MARKDOWN: RefCell<String> = const {RefCell::new(String::new())};
+    static CHANGES: RefCell<usize> = const {RefCell::new(0)};
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_actions(&mut self, _cx: &mut Cx, actions: &Actions) {
+        if self.ui.rich_text_input(id!(editor)).changed(actions) {
+            CHANGES.with( | c | *c.borrow_mut() += 1);
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let markdown = self.ui.rich_text_input(id!(editor)).to_markdown();
+        MARKDOWN.with( | m | *m.borrow_mut() = markdown);
+    }
+}
+
+fn markdown() -> String {
+    MARKDOWN.with( | m | m.borrow().clone())
+}
+
+const CTRL: KeyModifiers = KeyModifiers {control: true, shift: false, alt: false, logo: false};
+
+fn type_text(cx: &mut Cx, text: &str) {
+    cx.headless_text_input(text);
+    cx.headless_step(1.0 / 60.0);
+}
+
+fn bold(text: &str) -> RichSpan {
+    RichSpan::new(text, RichAttrs {bold: true, ..Default::default()})
+}
+
+fn plain(text: &str) -> RichSpan {
+    RichSpan::new(text, RichAttrs::default())
+}
+
+#[test]
+fn documents_edit_and_round_trip_through_markdown_and_html() {
+    let markdown = "# Title\n\nSome **bold**, *italic*, ***both*** and ~~gone~~ `code` and a [link](https://makepad.dev)\n\n- one\n- two\n\n1. first\n2. second\n\n> quoted\n\n```\nfn main() {\n    run()\n}\n```\n\nthe end";
+    let doc = RichDocument::from_markdown(markdown);
+    let kinds: Vec<RichBlockKind> = doc.blocks.iter().map( | block | block.kind).collect();
+    assert_eq!(kinds, vec![
+        RichBlockKind::Heading(1),
+        RichBlockKind::Paragraph,
+        RichBlockKind::Bullet,
+        RichBlockKind::Bullet,
+        RichBlockKind::Numbered,
+        RichBlockKind::Numbered,
+        RichBlockKind::Quote,
+        RichBlockKind::Code,
+        RichBlockKind::Code,
+        RichBlockKind::Code,
+        RichBlockKind::Paragraph,
+    ]);
+    let spans = &doc.blocks[1].spans;
+    assert_eq!(spans[1], bold("bold"));
+    assert_eq!(spans[5].attrs, RichAttrs {bold: true, italic: true, ..Default::default()});
+    assert!(spans[7].attrs.strikethrough && spans[9].attrs.code);
+    assert_eq!(spans[11].attrs.link.as_deref(), Some("https://makepad.dev"));
+    assert_eq!(doc.blocks[8].text(), "    run()");
+
+    // markdown comes back as it went in, and html keeps everything
+    assert_eq!(doc.to_markdown(), markdown);
+    assert_eq!(RichDocument::from_html(&doc.to_html()), doc);
+    let html = RichDocument::from_html("<p>Hello <b>big</b>\n  <u>wide</u> world</p>\n<ul><li>a</li><li>b<br>c</li></ul>");
+    assert_eq!(html.blocks[0].spans, vec![
+        plain("Hello "),
+        bold("big"),
+        plain(" "),
+        RichSpan::new("wide", RichAttrs {underline: true, ..Default::default()}),
+        plain(" world"),
+    ]);
+    assert_eq!(html.text(), "Hello big wide world\na\nb\nc");
+
+    // newlines split blocks, and deleting across blocks joins them
+    let mut doc = RichDocument::from_text("hello world");
+    let end = doc.insert(RichPos::new(0, 5), ",\nnew", &RichAttrs::default());
+    assert_eq!(end, RichPos::new(1, 3));
+    assert_eq!(doc.text(), "hello,\nnew world");
+    doc.set_attrs(RichPos::new(0, 2)..RichPos::new(1, 1), | attrs | attrs.bold = true);
+    assert_eq!(doc.blocks[0].spans, vec![plain("he"), bold("llo,")]);
+    assert_eq!(doc.blocks[1].spans, vec![bold("n"), plain("ew world")]);
+    assert!(doc.all_attrs(RichPos::new(0, 3)..RichPos::new(1, 1), | attrs | attrs.bold));
+    assert!(!doc.all_attrs(RichPos::new(0, 1)..RichPos::new(0, 3), | attrs | attrs.bold));
+    let slice = doc.slice(RichPos::new(0, 4)..RichPos::new(1, 2));
+    assert_eq!(slice.to_markdown(), "**o,**\n\n**n**e");
+    doc.delete(RichPos::new(0, 4)..RichPos::new(1, 2));
+    assert_eq!(doc.blocks.len(), 1);
+    assert_eq!(doc.blocks[0].spans, vec![plain("he"), bold("ll"), plain("w world")]);
+    let end = doc.insert_document(RichPos::new(0, 2), &slice);
+    assert_eq!(end, RichPos::new(1, 2));
+    assert_eq!(doc.text(), "heo,\nnellw world");
+}
+
+#[test]
+fn typing_formatting_undo_clipboard_and_ime() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(markdown(), "# Notes");
+
+    // clicking past the text puts the cursor at its end, return starts a paragraph after the heading
+    cx.headless_click(dvec2(300.0, 250.0));
+    common::press(&mut cx, KeyCode::ReturnKey, KeyModifiers::default());
+    type_text(&mut cx, "Hel");
+    type_text(&mut cx, "lo ");
+    common::press(&mut cx, KeyCode::KeyB, CTRL);
+    type_text(&mut cx, "world");
+    assert_eq!(markdown(), "# Notes\n\nHello **world**");
+
+    // the bold text is a group of its own, the plain text before it another one
+    common::press(&mut cx, KeyCode::KeyZ, CTRL);
+    assert_eq!(markdown(), "# Notes\n\nHello ");
+    common::press(&mut cx, KeyCode::KeyZ, CTRL);
+    assert_eq!(markdown(), "# Notes\n\n");
+    common::press(&mut cx, KeyCode::KeyZ, KeyModifiers {shift: true, ..CTRL});
+    common::press(&mut cx, KeyCode::KeyY, CTRL);
+    assert_eq!(markdown(), "# Notes\n\nHello **world**");
+
+    // shift+home selects the paragraph, copy puts it on the clipboard as markdown
+    common::press(&mut cx, KeyCode::Home, KeyModifiers {shift: true, ..Default::default()});
+    cx.headless_copy();
+    assert_eq!(cx.headless_clipboard(), "Hello **world**");
+
+    // pasting markdown over the selection keeps its styles, html is read as html
+    cx.headless_set_clipboard("<p>big <i>news</i></p><ul><li>item</li></ul>");
+    cx.headless_paste();
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(markdown(), "# Notes\n\nbig *news*\n\n- item");
+    common::press(&mut cx, KeyCode::KeyZ, CTRL);
+    assert_eq!(markdown(), "# Notes\n\nHello **world**");
+
+    // an input method replaces what it sent last while it composes
+    common::press(&mut cx, KeyCode::End, KeyModifiers::default());
+    common::press(&mut cx, KeyCode::KeyB, CTRL);
+    type_text(&mut cx, " n");
+    cx.headless_ime_input(" ni");
+    cx.headless_ime_input(" 你");
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(markdown(), "# Notes\n\nHello **world** 你");
+
+    // backspace at the start of a list item makes it a paragraph first
+    common::press(&mut cx, KeyCode::ReturnKey, KeyModifiers::default());
+    cx.headless_set_clipboard("- a");
+    cx.headless_paste();
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(markdown(), "# Notes\n\nHello **world** 你\n\n- a");
+    common::press(&mut cx, KeyCode::Home, KeyModifiers::default());
+    common::press(&mut cx, KeyCode::Backspace, KeyModifiers::default());
+    assert_eq!(markdown(), "# Notes\n\nHello **world** 你\n\na");
+    common::press(&mut cx, KeyCode::Backspace, KeyModifiers::default());
+    assert_eq!(markdown(), "# Notes\n\nHello **world** 你a");
+    assert!(CHANGES.with( | c | *c.borrow()) > 10);
+}
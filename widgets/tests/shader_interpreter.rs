@@ -0,0 +1,95 @@
+use makepad_widgets::*;
+use makepad_widgets::makepad_platform::makepad_shader_compiler::{
+    interpret::{ShaderInterpreter, InterpretInputs},
+    shader_ast::Ident,
+};
+
+mod common;
+
+const SIZE: usize = 16;
+
+// the unit quad of GeometryQuad2D
+const QUAD_GEOMETRY: [f32; 8] = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+const QUAD_INDICES: [u32; 6] = [0, 1, 2, 2, 3, 0];
+
+// what Pass::set_matrix uploads for a SIZE x SIZE pass
+fn set_pass_uniforms(interp: &ShaderInterpreter, inputs: &mut InterpretInputs) {
+    let ortho = Mat4::ortho(0.0, SIZE as f32, 0.0, SIZE as f32, 100.0, -100.0, 1.0, 1.0);
+    interp.set_uniform(inputs, Ident(live_id!(camera_projection)), &ortho.v);
+    interp.set_uniform(inputs, Ident(live_id!(camera_view)), &Mat4::identity().v);
+    interp.set_uniform(inputs, Ident(live_id!(view_transform)), &Mat4::identity().v);
+    interp.set_uniform(inputs, Ident(live_id!(dpi_factor)), &[1.0]);
+    interp.set_uniform(inputs, Ident(live_id!(dpi_dilate)), &[0.0]);
+}
+
+#[test]
+fn draw_color_renders_its_rect() {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_widgets::live_design(&mut cx);
+    cx.live_expand();
+    
+    let mut draw_color = DrawColor::new_local(&mut cx);
+    draw_color.color = vec4(1.0, 0.0, 0.0, 1.0);
+    draw_color.rect_pos = vec2(4.0, 2.0);
+    draw_color.rect_size = vec2(8.0, 6.0);
+    draw_color.draw_clip = vec4(-1e6, -1e6, 1e6, 1e6);
+    
+    let draw_shader = draw_color.draw_vars.draw_shader.unwrap();
+    let cx_shader = &cx.draw_shaders.shaders[draw_shader.draw_shader_id];
+    let draw_shader_def = cx.shader_registry.draw_shader_defs.get(&draw_shader.draw_shader_ptr).unwrap();
+    let interp = ShaderInterpreter::new(draw_shader_def, &cx_shader.mapping.const_table, &cx.shader_registry);
+    let mut inputs = interp.new_inputs();
+    set_pass_uniforms(&interp, &mut inputs);
+    
+    let mut target = vec![vec4(0.0, 0.0, 1.0, 1.0); SIZE * SIZE];
+    let mut shaded = 0;
+    interp.draw_triangles(&inputs, &QUAD_GEOMETRY, &QUAD_INDICES, draw_color.draw_vars.as_slice(), SIZE, SIZE, &mut | x, y, _depth, color | {
+        target[y * SIZE + x] = color;
+        shaded += 1;
+    }).unwrap();
+    
+    // every pixel is shaded once, the shared diagonal included
+    assert_eq!(shaded, 8 * 6);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let inside = (4..12).contains(&x) && (2..8).contains(&y);
+            let expected = if inside {vec4(1.0, 0.0, 0.0, 1.0)} else {vec4(0.0, 0.0, 1.0, 1.0)};
+            assert_eq!(target[y * SIZE + x], expected, "pixel {} {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn all_widget_shaders_interpret() {
+    let cx = common::instantiate_theme_widgets();
+    
+    let mut failures = Vec::new();
+    for draw_shader_ptr in &cx.draw_shaders.compile_set {
+        let item = cx.draw_shaders.ptr_to_item.get(draw_shader_ptr).unwrap();
+        let cx_shader = &cx.draw_shaders.shaders[item.draw_shader_id];
+        let draw_shader_def = cx.shader_registry.draw_shader_defs.get(draw_shader_ptr).unwrap();
+        let interp = ShaderInterpreter::new(draw_shader_def, &cx_shader.mapping.const_table, &cx.shader_registry);
+        
+        let mut inputs = interp.new_inputs();
+        set_pass_uniforms(&interp, &mut inputs);
+        inputs.live_uniforms = cx_shader.mapping.live_uniforms_buf.clone();
+        
+        let mut instance = vec![0.0; interp.instance_slots()];
+        interp.set_instance(&mut instance, Ident(live_id!(rect_pos)), &[2.0, 2.0]);
+        interp.set_instance(&mut instance, Ident(live_id!(rect_size)), &[12.0, 12.0]);
+        interp.set_instance(&mut instance, Ident(live_id!(draw_clip)), &[-1e6, -1e6, 1e6, 1e6]);
+        interp.set_instance(&mut instance, Ident(live_id!(draw_depth)), &[1.0]);
+        
+        let geometries: Vec<f32> = QUAD_GEOMETRY.chunks(2).flat_map( | v | {
+            let mut slots = v.to_vec();
+            slots.resize(interp.geometry_slots(), 0.0);
+            slots
+        }).collect();
+        if let Err(err) = interp.draw_triangles(&inputs, &geometries, &QUAD_INDICES, &instance, SIZE, SIZE, &mut | _, _, _, _ | {}) {
+            failures.push(format!("{:?}: {}", item.draw_shader_id, err.message));
+        }
+    }
+    if !failures.is_empty() {
+        panic!("{} of {} shaders failed to interpret:\n{}", failures.len(), cx.draw_shaders.compile_set.len(), failures.join("\n"));
+    }
+}
@@ -0,0 +1,96 @@
+use {
+    makepad_widgets::*,
+    makepad_widgets::makepad_draw::{
+        icon_atlas::CxIconAtlasRc,
+        makepad_vector::geometry::{AffineTransformation, Vector},
+    },
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 200)},
+                body = <View>{
+                    badge = <Svg>{source: dep("crate://self/tests/resources/badge.svg")}
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+thread_local! {
+    static BADGE: RefCell<Option<Rect>> = const {RefCell::new(None)};
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let badge = self.ui.widget(id!(badge)).area().rect(cx);
+        BADGE.with( | b | *b.borrow_mut() = Some(badge));
+    }
+}
+
+const BADGE_SVG: &str = include_str!("resources/badge.svg");
+
+fn rgba(pixel: u32) -> [u32; 4] {
+    [pixel >> 16 & 0xff, pixel >> 8 & 0xff, pixel & 0xff, pixel >> 24]
+}
+
+#[test]
+fn svg_documents_are_painted_with_gradients_clips_and_strokes() {
+    let document = SvgDocument::parse(BADGE_SVG).unwrap();
+    let view_box = document.view_box.unwrap();
+    assert_eq!((view_box.pos, view_box.size), (dvec2(0.0, 0.0), dvec2(200.0, 100.0)));
+    let bounds = document.bounds().unwrap();
+    assert_eq!((bounds.pos, bounds.size), (dvec2(0.0, 0.0), dvec2(200.0, 100.0)));
+
+    // at half size: the gradient runs red to blue over the top half, the green group only
+    // shows left of the clip, the circle is half transparent and the line has round caps
+    let pixels = document.rasterize(AffineTransformation::scaling(Vector::new(0.5, 0.5)), 100, 50);
+    let at = | x: usize, y: usize | rgba(pixels[y * 100 + x]);
+    let [r, g, b, a] = at(5, 12);
+    assert!(a == 255 && r > 200 && g == 0 && b < 40, "{:?}", (r, g, b, a));
+    let [r, g, b, a] = at(95, 12);
+    assert!(a == 255 && b > 200 && g == 0 && r < 40, "{:?}", (r, g, b, a));
+    assert_eq!(at(25, 37), [0, 255, 0, 255]);
+    assert_eq!(at(60, 37), [0, 0, 0, 0]);
+    let [r, g, b, a] = at(75, 37);
+    assert!((120..=135).contains(&a) && r == 0 && g == 0 && b == 0, "{:?}", (r, g, b, a));
+    assert_eq!(at(8, 45), [0, 0, 255, 255]);
+    assert_eq!(at(5, 45), [0, 255, 0, 255]);
+
+    // the widget is as big as the view box and paints the document in its own colors into the
+    // color page of the icon atlas
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let badge = BADGE.with( | b | b.borrow_mut().take()).unwrap();
+    // plus the pixel icons are padded with for subpixel positioning
+    assert_eq!(badge.size, dvec2(201.0, 101.0));
+    let atlas = cx.get_global::<CxIconAtlasRc>().clone();
+    let atlas = atlas.0.borrow();
+    assert!(atlas.color_alloc.xpos > 0.0);
+    let pixels = atlas.texture_color.take_vec_u32(&mut cx);
+    let painted: Vec<[u32; 4]> = pixels.iter().copied().map(rgba).filter( | c | c[3] == 255).collect();
+    assert!(painted.iter().any( | c | c[0] > 200 && c[2] < 40));
+    assert!(painted.iter().any( | c | c[1] == 255 && c[0] == 0 && c[2] == 0));
+    assert!(painted.iter().any( | c | c[2] == 255 && c[0] == 0 && c[1] == 0));
+    atlas.texture_color.put_back_vec_u32(&mut cx, pixels, None);
+}
@@ -0,0 +1,175 @@
+use {
+    makepad_widgets::*,
+    makepad_widgets::makepad_draw::font_atlas::{CxFontAtlas, CxFontsAtlasRc},
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        family: {
+            font_family: [
+                {font: {path: dep("crate://self/resources/IBMPlexSans-Text.ttf")}},
+                {font: {path: dep("crate://self/resources/IBMPlexSans-SemiBold.ttf")}, weight: 600},
+                {font: {path: dep("crate://self/resources/IBMPlexSans-Italic.ttf")}, style: Italic},
+                {font: {path: dep("crate://self/resources/IBMPlexSans-BoldItalic.ttf")}, weight: 700, style: Italic},
+            ]
+        }
+        bold_italic: <THEME_FONT_BOLD_ITALIC> {}
+        variable: {
+            font: {path: dep("crate://self/../libs/ttf-parser/benches/fonts/SourceSansVariable-Roman.ttf")}
+        }
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 300)},
+                body = <View>{
+                    flow: Down,
+                    plain = <View>{width: Fit, height: Fit, label = <Label>{
+                        text: "abc"
+                        draw_text: {text_style: {font_size: 20}}
+                    }}
+                    spaced = <View>{width: Fit, height: Fit, label = <Label>{
+                        text: "abc"
+                        draw_text: {text_style: {font_size: 20, letter_spacing: 2.0}}
+                    }}
+                    decorated = <View>{width: Fit, height: Fit, label = <Label>{
+                        text: "abc"
+                        draw_text: {
+                            color: #fff
+                            text_style: {
+                                font_size: 20,
+                                underline: true,
+                                strikethrough: true,
+                                decoration_color: #f00,
+                                decoration_thickness: 3.0
+                            }
+                        }
+                    }}
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+    #[live] family: TextStyle,
+    #[live] bold_italic: TextStyle,
+    #[live] variable: TextStyle,
+}
+
+/// The rects of the labels, and for each instance of the decorated label whether it is a
+/// decoration, its rect and its color
+struct State {
+    family: TextStyle,
+    bold_italic: TextStyle,
+    variable: TextStyle,
+    rects: Vec<Rect>,
+    instances: Vec<(bool, Rect, Vec4)>,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<State>> = const {RefCell::new(None)};
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let rects = [id!(plain), id!(spaced), id!(decorated)].iter().map( | id | {
+            self.ui.view(*id).area().rect(cx)
+        }).collect();
+        let area = self.ui.widget(id!(decorated.label)).area();
+        // the first `n` floats of an instance field, for every instance
+        let read = | id: LiveId, ty: ShaderTy, n: usize | area.get_read_ref(cx, id, ty).map( | r | {
+            (0..r.repeat).map( | i | r.buffer[i * r.stride..][..n].to_vec()).collect::<Vec<_>>()
+        }).unwrap_or_default();
+        let is_decoration = read(live_id!(is_decoration), ShaderTy::Float, 1);
+        let pos = read(live_id!(rect_pos), ShaderTy::Vec2, 2);
+        let size = read(live_id!(rect_size), ShaderTy::Vec2, 2);
+        let color = read(live_id!(color), ShaderTy::Vec4, 4);
+        let instances = (0..is_decoration.len()).map( | i | (
+            is_decoration[i][0] > 0.5,
+            Rect {pos: dvec2(pos[i][0] as f64, pos[i][1] as f64), size: dvec2(size[i][0] as f64, size[i][1] as f64)},
+            vec4(color[i][0], color[i][1], color[i][2], color[i][3]),
+        )).collect();
+        STATE.with( | s | *s.borrow_mut() = Some(State {
+            family: self.family.clone(),
+            bold_italic: self.bold_italic.clone(),
+            variable: self.variable.clone(),
+            rects,
+            instances,
+        }));
+    }
+}
+
+#[test]
+fn decorations_letter_spacing_and_font_selection() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let State {mut family, bold_italic, mut variable, rects, instances} = STATE.with( | s | s.borrow_mut().take()).unwrap();
+
+    // every character is followed by the letter spacing
+    assert!((rects[1].size.x - rects[0].size.x - 3.0 * 2.0).abs() < 0.001);
+
+    // an underline and a strikethrough across the whole text, over the glyphs, in their own color
+    let glyphs: Vec<_> = instances.iter().filter( | i | !i.0).collect();
+    let lines: Vec<_> = instances.iter().filter( | i | i.0).collect();
+    assert_eq!((glyphs.len(), lines.len()), (3, 2));
+    assert!(instances[..3].iter().all( | i | !i.0));
+    for (_, rect, color) in &lines {
+        assert_eq!((rect.pos.x, rect.size.y), (rects[2].pos.x, 3.0));
+        assert!((rect.size.x - rects[2].size.x).abs() < 0.001);
+        assert_eq!(*color, vec4(1.0, 0.0, 0.0, 1.0));
+    }
+    // the underline is below the strikethrough, and both are inside the line, quads are
+    // positioned by their bottom edge
+    assert!(lines[0].1.pos.y > lines[1].1.pos.y + 3.0);
+    assert!(lines[1].1.pos.y > rects[2].pos.y && lines[0].1.pos.y < rects[2].pos.y + rects[2].size.y);
+    assert_eq!(glyphs[0].2, vec4(1.0, 1.0, 1.0, 1.0));
+
+    let atlas = cx.get_global::<CxFontsAtlasRc>().clone();
+    let mut atlas = atlas.0.borrow_mut();
+    let file = | style: &TextStyle | {
+        let path = atlas.font_id_to_path[&style.font_id().unwrap()].to_string();
+        path.rsplit('/').next().unwrap().to_string()
+    };
+
+    // the face of a family closest in weight is picked, in the requested style if there is one
+    assert_eq!(file(&family), "IBMPlexSans-Text.ttf");
+    for (weight, style, expected) in [
+        (600.0, FontStyle::Normal, "IBMPlexSans-SemiBold.ttf"),
+        (900.0, FontStyle::Normal, "IBMPlexSans-SemiBold.ttf"),
+        (300.0, FontStyle::Normal, "IBMPlexSans-Text.ttf"),
+        (500.0, FontStyle::Italic, "IBMPlexSans-Italic.ttf"),
+        (600.0, FontStyle::Italic, "IBMPlexSans-BoldItalic.ttf"),
+    ] {
+        family.font_weight = weight;
+        family.font_style = style;
+        assert_eq!(file(&family), expected, "{} {:?}", weight, style);
+    }
+    // the theme's styles pick from its family the same way
+    assert_eq!(file(&bold_italic), "IBMPlexSans-BoldItalic.ttf");
+
+    // a variable font is instanced on its weight axis, and bolder glyphs are wider
+    let regular = variable.resolve_font_ids(&mut atlas)[0];
+    variable.font_weight = 700.0;
+    let bold = variable.resolve_font_ids(&mut atlas)[0];
+    assert_ne!(bold, regular);
+    assert!(atlas.font_id_to_path[&bold].ends_with("SourceSansVariable-Roman.ttf#wght=700"));
+    assert_eq!(variable.resolve_font_ids(&mut atlas)[0], bold);
+    let advance = | atlas: &mut CxFontAtlas, font_id: usize | {
+        atlas.fonts[font_id].as_mut().unwrap().get_advance_width_for_char('a').unwrap()
+    };
+    assert!(advance(&mut atlas, bold) > advance(&mut atlas, regular));
+}
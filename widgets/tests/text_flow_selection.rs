@@ -0,0 +1,84 @@
+use makepad_widgets::*;
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 300)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    flow: Down,
+                    doc = <Markdown>{
+                        width: Fill,
+                        height: 150,
+                        copy_format: Markdown,
+                        body: "# Title\n\nSome **bold** and `code`.\n\n- one\n- two"
+                    }
+                    page = <Html>{
+                        width: Fill,
+                        height: Fit,
+                        body: "Hello <b>big</b> world<br>second line"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+fn drag(cx: &mut Cx, from: DVec2, to: DVec2) {
+    cx.headless_mouse_move(from, KeyModifiers::default());
+    cx.headless_mouse_down(from, 0, KeyModifiers::default());
+    cx.headless_mouse_move(to, KeyModifiers::default());
+    cx.headless_mouse_up(to, 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+}
+
+fn copy(cx: &mut Cx) -> String {
+    cx.headless_copy();
+    cx.headless_clipboard().to_string()
+}
+
+#[test]
+fn selecting_and_copying_rich_text() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+
+    // the html sits right below the markdown, a drag past its end selects all of it
+    drag(&mut cx, dvec2(1.0, 152.0), dvec2(399.0, 299.0));
+    assert_eq!(copy(&mut cx), "Hello big world\nsecond line");
+
+    // a double click selects the word under the mouse, after a pause so the drag doesn't count as its first click
+    cx.headless_step(1.0);
+    cx.headless_click(dvec2(3.0, 160.0));
+    cx.headless_click(dvec2(3.0, 160.0));
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(copy(&mut cx), "Hello");
+
+    // clicking the markdown moves the key focus, which drops the html selection
+    cx.headless_click(dvec2(3.0, 10.0));
+    common::press(&mut cx, KeyCode::KeyA, KeyModifiers {control: true, ..Default::default()});
+    // the text flow only knows a heading by its bold font
+    assert_eq!(copy(&mut cx), "**Title**\n\nSome **bold** and `code`.\n\n- one\n- two");
+}
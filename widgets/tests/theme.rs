@@ -0,0 +1,134 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ocean_theme: {
+            THEME_COLOR_BG_APP: #204060
+            THEME_CORNER_RADIUS: 6.0
+        }
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(64, 48)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    show_bg: true,
+                    draw_bg: {color: (THEME_COLOR_BG_APP)}
+                    // swatches of colours that are derived from the palette or set per mode
+                    <View>{width: 8, height: 8, show_bg: true, draw_bg: {color: (THEME_COLOR_CAPTION_ICON)}}
+                    <View>{width: 8, height: 8, show_bg: true, draw_bg: {color: (THEME_COLOR_TEXT_LINK)}}
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Command {
+    Mode(ThemeMode),
+    Ocean(bool),
+}
+
+thread_local! {
+    static COMMAND: RefCell<Option<Command>> = const {RefCell::new(None)};
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ocean_theme: Option<LivePtr>,
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        if let Event::Signal = event {
+            match COMMAND.with( | c | c.borrow_mut().take()) {
+                Some(Command::Mode(mode)) => cx.set_theme_mode(mode),
+                Some(Command::Ocean(on)) => cx.set_theme_overrides(if on {self.ocean_theme} else {None}),
+                None => ()
+            }
+        }
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+fn command(cx: &mut Cx, command: Command) {
+    COMMAND.with( | c | *c.borrow_mut() = Some(command));
+    SignalToUI::set_ui_signal();
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+}
+
+fn background(cx: &Cx) -> Vec4 {
+    let (_, image) = cx.headless_framebuffers()[0];
+    image.pixel(32, 24)
+}
+
+fn assert_swatches(cx: &Cx, icon: f32, link: u32) {
+    let (_, image) = cx.headless_framebuffers()[0];
+    let pixel = image.pixel(4, 4);
+    assert!((pixel.x - icon).abs() < 0.02 && (pixel.z - icon).abs() < 0.02, "caption icon is {:?}, expected {}", pixel, icon);
+    let pixel = image.pixel(12, 4);
+    let expected = vec4((link >> 16) as f32, (link >> 8 & 0xff) as f32, (link & 0xff) as f32, 255.0) / 255.0;
+    let diff = pixel - expected;
+    assert!(diff.x.abs().max(diff.y.abs()).max(diff.z.abs()) < 0.02, "link is {:?}, expected {:06x}", pixel, link);
+}
+
+fn assert_grey(cx: &Cx, level: f32) {
+    let pixel = background(cx);
+    assert!((pixel.x - level).abs() < 0.02 && pixel.x == pixel.y && pixel.y == pixel.z, "background is {:?}, expected {}", pixel, level);
+}
+
+#[test]
+fn switching_the_theme_reapplies_every_widget() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(cx.theme_mode(), ThemeMode::Dark);
+    assert_grey(&cx, 0.3);
+    assert_swatches(&cx, 1.0, 0x4a90e2);
+
+    command(&mut cx, Command::Mode(ThemeMode::Light));
+    assert_eq!(cx.theme_mode(), ThemeMode::Light);
+    assert_grey(&cx, 0.93);
+    // the caption buttons draw dark on the light caption bar
+    assert_swatches(&cx, 0.0, 0x1f5fad);
+
+    command(&mut cx, Command::Mode(ThemeMode::HighContrast));
+    assert!(cx.dark_mode() && cx.high_contrast());
+    assert!(background(&cx).x < 0.06);
+    assert_swatches(&cx, 1.0, 0x9ccbff);
+
+    // following the OS waits for it to report a preference, then tracks its changes
+    command(&mut cx, Command::Mode(ThemeMode::System));
+    assert_grey(&cx, 0.3);
+    cx.headless_set_system_dark_mode(false);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    assert_eq!(cx.system_dark_mode(), Some(false));
+    assert_grey(&cx, 0.93);
+    cx.headless_set_system_dark_mode(true);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    assert_grey(&cx, 0.3);
+
+    // an app theme only replaces what it sets, and goes away again
+    command(&mut cx, Command::Ocean(true));
+    let pixel = background(&cx);
+    let diff = pixel - vec4(0x20 as f32 / 255.0, 0x40 as f32 / 255.0, 0x60 as f32 / 255.0, 1.0);
+    assert!(diff.x.abs().max(diff.y.abs()).max(diff.z.abs()) < 0.02, "background is {:?}", pixel);
+    command(&mut cx, Command::Ocean(false));
+    assert_grey(&cx, 0.3);
+}
@@ -0,0 +1,179 @@
+use {
+    makepad_widgets::*,
+    makepad_widgets::makepad_platform::makepad_shader_compiler::{
+        interpret::ShaderInterpreter,
+        shader_ast::Ident,
+    },
+    makepad_widgets::makepad_draw::{
+        stroke::{flatten, stroke_polygons},
+        makepad_vector::path::PathCommand,
+    },
+    std::{cell::RefCell, f64::consts::PI},
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(400, 300)},
+                body = <View>{
+                    padding: 10,
+                    canvas = <VectorCanvas>{width: 200, height: 100}
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Drawn {
+    size: DVec2,
+    filled: usize,
+    stroked: usize,
+    ramp: Option<Texture>,
+    disc: Area,
+    draw_shader: Option<(usize, DrawShaderPtr)>,
+}
+
+thread_local! {
+    static DRAWN: RefCell<Option<Drawn>> = const {RefCell::new(None)};
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+fn instance_count(area: Area) -> usize {
+    match area {
+        Area::Instance(inst) => inst.instance_count,
+        _ => 0
+    }
+}
+
+impl MatchEvent for App {
+    fn handle_draw_2d(&mut self, cx: &mut Cx2d) {
+        while let Some(item) = self.ui.draw(cx, &mut Scope::empty()).step() {
+            if let Some(mut canvas) = item.as_vector_canvas().borrow_mut() {
+                let mut drawn = Drawn {size: canvas.size(), ..Default::default()};
+                let mut rect = VectorPath::new();
+                rect.rect(Rect {pos: dvec2(0.0, 0.0), size: canvas.size()});
+                canvas.fill(cx, &rect, VectorPaint::LinearGradient {
+                    start: dvec2(0.0, 0.0),
+                    end: dvec2(200.0, 0.0),
+                    stops: vec![(0.0, vec4(1.0, 0.0, 0.0, 1.0)), (1.0, vec4(0.0, 0.0, 1.0, 1.0))],
+                }, FillRule::NonZero);
+                drawn.filled = instance_count(canvas.draw_vector.draw_vars.area);
+
+                let mut circle = VectorPath::new();
+                circle.circle(dvec2(100.0, 50.0), 40.0);
+                canvas.stroke(cx, &circle, vec4(1.0, 1.0, 1.0, 1.0), &StrokeStyle::new(4.0).with_dash(vec![8.0, 4.0], 0.0));
+                drawn.stroked = instance_count(canvas.draw_vector.draw_vars.area);
+                drawn.ramp = canvas.draw_vector.ramp.clone();
+
+                let mut disc = VectorPath::new();
+                disc.circle(dvec2(30.0, 30.0), 20.0);
+                canvas.fill(cx, &disc, vec4(0.0, 1.0, 0.0, 1.0), FillRule::NonZero);
+                drawn.disc = canvas.draw_vector.draw_vars.area;
+                drawn.draw_shader = canvas.draw_vector.draw_vars.draw_shader.map( | s | (s.draw_shader_id, s.draw_shader_ptr));
+                DRAWN.with( | d | *d.borrow_mut() = Some(drawn));
+            }
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        if self.match_event_with_draw_2d(cx, event).is_ok() {
+            return
+        }
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+#[test]
+fn paths_are_stroked_dashed_and_filled_on_the_canvas() {
+    // a 100 long line in dashes of 10 with gaps of 5 has 7 dashes
+    let mut line = VectorPath::new();
+    line.move_to(dvec2(0.0, 0.0)).line_to(dvec2(100.0, 0.0));
+    let contours = flatten(line.commands(), 0.1);
+    let dashed = stroke_polygons(&contours, &StrokeStyle::new(2.0).with_dash(vec![10.0, 5.0], 0.0), 0.1);
+    assert_eq!(dashed.len(), 7);
+
+    // a half circle is split into quarter arcs
+    let mut arc = VectorPath::new();
+    arc.arc(dvec2(0.0, 0.0), 10.0, 0.0, PI);
+    assert_eq!(arc.commands().len(), 3);
+    assert!(matches!(arc.commands()[2], PathCommand::ArcTo(p, _, _, false, true) if (p.x + 10.0).abs() < 1e-9 && p.y.abs() < 1e-9));
+
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let drawn = DRAWN.with( | d | d.borrow_mut().take()).unwrap();
+    assert_eq!(drawn.size, dvec2(200.0, 100.0));
+    // the rectangle is a single trapezoid, the dashed circle many more
+    assert_eq!(drawn.filled, 1);
+    assert!(drawn.stroked > 20, "{}", drawn.stroked);
+
+    // the gradient got a row of the ramp texture running from red to blue
+    let ramp = drawn.ramp.unwrap();
+    let pixels = ramp.take_vec_u32(&mut cx);
+    assert_eq!(pixels[0], 0xffff0000);
+    assert_eq!(pixels[255], 0xff0000ff);
+    assert_eq!(pixels[128] >> 24, 0xff);
+    ramp.put_back_vec_u32(&mut cx, pixels, None);
+
+    // the disc is cut into many trapezoids side by side, painted over each other they cover its
+    // inside fully, without seams where they meet
+    const SIZE: usize = 80;
+    let Area::Instance(disc) = drawn.disc else {panic!()};
+    let (draw_shader_id, draw_shader_ptr) = drawn.draw_shader.unwrap();
+    let cx_shader = &cx.draw_shaders.shaders[draw_shader_id];
+    let draw_shader_def = cx.shader_registry.draw_shader_defs.get(&draw_shader_ptr).unwrap();
+    let interp = ShaderInterpreter::new(draw_shader_def, &cx_shader.mapping.const_table, &cx.shader_registry);
+    let mut inputs = interp.new_inputs();
+    let ortho = Mat4::ortho(0.0, SIZE as f32, 0.0, SIZE as f32, 100.0, -100.0, 1.0, 1.0);
+    interp.set_uniform(&mut inputs, Ident(live_id!(camera_projection)), &ortho.v);
+    interp.set_uniform(&mut inputs, Ident(live_id!(camera_view)), &Mat4::identity().v);
+    interp.set_uniform(&mut inputs, Ident(live_id!(view_transform)), &Mat4::identity().v);
+    interp.set_uniform(&mut inputs, Ident(live_id!(dpi_factor)), &[1.0]);
+    inputs.live_uniforms = cx_shader.mapping.live_uniforms_buf.clone();
+
+    let slots = interp.instance_slots();
+    let draw_item = &cx.draw_lists[disc.draw_list_id].draw_items[disc.draw_item_id];
+    let instances = &draw_item.instances.as_ref().unwrap()[disc.instance_offset..][..disc.instance_count * slots];
+    assert!(disc.instance_count > 10, "{}", disc.instance_count);
+    let mut alpha = vec![0.0f32; SIZE * SIZE];
+    for instance in instances.chunks_exact(slots) {
+        interp.draw_triangles(&inputs, &[0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0], &[0, 1, 2, 2, 3, 0], instance, SIZE, SIZE, &mut | x, y, _, color | {
+            let a = &mut alpha[y * SIZE + x];
+            *a = color.w + *a * (1.0 - color.w);
+        }).unwrap();
+    }
+    // the canvas starts at the padding of its view
+    let center = dvec2(40.0, 40.0);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let distance = (dvec2(x as f64 + 0.5, y as f64 + 0.5) - center).length();
+            let a = alpha[y * SIZE + x];
+            if distance < 18.5 {
+                assert!(a > 0.999, "pixel {} {} has {}", x, y, a);
+            }
+            if distance > 21.5 {
+                assert_eq!(a, 0.0, "pixel {} {}", x, y);
+            }
+        }
+    }
+    let area: f32 = alpha.iter().sum();
+    assert!((area - 400.0 * PI as f32).abs() < 10.0, "{}", area);
+}
@@ -0,0 +1,173 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(240, 200)},
+                body = <View>{
+                    viewport = <Viewport3D>{
+                        width: 160,
+                        height: 120,
+                        source: dep("crate://self/tests/resources/scene.glb"),
+                        clear_color: #204060
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<(Rect, OrbitCamera)>> = const {RefCell::new(None)};
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let viewport = self.ui.widget(id!(viewport));
+        let camera = viewport.borrow::<Viewport3D>().unwrap().camera.clone();
+        STATE.with( | s | *s.borrow_mut() = Some((viewport.area().rect(cx), camera)));
+    }
+}
+
+fn state() -> (Rect, OrbitCamera) {
+    STATE.with( | s | s.borrow().clone()).unwrap()
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+// where a point in the scene shows up in the window
+fn project(rect: Rect, camera: &OrbitCamera, p: Vec3) -> (usize, usize) {
+    let projection = Mat4::perspective(camera.fov_y, (rect.size.x / rect.size.y) as f32, camera.near, camera.far);
+    let clip = projection.transform_vec4(camera.view_matrix().transform_vec4(vec4(p.x, p.y, p.z, 1.0)));
+    let x = rect.pos.x + (clip.x / clip.w * 0.5 + 0.5) as f64 * rect.size.x;
+    let y = rect.pos.y + (0.5 - clip.y / clip.w * 0.5) as f64 * rect.size.y;
+    (x as usize, y as usize)
+}
+
+#[test]
+fn gltf_scenes_load_with_their_transforms_and_materials() {
+    // a glb with a cube scaled by its parent node, and a transparent quad turned around
+    let scene = Scene3d::parse_gltf(include_bytes!("resources/scene.glb")).unwrap();
+    assert_eq!(scene.meshes.len(), 2);
+    let (min, max) = scene.bounds().unwrap();
+    assert!(close(min.x, -1.0) && close(min.y, -1.0) && close(min.z, -1.0), "{:?}", min);
+    assert!(close(max.x, 1.0) && close(max.y, 1.0) && close(max.z, 1.5), "{:?}", max);
+    let (cube, glass) = (&scene.meshes[0], &scene.meshes[1]);
+    assert_eq!((cube.indices.len(), glass.indices.len()), (36, 6));
+    assert_eq!(cube.material.base_color, vec4(1.0, 0.0, 0.0, 1.0));
+    assert_eq!((cube.material.metallic, cube.material.roughness, cube.material.is_transparent), (0.0, 0.5, false));
+    assert!(glass.material.is_transparent);
+    let (min, max) = glass.bounds().unwrap();
+    assert!(close(min.x, 0.2) && close(max.x, 0.8) && close(min.z, 1.5), "{:?} {:?}", min, max);
+    // the normals are turned with the quad, to face +z
+    assert!(glass.vertices.chunks(9).all( | v | close(v[6], 1.0)));
+
+    // a gltf with an embedded buffer and no scenes, its triangle gets a flat normal
+    let scene = Scene3d::parse_gltf(include_bytes!("resources/triangle.gltf")).unwrap();
+    assert_eq!(scene.meshes.len(), 1);
+    let triangle = &scene.meshes[0];
+    assert_eq!(triangle.indices, [0, 1, 2]);
+    let vertices: Vec<_> = triangle.vertices.chunks(9).collect();
+    assert_eq!(vertices[1], [1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0]);
+    // a masked material is opaque where it isn't cut away, metallic and rough by default
+    let material = &triangle.material;
+    assert_eq!(material.base_color, vec4(0.2, 0.4, 0.6, 1.0));
+    assert_eq!(material.emissive, vec3(0.5, 0.25, 0.0));
+    assert_eq!((material.metallic, material.roughness), (1.0, 1.0));
+
+    assert!(Scene3d::parse_gltf(b"glTF\x01\0\0\0").is_err());
+    assert!(Scene3d::parse_gltf(br#"{"buffers": [{"uri": "scene.bin", "byteLength": 4}]}"#).is_err());
+
+    // malformed files are errors rather than panics or allocations that fail
+    let malformed = | accessors: &str | {
+        let json = format!(r#"{{
+            "nodes": [{{"mesh": 0}}],
+            "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0, "TEXCOORD_0": 1}}}}]}}],
+            "accessors": [{}]
+        }}"#, accessors);
+        Scene3d::parse_gltf(json.as_bytes()).err()
+    };
+    assert!(malformed(r#"{"componentType": 5126, "count": 3, "type": "VEC3"}, {"componentType": 5126, "count": 3, "type": "VEC2"}"#).is_none());
+    assert_eq!(
+        malformed(r#"{"componentType": 5126, "count": 3, "type": "VEC3"}, {"componentType": 5126, "count": 2, "type": "VEC2"}"#).as_deref(),
+        Some("gltf NORMAL/TEXCOORD_0 count differs from POSITION")
+    );
+    assert_eq!(
+        malformed(r#"{"componentType": 5126, "count": 1e18, "type": "VEC3"}, {"componentType": 5126, "count": 3, "type": "VEC2"}"#).as_deref(),
+        Some("gltf accessor count is too large")
+    );
+}
+
+#[test]
+fn viewport_renders_and_orbits_the_scene() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let (rect, camera) = state();
+    assert_eq!(rect.size, dvec2(160.0, 120.0));
+    // the camera is fitted to the scene, and looks at its center
+    assert!(close(camera.target.z, 0.25), "{:?}", camera.target);
+    assert!(camera.distance > 4.0 && camera.distance < 6.0, "{}", camera.distance);
+
+    let window_id = cx.headless_framebuffers()[0].0;
+    let pixel = | cx: &Cx, (x, y): (usize, usize) | cx.headless_framebuffer(window_id).unwrap().pixel(x, y);
+    let clear = vec4(0x20 as f32, 0x40 as f32, 0x60 as f32, 255.0) / 255.0;
+    let near = | a: Vec4, b: Vec4 | (a - b).dot(a - b) < 0.02 * 0.02;
+
+    // the red cube, lit, in front of the clear color
+    let center = project(rect, &camera, vec3(-0.5, 0.0, 1.0));
+    let lit = pixel(&cx, center);
+    assert!(lit.x > 0.3 && lit.y < 0.1 && lit.z < 0.1, "{:?}", lit);
+    assert!(near(pixel(&cx, (rect.pos.x as usize + 2, rect.pos.y as usize + 2)), clear));
+    // the glass blends blue over the cube behind it
+    let glass = pixel(&cx, project(rect, &camera, vec3(0.5, 0.0, 1.5)));
+    assert!(glass.z > 0.2 && glass.x > 0.1 && glass.x < lit.x, "{:?}", glass);
+    // above the scene there is nothing
+    assert!(near(pixel(&cx, project(rect, &camera, vec3(0.0, 2.0, 0.0))), clear));
+
+    let before = cx.headless_framebuffer(window_id).unwrap().clone();
+
+    // dragging to the right turns the scene to the right, the camera to the left
+    let from = dvec2(rect.pos.x + 80.0, rect.pos.y + 60.0);
+    cx.headless_mouse_down(from, 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_mouse_move(from + dvec2(20.0, 0.0), KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    let (_, dragged) = state();
+    assert!(close(dragged.yaw, camera.yaw - 10.0), "{}", dragged.yaw);
+    assert!(close(dragged.pitch, camera.pitch));
+    cx.headless_mouse_move(from + dvec2(20.0, 20.0), KeyModifiers::default());
+    cx.headless_mouse_up(from + dvec2(20.0, 20.0), 0, KeyModifiers::default());
+    cx.headless_step(1.0 / 60.0);
+    let (_, dragged) = state();
+    assert!(dragged.pitch > camera.pitch, "{}", dragged.pitch);
+    assert_ne!(cx.headless_framebuffer(window_id).unwrap(), &before);
+
+    // scrolling down moves the camera away
+    cx.headless_scroll(from, dvec2(0.0, 100.0), true);
+    cx.headless_step(1.0 / 60.0);
+    let (_, zoomed) = state();
+    assert!(close(zoomed.distance, dragged.distance * 0.2f32.exp()), "{}", zoomed.distance);
+}
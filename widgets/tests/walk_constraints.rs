@@ -0,0 +1,117 @@
+use {
+    makepad_widgets::*,
+    std::cell::RefCell,
+};
+
+mod common;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(500, 500)},
+                body = <View>{
+                    flow: Down,
+                    weighted = <View>{
+                        width: 400,
+                        height: 20,
+                        flow: Right,
+                        a = <View>{width: Fill, height: Fill}
+                        b = <View>{width: Fill, height: Fill, flex: 3.0}
+                        c = <View>{width: Fill, height: Fill, width_max: 40}
+                    }
+                    bounded = <View>{
+                        width: 400,
+                        height: 20,
+                        flow: Right,
+                        d = <View>{width: Fill, height: Fill, width_min: 300}
+                        e = <View>{width: Fill, height: Fill}
+                    }
+                    column = <View>{
+                        width: 100,
+                        height: 200,
+                        flow: Down,
+                        f = <View>{width: Fill, height: Fill, height_max: 50}
+                        g = <View>{width: Fill, height: 30}
+                        h = <View>{width: Fill, height: Fill}
+                    }
+                    ratio = <View>{width: 200, height: Fit, aspect_ratio: 2.0}
+                    fit = <View>{
+                        width: Fit,
+                        height: Fit,
+                        width_min: 120,
+                        height_max: 10,
+                        <View>{width: 50, height: 20}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+thread_local! {
+    static RECTS: RefCell<Vec<Rect>> = const {RefCell::new(Vec::new())};
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+        let ids = [
+            id!(weighted), id!(a), id!(b), id!(c),
+            id!(bounded), id!(d), id!(e),
+            id!(column), id!(f), id!(g), id!(h),
+            id!(ratio), id!(fit)
+        ];
+        let rects = ids.iter().map( | id | self.ui.view(*id).area().rect(cx)).collect();
+        RECTS.with( | r | *r.borrow_mut() = rects);
+    }
+}
+
+#[test]
+fn flex_bounds_and_aspect_ratio() {
+    let mut cx = common::headless_app::<App>(live_design);
+    cx.headless_step(1.0 / 60.0);
+    cx.headless_step(1.0 / 60.0);
+    let rects = RECTS.with( | r | r.borrow().clone());
+    // the offset and length along the flow of a child in its parent
+    let along_x = | parent: usize, i: usize | (rects[i].pos.x - rects[parent].pos.x, rects[i].size.x);
+    let along_y = | parent: usize, i: usize | (rects[i].pos.y - rects[parent].pos.y, rects[i].size.y);
+
+    // 1:3:1 would give c 80, so it keeps its maximum and the others share the rest 1:3
+    assert_eq!(along_x(0, 1), (0.0, 90.0));
+    assert_eq!(along_x(0, 2), (90.0, 270.0));
+    assert_eq!(along_x(0, 3), (360.0, 40.0));
+
+    // an even split would give d 200, below its minimum
+    assert_eq!(along_x(4, 5), (0.0, 300.0));
+    assert_eq!(along_x(4, 6), (300.0, 100.0));
+
+    // bounds apply to fills around other children in a downward flow
+    assert_eq!(along_y(7, 8), (0.0, 50.0));
+    assert_eq!(along_y(7, 9), (50.0, 30.0));
+    assert_eq!(along_y(7, 10), (80.0, 120.0));
+
+    // a fitting height follows the width
+    assert_eq!(rects[11].size, dvec2(200.0, 100.0));
+    // a fitting view is clamped around its content
+    assert_eq!(rects[12].size, dvec2(120.0, 10.0));
+
+    let walk = Walk::fixed(300.0, 100.0).with_aspect_ratio(1.0);
+    assert_eq!(walk.constrain_size(dvec2(300.0, 100.0)), dvec2(100.0, 100.0));
+    let walk = Walk::fit().with_width_bounds(Some(50.0), Some(20.0));
+    assert_eq!(walk.clamp_size(dvec2(10.0, f64::NAN)).x, 50.0);
+    assert!(walk.clamp_size(dvec2(10.0, f64::NAN)).y.is_nan());
+}
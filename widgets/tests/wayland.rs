@@ -0,0 +1,140 @@
+#![cfg(target_os = "linux")]
+
+use {
+    makepad_widgets::*,
+    std::{
+        cell::RefCell,
+        path::PathBuf,
+        process::{Child, Command, Stdio},
+        rc::Rc,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    },
+};
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+    App = {{App}} {
+        ui: <Root>{
+            main_window = <Window>{
+                window: {inner_size: vec2(320, 240)},
+                body = <View>{
+                    width: Fill,
+                    height: Fill,
+                    show_bg: true,
+                    draw_bg: {color: #00f}
+                }
+            }
+        }
+    }
+}
+
+static FRAMES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Live, LiveHook)]
+pub struct App {
+    #[live] ui: WidgetRef,
+}
+
+impl LiveRegister for App {
+    fn live_register(cx: &mut Cx) {
+        makepad_widgets::live_design(cx);
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        match event {
+            Event::Startup => {
+                cx.new_next_frame();
+            }
+            Event::NextFrame(_) => {
+                // keep redrawing so every frame goes through the compositor
+                if FRAMES.fetch_add(1, Ordering::SeqCst) >= 10 {
+                    cx.quit();
+                }
+                else {
+                    self.ui.redraw(cx);
+                    cx.new_next_frame();
+                }
+            }
+            _ => ()
+        }
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+// a weston instance with the headless backend, rendering through software EGL
+struct HeadlessWeston {
+    child: Child,
+    runtime_dir: PathBuf,
+}
+
+impl HeadlessWeston {
+    const SOCKET: &'static str = "makepad-test-0";
+
+    fn start() -> Option<HeadlessWeston> {
+        if Command::new("weston").arg("--version").stdout(Stdio::null()).stderr(Stdio::null()).status().is_err() {
+            return None
+        }
+        let runtime_dir = std::env::temp_dir().join(format!("makepad-wayland-{}", std::process::id()));
+        std::fs::create_dir_all(&runtime_dir).ok()?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&runtime_dir, std::fs::Permissions::from_mode(0o700)).ok()?;
+        }
+        let child = Command::new("weston")
+            .arg("--backend=headless-backend.so")
+            .arg(format!("--socket={}", Self::SOCKET))
+            .arg("--idle-time=0")
+            .env("XDG_RUNTIME_DIR", &runtime_dir)
+            .env("LIBGL_ALWAYS_SOFTWARE", "1")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let weston = HeadlessWeston {child, runtime_dir};
+        let start = Instant::now();
+        while !weston.runtime_dir.join(Self::SOCKET).exists() {
+            if start.elapsed() > Duration::from_secs(10) {
+                return None
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        Some(weston)
+    }
+}
+
+impl Drop for HeadlessWeston {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.runtime_dir);
+    }
+}
+
+// run with `cargo test --test wayland -- --ignored` where weston is installed
+#[test]
+#[ignore = "needs weston with the headless backend"]
+fn runs_on_a_headless_compositor() {
+    let weston = HeadlessWeston::start().expect("weston with the headless backend is not available");
+
+    let app = Rc::new(RefCell::new(None));
+    let cx = Rc::new(RefCell::new(Cx::new(Box::new(move | cx, event | {
+        if let Event::Startup = event {
+            *app.borrow_mut() = Some(App::new_main(cx));
+        }
+        if let Some(app) = app.borrow_mut().as_mut() {
+            app.handle_event(cx, event);
+        }
+    }))));
+    App::register_main_module(&mut *cx.borrow_mut());
+    live_design(&mut *cx.borrow_mut());
+    cx.borrow_mut().init_cx_os();
+    // the socket is passed by its full path, so the environment is left alone
+    assert!(Cx::wayland_event_loop_on(cx, weston.runtime_dir.join(HeadlessWeston::SOCKET).to_str().unwrap()));
+
+    assert!(FRAMES.load(Ordering::SeqCst) > 10);
+}
@@ -0,0 +1,119 @@
+use makepad_widgets::*;
+use makepad_widgets::makepad_platform::makepad_shader_compiler::{generate_wgsl, shader_ast::Ty};
+
+mod common;
+
+live_design!{
+    import makepad_draw::shader::std::*;
+    import makepad_draw::shader::draw_quad::DrawQuad;
+    
+    DrawUniformLayout = <DrawQuad> {
+        uniform a: 1.0
+        uniform b: vec3(1.0, 2.0, 3.0)
+        uniform c: 4.0
+        uniform d: vec2(5.0, 6.0)
+        uniform e: vec4(7.0, 8.0, 9.0, 10.0)
+        fn pixel(self) -> vec4 {
+            return vec4(self.a + self.b.x + self.c + self.d.y) + self.e
+        }
+    }
+}
+
+// Parses and validates the generated WGSL, and checks that the uniform structs naga lays
+// out by the WGSL rules match the offsets the uniforms get packed at.
+fn check_shader(cx: &Cx, draw_shader_ptr: &DrawShaderPtr) -> Result<(), String> {
+    let item = cx.draw_shaders.ptr_to_item.get(draw_shader_ptr).unwrap();
+    let cx_shader = &cx.draw_shaders.shaders[item.draw_shader_id];
+    let draw_shader_def = cx.shader_registry.draw_shader_defs.get(draw_shader_ptr).unwrap();
+    let shader = generate_wgsl::generate_shader(draw_shader_def, &cx_shader.mapping.const_table, &cx.shader_registry);
+    
+    let module = naga::front::wgsl::parse_str(&shader.wgsl)
+        .map_err( | err | format!("{}\n{}", err.emit_to_string(&shader.wgsl), shader.wgsl)) ?;
+    let mut validator = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty()
+    );
+    validator.validate(&module)
+        .map_err( | err | format!("{}\n{}", err.emit_to_string(&shader.wgsl), shader.wgsl)) ?;
+    
+    let mapping = CxDrawShaderMapping::from_draw_shader_def(
+        draw_shader_def,
+        cx_shader.mapping.const_table.clone(),
+        DrawShaderInputPacking::UniformsWGSL
+    );
+    for (_, ty) in module.types.iter() {
+        let inputs = match ty.name.as_deref() {
+            Some("LiveUniforms") => &mapping.live_uniforms,
+            Some("Uniforms_draw") => &mapping.draw_uniforms,
+            Some("Uniforms_view") => &mapping.view_uniforms,
+            Some("Uniforms_pass") => &mapping.pass_uniforms,
+            Some("Uniforms_user") => &mapping.user_uniforms,
+            _ => continue
+        };
+        if let naga::TypeInner::Struct {members, span} = &ty.inner {
+            let naga_offsets: Vec<usize> = members.iter().map( | m | m.offset as usize / 4).collect();
+            let offsets: Vec<usize> = inputs.inputs.iter().map( | input | input.offset).collect();
+            if naga_offsets != offsets || *span as usize / 4 != inputs.total_slots {
+                return Err(format!(
+                    "{:?} is laid out at {:?} of {} slots, but packed at {:?} of {}\n{}",
+                    ty.name, naga_offsets, span / 4, offsets, inputs.total_slots, shader.wgsl
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn wgsl_uniform_packing_matches_naga_layout() {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_widgets::live_design(&mut cx);
+    live_design(&mut cx);
+    cx.live_expand();
+    let module_id = LiveModuleId::from_str(module_path!()).unwrap();
+    common::compile_draw_shaders(&mut cx, | id | id == module_id);
+    
+    for draw_shader_ptr in &cx.draw_shaders.compile_set {
+        if let Err(err) = check_shader(&cx, draw_shader_ptr) {
+            panic!("{}", err)
+        }
+        let draw_shader_def = cx.shader_registry.draw_shader_defs.get(draw_shader_ptr).unwrap();
+        let mapping = CxDrawShaderMapping::from_draw_shader_def(
+            draw_shader_def,
+            Default::default(),
+            DrawShaderInputPacking::UniformsWGSL
+        );
+        // the vec3 and the vec4 align to 4 slots, the float after the vec3 fills its gap
+        let offsets: Vec<(LiveId, usize)> = mapping.user_uniforms.inputs.iter().map( | input | (input.id, input.offset)).collect();
+        assert_eq!(offsets, vec![
+            (live_id!(a), 0),
+            (live_id!(b), 4),
+            (live_id!(c), 7),
+            (live_id!(d), 8),
+            (live_id!(e), 12),
+        ]);
+        assert_eq!(mapping.user_uniforms.total_slots, 16);
+    }
+}
+
+// Runs every draw shader of the draw and widgets crates through the WGSL generator,
+// naga's WGSL front-end and validator and the uniform layout check.
+#[test]
+fn all_draw_shaders_generate_valid_wgsl() {
+    let cx = common::compile_all_draw_shaders();
+    
+    let mut failures = Vec::new();
+    for draw_shader_ptr in &cx.draw_shaders.compile_set {
+        let draw_shader_def = cx.shader_registry.draw_shader_defs.get(draw_shader_ptr).unwrap();
+        // external textures only exist on Android, which renders with GLES
+        if draw_shader_def.fields.iter().any( | field | *field.ty_expr.ty.borrow() == Some(Ty::TextureOES)) {
+            continue
+        }
+        if let Err(err) = check_shader(&cx, draw_shader_ptr) {
+            failures.push(err);
+        }
+    }
+    if !failures.is_empty() {
+        panic!("{} of {} shaders produced invalid WGSL, first one:\n{}", failures.len(), cx.draw_shaders.compile_set.len(), failures[0]);
+    }
+}